@@ -0,0 +1,125 @@
+//! Detecção de Recursos da CPU (CPUID)
+//!
+//! Consulta as folhas básicas e estendidas do CPUID para descobrir quais
+//! extensões estão disponíveis (SSE, AVX, NX, páginas de 1 GiB...). O resultado
+//! vai para o kernel em `BootInfo::cpu_features`, poupando-o de sondar a CPU
+//! de novo no early boot.
+
+use bitflags::bitflags;
+
+bitflags! {
+    /// Recursos de CPU relevantes para o kernel.
+    ///
+    /// Os bits fazem parte da ABI de handoff: nunca reordenar, apenas acrescentar.
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct CpuFeatures: u64 {
+        const SSE      = 1 << 0;
+        const SSE2     = 1 << 1;
+        const SSE3     = 1 << 2;
+        const SSSE3    = 1 << 3;
+        const SSE4_1   = 1 << 4;
+        const SSE4_2   = 1 << 5;
+        const XSAVE    = 1 << 6;
+        const AVX      = 1 << 7;
+        const AVX2     = 1 << 8;
+        const RDRAND   = 1 << 9;
+        const RDSEED   = 1 << 10;
+        const PCID     = 1 << 11;
+        const NX       = 1 << 12;
+        const PAGE_1GB = 1 << 13;
+        const SMEP     = 1 << 14;
+        const SMAP     = 1 << 15;
+    }
+}
+
+/// Registradores retornados por uma execução de `CPUID`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CpuidResult {
+    pub eax: u32,
+    pub ebx: u32,
+    pub ecx: u32,
+    pub edx: u32,
+}
+
+/// Fonte de resultados do CPUID.
+///
+/// Abstraída para que a decodificação dos bits possa ser testada no host com
+/// valores fixos.
+pub trait CpuidProvider {
+    /// Executa `CPUID` com `EAX = leaf` e `ECX = subleaf`.
+    fn cpuid(&self, leaf: u32, subleaf: u32) -> CpuidResult;
+}
+
+/// Provider que executa a instrução `CPUID` real.
+pub struct NativeCpuid;
+
+impl CpuidProvider for NativeCpuid {
+    fn cpuid(&self, leaf: u32, subleaf: u32) -> CpuidResult {
+        #[allow(unused_unsafe)]
+        let r = unsafe { core::arch::x86_64::__cpuid_count(leaf, subleaf) };
+        CpuidResult {
+            eax: r.eax,
+            ebx: r.ebx,
+            ecx: r.ecx,
+            edx: r.edx,
+        }
+    }
+}
+
+// Folhas consultadas
+const LEAF_BASIC_MAX: u32 = 0x0000_0000;
+const LEAF_FEATURES: u32 = 0x0000_0001;
+const LEAF_EXT_FEATURES: u32 = 0x0000_0007;
+const LEAF_EXTENDED_MAX: u32 = 0x8000_0000;
+const LEAF_EXTENDED_FEATURES: u32 = 0x8000_0001;
+
+/// Detecta os recursos da CPU em execução.
+pub fn detect_features() -> CpuFeatures {
+    detect_features_with(&NativeCpuid)
+}
+
+/// Detecta recursos a partir de um provider arbitrário.
+///
+/// Folhas acima do máximo reportado pela CPU não são consultadas, pois o
+/// resultado delas é indefinido.
+pub fn detect_features_with<P: CpuidProvider + ?Sized>(cpu: &P) -> CpuFeatures {
+    let mut features = CpuFeatures::empty();
+
+    let max_basic = cpu.cpuid(LEAF_BASIC_MAX, 0).eax;
+
+    if max_basic >= LEAF_FEATURES {
+        let leaf1 = cpu.cpuid(LEAF_FEATURES, 0);
+
+        features.set(CpuFeatures::SSE, leaf1.edx & (1 << 25) != 0);
+        features.set(CpuFeatures::SSE2, leaf1.edx & (1 << 26) != 0);
+        features.set(CpuFeatures::SSE3, leaf1.ecx & (1 << 0) != 0);
+        features.set(CpuFeatures::SSSE3, leaf1.ecx & (1 << 9) != 0);
+        features.set(CpuFeatures::PCID, leaf1.ecx & (1 << 17) != 0);
+        features.set(CpuFeatures::SSE4_1, leaf1.ecx & (1 << 19) != 0);
+        features.set(CpuFeatures::SSE4_2, leaf1.ecx & (1 << 20) != 0);
+        features.set(CpuFeatures::XSAVE, leaf1.ecx & (1 << 26) != 0);
+        features.set(CpuFeatures::AVX, leaf1.ecx & (1 << 28) != 0);
+        features.set(CpuFeatures::RDRAND, leaf1.ecx & (1 << 30) != 0);
+    }
+
+    if max_basic >= LEAF_EXT_FEATURES {
+        let leaf7 = cpu.cpuid(LEAF_EXT_FEATURES, 0);
+
+        features.set(CpuFeatures::AVX2, leaf7.ebx & (1 << 5) != 0);
+        features.set(CpuFeatures::SMEP, leaf7.ebx & (1 << 7) != 0);
+        features.set(CpuFeatures::RDSEED, leaf7.ebx & (1 << 18) != 0);
+        features.set(CpuFeatures::SMAP, leaf7.ebx & (1 << 20) != 0);
+    }
+
+    let max_extended = cpu.cpuid(LEAF_EXTENDED_MAX, 0).eax;
+
+    if max_extended >= LEAF_EXTENDED_FEATURES {
+        let ext1 = cpu.cpuid(LEAF_EXTENDED_FEATURES, 0);
+
+        features.set(CpuFeatures::NX, ext1.edx & (1 << 20) != 0);
+        features.set(CpuFeatures::PAGE_1GB, ext1.edx & (1 << 26) != 0);
+    }
+
+    features
+}
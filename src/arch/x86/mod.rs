@@ -1,17 +1,19 @@
 //! Implementação para Arquitetura x86_64
 //!
 //! Contém primitivas de I/O, controle de registradores e drivers básicos
-//! (Serial).
+//! (Serial, PC speaker).
 
+pub mod cpuid;
 pub mod instructions;
 pub mod io;
 pub mod registers;
 pub mod serial;
+pub mod speaker;
 
 // Re-exports convenientes
 pub use instructions::{hlt, pause};
 pub use io::Port;
-pub use registers::{flush_tlb, read_cr3, write_cr3};
+pub use registers::{enable_global_pages, enable_nx, flush_tlb, read_cr3, write_cr3};
 
 /// Inicializa recursos específicos da arquitetura x86.
 pub fn init() {
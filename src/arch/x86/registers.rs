@@ -23,6 +23,89 @@ pub unsafe fn write_cr3(value: u64) {
     core::arch::asm!("mov cr3, {}", in(reg) value, options(nomem, nostack, preserves_flags));
 }
 
+/// CR4.PGE: habilita páginas globais (bit G das entradas de página).
+pub const CR4_PGE: u64 = 1 << 7;
+
+/// Lê o registrador CR4.
+#[inline]
+pub fn read_cr4() -> u64 {
+    let value: u64;
+    unsafe {
+        core::arch::asm!("mov {}, cr4", out(reg) value, options(nomem, nostack, preserves_flags));
+    }
+    value
+}
+
+/// Escreve o registrador CR4.
+///
+/// # Safety
+/// Alterar CR4 muda o comportamento de paginação e de instruções
+/// privilegiadas; `value` deve ser coerente com o estado atual da CPU.
+#[inline]
+pub unsafe fn write_cr4(value: u64) {
+    core::arch::asm!("mov cr4, {}", in(reg) value, options(nomem, nostack, preserves_flags));
+}
+
+/// Liga CR4.PGE para que entradas com o bit G sejam respeitadas.
+///
+/// # Safety
+/// Deve rodar em ring 0 com paginação ativa.
+pub unsafe fn enable_global_pages() {
+    let cr4 = read_cr4();
+    if cr4 & CR4_PGE == 0 {
+        write_cr4(cr4 | CR4_PGE);
+    }
+}
+
+/// MSR Extended Feature Enable Register.
+pub const IA32_EFER: u32 = 0xC000_0080;
+
+/// EFER.NXE: habilita o bit NX (63) das entradas de página.
+pub const EFER_NXE: u64 = 1 << 11;
+
+/// Lê um MSR.
+///
+/// # Safety
+/// `msr` deve existir na CPU atual; um MSR inválido gera #GP.
+#[inline]
+pub unsafe fn read_msr(msr: u32) -> u64 {
+    let (low, high): (u32, u32);
+    core::arch::asm!(
+        "rdmsr",
+        in("ecx") msr,
+        out("eax") low,
+        out("edx") high,
+        options(nomem, nostack, preserves_flags)
+    );
+    ((high as u64) << 32) | low as u64
+}
+
+/// Escreve um MSR.
+///
+/// # Safety
+/// `msr` deve existir na CPU atual e `value` ser válido para ele.
+#[inline]
+pub unsafe fn write_msr(msr: u32, value: u64) {
+    core::arch::asm!(
+        "wrmsr",
+        in("ecx") msr,
+        in("eax") value as u32,
+        in("edx") (value >> 32) as u32,
+        options(nomem, nostack, preserves_flags)
+    );
+}
+
+/// Liga EFER.NXE para que o bit NX das páginas seja respeitado.
+///
+/// # Safety
+/// Deve rodar em ring 0, numa CPU com NX (CPUID `0x8000_0001` EDX bit 20).
+pub unsafe fn enable_nx() {
+    let efer = read_msr(IA32_EFER);
+    if efer & EFER_NXE == 0 {
+        write_msr(IA32_EFER, efer | EFER_NXE);
+    }
+}
+
 /// Invalida a TLB para um endereço específico (INVLPG).
 /// Deve ser chamado ao alterar mapeamentos de página.
 #[inline]
@@ -1,48 +1,63 @@
 //! Driver de Porta Serial (UART 16550)
 //!
-//! Usado para logging via COM1 antes mesmo de termos vídeo.
+//! Usado para logging via serial antes mesmo de termos vídeo.
 //! É extremamente robusto e simples.
 
-use core::fmt;
+use core::{
+    fmt,
+    sync::atomic::{AtomicU16, Ordering},
+};
 
 use super::io::Port;
 
 // Endereços de porta padrão
 const COM1: u16 = 0x3F8;
 
-/// Inicializa a porta serial COM1 para 38400 baud.
+/// Base do UART em uso (COM1 até `init_serial_early` detectar outra).
+static PORT_BASE: AtomicU16 = AtomicU16::new(COM1);
+
+/// Base da porta serial ativa.
+pub fn port_base() -> u16 {
+    PORT_BASE.load(Ordering::Relaxed)
+}
+
+/// Detecta a porta serial (COM1..COM4) e a inicializa para 38400 baud.
 ///
 /// # Safety
 /// Acessa portas de I/O diretamente.
 pub fn init_serial_early() {
+    let base = crate::hardware::serial::detect_port();
+    PORT_BASE.store(base, Ordering::Relaxed);
+
     unsafe {
         // Desabilitar interrupções
-        Port::<u8>::new(COM1 + 1).write(0x00);
+        Port::<u8>::new(base + 1).write(0x00);
 
         // Ativar DLAB (Divisor Latch Access Bit) para setar baud rate
-        Port::<u8>::new(COM1 + 3).write(0x80);
+        Port::<u8>::new(base + 3).write(0x80);
 
         // Setar divisor para 38400 baud (115200 / 3) -> 3
-        Port::<u8>::new(COM1 + 0).write(0x03); // Low byte
-        Port::<u8>::new(COM1 + 1).write(0x00); // High byte
+        Port::<u8>::new(base + 0).write(0x03); // Low byte
+        Port::<u8>::new(base + 1).write(0x00); // High byte
 
         // 8 bits, sem paridade, 1 stop bit
-        Port::<u8>::new(COM1 + 3).write(0x03);
+        Port::<u8>::new(base + 3).write(0x03);
 
         // Habilitar FIFO, limpar buffers, trigger level 14 bytes
-        Port::<u8>::new(COM1 + 2).write(0xC7);
+        Port::<u8>::new(base + 2).write(0xC7);
 
         // Habilitar IRQs (RTS/DSR setados)
-        Port::<u8>::new(COM1 + 4).write(0x0B);
+        Port::<u8>::new(base + 4).write(0x0B);
     }
 }
 
 /// Escreve um byte na serial.
 pub fn send(byte: u8) {
     unsafe {
-        let status_port = Port::<u8>::new(COM1 + 5);
+        let base = port_base();
+        let status_port = Port::<u8>::new(base + 5);
 
-        let mut data_port = Port::<u8>::new(COM1);
+        let mut data_port = Port::<u8>::new(base);
 
         // Esperar buffer de transmissão esvaziar
         while (status_port.read() & 0x20) == 0 {
@@ -0,0 +1,86 @@
+//! PC Speaker
+//!
+//! O alto-falante é ligado à saída do canal 2 do PIT (8254): o canal gera uma
+//! onda quadrada na frequência `PIT_FREQUENCY_HZ / divisor`, e os bits 0
+//! (gate do timer 2) e 1 (dados do speaker) da porta 0x61 do controlador de
+//! teclado deixam o sinal chegar ao alto-falante.
+//!
+//! Útil como sinal audível em máquinas sem monitor (`beep_on_menu`,
+//! `beep_on_error`).
+
+use crate::hardware::serial::{NativePortIo, PortIo};
+
+/// Frequência de entrada do PIT.
+pub const PIT_FREQUENCY_HZ: u32 = 1_193_182;
+
+/// Porta de dados do canal 2 do PIT.
+pub const PIT_CHANNEL2_PORT: u16 = 0x42;
+
+/// Registrador de comando (modo) do PIT.
+pub const PIT_COMMAND_PORT: u16 = 0x43;
+
+/// Porta B do controlador de teclado (controle do speaker).
+pub const SPEAKER_CONTROL_PORT: u16 = 0x61;
+
+/// Canal 2, acesso byte baixo/alto, modo 3 (onda quadrada), binário.
+pub const PIT_CHANNEL2_SQUARE_WAVE: u8 = 0xB6;
+
+/// Gate do timer 2 (bit 0) e dados do speaker (bit 1).
+pub const SPEAKER_ENABLE_BITS: u8 = 0b11;
+
+/// Bipe ao exibir o menu de boot.
+pub const MENU_BEEP: (u32, u32) = (880, 100);
+
+/// Bipe em erro fatal (mais grave e longo).
+pub const ERROR_BEEP: (u32, u32) = (440, 500);
+
+/// Divisor do PIT para `freq_hz`, limitado ao intervalo do contador de 16
+/// bits.
+pub fn pit_divisor(freq_hz: u32) -> u16 {
+    (PIT_FREQUENCY_HZ / freq_hz.max(1)).clamp(1, u16::MAX as u32) as u16
+}
+
+/// Toca `freq_hz` por `ms` milissegundos em `io`, esperando com `delay`
+/// (microssegundos). O speaker é sempre desligado no fim.
+///
+/// `io` só é usado com as portas do PIT e do speaker, presentes em todo PC
+/// compatível.
+pub fn beep_with<P: PortIo>(io: &mut P, freq_hz: u32, ms: u32, delay: &mut dyn FnMut(usize)) {
+    if freq_hz == 0 || ms == 0 {
+        return;
+    }
+
+    let [low, high] = pit_divisor(freq_hz).to_le_bytes();
+    // SAFETY: só toca o canal 2 do PIT e os bits do speaker na porta 0x61,
+    // preservando os demais bits do controlador de teclado.
+    unsafe {
+        io.write(PIT_COMMAND_PORT, PIT_CHANNEL2_SQUARE_WAVE);
+        io.write(PIT_CHANNEL2_PORT, low);
+        io.write(PIT_CHANNEL2_PORT, high);
+
+        let control = io.read(SPEAKER_CONTROL_PORT);
+        io.write(SPEAKER_CONTROL_PORT, control | SPEAKER_ENABLE_BITS);
+    }
+
+    delay(ms as usize * 1_000);
+
+    // SAFETY: mesma porta do bloco acima; apenas desliga o gate e os dados.
+    unsafe {
+        let control = io.read(SPEAKER_CONTROL_PORT);
+        io.write(SPEAKER_CONTROL_PORT, control & !SPEAKER_ENABLE_BITS);
+    }
+}
+
+/// Toca `freq_hz` por `ms` milissegundos no PC speaker.
+pub fn beep(freq_hz: u32, ms: u32) {
+    beep_with(&mut NativePortIo, freq_hz, ms, &mut |us| {
+        if crate::uefi::boot_services_active() {
+            crate::uefi::system_table().boot_services().stall(us);
+        } else {
+            // Sem `Stall`: espera aproximada, só precisa ser audível
+            for _ in 0..us.saturating_mul(1_000) {
+                core::hint::spin_loop();
+            }
+        }
+    });
+}
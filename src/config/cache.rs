@@ -0,0 +1,461 @@
+//! Cache da Configuração Interpretada
+//!
+//! Com `config_cache: yes`, o `BootConfig` resultante do parse é serializado
+//! em uma variável UEFI junto com o CRC32 do `ignite.cfg` que o gerou. Nos
+//! boots seguintes, se o arquivo tiver o mesmo CRC, a configuração é lida da
+//! variável e o parser (com expansão de macros) é pulado.
+//!
+//! O formato é binário e privado ao Ignite: qualquer mudança em `BootConfig`
+//! exige incrementar `CACHE_FORMAT_VERSION`, o que invalida caches antigos.
+
+use alloc::{string::String, vec::Vec};
+
+use super::types::{
+    BootConfig, ConsoleMode, Entry, MapMode, MemoryMapDump, Module, ModuleType, Protocol, Timeout,
+    TimeoutAction,
+};
+use crate::{
+    core::{crc32::crc32, error::Result, logging::LogFormat},
+    recovery::state::{VariableStore, IGNITE_VENDOR_GUID},
+    uefi::table::runtime::{VARIABLE_BOOTSERVICE_ACCESS, VARIABLE_NON_VOLATILE},
+};
+
+/// Nome da variável que guarda o cache.
+pub const CONFIG_CACHE_VAR_NAME: &str = "IgnCfgCache";
+
+/// Atributos da variável (Non-Volatile + BootService).
+pub const CONFIG_CACHE_ATTR: u32 = VARIABLE_NON_VOLATILE | VARIABLE_BOOTSERVICE_ACCESS;
+
+/// Tamanho máximo do cache. Configurações maiores são sempre interpretadas.
+pub const MAX_CACHE_SIZE: usize = 8 * 1024;
+
+const CACHE_MAGIC: &[u8; 4] = b"ICFG";
+const CACHE_FORMAT_VERSION: u8 = 36;
+
+/// Obtém a configuração de `content`, usando o cache de `store` quando o CRC
+/// do arquivo coincidir e chamando `parse` caso contrário.
+///
+/// Após um parse bem-sucedido com `config_cache` ativo, o cache é regravado.
+/// Falhas de NVRAM nunca impedem o boot: no pior caso o arquivo é
+/// interpretado normalmente.
+pub fn load_cached<S, P>(store: &mut S, content: &str, parse: P) -> Result<BootConfig>
+where
+    S: VariableStore + ?Sized,
+    P: FnOnce(&str) -> Result<BootConfig>,
+{
+    let crc = crc32(content.as_bytes());
+
+    let mut buf = alloc::vec![0u8; MAX_CACHE_SIZE];
+    if let Some(len) = store.read(CONFIG_CACHE_VAR_NAME, &IGNITE_VENDOR_GUID, &mut buf) {
+        if let Some(config) = deserialize(&buf[..len], crc) {
+            return Ok(config);
+        }
+    }
+
+    let config = parse(content)?;
+
+    if config.config_cache {
+        let data = serialize(&config, crc);
+        if data.len() <= MAX_CACHE_SIZE {
+            if let Err(e) = store.write(
+                CONFIG_CACHE_VAR_NAME,
+                &IGNITE_VENDOR_GUID,
+                CONFIG_CACHE_ATTR,
+                &data,
+            ) {
+                log::warn!("Config: falha ao gravar cache: {:?}", e);
+            }
+        }
+    }
+
+    Ok(config)
+}
+
+/// Serializa `config` com o CRC do arquivo de origem.
+pub fn serialize(config: &BootConfig, source_crc: u32) -> Vec<u8> {
+    let mut w = Writer(Vec::new());
+
+    w.bytes(CACHE_MAGIC);
+    w.u8(CACHE_FORMAT_VERSION);
+    w.u32(source_crc);
+
+    match config.timeout {
+        Timeout::Disabled => w.u8(0),
+        Timeout::Millis(ms) => {
+            w.u8(1);
+            w.u32(ms);
+        },
+        Timeout::Infinite => w.u8(2),
+    }
+    w.u8(config.timeout_action as u8);
+    w.u32(config.default_entry_idx as u32);
+    w.bool(config.quiet);
+    w.bool(config.serial_enabled);
+    w.u8(config.console as u8);
+    match config.resolution {
+        Some((width, height, bpp)) => {
+            w.bool(true);
+            w.u32(width);
+            w.u32(height);
+            w.u32(bpp);
+        },
+        None => w.bool(false),
+    }
+    w.opt_str(config.wallpaper.as_deref());
+    w.opt_str(config.font_path.as_deref());
+    w.bool(config.video_test_pattern);
+    w.bool(config.config_cache);
+    w.bool(config.menu_health);
+    w.bool(config.strict_diagnostics);
+    w.bool(config.global_kernel_pages);
+    w.bool(config.enforce_nx);
+    w.bool(config.protocol_auto_detect);
+    w.bool(config.verify_kernel_arch);
+    w.bool(config.panic_reboot);
+    w.bool(config.dump_config);
+    w.u8(config.dump_memory_map as u8);
+    w.bool(config.beep_on_menu);
+    w.bool(config.beep_on_error);
+    w.u8(config.recovery_max_attempts);
+    w.u32(config.recovery_attempt_decay);
+    w.bool(config.verbose);
+    w.u8(config.log_format.map_or(u8::MAX, |format| format as u8));
+    w.u32(config.menu_dim.to_bits());
+    w.opt_str(config.config_device.as_deref());
+    w.opt_u32(config.background_color);
+
+    w.u32(config.entries.len() as u32);
+    for entry in &config.entries {
+        w.str(&entry.name);
+        w.u8(protocol_to_u8(entry.protocol));
+        w.str(&entry.path);
+        w.opt_str(entry.cmdline.as_deref());
+        w.u32(entry.modules.len() as u32);
+        for module in &entry.modules {
+            w.str(&module.path);
+            w.opt_str(module.cmdline.as_deref());
+            w.opt_u32(module.crc32);
+            w.u8(module.module_type.map_or(0, module_type_to_u8));
+        }
+        w.opt_str(entry.dtb_path.as_deref());
+        w.opt_str(entry.iso_path.as_deref());
+        w.bool(entry.hidden);
+        w.opt_u64(entry.load_addr);
+        w.bool(entry.pass_system_table);
+        w.opt_u32(entry.kernel_crc32);
+        w.pairs(&entry.params);
+        w.strs(&entry.requires);
+        w.bool(entry.pass_text_console);
+        w.u8(entry.map_mode as u8);
+        w.opt_str(entry.http_base.as_deref());
+        w.opt_u64(entry.min_memory);
+        w.opt_u64(entry.initrd_size);
+    }
+
+    w.0
+}
+
+/// Reconstrói a configuração se `data` for um cache válido para o arquivo
+/// com CRC `source_crc`.
+pub fn deserialize(data: &[u8], source_crc: u32) -> Option<BootConfig> {
+    let mut r = Reader(data);
+
+    if r.take(4)? != CACHE_MAGIC || r.u8()? != CACHE_FORMAT_VERSION || r.u32()? != source_crc {
+        return None;
+    }
+
+    let mut config = BootConfig {
+        timeout:                match r.u8()? {
+            0 => Timeout::Disabled,
+            1 => Timeout::Millis(r.u32()?),
+            2 => Timeout::Infinite,
+            _ => return None,
+        },
+        timeout_action:         match r.u8()? {
+            0 => TimeoutAction::Boot,
+            1 => TimeoutAction::Halt,
+            2 => TimeoutAction::Reboot,
+            _ => return None,
+        },
+        default_entry_idx:      r.u32()? as usize,
+        quiet:                  r.bool()?,
+        serial_enabled:         r.bool()?,
+        console:                match r.u8()? {
+            0 => ConsoleMode::Serial,
+            1 => ConsoleMode::Video,
+            2 => ConsoleMode::Both,
+            _ => return None,
+        },
+        resolution:             if r.bool()? {
+            Some((r.u32()?, r.u32()?, r.u32()?))
+        } else {
+            None
+        },
+        wallpaper:              r.opt_str()?,
+        font_path:              r.opt_str()?,
+        video_test_pattern:     r.bool()?,
+        config_cache:           r.bool()?,
+        menu_health:            r.bool()?,
+        strict_diagnostics:     r.bool()?,
+        global_kernel_pages:    r.bool()?,
+        enforce_nx:             r.bool()?,
+        protocol_auto_detect:   r.bool()?,
+        verify_kernel_arch:     r.bool()?,
+        panic_reboot:           r.bool()?,
+        dump_config:            r.bool()?,
+        dump_memory_map:        match r.u8()? {
+            0 => MemoryMapDump::Off,
+            1 => MemoryMapDump::Csv,
+            _ => return None,
+        },
+        beep_on_menu:           r.bool()?,
+        beep_on_error:          r.bool()?,
+        recovery_max_attempts:  r.u8()?,
+        recovery_attempt_decay: r.u32()?,
+        verbose:                r.bool()?,
+        log_format:             match r.u8()? {
+            u8::MAX => None,
+            value => Some(LogFormat::from_u8(value)?),
+        },
+        menu_dim:               f32::from_bits(r.u32()?),
+        config_device:          r.opt_str()?,
+        background_color:       r.opt_u32()?,
+        entries:                Vec::new(),
+    };
+
+    let count = r.u32()?;
+    for _ in 0..count {
+        let name = r.str()?;
+        let protocol = protocol_from_u8(r.u8()?)?;
+        let path = r.str()?;
+        let cmdline = r.opt_str()?;
+
+        let module_count = r.u32()?;
+        let mut modules = Vec::new();
+        for _ in 0..module_count {
+            modules.push(Module {
+                path:        r.str()?,
+                cmdline:     r.opt_str()?,
+                crc32:       r.opt_u32()?,
+                module_type: match r.u8()? {
+                    0 => None,
+                    value => Some(module_type_from_u8(value)?),
+                },
+            });
+        }
+
+        config.entries.push(Entry {
+            name,
+            protocol,
+            path,
+            cmdline,
+            modules,
+            dtb_path: r.opt_str()?,
+            iso_path: r.opt_str()?,
+            hidden: r.bool()?,
+            load_addr: r.opt_u64()?,
+            pass_system_table: r.bool()?,
+            kernel_crc32: r.opt_u32()?,
+            params: r.pairs()?,
+            requires: r.strs()?,
+            pass_text_console: r.bool()?,
+            map_mode: match r.u8()? {
+                0 => MapMode::Identity,
+                1 => MapMode::Direct,
+                2 => MapMode::Both,
+                _ => return None,
+            },
+            http_base: r.opt_str()?,
+            min_memory: r.opt_u64()?,
+            initrd_size: r.opt_u64()?,
+        });
+    }
+
+    // Bytes sobrando indicam cache corrompido ou de outro formato
+    if !r.0.is_empty() {
+        return None;
+    }
+
+    Some(config)
+}
+
+fn protocol_to_u8(protocol: Protocol) -> u8 {
+    match protocol {
+        Protocol::Linux => 0,
+        Protocol::Limine => 1,
+        Protocol::Redstone => 2,
+        Protocol::EfiChainload => 3,
+        Protocol::Multiboot2 => 4,
+        Protocol::Unknown => 0xFF,
+    }
+}
+
+fn protocol_from_u8(value: u8) -> Option<Protocol> {
+    Some(match value {
+        0 => Protocol::Linux,
+        1 => Protocol::Limine,
+        2 => Protocol::Redstone,
+        3 => Protocol::EfiChainload,
+        4 => Protocol::Multiboot2,
+        0xFF => Protocol::Unknown,
+        _ => return None,
+    })
+}
+
+fn module_type_to_u8(module_type: ModuleType) -> u8 {
+    match module_type {
+        ModuleType::Initrd => 1,
+        ModuleType::Cpio => 2,
+        ModuleType::Elf => 3,
+    }
+}
+
+fn module_type_from_u8(value: u8) -> Option<ModuleType> {
+    Some(match value {
+        1 => ModuleType::Initrd,
+        2 => ModuleType::Cpio,
+        3 => ModuleType::Elf,
+        _ => return None,
+    })
+}
+
+/// Escrita little-endian com strings prefixadas pelo tamanho.
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn bytes(&mut self, b: &[u8]) {
+        self.0.extend_from_slice(b);
+    }
+
+    fn u8(&mut self, v: u8) {
+        self.0.push(v);
+    }
+
+    fn bool(&mut self, v: bool) {
+        self.u8(v as u8);
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    fn opt_u32(&mut self, v: Option<u32>) {
+        self.bool(v.is_some());
+        if let Some(v) = v {
+            self.u32(v);
+        }
+    }
+
+    fn opt_u64(&mut self, v: Option<u64>) {
+        self.bool(v.is_some());
+        if let Some(v) = v {
+            self.bytes(&v.to_le_bytes());
+        }
+    }
+
+    fn str(&mut self, s: &str) {
+        self.u32(s.len() as u32);
+        self.bytes(s.as_bytes());
+    }
+
+    fn opt_str(&mut self, s: Option<&str>) {
+        self.bool(s.is_some());
+        if let Some(s) = s {
+            self.str(s);
+        }
+    }
+
+    fn pairs(&mut self, pairs: &[(String, String)]) {
+        self.u32(pairs.len() as u32);
+        for (key, value) in pairs {
+            self.str(key);
+            self.str(value);
+        }
+    }
+
+    fn strs(&mut self, values: &[String]) {
+        self.u32(values.len() as u32);
+        for value in values {
+            self.str(value);
+        }
+    }
+}
+
+/// Leitura com verificação de limites; `None` em qualquer inconsistência.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.0.len() < n {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn bool(&mut self) -> Option<bool> {
+        match self.u8()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let b = self.take(4)?;
+        Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn opt_u32(&mut self) -> Option<Option<u32>> {
+        Some(if self.bool()? {
+            Some(self.u32()?)
+        } else {
+            None
+        })
+    }
+
+    fn opt_u64(&mut self) -> Option<Option<u64>> {
+        Some(if self.bool()? {
+            let b = self.take(8)?;
+            Some(u64::from_le_bytes(b.try_into().ok()?))
+        } else {
+            None
+        })
+    }
+
+    fn str(&mut self) -> Option<String> {
+        let len = self.u32()? as usize;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+
+    fn opt_str(&mut self) -> Option<Option<String>> {
+        Some(if self.bool()? {
+            Some(self.str()?)
+        } else {
+            None
+        })
+    }
+    fn pairs(&mut self) -> Option<Vec<(String, String)>> {
+        let count = self.u32()?;
+        let mut pairs = Vec::new();
+        for _ in 0..count {
+            pairs.push((self.str()?, self.str()?));
+        }
+        Some(pairs)
+    }
+
+    fn strs(&mut self) -> Option<Vec<String>> {
+        let count = self.u32()?;
+        let mut values = Vec::new();
+        for _ in 0..count {
+            values.push(self.str()?);
+        }
+        Some(values)
+    }
+}
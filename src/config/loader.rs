@@ -2,41 +2,60 @@
 //!
 //! Responsável por localizar e ler o arquivo de configuração do disco.
 
-use super::{parser::Parser, types::BootConfig};
+use alloc::string::String;
+
+use super::{cache, parser::Parser, path::FsTarget, types::BootConfig};
 use crate::{
-    core::error::Result,
-    fs::{read_to_string, FileSystem},
+    core::error::Result, fs::read_to_string, recovery::state::FirmwareVariables, security::tpm,
 };
 
-const CONFIG_FILENAMES: &[&str] = &["EFI/BOOT/ignite.cfg", "boot/ignite.cfg"];
-
-/// Tenta carregar a configuração. Retorna `BootConfig::recovery()` se falhar.
-pub fn load_configuration(fs: &mut dyn FileSystem) -> Result<BootConfig> {
-    let mut parser = Parser::new();
+/// Caminhos procurados para o arquivo de configuração, em ordem.
+pub const CONFIG_FILENAMES: &[&str] = &["EFI/BOOT/ignite.cfg", "boot/ignite.cfg"];
 
-    // Tenta abrir a raiz do FS. Se falhar, é erro de I/O sério.
-    let mut root = match fs.root() {
-        Ok(r) => r,
-        Err(_) => return Ok(BootConfig::recovery()),
-    };
+/// Lê o primeiro arquivo de `CONFIG_FILENAMES` encontrado em `target`.
+///
+/// Retorna `Ok(None)` se nenhum existir (ou todos forem ilegíveis) e `Err`
+/// se nem a raiz do sistema de arquivos puder ser aberta.
+pub fn read_configuration(target: &mut FsTarget) -> Result<Option<(&'static str, String)>> {
+    let mut root = target.fs().root()?;
 
     for filename in CONFIG_FILENAMES {
-        // Tenta abrir o arquivo
         if let Ok(mut file) = root.open_file(filename) {
-            crate::println!("Carregando config: {}", filename);
-            let content = match read_to_string(file.as_mut()) {
-                Ok(c) => c,
+            match read_to_string(file.as_mut()) {
+                Ok(content) => return Ok(Some((filename, content))),
                 Err(_) => continue, // Arquivo ilegível, tenta próximo
-            };
-
-            // Se o parse falhar, retorna erro (não fallback silencioso)
-            // para que o usuário saiba que o arquivo existe mas está errado.
-            return parser.parse(&content);
+            }
         }
     }
 
-    crate::println!("Nenhum arquivo de configuração encontrado.");
-    // Se não encontrar, retorna configuração padrão (pode abrir um shell ou menu
-    // default)
-    Ok(BootConfig::default())
+    Ok(None)
+}
+
+/// Tenta carregar a configuração de `target` (ESP ou pool RedstoneFS, ver
+/// `ConfigPath::resolve`). Retorna `BootConfig::recovery()` se falhar.
+pub fn load_configuration(mut target: FsTarget) -> Result<BootConfig> {
+    let mut parser = Parser::new();
+
+    // Se nem a raiz do FS abre, é erro de I/O sério.
+    let (filename, content) = match read_configuration(&mut target) {
+        Ok(Some(found)) => found,
+        Ok(None) => {
+            crate::println!("Nenhum arquivo de configuração encontrado.");
+            // Se não encontrar, retorna configuração padrão (pode abrir um
+            // shell ou menu default)
+            return Ok(BootConfig::default());
+        },
+        Err(_) => return Ok(BootConfig::recovery()),
+    };
+    crate::println!("Carregando config: {} ({})", filename, target.fs().name());
+
+    // Mede o arquivo como está no disco, antes de qualquer interpretação
+    // (inclusive a do cache)
+    if let Err(e) = tpm::measure_config(content.as_bytes()) {
+        crate::println!("AVISO: Falha ao medir a config no TPM: {:?}", e);
+    }
+
+    // Se o parse falhar, retorna erro (não fallback silencioso) para que o
+    // usuário saiba que o arquivo existe mas está errado.
+    cache::load_cached(&mut FirmwareVariables, &content, |c| parser.parse(c))
 }
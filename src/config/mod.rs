@@ -2,6 +2,7 @@
 //!
 //! Gerencia o carregamento, parsing e interpretação das opções de boot.
 
+pub mod cache;
 pub mod loader;
 pub mod macros;
 pub mod parser;
@@ -10,5 +11,8 @@ pub mod types;
 
 // Re-exports principais
 pub use loader::load_configuration;
-pub use path::ConfigPath;
-pub use types::{BootConfig, Entry, Protocol};
+pub use path::{ConfigPath, FsTarget};
+pub use types::{
+    BootConfig, ConsoleMode, Entry, MapMode, MemoryMapDump, ModuleType, Protocol, Timeout,
+    TimeoutAction,
+};
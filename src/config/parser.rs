@@ -16,28 +16,51 @@ use alloc::{
 
 use super::{
     macros::MacroExpander,
-    types::{BootConfig, Entry, Module, Protocol},
+    path::DeviceSpecifier,
+    types::{
+        BootConfig, ConsoleMode, Entry, MapMode, MemoryMapDump, Module, ModuleType, Protocol,
+        Timeout, TimeoutAction,
+    },
+};
+use crate::{
+    core::{
+        config::limits,
+        error::{BootError, ConfigError, Result},
+        logging::LogFormat,
+    },
+    video::DEFAULT_BPP,
 };
-use crate::core::error::Result;
 
 pub struct Parser {
-    expander: MacroExpander,
+    expander:    MacroExpander,
+    max_depth:   usize,
+    max_entries: usize,
 }
 
 impl Parser {
     pub fn new() -> Self {
+        Self::with_limits(limits::MAX_ENTRY_DEPTH, limits::MAX_CONFIG_ENTRIES)
+    }
+
+    /// Parser que recusa entradas com mais de `max_depth` barras no início
+    /// (`//Sub` tem profundidade 2) e arquivos com mais de `max_entries`
+    /// entradas, em vez de crescer sem limite no heap.
+    pub fn with_limits(max_depth: usize, max_entries: usize) -> Self {
         Self {
             expander: MacroExpander::new(),
+            max_depth,
+            max_entries,
         }
     }
 
     pub fn parse(&mut self, content: &str) -> Result<BootConfig> {
         let mut config = BootConfig::default();
         let mut current_entry: Option<Entry> = None;
+        let mut default_name: Option<String> = None;
 
         let lines: Vec<&str> = content.lines().map(|l| l.trim()).collect();
 
-        for (_line_num, line) in lines.iter().enumerate() {
+        for (line_num, line) in lines.iter().enumerate() {
             // Ignorar vazios e comentários
             if line.is_empty() || line.starts_with('#') {
                 continue;
@@ -48,6 +71,24 @@ impl Parser {
 
             // Detecção de nova entrada (começa com /)
             if let Some(name) = line.strip_prefix('/') {
+                // Recusa antes de alocar a entrada: profundidade é o número de
+                // `/` iniciais, e a entrada em construção também conta
+                let depth = line.len() - line.trim_start_matches('/').len();
+                let entries = config.entries.len() + usize::from(current_entry.is_some());
+                let limit_error = if depth > self.max_depth {
+                    Some(ConfigError::NestingTooDeep)
+                } else if entries >= self.max_entries {
+                    Some(ConfigError::TooManyEntries)
+                } else {
+                    None
+                };
+                if let Some(error) = limit_error {
+                    return Err(BootError::ConfigLine {
+                        line: line_num + 1,
+                        error,
+                    });
+                }
+
                 // Se tínhamos uma entrada sendo construída, salvamos ela
                 if let Some(entry) = current_entry.take() {
                     config.entries.push(entry);
@@ -55,12 +96,24 @@ impl Parser {
 
                 // Iniciar nova entrada
                 current_entry = Some(Entry {
-                    name:     name.trim().to_string(),
-                    protocol: Protocol::Unknown,
-                    path:     String::new(),
-                    cmdline:  None,
-                    modules:  Vec::new(),
-                    dtb_path: None,
+                    name:              name.trim().to_string(),
+                    protocol:          Protocol::Unknown,
+                    path:              String::new(),
+                    cmdline:           None,
+                    modules:           Vec::new(),
+                    dtb_path:          None,
+                    iso_path:          None,
+                    hidden:            false,
+                    load_addr:         None,
+                    pass_system_table: false,
+                    kernel_crc32:      None,
+                    params:            Vec::new(),
+                    requires:          Vec::new(),
+                    pass_text_console: false,
+                    map_mode:          MapMode::Both,
+                    http_base:         None,
+                    min_memory:        None,
+                    initrd_size:       None,
                 });
                 continue;
             }
@@ -79,7 +132,7 @@ impl Parser {
             // Par Chave: Valor
             if let Some((key, val)) = line.split_once(':') {
                 let key = key.trim().to_lowercase();
-                let val = val.trim();
+                let val = option_value(val);
 
                 if let Some(entry) = &mut current_entry {
                     // Propriedades da Entrada
@@ -88,29 +141,183 @@ impl Parser {
                         "path" | "kernel_path" => entry.path = val.to_string(),
                         "cmdline" | "kernel_cmdline" => entry.cmdline = Some(val.to_string()),
                         "module_path" => entry.modules.push(Module {
-                            path:    val.to_string(),
-                            cmdline: None,
+                            path:        val.to_string(),
+                            cmdline:     None,
+                            crc32:       None,
+                            module_type: None,
                         }),
+                        "map_mode" => match MapMode::parse(val) {
+                            Some(mode) => entry.map_mode = mode,
+                            None => log::warn!("Config: map_mode invalido: '{}'", val),
+                        },
+                        // Aplica-se ao último `module_path` da entrada
+                        "module_cmdline" => {
+                            if let Some(module) = entry.modules.last_mut() {
+                                module.cmdline = Some(val.to_string());
+                            }
+                        },
                         "dtb_path" => entry.dtb_path = Some(val.to_string()),
+                        "iso_path" => entry.iso_path = Some(val.to_string()),
+                        "http_base" => entry.http_base = Some(val.to_string()),
+                        "min_memory" => match parse_size(val) {
+                            Some(bytes) => entry.min_memory = Some(bytes),
+                            None => log::warn!("Config: min_memory invalido: '{}'", val),
+                        },
+                        "initrd_size" => match parse_size(val) {
+                            Some(bytes) => entry.initrd_size = Some(bytes),
+                            None => log::warn!("Config: initrd_size invalido: '{}'", val),
+                        },
+                        "hidden" => {
+                            entry.hidden = val.eq_ignore_ascii_case("yes") || val == "true"
+                        },
+                        "load_addr" => match parse_address(val) {
+                            Some(addr) => entry.load_addr = Some(addr),
+                            None => log::warn!("Config: load_addr invalido: '{}'", val),
+                        },
+                        "pass_system_table" => {
+                            entry.pass_system_table =
+                                val.eq_ignore_ascii_case("yes") || val == "true"
+                        },
+                        "kernel_crc32" => match parse_crc32(val) {
+                            Some(crc) => entry.kernel_crc32 = Some(crc),
+                            None => log::warn!("Config: kernel_crc32 invalido: '{}'", val),
+                        },
+                        // Aplica-se ao último `module_path` da entrada
+                        "module_crc32" => match (parse_crc32(val), entry.modules.last_mut()) {
+                            (Some(crc), Some(module)) => module.crc32 = Some(crc),
+                            _ => log::warn!("Config: module_crc32 invalido: '{}'", val),
+                        },
+                        "param" => match val.split_once('=') {
+                            Some((k, v)) if !k.trim().is_empty() => {
+                                let pair = (k.trim().to_string(), v.trim().to_string());
+                                entry.params.push(pair);
+                            },
+                            _ => log::warn!("Config: param invalido: '{}'", val),
+                        },
+                        "requires" => entry.requires.push(val.to_string()),
+                        "pass_text_console" => {
+                            entry.pass_text_console =
+                                val.eq_ignore_ascii_case("yes") || val == "true"
+                        },
+                        // Aplica-se ao último `module_path` da entrada
+                        "module_type" => match (ModuleType::parse(val), entry.modules.last_mut()) {
+                            (Some(module_type), Some(module)) => {
+                                module.module_type = Some(module_type)
+                            },
+                            _ => log::warn!("Config: module_type invalido: '{}'", val),
+                        },
                         _ => {}, // Ignorar desconhecido
                     }
                 } else {
                     // Propriedades Globais
                     match key.as_str() {
-                        "timeout" => config.timeout = val.parse().ok(),
+                        "timeout" => {
+                            config.timeout = Timeout::parse(val).unwrap_or(Timeout::Disabled)
+                        },
+                        "timeout_action" => {
+                            if let Some(action) = TimeoutAction::parse(val) {
+                                config.timeout_action = action;
+                            }
+                        },
                         "default_entry" => {
-                            // Tenta parsear como número (1-based index)
+                            // Tenta parsear como número (1-based index); senão,
+                            // é o nome de uma entrada, resolvido ao final
                             if let Ok(idx) = val.parse::<usize>() {
                                 if idx > 0 {
                                     config.default_entry_idx = idx - 1;
                                 }
+                            } else {
+                                default_name = Some(val.to_string());
                             }
                         },
                         "serial" => {
                             config.serial_enabled = val.eq_ignore_ascii_case("yes") || val == "true"
                         },
+                        "console" => {
+                            if let Some(mode) = ConsoleMode::parse(val) {
+                                config.console = mode;
+                            }
+                        },
                         "quiet" => config.quiet = val.eq_ignore_ascii_case("yes") || val == "true",
+                        "resolution" => config.resolution = parse_resolution_full(val),
                         "wallpaper" => config.wallpaper = Some(val.to_string()),
+                        "font_path" => config.font_path = Some(val.to_string()),
+                        "config_device" => match DeviceSpecifier::parse(val) {
+                            Some(_) => config.config_device = Some(val.to_string()),
+                            None => log::warn!("Config: config_device invalido: '{}'", val),
+                        },
+                        "video_test_pattern" => {
+                            config.video_test_pattern =
+                                val.eq_ignore_ascii_case("yes") || val == "true"
+                        },
+                        "config_cache" => {
+                            config.config_cache = val.eq_ignore_ascii_case("yes") || val == "true"
+                        },
+                        "menu_health" => {
+                            config.menu_health = val.eq_ignore_ascii_case("yes") || val == "true"
+                        },
+                        "strict_diagnostics" => {
+                            config.strict_diagnostics =
+                                val.eq_ignore_ascii_case("yes") || val == "true"
+                        },
+                        "global_kernel_pages" => {
+                            config.global_kernel_pages =
+                                val.eq_ignore_ascii_case("yes") || val == "true"
+                        },
+                        "enforce_nx" => {
+                            config.enforce_nx = val.eq_ignore_ascii_case("yes") || val == "true"
+                        },
+                        "protocol_auto_detect" => {
+                            config.protocol_auto_detect =
+                                val.eq_ignore_ascii_case("yes") || val == "true"
+                        },
+                        "verify_kernel_arch" => {
+                            config.verify_kernel_arch =
+                                val.eq_ignore_ascii_case("yes") || val == "true"
+                        },
+                        "panic_reboot" => {
+                            config.panic_reboot = val.eq_ignore_ascii_case("yes") || val == "true"
+                        },
+                        "dump_config" => {
+                            config.dump_config = val.eq_ignore_ascii_case("yes") || val == "true"
+                        },
+                        "beep_on_menu" => {
+                            config.beep_on_menu = val.eq_ignore_ascii_case("yes") || val == "true"
+                        },
+                        "beep_on_error" => {
+                            config.beep_on_error = val.eq_ignore_ascii_case("yes") || val == "true"
+                        },
+                        "verbose" => {
+                            config.verbose = val.eq_ignore_ascii_case("yes") || val == "true"
+                        },
+                        "dump_memory_map" => match MemoryMapDump::parse(val) {
+                            Some(dump) => config.dump_memory_map = dump,
+                            None => log::warn!("Config: dump_memory_map invalido: '{}'", val),
+                        },
+                        "log_format" => match LogFormat::parse(val) {
+                            Some(format) => config.log_format = Some(format),
+                            None => log::warn!("Config: log_format invalido: '{}'", val),
+                        },
+                        "recovery_max_attempts" => match val.parse::<u8>() {
+                            Ok(attempts) => config.recovery_max_attempts = attempts,
+                            Err(_) => {
+                                log::warn!("Config: recovery_max_attempts invalido: '{}'", val)
+                            },
+                        },
+                        "recovery_attempt_decay" => match val.parse() {
+                            Ok(seconds) => config.recovery_attempt_decay = seconds,
+                            Err(_) => {
+                                log::warn!("Config: recovery_attempt_decay invalido: '{}'", val)
+                            },
+                        },
+                        "menu_dim" => match val.parse::<f32>() {
+                            Ok(factor) if (0.0..=1.0).contains(&factor) => config.menu_dim = factor,
+                            _ => log::warn!("Config: menu_dim invalido: '{}'", val),
+                        },
+                        "background_color" => match parse_rgb(val) {
+                            Some(rgb) => config.background_color = Some(rgb),
+                            None => log::warn!("Config: background_color invalido: '{}'", val),
+                        },
                         _ => {},
                     }
                 }
@@ -123,6 +330,10 @@ impl Parser {
             config.entries.push(entry);
         }
 
+        if let Some(idx) = default_name.and_then(|name| config.find_entry(&name)) {
+            config.default_entry_idx = idx;
+        }
+
         self.validate(&config)?;
         Ok(config)
     }
@@ -136,3 +347,102 @@ impl Parser {
         Ok(())
     }
 }
+
+/// Valor de uma opção sem o comentário final e sem aspas externas.
+///
+/// O comentário começa no primeiro `#` fora de aspas precedido de espaço, de
+/// forma que valores como `#RRGGBB` e URLs com `#` continuam inteiros. Um
+/// valor todo entre aspas (`"quiet # mantido"`) é devolvido sem elas.
+fn option_value(val: &str) -> &str {
+    let val = val.trim();
+    let mut quoted = false;
+    let mut after_space = false;
+    let mut end = val.len();
+    for (i, c) in val.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted && after_space => {
+                end = i;
+                break;
+            },
+            _ => {},
+        }
+        after_space = c.is_whitespace();
+    }
+
+    let val = val[..end].trim();
+    val.strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+        .filter(|inner| !inner.contains('"'))
+        .unwrap_or(val)
+}
+
+/// Interpreta uma resolução no formato `LARGURAxALTURA` ou
+/// `LARGURAxALTURAxBPP`.
+///
+/// Sem o componente de bpp, assume `DEFAULT_BPP`. Retorna `None` se algum
+/// componente não for um número positivo.
+pub fn parse_resolution_full(val: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = val.trim().split(['x', 'X']);
+
+    let width: u32 = parts.next()?.trim().parse().ok()?;
+    let height: u32 = parts.next()?.trim().parse().ok()?;
+    let bpp: u32 = match parts.next() {
+        Some(bpp) => bpp.trim().parse().ok()?,
+        None => DEFAULT_BPP,
+    };
+
+    if parts.next().is_some() || width == 0 || height == 0 || bpp == 0 {
+        return None;
+    }
+
+    Some((width, height, bpp))
+}
+
+/// Interpreta um endereço em hexadecimal (`0x200000`) ou decimal.
+pub fn parse_address(val: &str) -> Option<u64> {
+    let val = val.trim();
+    match val.strip_prefix("0x").or_else(|| val.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => val.parse().ok(),
+    }
+}
+
+/// Interpreta um tamanho em bytes com sufixo opcional `K`, `M` ou `G`
+/// (potências de 1024, ex: `512M`, `8G`, `8GB`).
+pub fn parse_size(val: &str) -> Option<u64> {
+    let val = val.trim();
+    let val = val
+        .strip_suffix(['B', 'b'])
+        .filter(|rest| rest.ends_with(|c: char| c.is_ascii_alphabetic()))
+        .unwrap_or(val);
+    let (digits, shift) = match val.as_bytes().last()?.to_ascii_uppercase() {
+        b'K' => (&val[..val.len() - 1], 10),
+        b'M' => (&val[..val.len() - 1], 20),
+        b'G' => (&val[..val.len() - 1], 30),
+        _ => (val, 0),
+    };
+    digits.trim().parse::<u64>().ok()?.checked_mul(1 << shift)
+}
+
+/// Interpreta uma cor `#RRGGBB` como `0xRRGGBB`.
+pub fn parse_rgb(val: &str) -> Option<u32> {
+    let hex = val.trim().strip_prefix('#')?;
+    if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
+
+/// Interpreta um CRC32 em hexadecimal, com ou sem `0x` (`1c291ca3`).
+pub fn parse_crc32(val: &str) -> Option<u32> {
+    let val = val.trim();
+    let hex = val
+        .strip_prefix("0x")
+        .or_else(|| val.strip_prefix("0X"))
+        .unwrap_or(val);
+    if hex.is_empty() || hex.len() > 8 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
@@ -5,10 +5,17 @@
 
 use alloc::string::{String, ToString};
 
+use crate::{
+    core::error::{BootError, ConfigError, Result},
+    fs::FileSystem,
+};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DeviceSpecifier {
     /// `boot()`: Dispositivo onde o bootloader está rodando.
     BootVolume,
+    /// `root()`: Pool RedstoneFS da partição do sistema.
+    RootPool,
     /// `boot(N)`: Partição N do disco de boot.
     BootPartition(u32),
     /// `uuid(X)`: Partição com UUID X.
@@ -17,6 +24,28 @@ pub enum DeviceSpecifier {
     Label(String),
 }
 
+impl DeviceSpecifier {
+    /// Interpreta só a parte do dispositivo (`nome(arg)`), sem o `:`.
+    pub fn parse(dev_part: &str) -> Option<Self> {
+        if dev_part == "boot()" {
+            Some(Self::BootVolume)
+        } else if dev_part == "root()" {
+            Some(Self::RootPool)
+        } else if dev_part.starts_with("boot(") && dev_part.ends_with(')') {
+            let num_str = &dev_part[5..dev_part.len() - 1];
+            let num = num_str.parse::<u32>().ok()?;
+            Some(Self::BootPartition(num))
+        } else if dev_part.starts_with("uuid(") && dev_part.ends_with(')') {
+            let uuid = &dev_part[5..dev_part.len() - 1];
+            Some(Self::Uuid(uuid.to_string()))
+        } else {
+            // Default ou desconhecido, tratar como caminho relativo se não
+            // tiver parenteses
+            None
+        }
+    }
+}
+
 /// Representa um caminho completo parseado da config.
 #[derive(Debug, Clone)]
 pub struct ConfigPath {
@@ -28,23 +57,8 @@ impl ConfigPath {
     pub fn parse(input: &str) -> Option<Self> {
         // Verifica se tem separador de dispositivo (:)
         if let Some((dev_part, path_part)) = input.split_once(':') {
-            // Parse do dispositivo: nome(arg)
-            let dev_spec = if dev_part == "boot()" {
-                DeviceSpecifier::BootVolume
-            } else if dev_part.starts_with("boot(") && dev_part.ends_with(')') {
-                let num_str = &dev_part[5..dev_part.len() - 1];
-                let num = num_str.parse::<u32>().ok()?;
-                DeviceSpecifier::BootPartition(num)
-            } else if dev_part.starts_with("uuid(") && dev_part.ends_with(')') {
-                let uuid = &dev_part[5..dev_part.len() - 1];
-                DeviceSpecifier::Uuid(uuid.to_string())
-            } else {
-                // Default ou desconhecido, tratar como caminho relativo se não tiver parenteses
-                return None;
-            };
-
             Some(Self {
-                device: dev_spec,
+                device: DeviceSpecifier::parse(dev_part)?,
                 path:   path_part.to_string(),
             })
         } else {
@@ -55,4 +69,61 @@ impl ConfigPath {
             })
         }
     }
+
+    /// Escolhe o sistema de arquivos onde o caminho deve ser lido.
+    ///
+    /// `esp` é o volume de onde o Ignite foi carregado e `root`, o pool
+    /// RedstoneFS, se montado. Retorna `None` para `root()` sem pool e para
+    /// dispositivos que ainda não são resolvidos aqui (`boot(N)`, `uuid()`).
+    pub fn resolve<'a>(
+        &self,
+        esp: &'a mut dyn FileSystem,
+        root: Option<&'a mut dyn FileSystem>,
+    ) -> Option<FsTarget<'a>> {
+        match self.device {
+            DeviceSpecifier::BootVolume => Some(FsTarget::Esp(esp)),
+            DeviceSpecifier::RootPool => root.map(FsTarget::RedstoneFs),
+            _ => None,
+        }
+    }
+}
+
+/// Alvo de `config_device` (só o dispositivo, ex: `root()`).
+///
+/// Diferente de `ConfigPath::resolve`, falha com o motivo: especificador
+/// desconhecido ou dispositivo que o Ignite ainda não monta. Enquanto nenhum
+/// driver monta o pool RedstoneFS, `root()` chega aqui com `root: None`.
+pub fn resolve_config_device<'a>(
+    device: &str,
+    esp: &'a mut dyn FileSystem,
+    root: Option<&'a mut dyn FileSystem>,
+) -> Result<FsTarget<'a>> {
+    let device = DeviceSpecifier::parse(device).ok_or(BootError::Config(ConfigError::Invalid(
+        "config_device: dispositivo desconhecido",
+    )))?;
+    ConfigPath {
+        device,
+        path: String::new(),
+    }
+    .resolve(esp, root)
+    .ok_or(BootError::Config(ConfigError::Invalid(
+        "config_device: dispositivo nao montado",
+    )))
+}
+
+/// Sistema de arquivos resolvido a partir de um `ConfigPath`.
+pub enum FsTarget<'a> {
+    /// ESP de onde o Ignite foi carregado (`boot()`).
+    Esp(&'a mut dyn FileSystem),
+    /// Pool RedstoneFS (`root()`).
+    RedstoneFs(&'a mut dyn FileSystem),
+}
+
+impl FsTarget<'_> {
+    /// Sistema de arquivos alvo.
+    pub fn fs(&mut self) -> &mut dyn FileSystem {
+        match self {
+            FsTarget::Esp(fs) | FsTarget::RedstoneFs(fs) => &mut **fs,
+        }
+    }
 }
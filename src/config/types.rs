@@ -7,12 +7,18 @@ use alloc::{
     string::{String, ToString},
     vec::Vec,
 };
+use core::fmt;
+
+use crate::core::logging::LogFormat;
 
 /// Configuração global do Bootloader.
 #[derive(Debug, Clone)]
 pub struct BootConfig {
-    /// Tempo em segundos antes de iniciar a entrada padrão.
-    pub timeout: Option<u32>,
+    /// Espera do menu antes de iniciar a entrada padrão.
+    pub timeout: Timeout,
+
+    /// O que fazer quando o timeout do menu expira sem seleção.
+    pub timeout_action: TimeoutAction,
 
     /// Índice da entrada padrão.
     pub default_entry_idx: usize,
@@ -23,12 +29,85 @@ pub struct BootConfig {
     /// Habilita saída serial.
     pub serial_enabled: bool,
 
-    /// Resolução desejada.
-    pub resolution: Option<(u32, u32)>,
+    /// Onde o menu e os logs são exibidos (serial, vídeo ou ambos).
+    pub console: ConsoleMode,
+
+    /// Resolução desejada (largura, altura, bits por pixel).
+    pub resolution: Option<(u32, u32, u32)>,
 
     /// Caminho do wallpaper.
     pub wallpaper: Option<String>,
 
+    /// Fonte PSF extra para caracteres fora do ASCII no menu.
+    pub font_path: Option<String>,
+
+    /// Pinta barras de cor no framebuffer antes do handoff (bring-up).
+    pub video_test_pattern: bool,
+
+    /// Guarda a configuração já interpretada em NVRAM (ver `config::cache`).
+    pub config_cache: bool,
+
+    /// Mostra no menu a saúde de cada entrada (ver `Diagnostics::score_entry`).
+    pub menu_health: bool,
+
+    /// Trata avisos do diagnóstico pré-boot como falhas críticas.
+    pub strict_diagnostics: bool,
+
+    /// Mapeia os segmentos do kernel com o bit Global (ver `PageTableManager`).
+    pub global_kernel_pages: bool,
+
+    /// Marca os segmentos não executáveis do kernel com NX e liga EFER.NXE.
+    pub enforce_nx: bool,
+
+    /// Detecta o protocolo pelo binário quando a entrada não define
+    /// `protocol:` (ver `protos::resolve_protocol`).
+    pub protocol_auto_detect: bool,
+
+    /// Recusa kernels de outra arquitetura que a do Ignite (ver
+    /// `protos::arch::check_kernel_arch`).
+    pub verify_kernel_arch: bool,
+
+    /// Reinicia a máquina alguns segundos após um panic em vez de paralisar.
+    pub panic_reboot: bool,
+
+    /// Registra a configuração interpretada no log (ver `BootConfig::dump`).
+    pub dump_config: bool,
+
+    /// Exporta o mapa de memória capturado na serial antes do handoff.
+    pub dump_memory_map: MemoryMapDump,
+
+    /// Bipe no PC speaker ao exibir o menu.
+    pub beep_on_menu: bool,
+
+    /// Bipe no PC speaker em erro fatal (panic).
+    pub beep_on_error: bool,
+
+    /// Falhas consecutivas antes de cair na entrada de recuperação (0
+    /// desativa o contador).
+    pub recovery_max_attempts: u8,
+
+    /// Segundos de espera por falha registrada antes de tentar de novo a
+    /// entrada padrão (0 desativa).
+    pub recovery_attempt_decay: u32,
+
+    /// Prefixa os registros de log com a hora do RTC (HH:MM:SS).
+    pub verbose: bool,
+
+    /// Formato das linhas de log na serial. Sem a chave, `verbose` decide
+    /// entre `Leveled` e `Timestamped` (ver `effective_log_format`).
+    pub log_format: Option<LogFormat>,
+
+    /// Fator de brilho (0.0 a 1.0) aplicado ao fundo sob a lista do menu.
+    pub menu_dim: f32,
+
+    /// Dispositivo de onde reler a configuração (`root()` para o pool
+    /// RedstoneFS), quando o ESP guarda só o redirecionamento.
+    pub config_device: Option<String>,
+
+    /// Cor de fundo do menu (`0xRRGGBB`) quando não há wallpaper; `None` usa
+    /// a do tema.
+    pub background_color: Option<u32>,
+
     /// Lista de sistemas operacionais.
     pub entries: Vec<Entry>,
 }
@@ -38,29 +117,226 @@ impl Default for BootConfig {
     /// Usada pelo Parser como base para ler o arquivo de configuração.
     fn default() -> Self {
         Self {
-            timeout:           Some(5),
-            default_entry_idx: 0,
-            quiet:             false,
-            serial_enabled:    true,
-            resolution:        None,
-            wallpaper:         None,
-            entries:           Vec::new(), // IMPORTANTE: Começa vazio para não duplicar entradas
+            timeout:                Timeout::Millis(5000),
+            timeout_action:         TimeoutAction::Boot,
+            default_entry_idx:      0,
+            quiet:                  false,
+            serial_enabled:         true,
+            console:                ConsoleMode::Both,
+            resolution:             None,
+            wallpaper:              None,
+            font_path:              None,
+            video_test_pattern:     false,
+            config_cache:           false,
+            menu_health:            false,
+            strict_diagnostics:     false,
+            global_kernel_pages:    true,
+            enforce_nx:             true,
+            protocol_auto_detect:   true,
+            verify_kernel_arch:     true,
+            panic_reboot:           false,
+            dump_config:            false,
+            dump_memory_map:        MemoryMapDump::Off,
+            beep_on_menu:           false,
+            beep_on_error:          false,
+            recovery_max_attempts:  0,
+            recovery_attempt_decay: 0,
+            verbose:                false,
+            log_format:             None,
+            menu_dim:               1.0,
+            config_device:          None,
+            background_color:       None,
+            entries:                Vec::new(), /* IMPORTANTE: Começa vazio para não duplicar
+                                                 * entradas */
         }
     }
 }
 
 impl BootConfig {
+    /// Índice da entrada chamada `name`, incluindo entradas ocultas.
+    pub fn find_entry(&self, name: &str) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.name == name)
+    }
+
+    /// Registra no log cada campo resolvido e cada entrada, uma linha por
+    /// registro, para conferir como o arquivo foi interpretado.
+    pub fn dump(&self) {
+        self.dump_with(|line| log::info!("config: {}", line));
+    }
+
+    /// Emite o dump linha a linha para `line`, na ordem das chaves da
+    /// documentação. Nada é alocado: cada linha chega como
+    /// `fmt::Arguments`.
+    pub fn dump_with(&self, mut line: impl FnMut(fmt::Arguments)) {
+        let flag = |value: bool| if value { "yes" } else { "no" };
+        fn text(value: &Option<String>) -> &str {
+            value.as_deref().unwrap_or("-")
+        }
+
+        match self.timeout {
+            Timeout::Disabled => line(format_args!("timeout: no")),
+            Timeout::Millis(ms) if ms % 1000 == 0 => line(format_args!("timeout: {}", ms / 1000)),
+            Timeout::Millis(ms) => line(format_args!("timeout: {}ms", ms)),
+            Timeout::Infinite => line(format_args!("timeout: infinite")),
+        }
+        line(format_args!("timeout_action: {:?}", self.timeout_action));
+        match self.entries.get(self.default_entry_idx) {
+            Some(entry) => line(format_args!(
+                "default_entry: {} ({})",
+                self.default_entry_idx, entry.name
+            )),
+            None => line(format_args!(
+                "default_entry: {} (inexistente)",
+                self.default_entry_idx
+            )),
+        }
+        line(format_args!("quiet: {}", flag(self.quiet)));
+        line(format_args!("serial: {}", flag(self.serial_enabled)));
+        line(format_args!("console: {:?}", self.console));
+        match self.resolution {
+            Some((width, height, bpp)) => {
+                line(format_args!("resolution: {}x{}x{}", width, height, bpp))
+            },
+            None => line(format_args!("resolution: -")),
+        }
+        line(format_args!("wallpaper: {}", text(&self.wallpaper)));
+        line(format_args!("font_path: {}", text(&self.font_path)));
+        line(format_args!(
+            "video_test_pattern: {}",
+            flag(self.video_test_pattern)
+        ));
+        line(format_args!("config_cache: {}", flag(self.config_cache)));
+        line(format_args!("menu_health: {}", flag(self.menu_health)));
+        line(format_args!(
+            "strict_diagnostics: {}",
+            flag(self.strict_diagnostics)
+        ));
+        line(format_args!(
+            "global_kernel_pages: {}",
+            flag(self.global_kernel_pages)
+        ));
+        line(format_args!("enforce_nx: {}", flag(self.enforce_nx)));
+        line(format_args!(
+            "protocol_auto_detect: {}",
+            flag(self.protocol_auto_detect)
+        ));
+        line(format_args!(
+            "verify_kernel_arch: {}",
+            flag(self.verify_kernel_arch)
+        ));
+        line(format_args!("panic_reboot: {}", flag(self.panic_reboot)));
+        line(format_args!("dump_config: {}", flag(self.dump_config)));
+        line(format_args!("dump_memory_map: {:?}", self.dump_memory_map));
+        line(format_args!("beep_on_menu: {}", flag(self.beep_on_menu)));
+        line(format_args!("beep_on_error: {}", flag(self.beep_on_error)));
+        line(format_args!(
+            "recovery_max_attempts: {}",
+            self.recovery_max_attempts
+        ));
+        line(format_args!(
+            "recovery_attempt_decay: {}",
+            self.recovery_attempt_decay
+        ));
+        line(format_args!("verbose: {}", flag(self.verbose)));
+        match self.log_format {
+            Some(format) => line(format_args!("log_format: {:?}", format)),
+            None => line(format_args!("log_format: -")),
+        }
+        line(format_args!("menu_dim: {:.2}", self.menu_dim));
+        line(format_args!("config_device: {}", text(&self.config_device)));
+        match self.background_color {
+            Some(rgb) => line(format_args!("background_color: #{:06x}", rgb)),
+            None => line(format_args!("background_color: -")),
+        }
+        line(format_args!("entries: {}", self.entries.len()));
+
+        for entry in &self.entries {
+            line(format_args!("/{}", entry.name));
+            line(format_args!("  protocol: {:?}", entry.protocol));
+            line(format_args!("  path: {}", entry.path));
+            line(format_args!("  cmdline: {}", text(&entry.cmdline)));
+            for module in &entry.modules {
+                line(format_args!(
+                    "  module_path: {} [{}]",
+                    module.path,
+                    text(&module.cmdline)
+                ));
+                if let Some(crc) = module.crc32 {
+                    line(format_args!("  module_crc32: {:08x}", crc));
+                }
+                if let Some(module_type) = module.module_type {
+                    line(format_args!("  module_type: {:?}", module_type));
+                }
+            }
+            line(format_args!("  dtb_path: {}", text(&entry.dtb_path)));
+            line(format_args!("  iso_path: {}", text(&entry.iso_path)));
+            line(format_args!("  hidden: {}", flag(entry.hidden)));
+            if let Some(addr) = entry.load_addr {
+                line(format_args!("  load_addr: {:#x}", addr));
+            }
+            if entry.pass_system_table {
+                line(format_args!("  pass_system_table: yes"));
+            }
+            if let Some(crc) = entry.kernel_crc32 {
+                line(format_args!("  kernel_crc32: {:08x}", crc));
+            }
+            for (key, value) in &entry.params {
+                line(format_args!("  param: {}={}", key, value));
+            }
+            for path in &entry.requires {
+                line(format_args!("  requires: {}", path));
+            }
+            if entry.pass_text_console {
+                line(format_args!("  pass_text_console: yes"));
+            }
+            if entry.map_mode != MapMode::Both {
+                line(format_args!("  map_mode: {:?}", entry.map_mode));
+            }
+            if let Some(base) = &entry.http_base {
+                line(format_args!("  http_base: {}", base));
+            }
+            if let Some(bytes) = entry.min_memory {
+                line(format_args!("  min_memory: {}", bytes));
+            }
+            if let Some(bytes) = entry.initrd_size {
+                line(format_args!("  initrd_size: {}", bytes));
+            }
+        }
+    }
+
+    /// Formato de log a aplicar: `log_format` se presente; senão
+    /// `Timestamped` com `verbose` e `Leveled` sem.
+    pub fn effective_log_format(&self) -> LogFormat {
+        match self.log_format {
+            Some(format) => format,
+            None if self.verbose => LogFormat::Timestamped,
+            None => LogFormat::Leveled,
+        }
+    }
+
     /// Retorna a configuração de Recuperação (Recovery).
     /// Deve ser usada APENAS quando o arquivo de configuração não for
     /// encontrado.
     pub fn recovery() -> Self {
         let recovery_entry = Entry {
-            name:     "UEFI Shell (Recovery)".to_string(),
-            protocol: Protocol::EfiChainload,
-            path:     "boot():/EFI/BOOT/shellx64.efi".to_string(),
-            cmdline:  None,
-            modules:  Vec::new(),
-            dtb_path: None,
+            name:              "UEFI Shell (Recovery)".to_string(),
+            protocol:          Protocol::EfiChainload,
+            path:              "boot():/EFI/BOOT/shellx64.efi".to_string(),
+            cmdline:           None,
+            modules:           Vec::new(),
+            dtb_path:          None,
+            iso_path:          None,
+            hidden:            false,
+            load_addr:         None,
+            pass_system_table: false,
+            kernel_crc32:      None,
+            params:            Vec::new(),
+            requires:          Vec::new(),
+            pass_text_console: false,
+            map_mode:          MapMode::Both,
+            http_base:         None,
+            min_memory:        None,
+            initrd_size:       None,
         };
 
         // Usa os defaults, mas adiciona a entrada de rescue
@@ -73,19 +349,239 @@ impl BootConfig {
 /// Uma entrada no menu de boot.
 #[derive(Debug, Clone)]
 pub struct Entry {
-    pub name:     String,
-    pub protocol: Protocol,
-    pub path:     String,
-    pub cmdline:  Option<String>,
-    pub modules:  Vec<Module>,
-    pub dtb_path: Option<String>,
+    pub name:              String,
+    pub protocol:          Protocol,
+    pub path:              String,
+    pub cmdline:           Option<String>,
+    pub modules:           Vec<Module>,
+    pub dtb_path:          Option<String>,
+    /// Imagem ISO/GPT cujo ESP embutido contém o kernel e os módulos.
+    pub iso_path:          Option<String>,
+    /// Fora do menu, mas ainda inicializável pelo nome (`default_entry`,
+    /// boot-once).
+    pub hidden:            bool,
+    /// Endereço físico fixo onde o kernel ELF deve ser carregado.
+    pub load_addr:         Option<u64>,
+    /// Entrega ao kernel o endereço da EFI System Table
+    /// (`BootInfo::efi_system_table_addr`).
+    pub pass_system_table: bool,
+    /// CRC32 esperado do arquivo do kernel (`kernel_crc32`).
+    pub kernel_crc32:      Option<u32>,
+    /// Pares `chave=valor` das linhas `param`, na ordem do arquivo
+    /// (`BootInfo::params_addr`).
+    pub params:            Vec<(String, String)>,
+    /// Arquivos que precisam existir para a entrada ser inicializável
+    /// (`requires`, ex: microcode).
+    pub requires:          Vec<String>,
+    /// Entrega ao kernel o tamanho e o cursor do console de texto do
+    /// firmware (`BootInfo::text_console`).
+    pub pass_text_console: bool,
+    /// Mapeamentos da memória física montados para o kernel (`map_mode`).
+    pub map_mode:          MapMode,
+    /// URL de onde kernel e módulos são baixados por HTTP (`http_base`).
+    pub http_base:         Option<String>,
+    /// RAM utilizável mínima, em bytes, para dar boot na entrada
+    /// (`min_memory`).
+    pub min_memory:        Option<u64>,
+    /// Tamanho esperado, em bytes, do initrd (primeiro módulo), conferido
+    /// após a leitura (`initrd_size`).
+    pub initrd_size:       Option<u64>,
 }
 
 /// Módulo carregável (InitRD, Drivers).
 #[derive(Debug, Clone)]
 pub struct Module {
-    pub path:    String,
-    pub cmdline: Option<String>,
+    pub path:        String,
+    pub cmdline:     Option<String>,
+    /// CRC32 esperado do arquivo (`module_crc32`).
+    pub crc32:       Option<u32>,
+    /// Formato declarado em `module_type`, conferido pelo magic.
+    pub module_type: Option<ModuleType>,
+}
+
+/// Formato de módulo declarado por `module_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleType {
+    /// Initrd: cpio newc, puro ou comprimido com gzip.
+    Initrd,
+    /// Arquivo cpio newc sem compressão.
+    Cpio,
+    /// Binário ELF (drivers, serviços).
+    Elf,
+}
+
+impl ModuleType {
+    /// Interpreta o valor de `module_type`; `None` para valores inválidos.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "initrd" => Some(ModuleType::Initrd),
+            "cpio" => Some(ModuleType::Cpio),
+            "elf" => Some(ModuleType::Elf),
+            _ => None,
+        }
+    }
+}
+
+/// Duração de um passo do contador do menu.
+pub const TIMEOUT_TICK_MS: u32 = 100;
+
+/// Valor de `timeout`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timeout {
+    /// `no` ou `0`: inicia a entrada padrão sem exibir o menu.
+    Disabled,
+    /// Contador em milissegundos (`5` são segundos, `500ms` meio segundo).
+    Millis(u32),
+    /// `infinite` (ou `-1`): o menu espera uma tecla, sem contador.
+    Infinite,
+}
+
+impl Timeout {
+    /// Interpreta o valor de `timeout`; `None` para valores inválidos.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_lowercase();
+        let ms = match s.as_str() {
+            "no" => return Some(Timeout::Disabled),
+            "infinite" | "-1" => return Some(Timeout::Infinite),
+            _ => match s.strip_suffix("ms") {
+                Some(ms) => ms.trim().parse().ok()?,
+                None => s.parse::<u32>().ok()?.checked_mul(1000)?,
+            },
+        };
+
+        match ms {
+            0 => Some(Timeout::Disabled),
+            ms => Some(Timeout::Millis(ms)),
+        }
+    }
+
+    /// Se o menu é exibido antes do boot.
+    pub fn shows_menu(self) -> bool {
+        !matches!(self, Timeout::Disabled)
+    }
+
+    /// Passos de `TIMEOUT_TICK_MS` até o boot automático; `None` sem
+    /// contador. Frações de passo contam como um passo inteiro.
+    pub fn ticks(self) -> Option<u32> {
+        match self {
+            Timeout::Millis(ms) => Some(ms.div_ceil(TIMEOUT_TICK_MS)),
+            Timeout::Disabled | Timeout::Infinite => None,
+        }
+    }
+}
+
+/// Ação executada quando o timeout do menu expira.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeoutAction {
+    /// Inicia a entrada selecionada (padrão).
+    #[default]
+    Boot,
+    /// Para a máquina (quiosques sem operador).
+    Halt,
+    /// Reinicia o sistema.
+    Reboot,
+}
+
+impl TimeoutAction {
+    /// Interpreta o valor de `timeout_action`; `None` para valores inválidos.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "boot" => Some(TimeoutAction::Boot),
+            "halt" => Some(TimeoutAction::Halt),
+            "reboot" => Some(TimeoutAction::Reboot),
+            _ => None,
+        }
+    }
+}
+
+/// Exportação do mapa de memória na serial, escolhida por `dump_memory_map`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemoryMapDump {
+    /// Sem exportação (padrão).
+    #[default]
+    Off,
+    /// Uma linha `base,len,type` por entrada (ver `memory::map::CsvRow`).
+    Csv,
+}
+
+impl MemoryMapDump {
+    /// Interpreta o valor de `dump_memory_map`; `None` para valores inválidos.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "csv" => Some(MemoryMapDump::Csv),
+            "no" | "off" | "false" => Some(MemoryMapDump::Off),
+            _ => None,
+        }
+    }
+}
+
+/// Destino da saída do bootloader, escolhido pela chave `console`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConsoleMode {
+    /// Apenas serial (máquinas sem monitor).
+    Serial,
+    /// Apenas vídeo; o menu não é ecoado na serial.
+    Video,
+    /// Serial e vídeo (padrão).
+    #[default]
+    Both,
+}
+
+impl ConsoleMode {
+    /// Interpreta o valor de `console`; `None` para valores inválidos.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "serial" => Some(ConsoleMode::Serial),
+            "video" => Some(ConsoleMode::Video),
+            "both" => Some(ConsoleMode::Both),
+            _ => None,
+        }
+    }
+
+    /// Se a saída serial está habilitada.
+    pub fn serial(self) -> bool {
+        matches!(self, ConsoleMode::Serial | ConsoleMode::Both)
+    }
+
+    /// Se a saída de vídeo está habilitada.
+    pub fn video(self) -> bool {
+        matches!(self, ConsoleMode::Video | ConsoleMode::Both)
+    }
+}
+
+/// Mapeamentos da memória física entregues ao kernel, escolhidos pela chave
+/// `map_mode` da entrada.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MapMode {
+    /// Só o identity map (virtual == físico).
+    Identity,
+    /// Só o HHDM; da metade inferior resta apenas o trampolim do salto.
+    Direct,
+    /// Identity map e HHDM (padrão).
+    #[default]
+    Both,
+}
+
+impl MapMode {
+    /// Interpreta o valor de `map_mode`; `None` para valores inválidos.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "identity" => Some(MapMode::Identity),
+            "direct" => Some(MapMode::Direct),
+            "both" => Some(MapMode::Both),
+            _ => None,
+        }
+    }
+
+    /// Se a memória física inteira é mapeada em virtual == físico.
+    pub fn identity(self) -> bool {
+        matches!(self, MapMode::Identity | MapMode::Both)
+    }
+
+    /// Se a memória física inteira é mapeada no HHDM.
+    pub fn direct(self) -> bool {
+        matches!(self, MapMode::Direct | MapMode::Both)
+    }
 }
 
 /// Protocolos suportados.
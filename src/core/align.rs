@@ -0,0 +1,30 @@
+//! Alinhamento de Endereços
+//!
+//! Arredondamento para potências de dois (páginas de 4 KiB, huge pages de
+//! 2 MiB, limites de 1 GiB) e conversão de tamanhos em páginas. Tudo `const`
+//! para servir também em constantes de layout.
+
+use crate::memory::layout::PAGE_SIZE;
+
+/// Arredonda `value` para baixo até um múltiplo de `align` (potência de dois).
+pub const fn align_down(value: u64, align: u64) -> u64 {
+    debug_assert!(align.is_power_of_two());
+    value & !(align - 1)
+}
+
+/// Arredonda `value` para cima até um múltiplo de `align` (potência de dois).
+///
+/// Retorna `None` se o resultado não couber em `u64` (ex: `u64::MAX`
+/// alinhado a 4 KiB), em vez de dar a volta para um endereço baixo.
+pub const fn align_up(value: u64, align: u64) -> Option<u64> {
+    debug_assert!(align.is_power_of_two());
+    match value.checked_add(align - 1) {
+        Some(end) => Some(end & !(align - 1)),
+        None => None,
+    }
+}
+
+/// Número de páginas de 4 KiB necessárias para `bytes` bytes.
+pub const fn div_ceil_pages(bytes: u64) -> u64 {
+    bytes.div_ceil(PAGE_SIZE)
+}
@@ -8,6 +8,25 @@ pub mod meta {
     pub const NAME: &str = "Ignite Bootloader";
     pub const VERSION: &str = env!("CARGO_PKG_VERSION");
     pub const VENDOR: &str = "Redstone OS Project";
+
+    /// Nome curto entregue ao kernel no handoff ("Ignite x.y.z").
+    pub const LOADER_NAME: &str = concat!("Ignite ", env!("CARGO_PKG_VERSION"));
+
+    /// Versão numérica: `major << 32 | minor << 16 | patch`.
+    pub const VERSION_CODE: u64 = (parse_u64(env!("CARGO_PKG_VERSION_MAJOR")) << 32)
+        | (parse_u64(env!("CARGO_PKG_VERSION_MINOR")) << 16)
+        | parse_u64(env!("CARGO_PKG_VERSION_PATCH"));
+
+    const fn parse_u64(s: &str) -> u64 {
+        let bytes = s.as_bytes();
+        let mut value = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            value = value * 10 + (bytes[i] - b'0') as u64;
+            i += 1;
+        }
+        value
+    }
 }
 
 /// Configurações de Memória e Stack.
@@ -19,6 +38,10 @@ pub mod memory {
     /// Suficiente para tabelas de páginas e alocação de estruturas de arquivos.
     pub const BOOTLOADER_HEAP_SIZE: usize = 4 * 1024 * 1024;
 
+    /// Menor Heap aceitável quando o firmware não consegue fornecer o tamanho
+    /// padrão (512 KiB). Abaixo disso nem o menu consegue ser montado.
+    pub const BOOTLOADER_HEAP_MIN_SIZE: usize = 512 * 1024;
+
     /// Endereço virtual onde o Kernel será linkado (Higher Half).
     /// -2GiB (0xFFFFFFFF80000000) é padrão comum em x86_64 (mcmodel=kernel).
     pub const KERNEL_VIRTUAL_BASE: u64 = 0xFFFF_8000_0000_0000;
@@ -41,6 +64,14 @@ pub mod hardware {
 pub mod limits {
     /// Tamanho máximo do arquivo de config (16 KiB).
     pub const MAX_CONFIG_SIZE: usize = 16 * 1024;
+    /// Entradas aceitas pelo parser de config.
+    pub const MAX_CONFIG_ENTRIES: usize = 128;
+    /// Níveis de aninhamento de entrada (`/` no início da linha) aceitos pelo
+    /// parser de config.
+    pub const MAX_ENTRY_DEPTH: usize = 8;
     /// Tamanho máximo do Kernel (proteção contra OOM no bootloader).
     pub const MAX_KERNEL_SIZE: usize = 64 * 1024 * 1024; // 64 MB
+    /// Tamanho máximo de cada módulo (InitRD, drivers), conferido antes de
+    /// alocar o buffer no pool do firmware.
+    pub const MAX_MODULE_SIZE: usize = 256 * 1024 * 1024; // 256 MB
 }
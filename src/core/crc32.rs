@@ -0,0 +1,37 @@
+//! CRC32 (IEEE 802.3)
+//!
+//! Mesmo polinômio usado pelo UEFI em cabeçalhos de tabela e pela GPT, para que
+//! um único helper sirva a todas as verificações de integridade do Ignite.
+
+/// Polinômio refletido do CRC32 IEEE.
+const POLY: u32 = 0xEDB8_8320;
+
+/// Tabela de 256 entradas gerada em tempo de compilação.
+const TABLE: [u32; 256] = build_table();
+
+const fn build_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ POLY
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Calcula o CRC32 de `data`.
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
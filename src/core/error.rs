@@ -32,6 +32,28 @@ pub enum BootError {
     /// Erros de Configuração (Parser, Validação).
     Config(ConfigError),
 
+    /// Erro de configuração numa linha do arquivo (1 = primeira linha).
+    ConfigLine { line: usize, error: ConfigError },
+
+    /// Erro de sistema de arquivos ao acessar `path`.
+    File {
+        path: ErrorPath,
+        kind: FileSystemError,
+    },
+
+    /// Erro num segmento do ELF (`index` é o índice do program header).
+    ElfSegment { index: usize, error: ElfError },
+
+    /// Erro de memória no intervalo físico `[addr, addr + size)`.
+    MemoryRegion {
+        addr:  u64,
+        size:  u64,
+        error: MemoryError,
+    },
+
+    /// Erros dos Protocolos de Boot (detecção, carregadores).
+    Protocol(ProtocolError),
+
     /// Erro genérico para casos não categorizados (Stubs, TODOs).
     Generic(&'static str),
 
@@ -39,6 +61,68 @@ pub enum BootError {
     Panic(&'static str),
 }
 
+impl BootError {
+    /// Erro de `kind` ao acessar `path`.
+    pub fn file(path: &str, kind: FileSystemError) -> Self {
+        BootError::File {
+            path: ErrorPath::new(path),
+            kind,
+        }
+    }
+
+    /// Se o erro é de memória, com ou sem o intervalo afetado.
+    pub fn is_memory(&self) -> bool {
+        matches!(self, BootError::Memory(_) | BootError::MemoryRegion { .. })
+    }
+}
+
+/// Bytes de caminho guardados em `BootError::File`.
+pub const MAX_ERROR_PATH: usize = 64;
+
+/// Caminho copiado para dentro do erro, truncado em `MAX_ERROR_PATH` bytes.
+///
+/// Um buffer fixo em vez de `String` mantém `BootError` `Copy` e permite
+/// criá-lo sem heap.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ErrorPath {
+    bytes: [u8; MAX_ERROR_PATH],
+    len:   u8,
+}
+
+impl ErrorPath {
+    /// Copia `path`, cortando no último caractere que couber inteiro.
+    pub fn new(path: &str) -> Self {
+        let mut len = path.len().min(MAX_ERROR_PATH);
+        while !path.is_char_boundary(len) {
+            len -= 1;
+        }
+
+        let mut bytes = [0u8; MAX_ERROR_PATH];
+        bytes[..len].copy_from_slice(&path.as_bytes()[..len]);
+        Self {
+            bytes,
+            len: len as u8,
+        }
+    }
+
+    /// O caminho guardado (já truncado).
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len as usize]).unwrap_or("")
+    }
+}
+
+impl fmt::Debug for ErrorPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+impl fmt::Display for ErrorPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Erros de I/O de Dispositivo (Hardware).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IoError {
@@ -61,8 +145,12 @@ pub enum FileSystemError {
     UnsupportedFsType,
     InvalidSize,
     NotRegularFile,
-    BufferTooSmall, // Capitalização corrigida
-    DeviceError,    // Re-mapa de IO se necessário no contexto de FS
+    BufferTooSmall,   // Capitalização corrigida
+    DeviceError,      // Re-mapa de IO se necessário no contexto de FS
+    Truncated,        // Arquivo terminou antes do tamanho esperado
+    FileTooLarge,     // Acima do limite de `core::config::limits`
+    ChecksumMismatch, // CRC32 diferente do declarado na configuração
+    InvalidEncoding,  // Conteúdo lido como texto não é UTF-8 válido
 }
 
 /// Erros de Memória.
@@ -76,6 +164,7 @@ pub enum MemoryError {
     InvalidAddress,
     InvalidSize,
     OutOfMemory,
+    RegionOverlap,
 }
 
 /// Erros de Executáveis (ELF/Kernel).
@@ -92,6 +181,11 @@ pub enum ElfError {
     SegmentMapFailed,
     SegmentCopyError,
     InvalidFormat,
+    /// Um segmento `PT_LOAD` toca endereços além da memória mapeada pelo
+    /// bootloader (identity map).
+    SegmentOutsideMappedMemory,
+    /// Dois segmentos `PT_LOAD` disputam os mesmos endereços virtuais.
+    OverlappingSegments,
 }
 
 /// Erros de Vídeo.
@@ -116,6 +210,29 @@ pub enum ConfigError {
     InvalidKey,
     ValueOutOfRange,
     Invalid(&'static str),
+    /// Entrada com mais `/` no início que o limite de aninhamento do parser.
+    NestingTooDeep,
+    /// Mais entradas que o limite do parser.
+    TooManyEntries,
+}
+
+/// Erros dos Protocolos de Boot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolError {
+    /// Entrada sem `protocol` com `protocol_auto_detect` desabilitado.
+    AutoDetectDisabled,
+    /// O kernel não corresponde a nenhum protocolo conhecido.
+    UnknownFormat,
+    /// Protocolo executado fora de `load_any` (ex: chainload via LoadImage).
+    NoNativeLoader,
+    /// Protocolo reconhecido, mas ainda sem implementação.
+    NotImplemented,
+    /// O kernel declara uma arquitetura que não roda nesta CPU (ver
+    /// `protos::arch::check_kernel_arch`).
+    ArchMismatch {
+        kernel: crate::protos::arch::KernelArch,
+        cpu:    crate::protos::arch::KernelArch,
+    },
 }
 
 // --- Conversões Automáticas (Syntactic Sugar para '?') ---
@@ -126,6 +243,12 @@ impl From<crate::uefi::Status> for BootError {
     }
 }
 
+impl From<crate::uefi::table::runtime::VariableError> for BootError {
+    fn from(e: crate::uefi::table::runtime::VariableError) -> Self {
+        BootError::Uefi(e.status())
+    }
+}
+
 impl From<IoError> for BootError {
     fn from(e: IoError) -> Self {
         BootError::Io(e)
@@ -162,6 +285,12 @@ impl From<ConfigError> for BootError {
     }
 }
 
+impl From<ProtocolError> for BootError {
+    fn from(e: ProtocolError) -> Self {
+        BootError::Protocol(e)
+    }
+}
+
 // --- Implementação de Display (Logs) ---
 
 impl fmt::Display for BootError {
@@ -174,6 +303,26 @@ impl fmt::Display for BootError {
             BootError::Elf(e) => write!(f, "ELF Error: {:?}", e),
             BootError::Video(e) => write!(f, "Video Error: {:?}", e),
             BootError::Config(e) => write!(f, "Config Error: {:?}", e),
+            BootError::ConfigLine { line, error } => {
+                write!(f, "Config Error: {:?} (linha {})", error, line)
+            },
+            BootError::File { path, kind } => write!(f, "FS Error: {:?} ({})", kind, path),
+            BootError::ElfSegment { index, error } => {
+                write!(f, "ELF Error: {:?} (segmento {})", error, index)
+            },
+            BootError::MemoryRegion { addr, size, error } => write!(
+                f,
+                "Memory Error: {:?} ([{:#x}..{:#x}))",
+                error,
+                addr,
+                addr.saturating_add(*size)
+            ),
+            BootError::Protocol(ProtocolError::ArchMismatch { kernel, cpu }) => write!(
+                f,
+                "Protocol Error: kernel {} nao roda nesta CPU ({})",
+                kernel, cpu
+            ),
+            BootError::Protocol(e) => write!(f, "Protocol Error: {:?}", e),
             BootError::Generic(s) => write!(f, "Generic Error: {}", s),
             BootError::Panic(s) => write!(f, "Panic: {}", s),
         }
@@ -212,3 +361,8 @@ impl fmt::Display for ConfigError {
         write!(f, "{:?}", self)
     }
 }
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
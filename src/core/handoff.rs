@@ -42,7 +42,42 @@ pub const BOOT_INFO_MAGIC: u64 = 0x524544_53544F4E45;
 
 /// Versão atual da estrutura de BootInfo. Incrementar se mudar o layout.
 /// v3: Adicionado hhdm_offset e hhdm_size para o novo subsistema de memoria.
-pub const BOOT_INFO_VERSION: u32 = 3;
+/// v4: Adicionado cpu_features (bitmask de `arch::x86::cpuid::CpuFeatures`).
+/// v5: Adicionado cmdline_addr e cmdline_len.
+/// v6: Adicionado loader_name_addr e loader_version.
+/// v7: Adicionada topologia da MADT (local_apic_addr, cpu_count, IO APICs).
+/// v8: Adicionado xsdt_addr e acpi_table_count (RSDP/XSDT já validados).
+/// v9: Adicionado modules_addr e module_count (array de `ModuleInfo`).
+/// v10: Adicionado pagetable_frames_addr e pagetable_frames_count.
+/// v11: Adicionado max_phys_addr.
+/// v12: Adicionado secure_boot.
+/// v13: Adicionado efi_system_table_addr.
+/// v14: Adicionado params_addr e params_count.
+/// v15: Adicionado text_console.
+/// v16: Adicionado map_mode.
+/// v17: Adicionado framebuffer.masks (formato Bitmask).
+/// v18: Adicionado secure_boot_state.
+/// v19: Adicionado framebuffers_addr e framebuffers_count (multi-GPU).
+pub const BOOT_INFO_VERSION: u32 = 19;
+
+/// `BootInfo::map_mode`: a memória física está em identity map.
+pub const MAP_MODE_IDENTITY: u32 = 1 << 0;
+
+/// `BootInfo::map_mode`: a memória física está no HHDM (`hhdm_offset`).
+pub const MAP_MODE_DIRECT: u32 = 1 << 1;
+
+/// `BootInfo::secure_boot_state`: Secure Boot desligado ou estado ilegível.
+pub const SECURE_BOOT_STATE_DISABLED: u8 = 0;
+
+/// `BootInfo::secure_boot_state`: Secure Boot ativo, aplicando as políticas.
+pub const SECURE_BOOT_STATE_ENABLED: u8 = 1;
+
+/// `BootInfo::secure_boot_state`: firmware em Setup Mode (chaves sendo
+/// cadastradas).
+pub const SECURE_BOOT_STATE_SETUP: u8 = 2;
+
+/// Quantidade máxima de IO APICs repassados em `BootInfo::io_apic_addrs`.
+pub const MAX_IO_APICS: usize = 8;
 
 /// Informações completas de Boot entregues ao Kernel.
 /// DEVE corresponder EXATAMENTE a forge/src/core/handoff.rs::BootInfo
@@ -93,6 +128,222 @@ pub struct BootInfo {
 
     /// Tamanho da RAM mapeada no HHDM (em bytes).
     pub hhdm_size: u64,
+
+    /// Recursos da CPU detectados via CPUID (bits de `CpuFeatures`).
+    pub cpu_features: u64,
+
+    /// Linha de comando do kernel (string ASCII/UTF-8 terminada em NUL).
+    /// `cmdline_len` não inclui o NUL; ambos são 0 se não houver cmdline.
+    pub cmdline_addr: u64,
+    pub cmdline_len:  u64,
+
+    /// Nome e versão do bootloader ("Ignite x.y.z", terminada em NUL).
+    pub loader_name_addr: u64,
+
+    /// Versão numérica do bootloader (`major << 32 | minor << 16 | patch`).
+    pub loader_version: u64,
+
+    /// Endereço físico do Local APIC (0 se a MADT não foi encontrada).
+    pub local_apic_addr: u64,
+
+    /// Processadores utilizáveis listados na MADT.
+    pub cpu_count: u32,
+
+    /// Número de entradas válidas em `io_apic_addrs`.
+    pub io_apic_count: u32,
+
+    /// Endereços físicos dos IO APICs.
+    pub io_apic_addrs: [u64; MAX_IO_APICS],
+
+    /// Endereço físico da XSDT com checksum validado (0 se ausente/inválida).
+    pub xsdt_addr: u64,
+
+    /// Número de tabelas referenciadas pela XSDT.
+    pub acpi_table_count: u64,
+
+    /// Array de `ModuleInfo` com todos os módulos carregados, na ordem da
+    /// configuração. O primeiro também aparece em `initramfs_addr`.
+    pub modules_addr: u64,
+    pub module_count: u64,
+
+    /// Array de `u64` com o endereço físico de cada frame das page tables do
+    /// bootloader (PML4 inclusa). Permite ao kernel recuperá-los depois de
+    /// montar suas próprias tabelas.
+    pub pagetable_frames_addr:  u64,
+    pub pagetable_frames_count: u64,
+
+    // --- Limite da RAM ---
+    /// Fim (exclusivo) da região `Usable` mais alta do mapa de memória.
+    /// MMIO e regiões reservadas acima da RAM não contam, então o valor serve
+    /// para dimensionar o alocador físico do kernel.
+    pub max_phys_addr: u64,
+
+    // --- Segurança ---
+    /// Secure Boot do firmware estava ativo (`SecureBoot == 1`, fora do Setup
+    /// Mode). Permite ao kernel exigir módulos assinados só nesse caso.
+    pub secure_boot: bool,
+
+    // --- Firmware ---
+    /// Endereço da EFI System Table, ou 0 se a entrada não tiver
+    /// `pass_system_table: yes`. Após o handoff só os Runtime Services são
+    /// válidos, e exigem `SetVirtualAddressMap` antes de serem chamados com o
+    /// mapeamento do kernel.
+    pub efi_system_table_addr: u64,
+
+    // --- Parâmetros ---
+    /// Pares das linhas `param: chave=valor` da entrada, gravados em sequência
+    /// como `chave\0valor\0`. `params_count` conta pares, não strings; ambos
+    /// são 0 se a entrada não tiver parâmetros.
+    pub params_addr:  u64,
+    pub params_count: u64,
+
+    // --- Console de Texto ---
+    /// Dimensões e cursor do ConOut do firmware no boot, para o renderizador
+    /// de texto do kernel continuar de onde o bootloader parou. Zerado sem
+    /// `pass_text_console: yes`.
+    pub text_console: TextConsoleInfo,
+
+    // --- Paginação ---
+    /// Mapeamentos montados em `cr3_phys` (`MAP_MODE_IDENTITY`,
+    /// `MAP_MODE_DIRECT`). Sem `MAP_MODE_IDENTITY`, a metade inferior só
+    /// contém a imagem do bootloader: `rdi` e `rsp` já chegam pelo HHDM
+    /// (`HHDM_BASE` + físico), mas os endereços dentro do BootInfo continuam
+    /// físicos e precisam de `hhdm_offset` somado.
+    pub map_mode: u32,
+
+    /// Estado do Secure Boot no boot (`SECURE_BOOT_STATE_*`). Ao contrário de
+    /// `secure_boot`, distingue o Setup Mode do desligado, o que interessa a
+    /// kernels de provisionamento que cadastram chaves.
+    pub secure_boot_state: u8,
+
+    // --- Vídeo (todas as saídas) ---
+    /// Array de `FramebufferInfo` com todas as saídas GOP ativas. O primeiro
+    /// é sempre `framebuffer`; os demais são outros displays, cada um no seu
+    /// melhor modo. Ambos são 0 sem framebuffer.
+    pub framebuffers_addr:  u64,
+    pub framebuffers_count: u64,
+}
+
+/// Assinatura do `HandoffHeader` ("IGNHDOFF" em ASCII).
+pub const HANDOFF_HEADER_MAGIC: u64 = 0x4947_4E48_444F_4646;
+
+/// Campos de primeiro nível de `BootInfo` (structs e arrays contam como um).
+/// Atualizar junto com `BOOT_INFO_VERSION`.
+pub const BOOT_INFO_FIELD_COUNT: u32 = 39;
+
+/// Cabeçalho autodescritivo gravado imediatamente antes do `BootInfo`.
+///
+/// Ferramentas que leem um dump de memória procuram `HANDOFF_HEADER_MAGIC`
+/// e descobrem versão e tamanho sem depender deste código-fonte. O kernel
+/// continua recebendo em `rdi` o endereço do `BootInfo`; o cabeçalho fica em
+/// `rdi - header_size`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandoffHeader {
+    /// `HANDOFF_HEADER_MAGIC`.
+    pub magic:       u64,
+    /// Versão do `BootInfo` que segue (`BOOT_INFO_VERSION`).
+    pub version:     u32,
+    /// Tamanho deste cabeçalho; o `BootInfo` começa nesse offset.
+    pub header_size: u32,
+    /// Cabeçalho + `BootInfo`, em bytes.
+    pub total_size:  u64,
+    /// `BOOT_INFO_FIELD_COUNT`.
+    pub field_count: u32,
+    pub _reserved:   u32,
+}
+
+impl HandoffHeader {
+    /// Cabeçalho para o `BootInfo` desta versão do bootloader.
+    pub const fn current() -> Self {
+        Self {
+            magic:       HANDOFF_HEADER_MAGIC,
+            version:     BOOT_INFO_VERSION,
+            header_size: core::mem::size_of::<HandoffHeader>() as u32,
+            total_size:  core::mem::size_of::<HandoffBlock>() as u64,
+            field_count: BOOT_INFO_FIELD_COUNT,
+            _reserved:   0,
+        }
+    }
+}
+
+/// Conteúdo do frame de handoff: cabeçalho seguido do `BootInfo`, sem
+/// padding entre os dois (o cabeçalho tem múltiplo de 8 bytes).
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct HandoffBlock {
+    pub header: HandoffHeader,
+    pub info:   BootInfo,
+}
+
+impl HandoffBlock {
+    pub fn new(info: BootInfo) -> Self {
+        Self {
+            header: HandoffHeader::current(),
+            info,
+        }
+    }
+}
+
+/// Estado do console de texto do firmware (`BootInfo::text_console`).
+///
+/// Em células de caractere; `columns == 0` indica que nada foi capturado.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextConsoleInfo {
+    pub columns:       u32,
+    pub rows:          u32,
+    pub cursor_column: u32,
+    pub cursor_row:    u32,
+}
+
+/// Módulo entregue ao kernel (elemento do array em `BootInfo::modules_addr`).
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ModuleInfo {
+    /// Endereço físico do conteúdo do módulo.
+    pub addr: u64,
+    /// Tamanho em bytes.
+    pub size: u64,
+    /// `module_cmdline` terminada em NUL; 0 se o módulo não tiver uma.
+    /// `cmdline_len` não inclui o NUL.
+    pub cmdline_addr: u64,
+    pub cmdline_len:  u64,
+}
+
+/// Falhas de `BootInfo::validate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandoffError {
+    /// `magic` não é `BOOT_INFO_MAGIC` (ponteiro errado ou memória corrompida).
+    InvalidMagic(u64),
+    /// Estrutura mais antiga que a versão exigida.
+    VersionTooOld { found: u32, required: u32 },
+}
+
+impl BootInfo {
+    /// Se a estrutura pode ser lida por um kernel que exige ao menos a versão
+    /// `kernel_min`.
+    ///
+    /// Campos só são acrescentados ao fim, então versões mais novas continuam
+    /// compatíveis com kernels antigos.
+    pub fn is_compatible(&self, kernel_min: u32) -> bool {
+        self.magic == BOOT_INFO_MAGIC && self.version >= kernel_min
+    }
+
+    /// Confere magic e versão contra as constantes deste arquivo
+    /// (`BOOT_INFO_MAGIC`, `BOOT_INFO_VERSION`).
+    pub fn validate(&self) -> core::result::Result<(), HandoffError> {
+        if self.magic != BOOT_INFO_MAGIC {
+            return Err(HandoffError::InvalidMagic(self.magic));
+        }
+        if !self.is_compatible(BOOT_INFO_VERSION) {
+            return Err(HandoffError::VersionTooOld {
+                found:    self.version,
+                required: BOOT_INFO_VERSION,
+            });
+        }
+        Ok(())
+    }
 }
 
 /// Detalhes sobre o Framebuffer Gráfico.
@@ -111,6 +362,19 @@ pub struct FramebufferInfo {
     pub stride: u32,
     /// Formato de pixel (como u32 para compatibilidade C).
     pub format: PixelFormat,
+    /// Máscaras dos canais em `PixelFormat::Bitmask`; zeradas nos demais
+    /// formatos.
+    pub masks:  PixelMasks,
+}
+
+/// Bits de cada canal dentro do pixel (`EFI_PIXEL_BITMASK`).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PixelMasks {
+    pub red:      u32,
+    pub green:    u32,
+    pub blue:     u32,
+    pub reserved: u32,
 }
 
 #[repr(u32)]
@@ -122,6 +386,14 @@ pub enum PixelFormat {
     BltOnly = 3,
 }
 
+impl PixelFormat {
+    /// Se há um framebuffer linear em `addr`. Em `BltOnly` o firmware só
+    /// desenha via `Blt()`, e escrever em `addr` não aparece na tela.
+    pub fn is_linear(self) -> bool {
+        self != Self::BltOnly
+    }
+}
+
 /// Entrada do mapa de memória física
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
@@ -31,24 +31,223 @@
 //!   não estiver conectado (e o hardware não tiver buffer FIFO profundo), pode
 //!   atrasar o boot.
 //!
+//! ## Formato na Serial
+//! `log_format` escolhe o prefixo de cada linha enviada à serial: nenhum
+//! (`plain`), o nível (`leveled`, padrão) ou hora do RTC e nível
+//! (`timestamped`). A linha é escrita direto na porta, sem montar uma
+//! `String` por registro.
+//!
+//! ## Buffer de Início
+//! Até o vídeo subir, os registros só chegam à serial. As últimas
+//! `EARLY_LOG_LINES` linhas ficam também em `EarlyLogBuffer` (memória fixa,
+//! sem alocação) e são reproduzidas no sink de vídeo quando ele é conectado
+//! por `attach_video_sink`. O mesmo buffer guarda o que chega enquanto o sink
+//! está desconectado (`detach_video_sink`), como durante o menu.
+//!
 //! ## 🛠️ TODOs e Roadmap
-//! - [ ] **TODO: (Feature)** Adicionar **In-Memory RingBuffer Logger**.
-//!   - *Motivo:* Permitir descarregar logs para o Kernel (via `BootInfo`) para
-//!     que o `dmesg` do Linux/Redstone mostre o que aconteceu no boot.
+//! - [ ] **TODO: (Feature)** Entregar os logs ao Kernel (via `BootInfo`) para
+//!   que o `dmesg` do Linux/Redstone mostre o que aconteceu no boot.
 //! - [ ] **TODO: (Refactor)** Suportar múltiplos sinks dinâmicos (Serial + GOP
 //!   + File).
 
-use log::{LevelFilter, Log, Metadata, Record};
+use core::{
+    fmt::{self, Write},
+    sync::atomic::{AtomicBool, AtomicU8, Ordering},
+};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// Linhas guardadas antes do sink de vídeo; as mais antigas são descartadas.
+pub const EARLY_LOG_LINES: usize = 32;
+
+/// Bytes por linha guardada; o excesso é truncado.
+pub const EARLY_LOG_LINE_LEN: usize = 128;
 
 /// Logger global estático.
 static LOGGER: GlobalLogger = GlobalLogger;
 
+/// Sink serial ativo (desligado por `console: video`).
+static SERIAL_SINK: AtomicBool = AtomicBool::new(true);
+
+/// Formato das linhas na serial (`LogFormat` como `u8`).
+static LOG_FORMAT: AtomicU8 = AtomicU8::new(LogFormat::Leveled as u8);
+
+/// Registros emitidos antes de `attach_video_sink`.
+static mut EARLY_LOG: EarlyLogBuffer = EarlyLogBuffer::new();
+
+/// Console de vídeo conectado por `attach_video_sink`.
+static mut VIDEO_SINK: Option<&'static mut dyn LogWriter> = None;
+
+/// Prefixo das linhas de log na serial, escolhido pela chave `log_format`.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Só a mensagem.
+    Plain = 0,
+    /// `[NIVEL] mensagem` (padrão).
+    #[default]
+    Leveled = 1,
+    /// `HH:MM:SS [NIVEL] mensagem`, com a hora do RTC.
+    Timestamped = 2,
+}
+
+impl LogFormat {
+    /// Interpreta o valor de `log_format`; `None` para valores inválidos.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "plain" => Some(LogFormat::Plain),
+            "leveled" => Some(LogFormat::Leveled),
+            "timestamped" => Some(LogFormat::Timestamped),
+            _ => None,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(LogFormat::Plain),
+            1 => Some(LogFormat::Leveled),
+            2 => Some(LogFormat::Timestamped),
+            _ => None,
+        }
+    }
+}
+
+/// Escreve um registro em `out` no formato `format`, terminado em `\n`.
+///
+/// `clock` só é consultado em `Timestamped` e devolve (hora, minuto,
+/// segundo); sem hora disponível, a linha sai como em `Leveled`.
+pub fn write_record(
+    out: &mut dyn Write,
+    format: LogFormat,
+    level: Level,
+    args: fmt::Arguments,
+    clock: &mut dyn FnMut() -> Option<(u8, u8, u8)>,
+) -> fmt::Result {
+    match format {
+        LogFormat::Plain => writeln!(out, "{}", args),
+        LogFormat::Timestamped => match clock() {
+            Some((hour, minute, second)) => writeln!(
+                out,
+                "{:02}:{:02}:{:02} [{}] {}",
+                hour, minute, second, level, args
+            ),
+            None => writeln!(out, "[{}] {}", level, args),
+        },
+        LogFormat::Leveled => writeln!(out, "[{}] {}", level, args),
+    }
+}
+
+/// `fmt::Write` direto na porta serial.
+struct SerialOut;
+
+impl Write for SerialOut {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        crate::arch::x86::serial::serial_print(s);
+        Ok(())
+    }
+}
+
 /// Trait para backends de escrita (Serial, Framebuffer).
 pub trait LogWriter: Send + Sync {
     fn write_char(&mut self, c: char);
     fn write_str(&mut self, s: &str);
 }
 
+/// Anel de tamanho fixo com as últimas linhas de log.
+pub struct EarlyLogBuffer {
+    lines: [[u8; EARLY_LOG_LINE_LEN]; EARLY_LOG_LINES],
+    lens:  [usize; EARLY_LOG_LINES],
+    /// Índice da linha mais antiga.
+    start: usize,
+    count: usize,
+}
+
+impl EarlyLogBuffer {
+    pub const fn new() -> Self {
+        Self {
+            lines: [[0; EARLY_LOG_LINE_LEN]; EARLY_LOG_LINES],
+            lens:  [0; EARLY_LOG_LINES],
+            start: 0,
+            count: 0,
+        }
+    }
+
+    /// Acrescenta uma linha formatada, descartando a mais antiga se o anel
+    /// estiver cheio.
+    pub fn push_fmt(&mut self, args: fmt::Arguments) {
+        let slot = (self.start + self.count) % EARLY_LOG_LINES;
+        if self.count == EARLY_LOG_LINES {
+            self.start = (self.start + 1) % EARLY_LOG_LINES;
+        } else {
+            self.count += 1;
+        }
+
+        let mut line = LineWriter {
+            buf: &mut self.lines[slot],
+            len: 0,
+        };
+        let _ = line.write_fmt(args);
+        self.lens[slot] = line.len;
+    }
+
+    /// Acrescenta `line` (sem `\n`).
+    pub fn push(&mut self, line: &str) {
+        self.push_fmt(format_args!("{}", line));
+    }
+
+    /// Quantidade de linhas guardadas.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Linhas da mais antiga para a mais recente.
+    pub fn lines(&self) -> impl Iterator<Item = &str> + '_ {
+        (0..self.count).map(move |i| {
+            let slot = (self.start + i) % EARLY_LOG_LINES;
+            // `LineWriter` só corta em fronteira de caractere
+            core::str::from_utf8(&self.lines[slot][..self.lens[slot]]).unwrap_or("")
+        })
+    }
+
+    /// Escreve as linhas guardadas em `writer`, em ordem, e esvazia o anel.
+    pub fn replay(&mut self, writer: &mut dyn LogWriter) {
+        for line in self.lines() {
+            writer.write_str(line);
+            writer.write_char('\n');
+        }
+        self.start = 0;
+        self.count = 0;
+    }
+}
+
+impl Default for EarlyLogBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Escreve em uma linha do anel, truncando no último caractere que couber.
+struct LineWriter<'a> {
+    buf: &'a mut [u8; EARLY_LOG_LINE_LEN],
+    len: usize,
+}
+
+impl Write for LineWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut end = s.len().min(EARLY_LOG_LINE_LEN - self.len);
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.buf[self.len..self.len + end].copy_from_slice(&s.as_bytes()[..end]);
+        self.len += end;
+        Ok(())
+    }
+}
+
 /// O Logger principal que despacha para o Writer registrado.
 struct GlobalLogger;
 
@@ -58,21 +257,39 @@ impl Log for GlobalLogger {
     }
 
     fn log(&self, record: &Record) {
-        if self.enabled(record.metadata()) {
-            // Aqui conectaríamos com um SerialWriter global protegido por Spinlock.
-            // Como `core` não pode depender de `hardware`, usamos uma função de hook.
-            // Para simplificar este arquivo core:
-            crate::arch::x86::serial::serial_print_fmt(format_args!(
-                "[{}] {}\n",
-                record.level(),
-                record.args()
-            ));
+        // SAFETY: o bootloader roda em um único processador, sem interrupções
+        // que registrem logs.
+        let (level, args) = (record.level(), record.args());
+        unsafe {
+            match &mut *core::ptr::addr_of_mut!(VIDEO_SINK) {
+                Some(sink) => {
+                    let _ = writeln!(SinkWriter(&mut **sink), "[{}] {}", level, args);
+                },
+                None => (*core::ptr::addr_of_mut!(EARLY_LOG))
+                    .push_fmt(format_args!("[{}] {}", level, args)),
+            }
+        }
+
+        if serial_sink_enabled() {
+            let _ = write_record(&mut SerialOut, log_format(), level, *args, &mut || {
+                crate::hardware::rtc::read_time().map(|t| (t.hour, t.minute, t.second))
+            });
         }
     }
 
     fn flush(&self) {}
 }
 
+/// Adapta um `LogWriter` para `core::fmt::Write`.
+struct SinkWriter<'a>(&'a mut dyn LogWriter);
+
+impl Write for SinkWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write_str(s);
+        Ok(())
+    }
+}
+
 /// Inicializa o sistema de logs.
 pub fn init() {
     // Configura o logger global.
@@ -81,6 +298,47 @@ pub fn init() {
     log::set_max_level(LevelFilter::Trace);
 }
 
+/// Conecta o console de vídeo: reproduz nele as linhas guardadas desde o
+/// início e passa a enviar os próximos registros também para ele.
+///
+/// # Safety
+/// Não pode ser chamada enquanto um registro de log estiver em andamento
+/// (ex.: de dentro de um `LogWriter`).
+pub unsafe fn attach_video_sink(sink: &'static mut dyn LogWriter) {
+    (*core::ptr::addr_of_mut!(EARLY_LOG)).replay(sink);
+    VIDEO_SINK = Some(sink);
+}
+
+/// Desconecta o console de vídeo, ex.: antes do menu ou do handoff, que
+/// passam a desenhar no framebuffer. Os registros seguintes voltam ao buffer
+/// de início e são reproduzidos no próximo `attach_video_sink`.
+///
+/// # Safety
+/// Mesma condição de `attach_video_sink`.
+pub unsafe fn detach_video_sink() {
+    VIDEO_SINK = None;
+}
+
+/// Liga ou desliga o envio dos registros de log para a serial.
+pub fn set_serial_sink(enabled: bool) {
+    SERIAL_SINK.store(enabled, Ordering::Relaxed);
+}
+
+/// Define o formato das linhas de log na serial.
+pub fn set_log_format(format: LogFormat) {
+    LOG_FORMAT.store(format as u8, Ordering::Relaxed);
+}
+
+/// Formato atual das linhas de log na serial.
+pub fn log_format() -> LogFormat {
+    LogFormat::from_u8(LOG_FORMAT.load(Ordering::Relaxed)).unwrap_or_default()
+}
+
+/// Se os registros de log estão sendo enviados para a serial.
+pub fn serial_sink_enabled() -> bool {
+    SERIAL_SINK.load(Ordering::Relaxed)
+}
+
 // Macro helper para print sem newline (estilo print!)
 #[macro_export]
 macro_rules! print {
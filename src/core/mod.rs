@@ -4,10 +4,13 @@
 //! estruturas de handoff para o kernel. Este módulo não deve depender de
 //! drivers específicos ou UEFI complexo.
 
+pub mod align;
 pub mod config;
+pub mod crc32;
 pub mod error;
 pub mod handoff;
 pub mod logging;
+pub mod sha256;
 pub mod colors;  // Cores ANSI para terminal serial
 pub mod types; // Expondo o módulo types.rs
 
@@ -4,14 +4,18 @@
 //! informações entre diferentes subsistemas (FS, Loader, Video) sem criar
 //! dependências circulares.
 
+use alloc::string::String;
+
 /// Representa um arquivo carregado na memória.
 /// Usado pelo FileSystem para retornar dados para o Loader.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct LoadedFile {
     /// Endereço físico do início do buffer.
-    pub ptr:  u64,
+    pub ptr:     u64,
     /// Tamanho do arquivo em bytes.
-    pub size: usize,
+    pub size:    usize,
+    /// Linha de comando do módulo (`module_cmdline`), repassada ao kernel.
+    pub cmdline: Option<String>,
 }
 
 /// Representa um Kernel carregado e pronto para execução.
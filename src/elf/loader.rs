@@ -3,21 +3,292 @@
 //! Lê segmentos `PT_LOAD`, aloca frames físicos correspondentes e mapeia
 //! no endereço virtual solicitado pelo Kernel.
 
+use alloc::vec::Vec;
+
 use goblin::elf::{program_header::PT_LOAD, Elf};
 
 use super::header::validate_header;
 use crate::{
     core::{
-        error::{BootError, ElfError, Result},
+        align::{align_down, div_ceil_pages},
+        error::{BootError, ElfError, MemoryError, Result},
         types::LoadedKernel,
     },
-    memory::{layout::PAGE_SIZE, FrameAllocator, PageTableManager},
+    memory::{
+        layout::{is_aligned, PlacedRegion, PAGE_SIZE},
+        map::check_range_usable,
+        region::PhysicalMemoryRegion,
+        FrameAllocator, PageTableManager,
+    },
 };
 
+/// Início da metade superior (canônica) do espaço virtual x86_64.
+/// Segmentos acima disso são mapeados explicitamente por `map_kernel`.
+const HIGHER_HALF_START: u64 = 0xFFFF_8000_0000_0000;
+
+/// Maior endereço físico possível em x86_64 (MAXPHYADDR = 52 bits).
+/// `p_paddr` acima disso indica que o linker apenas copiou `p_vaddr`.
+const MAX_PHYS_ADDR: u64 = 1 << 52;
+
+/// Dono da nota ELF em que o kernel anuncia suas capacidades ao bootloader.
+pub const REDSTONE_NOTE_OWNER: &str = "Redstone";
+
+/// Tipo da nota que declara o kernel fisicamente relocável: ele não depende
+/// de `load_addr`, só do mapeamento virtual dos segmentos.
+pub const NT_REDSTONE_RELOCATABLE: u32 = 1;
+
+/// Verifica se o ELF traz a nota `NT_REDSTONE_RELOCATABLE` em um `PT_NOTE`.
+pub fn is_relocatable(elf: &Elf, file_data: &[u8]) -> bool {
+    let Some(notes) = elf.iter_note_headers(file_data) else {
+        return false;
+    };
+    notes
+        .filter_map(|note| note.ok())
+        .any(|note| note.n_type == NT_REDSTONE_RELOCATABLE && note.name == REDSTONE_NOTE_OWNER)
+}
+
+/// Verifica se algum par de segmentos `PT_LOAD` compartilha endereços
+/// virtuais.
+///
+/// Com sobreposição, a cópia do segundo segmento sobrescreveria parte do
+/// primeiro, ou o mapeamento apontaria a mesma página para dois frames.
+pub fn check_segment_overlap(elf: &Elf) -> Result<()> {
+    let segments: Vec<PlacedRegion> = elf
+        .program_headers
+        .iter()
+        .filter(|ph| ph.p_type == PT_LOAD && ph.p_memsz > 0)
+        .map(|ph| PlacedRegion {
+            name: "segment",
+            base: ph.p_vaddr,
+            size: ph.p_memsz,
+        })
+        .collect();
+
+    for (i, a) in segments.iter().enumerate() {
+        if let Some((j, b)) = segments
+            .iter()
+            .enumerate()
+            .skip(i + 1)
+            .find(|(_, b)| a.overlaps(b))
+        {
+            log::error!(
+                "ELF: segmento {} [{:#x}..{:#x}) sobrepoe segmento {} [{:#x}..{:#x})",
+                i,
+                a.base,
+                a.end(),
+                j,
+                b.base,
+                b.end()
+            );
+            return Err(BootError::Elf(ElfError::OverlappingSegments));
+        }
+    }
+
+    Ok(())
+}
+
+/// Extremos de endereço tocados pelos segmentos `PT_LOAD` de um ELF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadBounds {
+    /// Fim (exclusivo) do maior `p_paddr + p_memsz` plausível.
+    pub phys_end:       u64,
+    /// Fim (exclusivo) dos segmentos na metade inferior (identity map).
+    pub lower_virt_end: u64,
+    /// Fim (exclusivo) de todos os segmentos virtuais.
+    pub virt_end:       u64,
+}
+
+impl LoadBounds {
+    /// Calcula os limites a partir do binário ELF bruto.
+    pub fn from_bytes(file_data: &[u8]) -> Result<Self> {
+        let elf = Elf::parse(file_data).map_err(|_| BootError::Elf(ElfError::ParseError))?;
+        Self::from_elf(&elf)
+    }
+
+    /// Calcula os limites percorrendo os program headers `PT_LOAD`.
+    pub fn from_elf(elf: &Elf) -> Result<Self> {
+        let mut bounds = Self {
+            phys_end:       0,
+            lower_virt_end: 0,
+            virt_end:       0,
+        };
+
+        for ph in elf.program_headers.iter() {
+            if ph.p_type != PT_LOAD || ph.p_memsz == 0 {
+                continue;
+            }
+
+            let virt_end = ph
+                .p_vaddr
+                .checked_add(ph.p_memsz)
+                .ok_or(BootError::Elf(ElfError::InvalidFormat))?;
+            bounds.virt_end = bounds.virt_end.max(virt_end);
+
+            if ph.p_vaddr < HIGHER_HALF_START {
+                bounds.lower_virt_end = bounds.lower_virt_end.max(virt_end);
+            }
+
+            if ph.p_paddr < MAX_PHYS_ADDR {
+                let phys_end = ph
+                    .p_paddr
+                    .checked_add(ph.p_memsz)
+                    .ok_or(BootError::Elf(ElfError::InvalidFormat))?;
+                bounds.phys_end = bounds.phys_end.max(phys_end);
+            }
+        }
+
+        Ok(bounds)
+    }
+
+    /// Verifica se tudo que depende do identity map cabe em `mapped_limit`.
+    ///
+    /// Segmentos na metade superior não entram na conta: `map_kernel` cria o
+    /// mapeamento deles sob demanda.
+    pub fn check_mapped_limit(&self, mapped_limit: u64) -> Result<()> {
+        if self.phys_end > mapped_limit || self.lower_virt_end > mapped_limit {
+            return Err(BootError::Elf(ElfError::SegmentOutsideMappedMemory));
+        }
+        Ok(())
+    }
+}
+
+/// Posição física fixa do kernel, pedida por `load_addr` na entrada.
+///
+/// Os segmentos mantêm entre si o mesmo deslocamento que têm no espaço
+/// virtual: o segmento de menor endereço começa em `phys_base`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedPlacement {
+    /// Endereço físico do primeiro segmento.
+    pub phys_base: u64,
+    /// Página virtual do primeiro segmento.
+    pub virt_base: u64,
+    /// Páginas reservadas a partir de `phys_base`.
+    pub pages:     usize,
+    /// `phys_base` veio do alocador porque a base pedida estava ocupada.
+    pub relocated: bool,
+}
+
+impl FixedPlacement {
+    /// Reserva a posição fixa para o binário ELF bruto.
+    pub fn reserve_bytes<A: FrameAllocator + ?Sized>(
+        file_data: &[u8],
+        phys_base: u64,
+        regions: &[PhysicalMemoryRegion],
+        allocator: &mut A,
+    ) -> Result<Self> {
+        let elf = Elf::parse(file_data).map_err(|_| BootError::Elf(ElfError::ParseError))?;
+        Self::reserve(&elf, file_data, phys_base, regions, allocator)
+    }
+
+    /// Confere que `[phys_base, phys_base + extensão dos segmentos)` está em
+    /// memória `Usable` e o aloca com `allocate_at`.
+    ///
+    /// Se o intervalo não estiver disponível e o kernel for relocável (ver
+    /// `is_relocatable`), os segmentos vão para páginas escolhidas pelo
+    /// alocador, mantendo o mesmo layout relativo; os endereços virtuais não
+    /// mudam. Sem a nota, a falha é devolvida como antes.
+    pub fn reserve<A: FrameAllocator + ?Sized>(
+        elf: &Elf,
+        file_data: &[u8],
+        phys_base: u64,
+        regions: &[PhysicalMemoryRegion],
+        allocator: &mut A,
+    ) -> Result<Self> {
+        if !is_aligned(phys_base) {
+            return Err(BootError::Memory(MemoryError::InvalidAlignment));
+        }
+
+        let mut virt_base = u64::MAX;
+        let mut virt_end = 0;
+        for ph in elf.program_headers.iter() {
+            if ph.p_type != PT_LOAD || ph.p_memsz == 0 {
+                continue;
+            }
+            virt_base = virt_base.min(align_down(ph.p_vaddr, PAGE_SIZE));
+            virt_end = virt_end.max(
+                ph.p_vaddr
+                    .checked_add(ph.p_memsz)
+                    .ok_or(BootError::Elf(ElfError::InvalidFormat))?,
+            );
+        }
+        if virt_end == 0 {
+            return Err(BootError::Elf(ElfError::InvalidFormat));
+        }
+
+        let pages = div_ceil_pages(virt_end - virt_base) as usize;
+        let phys_end = phys_base
+            .checked_add(pages as u64 * PAGE_SIZE)
+            .ok_or(BootError::Memory(MemoryError::InvalidAddress))?;
+
+        let err = match Self::allocate_exact(phys_base, phys_end, pages, regions, allocator) {
+            Ok(()) => {
+                return Ok(Self {
+                    phys_base,
+                    virt_base,
+                    pages,
+                    relocated: false,
+                })
+            },
+            Err(err) => err,
+        };
+
+        if !err.is_memory() || !is_relocatable(elf, file_data) {
+            return Err(err);
+        }
+
+        let relocated_base = allocator.allocate_frame(pages)?;
+        log::warn!(
+            "ELF: load_addr {:#x} indisponivel, kernel relocavel carregado em {:#x}",
+            phys_base,
+            relocated_base
+        );
+
+        Ok(Self {
+            phys_base: relocated_base,
+            virt_base,
+            pages,
+            relocated: true,
+        })
+    }
+
+    /// Aloca exatamente `[phys_base, phys_end)`, que precisa ser `Usable`.
+    fn allocate_exact<A: FrameAllocator + ?Sized>(
+        phys_base: u64,
+        phys_end: u64,
+        pages: usize,
+        regions: &[PhysicalMemoryRegion],
+        allocator: &mut A,
+    ) -> Result<()> {
+        check_range_usable(regions.iter().copied(), phys_base, phys_end)?;
+
+        let got = allocator.allocate_at(phys_base, pages)?;
+        if got != phys_base {
+            log::error!(
+                "ELF: load_addr {:#x} pedido, alocador devolveu {:#x}",
+                phys_base,
+                got
+            );
+            return Err(BootError::MemoryRegion {
+                addr:  phys_base,
+                size:  phys_end - phys_base,
+                error: MemoryError::AllocationFailed,
+            });
+        }
+        Ok(())
+    }
+
+    /// Endereço físico da página virtual `virt_page`.
+    fn phys_for(&self, virt_page: u64) -> u64 {
+        self.phys_base + (virt_page - self.virt_base)
+    }
+}
+
 // ?Sized permite aceitar Trait Objects
 pub struct ElfLoader<'a, A: FrameAllocator + ?Sized> {
-    allocator:  &'a mut A,
-    page_table: &'a mut PageTableManager,
+    allocator:    &'a mut A,
+    page_table:   &'a mut PageTableManager,
+    mapped_limit: Option<u64>,
+    load_base:    Option<(u64, Vec<PhysicalMemoryRegion>)>,
 }
 
 impl<'a, A: FrameAllocator + ?Sized> ElfLoader<'a, A> {
@@ -25,28 +296,65 @@ impl<'a, A: FrameAllocator + ?Sized> ElfLoader<'a, A> {
         Self {
             allocator,
             page_table,
+            mapped_limit: None,
+            load_base: None,
         }
     }
 
+    /// Carrega os segmentos a partir do endereço físico `base` em vez de onde
+    /// o alocador escolher. O intervalo precisa estar em `regions` `Usable`.
+    pub fn with_load_base(mut self, base: u64, regions: Vec<PhysicalMemoryRegion>) -> Self {
+        self.load_base = Some((base, regions));
+        self
+    }
+
+    /// Define até onde o identity map cobre a memória física. Com isso, o
+    /// loader rejeita kernels que iriam falhar (page fault) durante a carga.
+    pub fn with_mapped_limit(mut self, mapped_limit: u64) -> Self {
+        self.mapped_limit = Some(mapped_limit);
+        self
+    }
+
     /// Carrega, aloca e mapeia o Kernel na memória.
     ///
     /// # Passos
     /// 1. Parse e validação do header ELF.
-    /// 2. Iteração de segmentos `PT_LOAD`.
-    /// 3. Alocação de frames físicos (sob demanda).
-    /// 4. Cópia de dados (arquivo -> RAM física).
-    /// 5. Zeroização de BSS (memória restante do segmento).
-    /// 6. Mapeamento (tabela de páginas: virtual -> física).
+    /// 2. Verificação dos limites contra a memória mapeada (se configurada) e
+    ///    de sobreposição entre segmentos.
+    /// 3. Iteração de segmentos `PT_LOAD`.
+    /// 4. Alocação de frames físicos (sob demanda).
+    /// 5. Cópia de dados (arquivo -> RAM física).
+    /// 6. Zeroização de BSS (memória restante do segmento).
+    /// 7. Mapeamento (tabela de páginas: virtual -> física).
     pub fn load_kernel(&mut self, file_data: &[u8]) -> Result<LoadedKernel> {
         let elf = Elf::parse(file_data).map_err(|_| BootError::Elf(ElfError::ParseError))?;
         validate_header(&elf.header)?;
 
+        // Passo prévio: falhar cedo, com erro descritivo, em vez de gerar page
+        // fault no meio da cópia dos segmentos.
+        let bounds = LoadBounds::from_elf(&elf)?;
+        check_segment_overlap(&elf)?;
+        if let Some(limit) = self.mapped_limit {
+            bounds.check_mapped_limit(limit)?;
+        }
+
+        let fixed = match &self.load_base {
+            Some((base, regions)) => Some(FixedPlacement::reserve(
+                &elf,
+                file_data,
+                *base,
+                regions,
+                self.allocator,
+            )?),
+            None => None,
+        };
+
         let mut kernel_phys_start = u64::MAX;
         let mut kernel_phys_end = 0;
         let mut kernel_virt_start = u64::MAX;
         let mut kernel_virt_end = 0;
 
-        for ph in elf.program_headers.iter() {
+        for (index, ph) in elf.program_headers.iter().enumerate() {
             if ph.p_type != PT_LOAD || ph.p_memsz == 0 {
                 continue;
             }
@@ -62,19 +370,24 @@ impl<'a, A: FrameAllocator + ?Sized> ElfLoader<'a, A> {
 
             // Validação de limites do arquivo
             if file_end > file_data.len() {
-                return Err(BootError::Elf(ElfError::SegmentCopyError));
+                return Err(BootError::ElfSegment {
+                    index,
+                    error: ElfError::SegmentCopyError,
+                });
             }
 
             // Alinhamento de páginas
-            let page_offset = virt_start % PAGE_SIZE;
-            let virt_page_start = virt_start - page_offset;
-            let total_bytes_needed = (virt_end - virt_page_start) as usize;
-            let pages_needed = (total_bytes_needed + (PAGE_SIZE as usize - 1)) / PAGE_SIZE as usize;
+            let virt_page_start = align_down(virt_start, PAGE_SIZE);
+            let page_offset = virt_start - virt_page_start;
+            let pages_needed = div_ceil_pages(virt_end - virt_page_start) as usize;
 
             // Log de debug removido para output limpo
 
-            // 1. Alocar memória física
-            let phys_addr = self.allocator.allocate_frame(pages_needed)?;
+            // 1. Alocar memória física (ou usar a posição fixa já reservada)
+            let phys_addr = match &fixed {
+                Some(placement) => placement.phys_for(virt_page_start),
+                None => self.allocator.allocate_frame(pages_needed)?,
+            };
 
             // Rastrear limites físicos
             if phys_addr < kernel_phys_start {
@@ -93,9 +406,15 @@ impl<'a, A: FrameAllocator + ?Sized> ElfLoader<'a, A> {
                 kernel_virt_end = virt_end;
             }
 
-            // 2. Mapear na tabela de páginas (virtual -> física)
-            self.page_table
-                .map_kernel(phys_addr, virt_page_start, pages_needed, self.allocator)?;
+            // 2. Mapear na tabela de páginas (virtual -> física); segmentos
+            // sem PF_X recebem NX se `enforce_nx` estiver ligado
+            self.page_table.map_kernel_segment(
+                phys_addr,
+                virt_page_start,
+                pages_needed,
+                ph.is_executable(),
+                self.allocator,
+            )?;
 
             // 3. CRÍTICO: Garantir que o identity map tenha páginas 4KiB para esta região
             // Isso permite que o kernel acesse memória física via phys_to_virt()
@@ -10,7 +10,7 @@ pub mod loader;
 // O Parser agora é um detalhe interno do loader ou do header,
 // não precisamos expô-lo diretamente a menos que seja para debug.
 // Re-exportamos o Loader que é a interface principal.
-pub use loader::ElfLoader;
+pub use loader::{check_segment_overlap, is_relocatable, ElfLoader, FixedPlacement, LoadBounds};
 
 // Re-exportar erros específicos se necessário
 pub use crate::core::error::ElfError;
@@ -0,0 +1,149 @@
+//! Leitor de Arquivos cpio (newc)
+//!
+//! Initrds costumam ser arquivos cpio no formato "newc" (`070701`): cada
+//! arquivo é um cabeçalho ASCII de 110 bytes com campos hexadecimais, seguido
+//! do nome (com NUL) e do conteúdo, ambos completados até múltiplos de 4
+//! bytes. O registro `TRAILER!!!` marca o fim.
+//!
+//! Só leitura e sem cópias: as entradas apontam para o buffer original.
+
+use crate::core::align::align_up;
+
+/// Magic do formato newc.
+pub const NEWC_MAGIC: &[u8; 6] = b"070701";
+
+/// Nome do registro que encerra o arquivo.
+pub const TRAILER_NAME: &str = "TRAILER!!!";
+
+/// Tamanho do cabeçalho newc: magic + 13 campos de 8 dígitos hex.
+const HEADER_SIZE: usize = 110;
+
+/// Posições (em campos de 8 bytes após o magic) usadas do cabeçalho.
+const FIELD_MODE: usize = 1;
+const FIELD_FILESIZE: usize = 6;
+const FIELD_NAMESIZE: usize = 11;
+
+/// Arquivo dentro do cpio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpioEntry<'a> {
+    /// Caminho gravado no arquivo (ex: `bin/init`), sem o NUL.
+    pub name:   &'a str,
+    /// `c_mode`: tipo e permissões no formato do `stat`.
+    pub mode:   u32,
+    /// Tamanho do conteúdo em bytes.
+    pub size:   usize,
+    /// Offset do conteúdo a partir do início do arquivo cpio.
+    pub offset: usize,
+}
+
+impl<'a> CpioEntry<'a> {
+    /// Conteúdo da entrada dentro de `archive` (o mesmo buffer percorrido).
+    pub fn data(&self, archive: &'a [u8]) -> &'a [u8] {
+        &archive[self.offset..self.offset + self.size]
+    }
+}
+
+/// Iterador sobre as entradas de um cpio newc.
+///
+/// Termina no `TRAILER!!!` ou no primeiro cabeçalho inválido; use
+/// `reached_trailer` para distinguir um arquivo completo de um truncado.
+pub struct CpioIter<'a> {
+    data:    &'a [u8],
+    pos:     usize,
+    done:    bool,
+    trailer: bool,
+}
+
+impl<'a> CpioIter<'a> {
+    /// Se a iteração terminou no registro `TRAILER!!!`.
+    pub fn reached_trailer(&self) -> bool {
+        self.trailer
+    }
+
+    fn parse_next(&mut self) -> Option<CpioEntry<'a>> {
+        let header = self
+            .data
+            .get(self.pos..self.pos.checked_add(HEADER_SIZE)?)?;
+        if &header[..NEWC_MAGIC.len()] != NEWC_MAGIC {
+            return None;
+        }
+
+        let mode = field(header, FIELD_MODE)?;
+        let size = field(header, FIELD_FILESIZE)? as usize;
+        let name_size = field(header, FIELD_NAMESIZE)? as usize;
+
+        // O nome inclui o NUL final
+        let name_start = self.pos + HEADER_SIZE;
+        let name_bytes = self
+            .data
+            .get(name_start..name_start.checked_add(name_size)?)?;
+        let (&nul, name) = name_bytes.split_last()?;
+        if nul != 0 {
+            return None;
+        }
+        let name = core::str::from_utf8(name).ok()?;
+
+        let offset = align_up((name_start + name_size) as u64, 4)? as usize;
+        let end = offset.checked_add(size)?;
+        if end > self.data.len() {
+            return None;
+        }
+
+        self.pos = align_up(end as u64, 4)? as usize;
+        Some(CpioEntry {
+            name,
+            mode,
+            size,
+            offset,
+        })
+    }
+}
+
+impl<'a> Iterator for CpioIter<'a> {
+    type Item = CpioEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.parse_next() {
+            Some(entry) if entry.name == TRAILER_NAME => {
+                self.done = true;
+                self.trailer = true;
+                None
+            },
+            Some(entry) => Some(entry),
+            None => {
+                self.done = true;
+                None
+            },
+        }
+    }
+}
+
+/// Percorre as entradas de `data`, um arquivo cpio newc.
+pub fn iter(data: &[u8]) -> CpioIter<'_> {
+    CpioIter {
+        data,
+        pos: 0,
+        done: false,
+        trailer: false,
+    }
+}
+
+/// Procura `name` no arquivo e devolve seu conteúdo. Um `/` inicial é
+/// ignorado, já que o cpio grava caminhos relativos.
+pub fn find<'a>(data: &'a [u8], name: &str) -> Option<&'a [u8]> {
+    let name = name.trim_start_matches('/');
+    iter(data)
+        .find(|entry| entry.name == name)
+        .map(|entry| entry.data(data))
+}
+
+/// Lê o campo hexadecimal `index` do cabeçalho em `header`.
+fn field(header: &[u8], index: usize) -> Option<u32> {
+    let start = NEWC_MAGIC.len() + index * 8;
+    let text = core::str::from_utf8(&header[start..start + 8]).ok()?;
+    u32::from_str_radix(text, 16).ok()
+}
@@ -0,0 +1,439 @@
+//! Driver Nativo de Sistema de Arquivos ext2 (somente leitura)
+//!
+//! Permite carregar kernels de uma partição `/boot` ext2/ext3, comum em
+//! instalações Linux, sem depender do firmware. Cobre superbloco, descritores
+//! de grupo, busca de inodes por caminho e blocos diretos/indiretos.
+//!
+//! Inodes com extents (ext4) não são suportados: a leitura falha com
+//! `UnsupportedFsType` em vez de devolver dados errados.
+
+use alloc::{boxed::Box, rc::Rc, string::String, vec, vec::Vec};
+use core::cell::RefCell;
+
+use crate::{
+    core::error::{BootError, FileSystemError, Result},
+    fs::{
+        dev::BlockDevice,
+        vfs::{Directory, File, FileSystem, Metadata},
+    },
+};
+
+/// Posição do superbloco, em bytes, a partir do início da partição.
+const SUPERBLOCK_OFFSET: u64 = 1024;
+const SUPERBLOCK_SIZE: usize = 1024;
+
+/// `s_magic`.
+const EXT2_MAGIC: u16 = 0xEF53;
+
+/// Inode do diretório raiz.
+const ROOT_INODE: u32 = 2;
+
+/// Ponteiros diretos em `i_block` (seguidos de indireto simples, duplo e
+/// triplo).
+const DIRECT_BLOCKS: u64 = 12;
+
+/// `i_flags`: o inode usa árvore de extents (ext4).
+const EXTENTS_FL: u32 = 0x0008_0000;
+
+/// `s_feature_incompat`: descritores de grupo de 64 bytes.
+const INCOMPAT_64BIT: u32 = 0x80;
+
+/// Maior diretório lido de uma vez. `i_size` vem do disco e, sem limite, um
+/// inode corrompido pediria uma alocação de até 4 GiB.
+const MAX_DIR_SIZE: u64 = 4 * 1024 * 1024;
+
+const S_IFMT: u16 = 0xF000;
+const S_IFDIR: u16 = 0x4000;
+const S_IFREG: u16 = 0x8000;
+
+fn le16(buf: &[u8], off: usize) -> u16 {
+    u16::from_le_bytes([buf[off], buf[off + 1]])
+}
+
+fn le32(buf: &[u8], off: usize) -> u32 {
+    u32::from_le_bytes([buf[off], buf[off + 1], buf[off + 2], buf[off + 3]])
+}
+
+fn fs_error(e: FileSystemError) -> BootError {
+    BootError::FileSystem(e)
+}
+
+/// Campos do inode usados pelo driver.
+#[derive(Debug, Clone)]
+struct Inode {
+    mode:  u16,
+    size:  u64,
+    flags: u32,
+    block: [u32; 15],
+}
+
+impl Inode {
+    fn parse(raw: &[u8]) -> Self {
+        let mode = le16(raw, 0);
+        let mut size = le32(raw, 4) as u64;
+        // `i_size_high` só vale para arquivos regulares (em diretórios é ACL)
+        if mode & S_IFMT == S_IFREG {
+            size |= (le32(raw, 108) as u64) << 32;
+        }
+
+        let mut block = [0u32; 15];
+        for (i, b) in block.iter_mut().enumerate() {
+            *b = le32(raw, 40 + i * 4);
+        }
+
+        Self {
+            mode,
+            size,
+            flags: le32(raw, 32),
+            block,
+        }
+    }
+
+    fn is_dir(&self) -> bool {
+        self.mode & S_IFMT == S_IFDIR
+    }
+
+    fn is_regular(&self) -> bool {
+        self.mode & S_IFMT == S_IFREG
+    }
+}
+
+/// Geometria do volume e o dispositivo, compartilhados pelos diretórios e
+/// arquivos abertos.
+struct Volume<D: BlockDevice> {
+    device:           D,
+    block_size:       u64,
+    inodes_per_group: u32,
+    inode_size:       u64,
+    group_count:      u32,
+    /// Primeiro bloco da tabela de descritores de grupo.
+    gdt_block:        u64,
+    desc_size:        u64,
+}
+
+impl<D: BlockDevice> Volume<D> {
+    /// Lê `buf.len()` bytes a partir do byte `offset` da partição.
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> Result<()> {
+        let sector = self.device.block_size();
+        let skip = (offset % sector) as usize;
+        let sectors = (skip + buf.len()).div_ceil(sector as usize);
+
+        let mut tmp = vec![0u8; sectors * sector as usize];
+        self.device.read_blocks(offset / sector, &mut tmp)?;
+        buf.copy_from_slice(&tmp[skip..skip + buf.len()]);
+        Ok(())
+    }
+
+    /// Lê um bloco do FS; o bloco 0 representa um buraco (arquivo esparso).
+    fn read_block(&mut self, block: u64, buf: &mut [u8]) -> Result<()> {
+        if block == 0 {
+            buf.fill(0);
+            return Ok(());
+        }
+        self.read_at(block * self.block_size, buf)
+    }
+
+    fn read_inode(&mut self, number: u32) -> Result<Inode> {
+        if number == 0 {
+            return Err(fs_error(FileSystemError::FileNotFound));
+        }
+
+        let group = (number - 1) / self.inodes_per_group;
+        let index = (number - 1) % self.inodes_per_group;
+        if group >= self.group_count {
+            return Err(fs_error(FileSystemError::InvalidSize));
+        }
+
+        // `bg_inode_table` (parte baixa) no offset 8 do descritor
+        let mut desc = [0u8; 12];
+        let desc_offset = self.gdt_block * self.block_size + group as u64 * self.desc_size;
+        self.read_at(desc_offset, &mut desc)?;
+        let inode_table = le32(&desc, 8) as u64;
+
+        let mut raw = [0u8; 128];
+        self.read_at(
+            inode_table * self.block_size + index as u64 * self.inode_size,
+            &mut raw,
+        )?;
+        Ok(Inode::parse(&raw))
+    }
+
+    /// Traduz o bloco lógico `index` do inode para o bloco físico.
+    fn map_block(&mut self, inode: &Inode, index: u64) -> Result<u64> {
+        if inode.flags & EXTENTS_FL != 0 {
+            return Err(fs_error(FileSystemError::UnsupportedFsType));
+        }
+        if index < DIRECT_BLOCKS {
+            return Ok(inode.block[index as usize] as u64);
+        }
+
+        let per_block = self.block_size / 4;
+        let mut index = index - DIRECT_BLOCKS;
+        let mut span = per_block;
+
+        // Nível 0: indireto simples, 1: duplo, 2: triplo
+        for level in 0..3u32 {
+            if index < span {
+                let mut block = inode.block[DIRECT_BLOCKS as usize + level as usize] as u64;
+                for depth in (0..=level).rev() {
+                    if block == 0 {
+                        return Ok(0);
+                    }
+                    let slot = (index / per_block.pow(depth)) % per_block;
+                    let mut entry = [0u8; 4];
+                    self.read_at(block * self.block_size + slot * 4, &mut entry)?;
+                    block = le32(&entry, 0) as u64;
+                }
+                return Ok(block);
+            }
+            index -= span;
+            span = span.saturating_mul(per_block);
+        }
+
+        Err(fs_error(FileSystemError::InvalidSize))
+    }
+
+    /// Lê o conteúdo do inode a partir de `offset`. Retorna os bytes lidos.
+    fn read_data(&mut self, inode: &Inode, offset: u64, buf: &mut [u8]) -> Result<usize> {
+        if offset >= inode.size {
+            return Ok(0);
+        }
+
+        let len = (buf.len() as u64).min(inode.size - offset) as usize;
+        let block_size = self.block_size as usize;
+        let mut block = vec![0u8; block_size];
+        let mut done = 0;
+
+        while done < len {
+            let pos = offset + done as u64;
+            let within = (pos % self.block_size) as usize;
+            let n = (block_size - within).min(len - done);
+
+            let phys = self.map_block(inode, pos / self.block_size)?;
+            self.read_block(phys, &mut block)?;
+            buf[done..done + n].copy_from_slice(&block[within..within + n]);
+            done += n;
+        }
+
+        Ok(len)
+    }
+
+    /// Entradas de um diretório como `(nome, inode)`.
+    fn read_dir(&mut self, dir: &Inode) -> Result<Vec<(String, u32)>> {
+        if dir.size > MAX_DIR_SIZE {
+            return Err(fs_error(FileSystemError::InvalidSize));
+        }
+
+        let mut data = vec![0u8; dir.size as usize];
+        self.read_data(dir, 0, &mut data)?;
+
+        let mut entries = Vec::new();
+        let mut off = 0;
+        while off + 8 <= data.len() {
+            let inode = le32(&data, off);
+            let rec_len = le16(&data, off + 4) as usize;
+            let name_len = data[off + 6] as usize;
+
+            if rec_len < 8 || off + rec_len > data.len() || 8 + name_len > rec_len {
+                return Err(fs_error(FileSystemError::InvalidSize));
+            }
+
+            // Inode 0 marca uma entrada removida
+            if inode != 0 {
+                let name = &data[off + 8..off + 8 + name_len];
+                entries.push((String::from_utf8_lossy(name).into_owned(), inode));
+            }
+            off += rec_len;
+        }
+
+        Ok(entries)
+    }
+
+    /// Resolve `path` a partir de `dir`, um componente por vez.
+    fn lookup(&mut self, dir: &Inode, path: &str) -> Result<Inode> {
+        let mut current = dir.clone();
+
+        for component in path
+            .split(['/', '\\'])
+            .filter(|c| !c.is_empty() && *c != ".")
+        {
+            if !current.is_dir() {
+                return Err(fs_error(FileSystemError::FileNotFound));
+            }
+            let number = self
+                .read_dir(&current)?
+                .into_iter()
+                .find(|(name, _)| name == component)
+                .map(|(_, inode)| inode)
+                .ok_or(fs_error(FileSystemError::FileNotFound))?;
+            current = self.read_inode(number)?;
+        }
+
+        Ok(current)
+    }
+}
+
+/// Volume ext2 montado somente leitura.
+pub struct Ext2FileSystem<D: BlockDevice> {
+    volume: Rc<RefCell<Volume<D>>>,
+}
+
+impl<D: BlockDevice> Ext2FileSystem<D> {
+    /// Lê o superbloco e monta o volume.
+    pub fn mount(device: D) -> Result<Self> {
+        let mut volume = Volume {
+            device,
+            block_size: 1024,
+            inodes_per_group: 0,
+            inode_size: 128,
+            group_count: 0,
+            gdt_block: 0,
+            desc_size: 32,
+        };
+
+        let mut sb = vec![0u8; SUPERBLOCK_SIZE];
+        volume.read_at(SUPERBLOCK_OFFSET, &mut sb)?;
+
+        if le16(&sb, 56) != EXT2_MAGIC {
+            return Err(fs_error(FileSystemError::InvalidSignature));
+        }
+
+        // Blocos de 1 KiB a 64 KiB
+        let log_block_size = le32(&sb, 24);
+        if log_block_size > 6 {
+            return Err(fs_error(FileSystemError::InvalidSize));
+        }
+
+        let blocks_count = le32(&sb, 4);
+        let first_data_block = le32(&sb, 20);
+        let blocks_per_group = le32(&sb, 32);
+        let inodes_per_group = le32(&sb, 40);
+        if blocks_per_group == 0 || inodes_per_group == 0 || first_data_block >= blocks_count {
+            return Err(fs_error(FileSystemError::InvalidSize));
+        }
+
+        // Revisão 0 tem inodes fixos de 128 bytes
+        let inode_size = if le32(&sb, 76) >= 1 {
+            le16(&sb, 88) as u64
+        } else {
+            128
+        };
+        if inode_size < 128 {
+            return Err(fs_error(FileSystemError::InvalidSize));
+        }
+
+        let desc_size = if le32(&sb, 96) & INCOMPAT_64BIT != 0 {
+            (le16(&sb, 254) as u64).max(32)
+        } else {
+            32
+        };
+
+        volume.block_size = 1024 << log_block_size;
+        volume.inodes_per_group = inodes_per_group;
+        volume.inode_size = inode_size;
+        volume.group_count = (blocks_count - first_data_block).div_ceil(blocks_per_group);
+        volume.gdt_block = first_data_block as u64 + 1;
+        volume.desc_size = desc_size;
+
+        Ok(Self {
+            volume: Rc::new(RefCell::new(volume)),
+        })
+    }
+}
+
+impl<D: BlockDevice + 'static> FileSystem for Ext2FileSystem<D> {
+    fn root(&mut self) -> Result<Box<dyn Directory>> {
+        let inode = self.volume.borrow_mut().read_inode(ROOT_INODE)?;
+        Ok(Box::new(Ext2Directory {
+            volume: self.volume.clone(),
+            inode,
+        }))
+    }
+
+    fn name(&self) -> &str {
+        "EXT2"
+    }
+}
+
+/// Diretório aberto em um volume ext2.
+struct Ext2Directory<D: BlockDevice> {
+    volume: Rc<RefCell<Volume<D>>>,
+    inode:  Inode,
+}
+
+impl<D: BlockDevice + 'static> Directory for Ext2Directory<D> {
+    fn open_file(&mut self, path: &str) -> Result<Box<dyn File>> {
+        let inode = self.volume.borrow_mut().lookup(&self.inode, path)?;
+        if !inode.is_regular() {
+            return Err(fs_error(FileSystemError::NotRegularFile));
+        }
+
+        Ok(Box::new(Ext2File {
+            volume: self.volume.clone(),
+            inode,
+            pos: 0,
+        }))
+    }
+
+    fn open_dir(&mut self, path: &str) -> Result<Box<dyn Directory>> {
+        let inode = self.volume.borrow_mut().lookup(&self.inode, path)?;
+        if !inode.is_dir() {
+            return Err(fs_error(FileSystemError::InvalidPath));
+        }
+
+        Ok(Box::new(Ext2Directory {
+            volume: self.volume.clone(),
+            inode,
+        }))
+    }
+
+    fn list(&mut self) -> Result<Vec<String>> {
+        Ok(self
+            .volume
+            .borrow_mut()
+            .read_dir(&self.inode)?
+            .into_iter()
+            .map(|(name, _)| name)
+            .filter(|name| name != "." && name != "..")
+            .collect())
+    }
+}
+
+/// Arquivo regular aberto em um volume ext2.
+struct Ext2File<D: BlockDevice> {
+    volume: Rc<RefCell<Volume<D>>>,
+    inode:  Inode,
+    pos:    u64,
+}
+
+impl<D: BlockDevice> File for Ext2File<D> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = self
+            .volume
+            .borrow_mut()
+            .read_data(&self.inode, self.pos, buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> Result<usize> {
+        Err(fs_error(FileSystemError::WriteError))
+    }
+
+    fn seek(&mut self, offset: u64) -> Result<u64> {
+        self.pos = offset;
+        Ok(offset)
+    }
+
+    fn metadata(&self) -> Result<Metadata> {
+        Ok(Metadata {
+            size:        self.inode.size,
+            is_dir:      false,
+            is_readonly: true,
+        })
+    }
+
+    fn size(&mut self) -> Result<u64> {
+        Ok(self.inode.size)
+    }
+}
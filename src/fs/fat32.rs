@@ -1,117 +1,640 @@
-//! Driver Nativo de Sistema de Arquivos FAT32
+//! Driver Nativo de Sistema de Arquivos FAT (somente leitura)
 //!
-//! Permite ler partições FAT32 diretamente, sem depender do UEFI.
-//! Útil para montar partições extras que o firmware não reconheceu.
+//! Permite ler partições FAT diretamente, sem depender do UEFI. Útil para
+//! montar partições extras que o firmware não reconheceu e o ESP embutido em
+//! imagens ISO híbridas (`iso_path`), que costuma ser FAT12/16.
+//!
+//! O tipo (FAT12, FAT16 ou FAT32) vem da contagem de clusters, como manda a
+//! especificação. Nomes longos (LFN) são lidos e conferidos pelo checksum do
+//! nome curto; a busca por caminho ignora maiúsculas/minúsculas.
 
-use alloc::{boxed::Box, vec};
+use alloc::{boxed::Box, rc::Rc, string::String, vec, vec::Vec};
+use core::cell::RefCell;
 
 use crate::{
-    core::error::{BootError, Result},
+    core::error::{BootError, FileSystemError, Result},
     fs::{
         dev::BlockDevice,
-        vfs::{Directory, FileSystem},
+        path::normalize_path,
+        vfs::{Directory, File, FileSystem, Metadata},
     },
 };
 
-/// BPB (Bios Parameter Block) para FAT32.
-#[repr(C, packed)]
-#[derive(Debug, Copy, Clone)]
-struct BiosParameterBlock {
-    jmp_boot:            [u8; 3],
-    oem_name:            [u8; 8],
-    bytes_per_sector:    u16,
-    sectors_per_cluster: u8,
-    reserved_sectors:    u16,
-    num_fats:            u8,
-    root_entry_count:    u16,
-    total_sectors_16:    u16,
-    media:               u8,
-    fat_size_16:         u16,
-    sectors_per_track:   u16,
-    num_heads:           u16,
-    hidden_sectors:      u32,
-    total_sectors_32:    u32,
-}
-
-/// Extended BPB.
-#[repr(C, packed)]
-#[derive(Debug, Copy, Clone)]
-#[allow(dead_code)]
-struct Fat32Ext {
-    fat_size_32:        u32,
-    ext_flags:          u16,
-    fs_version:         u16,
-    root_cluster:       u32,
-    fs_info:            u16,
-    backup_boot_sector: u16,
-    reserved:           [u8; 12],
-    drive_number:       u8,
-    reserved1:          u8,
-    boot_signature:     u8,
-    volume_id:          u32,
-    volume_label:       [u8; 11],
-    fs_type:            [u8; 8],
-}
-
-#[allow(dead_code)]
+/// Tamanho de uma entrada de diretório.
+const DIR_ENTRY_SIZE: usize = 32;
+
+/// Limite de um diretório FAT (65536 entradas).
+const MAX_DIR_SIZE: u64 = 65536 * DIR_ENTRY_SIZE as u64;
+
+/// Janela da FAT mantida em memória: seguir uma cadeia lê a tabela em
+/// sequência, e sem ela cada cluster custaria uma leitura do dispositivo.
+const FAT_CACHE_SIZE: u64 = 4096;
+
+/// Caracteres UCS-2 por entrada LFN e seus offsets na entrada.
+const LFN_CHARS: usize = 13;
+const LFN_OFFSETS: [usize; LFN_CHARS] = [1, 3, 5, 7, 9, 14, 16, 18, 20, 22, 24, 28, 30];
+
+const ATTR_VOLUME_ID: u8 = 0x08;
+const ATTR_DIRECTORY: u8 = 0x10;
+const ATTR_LONG_NAME: u8 = 0x0F;
+
+/// Primeiro byte do nome: fim do diretório e entrada removida.
+const ENTRY_END: u8 = 0x00;
+const ENTRY_DELETED: u8 = 0xE5;
+
+/// `DIR_NTRes`: base e extensão do nome curto em minúsculas.
+const NT_LOWER_BASE: u8 = 0x08;
+const NT_LOWER_EXT: u8 = 0x10;
+
+fn le16(buf: &[u8], off: usize) -> u16 {
+    u16::from_le_bytes([buf[off], buf[off + 1]])
+}
+
+fn le32(buf: &[u8], off: usize) -> u32 {
+    u32::from_le_bytes([buf[off], buf[off + 1], buf[off + 2], buf[off + 3]])
+}
+
+fn fs_error(e: FileSystemError) -> BootError {
+    BootError::FileSystem(e)
+}
+
+/// Variante da FAT, pela contagem de clusters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FatType {
+    Fat12,
+    Fat16,
+    Fat32,
+}
+
+impl FatType {
+    fn from_cluster_count(clusters: u32) -> Self {
+        if clusters < 4085 {
+            FatType::Fat12
+        } else if clusters < 65525 {
+            FatType::Fat16
+        } else {
+            FatType::Fat32
+        }
+    }
+
+    /// Valores a partir deste marcam o fim da cadeia.
+    fn end_of_chain(self) -> u32 {
+        match self {
+            FatType::Fat12 => 0xFF8,
+            FatType::Fat16 => 0xFFF8,
+            FatType::Fat32 => 0x0FFF_FFF8,
+        }
+    }
+}
+
+/// Arquivo ou diretório encontrado numa entrada de diretório. Cluster 0 num
+/// diretório é a raiz fixa de FAT12/16.
+#[derive(Debug, Clone)]
+struct Node {
+    cluster: u32,
+    size:    u64,
+    is_dir:  bool,
+}
+
+/// Entrada de diretório: nome longo (ou curto, se não houver) e o nome
+/// curto 8.3.
+struct DirEntry {
+    name:  String,
+    short: String,
+    node:  Node,
+}
+
+/// Nome curto `BASE.EXT`, respeitando as minúsculas de `DIR_NTRes`.
+fn short_name(raw: &[u8]) -> String {
+    let mut base = [0u8; 8];
+    base.copy_from_slice(&raw[..8]);
+    // 0x05 no primeiro byte representa um 0xE5 real (KANJI)
+    if base[0] == 0x05 {
+        base[0] = ENTRY_DELETED;
+    }
+
+    let mut name = String::new();
+    let lower = |b: u8, flag: u8| {
+        if raw[12] & flag != 0 {
+            b.to_ascii_lowercase()
+        } else {
+            b
+        }
+    };
+    for &b in base.iter().take_while(|&&b| b != b' ') {
+        name.push(lower(b, NT_LOWER_BASE) as char);
+    }
+    let ext = &raw[8..11];
+    if ext[0] != b' ' {
+        name.push('.');
+        for &b in ext.iter().take_while(|&&b| b != b' ') {
+            name.push(lower(b, NT_LOWER_EXT) as char);
+        }
+    }
+    name
+}
+
+/// Checksum do nome curto gravado em cada entrada LFN.
+fn short_name_checksum(raw: &[u8]) -> u8 {
+    raw[..11]
+        .iter()
+        .fold(0u8, |sum, &b| sum.rotate_right(1).wrapping_add(b))
+}
+
+/// Nome longo sendo montado a partir das entradas LFN, que vêm em ordem
+/// reversa antes da entrada curta.
+#[derive(Default)]
+struct LongName {
+    chars:    Vec<u16>,
+    checksum: u8,
+    next:     u8,
+}
+
+impl LongName {
+    fn push(&mut self, raw: &[u8]) {
+        let order = raw[0] & 0x1F;
+        if raw[0] & 0x40 != 0 {
+            // Última parte do nome (primeira no disco)
+            self.chars = vec![0xFFFF; order as usize * LFN_CHARS];
+            self.checksum = raw[13];
+        } else if order == 0 || order != self.next || raw[13] != self.checksum {
+            self.chars.clear();
+        }
+        if order == 0 || self.chars.is_empty() {
+            return;
+        }
+
+        let start = (order as usize - 1) * LFN_CHARS;
+        for (i, &off) in LFN_OFFSETS.iter().enumerate() {
+            self.chars[start + i] = le16(raw, off);
+        }
+        self.next = order - 1;
+    }
+
+    /// Nome completo, se todas as partes chegaram e o checksum confere com a
+    /// entrada curta `raw`.
+    fn take(&mut self, raw: &[u8]) -> Option<String> {
+        let chars = core::mem::take(&mut self.chars);
+        if chars.is_empty() || self.next != 0 || self.checksum != short_name_checksum(raw) {
+            return None;
+        }
+        let len = chars
+            .iter()
+            .position(|&c| c == 0 || c == 0xFFFF)
+            .unwrap_or(chars.len());
+        Some(
+            char::decode_utf16(chars[..len].iter().copied())
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect(),
+        )
+    }
+}
+
+/// Geometria do volume e o dispositivo, compartilhados pelos diretórios e
+/// arquivos abertos. Offsets em bytes a partir do início da partição.
+struct Volume<D: BlockDevice> {
+    device:        D,
+    fat_type:      FatType,
+    cluster_size:  u64,
+    fat_start:     u64,
+    /// Raiz fixa de FAT12/16 (vazia em FAT32).
+    root_start:    u64,
+    root_size:     u64,
+    data_start:    u64,
+    cluster_count: u32,
+    /// Primeiro cluster da raiz em FAT32.
+    root_cluster:  u32,
+    /// Janela da FAT em cache e seu offset dentro da tabela.
+    fat_cache:     Vec<u8>,
+    fat_cache_at:  Option<u64>,
+}
+
+impl<D: BlockDevice> Volume<D> {
+    /// Lê `buf.len()` bytes a partir do byte `offset` da partição. Setores
+    /// inteiros vão direto para `buf`; só as pontas passam por um setor
+    /// temporário, já que `buf` pode ser o kernel inteiro.
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> Result<()> {
+        let sector = self.device.block_size();
+        let mut done = 0;
+
+        while done < buf.len() {
+            let pos = offset + done as u64;
+            let skip = (pos % sector) as usize;
+            let rest = buf.len() - done;
+
+            if skip == 0 && rest >= sector as usize {
+                let n = rest - rest % sector as usize;
+                self.device
+                    .read_blocks(pos / sector, &mut buf[done..done + n])?;
+                done += n;
+            } else {
+                let n = (sector as usize - skip).min(rest);
+                let mut tmp = vec![0u8; sector as usize];
+                self.device.read_blocks(pos / sector, &mut tmp)?;
+                buf[done..done + n].copy_from_slice(&tmp[skip..skip + n]);
+                done += n;
+            }
+        }
+        Ok(())
+    }
+
+    /// Lê bytes da FAT a partir de `offset` (relativo ao início da tabela),
+    /// pela janela em cache.
+    fn read_fat(&mut self, offset: u64, buf: &mut [u8]) -> Result<()> {
+        let window = offset - offset % FAT_CACHE_SIZE;
+        // Entrada FAT12 dividida entre duas janelas
+        if offset + buf.len() as u64 > window + FAT_CACHE_SIZE {
+            return self.read_at(self.fat_start + offset, buf);
+        }
+
+        if self.fat_cache_at != Some(window) {
+            let mut cache = core::mem::take(&mut self.fat_cache);
+            cache.resize(FAT_CACHE_SIZE as usize, 0);
+            self.fat_cache_at = None;
+            self.read_at(self.fat_start + window, &mut cache)?;
+            self.fat_cache = cache;
+            self.fat_cache_at = Some(window);
+        }
+
+        let start = (offset - window) as usize;
+        buf.copy_from_slice(&self.fat_cache[start..start + buf.len()]);
+        Ok(())
+    }
+
+    fn root(&self) -> Node {
+        Node {
+            cluster: if self.fat_type == FatType::Fat32 {
+                self.root_cluster
+            } else {
+                0
+            },
+            size:    0,
+            is_dir:  true,
+        }
+    }
+
+    fn cluster_offset(&self, cluster: u32) -> u64 {
+        self.data_start + (cluster as u64 - 2) * self.cluster_size
+    }
+
+    /// Cluster seguinte a `cluster` na FAT; `None` no fim da cadeia.
+    fn next_cluster(&mut self, cluster: u32) -> Result<Option<u32>> {
+        let mut entry = [0u8; 4];
+        let value = match self.fat_type {
+            FatType::Fat12 => {
+                let off = cluster as u64 + cluster as u64 / 2;
+                self.read_fat(off, &mut entry[..2])?;
+                let pair = le16(&entry, 0) as u32;
+                if cluster & 1 == 1 {
+                    pair >> 4
+                } else {
+                    pair & 0xFFF
+                }
+            },
+            FatType::Fat16 => {
+                self.read_fat(cluster as u64 * 2, &mut entry[..2])?;
+                le16(&entry, 0) as u32
+            },
+            FatType::Fat32 => {
+                self.read_fat(cluster as u64 * 4, &mut entry)?;
+                le32(&entry, 0) & 0x0FFF_FFFF
+            },
+        };
+
+        if value >= self.fat_type.end_of_chain() {
+            Ok(None)
+        } else {
+            self.check_cluster(value).map(Some)
+        }
+    }
+
+    /// Recusa clusters livres, defeituosos ou além do volume.
+    fn check_cluster(&self, cluster: u32) -> Result<u32> {
+        if cluster < 2 || cluster - 2 >= self.cluster_count {
+            return Err(fs_error(FileSystemError::InvalidSize));
+        }
+        Ok(cluster)
+    }
+
+    /// Até `count` clusters da cadeia que começa em `first`, em ordem. Uma
+    /// cadeia em ciclo para no limite em vez de prender o boot.
+    fn chain(&mut self, first: u32, count: u64) -> Result<Vec<u32>> {
+        let mut chain = Vec::new();
+        let mut cluster = match first {
+            0 => None,
+            first => Some(self.check_cluster(first)?),
+        };
+        while let Some(current) = cluster {
+            if chain.len() as u64 == count {
+                break;
+            }
+            chain.push(current);
+            cluster = self.next_cluster(current)?;
+        }
+        Ok(chain)
+    }
+
+    /// Lê `buf.len()` bytes de `chain` a partir de `offset`. Clusters
+    /// consecutivos no disco viram uma única leitura.
+    fn read_chain(&mut self, chain: &[u32], offset: u64, buf: &mut [u8]) -> Result<()> {
+        let mut done = 0;
+        while done < buf.len() {
+            let pos = offset + done as u64;
+            let index = (pos / self.cluster_size) as usize;
+            let within = pos % self.cluster_size;
+            let first = *chain
+                .get(index)
+                .ok_or(fs_error(FileSystemError::Truncated))?;
+
+            let rest = (buf.len() - done) as u64;
+            let mut run = 1;
+            while run * self.cluster_size - within < rest
+                && chain.get(index + run as usize) == Some(&(first + run as u32))
+            {
+                run += 1;
+            }
+            let n = (run * self.cluster_size - within).min(rest) as usize;
+
+            let start = self.cluster_offset(first) + within;
+            self.read_at(start, &mut buf[done..done + n])?;
+            done += n;
+        }
+        Ok(())
+    }
+
+    /// Entradas válidas de um diretório (sem volume label nem removidas).
+    fn read_dir(&mut self, dir: &Node) -> Result<Vec<DirEntry>> {
+        let data = if dir.cluster == 0 {
+            let mut data = vec![0u8; self.root_size as usize];
+            self.read_at(self.root_start, &mut data)?;
+            data
+        } else {
+            let chain = self.chain(dir.cluster, (MAX_DIR_SIZE / self.cluster_size).max(1))?;
+            let mut data = vec![0u8; chain.len() * self.cluster_size as usize];
+            self.read_chain(&chain, 0, &mut data)?;
+            data
+        };
+
+        let mut entries = Vec::new();
+        let mut long_name = LongName::default();
+        for raw in data.as_chunks::<DIR_ENTRY_SIZE>().0 {
+            match raw[0] {
+                ENTRY_END => break,
+                ENTRY_DELETED => {
+                    long_name = LongName::default();
+                    continue;
+                },
+                _ => {},
+            }
+
+            let attr = raw[11];
+            if attr & 0x3F == ATTR_LONG_NAME {
+                long_name.push(raw);
+                continue;
+            }
+            if attr & ATTR_VOLUME_ID != 0 {
+                long_name = LongName::default();
+                continue;
+            }
+
+            let short = short_name(raw);
+            let mut cluster = le16(raw, 26) as u32;
+            if self.fat_type == FatType::Fat32 {
+                cluster |= (le16(raw, 20) as u32) << 16;
+            }
+            entries.push(DirEntry {
+                name: long_name.take(raw).unwrap_or_else(|| short.clone()),
+                short,
+                node: Node {
+                    cluster,
+                    size: le32(raw, 28) as u64,
+                    is_dir: attr & ATTR_DIRECTORY != 0,
+                },
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Resolve `path` a partir de `dir`, um componente por vez. Prefixos como
+    /// `boot():` são descartados, como no ESP.
+    fn lookup(&mut self, dir: &Node, path: &str) -> Result<Node> {
+        let mut current = dir.clone();
+
+        for component in normalize_path(path)
+            .split('\\')
+            .filter(|c| !c.is_empty() && *c != ".")
+        {
+            if !current.is_dir {
+                return Err(fs_error(FileSystemError::FileNotFound));
+            }
+            current = self
+                .read_dir(&current)?
+                .into_iter()
+                .find(|entry| {
+                    entry.name.eq_ignore_ascii_case(component)
+                        || entry.short.eq_ignore_ascii_case(component)
+                })
+                .map(|entry| entry.node)
+                .ok_or(fs_error(FileSystemError::FileNotFound))?;
+
+            // `..` apontando para a raiz grava o cluster 0, mesmo em FAT32
+            if current.is_dir && current.cluster == 0 {
+                current = self.root();
+            }
+        }
+
+        Ok(current)
+    }
+}
+
+/// Volume FAT montado somente leitura.
 pub struct Fat32FileSystem<D: BlockDevice> {
-    #[allow(dead_code)]
-    device:              D,
-    // Metadados do FS em cache
-    #[allow(dead_code)]
-    fat_start_lba:       u64,
-    #[allow(dead_code)]
-    data_start_lba:      u64,
-    #[allow(dead_code)]
-    sectors_per_cluster: u64,
-    #[allow(dead_code)]
-    root_cluster:        u32,
+    volume: Rc<RefCell<Volume<D>>>,
 }
 
 impl<D: BlockDevice> Fat32FileSystem<D> {
-    /// Tenta montar um volume FAT32 a partir de um dispositivo de bloco.
+    /// Lê o setor de boot (BPB) e monta o volume.
     pub fn mount(mut device: D) -> Result<Self> {
-        let mut buf = vec![0u8; 512];
-        device
-            .read_blocks(0, &mut buf)
-            .map_err(|_| BootError::FileSystem(crate::core::error::FileSystemError::ReadError))?;
+        let mut bpb = vec![0u8; device.block_size().max(512) as usize];
+        device.read_blocks(0, &mut bpb)?;
 
         // Validação de assinatura
-        if buf[510] != 0x55 || buf[511] != 0xAA {
-            return Err(BootError::FileSystem(
-                crate::core::error::FileSystemError::InvalidSignature,
-            ));
+        if bpb[510] != 0x55 || bpb[511] != 0xAA {
+            return Err(fs_error(FileSystemError::InvalidSignature));
         }
 
-        // Parse manual simplificado ou via struct (unsafe cast)
-        let bpb = unsafe { &*(buf.as_ptr() as *const BiosParameterBlock) };
+        let bytes_per_sector = le16(&bpb, 11) as u64;
+        let sectors_per_cluster = bpb[13] as u64;
+        let reserved_sectors = le16(&bpb, 14) as u64;
+        let num_fats = bpb[16] as u64;
+        let root_entries = le16(&bpb, 17) as u64;
+        let fat_size = match le16(&bpb, 22) {
+            0 => le32(&bpb, 36) as u64,
+            size => size as u64,
+        };
+        let total_sectors = match le16(&bpb, 19) {
+            0 => le32(&bpb, 32) as u64,
+            total => total as u64,
+        };
 
-        // Verifica se é FAT32
-        if bpb.fat_size_16 != 0 {
-            return Err(BootError::FileSystem(
-                crate::core::error::FileSystemError::UnsupportedFsType,
-            ));
+        if !matches!(bytes_per_sector, 512 | 1024 | 2048 | 4096)
+            || !sectors_per_cluster.is_power_of_two()
+            || reserved_sectors == 0
+            || num_fats == 0
+            || fat_size == 0
+        {
+            return Err(fs_error(FileSystemError::InvalidSize));
         }
 
-        // TODO: Completar cálculos de LBA
+        let root_sectors = (root_entries * DIR_ENTRY_SIZE as u64).div_ceil(bytes_per_sector);
+        let data_sector = reserved_sectors + num_fats * fat_size + root_sectors;
+        if total_sectors <= data_sector {
+            return Err(fs_error(FileSystemError::InvalidSize));
+        }
 
-        Ok(Self {
+        let cluster_count = ((total_sectors - data_sector) / sectors_per_cluster) as u32;
+        let fat_type = FatType::from_cluster_count(cluster_count);
+        let root_cluster = le32(&bpb, 44);
+        if fat_type == FatType::Fat32 && (root_entries != 0 || root_cluster < 2) {
+            return Err(fs_error(FileSystemError::InvalidSize));
+        }
+
+        let volume = Volume {
             device,
-            fat_start_lba: 0,  // Placeholder
-            data_start_lba: 0, // Placeholder
-            sectors_per_cluster: bpb.sectors_per_cluster as u64,
-            root_cluster: 2, // Geralmente 2, mas deve ser lido do ext_bpb
+            fat_type,
+            cluster_size: sectors_per_cluster * bytes_per_sector,
+            fat_start: reserved_sectors * bytes_per_sector,
+            root_start: (data_sector - root_sectors) * bytes_per_sector,
+            root_size: root_entries * DIR_ENTRY_SIZE as u64,
+            data_start: data_sector * bytes_per_sector,
+            cluster_count,
+            root_cluster,
+            fat_cache: Vec::new(),
+            fat_cache_at: None,
+        };
+        if fat_type == FatType::Fat32 {
+            volume.check_cluster(root_cluster)?;
+        }
+
+        Ok(Self {
+            volume: Rc::new(RefCell::new(volume)),
         })
     }
+
+    /// Variante detectada na montagem.
+    pub fn fat_type(&self) -> FatType {
+        self.volume.borrow().fat_type
+    }
 }
 
-// Implementação VFS (Stubs para compilação)
 impl<D: BlockDevice + 'static> FileSystem for Fat32FileSystem<D> {
     fn root(&mut self) -> Result<Box<dyn Directory>> {
-        Err(BootError::Generic("FAT32 nativo ainda não implementado"))
+        let node = self.volume.borrow().root();
+        Ok(Box::new(FatDirectory {
+            volume: self.volume.clone(),
+            node,
+        }))
     }
 
     fn name(&self) -> &str {
         "FAT32_NATIVE"
     }
 }
+
+/// Diretório aberto em um volume FAT.
+struct FatDirectory<D: BlockDevice> {
+    volume: Rc<RefCell<Volume<D>>>,
+    node:   Node,
+}
+
+impl<D: BlockDevice + 'static> Directory for FatDirectory<D> {
+    fn open_file(&mut self, path: &str) -> Result<Box<dyn File>> {
+        let mut volume = self.volume.borrow_mut();
+        let node = volume.lookup(&self.node, path)?;
+        if node.is_dir {
+            return Err(fs_error(FileSystemError::NotRegularFile));
+        }
+
+        // A cadeia precisa cobrir o tamanho declarado
+        let clusters = node.size.div_ceil(volume.cluster_size);
+        let chain = volume.chain(node.cluster, clusters)?;
+        if (chain.len() as u64) < clusters {
+            return Err(fs_error(FileSystemError::Truncated));
+        }
+
+        Ok(Box::new(FatFile {
+            volume: self.volume.clone(),
+            chain,
+            size: node.size,
+            pos: 0,
+        }))
+    }
+
+    fn open_dir(&mut self, path: &str) -> Result<Box<dyn Directory>> {
+        let node = self.volume.borrow_mut().lookup(&self.node, path)?;
+        if !node.is_dir {
+            return Err(fs_error(FileSystemError::InvalidPath));
+        }
+
+        Ok(Box::new(FatDirectory {
+            volume: self.volume.clone(),
+            node,
+        }))
+    }
+
+    fn list(&mut self) -> Result<Vec<String>> {
+        Ok(self
+            .volume
+            .borrow_mut()
+            .read_dir(&self.node)?
+            .into_iter()
+            .map(|entry| entry.name)
+            .filter(|name| name != "." && name != "..")
+            .collect())
+    }
+}
+
+/// Arquivo aberto em um volume FAT, com a cadeia de clusters já resolvida.
+struct FatFile<D: BlockDevice> {
+    volume: Rc<RefCell<Volume<D>>>,
+    chain:  Vec<u32>,
+    size:   u64,
+    pos:    u64,
+}
+
+impl<D: BlockDevice> File for FatFile<D> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.pos >= self.size {
+            return Ok(0);
+        }
+
+        let n = (buf.len() as u64).min(self.size - self.pos) as usize;
+        self.volume
+            .borrow_mut()
+            .read_chain(&self.chain, self.pos, &mut buf[..n])?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> Result<usize> {
+        Err(fs_error(FileSystemError::WriteError))
+    }
+
+    fn seek(&mut self, offset: u64) -> Result<u64> {
+        self.pos = offset;
+        Ok(offset)
+    }
+
+    fn metadata(&self) -> Result<Metadata> {
+        Ok(Metadata {
+            size:        self.size,
+            is_dir:      false,
+            is_readonly: true,
+        })
+    }
+
+    fn size(&mut self) -> Result<u64> {
+        Ok(self.size)
+    }
+}
@@ -0,0 +1,284 @@
+//! Tabela de Partições GPT
+//!
+//! Valida o MBR protetor (LBA 0) e o cabeçalho GPT primário (LBA 1) antes de
+//! confiar na tabela de um disco, e lê as entradas de partição com os LBAs
+//! reais. Tudo é medido em blocos do próprio dispositivo, então discos 4Kn
+//! (setores de 4096 bytes) funcionam como os de 512.
+//!
+//! Referência: UEFI Spec 2.10, Seção 5.
+
+use alloc::{vec, vec::Vec};
+
+use super::dev::BlockDevice;
+use crate::core::{
+    crc32::crc32,
+    error::{BootError, FileSystemError, Result},
+};
+
+/// Assinatura do cabeçalho GPT.
+pub const GPT_SIGNATURE: &[u8; 8] = b"EFI PART";
+
+/// GUID de tipo da partição de sistema EFI, no layout de disco (mixed-endian).
+/// C12A7328-F81F-11D2-BA4B-00A0C93EC93B
+pub const ESP_TYPE_GUID: [u8; 16] = [
+    0x28, 0x73, 0x2a, 0xc1, 0x1f, 0xf8, 0xd2, 0x11, 0xba, 0x4b, 0x00, 0xa0, 0xc9, 0x3e, 0xc9, 0x3b,
+];
+
+/// Tamanho mínimo do cabeçalho (revisão 1.0).
+pub const GPT_HEADER_MIN_SIZE: usize = 92;
+
+/// Tamanho mínimo de uma entrada da tabela de partições.
+const MIN_ENTRY_SIZE: usize = 128;
+
+/// Maior entrada aceita. A spec exige `128 * 2^n`; discos reais usam 128.
+pub const MAX_ENTRY_SIZE: usize = 1024;
+
+/// Limite de entradas lidas, contra cabeçalhos que pedem tabelas absurdas.
+pub const MAX_PARTITION_ENTRIES: usize = 1024;
+
+/// Maior array de entradas lido para a memória.
+pub const MAX_TABLE_SIZE: usize = 256 * 1024;
+
+/// Offset do campo `HeaderCRC32`, zerado durante o cálculo.
+const HEADER_CRC_OFFSET: usize = 16;
+
+/// Tipo de partição do MBR protetor.
+const PROTECTIVE_MBR_TYPE: u8 = 0xEE;
+
+/// Início da tabela de partições do MBR e tamanho de cada entrada.
+const MBR_TABLE_OFFSET: usize = 446;
+const MBR_ENTRY_SIZE: usize = 16;
+
+/// Cabeçalho GPT já validado.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GptHeader {
+    /// LBA deste cabeçalho (1 no primário).
+    pub my_lba:           u64,
+    /// LBA do cabeçalho de backup.
+    pub alternate_lba:    u64,
+    pub first_usable_lba: u64,
+    pub last_usable_lba:  u64,
+    pub disk_guid:        [u8; 16],
+    /// LBA inicial do array de entradas de partição.
+    pub entries_lba:      u64,
+    pub num_entries:      u32,
+    pub entry_size:       u32,
+    /// CRC32 do array de entradas (`num_entries * entry_size` bytes).
+    pub entries_crc32:    u32,
+}
+
+impl GptHeader {
+    /// Tamanho em bytes do array de entradas.
+    ///
+    /// Recusa `entry_size` fora de `128 * 2^n` (até `MAX_ENTRY_SIZE`) e
+    /// tabelas acima de `MAX_PARTITION_ENTRIES` entradas ou `MAX_TABLE_SIZE`
+    /// bytes.
+    pub fn table_len(&self) -> Result<usize> {
+        let entry_size = self.entry_size as usize;
+        let num_entries = self.num_entries as usize;
+        if !(MIN_ENTRY_SIZE..=MAX_ENTRY_SIZE).contains(&entry_size)
+            || !entry_size.is_power_of_two()
+            || num_entries > MAX_PARTITION_ENTRIES
+        {
+            return Err(BootError::FileSystem(FileSystemError::InvalidSize));
+        }
+        match num_entries * entry_size {
+            len if len <= MAX_TABLE_SIZE => Ok(len),
+            _ => Err(BootError::FileSystem(FileSystemError::InvalidSize)),
+        }
+    }
+}
+
+/// Entrada de partição em uso.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GptPartition {
+    pub type_guid:   [u8; 16],
+    pub unique_guid: [u8; 16],
+    pub first_lba:   u64,
+    /// Último LBA (inclusivo).
+    pub last_lba:    u64,
+}
+
+impl GptPartition {
+    /// Quantidade de blocos da partição.
+    pub fn num_blocks(&self) -> u64 {
+        self.last_lba - self.first_lba + 1
+    }
+}
+
+fn read_u32(buf: &[u8], off: usize) -> u32 {
+    u32::from_le_bytes([buf[off], buf[off + 1], buf[off + 2], buf[off + 3]])
+}
+
+fn read_u64(buf: &[u8], off: usize) -> u64 {
+    (read_u32(buf, off) as u64) | ((read_u32(buf, off + 4) as u64) << 32)
+}
+
+fn guid_at(buf: &[u8], off: usize) -> [u8; 16] {
+    let mut guid = [0u8; 16];
+    guid.copy_from_slice(&buf[off..off + 16]);
+    guid
+}
+
+/// Verifica se `sector` (LBA 0) é um MBR protetor: assinatura `55 AA` e uma
+/// entrada do tipo `0xEE` cobrindo o disco.
+pub fn check_protective_mbr(sector: &[u8]) -> Result<()> {
+    if sector.len() < 512 || sector[510] != 0x55 || sector[511] != 0xAA {
+        return Err(BootError::FileSystem(FileSystemError::InvalidSignature));
+    }
+
+    let protective = (0..4).any(|i| {
+        let entry = MBR_TABLE_OFFSET + i * MBR_ENTRY_SIZE;
+        sector[entry + 4] == PROTECTIVE_MBR_TYPE
+    });
+    if !protective {
+        return Err(BootError::FileSystem(FileSystemError::UnsupportedFsType));
+    }
+    Ok(())
+}
+
+/// Interpreta e valida o cabeçalho GPT no início de `block`.
+///
+/// Confere a assinatura, o tamanho declarado e o `HeaderCRC32`, calculado
+/// com o próprio campo zerado.
+pub fn parse_header(block: &[u8]) -> Result<GptHeader> {
+    if block.len() < GPT_HEADER_MIN_SIZE || &block[..8] != GPT_SIGNATURE {
+        return Err(BootError::FileSystem(FileSystemError::InvalidSignature));
+    }
+
+    let header_size = read_u32(block, 12) as usize;
+    if header_size < GPT_HEADER_MIN_SIZE || header_size > block.len() {
+        return Err(BootError::FileSystem(FileSystemError::InvalidSize));
+    }
+
+    let mut copy = block[..header_size].to_vec();
+    copy[HEADER_CRC_OFFSET..HEADER_CRC_OFFSET + 4].fill(0);
+    if crc32(&copy) != read_u32(block, HEADER_CRC_OFFSET) {
+        return Err(BootError::FileSystem(FileSystemError::ChecksumMismatch));
+    }
+
+    let header = GptHeader {
+        my_lba:           read_u64(block, 24),
+        alternate_lba:    read_u64(block, 32),
+        first_usable_lba: read_u64(block, 40),
+        last_usable_lba:  read_u64(block, 48),
+        disk_guid:        guid_at(block, 56),
+        entries_lba:      read_u64(block, 72),
+        num_entries:      read_u32(block, 80),
+        entry_size:       read_u32(block, 84),
+        entries_crc32:    read_u32(block, 88),
+    };
+
+    header.table_len()?;
+    Ok(header)
+}
+
+/// Lê e valida o MBR protetor e o cabeçalho GPT primário de `disk`.
+pub fn primary_header<D: BlockDevice + ?Sized>(disk: &mut D) -> Result<GptHeader> {
+    let block_size = disk.block_size() as usize;
+    if block_size < 512 {
+        return Err(BootError::FileSystem(FileSystemError::InvalidSize));
+    }
+    let mut block = vec![0u8; block_size];
+
+    disk.read_blocks(0, &mut block)?;
+    check_protective_mbr(&block)?;
+
+    disk.read_blocks(1, &mut block)?;
+    let header = parse_header(&block)?;
+    if header.my_lba != 1 {
+        return Err(BootError::FileSystem(FileSystemError::InvalidSignature));
+    }
+    Ok(header)
+}
+
+/// Lê as entradas em uso da tabela descrita por `header`, conferindo o CRC32
+/// do array.
+pub fn partitions<D: BlockDevice + ?Sized>(
+    disk: &mut D,
+    header: &GptHeader,
+) -> Result<Vec<GptPartition>> {
+    let block_size = disk.block_size() as usize;
+    let table_len = header.table_len()?;
+
+    let mut table = vec![0u8; table_len.div_ceil(block_size) * block_size];
+    disk.read_blocks(header.entries_lba, &mut table)?;
+    parse_entries(&table[..table_len], header)
+}
+
+/// Interpreta o array de entradas `table` (`header.table_len()` bytes),
+/// conferindo o CRC32, e retorna as entradas em uso.
+pub fn parse_entries(table: &[u8], header: &GptHeader) -> Result<Vec<GptPartition>> {
+    if table.len() != header.table_len()? {
+        return Err(BootError::FileSystem(FileSystemError::InvalidSize));
+    }
+    if crc32(table) != header.entries_crc32 {
+        return Err(BootError::FileSystem(FileSystemError::ChecksumMismatch));
+    }
+
+    Ok(table
+        .chunks_exact(header.entry_size as usize)
+        .filter(|entry| entry[..16].iter().any(|&b| b != 0))
+        .map(|entry| GptPartition {
+            type_guid:   guid_at(entry, 0),
+            unique_guid: guid_at(entry, 16),
+            first_lba:   read_u64(entry, 32),
+            last_lba:    read_u64(entry, 40),
+        })
+        .filter(|part| part.first_lba <= part.last_lba)
+        .collect())
+}
+
+/// Primeira partição de `disk` com o tipo `type_guid`, após validar a GPT.
+pub fn find_partition<D: BlockDevice + ?Sized>(
+    disk: &mut D,
+    type_guid: &[u8; 16],
+) -> Result<GptPartition> {
+    let header = primary_header(disk)?;
+    partitions(disk, &header)?
+        .into_iter()
+        .find(|part| &part.type_guid == type_guid)
+        .ok_or(BootError::FileSystem(FileSystemError::FileNotFound))
+}
+
+/// Janela de `disk` restrita a uma partição: o LBA 0 do dispositivo é o
+/// `first_lba` da partição.
+///
+/// Usada para montar drivers nativos (RedstoneFS, FAT32) no início real da
+/// partição em vez de supor um offset fixo.
+pub struct PartitionDevice<D: BlockDevice> {
+    disk:      D,
+    partition: GptPartition,
+}
+
+impl<D: BlockDevice> PartitionDevice<D> {
+    pub fn new(disk: D, partition: GptPartition) -> Self {
+        Self { disk, partition }
+    }
+}
+
+impl<D: BlockDevice> BlockDevice for PartitionDevice<D> {
+    fn read_blocks(&mut self, lba: u64, buf: &mut [u8]) -> Result<()> {
+        let blocks = (buf.len() as u64).div_ceil(self.block_size());
+        if lba.saturating_add(blocks) > self.num_blocks() {
+            return Err(BootError::FileSystem(FileSystemError::ReadError));
+        }
+        self.disk.read_blocks(self.partition.first_lba + lba, buf)
+    }
+
+    fn write_blocks(&mut self, lba: u64, buf: &[u8]) -> Result<()> {
+        let blocks = (buf.len() as u64).div_ceil(self.block_size());
+        if lba.saturating_add(blocks) > self.num_blocks() {
+            return Err(BootError::FileSystem(FileSystemError::WriteError));
+        }
+        self.disk.write_blocks(self.partition.first_lba + lba, buf)
+    }
+
+    fn block_size(&self) -> u64 {
+        self.disk.block_size()
+    }
+
+    fn num_blocks(&self) -> u64 {
+        self.partition.num_blocks()
+    }
+}
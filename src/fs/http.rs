@@ -0,0 +1,700 @@
+//! Sistema de Arquivos HTTP (Boot por Rede)
+//!
+//! Lê kernel e módulos de um servidor HTTP: a entrada define `http_base` e
+//! cada `open_file` faz um `GET` de `http_base` + caminho. O corpo chega
+//! delimitado por `Content-Length` (lido sob demanda, direto no buffer do
+//! chamador) ou em `Transfer-Encoding: chunked` (decodificado e guardado na
+//! abertura, já que o tamanho só é conhecido no fim).
+//!
+//! O transporte fica atrás de `HttpTransport`, para que a decodificação possa
+//! ser testada no host. `FirmwareHttp` usa os protocolos HTTP do firmware e
+//! falha em `open` quando eles não existem; o chamador segue então com o
+//! disco local.
+
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use super::vfs::{Directory, File, FileSystem, Metadata};
+use crate::{
+    core::{
+        config::limits::MAX_MODULE_SIZE,
+        error::{BootError, FileSystemError, IoError, Result},
+    },
+    uefi::{
+        base::{Event, Handle, Status, FALSE, TRUE},
+        proto::network::http::{
+            status_code, HttpConfigData, HttpHeader, HttpMessage, HttpProtocol, HttpRequestData,
+            HttpResponseData, HttpToken, HttpV4AccessPoint, ServiceBindingProtocol,
+            DNS4_SERVICE_BINDING_PROTOCOL_GUID, HTTP_METHOD_GET, HTTP_PROTOCOL_GUID,
+            HTTP_SERVICE_BINDING_PROTOCOL_GUID, HTTP_VERSION_11,
+        },
+        system_table,
+        table::boot::OPEN_PROTOCOL_GET_PROTOCOL,
+    },
+};
+
+/// Limite de um corpo guardado em memória (chunked ou sem tamanho).
+pub const MAX_BUFFERED_BODY: usize = MAX_MODULE_SIZE;
+
+/// Tempo máximo de espera por cada operação do firmware (30s).
+pub const HTTP_TIMEOUT_MS: usize = 30_000;
+
+/// Resposta a um `GET`, com o corpo ainda não lido.
+pub trait HttpResponse {
+    /// Código de status HTTP (200, 404, ...).
+    fn status(&self) -> u16;
+
+    /// Valor do cabeçalho `name` (sem diferenciar maiúsculas).
+    fn header(&self, name: &str) -> Option<&str>;
+
+    /// Lê os próximos bytes do corpo como vieram da rede, ainda com o
+    /// enquadramento chunked. Retorna 0 quando a conexão termina.
+    fn read_raw(&mut self, buf: &mut [u8]) -> Result<usize>;
+}
+
+/// Origem das respostas HTTP.
+pub trait HttpTransport {
+    /// Envia `GET url` e devolve a resposta.
+    fn get(&mut self, url: &str) -> Result<Box<dyn HttpResponse>>;
+}
+
+/// Como o fim do corpo é determinado.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyFraming {
+    /// `Content-Length`.
+    Length(u64),
+    /// `Transfer-Encoding: chunked`.
+    Chunked,
+    /// Sem nenhum dos dois: o corpo vai até o fim da conexão.
+    UntilClose,
+}
+
+impl BodyFraming {
+    /// Enquadramento declarado nos cabeçalhos de `response`. `chunked`
+    /// prevalece sobre `Content-Length`, como manda a RFC 9112.
+    pub fn of(response: &dyn HttpResponse) -> Result<Self> {
+        let chunked = response.header("transfer-encoding").is_some_and(|codings| {
+            codings
+                .rsplit(',')
+                .next()
+                .is_some_and(|last| last.trim().eq_ignore_ascii_case("chunked"))
+        });
+        if chunked {
+            return Ok(BodyFraming::Chunked);
+        }
+
+        match response.header("content-length") {
+            Some(len) => len
+                .trim()
+                .parse()
+                .map(BodyFraming::Length)
+                .map_err(|_| BootError::FileSystem(FileSystemError::InvalidSize)),
+            None => Ok(BodyFraming::UntilClose),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ChunkState {
+    /// Dígitos hexadecimais do tamanho.
+    #[default]
+    Size,
+    /// Extensões após `;`, ignoradas até o CR.
+    Extension,
+    SizeLf,
+    Data,
+    DataCr,
+    DataLf,
+    /// Início de uma linha de trailer (ou do CRLF final).
+    Trailer,
+    TrailerLine,
+    FinalLf,
+    Done,
+}
+
+/// Decodificador incremental de `Transfer-Encoding: chunked`.
+#[derive(Debug, Default)]
+pub struct ChunkedDecoder {
+    state:  ChunkState,
+    size:   u64,
+    digits: usize,
+}
+
+impl ChunkedDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Se o chunk final e os trailers já foram lidos.
+    pub fn is_done(&self) -> bool {
+        self.state == ChunkState::Done
+    }
+
+    /// Decodifica `buf` no próprio buffer: os dados dos chunks são movidos
+    /// para o início e a quantidade é retornada. Bytes após o fim do corpo
+    /// são descartados.
+    pub fn decode_in_place(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let malformed = BootError::FileSystem(FileSystemError::ReadError);
+        let mut out = 0;
+        let mut i = 0;
+
+        while i < buf.len() {
+            let byte = buf[i];
+            match self.state {
+                ChunkState::Size => match byte {
+                    b'0'..=b'9' | b'a'..=b'f' | b'A'..=b'F' => {
+                        let digit = (byte as char).to_digit(16).unwrap_or(0) as u64;
+                        self.size = self
+                            .size
+                            .checked_mul(16)
+                            .and_then(|size| size.checked_add(digit))
+                            .ok_or(malformed)?;
+                        self.digits += 1;
+                    },
+                    b';' | b' ' | b'\t' if self.digits > 0 => self.state = ChunkState::Extension,
+                    b'\r' if self.digits > 0 => self.state = ChunkState::SizeLf,
+                    _ => return Err(malformed),
+                },
+                ChunkState::Extension => {
+                    if byte == b'\r' {
+                        self.state = ChunkState::SizeLf;
+                    }
+                },
+                ChunkState::SizeLf => {
+                    if byte != b'\n' {
+                        return Err(malformed);
+                    }
+                    self.state = if self.size == 0 {
+                        ChunkState::Trailer
+                    } else {
+                        ChunkState::Data
+                    };
+                },
+                ChunkState::Data => {
+                    let n = (self.size as usize).min(buf.len() - i);
+                    buf.copy_within(i..i + n, out);
+                    out += n;
+                    self.size -= n as u64;
+                    if self.size == 0 {
+                        self.state = ChunkState::DataCr;
+                    }
+                    i += n;
+                    continue;
+                },
+                ChunkState::DataCr if byte == b'\r' => self.state = ChunkState::DataLf,
+                ChunkState::DataLf if byte == b'\n' => {
+                    self.state = ChunkState::Size;
+                    self.digits = 0;
+                },
+                ChunkState::DataCr | ChunkState::DataLf => return Err(malformed),
+                ChunkState::Trailer => {
+                    self.state = if byte == b'\r' {
+                        ChunkState::FinalLf
+                    } else {
+                        ChunkState::TrailerLine
+                    };
+                },
+                ChunkState::TrailerLine => {
+                    if byte == b'\n' {
+                        self.state = ChunkState::Trailer;
+                    }
+                },
+                ChunkState::FinalLf if byte == b'\n' => self.state = ChunkState::Done,
+                ChunkState::FinalLf => return Err(malformed),
+                ChunkState::Done => break,
+            }
+            i += 1;
+        }
+
+        Ok(out)
+    }
+}
+
+/// Lê o corpo inteiro de `response` para a memória.
+fn read_whole_body(response: &mut dyn HttpResponse, framing: BodyFraming) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    let mut chunk = vec![0u8; 4096];
+    let mut decoder = ChunkedDecoder::new();
+
+    while !(framing == BodyFraming::Chunked && decoder.is_done()) {
+        let n = response.read_raw(&mut chunk)?;
+        if n == 0 {
+            if framing == BodyFraming::Chunked {
+                return Err(BootError::FileSystem(FileSystemError::Truncated));
+            }
+            break;
+        }
+
+        let data = match framing {
+            BodyFraming::Chunked => decoder.decode_in_place(&mut chunk[..n])?,
+            _ => n,
+        };
+        if body.len() + data > MAX_BUFFERED_BODY {
+            return Err(BootError::FileSystem(FileSystemError::FileTooLarge));
+        }
+        body.extend_from_slice(&chunk[..data]);
+    }
+
+    Ok(body)
+}
+
+enum Body {
+    /// `Content-Length`: lido sob demanda.
+    Stream {
+        response:  Box<dyn HttpResponse>,
+        remaining: u64,
+    },
+    /// Corpo já decodificado.
+    Buffered(Vec<u8>),
+}
+
+/// Arquivo obtido por `GET`. Só leitura; com `Content-Length` só aceita
+/// leitura sequencial.
+pub struct HttpFile {
+    body: Body,
+    size: u64,
+    pos:  u64,
+}
+
+impl HttpFile {
+    /// Confere o status de `response` e prepara a leitura do corpo. `path` só
+    /// aparece nos erros.
+    pub fn from_response(path: &str, mut response: Box<dyn HttpResponse>) -> Result<Self> {
+        match response.status() {
+            200..=299 => {},
+            404 | 410 => return Err(BootError::file(path, FileSystemError::FileNotFound)),
+            status => {
+                log::warn!("HTTP: GET '{}' retornou {}", path, status);
+                return Err(BootError::file(path, FileSystemError::ReadError));
+            },
+        }
+
+        let body = match BodyFraming::of(response.as_ref())? {
+            BodyFraming::Length(remaining) => Body::Stream {
+                response,
+                remaining,
+            },
+            framing => Body::Buffered(read_whole_body(response.as_mut(), framing)?),
+        };
+        let size = match &body {
+            Body::Stream { remaining, .. } => *remaining,
+            Body::Buffered(data) => data.len() as u64,
+        };
+
+        Ok(Self { body, size, pos: 0 })
+    }
+}
+
+impl File for HttpFile {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = match &mut self.body {
+            Body::Stream {
+                response,
+                remaining,
+            } => {
+                let want = (*remaining).min(buf.len() as u64) as usize;
+                if want == 0 {
+                    return Ok(0);
+                }
+                let n = response.read_raw(&mut buf[..want])?;
+                *remaining -= n as u64;
+                n
+            },
+            Body::Buffered(data) => {
+                let start = (self.pos as usize).min(data.len());
+                let n = (data.len() - start).min(buf.len());
+                buf[..n].copy_from_slice(&data[start..start + n]);
+                n
+            },
+        };
+
+        self.pos += n as u64;
+        Ok(n)
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> Result<usize> {
+        Err(BootError::FileSystem(FileSystemError::WriteError))
+    }
+
+    fn seek(&mut self, offset: u64) -> Result<u64> {
+        match self.body {
+            Body::Stream { .. } if offset != self.pos => {
+                Err(BootError::FileSystem(FileSystemError::SeekError))
+            },
+            _ => {
+                self.pos = offset;
+                Ok(offset)
+            },
+        }
+    }
+
+    fn metadata(&self) -> Result<Metadata> {
+        Ok(Metadata {
+            size:        self.size,
+            is_dir:      false,
+            is_readonly: true,
+        })
+    }
+
+    fn size(&mut self) -> Result<u64> {
+        Ok(self.size)
+    }
+}
+
+/// Junta `base` e `path` numa URL. Prefixos de dispositivo (`boot():`) são
+/// removidos e `\` vira `/`.
+pub fn join_url(base: &str, path: &str) -> String {
+    let path = ["boot():", "boot()", "boot:", "vol():"]
+        .iter()
+        .find_map(|prefix| path.strip_prefix(prefix))
+        .unwrap_or(path)
+        .replace('\\', "/");
+
+    let mut url = String::from(base.trim_end_matches('/'));
+    url.push('/');
+    url.push_str(path.trim_start_matches('/'));
+    url
+}
+
+/// Diretório virtual: um prefixo de URL.
+pub struct HttpDirectory<T: HttpTransport + Clone> {
+    transport: T,
+    base:      String,
+}
+
+impl<T: HttpTransport + Clone + 'static> Directory for HttpDirectory<T> {
+    fn open_file(&mut self, path: &str) -> Result<Box<dyn File>> {
+        let url = join_url(&self.base, path);
+        let response = self.transport.get(&url)?;
+        Ok(Box::new(HttpFile::from_response(path, response)?))
+    }
+
+    fn open_dir(&mut self, path: &str) -> Result<Box<dyn Directory>> {
+        Ok(Box::new(HttpDirectory {
+            transport: self.transport.clone(),
+            base:      join_url(&self.base, path),
+        }))
+    }
+
+    fn list(&mut self) -> Result<Vec<String>> {
+        // HTTP não tem listagem de diretório
+        Err(BootError::FileSystem(FileSystemError::UnsupportedFsType))
+    }
+}
+
+/// Servidor HTTP montado em `base` (`http://servidor/caminho`).
+pub struct HttpFileSystem<T: HttpTransport + Clone> {
+    transport: T,
+    base:      String,
+}
+
+impl<T: HttpTransport + Clone> HttpFileSystem<T> {
+    pub fn new(transport: T, base: &str) -> Result<Self> {
+        if !base.starts_with("http://") && !base.starts_with("https://") {
+            return Err(BootError::file(base, FileSystemError::InvalidPath));
+        }
+        Ok(Self {
+            transport,
+            base: base.to_string(),
+        })
+    }
+}
+
+impl<T: HttpTransport + Clone + 'static> FileSystem for HttpFileSystem<T> {
+    fn root(&mut self) -> Result<Box<dyn Directory>> {
+        Ok(Box::new(HttpDirectory {
+            transport: self.transport.clone(),
+            base:      self.base.clone(),
+        }))
+    }
+
+    fn name(&self) -> &str {
+        "HTTP"
+    }
+}
+
+/// Host (com porta, se houver) de `url`, como vai no cabeçalho `Host`.
+pub fn url_authority(url: &str) -> Option<&str> {
+    let rest = url.split_once("://")?.1;
+    let authority = rest.split('/').next()?;
+    (!authority.is_empty()).then_some(authority)
+}
+
+/// Se o host de `authority` é um IPv4 literal (dispensa DNS).
+fn is_ipv4_literal(authority: &str) -> bool {
+    let host = authority.split(':').next().unwrap_or(authority);
+    host.split('.').count() == 4 && host.split('.').all(|part| part.parse::<u8>().is_ok())
+}
+
+/// `HttpTransport` sobre o `EFI_HTTP_PROTOCOL` do firmware.
+///
+/// As cópias (uma por diretório e resposta abertos) dividem a mesma instância,
+/// que só é destruída por `close`.
+#[derive(Clone, Copy)]
+pub struct FirmwareHttp {
+    http:    *mut HttpProtocol,
+    binding: *mut ServiceBindingProtocol,
+    child:   Handle,
+}
+
+impl FirmwareHttp {
+    /// Cria e configura uma instância HTTP (IPv4, endereço do DHCP) pelo
+    /// Service Binding do firmware.
+    ///
+    /// Falha se o firmware não tiver a pilha HTTP, ou se `base` usar um nome
+    /// de host e não houver DNS4.
+    pub fn open(image_handle: Handle, base: &str) -> Result<Self> {
+        let bs = system_table().boot_services();
+
+        let binding =
+            bs.locate_protocol(&HTTP_SERVICE_BINDING_PROTOCOL_GUID)? as *mut ServiceBindingProtocol;
+        if !url_authority(base).is_some_and(is_ipv4_literal)
+            && bs
+                .locate_protocol(&DNS4_SERVICE_BINDING_PROTOCOL_GUID)
+                .is_err()
+        {
+            log::warn!("HTTP: sem DNS4 no firmware para resolver '{}'", base);
+            return Err(BootError::Uefi(Status::UNSUPPORTED));
+        }
+
+        // SAFETY: ponteiro devolvido pelo LocateProtocol do firmware.
+        let child = unsafe { (*binding).create_child()? };
+        let http = bs
+            .open_protocol(
+                child,
+                &HTTP_PROTOCOL_GUID,
+                image_handle,
+                Handle::null(),
+                OPEN_PROTOCOL_GET_PROTOCOL,
+            )
+            .map(|http| http as *mut HttpProtocol)
+            .and_then(|http| {
+                let mut access_point = HttpV4AccessPoint {
+                    use_default_address: TRUE,
+                    local_address:       [0; 4],
+                    local_subnet:        [0; 4],
+                    local_port:          0,
+                };
+                let config = HttpConfigData {
+                    http_version:          HTTP_VERSION_11,
+                    timeout_millisec:      HTTP_TIMEOUT_MS as u32,
+                    local_address_is_ipv6: FALSE,
+                    access_point:          &mut access_point,
+                };
+                // SAFETY: instância recém-aberta; `config` vive durante a
+                // chamada.
+                unsafe { ((*http).configure)(http, &config).to_result()? };
+                Ok(http)
+            });
+
+        match http {
+            Ok(http) => Ok(Self {
+                http,
+                binding,
+                child,
+            }),
+            Err(e) => {
+                // SAFETY: filho criado acima, sem outra referência.
+                let _ = unsafe { (*binding).destroy_child(child) };
+                Err(e.into())
+            },
+        }
+    }
+
+    /// Destrói a instância HTTP criada por `open`.
+    ///
+    /// # Safety
+    /// Nenhuma cópia desta instância (diretórios, arquivos e respostas
+    /// abertos sobre ela) pode ser usada depois.
+    pub unsafe fn close(self) {
+        if let Err(e) = (*self.binding).destroy_child(self.child) {
+            log::warn!("HTTP: falha ao destruir a instancia: {:?}", e);
+        }
+    }
+
+    /// Submete `token` com `op` e espera o evento, chamando `Poll`.
+    fn run(
+        &self,
+        op: extern "efiapi" fn(*mut HttpProtocol, *mut HttpToken) -> Status,
+        token: &mut HttpToken,
+    ) -> Result<()> {
+        let bs = system_table().boot_services();
+        token.status = Status::SUCCESS;
+        op(self.http, token).to_result()?;
+
+        for _ in 0..HTTP_TIMEOUT_MS {
+            // SAFETY: `http` foi aberto e configurado em `open`.
+            unsafe {
+                let _ = ((*self.http).poll)(self.http);
+                if (bs.check_event_f)(token.event).is_success() {
+                    return Ok(token.status.to_result()?);
+                }
+            }
+            bs.stall(1_000);
+        }
+
+        // SAFETY: cancela o token ainda pendente antes de descartá-lo.
+        unsafe {
+            let _ = ((*self.http).cancel)(self.http, token);
+        }
+        Err(BootError::Io(IoError::Timeout))
+    }
+}
+
+impl HttpTransport for FirmwareHttp {
+    fn get(&mut self, url: &str) -> Result<Box<dyn HttpResponse>> {
+        let bs = system_table().boot_services();
+        let mut event = Event(core::ptr::null_mut());
+        // SAFETY: evento sem notificação, consultado com CheckEvent.
+        unsafe { (bs.create_event_f)(0, 0, 0, core::ptr::null_mut(), &mut event).to_result()? };
+
+        let response = FirmwareResponse {
+            http: *self,
+            event,
+            status: 0,
+            headers: Vec::new(),
+        };
+        response.send(url)
+    }
+}
+
+/// Resposta em andamento numa instância `FirmwareHttp`.
+struct FirmwareResponse {
+    http:    FirmwareHttp,
+    event:   Event,
+    status:  u16,
+    /// Nomes em minúsculas.
+    headers: Vec<(String, String)>,
+}
+
+impl FirmwareResponse {
+    /// Envia o `GET` e lê status e cabeçalhos.
+    fn send(mut self, url: &str) -> Result<Box<dyn HttpResponse>> {
+        let mut url16: Vec<u16> = url.encode_utf16().collect();
+        url16.push(0);
+        let mut host = Vec::from(url_authority(url).unwrap_or("").as_bytes());
+        host.push(0);
+
+        let mut request = HttpRequestData {
+            method: HTTP_METHOD_GET,
+            url:    url16.as_ptr(),
+        };
+        let mut request_headers = [HttpHeader {
+            field_name:  c"Host".as_ptr() as *mut u8,
+            field_value: host.as_mut_ptr(),
+        }];
+        let mut message = HttpMessage {
+            data:         &mut request as *mut HttpRequestData as *mut _,
+            header_count: request_headers.len(),
+            headers:      request_headers.as_mut_ptr(),
+            body_length:  0,
+            body:         core::ptr::null_mut(),
+        };
+        let mut token = HttpToken {
+            event:   self.event,
+            status:  Status::SUCCESS,
+            message: &mut message,
+        };
+        // SAFETY: `request` foi configurado em `FirmwareHttp::open`.
+        self.http
+            .run(unsafe { (*self.http.http).request }, &mut token)?;
+
+        // Primeiro Response sem corpo: só status e cabeçalhos
+        let mut data = HttpResponseData { status_code: 0 };
+        let mut message = HttpMessage {
+            data:         &mut data as *mut HttpResponseData as *mut _,
+            header_count: 0,
+            headers:      core::ptr::null_mut(),
+            body_length:  0,
+            body:         core::ptr::null_mut(),
+        };
+        token.message = &mut message;
+        self.http
+            .run(unsafe { (*self.http.http).response }, &mut token)?;
+
+        self.status = status_code(data.status_code);
+        if !message.headers.is_null() {
+            let bs = system_table().boot_services();
+            // SAFETY: array e strings alocados pelo firmware para o chamador,
+            // que os libera com FreePool depois de copiá-los.
+            unsafe {
+                for header in core::slice::from_raw_parts(message.headers, message.header_count) {
+                    let name = c_str(header.field_name).to_ascii_lowercase();
+                    self.headers
+                        .push((name, String::from(c_str(header.field_value))));
+                    let _ = bs.free_pool(header.field_name);
+                    let _ = bs.free_pool(header.field_value);
+                }
+                let _ = bs.free_pool(message.headers as *mut u8);
+            }
+        }
+
+        Ok(Box::new(self))
+    }
+}
+
+/// String ASCII terminada em NUL; vazia se nula ou inválida.
+///
+/// # Safety
+/// `ptr` deve ser nulo ou apontar para uma string terminada em NUL.
+unsafe fn c_str<'a>(ptr: *const u8) -> &'a str {
+    if ptr.is_null() {
+        return "";
+    }
+    let len = (0..).take_while(|&i| *ptr.add(i) != 0).count();
+    core::str::from_utf8(core::slice::from_raw_parts(ptr, len)).unwrap_or("")
+}
+
+impl HttpResponse for FirmwareResponse {
+    fn status(&self) -> u16 {
+        self.status
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn read_raw(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let mut message = HttpMessage {
+            data:         core::ptr::null_mut(),
+            header_count: 0,
+            headers:      core::ptr::null_mut(),
+            body_length:  buf.len(),
+            body:         buf.as_mut_ptr() as *mut _,
+        };
+        let mut token = HttpToken {
+            event:   self.event,
+            status:  Status::SUCCESS,
+            message: &mut message,
+        };
+
+        // SAFETY: instância aberta em `FirmwareHttp::open`; `buf` vive
+        // durante a operação.
+        match self
+            .http
+            .run(unsafe { (*self.http.http).response }, &mut token)
+        {
+            Ok(()) => Ok(message.body_length),
+            // O servidor fechou a conexão: fim do corpo
+            Err(BootError::Uefi(status)) if status == Status::END_OF_FILE => Ok(0),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Drop for FirmwareResponse {
+    fn drop(&mut self) {
+        let bs = system_table().boot_services();
+        // SAFETY: evento criado em `FirmwareHttp::get`.
+        unsafe {
+            let _ = (bs.close_event_f)(self.event);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Loopback de Imagens ISO Híbridas
+//!
+//! Imagens "live" (ex: `redstone-live.iso`) são ISO9660 híbridas: além do
+//! El Torito, carregam uma tabela GPT cujo ESP (FAT) contém kernel e initrd.
+//! Este módulo localiza esse ESP dentro do arquivo e o expõe como um
+//! dispositivo de bloco, para ser montado pelo driver FAT32 nativo.
+
+use alloc::{boxed::Box, vec};
+
+use crate::{
+    core::error::{BootError, FileSystemError, Result},
+    fs::{
+        dev::BlockDevice,
+        fat32::Fat32FileSystem,
+        gpt::{self, ESP_TYPE_GUID},
+        read_exact,
+        vfs::{Directory, File, FileSystem},
+    },
+};
+
+/// Tamanho de setor assumido pela GPT da imagem.
+pub const ISO_SECTOR_SIZE: u64 = 512;
+
+/// Bytes lidos do início da imagem para localizar a GPT e suas entradas.
+pub const ISO_HEADER_SIZE: usize = 32 * 1024;
+
+/// Localiza o ESP dentro de uma imagem híbrida.
+///
+/// `header` deve conter o início da imagem (ao menos o cabeçalho GPT e a
+/// tabela de partições). O cabeçalho e o array de entradas passam pelas
+/// mesmas validações (tamanhos e CRC32) de um disco GPT. Retorna o offset, em
+/// bytes, do início do ESP.
+pub fn find_esp_offset(header: &[u8]) -> Result<u64> {
+    let gpt_header = header
+        .get(ISO_SECTOR_SIZE as usize..)
+        .ok_or(BootError::FileSystem(FileSystemError::InvalidSignature))
+        .and_then(gpt::parse_header)?;
+
+    let table_len = gpt_header.table_len()?;
+    let table = gpt_header
+        .entries_lba
+        .checked_mul(ISO_SECTOR_SIZE)
+        .and_then(|start| usize::try_from(start).ok())
+        .and_then(|start| header.get(start..start.checked_add(table_len)?))
+        .ok_or(BootError::FileSystem(FileSystemError::InvalidSize))?;
+
+    gpt::parse_entries(table, &gpt_header)?
+        .into_iter()
+        .find(|part| part.type_guid == ESP_TYPE_GUID)
+        .ok_or(BootError::FileSystem(FileSystemError::UnsupportedFsType))?
+        .first_lba
+        .checked_mul(ISO_SECTOR_SIZE)
+        .ok_or(BootError::FileSystem(FileSystemError::InvalidSize))
+}
+
+/// Dispositivo de bloco sobre uma janela de um arquivo aberto.
+pub struct FileBlockDevice {
+    file:   Box<dyn File>,
+    offset: u64,
+    size:   u64,
+}
+
+impl FileBlockDevice {
+    /// Cria um dispositivo que começa em `offset` bytes dentro de `file`.
+    pub fn new(mut file: Box<dyn File>, offset: u64) -> Result<Self> {
+        let total = file.size()?;
+        if offset >= total {
+            return Err(BootError::FileSystem(FileSystemError::InvalidSize));
+        }
+
+        Ok(Self {
+            file,
+            offset,
+            size: total - offset,
+        })
+    }
+}
+
+impl BlockDevice for FileBlockDevice {
+    fn read_blocks(&mut self, lba: u64, buf: &mut [u8]) -> Result<()> {
+        self.file.seek(self.offset + lba * ISO_SECTOR_SIZE)?;
+        read_exact(self.file.as_mut(), buf)
+    }
+
+    fn write_blocks(&mut self, _lba: u64, _buf: &[u8]) -> Result<()> {
+        // Imagens são montadas somente leitura
+        Err(BootError::FileSystem(FileSystemError::WriteError))
+    }
+
+    fn block_size(&self) -> u64 {
+        ISO_SECTOR_SIZE
+    }
+
+    fn num_blocks(&self) -> u64 {
+        self.size / ISO_SECTOR_SIZE
+    }
+}
+
+/// Monta o ESP embutido em uma imagem ISO/GPT.
+pub fn mount_iso(mut file: Box<dyn File>) -> Result<Fat32FileSystem<FileBlockDevice>> {
+    let size = file.size()?;
+    let mut header = vec![0u8; ISO_HEADER_SIZE.min(size as usize)];
+
+    file.seek(0)?;
+    read_exact(file.as_mut(), &mut header)?;
+
+    let offset = find_esp_offset(&header)?;
+    Fat32FileSystem::mount(FileBlockDevice::new(file, offset)?)
+}
+
+/// Abre `iso_path` em `fs` e devolve a raiz do ESP embutido. Qualquer falha
+/// (imagem ausente, sem ESP, FAT inválida) vira erro para a entrada voltar ao
+/// menu.
+pub fn open_iso_root(fs: &mut dyn FileSystem, iso_path: &str) -> Result<Box<dyn Directory>> {
+    let iso_file = fs.root()?.open_file(iso_path)?;
+    mount_iso(iso_file)?.root()
+}
@@ -3,12 +3,18 @@
 //! Utilitário para localizar e ler arquivos completos para a memória.
 //! Abstrai a abertura de diretórios e leitura em chunks.
 
-use alloc::{boxed::Box, vec::Vec};
+use alloc::{boxed::Box, string::String, vec::Vec};
 
-use super::FileSystem;
-use crate::core::{
-    error::{BootError, FileSystemError, Result},
-    types::LoadedFile,
+use super::{read_exact_with, File, FileSystem};
+use crate::{
+    config::ModuleType,
+    core::{
+        config::limits::{MAX_KERNEL_SIZE, MAX_MODULE_SIZE},
+        crc32::crc32,
+        error::{BootError, FileSystemError, MemoryError, Result},
+        types::LoadedFile,
+    },
+    uefi::table::boot::MemoryType,
 };
 
 /// Abstração para carregamento de arquivos.
@@ -44,10 +50,10 @@ impl<'a> FileLoader<'a> {
         let mut root = self.fs.root()?;
         let mut file = root
             .open_file(path)
-            .map_err(|_| BootError::FileSystem(FileSystemError::FileNotFound))?;
+            .map_err(|_| BootError::file(path, FileSystemError::FileNotFound))?;
 
         // Aloca buffer com tamanho do arquivo
-        let size = file.metadata()?.size as usize;
+        let size = file.size()? as usize;
         let mut buffer = Vec::with_capacity(size);
         buffer.resize(size, 0);
 
@@ -63,6 +69,213 @@ impl<'a> FileLoader<'a> {
 
         crate::println!("Arquivo carregado: {} ({} bytes) @ {:#x}", path, size, ptr);
 
-        Ok(LoadedFile { ptr, size })
+        Ok(LoadedFile {
+            ptr,
+            size,
+            cmdline: None,
+        })
     }
 }
+
+/// Lê um módulo inteiro para um buffer do pool do firmware (`LoaderData`).
+///
+/// Ver `load_module_with`.
+pub fn load_module(file: &mut dyn File, cmdline: Option<String>) -> Result<Option<LoadedFile>> {
+    let bs = crate::uefi::system_table().boot_services();
+    load_module_with(
+        file,
+        cmdline,
+        &mut |size| {
+            bs.allocate_pool(MemoryType::LoaderData, size)
+                .map_err(|_| BootError::Memory(MemoryError::OutOfMemory))
+        },
+        &mut |us| bs.stall(us),
+    )
+}
+
+/// Lê um módulo inteiro para a memória devolvida por `allocate`.
+///
+/// O tamanho é conferido contra `MAX_MODULE_SIZE` antes de qualquer alocação:
+/// um módulo enorme (ou malicioso) é recusado com
+/// `FileSystemError::FileTooLarge` em vez de esgotar o pool do firmware.
+/// Módulos vazios retornam `Ok(None)`.
+pub fn load_module_with(
+    file: &mut dyn File,
+    cmdline: Option<String>,
+    allocate: &mut dyn FnMut(usize) -> Result<*mut u8>,
+    stall: &mut dyn FnMut(usize),
+) -> Result<Option<LoadedFile>> {
+    let size = file.size()?;
+    if size > MAX_MODULE_SIZE as u64 {
+        log::warn!(
+            "Modulo muito grande: {} bytes (max: {} bytes)",
+            size,
+            MAX_MODULE_SIZE
+        );
+        return Err(BootError::FileSystem(FileSystemError::FileTooLarge));
+    }
+    let size = size as usize;
+    if size == 0 {
+        return Ok(None);
+    }
+
+    let ptr = allocate(size)?;
+    let data = unsafe { core::slice::from_raw_parts_mut(ptr, size) };
+    read_exact_with(file, data, stall)?;
+
+    Ok(Some(LoadedFile {
+        ptr: ptr as u64,
+        size,
+        cmdline,
+    }))
+}
+
+/// Lê o kernel inteiro para um buffer do pool do firmware (`LoaderData`).
+///
+/// Ver `load_kernel_with`.
+pub fn load_kernel(file: &mut dyn File) -> Result<LoadedFile> {
+    let bs = crate::uefi::system_table().boot_services();
+    load_kernel_with(
+        file,
+        &mut |size| {
+            bs.allocate_pool(MemoryType::LoaderData, size)
+                .map_err(|_| BootError::Memory(MemoryError::OutOfMemory))
+        },
+        &mut |us| bs.stall(us),
+    )
+}
+
+/// Lê o kernel inteiro para a memória devolvida por `allocate`.
+///
+/// Ao contrário de um módulo, um kernel vazio é um erro
+/// (`FileSystemError::InvalidSize`), e o limite é `MAX_KERNEL_SIZE`
+/// (`FileSystemError::FileTooLarge`). Os dois são conferidos antes de
+/// alocar, e o chamador volta ao menu em vez de parar a máquina.
+pub fn load_kernel_with(
+    file: &mut dyn File,
+    allocate: &mut dyn FnMut(usize) -> Result<*mut u8>,
+    stall: &mut dyn FnMut(usize),
+) -> Result<LoadedFile> {
+    let size = file.size()?;
+    if size == 0 {
+        log::warn!("Kernel tem tamanho zero. Arquivo corrompido?");
+        return Err(BootError::FileSystem(FileSystemError::InvalidSize));
+    }
+    if size > MAX_KERNEL_SIZE as u64 {
+        log::warn!(
+            "Kernel muito grande: {} bytes (max: {} bytes)",
+            size,
+            MAX_KERNEL_SIZE
+        );
+        return Err(BootError::FileSystem(FileSystemError::FileTooLarge));
+    }
+    let size = size as usize;
+
+    let ptr = allocate(size)?;
+    let data = unsafe { core::slice::from_raw_parts_mut(ptr, size) };
+    read_exact_with(file, data, stall)?;
+
+    Ok(LoadedFile {
+        ptr: ptr as u64,
+        size,
+        cmdline: None,
+    })
+}
+
+/// Confere o CRC32 de um arquivo já carregado com o valor declarado na
+/// configuração (`kernel_crc32`/`module_crc32`).
+///
+/// Pega corrupção da mídia (bit-rot), não adulteração: não substitui a
+/// verificação de assinaturas. Sem valor esperado, não há o que conferir.
+pub fn verify_crc32(data: &[u8], expected: Option<u32>) -> Result<()> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+
+    let actual = crc32(data);
+    if actual != expected {
+        log::warn!(
+            "CRC32 divergente: esperado {:08x}, calculado {:08x}",
+            expected,
+            actual
+        );
+        return Err(BootError::FileSystem(FileSystemError::ChecksumMismatch));
+    }
+    Ok(())
+}
+
+/// Confere o tamanho lido de um arquivo com o declarado na configuração
+/// (`initrd_size`) e devolve o tamanho verificado.
+///
+/// Um initrd truncado ou trocado costuma falhar só dentro do kernel, longe da
+/// causa; aqui a divergência vira `FileSystemError::InvalidSize`.
+pub fn verify_size(actual: usize, expected: Option<u64>) -> Result<usize> {
+    match expected {
+        Some(expected) if expected != actual as u64 => {
+            log::warn!(
+                "Tamanho divergente: esperado {} bytes, lido {} bytes",
+                expected,
+                actual
+            );
+            Err(BootError::FileSystem(FileSystemError::InvalidSize))
+        },
+        _ => Ok(actual),
+    }
+}
+
+/// Formato reconhecido pelo magic no início de um módulo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleFormat {
+    /// cpio newc (`070701`).
+    Cpio,
+    /// Fluxo gzip (`1f 8b`).
+    Gzip,
+    /// Binário ELF (`7f 'E' 'L' 'F'`).
+    Elf,
+}
+
+/// Identifica o formato de `data` pelo magic; `None` se não reconhecido.
+pub fn detect_module_format(data: &[u8]) -> Option<ModuleFormat> {
+    if data.starts_with(super::cpio::NEWC_MAGIC) {
+        Some(ModuleFormat::Cpio)
+    } else if data.starts_with(&[0x1F, 0x8B]) {
+        Some(ModuleFormat::Gzip)
+    } else if data.starts_with(b"\x7FELF") {
+        Some(ModuleFormat::Elf)
+    } else {
+        None
+    }
+}
+
+/// Confere o magic de um módulo com o `module_type` declarado.
+///
+/// Retorna `Ok(true)` se o conteúdo confere (ou não há tipo declarado) e
+/// `Ok(false)` se a divergência foi tolerada com um aviso no log; com
+/// `strict` (`strict_diagnostics`) o módulo é recusado.
+pub fn verify_module_type(data: &[u8], expected: Option<ModuleType>, strict: bool) -> Result<bool> {
+    let Some(expected) = expected else {
+        return Ok(true);
+    };
+
+    let found = detect_module_format(data);
+    let matches = match expected {
+        ModuleType::Initrd => {
+            matches!(found, Some(ModuleFormat::Cpio | ModuleFormat::Gzip))
+        },
+        ModuleType::Cpio => found == Some(ModuleFormat::Cpio),
+        ModuleType::Elf => found == Some(ModuleFormat::Elf),
+    };
+    if matches {
+        return Ok(true);
+    }
+
+    log::warn!(
+        "Modulo: module_type {:?} declarado, conteudo parece {:?}",
+        expected,
+        found
+    );
+    if strict {
+        return Err(BootError::FileSystem(FileSystemError::InvalidSignature));
+    }
+    Ok(false)
+}
@@ -30,8 +30,13 @@
 //!   - *Meta:* Permitir que o `/boot` resida dentro do pool RFS, eliminando a
 //!     dependência da partição ESP (FAT32) para o Kernel.
 
+pub mod cpio;
 pub mod dev;
+pub mod ext2;
 pub mod fat32;
+pub mod gpt;
+pub mod http;
+pub mod iso;
 pub mod loader;
 pub mod path;
 pub mod redstonefs;
@@ -54,8 +59,11 @@ pub fn read_to_string(file: &mut dyn File) -> crate::core::error::Result<alloc::
         }
         buf.extend_from_slice(&temp[..n]);
     }
-    alloc::string::String::from_utf8(buf)
-        .map_err(|_| crate::core::error::BootError::Generic("Invalid UTF-8"))
+    alloc::string::String::from_utf8(buf).map_err(|_| {
+        crate::core::error::BootError::FileSystem(
+            crate::core::error::FileSystemError::InvalidEncoding,
+        )
+    })
 }
 
 pub fn read_to_bytes(file: &mut dyn File) -> crate::core::error::Result<alloc::vec::Vec<u8>> {
@@ -71,6 +79,13 @@ pub fn read_to_bytes(file: &mut dyn File) -> crate::core::error::Result<alloc::v
     Ok(buf)
 }
 
+/// Leituras de 0 bytes toleradas em sequência antes de `read_exact` concluir
+/// que o arquivo acabou.
+pub const READ_ZERO_RETRIES: usize = 3;
+
+/// Espera entre leituras vazias (microssegundos).
+const READ_RETRY_DELAY_US: usize = 1_000;
+
 /// Lê exatamente `buffer.len()` bytes do arquivo para o buffer fornecido.
 /// Retorna erro se não conseguir ler todos os bytes (arquivo truncado ou
 /// corrompido).
@@ -79,18 +94,40 @@ pub fn read_to_bytes(file: &mut dyn File) -> crate::core::error::Result<alloc::v
 /// allocate_pool) e quer ler o arquivo diretamente neste buffer, sem alocações
 /// intermediárias.
 pub fn read_exact(file: &mut dyn File, buffer: &mut [u8]) -> crate::core::error::Result<()> {
+    read_exact_with(file, buffer, &mut |us| {
+        crate::uefi::system_table().boot_services().stall(us)
+    })
+}
+
+/// `read_exact` com a espera entre tentativas fornecida pelo chamador.
+///
+/// Alguns drivers de FS do firmware devolvem 0 bytes de forma transitória no
+/// meio do arquivo. Só após `READ_ZERO_RETRIES` leituras vazias seguidas o
+/// arquivo é considerado truncado.
+pub fn read_exact_with(
+    file: &mut dyn File,
+    buffer: &mut [u8],
+    stall: &mut dyn FnMut(usize),
+) -> crate::core::error::Result<()> {
     let mut total_read = 0;
+    let mut zero_reads = 0;
 
     while total_read < buffer.len() {
         let n = file.read(&mut buffer[total_read..])?;
 
         if n == 0 {
-            // EOF antes de ler tudo - arquivo corrompido ou menor que esperado
-            return Err(crate::core::error::BootError::FileSystem(
-                crate::core::error::FileSystemError::ReadError,
-            ));
+            zero_reads += 1;
+            if zero_reads > READ_ZERO_RETRIES {
+                // EOF antes de ler tudo - arquivo corrompido ou menor que esperado
+                return Err(crate::core::error::BootError::FileSystem(
+                    crate::core::error::FileSystemError::Truncated,
+                ));
+            }
+            stall(READ_RETRY_DELAY_US);
+            continue;
         }
 
+        zero_reads = 0;
         total_read += n;
     }
 
@@ -3,7 +3,12 @@
 //! Normaliza caminhos entre estilos Unix (usado na config) e UEFI (usado no
 //! firmware).
 
-use alloc::string::{String, ToString};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::core::error::{BootError, FileSystemError, Result};
 
 /// Normaliza um caminho para o formato UEFI (separador `\`).
 /// Remove prefixos como `boot():`, `boot:` ou `/` inicial.
@@ -42,3 +47,42 @@ pub fn split_filename(path: &str) -> (String, String) {
         (String::new(), normalized)
     }
 }
+
+/// Converte um caminho para UCS-2 terminado em NUL, como esperado por
+/// `EFI_FILE_PROTOCOL.Open`.
+///
+/// Remove o prefixo de dispositivo (`boot()`, `boot():`, ...) e troca `/` por
+/// `\`; a barra inicial, se houver, é preservada. Caracteres fora do BMP não
+/// cabem em uma única unidade UCS-2 e resultam em `InvalidPath`.
+pub fn to_ucs2(path: &str) -> Result<Vec<u16>> {
+    let path = ["boot():", "boot()", "boot:", "vol():"]
+        .iter()
+        .find_map(|prefix| path.strip_prefix(prefix))
+        .unwrap_or(path);
+
+    let mut out = Vec::with_capacity(path.len() + 1);
+    for c in path.chars() {
+        let c = if c == '/' { '\\' } else { c };
+        let unit = u16::try_from(u32::from(c))
+            .map_err(|_| BootError::FileSystem(FileSystemError::InvalidPath))?;
+        out.push(unit);
+    }
+    out.push(0);
+
+    Ok(out)
+}
+
+/// Converte UCS-2 de volta para `String`, parando no primeiro NUL.
+///
+/// Unidades substitutas (0xD800..=0xDFFF) não são caracteres UCS-2 válidos e
+/// resultam em `InvalidPath`.
+pub fn from_ucs2(units: &[u16]) -> Result<String> {
+    units
+        .iter()
+        .take_while(|&&unit| unit != 0)
+        .map(|&unit| {
+            char::from_u32(u32::from(unit))
+                .ok_or(BootError::FileSystem(FileSystemError::InvalidPath))
+        })
+        .collect()
+}
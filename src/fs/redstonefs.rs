@@ -7,9 +7,10 @@ use alloc::boxed::Box;
 
 use super::{
     dev::BlockDevice,
+    gpt::{GptPartition, PartitionDevice},
     vfs::{Directory, FileSystem},
 };
-use crate::core::error::{BootError, Result};
+use crate::core::error::{BootError, FileSystemError, Result};
 
 pub struct RedstoneFileSystem<D: BlockDevice> {
     #[allow(dead_code)]
@@ -23,9 +24,17 @@ impl<D: BlockDevice> RedstoneFileSystem<D> {
     }
 }
 
+impl<D: BlockDevice> RedstoneFileSystem<PartitionDevice<D>> {
+    /// Monta a partição `partition` de `disk` (ver `gpt::find_partition`),
+    /// começando no LBA declarado na GPT.
+    pub fn mount_partition(disk: D, partition: GptPartition) -> Result<Self> {
+        Self::mount(PartitionDevice::new(disk, partition))
+    }
+}
+
 impl<D: BlockDevice + 'static> FileSystem for RedstoneFileSystem<D> {
     fn root(&mut self) -> Result<Box<dyn Directory>> {
-        Err(BootError::Generic("RedstoneFS ainda não implementado"))
+        Err(BootError::FileSystem(FileSystemError::UnsupportedFsType))
     }
 
     fn name(&self) -> &str {
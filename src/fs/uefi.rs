@@ -6,21 +6,264 @@
 use alloc::{
     boxed::Box,
     string::String,
+    vec,
     vec::Vec,
 };
 
-use super::path::normalize_path;
+use super::path::{normalize_path, to_ucs2};
 use crate::{
     core::error::{BootError, FileSystemError, Result},
     fs::vfs::{Directory, File, FileSystem, Metadata},
+    uefi::proto::device_path::{
+        device_path_bytes, path_key, DevicePathProtocol, DEVICE_PATH_PROTOCOL_GUID,
+    },
     uefi::proto::media::{
             file::{
-                FILE_MODE_READ, FileProtocol,
+                FILE_DIRECTORY, FILE_INFO_GUID, FILE_MODE_READ, FILE_READ_ONLY, FileInfo,
+                FileProtocol,
             },
-            fs::SimpleFileSystemProtocol,
+            fs::{SIMPLE_FILE_SYSTEM_PROTOCOL_GUID, SimpleFileSystemProtocol},
         },
+    uefi::{
+        Handle, Status,
+        system_table,
+        table::boot::{LocateSearchType, OPEN_PROTOCOL_GET_PROTOCOL},
+    },
 };
 
+/// Tentativas de abrir o SimpleFileSystem do dispositivo de boot antes de
+/// procurar outro volume.
+pub const SFS_OPEN_ATTEMPTS: usize = 3;
+
+/// Espera entre tentativas (100ms).
+pub const SFS_RETRY_DELAY_US: usize = 100_000;
+
+/// Acesso aos volumes SimpleFileSystem do firmware.
+///
+/// Abstraído para que a retentativa e o fallback de `open_boot_volume` possam
+/// ser testados no host.
+pub trait VolumeProvider {
+    /// Abre o SimpleFileSystem instalado em `handle`.
+    fn open_volume(&mut self, handle: Handle) -> Result<*mut SimpleFileSystemProtocol>;
+
+    /// Handles com SimpleFileSystem, na ordem devolvida pelo firmware.
+    fn volume_handles(&mut self) -> Result<Vec<Handle>>;
+
+    /// Device path de `handle` em texto (ver `device_path::path_key`), ou
+    /// `None` se o firmware não o expuser.
+    fn device_path(&mut self, handle: Handle) -> Option<String>;
+
+    /// Pausa entre tentativas.
+    fn delay(&mut self, microseconds: usize);
+}
+
+/// `VolumeProvider` sobre os Boot Services, abrindo protocolos em nome de
+/// `image_handle`.
+pub struct FirmwareVolumes {
+    image_handle: Handle,
+}
+
+impl FirmwareVolumes {
+    pub fn new(image_handle: Handle) -> Self {
+        Self { image_handle }
+    }
+}
+
+impl VolumeProvider for FirmwareVolumes {
+    fn open_volume(&mut self, handle: Handle) -> Result<*mut SimpleFileSystemProtocol> {
+        system_table()
+            .boot_services()
+            .open_protocol(
+                handle,
+                &SIMPLE_FILE_SYSTEM_PROTOCOL_GUID,
+                self.image_handle,
+                Handle::null(),
+                OPEN_PROTOCOL_GET_PROTOCOL,
+            )
+            .map(|ptr| ptr as *mut SimpleFileSystemProtocol)
+            .map_err(|_| BootError::FileSystem(FileSystemError::VolumeOpenError))
+    }
+
+    fn volume_handles(&mut self) -> Result<Vec<Handle>> {
+        let bs = system_table().boot_services();
+        let mut count = 0usize;
+        let mut buffer: *mut Handle = core::ptr::null_mut();
+
+        unsafe {
+            (bs.locate_handle_buffer_f)(
+                LocateSearchType::ByProtocol,
+                &SIMPLE_FILE_SYSTEM_PROTOCOL_GUID,
+                core::ptr::null_mut(),
+                &mut count,
+                &mut buffer,
+            )
+            .to_result()
+            .map_err(|_| BootError::FileSystem(FileSystemError::VolumeOpenError))?;
+
+            // O buffer pertence ao pool do firmware: copiamos e liberamos
+            let handles = core::slice::from_raw_parts(buffer, count).to_vec();
+            let _ = bs.free_pool(buffer as *mut u8);
+            Ok(handles)
+        }
+    }
+
+    fn device_path(&mut self, handle: Handle) -> Option<String> {
+        let ptr = system_table()
+            .boot_services()
+            .open_protocol(
+                handle,
+                &DEVICE_PATH_PROTOCOL_GUID,
+                self.image_handle,
+                Handle::null(),
+                OPEN_PROTOCOL_GET_PROTOCOL,
+            )
+            .ok()?;
+
+        path_key(unsafe { device_path_bytes(ptr as *const DevicePathProtocol) }?)
+    }
+
+    fn delay(&mut self, microseconds: usize) {
+        system_table().boot_services().stall(microseconds);
+    }
+}
+
+/// Ordem de busca dos volumes: `boot` primeiro, depois os demais de `handles`
+/// ordenados pelo texto do device path (`path_of`).
+///
+/// A ordem de enumeração do firmware muda entre boots em algumas máquinas;
+/// ordenar pelo caminho físico torna a escolha do volume reprodutível.
+/// Volumes sem device path vão para o fim, na ordem original.
+pub fn disk_device_priority(
+    boot: Handle,
+    handles: Vec<Handle>,
+    mut path_of: impl FnMut(Handle) -> Option<String>,
+) -> Vec<Handle> {
+    let mut other_disks: Vec<(Option<String>, Handle)> = handles
+        .into_iter()
+        .filter(|&handle| handle != boot)
+        .map(|handle| (path_of(handle), handle))
+        .collect();
+
+    // Sem caminho vai para o fim; `sort_by` é estável e mantém a ordem do
+    // firmware entre empates
+    other_disks.sort_by(|(a, _), (b, _)| match (a, b) {
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => core::cmp::Ordering::Less,
+        (None, Some(_)) => core::cmp::Ordering::Greater,
+        (None, None) => core::cmp::Ordering::Equal,
+    });
+
+    let mut order = Vec::with_capacity(other_disks.len() + 1);
+    order.push(boot);
+    order.extend(other_disks.into_iter().map(|(_, handle)| handle));
+    order
+}
+
+/// Abre o SimpleFileSystem do dispositivo de boot (`device`).
+///
+/// Falhas transitórias do firmware são retentadas `SFS_OPEN_ATTEMPTS` vezes.
+/// Se o dispositivo continuar inacessível, usa o primeiro outro volume
+/// SimpleFileSystem que abrir (a UEFI só expõe esse protocolo sobre FAT, ou
+/// seja, um ESP), na ordem de `disk_device_priority`. Retorna o handle usado
+/// e o protocolo.
+pub fn open_boot_volume<P: VolumeProvider + ?Sized>(
+    provider: &mut P,
+    device: Handle,
+) -> Result<(Handle, *mut SimpleFileSystemProtocol)> {
+    for attempt in 1..=SFS_OPEN_ATTEMPTS {
+        match provider.open_volume(device) {
+            Ok(protocol) => return Ok((device, protocol)),
+            Err(e) => {
+                log::warn!(
+                    "FS: SimpleFileSystem do dispositivo de boot falhou ({}/{}): {:?}",
+                    attempt,
+                    SFS_OPEN_ATTEMPTS,
+                    e
+                );
+                if attempt < SFS_OPEN_ATTEMPTS {
+                    provider.delay(SFS_RETRY_DELAY_US);
+                }
+            },
+        }
+    }
+
+    let handles = provider.volume_handles()?;
+    let order = disk_device_priority(device, handles, |handle| provider.device_path(handle));
+    for handle in order.into_iter().skip(1) {
+        if let Ok(protocol) = provider.open_volume(handle) {
+            log::warn!("FS: usando volume alternativo {:?}", handle);
+            return Ok((handle, protocol));
+        }
+    }
+
+    log::error!("FS: nenhum volume SimpleFileSystem utilizavel");
+    Err(BootError::FileSystem(FileSystemError::VolumeOpenError))
+}
+
+/// Tamanho inicial do buffer de `FileInfo`. Suficiente para nomes curtos;
+/// nomes longos são tratados com uma segunda chamada.
+const FILE_INFO_INITIAL_SIZE: usize = 128;
+
+/// Informações de um arquivo obtidas via `EFI_FILE_INFO`.
+#[derive(Debug, Clone)]
+pub struct UefiFileInfo {
+    pub info: FileInfo,
+    pub name: String,
+}
+
+/// Lê o `FileInfo` de um arquivo aberto.
+///
+/// Segue o padrão de duas chamadas da UEFI: se o buffer inicial for pequeno
+/// demais, o firmware devolve `BUFFER_TOO_SMALL` com o tamanho necessário, e
+/// a leitura é refeita com um buffer desse tamanho.
+///
+/// # Safety
+/// `protocol` deve apontar para um `FileProtocol` válido.
+pub unsafe fn read_file_info(protocol: *mut FileProtocol) -> Result<UefiFileInfo> {
+    let header = core::mem::size_of::<FileInfo>();
+
+    // Buffer em u64 para garantir o alinhamento de `FileInfo`
+    let mut size = FILE_INFO_INITIAL_SIZE;
+    let mut buf: Vec<u64> = vec![0; size.div_ceil(8)];
+
+    let mut status = ((*protocol).get_info)(
+        protocol,
+        &FILE_INFO_GUID,
+        &mut size,
+        buf.as_mut_ptr() as *mut _,
+    );
+
+    if status == Status::BUFFER_TOO_SMALL {
+        buf = vec![0; size.div_ceil(8)];
+        status = ((*protocol).get_info)(
+            protocol,
+            &FILE_INFO_GUID,
+            &mut size,
+            buf.as_mut_ptr() as *mut _,
+        );
+    }
+
+    status
+        .to_result()
+        .map_err(|_| BootError::FileSystem(FileSystemError::ReadError))?;
+
+    if size < header || size > buf.len() * 8 {
+        return Err(BootError::FileSystem(FileSystemError::InvalidSize));
+    }
+
+    let info = core::ptr::read(buf.as_ptr() as *const FileInfo);
+
+    // Nome em UCS-2 logo após o cabeçalho, até o NUL ou o fim do buffer
+    let name_ptr = (buf.as_ptr() as *const u8).add(header) as *const u16;
+    let name_units = core::slice::from_raw_parts(name_ptr, (size - header) / 2);
+    let len = name_units.iter().position(|&c| c == 0).unwrap_or(name_units.len());
+    let name = char::decode_utf16(name_units[..len].iter().copied())
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect();
+
+    Ok(UefiFileInfo { info, name })
+}
+
 // --- Estruturas Wrapper ---
 
 pub struct UefiFileSystem<'a> {
@@ -48,6 +291,31 @@ pub struct UefiFile {
     protocol: *mut FileProtocol,
 }
 
+impl UefiFile {
+    /// Tamanho do arquivo via seek até o fim, sem consultar `FileInfo`.
+    /// A posição atual é restaurada ao final.
+    fn end_position(&self) -> Result<u64> {
+        let mut size = 0u64;
+        unsafe {
+            // Backup position
+            let mut current_pos = 0u64;
+            ((*self.protocol).get_position)(self.protocol, &mut current_pos)
+                .to_result()
+                .map_err(|_| BootError::FileSystem(FileSystemError::SeekError))?;
+
+            // Seek end
+            ((*self.protocol).set_position)(self.protocol, 0xFFFFFFFFFFFFFFFF)
+                .to_result()
+                .map_err(|_| BootError::FileSystem(FileSystemError::SeekError))?;
+            ((*self.protocol).get_position)(self.protocol, &mut size);
+
+            // Restore
+            ((*self.protocol).set_position)(self.protocol, current_pos);
+        }
+        Ok(size)
+    }
+}
+
 impl File for UefiFile {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
         let mut size = buf.len();
@@ -84,29 +352,23 @@ impl File for UefiFile {
     }
 
     fn metadata(&self) -> Result<Metadata> {
-        // Para uma implementação completa, deveríamos chamar GetInfo aqui.
-        // Como o buffer size é variável, simplificamos retornando o tamanho
-        // através de uma leitura de seek (estratégia comum em bootloaders simples).
-
-        let mut size = 0u64;
-        unsafe {
-            // Backup position
-            let mut current_pos = 0u64;
-            ((*self.protocol).get_position)(self.protocol, &mut current_pos);
-
-            // Seek end
-            ((*self.protocol).set_position)(self.protocol, 0xFFFFFFFFFFFFFFFF);
-            ((*self.protocol).get_position)(self.protocol, &mut size);
-
-            // Restore
-            ((*self.protocol).set_position)(self.protocol, current_pos);
+        match unsafe { read_file_info(self.protocol) } {
+            Ok(file_info) => Ok(Metadata {
+                size:        file_info.info.file_size,
+                is_dir:      file_info.info.attribute & FILE_DIRECTORY != 0,
+                is_readonly: file_info.info.attribute & FILE_READ_ONLY != 0,
+            }),
+            // Firmwares sem GetInfo funcional: tamanho via seek
+            Err(_) => Ok(Metadata {
+                size:        self.end_position()?,
+                is_dir:      false,
+                is_readonly: false,
+            }),
         }
+    }
 
-        Ok(Metadata {
-            size,
-            is_dir: false,
-            is_readonly: false,
-        })
+    fn size(&mut self) -> Result<u64> {
+        self.end_position()
     }
 
     fn close(&mut self) -> Result<()> {
@@ -136,11 +398,7 @@ impl Directory for UefiDir {
         // Log para debug de caminho (Aparecerá no console QEMU)
         // crate::println!("[DEBUG] FS Open: '{}' -> '{}'", path, path_norm);
 
-        // Conversão UCS-2
-        let path_utf16: Vec<u16> = path_norm
-            .encode_utf16()
-            .chain(core::iter::once(0))
-            .collect();
+        let path_utf16 = to_ucs2(&path_norm)?;
 
         unsafe {
             let status = ((*self.protocol).open)(
@@ -163,10 +421,7 @@ impl Directory for UefiDir {
     fn open_dir(&mut self, path: &str) -> Result<Box<dyn Directory>> {
         let path_norm = normalize_path(path);
         let mut dir_ptr = core::ptr::null_mut();
-        let path_utf16: Vec<u16> = path_norm
-            .encode_utf16()
-            .chain(core::iter::once(0))
-            .collect();
+        let path_utf16 = to_ucs2(&path_norm)?;
 
         unsafe {
             ((*self.protocol).open)(
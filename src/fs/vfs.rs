@@ -31,6 +31,14 @@ pub trait File {
     /// Obtém metadados.
     fn metadata(&self) -> Result<Metadata>;
 
+    /// Obtém apenas o tamanho do arquivo, em bytes.
+    ///
+    /// Backends que conseguem o tamanho sem montar `Metadata` completo devem
+    /// sobrescrever este método.
+    fn size(&mut self) -> Result<u64> {
+        Ok(self.metadata()?.size)
+    }
+
     /// Fecha o arquivo (opcional, pois Drop deve lidar com isso).
     fn close(&mut self) -> Result<()> {
         Ok(())
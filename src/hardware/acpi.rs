@@ -18,23 +18,24 @@
 //! - **Segurança de Tipo:** Usa GUIDs tipados da crate `uefi`.
 //!
 //! ### ⚠️ Pontos de Atenção (Riscos)
-//! - **Confiança Cega:** O módulo retorna o endereço sem validar o Checksum do
-//!   RSDP.
-//!   - *Risco:* Se a BIOS estiver bugada e apontar para lixo, o Kernel vai
-//!     travar ao tentar parsear.
-//! - **Sem Leitura:** O Bootloader não lê as tabelas, apenas passa o ponteiro.
-//!   Isso é bom (mantém bootloader simples) e ruim (perde chance de validar
-//!   cedo).
+//! - **Validação Rasa:** Os checksums do RSDP (v1 e v2) e da XSDT são
+//!   conferidos antes do handoff, mas as demais tabelas não.
+//!   - *Risco:* Uma MADT/FADT corrompida ainda chega intacta ao Kernel.
+//! - **Leitura Mínima:** Além do ponteiro, o Bootloader só lê a MADT (CPUs e
+//!   IO APICs) para o `BootInfo`. O resto da topologia fica com o Kernel.
 //!
 //! ## 🛠️ TODOs e Roadmap
-//! - [ ] **TODO: (Reliability)** Validar Checksum do RSDP antes de aceitar.
-//!   - *Motivo:* Fail-fast. Se o RSDP estiver corrompido, avisar o usuário
-//!     antes de bootar o kernel.
+//! - [x] **Reliability:** Validar Checksum do RSDP antes de aceitar
+//!   (`AcpiManager::validate_rsdp`).
 //! - [ ] **TODO: (Feature)** Dump básico da topologia para debug.
 //!   - *Idea:* Imprimir "Found X CPUs" se `ignite.cfg` tiver `debug=true`.
 
 use crate::{
-    core::error::{BootError, Result},
+    core::{
+        error::{BootError, Result},
+        handoff::MAX_IO_APICS,
+    },
+    hardware::io::Mmio,
     uefi::{
         system_table,
         table::config::{ACPI_20_TABLE_GUID, ACPI_TABLE_GUID},
@@ -55,13 +56,13 @@ impl AcpiManager {
         let st = system_table();
 
         // 1. Tentar encontrar ACPI 2.0 (Preferencial em x86_64 e AArch64)
-        if let Some(addr) = st.get_configuration_table(&ACPI_20_TABLE_GUID) {
+        if let Some(addr) = st.find_config_table(&ACPI_20_TABLE_GUID) {
             crate::println!("Hardware: ACPI 2.0 (XSDT) encontrado em {:#p}", addr);
             return Ok(addr as u64);
         }
 
         // 2. Fallback para ACPI 1.0 (Sistemas Legacy/VMs antigas)
-        if let Some(addr) = st.get_configuration_table(&ACPI_TABLE_GUID) {
+        if let Some(addr) = st.find_config_table(&ACPI_TABLE_GUID) {
             crate::println!("Hardware: ACPI 1.0 (RSDT) encontrado em {:#p}", addr);
             return Ok(addr as u64);
         }
@@ -70,3 +71,229 @@ impl AcpiManager {
         Err(BootError::Generic("ACPI RSDP not found"))
     }
 }
+
+/// Raiz das tabelas ACPI após validação dos checksums.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AcpiRoot {
+    /// Endereço físico do RSDP validado.
+    pub rsdp_addr:   u64,
+    /// Endereço físico da XSDT (0 em ACPI 1.0 ou se a XSDT for inválida).
+    pub xsdt_addr:   u64,
+    /// Número de ponteiros de tabela na XSDT.
+    pub table_count: u64,
+}
+
+/// Topologia de interrupções extraída da MADT.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MadtInfo {
+    /// Endereço físico do Local APIC (com override de 64 bits aplicado).
+    pub local_apic_addr: u64,
+    /// Processadores habilitados ou que podem ser ligados (LAPIC e x2APIC).
+    pub cpu_count:       u32,
+    /// Número de IO APICs válidos em `io_apic_addrs`.
+    pub io_apic_count:   u32,
+    /// Endereços físicos dos IO APICs (até `MAX_IO_APICS`).
+    pub io_apic_addrs:   [u64; MAX_IO_APICS],
+}
+
+// Tipos de entrada da MADT
+const MADT_LOCAL_APIC: u8 = 0;
+const MADT_IO_APIC: u8 = 1;
+const MADT_LAPIC_ADDR_OVERRIDE: u8 = 5;
+const MADT_LOCAL_X2APIC: u8 = 9;
+
+/// Processador habilitado (bit 0) ou "online capable" (bit 1).
+const LAPIC_USABLE: u32 = 0b11;
+
+/// Tamanho do cabeçalho comum das tabelas SDT.
+const SDT_HEADER_LEN: u64 = 36;
+
+/// Lê tabelas ACPI byte a byte: elas não têm alinhamento garantido.
+struct AcpiReader;
+
+impl AcpiReader {
+    fn u8(addr: u64) -> u8 {
+        unsafe { Mmio::<u8>::new(addr as usize) }.read()
+    }
+
+    fn u32(addr: u64) -> u32 {
+        u32::from_le_bytes([
+            Self::u8(addr),
+            Self::u8(addr + 1),
+            Self::u8(addr + 2),
+            Self::u8(addr + 3),
+        ])
+    }
+
+    fn u64(addr: u64) -> u64 {
+        Self::u32(addr) as u64 | ((Self::u32(addr + 4) as u64) << 32)
+    }
+
+    /// Soma de todos os bytes; tabelas ACPI válidas somam 0.
+    fn checksum(addr: u64, len: u64) -> u8 {
+        (0..len).fold(0u8, |acc, i| acc.wrapping_add(Self::u8(addr + i)))
+    }
+
+    fn signature(addr: u64) -> [u8; 4] {
+        [
+            Self::u8(addr),
+            Self::u8(addr + 1),
+            Self::u8(addr + 2),
+            Self::u8(addr + 3),
+        ]
+    }
+}
+
+/// Assinatura do RSDP.
+const RSDP_SIGNATURE: &[u8; 8] = b"RSD PTR ";
+
+/// Bytes cobertos pelo checksum do RSDP ACPI 1.0.
+const RSDP_V1_LEN: u64 = 20;
+
+/// Tamanho mínimo do RSDP ACPI 2.0+.
+const RSDP_V2_LEN: u64 = 36;
+
+impl AcpiManager {
+    /// Valida o RSDP e, em ACPI 2.0+, a XSDT apontada por ele.
+    ///
+    /// O checksum v1 cobre os primeiros 20 bytes; a partir da revisão 2, o
+    /// checksum estendido cobre `length` bytes. Uma XSDT corrompida não
+    /// invalida o RSDP: apenas resulta em `xsdt_addr = 0`.
+    ///
+    /// # Safety
+    /// `rsdp_addr` precisa apontar para memória legível.
+    pub unsafe fn validate_rsdp(rsdp_addr: u64) -> Result<AcpiRoot> {
+        let signature: [u8; 8] = core::array::from_fn(|i| AcpiReader::u8(rsdp_addr + i as u64));
+        if &signature != RSDP_SIGNATURE {
+            return Err(BootError::Generic("ACPI RSDP signature invalid"));
+        }
+
+        if AcpiReader::checksum(rsdp_addr, RSDP_V1_LEN) != 0 {
+            return Err(BootError::Generic("ACPI RSDP checksum invalid"));
+        }
+
+        let mut root = AcpiRoot {
+            rsdp_addr,
+            ..AcpiRoot::default()
+        };
+
+        if AcpiReader::u8(rsdp_addr + 15) < 2 {
+            return Ok(root);
+        }
+
+        let length = AcpiReader::u32(rsdp_addr + 20) as u64;
+        if length < RSDP_V2_LEN || AcpiReader::checksum(rsdp_addr, length) != 0 {
+            return Err(BootError::Generic("ACPI RSDP extended checksum invalid"));
+        }
+
+        let xsdt = AcpiReader::u64(rsdp_addr + 24);
+        if xsdt == 0 {
+            return Ok(root);
+        }
+
+        let xsdt_len = AcpiReader::u32(xsdt + 4) as u64;
+        if &AcpiReader::signature(xsdt) != b"XSDT"
+            || xsdt_len < SDT_HEADER_LEN
+            || AcpiReader::checksum(xsdt, xsdt_len) != 0
+        {
+            log::warn!("ACPI: XSDT em {:#x} invalida, ignorando", xsdt);
+            return Ok(root);
+        }
+
+        root.xsdt_addr = xsdt;
+        root.table_count = (xsdt_len - SDT_HEADER_LEN) / 8;
+        Ok(root)
+    }
+
+    /// Localiza a MADT a partir do RSDP e enumera seus LAPICs e IO APICs.
+    ///
+    /// Usa a XSDT quando o RSDP é ACPI 2.0+, senão a RSDT.
+    ///
+    /// # Safety
+    /// `rsdp_addr` e as tabelas referenciadas precisam estar acessíveis
+    /// (identity map do firmware durante Boot Services).
+    pub unsafe fn parse_madt(rsdp_addr: u64) -> Result<MadtInfo> {
+        let madt = Self::find_table(rsdp_addr, b"APIC")
+            .ok_or(BootError::Generic("ACPI MADT not found"))?;
+
+        let length = AcpiReader::u32(madt + 4) as u64;
+        let mut info = MadtInfo {
+            local_apic_addr: AcpiReader::u32(madt + SDT_HEADER_LEN) as u64,
+            ..MadtInfo::default()
+        };
+
+        // Entradas começam após o cabeçalho + endereço do LAPIC + flags
+        let end = madt + length;
+        let mut entry = madt + SDT_HEADER_LEN + 8;
+
+        while entry + 2 <= end {
+            let kind = AcpiReader::u8(entry);
+            let len = AcpiReader::u8(entry + 1) as u64;
+            if len < 2 || entry + len > end {
+                break;
+            }
+
+            match kind {
+                MADT_LOCAL_APIC if len >= 8 => {
+                    if AcpiReader::u32(entry + 4) & LAPIC_USABLE != 0 {
+                        info.cpu_count += 1;
+                    }
+                },
+                MADT_LOCAL_X2APIC if len >= 16 => {
+                    if AcpiReader::u32(entry + 8) & LAPIC_USABLE != 0 {
+                        info.cpu_count += 1;
+                    }
+                },
+                MADT_IO_APIC if len >= 12 => {
+                    let idx = info.io_apic_count as usize;
+                    if idx < MAX_IO_APICS {
+                        info.io_apic_addrs[idx] = AcpiReader::u32(entry + 4) as u64;
+                        info.io_apic_count += 1;
+                    }
+                },
+                MADT_LAPIC_ADDR_OVERRIDE if len >= 12 => {
+                    info.local_apic_addr = AcpiReader::u64(entry + 4);
+                },
+                _ => {},
+            }
+
+            entry += len;
+        }
+
+        Ok(info)
+    }
+
+    /// Procura uma tabela pela assinatura na XSDT (ou RSDT).
+    unsafe fn find_table(rsdp_addr: u64, signature: &[u8; 4]) -> Option<u64> {
+        let revision = AcpiReader::u8(rsdp_addr + 15);
+        let xsdt = if revision >= 2 {
+            AcpiReader::u64(rsdp_addr + 24)
+        } else {
+            0
+        };
+
+        let (root, entry_size) = if xsdt != 0 {
+            (xsdt, 8)
+        } else {
+            (AcpiReader::u32(rsdp_addr + 16) as u64, 4)
+        };
+
+        if root == 0 {
+            return None;
+        }
+
+        let length = AcpiReader::u32(root + 4) as u64;
+        let count = length.saturating_sub(SDT_HEADER_LEN) / entry_size;
+
+        (0..count)
+            .map(|i| {
+                let slot = root + SDT_HEADER_LEN + i * entry_size;
+                if entry_size == 8 {
+                    AcpiReader::u64(slot)
+                } else {
+                    AcpiReader::u32(slot) as u64
+                }
+            })
+            .find(|&table| table != 0 && &AcpiReader::signature(table) == signature)
+    }
+}
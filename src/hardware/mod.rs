@@ -6,7 +6,9 @@
 
 pub mod acpi;
 pub mod io;
+pub mod rtc;
 pub mod serial;
+pub mod smbios;
 pub mod storage;
 
 // Re-exports
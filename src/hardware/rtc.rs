@@ -0,0 +1,137 @@
+//! Relógio de Tempo Real (CMOS RTC)
+//!
+//! Lê a hora do RTC pelas portas 0x70 (índice) e 0x71 (dados), sem depender
+//! do `GetTime` do firmware. Usado para carimbar as linhas de log.
+
+use super::serial::{NativePortIo, PortIo};
+use crate::uefi::table::runtime::Time;
+
+/// Porta de seleção de registrador do CMOS.
+const CMOS_INDEX: u16 = 0x70;
+
+/// Porta de dados do CMOS.
+const CMOS_DATA: u16 = 0x71;
+
+const REG_SECONDS: u8 = 0x00;
+const REG_MINUTES: u8 = 0x02;
+const REG_HOURS: u8 = 0x04;
+const REG_DAY: u8 = 0x07;
+const REG_MONTH: u8 = 0x08;
+const REG_YEAR: u8 = 0x09;
+const REG_STATUS_A: u8 = 0x0A;
+const REG_STATUS_B: u8 = 0x0B;
+
+/// Status A: atualização em andamento; os registradores podem estar
+/// inconsistentes enquanto estiver ligado.
+const STATUS_A_UIP: u8 = 0x80;
+
+/// Status B: valores em binário (senão, BCD).
+const STATUS_B_BINARY: u8 = 0x04;
+
+/// Status B: relógio de 24 horas (senão, 12 horas com bit de PM).
+const STATUS_B_24H: u8 = 0x02;
+
+/// Bit de PM no registrador de horas em modo 12 horas.
+const HOUR_PM: u8 = 0x80;
+
+/// Leituras do status A antes de desistir de esperar o fim da atualização.
+/// Uma atualização dura no máximo ~2ms, bem abaixo deste limite.
+pub const MAX_UIP_POLLS: usize = 100_000;
+
+/// Registradores brutos de uma leitura, antes da decodificação.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RawTime {
+    second: u8,
+    minute: u8,
+    hour:   u8,
+    day:    u8,
+    month:  u8,
+    year:   u8,
+}
+
+unsafe fn read_register<P: PortIo>(io: &mut P, register: u8) -> u8 {
+    io.write(CMOS_INDEX, register);
+    io.read(CMOS_DATA)
+}
+
+/// Espera o fim de uma atualização em andamento. Retorna `false` se o bit
+/// UIP não baixar em `MAX_UIP_POLLS` leituras (RTC ausente ou travado).
+fn wait_update<P: PortIo>(io: &mut P) -> bool {
+    (0..MAX_UIP_POLLS).any(|_| unsafe { read_register(io, REG_STATUS_A) } & STATUS_A_UIP == 0)
+}
+
+fn read_raw<P: PortIo>(io: &mut P) -> RawTime {
+    unsafe {
+        RawTime {
+            second: read_register(io, REG_SECONDS),
+            minute: read_register(io, REG_MINUTES),
+            hour:   read_register(io, REG_HOURS),
+            day:    read_register(io, REG_DAY),
+            month:  read_register(io, REG_MONTH),
+            year:   read_register(io, REG_YEAR),
+        }
+    }
+}
+
+fn bcd_to_binary(value: u8) -> u8 {
+    (value >> 4) * 10 + (value & 0x0F)
+}
+
+/// Lê a hora do RTC por `io`.
+///
+/// Espera o bit UIP baixar e repete a leitura até duas seguidas coincidirem,
+/// já que uma atualização pode começar no meio da sequência. Retorna `None`
+/// se o RTC não sair do estado de atualização.
+pub fn read_time_with<P: PortIo>(io: &mut P) -> Option<Time> {
+    if !wait_update(io) {
+        return None;
+    }
+    let mut raw = read_raw(io);
+
+    loop {
+        if !wait_update(io) {
+            return None;
+        }
+        let again = read_raw(io);
+        if again == raw {
+            break;
+        }
+        raw = again;
+    }
+
+    let status_b = unsafe { read_register(io, REG_STATUS_B) };
+    let pm = raw.hour & HOUR_PM != 0;
+    let decode = |value: u8| {
+        if status_b & STATUS_B_BINARY != 0 {
+            value
+        } else {
+            bcd_to_binary(value)
+        }
+    };
+
+    let mut hour = decode(raw.hour & !HOUR_PM);
+    if status_b & STATUS_B_24H == 0 {
+        // 12 horas: 12 AM é meia-noite e 12 PM é meio-dia
+        hour = match (hour, pm) {
+            (12, false) => 0,
+            (12, true) => 12,
+            (h, true) => h + 12,
+            (h, false) => h,
+        };
+    }
+
+    Some(Time {
+        year: 2000 + decode(raw.year) as u16,
+        month: decode(raw.month),
+        day: decode(raw.day),
+        hour,
+        minute: decode(raw.minute),
+        second: decode(raw.second),
+        ..Time::default()
+    })
+}
+
+/// Lê a hora do RTC do hardware.
+pub fn read_time() -> Option<Time> {
+    read_time_with(&mut NativePortIo)
+}
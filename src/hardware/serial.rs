@@ -9,6 +9,68 @@ use crate::arch::io::Port; // Usa a abstração do módulo arch
 
 const COM1_BASE: u16 = 0x3F8;
 
+/// Bases das portas COM1..COM4, na ordem de preferência da detecção.
+pub const COM_PORTS: [u16; 4] = [0x3F8, 0x2F8, 0x3E8, 0x2E8];
+
+/// Offset do Scratch Register (sem função no UART, só guarda o último byte).
+const SCRATCH_OFFSET: u16 = 7;
+
+/// Padrões gravados no scratch register; uma porta ausente lê 0xFF.
+const SCRATCH_PATTERNS: [u8; 2] = [0x5A, 0xA5];
+
+/// Acesso a portas de I/O de 8 bits.
+///
+/// Permite testar a detecção no host com um barramento simulado.
+pub trait PortIo {
+    /// Lê um byte de `port`.
+    ///
+    /// # Safety
+    /// Acessa I/O de hardware na implementação nativa.
+    unsafe fn read(&mut self, port: u16) -> u8;
+
+    /// Escreve `value` em `port`.
+    ///
+    /// # Safety
+    /// Acessa I/O de hardware na implementação nativa.
+    unsafe fn write(&mut self, port: u16, value: u8);
+}
+
+/// Port I/O real via instruções `in`/`out`.
+pub struct NativePortIo;
+
+impl PortIo for NativePortIo {
+    unsafe fn read(&mut self, port: u16) -> u8 {
+        Port::<u8>::new(port).read()
+    }
+
+    unsafe fn write(&mut self, port: u16, value: u8) {
+        Port::<u8>::new(port).write(value)
+    }
+}
+
+/// Se há um UART em `base`: o scratch register devolve o que foi escrito.
+pub fn port_responds<P: PortIo>(io: &mut P, base: u16) -> bool {
+    SCRATCH_PATTERNS.iter().all(|&pattern| unsafe {
+        io.write(base + SCRATCH_OFFSET, pattern);
+        io.read(base + SCRATCH_OFFSET) == pattern
+    })
+}
+
+/// Retorna a primeira porta de `COM_PORTS` cujo scratch register devolve o
+/// que foi escrito, ou COM1 se nenhuma responder.
+pub fn detect_port_with<P: PortIo>(io: &mut P) -> u16 {
+    COM_PORTS
+        .iter()
+        .copied()
+        .find(|&base| port_responds(io, base))
+        .unwrap_or(COM1_BASE)
+}
+
+/// Detecta a porta serial presente no hardware (COM1..COM4).
+pub fn detect_port() -> u16 {
+    detect_port_with(&mut NativePortIo)
+}
+
 pub struct SerialPort {
     data:       Port<u8>,
     int_en:     Port<u8>,
@@ -21,13 +83,18 @@ pub struct SerialPort {
 impl SerialPort {
     /// Cria uma interface para a porta COM1 padrão.
     pub const fn new() -> Self {
+        Self::with_base(COM1_BASE)
+    }
+
+    /// Cria uma interface para o UART em `base` (ver `detect_port`).
+    pub const fn with_base(base: u16) -> Self {
         Self {
-            data:       Port::new(COM1_BASE),
-            int_en:     Port::new(COM1_BASE + 1),
-            fifo_ctrl:  Port::new(COM1_BASE + 2),
-            line_ctrl:  Port::new(COM1_BASE + 3),
-            modem_ctrl: Port::new(COM1_BASE + 4),
-            line_sts:   Port::new(COM1_BASE + 5),
+            data:       Port::new(base),
+            int_en:     Port::new(base + 1),
+            fifo_ctrl:  Port::new(base + 2),
+            line_ctrl:  Port::new(base + 3),
+            modem_ctrl: Port::new(base + 4),
+            line_sts:   Port::new(base + 5),
         }
     }
 
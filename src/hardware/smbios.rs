@@ -0,0 +1,37 @@
+//! Descoberta do SMBIOS
+//!
+//! Assim como o RSDP do ACPI, o ponto de entrada do SMBIOS é publicado pelo
+//! firmware na Tabela de Configuração. Há duas variantes: a de 64 bits
+//! (SMBIOS 3.0, âncora `_SM3_`) e a legada de 32 bits (âncora `_SM_`).
+
+use crate::uefi::table::{
+    config::{SMBIOS3_TABLE_GUID, SMBIOS_TABLE_GUID},
+    system::SystemTable,
+};
+
+/// Ponto de entrada do SMBIOS encontrado na Tabela de Configuração.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmbiosEntryPoint {
+    /// Estrutura de 64 bits (SMBIOS 3.0+).
+    V3(u64),
+    /// Estrutura de 32 bits (SMBIOS 2.x).
+    V2(u64),
+}
+
+impl SmbiosEntryPoint {
+    /// Endereço físico da estrutura de entrada.
+    pub fn address(&self) -> u64 {
+        match *self {
+            SmbiosEntryPoint::V3(addr) | SmbiosEntryPoint::V2(addr) => addr,
+        }
+    }
+}
+
+/// Localiza o ponto de entrada do SMBIOS em `st`, preferindo o de 64 bits.
+pub fn find_entry_point(st: &SystemTable) -> Option<SmbiosEntryPoint> {
+    if let Some(addr) = st.find_config_table(&SMBIOS3_TABLE_GUID) {
+        return Some(SmbiosEntryPoint::V3(addr as u64));
+    }
+    st.find_config_table(&SMBIOS_TABLE_GUID)
+        .map(|addr| SmbiosEntryPoint::V2(addr as u64))
+}
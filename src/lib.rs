@@ -36,7 +36,8 @@
 //! - [ ] **TODO: (Refactor)** Separar `uefi` em uma crate externa ou usar
 //!   `uefi-rs` puro (upstream).
 //!   - *Motivo:* Manter bindings UEFI manuais é propenso a erro e redundante.
-//! - [ ] **TODO: (Test)** Criar target de teste em QEMU/OVMF automatizado.
+//! - [x] **TODO: (Test)** Criar target de teste em QEMU/OVMF automatizado
+//!   (`tests/qemu`).
 
 #![no_std]
 // Habilita recursos experimentais necessários para certas operações de baixo nível
@@ -92,11 +93,16 @@ pub use crate::core::{
 
 // Permite que a biblioteca forneça o alocador se o binário não quiser
 // implementar o seu próprio. No nosso caso, o main.rs geralmente define o seu,
-// mas deixamos isso preparado para testes.
-#[cfg(feature = "lib_allocator")]
+// mas deixamos isso preparado para testes. `freelist_allocator` troca o bump
+// pelo alocador de lista livre.
+#[cfg(all(feature = "lib_allocator", not(feature = "freelist_allocator")))]
 #[global_allocator]
 static ALLOCATOR: memory::BumpAllocator = memory::BumpAllocator::new();
 
+#[cfg(all(feature = "lib_allocator", feature = "freelist_allocator"))]
+#[global_allocator]
+static ALLOCATOR: memory::FreeListAllocator = memory::FreeListAllocator::new();
+
 // ============================================================================
 // Helpers Globais
 // ============================================================================
@@ -76,18 +76,27 @@ extern crate alloc;
 
 // Imports da biblioteca Ignite
 use ignite::{
-    config::{loader::load_configuration, BootConfig, Protocol},
+    config::{
+        loader::load_configuration, path::resolve_config_device, BootConfig, FsTarget,
+        MemoryMapDump, Protocol,
+    },
     core::{
         handoff::FramebufferInfo as HandoffFbInfo, // Alias para evitar colisão
         logging,
     },
     fs::{FileSystem, UefiFileSystem},
-    memory::{BumpAllocator, PageTableManager, UefiFrameAllocator},
-    protos::load_any,
-    recovery::Diagnostics,
+    memory::{
+        exit::{exit_boot_services_or_reset, FirmwareExit},
+        PageTableManager, UefiFrameAllocator,
+    },
+    protos::{load_any, redstone::RedstoneOptions, resolve_protocol},
+    recovery::{selftest, Diagnostics, FailureAction, LastGoodFallback, RecoveryManager},
     security::{validate_and_measure, SecurityPolicy},
     uefi::{self, Handle, SystemTable},
-    ui::Menu,
+    ui::{
+        console::FramebufferConsole, graphics::GraphicsContext, input::InputManager, Menu,
+        MenuAction, Theme,
+    },
     video,
 };
 
@@ -95,9 +104,15 @@ use ignite::{
 // Alocador Global
 // ============================================================================
 
-// Define o alocador de memória para este binário.
+// Define o alocador de memória para este binário: bump por padrão, lista
+// livre com a feature `freelist_allocator`.
+#[cfg(not(feature = "freelist_allocator"))]
 #[global_allocator]
-static ALLOCATOR: BumpAllocator = BumpAllocator::new();
+static ALLOCATOR: ignite::memory::BumpAllocator = ignite::memory::BumpAllocator::new();
+
+#[cfg(feature = "freelist_allocator")]
+#[global_allocator]
+static ALLOCATOR: ignite::memory::FreeListAllocator = ignite::memory::FreeListAllocator::new();
 
 // ============================================================================
 // Ponto de Entrada UEFI
@@ -115,16 +130,23 @@ pub extern "efiapi" fn efi_main(image_handle: Handle, system_table: *mut SystemT
     ignite::println!("Ignite Bootloader Iniciando...");
 
     // 2. Inicializar Heap Global
-    unsafe {
-        let heap_size = ignite::core::config::memory::BOOTLOADER_HEAP_SIZE;
-        let heap_start = uefi::system_table()
-            .boot_services()
-            .allocate_pool(uefi::table::boot::MemoryType::LoaderData, heap_size)
-            .expect("[FAIL] Nao foi possivel alocar Heap inicial");
-
-        ALLOCATOR.init(heap_start as usize, heap_size);
-    }
-    ignite::println!("[OK] Heap inicializada.");
+    // Firmwares com pouca memória livre podem recusar o tamanho padrão; nesse
+    // caso tentamos tamanhos menores antes de desistir.
+    let heap_size = unsafe {
+        let bs = uefi::system_table().boot_services();
+        ALLOCATOR
+            .init_with_fallback(
+                ignite::core::config::memory::BOOTLOADER_HEAP_SIZE,
+                ignite::core::config::memory::BOOTLOADER_HEAP_MIN_SIZE,
+                |size| {
+                    bs.allocate_pool(uefi::table::boot::MemoryType::LoaderData, size)
+                        .ok()
+                        .map(|ptr| ptr as usize)
+                },
+            )
+            .expect("[FAIL] Nao foi possivel alocar Heap inicial")
+    };
+    ignite::println!("[OK] Heap inicializada ({} KiB).", heap_size / 1024);
 
     // 3. Configurar Sistema de Arquivos de Boot (ESP)
     let bs = uefi::system_table().boot_services();
@@ -143,23 +165,19 @@ pub extern "efiapi" fn efi_main(image_handle: Handle, system_table: *mut SystemT
         unsafe { &*(loaded_image_ptr as *mut uefi::proto::loaded_image::LoadedImageProtocol) };
     let device_handle = loaded_image.device_handle;
 
-    let fs_proto_ptr = bs
-        .open_protocol(
-            device_handle,
-            &uefi::proto::media::fs::SIMPLE_FILE_SYSTEM_PROTOCOL_GUID,
-            image_handle,
-            Handle::null(),
-            uefi::table::boot::OPEN_PROTOCOL_GET_PROTOCOL,
-        )
-        .expect("[FAIL] Falha ao abrir SimpleFileSystem");
+    // Retenta o dispositivo de boot e, se preciso, usa outro ESP
+    let (_, fs_proto_ptr) = ignite::fs::uefi::open_boot_volume(
+        &mut ignite::fs::uefi::FirmwareVolumes::new(image_handle),
+        device_handle,
+    )
+    .expect("[FAIL] Nenhum SimpleFileSystem utilizavel");
 
-    let fs_proto_ref =
-        unsafe { &mut *(fs_proto_ptr as *mut uefi::proto::media::fs::SimpleFileSystemProtocol) };
+    let fs_proto_ref = unsafe { &mut *fs_proto_ptr };
     let mut boot_fs = UefiFileSystem::new(fs_proto_ref);
 
     // 4. Carregar Configuração
     // Tenta ler do disco. Se falhar ou retornar config vazia, força Rescue.
-    let mut config = match load_configuration(&mut boot_fs) {
+    let mut config = match load_configuration(FsTarget::Esp(&mut boot_fs)) {
         Ok(cfg) => cfg,
         Err(e) => {
             ignite::println!(
@@ -170,6 +188,30 @@ pub extern "efiapi" fn efi_main(image_handle: Handle, system_table: *mut SystemT
         },
     };
 
+    // `config_device` redireciona a leitura para outro sistema de arquivos
+    // (ex.: `root()`, com o arquivo real no pool RedstoneFS)
+    if let Some(device) = config.config_device.clone() {
+        // Nenhum driver monta o pool RedstoneFS ainda: `root()` é recusado
+        // com erro (e a config do ESP segue valendo) em vez de ignorado.
+        let root_fs: Option<&mut dyn FileSystem> = None;
+        match resolve_config_device(&device, &mut boot_fs, root_fs) {
+            Ok(FsTarget::Esp(_)) => {},
+            Ok(target) => match load_configuration(target) {
+                Ok(cfg) => config = cfg,
+                Err(e) => ignite::println!(
+                    "[FAIL] Config de '{}' invalida: {}. Usando a do ESP.",
+                    device,
+                    e
+                ),
+            },
+            Err(e) => ignite::println!(
+                "[FAIL] config_device '{}' recusado: {}. Usando a config do ESP.",
+                device,
+                e
+            ),
+        }
+    }
+
     // REDE DE SEGURANÇA: Se a config carregada não tiver entradas (ex: arquivo
     // vazio ou parser falhou silenciosamente), força o modo de recuperação para
     // evitar pânico na UI.
@@ -180,261 +222,413 @@ pub extern "efiapi" fn efi_main(image_handle: Handle, system_table: *mut SystemT
         config = BootConfig::recovery();
     }
 
+    // Logs na serial apenas se `console` incluir a serial
+    ignite::core::logging::set_serial_sink(config.console.serial());
+    ignite::core::logging::set_log_format(config.effective_log_format());
+    ignite::panic::set_panic_reboot(config.panic_reboot);
+    ignite::panic::set_panic_beep(config.beep_on_error);
+    if config.dump_config {
+        config.dump();
+    }
+
     // 5. Configurar Vídeo (GOP)
-    let (_gop, fb_info) = video::init_video(bs).expect("[FAIL] Nao foi possivel iniciar Video GOP");
+    let (_gop, mut fb_info) = video::init_video(bs, config.resolution)
+        .expect("[FAIL] Nao foi possivel iniciar Video GOP");
 
     // Preparar estrutura de Handoff para o Kernel (e UI)
-    let handoff_fb_info = HandoffFbInfo {
-        addr:   fb_info.addr,
-        size:   fb_info.size as u64,
-        width:  fb_info.width,
-        height: fb_info.height,
-        stride: fb_info.stride,
-        format: match fb_info.format {
-            ignite::video::PixelFormat::RgbReserved8Bit => ignite::core::handoff::PixelFormat::Rgb,
-            ignite::video::PixelFormat::BgrReserved8Bit => ignite::core::handoff::PixelFormat::Bgr,
-            ignite::video::PixelFormat::Bitmask => ignite::core::handoff::PixelFormat::Bitmask,
-            ignite::video::PixelFormat::BltOnly => ignite::core::handoff::PixelFormat::BltOnly,
-        },
-    };
+    let mut handoff_fb_info: HandoffFbInfo = fb_info.into();
+    if config.console.video() {
+        attach_log_console(handoff_fb_info);
+    }
 
     // 6. Interface de Usuário (Menu Gráfico)
-    let selected_entry = if !config.quiet && config.timeout.unwrap_or(0) > 0 {
-        let fb_ptr = fb_info.addr;
-        let mut menu = Menu::new(&config);
-        // Reuse handoff_fb_info (Copy trait required or clone)
-        // HandoffFbInfo derives Copy/Clone
-        unsafe { menu.run(fb_ptr, handoff_fb_info) }
-    } else {
-        // Fallback seguro se o índice padrão for inválido
-        if config.default_entry_idx >= config.entries.len() {
-            &config.entries[0]
-        } else {
-            &config.entries[config.default_entry_idx]
+    // Um pedido de boot-once pula o menu, inclusive para entradas ocultas
+    let mut boot_once = ignite::recovery::state::take_boot_once().and_then(|name| {
+        let idx = config.find_entry(&name);
+        if idx.is_none() {
+            ignite::println!("[WARN] Boot-once: entrada '{}' nao existe.", name);
         }
-    };
-
-    ignite::println!("Bootando: {}", selected_entry.name);
-
-    // 7. Diagnóstico
-    let health = Diagnostics::check_entry(&mut boot_fs, selected_entry);
-    if let ignite::recovery::diagnostics::HealthStatus::Critical(msg) = health {
-        panic!(
-            "Diagnostico falhou para entrada '{}': {}",
-            selected_entry.name, msg
-        );
-    }
-
-    // 8. Carregar Kernel (Alocação UEFI Direta - Padrão Industrial)
-    // ----------------------------------------------------------------
-    // Ao invés de usar Vec<u8> no heap do bootloader (limitado a 4MB),
-    // alocamos diretamente via UEFI allocate_pool. Isso permite carregar
-    // kernels de qualquer tamanho sem desperdício de RAM.
-
-    let mut root_dir = boot_fs.root().expect("[FAIL] Falha raiz FS");
-    let mut kernel_file = root_dir
-        .open_file(&selected_entry.path)
-        .expect("[FAIL] Kernel nao encontrado no disco");
-
-    // 8.1: Obter tamanho exato do kernel via metadata
-    let kernel_metadata = kernel_file
-        .metadata()
-        .expect("Falha ao obter metadata do kernel");
-    let kernel_size = kernel_metadata.size as usize;
-
-    ignite::println!(
-        "Tamanho do kernel: {} bytes ({} MB)",
-        kernel_size,
-        kernel_size / (1024 * 1024)
-    );
-
-    // 8.2: Validar tamanho (proteção contra kernels malformados ou muito grandes)
-    if kernel_size == 0 {
-        panic!("[FAIL] Kernel tem tamanho zero! Arquivo corrompido?");
-    }
-    if kernel_size > ignite::core::config::limits::MAX_KERNEL_SIZE {
-        panic!(
-            "[FAIL] Kernel muito grande: {} bytes (max: {} bytes)",
-            kernel_size,
-            ignite::core::config::limits::MAX_KERNEL_SIZE
-        );
-    }
-
-    // 8.3: Alocar memória UEFI diretamente (LoaderData - será passada ao kernel via
-    // memory map)
-    let kernel_buffer_ptr = bs
-        .allocate_pool(uefi::table::boot::MemoryType::LoaderData, kernel_size)
-        .expect("[FAIL] Nao foi possivel alocar memoria UEFI para o kernel");
-
-    ignite::println!(
-        "[OK] Buffer UEFI alocado em: 0x{:X}",
-        kernel_buffer_ptr as u64
-    );
-
-    // 8.4: Criar slice Rust do buffer UEFI (unsafe: confiamos que UEFI alocou
-    // corretamente)
-    let kernel_data: &mut [u8] =
-        unsafe { core::slice::from_raw_parts_mut(kernel_buffer_ptr as *mut u8, kernel_size) };
-
-    // 8.5: Ler kernel diretamente para o buffer (sem alocações intermediárias)
-    ignite::fs::read_exact(kernel_file.as_mut(), kernel_data)
-        .expect("[FAIL] Erro de I/O ao ler Kernel para buffer UEFI");
-
-    // 8.6: Carregar Módulos (InitRD, Drivers)
-    let mut loaded_modules = alloc::vec::Vec::new();
-    for module_cfg in &selected_entry.modules {
-        ignite::println!("Carregando modulo: {}", module_cfg.path);
-
-        let mut module_file = root_dir
-            .open_file(&module_cfg.path)
-            .expect("[FAIL] Modulo nao encontrado no disco");
-
-        let mod_meta = module_file
-            .metadata()
-            .expect("[FAIL] Falha ao obter metadata do modulo");
-        let mod_size = mod_meta.size as usize;
+        idx
+    });
+
+    // Uma entrada recusada durante a carga (ex.: módulo grande demais) volta
+    // ao menu; a partir daí o menu é sempre exibido.
+    let mut force_menu = false;
+    // Se a entrada falhar ao carregar, a última válida é tentada uma vez
+    let mut last_good_fallback = LastGoodFallback::new();
+    let mut retry_entry = None;
+    let (selected_entry, prepared) = loop {
+        let selected_entry = if let Some(idx) = retry_entry.take() {
+            &config.entries[idx]
+        } else if let Some(idx) = boot_once.take() {
+            ignite::println!("Boot-once: {}", config.entries[idx].name);
+            &config.entries[idx]
+        } else if force_menu || (!config.quiet && config.timeout.shows_menu()) {
+            let fb_ptr = fb_info.addr;
+            let mut menu = Menu::new(&config);
+            if let Some(font_path) = &config.font_path {
+                match load_extra_font(&mut boot_fs, font_path) {
+                    Ok(font) => menu = menu.with_font(ignite::ui::font::BitFont::with_extra(font)),
+                    Err(e) => ignite::println!("[WARN] Fonte '{}' ignorada: {:?}", font_path, e),
+                }
+            }
+            if config.menu_health {
+                let state = ignite::recovery::state::PersistentState::load();
+                let scores = config
+                    .entries
+                    .iter()
+                    .enumerate()
+                    .map(|(i, entry)| {
+                        Diagnostics::score_entry(&mut boot_fs, entry, state.failures_for(i))
+                    })
+                    .collect();
+                menu = menu.with_health(scores);
+            }
+            // De volta após uma falha: espera a escolha do usuário
+            if force_menu {
+                menu = menu.without_countdown();
+            }
+            if config.beep_on_menu {
+                let (freq, ms) = ignite::arch::x86::speaker::MENU_BEEP;
+                ignite::arch::x86::speaker::beep(freq, ms);
+            }
+            // Os logs saem do framebuffer enquanto o menu está na tela
+            detach_log_console();
+            // Reuse handoff_fb_info (Copy trait required or clone)
+            // HandoffFbInfo derives Copy/Clone
+            let entry = loop {
+                match unsafe { menu.run(fb_ptr, handoff_fb_info) } {
+                    MenuAction::Boot(entry) => break entry,
+                    MenuAction::Halt => {
+                        ignite::println!("Timeout do menu: parando o sistema.");
+                        loop {
+                            ignite::arch::hlt();
+                        }
+                    },
+                    MenuAction::Reboot => {
+                        ignite::println!("Timeout do menu: reiniciando.");
+                        let rt = uefi::system_table().runtime_services();
+                        rt.reset_system(
+                            uefi::table::runtime::ResetType::Cold,
+                            uefi::Status::SUCCESS,
+                        );
+                    },
+                    MenuAction::SelfTest => {
+                        let mut os = selftest::FirmwareSelfTest::new(bs, &mut boot_fs);
+                        let report = selftest::run(&mut os);
+                        let (title, details) = (report.summary(), report.details());
+                        for line in details.lines() {
+                            ignite::println!("{}", line);
+                        }
+                        ignite::println!("{}.", title);
+
+                        // O relatório fica na tela até uma tecla; o menu se
+                        // redesenha na volta do laço
+                        if config.console.video() && handoff_fb_info.format.is_linear() {
+                            let theme = Theme::from_config(&config);
+                            let accent = if report.passed() {
+                                theme.highlight
+                            } else {
+                                theme.error
+                            };
+                            // SAFETY: `handoff_fb_info` descreve o framebuffer
+                            // do modo GOP atual.
+                            let mut gfx = unsafe {
+                                GraphicsContext::new(handoff_fb_info.addr, handoff_fb_info)
+                            };
+                            ignite::recovery::screen::show_card(
+                                &mut gfx,
+                                &theme,
+                                accent,
+                                &title,
+                                &details,
+                                ignite::recovery::screen::MENU_HINT,
+                            );
+                            InputManager::new().wait_for_key();
+                        }
+                    },
+                }
+            };
+            if config.console.video() {
+                attach_log_console(handoff_fb_info);
+            }
+            entry
+        } else {
+            // Boot automático: com `recovery_max_attempts` ativo, após essas
+            // falhas seguidas (ou com `R` pressionado) vai a entrada de
+            // recuperação
+            RecoveryManager::new(&config).select_entry(&config)
+        };
 
-        ignite::println!("Tamanho: {} bytes ({} KB)", mod_size, mod_size / 1024);
+        ignite::println!("Bootando: {}", selected_entry.name);
 
-        if mod_size == 0 {
-            ignite::println!("AVISO: Modulo vazio ignorado.");
+        // 7. Diagnóstico: kernel ausente (ou outra falha fatal) também tenta
+        // a última entrada válida antes de voltar ao menu
+        let health = Diagnostics::check_entry(&mut boot_fs, selected_entry);
+        if health.is_fatal(config.strict_diagnostics) {
+            let msg = health.message().unwrap_or("");
+            ignite::println!(
+                "[FAIL] Diagnostico falhou para entrada '{}': {}",
+                selected_entry.name,
+                msg
+            );
+            if let Some(idx) = last_good_retry(&mut last_good_fallback, &config, selected_entry) {
+                retry_entry = Some(idx);
+                continue;
+            }
+            if config.console.video() {
+                let title = alloc::format!("Diagnostico falhou para '{}'", selected_entry.name);
+                show_error_and_wait(handoff_fb_info, &Theme::from_config(&config), &title, msg);
+            }
+            force_menu = true;
             continue;
         }
+        if let ignite::recovery::diagnostics::HealthStatus::Warning(msg) = health {
+            ignite::println!("[WARN] Diagnostico: {} (continuando)", msg);
+            if config.console.video() && !config.quiet {
+                let title = alloc::format!("Aviso em '{}':", selected_entry.name);
+                unsafe {
+                    ignite::ui::menu::show_warning(fb_info.addr, handoff_fb_info, &title, msg)
+                };
+            }
+        }
 
-        let mod_buffer_ptr = bs
-            .allocate_pool(uefi::table::boot::MemoryType::LoaderData, mod_size)
-            .expect("[FAIL] OOM ao alocar memoria para modulo");
-
-        let mod_data: &mut [u8] =
-            unsafe { core::slice::from_raw_parts_mut(mod_buffer_ptr as *mut u8, mod_size) };
-
-        ignite::fs::read_exact(module_file.as_mut(), mod_data)
-            .expect("[FAIL] Erro de I/O ao ler modulo");
+        // 8. Carregar Kernel (Alocação UEFI Direta - Padrão Industrial)
+        // ----------------------------------------------------------------
+        // Ao invés de usar Vec<u8> no heap do bootloader (limitado a 4MB),
+        // alocamos diretamente via UEFI allocate_pool. Isso permite carregar
+        // kernels de qualquer tamanho sem desperdício de RAM.
+
+        // Entradas com `iso_path` leem kernel e módulos do ESP embutido na
+        // imagem; com `http_base`, do servidor HTTP (ou do disco, se o
+        // firmware não tiver pilha HTTP)
+        let (http_root, http_session) = selected_entry
+            .http_base
+            .as_deref()
+            .and_then(|base| open_http_root(image_handle, base))
+            .unzip();
+        let root_dir = match (&selected_entry.iso_path, http_root) {
+            (_, Some(dir)) => Ok(dir),
+            (Some(iso_path), None) => ignite::fs::iso::open_iso_root(&mut boot_fs, iso_path),
+            (None, None) => boot_fs.root(),
+        };
 
-        loaded_modules.push(ignite::core::types::LoadedFile {
-            ptr:  mod_buffer_ptr as u64,
-            size: mod_size,
+        // 8.1: Ler o kernel para um buffer UEFI (LoaderData - será passado ao
+        // kernel via memory map). Raiz ou imagem ISO inacessível e kernel
+        // ausente, vazio ou acima de `MAX_KERNEL_SIZE` são recusados antes de
+        // alocar e, como as falhas abaixo, voltam ao menu.
+        let kernel = root_dir.and_then(|mut root_dir| {
+            root_dir
+                .open_file(&selected_entry.path)
+                .and_then(|mut kernel_file| ignite::fs::loader::load_kernel(kernel_file.as_mut()))
+                .map(|kernel| (kernel, root_dir))
         });
 
-        ignite::println!("[OK] Modulo carregado em: 0x{:X}", mod_buffer_ptr as u64);
-    }
-
-    // 9. Segurança
-    let policy = SecurityPolicy::new(&config);
-    if let Err(e) = validate_and_measure(&kernel_data, &selected_entry.name, &policy) {
-        panic!("[FAIL] Violacao de Seguranca detectada: {:?}", e);
-    }
-    // TODO: Validar módulos também
-
-    // 10. Executar Protocolo de Boot
-    // RAMIFICAÇÃO: Chainload vs Kernel Nativo
+        // 8.2: Conferir o CRC32 do kernel e carregar os módulos (InitRD,
+        // Drivers). Em caso de falha o buffer do kernel já foi liberado.
+        let loaded = kernel.and_then(|(kernel, mut root_dir)| {
+            ignite::println!(
+                "[OK] Kernel lido: {} bytes ({} MB) em 0x{:X}",
+                kernel.size,
+                kernel.size / (1024 * 1024),
+                kernel.ptr
+            );
+            let kernel_data =
+                unsafe { core::slice::from_raw_parts(kernel.ptr as *const u8, kernel.size) };
+            let modules =
+                ignite::fs::loader::verify_crc32(kernel_data, selected_entry.kernel_crc32)
+                    .and_then(|_| {
+                        load_modules(
+                            bs,
+                            root_dir.as_mut(),
+                            selected_entry,
+                            config.strict_diagnostics,
+                        )
+                    });
+            if modules.is_err() {
+                let _ = bs.free_pool(kernel.ptr as *mut u8);
+            }
+            modules.map(|modules| (kernel, modules))
+        });
 
-    if selected_entry.protocol == Protocol::EfiChainload {
-        ignite::println!("Executando EFI Chainload...");
+        // Kernel e módulos já estão na memória (ou a entrada falhou): a
+        // instância HTTP não é mais usada, nem numa volta ao menu
+        if let Some(http) = http_session {
+            // SAFETY: `root_dir` e os arquivos abertos nele já foram
+            // descartados.
+            unsafe { http.close() };
+        }
 
-        let mut child_handle = Handle::null();
+        // 9. Segurança e 10. Protocolo de Boot: o `protocol:` da entrada
+        // prevalece; sem ele, detecta pelo binário
+        let loaded = loaded.and_then(|(kernel, modules)| {
+            let kernel_data =
+                unsafe { core::slice::from_raw_parts(kernel.ptr as *const u8, kernel.size) };
+            let policy = SecurityPolicy::new(&config);
+            // TODO: Validar módulos também
+            let protocol = validate_and_measure(kernel_data, &selected_entry.name, &policy)
+                .and_then(|_| {
+                    resolve_protocol(
+                        selected_entry.protocol,
+                        kernel_data,
+                        config.protocol_auto_detect,
+                    )
+                });
+            if protocol.is_err() {
+                free_loaded(bs, &kernel, &modules);
+            }
+            protocol.map(|protocol| (kernel, modules, protocol))
+        });
 
-        // LoadImage espera SourceBuffer se BootPolicy=FALSE(0)
-        let status = unsafe {
-            (bs.load_image_f)(
-                0, // Boot from Memory
-                image_handle,
-                core::ptr::null_mut(),
-                kernel_data.as_ptr() as *mut core::ffi::c_void,
-                kernel_data.len(),
-                &mut child_handle,
-            )
+        let error = match loaded {
+            Ok((kernel, modules, protocol)) => {
+                let kernel_buffer_ptr = kernel.ptr as *mut u8;
+                let kernel_data: &mut [u8] =
+                    unsafe { core::slice::from_raw_parts_mut(kernel_buffer_ptr, kernel.size) };
+
+                // ESP com imagens de várias arquiteturas: um kernel que esta
+                // CPU não executa volta ao menu com o motivo na tela
+                if config.verify_kernel_arch {
+                    if let Err(e) = ignite::protos::arch::check_kernel_arch(protocol, kernel_data) {
+                        ignite::println!("[FAIL] Entrada '{}': {}", selected_entry.name, e);
+                        if config.console.video() {
+                            let title = alloc::format!(
+                                "Arquitetura incompativel em '{}'",
+                                selected_entry.name
+                            );
+                            show_error_and_wait(
+                                handoff_fb_info,
+                                &Theme::from_config(&config),
+                                &title,
+                                &alloc::format!("{}", e),
+                            );
+                        }
+                        free_loaded(bs, &kernel, &modules);
+                        force_menu = true;
+                        continue;
+                    }
+                }
+
+                // RAMIFICAÇÃO: Chainload vs Kernel Nativo
+                if protocol == Protocol::EfiChainload {
+                    // Só retorna se a aplicação EFI falhar: volta ao menu
+                    let status = run_chainload(bs, image_handle, kernel_data, modules.first());
+                    free_loaded(bs, &kernel, &modules);
+                    ignite::println!("[FAIL] Aplicacao EFI retornou erro: {:?}", status);
+
+                    // A aplicação pode ter deixado o GOP num modo inutilizável
+                    // O console de log é recriado no novo modo após o menu
+                    match video::gop::reset_to_safe_mode() {
+                        Ok(info) => {
+                            fb_info = info;
+                            handoff_fb_info = info.into();
+                        },
+                        Err(e) => ignite::println!("[WARN] Falha ao restaurar o video: {:?}", e),
+                    }
+                    force_menu = true;
+                    continue;
+                }
+
+                let prepared = prepare_kernel(
+                    bs,
+                    &config,
+                    selected_entry,
+                    protocol,
+                    kernel_data,
+                    modules.clone(),
+                    handoff_fb_info,
+                );
+                match prepared {
+                    Ok(prepared) => break (selected_entry, prepared),
+                    Err(e) => {
+                        free_loaded(bs, &kernel, &modules);
+                        e
+                    },
+                }
+            },
+            Err(e) => e,
         };
 
-        if status.is_error() {
-            panic!("[FAIL] Falha ao carregar imagem EFI: {:?}", status);
-        }
-
-        // Iniciar a imagem
-        let mut exit_data_size: usize = 0;
-        let mut exit_data: *mut u16 = core::ptr::null_mut();
-
-        // Passa o controle para o aplicativo EFI (Shell)
-        let status =
-            unsafe { (bs.start_image_f)(child_handle, &mut exit_data_size, &mut exit_data) };
+        // Entrada recusada em qualquer etapa acima: tenta a última válida uma
+        // vez, senão volta ao menu
+        ignite::println!("[FAIL] Entrada '{}': {}", selected_entry.name, error);
 
-        if status.is_error() {
-            ignite::println!("[FAIL] Aplicacao EFI retornou erro: {:?}", status);
-            // Se falhar, voltamos ao menu ou paramos
-            loop {
-                core::hint::spin_loop();
-            }
-        } else {
-            // Se o app retornar (ex: usuário digitou 'exit' no shell), reinicia.
-            ignite::println!("App finalizado. Reiniciando sistema...");
-            let rt = uefi::system_table().runtime_services();
-            rt.reset_system(uefi::table::runtime::ResetType::Cold, uefi::Status::SUCCESS);
+        if let Some(idx) = last_good_retry(&mut last_good_fallback, &config, selected_entry) {
+            retry_entry = Some(idx);
+            continue;
         }
-    }
-
-    // --- CAMINHO KERNEL NATIVO / LINUX ---
-
-    let mut frame_allocator = UefiFrameAllocator::new(bs);
-    let mut page_table =
-        PageTableManager::new(&mut frame_allocator).expect("Falha ao criar PageTables");
 
-    // CRÍTICO: Capturar Memory Map ANTES de exit_boot_services
-    // O kernel precisa saber quais regiões de memória estão disponíveis
-    let memory_map_buffer = capture_memory_map(bs);
+        if config.console.video() {
+            let title = alloc::format!("Falha ao carregar '{}':", selected_entry.name);
+            let msg = alloc::format!("{}", error);
+            unsafe { ignite::ui::menu::show_warning(fb_info.addr, handoff_fb_info, &title, &msg) };
+        }
+        force_menu = true;
+    };
+    let PreparedKernel {
+        page_table,
+        launch_info,
+        enforce_nx,
+    } = prepared;
 
-    let launch_info = load_any(
-        &mut frame_allocator,
-        &mut page_table,
-        &kernel_data,
-        selected_entry.cmdline.as_deref(),
-        loaded_modules,
-        memory_map_buffer,     // Passa o memory map
-        Some(handoff_fb_info), // Passa Framebuffer Info
-    )
-    .expect("[FAIL] Falha ao preparar Kernel (Protocol Error)");
+    // Daqui em diante o framebuffer é do kernel
+    detach_log_console();
 
     ignite::println!("Saindo dos servicos de boot UEFI...");
 
     // LIMPAR TELA: Preencher framebuffer com preto antes do salto
-    // Isso garante que qualquer desenho feito pelo kernel seja visível
-    unsafe {
-        let fb_ptr = handoff_fb_info.addr as *mut u32;
-        // stride está em PIXELS (pixels_per_scan_line), não bytes
-        let stride_pixels = handoff_fb_info.stride as usize;
-        let height = handoff_fb_info.height as usize;
-
-        // Limpar linha por linha usando stride em pixels
-        for y in 0..height {
-            let row_ptr = fb_ptr.add(y * stride_pixels);
-            for x in 0..stride_pixels {
-                row_ptr.add(x).write_volatile(0x000000); // Preto
+    // Isso garante que qualquer desenho feito pelo kernel seja visível.
+    // Com `video_test_pattern`, pintamos barras de cor no lugar para validar
+    // os parâmetros de framebuffer entregues ao kernel.
+    if config.video_test_pattern {
+        let mut fb = unsafe { video::Framebuffer::new(fb_info.addr, fb_info) };
+        video::draw_test_pattern(&mut fb);
+        ignite::println!("Padrao de teste desenhado.");
+    } else {
+        unsafe {
+            let fb_ptr = handoff_fb_info.addr as *mut u32;
+            // stride está em PIXELS (pixels_per_scan_line), não bytes
+            let stride_pixels = handoff_fb_info.stride as usize;
+            let height = handoff_fb_info.height as usize;
+
+            // Limpar linha por linha usando stride em pixels
+            for y in 0..height {
+                let row_ptr = fb_ptr.add(y * stride_pixels);
+                for x in 0..stride_pixels {
+                    row_ptr.add(x).write_volatile(0x000000); // Preto
+                }
             }
         }
+
+        ignite::println!("Tela limpa.");
     }
 
-    ignite::println!("Tela limpa.");
+    // Persistir a entrada como "última boa" enquanto ainda temos Boot Services.
+    // Depois do salto não há retorno para confirmar o sucesso do handoff.
+    if let Err(e) = ignite::recovery::state::set_last_good(&selected_entry.name) {
+        ignite::println!("AVISO: Falha ao gravar ultima entrada valida: {:?}", e);
+    }
 
     // 11. Exit Boot Services
-    let (map_key, _iter) = get_memory_map_key(bs);
-    if bs
-        .exit_boot_services(image_handle, map_key)
-        .to_result()
-        .is_err()
-    {
-        let (retry_key, _) = get_memory_map_key(bs);
-        if bs
-            .exit_boot_services(image_handle, retry_key)
-            .to_result()
-            .is_err()
-        {
-            loop {
-                core::hint::spin_loop();
-            }
+    // Se falhar, o diagnóstico vai para a serial e a máquina é reiniciada
+    let mut boot_exit = FirmwareExit::new(bs, image_handle);
+    if !exit_boot_services_or_reset(&mut boot_exit) {
+        loop {
+            core::hint::spin_loop();
         }
     }
 
     // 12. Salto para o Kernel
     unsafe {
+        // Sem CR4.PGE o bit G das páginas do kernel seria ignorado
+        if config.global_kernel_pages {
+            ignite::arch::x86::enable_global_pages();
+        }
+        // As páginas de dados do kernel têm NX; sem EFER.NXE dariam page fault
+        if enforce_nx {
+            ignite::arch::x86::enable_nx();
+        }
+
         jump_to_kernel(
             launch_info.entry_point,
             launch_info.use_fixed_redstone_entry,
@@ -452,34 +646,357 @@ pub extern "efiapi" fn efi_main(image_handle: Handle, system_table: *mut SystemT
 // Helpers Internos
 // ============================================================================
 
-fn get_memory_map_key(
+/// Carrega e inicia a aplicação EFI em `image` (chainload).
+///
+/// Se a aplicação terminar normalmente (ex: `exit` no shell), reinicia a
+/// máquina; só retorna com o status de erro de `LoadImage` ou `StartImage`.
+///
+/// O primeiro módulo, se houver, é servido como initrd pelo `LoadFile2`
+/// (`LINUX_EFI_INITRD_MEDIA_GUID`) para kernels Linux com EFI stub. O
+/// protocolo é removido se a imagem falhar ou retornar.
+fn run_chainload(
     bs: &ignite::uefi::BootServices,
-) -> (
-    usize,
-    impl Iterator<Item = ignite::memory::region::PhysicalMemoryRegion>,
-) {
-    let mut map_size = 0;
-    let mut map_key = 0;
-    let mut descriptor_size = 0;
-    let mut descriptor_version = 0;
+    image_handle: Handle,
+    image: &[u8],
+    initrd: Option<&ignite::core::types::LoadedFile>,
+) -> uefi::Status {
+    use ignite::uefi::proto::media::InitrdMedia;
+
+    ignite::println!("Executando EFI Chainload...");
+
+    let initrd_media = initrd.and_then(|initrd| {
+        match InitrdMedia::install(bs, initrd.ptr as *const u8, initrd.size) {
+            Ok(media) => {
+                ignite::println!("[OK] Initrd exposto via LoadFile2 ({} bytes)", initrd.size);
+                Some(media)
+            },
+            Err(e) => {
+                ignite::println!("[WARN] Falha ao instalar o LoadFile2 do initrd: {:?}", e);
+                None
+            },
+        }
+    });
+    let uninstall_initrd = |media: Option<InitrdMedia>| {
+        if let Some(Err(e)) = media.map(|media| media.uninstall(bs)) {
+            ignite::println!("[WARN] Falha ao remover o LoadFile2 do initrd: {:?}", e);
+        }
+    };
 
-    let _ = unsafe {
-        (bs.get_memory_map_f)(
-            &mut map_size,
+    let mut child_handle = Handle::null();
+
+    // LoadImage espera SourceBuffer se BootPolicy=FALSE(0)
+    let status = unsafe {
+        (bs.load_image_f)(
+            0, // Boot from Memory
+            image_handle,
             core::ptr::null_mut(),
-            &mut map_key,
-            &mut descriptor_size,
-            &mut descriptor_version,
+            image.as_ptr() as *mut core::ffi::c_void,
+            image.len(),
+            &mut child_handle,
         )
     };
 
-    // Retorna o mapa de memória e um iterador vazio
-    (map_key, core::iter::empty())
+    if status.is_error() {
+        ignite::println!("[FAIL] Falha ao carregar imagem EFI: {:?}", status);
+        uninstall_initrd(initrd_media);
+        return status;
+    }
+
+    // Iniciar a imagem
+    let mut exit_data_size: usize = 0;
+    let mut exit_data: *mut u16 = core::ptr::null_mut();
+
+    // Passa o controle para o aplicativo EFI (Shell). Um kernel com EFI stub
+    // não volta daqui; o initrd só é removido se a imagem retornar.
+    let status = unsafe { (bs.start_image_f)(child_handle, &mut exit_data_size, &mut exit_data) };
+    uninstall_initrd(initrd_media);
+    if status.is_error() {
+        return status;
+    }
+
+    // Se o app retornar (ex: usuário digitou 'exit' no shell), reinicia.
+    ignite::println!("App finalizado. Reiniciando sistema...");
+    let rt = uefi::system_table().runtime_services();
+    rt.reset_system(uefi::table::runtime::ResetType::Cold, uefi::Status::SUCCESS)
+}
+
+/// Kernel nativo pronto para o salto.
+struct PreparedKernel {
+    page_table:  PageTableManager,
+    launch_info: ignite::protos::KernelLaunchInfo,
+    /// Páginas de dados do kernel mapeadas com NX (exige EFER.NXE).
+    enforce_nx:  bool,
+}
+
+/// Caminho do kernel nativo / Linux: monta as page tables, ativa as outras
+/// saídas de vídeo, captura o mapa de memória e prepara o handoff
+/// (`load_any`).
+///
+/// Uma falha aqui (ex: RAM abaixo de `min_memory`) recusa só a entrada e
+/// volta ao menu, como as falhas de carga; as page tables e o mapa de memória
+/// de uma tentativa recusada não são reaproveitados.
+fn prepare_kernel(
+    bs: &ignite::uefi::BootServices,
+    config: &BootConfig,
+    entry: &ignite::config::Entry,
+    protocol: Protocol,
+    kernel_data: &[u8],
+    modules: alloc::vec::Vec<ignite::core::types::LoadedFile>,
+    fb_info: HandoffFbInfo,
+) -> ignite::core::error::Result<PreparedKernel> {
+    let mut frame_allocator = UefiFrameAllocator::new(bs);
+    let mut page_table = PageTableManager::new(&mut frame_allocator)?;
+    page_table.set_global_kernel_pages(config.global_kernel_pages);
+
+    // NX só numa CPU que o suporte: sem EFER.NXE o bit é reservado
+    let enforce_nx = config.enforce_nx
+        && ignite::arch::x86::cpuid::detect_features()
+            .contains(ignite::arch::x86::cpuid::CpuFeatures::NX);
+    if !config.enforce_nx {
+        ignite::println!("[WARN] ============================================");
+        ignite::println!("[WARN] enforce_nx: no -> NX DESLIGADO para o kernel");
+        ignite::println!("[WARN] Segmentos de dados do kernel ficam executaveis.");
+        ignite::println!("[WARN] ============================================");
+    } else if !enforce_nx {
+        ignite::println!("[WARN] CPU sem NX: segmentos do kernel mapeados sem NX.");
+    }
+    page_table.set_enforce_nx(enforce_nx);
+
+    // Outros displays (multi-GPU) entram no BootInfo, cada um no seu melhor
+    // modo. A troca de modo vem antes do mapa de memória.
+    let extra_framebuffers: alloc::vec::Vec<HandoffFbInfo> =
+        if matches!(protocol, Protocol::Redstone | Protocol::Limine) {
+            let mut outputs = video::GopDriver::secondary_outputs(bs).unwrap_or_default();
+            video::gop::activate_best_modes(&mut outputs, fb_info.addr)
+                .into_iter()
+                .map(Into::into)
+                .collect()
+        } else {
+            alloc::vec::Vec::new()
+        };
+    if !extra_framebuffers.is_empty() {
+        ignite::println!("Video: {} display(s) extra(s)", extra_framebuffers.len());
+    }
+
+    // CRÍTICO: Capturar Memory Map ANTES de exit_boot_services
+    // O kernel precisa saber quais regiões de memória estão disponíveis
+    let (memory_map_buffer, total_usable_ram) = capture_memory_map(bs);
+    if config.dump_memory_map == MemoryMapDump::Csv {
+        dump_memory_map_csv(memory_map_buffer);
+    }
+
+    // Recusa a entrada aqui, com mensagem clara, em vez de deixar o kernel
+    // falhar por falta de RAM
+    if let Err(e) = ignite::memory::map::check_min_memory(total_usable_ram, entry.min_memory) {
+        ignite::println!(
+            "[FAIL] RAM insuficiente para '{}': {} MB utilizaveis, min_memory exige {} MB",
+            entry.name,
+            total_usable_ram / (1024 * 1024),
+            entry.min_memory.unwrap_or(0) / (1024 * 1024)
+        );
+        return Err(e);
+    }
+
+    let launch_info = load_any(
+        &mut frame_allocator,
+        &mut page_table,
+        protocol,
+        RedstoneOptions::from_entry(entry, &extra_framebuffers),
+        kernel_data,
+        entry.cmdline.as_deref(),
+        modules,
+        memory_map_buffer, // Passa o memory map
+        Some(fb_info),     // Passa Framebuffer Info
+    )?;
+
+    Ok(PreparedKernel {
+        page_table,
+        launch_info,
+        enforce_nx,
+    })
+}
+
+/// Devolve ao firmware os buffers do kernel e dos módulos de uma entrada
+/// recusada.
+fn free_loaded(
+    bs: &ignite::uefi::BootServices,
+    kernel: &ignite::core::types::LoadedFile,
+    modules: &[ignite::core::types::LoadedFile],
+) {
+    for file in core::iter::once(kernel).chain(modules) {
+        let _ = bs.free_pool(file.ptr as *mut u8);
+    }
+}
+
+/// Mostra o cartão de erro de `recovery::screen` e espera uma tecla antes de
+/// voltar ao menu.
+fn show_error_and_wait(fb: HandoffFbInfo, theme: &Theme, title: &str, msg: &str) {
+    // SAFETY: `fb` descreve o framebuffer do modo GOP atual.
+    let mut gfx = unsafe { GraphicsContext::new(fb.addr, fb) };
+    ignite::recovery::screen::show_error(
+        &mut gfx,
+        theme,
+        title,
+        msg,
+        ignite::recovery::screen::MENU_HINT,
+    );
+    InputManager::new().wait_for_key();
+}
+
+/// Console de vídeo dos logs, recriado no lugar a cada `attach_log_console`.
+static mut LOG_CONSOLE: Option<FramebufferConsole> = None;
+
+/// Conecta os logs a um console sobre o framebuffer `fb` (limpando a tela),
+/// reproduzindo as linhas registradas enquanto não havia console.
+fn attach_log_console(fb: HandoffFbInfo) {
+    // SAFETY: `fb` descreve o framebuffer do modo GOP atual.
+    let gfx = unsafe { GraphicsContext::new(fb.addr, fb) };
+    // SAFETY: nenhum registro de log em andamento; o sink antigo é
+    // desconectado antes de o console ser substituído.
+    unsafe {
+        logging::detach_video_sink();
+        let console = (*core::ptr::addr_of_mut!(LOG_CONSOLE)).insert(FramebufferConsole::new(gfx));
+        logging::attach_video_sink(console);
+    }
+}
+
+/// Tira os logs do framebuffer (menu, telas de erro ou handoff); voltam no
+/// próximo `attach_log_console`.
+fn detach_log_console() {
+    // SAFETY: nenhum registro de log em andamento.
+    unsafe { logging::detach_video_sink() };
+}
+
+/// Raiz do servidor `base` pela pilha HTTP do firmware, com a instância por
+/// trás dela (fechada com `FirmwareHttp::close` depois da carga). `None` (com
+/// aviso) se o firmware não tiver HTTP, para a entrada seguir pelo disco local.
+fn open_http_root(
+    image_handle: Handle,
+    base: &str,
+) -> Option<(
+    alloc::boxed::Box<dyn ignite::fs::Directory>,
+    ignite::fs::http::FirmwareHttp,
+)> {
+    use ignite::fs::http::{FirmwareHttp, HttpFileSystem};
+
+    let root = FirmwareHttp::open(image_handle, base).and_then(|transport| {
+        let root = HttpFileSystem::new(transport, base).and_then(|mut fs| fs.root());
+        if root.is_err() {
+            // SAFETY: nenhum diretório foi aberto sobre a instância.
+            unsafe { transport.close() };
+        }
+        root.map(|dir| (dir, transport))
+    });
+    match root {
+        Ok(root) => {
+            ignite::println!("[OK] Boot por HTTP: {}", base);
+            Some(root)
+        },
+        Err(e) => {
+            ignite::println!("[WARN] HTTP indisponivel ({:?}); usando o disco local", e);
+            None
+        },
+    }
+}
+
+/// Entrada a tentar depois da falha de `failed`: a última que chegou ao
+/// kernel, uma única vez por boot (ver `LastGoodFallback`).
+fn last_good_retry(
+    fallback: &mut LastGoodFallback,
+    config: &BootConfig,
+    failed: &ignite::config::Entry,
+) -> Option<usize> {
+    let last_good = ignite::recovery::state::get_last_good();
+    match fallback.on_failure(config, failed, last_good.as_deref()) {
+        FailureAction::RetryLastGood(idx) => {
+            ignite::println!(
+                "[WARN] Tentando a ultima entrada valida: {}",
+                config.entries[idx].name
+            );
+            Some(idx)
+        },
+        FailureAction::Recovery => None,
+    }
+}
+
+/// Carrega os módulos de `entry` a partir de `root_dir`.
+///
+/// Um módulo ausente, acima de `MAX_MODULE_SIZE`, com CRC32 divergente ou,
+/// com `strict`, de formato diferente do `module_type` interrompe a carga; os
+/// buffers já alocados para a entrada são devolvidos ao firmware.
+fn load_modules(
+    bs: &ignite::uefi::BootServices,
+    root_dir: &mut dyn ignite::fs::Directory,
+    entry: &ignite::config::Entry,
+    strict: bool,
+) -> ignite::core::error::Result<alloc::vec::Vec<ignite::core::types::LoadedFile>> {
+    let mut loaded_modules = alloc::vec::Vec::new();
+    for (index, module_cfg) in entry.modules.iter().enumerate() {
+        ignite::println!("Carregando modulo: {}", module_cfg.path);
+
+        let loaded = root_dir
+            .open_file(&module_cfg.path)
+            .and_then(|mut module_file| {
+                ignite::fs::loader::load_module(module_file.as_mut(), module_cfg.cmdline.clone())
+            });
+        // O buffer entra na lista antes da conferência para ser liberado
+        // junto com os demais se o CRC, o formato ou o tamanho divergirem.
+        // O primeiro módulo é o initrd do handoff: o tamanho entregue ao
+        // kernel é o verificado contra `initrd_size`.
+        let loaded = loaded.and_then(|module| match module {
+            Some(mut module) => {
+                loaded_modules.push(module.clone());
+                let data =
+                    unsafe { core::slice::from_raw_parts(module.ptr as *const u8, module.size) };
+                let expected_size = if index == 0 { entry.initrd_size } else { None };
+                ignite::fs::loader::verify_crc32(data, module_cfg.crc32)
+                    .and_then(|_| {
+                        ignite::fs::loader::verify_module_type(data, module_cfg.module_type, strict)
+                    })
+                    .and_then(|_| ignite::fs::loader::verify_size(module.size, expected_size))
+                    .map(|size| {
+                        module.size = size;
+                        Some(module)
+                    })
+            },
+            None => Ok(None),
+        });
+        match loaded {
+            Ok(Some(module)) => {
+                ignite::println!(
+                    "[OK] Modulo carregado em: 0x{:X} ({} KB)",
+                    module.ptr,
+                    module.size / 1024
+                );
+            },
+            Ok(None) => ignite::println!("AVISO: Modulo vazio ignorado."),
+            Err(e) => {
+                ignite::println!("[FAIL] Modulo '{}': {}", module_cfg.path, e);
+                for module in &loaded_modules {
+                    let _ = bs.free_pool(module.ptr as *mut u8);
+                }
+                return Err(e);
+            },
+        }
+    }
+    Ok(loaded_modules)
+}
+
+/// Carrega a fonte PSF de `font_path`. O buffer do arquivo e a fonte
+/// convertida vivem até o handoff (`Box::leak`).
+fn load_extra_font(
+    fs: &mut dyn FileSystem,
+    path: &str,
+) -> ignite::core::error::Result<&'static ignite::ui::psf::PsfFont> {
+    let file = ignite::fs::loader::FileLoader::new(fs).load_file(path)?;
+    let data = unsafe { core::slice::from_raw_parts(file.ptr as *const u8, file.size) };
+    let font = ignite::ui::psf::PsfFont::parse(data)?;
+    Ok(alloc::boxed::Box::leak(alloc::boxed::Box::new(font)))
 }
 
 /// Captura o Memory Map do UEFI em um buffer persistente.
-/// Retorna (ponteiro, contagem de entradas).
-fn capture_memory_map(bs: &ignite::uefi::BootServices) -> (u64, u64) {
+/// Retorna ((ponteiro, contagem de entradas), RAM utilizável em bytes).
+fn capture_memory_map(bs: &ignite::uefi::BootServices) -> ((u64, u64), u64) {
     use ignite::core::handoff::MemoryMapEntry;
 
     let mut map_size = 0;
@@ -517,7 +1034,7 @@ fn capture_memory_map(bs: &ignite::uefi::BootServices) -> (u64, u64) {
 
     if status.is_error() {
         ignite::println!("[FAIL] Falha ao capturar memory map!");
-        return (0, 0);
+        return ((0, 0), 0);
     }
 
     // 4. Converter entradas UEFI para formato do Forge
@@ -542,7 +1059,6 @@ fn capture_memory_map(bs: &ignite::uefi::BootServices) -> (u64, u64) {
     // 5. Converter cada entrada - IMPORTANTE: usar descriptor_size, não sizeof!
     let mut valid_entries = 0;
     let mut total_usable_ram: u64 = 0;
-    let mut max_ram_address: u64 = 0;
 
     if DEBUG_MEMORY_MAP {
         ignite::println!("=== DEBUG: Analisando Memory Map UEFI ===");
@@ -619,15 +1135,10 @@ fn capture_memory_map(bs: &ignite::uefi::BootServices) -> (u64, u64) {
             );
         }
 
-        // Contabilizar RAM usável E calcular endereço máximo APENAS com RAM real
+        // Contabilizar RAM usável (o endereço máximo vai no BootInfo, calculado
+        // por `memory::map::max_usable_phys` sobre estas entradas)
         if desc.ty == MemoryType::ConventionalMemory as u32 {
             total_usable_ram += size;
-
-            // Calcular endereço máximo APENAS da RAM utilizável
-            let end = desc.physical_start + size;
-            if end > max_ram_address {
-                max_ram_address = end;
-            }
         }
 
         forge_entries[valid_entries] = MemoryMapEntry {
@@ -663,7 +1174,27 @@ fn capture_memory_map(bs: &ignite::uefi::BootServices) -> (u64, u64) {
         total_usable_ram / (1024 * 1024 * 1024)
     );
 
-    (entries_ptr as u64, valid_entries as u64)
+    ((entries_ptr as u64, valid_entries as u64), total_usable_ram)
+}
+
+/// Exporta na serial as entradas capturadas por `capture_memory_map`, uma
+/// linha CSV cada, entre marcadores para facilitar a extração do log.
+fn dump_memory_map_csv((entries_ptr, count): (u64, u64)) {
+    use ignite::{core::handoff::MemoryMapEntry, memory::map};
+
+    if entries_ptr == 0 {
+        return;
+    }
+    let entries = unsafe {
+        core::slice::from_raw_parts(entries_ptr as *const MemoryMapEntry, count as usize)
+    };
+
+    ignite::println!("--- memory map csv ---");
+    ignite::println!("{}", map::CSV_HEADER);
+    for entry in entries {
+        ignite::println!("{}", map::CsvRow(entry));
+    }
+    ignite::println!("--- fim ---");
 }
 
 /// Jump para o kernel: escolhe entre Redstone (fixo) ou genérico (dinâmico).
@@ -702,8 +1233,9 @@ unsafe extern "C" fn jump_to_kernel_redstone(
     arg4: u64,
     cr3: u64,
 ) -> ! {
-    // Endereço fixo do kernel Forge (convenção Redstone OS)
-    const REDSTONE_KERNEL_ENTRY: u64 = 0xffffffff80000000;
+    // Endereço fixo do kernel Forge (convenção Redstone OS), conferido em
+    // `RedstoneProtocol::load`
+    const REDSTONE_KERNEL_ENTRY: u64 = ignite::protos::redstone::REDSTONE_KERNEL_ENTRY;
 
     ignite::println!(
         "[DEBUG] jump_to_kernel_redstone: Target={:#x}, CR3={:#x}, Stack={:#x}",
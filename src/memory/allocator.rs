@@ -31,28 +31,14 @@ impl<'a> FrameAllocator for UefiFrameAllocator<'a> {
                 AllocateType::AllocateAnyPages,
                 MemoryType::LoaderData,
                 count,
+                0,
             )
             .map_err(|_| BootError::Memory(MemoryError::AllocationFailed))
     }
 
-    fn allocate_at(&mut self, _addr: u64, count: usize) -> Result<u64> {
-        // CORREÇÃO: allocate_at agora é um método específico ou usa allocate_pages
-        // Se sua impl em uefi/table/boot.rs não tem allocate_at, use allocate_pages com
-        // Address
-
-        // Opção A: Se você implementou o helper allocate_at no BootServices:
-        // self.boot_services.allocate_at(MemoryType::LoaderData, count, addr)
-
-        // Opção B (Genérica): Hack para passar o endereço via referência mutável
-        // Como o wrapper seguro do uefi/table/boot.rs pode não expor Address in/out:
-        // Vamos assumir que você adicionou o helper `allocate_at` conforme sugerido no
-        // refactor.
-
+    fn allocate_at(&mut self, addr: u64, count: usize) -> Result<u64> {
         self.boot_services
-             .allocate_pages(AllocateType::AllocateAddress, MemoryType::LoaderData, count)
-             // Nota: O wrapper seguro no boot.rs ignora addr input para AllocateAddress?
-             // Se sim, precisamos usar a versão unsafe ou corrigir o boot.rs.
-             // Assumindo correção no boot.rs abaixo.
-             .map_err(|_| BootError::Memory(MemoryError::AllocationFailed))
+            .allocate_at(MemoryType::LoaderData, count, addr)
+            .map_err(|_| BootError::Memory(MemoryError::AllocationFailed))
     }
 }
@@ -2,6 +2,10 @@
 //!
 //! Implementa `GlobalAlloc` de forma simples e segura para ambientes
 //! single-threaded.
+//!
+//! `init` rejeita limites inválidos e grava um canário no início da arena;
+//! se ele tiver sido sobrescrito quando a arena (re)começa a ser usada, algo
+//! escreveu no Heap sem ter alocado.
 
 use core::{
     alloc::{GlobalAlloc, Layout},
@@ -9,6 +13,17 @@ use core::{
     ptr::null_mut,
 };
 
+use crate::core::{
+    align::align_up,
+    error::{BootError, MemoryError, Result},
+};
+
+/// Valor gravado no início da arena por `init`.
+const HEAP_CANARY: u64 = 0x4947_4E48_4541_5021; // "IGNHEAP!"
+
+/// Bytes reservados para o canário no início da arena.
+pub const HEAP_CANARY_SIZE: usize = core::mem::size_of::<u64>();
+
 /// Alocador "Bump" (Incremento Linear).
 pub struct BumpAllocator {
     heap_start:  UnsafeCell<usize>,
@@ -33,16 +48,93 @@ impl BumpAllocator {
 
     /// Inicializa o alocador com um bloco de memória.
     ///
+    /// # Panics
+    /// Se `heap_start` for nulo, se o bloco não couber o canário ou se
+    /// `heap_start + heap_size` estourar o espaço de endereçamento.
+    ///
     /// # Safety
     /// O chamador deve garantir que o intervalo de memória [heap_start,
     /// heap_start + heap_size) é válido e não está em uso.
     pub unsafe fn init(&self, heap_start: usize, heap_size: usize) {
+        assert!(heap_start != 0, "BumpAllocator::init: heap_start nulo");
+        assert!(
+            heap_size >= HEAP_CANARY_SIZE,
+            "BumpAllocator::init: heap_size {:#x} menor que o canario",
+            heap_size
+        );
+        let heap_end = heap_start.checked_add(heap_size).unwrap_or_else(|| {
+            panic!(
+                "BumpAllocator::init: {:#x} + {:#x} estoura o espaco de enderecamento",
+                heap_start, heap_size
+            )
+        });
+
+        (heap_start as *mut u64).write_unaligned(HEAP_CANARY);
+
         *self.heap_start.get() = heap_start;
-        *self.heap_end.get() = heap_start + heap_size;
-        *self.next.get() = heap_start;
+        *self.heap_end.get() = heap_end;
+        *self.next.get() = heap_start + HEAP_CANARY_SIZE;
+    }
+
+    /// Inicializa o alocador tentando Heaps cada vez menores.
+    ///
+    /// Chama `alloc` com `requested`, depois com metade, um quarto... até
+    /// `min_size`, e usa o primeiro bloco obtido. Retorna o tamanho final.
+    ///
+    /// # Safety
+    /// O bloco devolvido por `alloc` deve ser válido para o tamanho pedido e
+    /// não estar em uso.
+    pub unsafe fn init_with_fallback<F>(
+        &self,
+        requested: usize,
+        min_size: usize,
+        alloc: F,
+    ) -> Result<usize>
+    where
+        F: FnMut(usize) -> Option<usize>,
+    {
+        let (start, size) = shrinking_heap(requested, min_size, alloc)?;
+        self.init(start, size);
+        Ok(size)
+    }
+
+    /// Tamanho total do Heap gerenciado (0 se não inicializado).
+    pub fn capacity(&self) -> usize {
+        unsafe { *self.heap_end.get() - *self.heap_start.get() }
     }
 }
 
+/// Pede a `alloc` um bloco de `requested` bytes, depois metade, um quarto...
+/// até `min_size`. Retorna `(início, tamanho)` do primeiro bloco obtido.
+///
+/// Compartilhado pelos alocadores globais (`init_with_fallback`).
+pub(crate) fn shrinking_heap<F>(
+    requested: usize,
+    min_size: usize,
+    mut alloc: F,
+) -> Result<(usize, usize)>
+where
+    F: FnMut(usize) -> Option<usize>,
+{
+    let mut size = requested;
+
+    while size >= min_size && size > 0 {
+        if let Some(start) = alloc(size) {
+            if size < requested {
+                log::warn!(
+                    "Heap reduzida para {} KiB ({} KiB pedidos)",
+                    size / 1024,
+                    requested / 1024
+                );
+            }
+            return Ok((start, size));
+        }
+        size /= 2;
+    }
+
+    Err(BootError::Memory(MemoryError::OutOfMemory))
+}
+
 unsafe impl GlobalAlloc for BumpAllocator {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
         let heap_start = *self.heap_start.get();
@@ -53,7 +145,21 @@ unsafe impl GlobalAlloc for BumpAllocator {
             return null_mut(); // Não inicializado
         }
 
-        let alloc_start = align_up(next, layout.align());
+        // Arena (re)começando: o canário ainda deve estar intacto
+        if *self.allocations.get() == 0 {
+            let canary = (heap_start as *const u64).read_unaligned();
+            assert!(
+                canary == HEAP_CANARY,
+                "BumpAllocator: canario em {:#x} corrompido ({:#x})",
+                heap_start,
+                canary
+            );
+        }
+
+        let alloc_start = match align_up(next as u64, layout.align() as u64) {
+            Some(start) => start as usize,
+            None => return null_mut(),
+        };
         let alloc_end = match alloc_start.checked_add(layout.size()) {
             Some(end) => end,
             None => return null_mut(),
@@ -72,12 +178,7 @@ unsafe impl GlobalAlloc for BumpAllocator {
     unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {
         *self.allocations.get() -= 1;
         if *self.allocations.get() == 0 {
-            *self.next.get() = *self.heap_start.get();
+            *self.next.get() = *self.heap_start.get() + HEAP_CANARY_SIZE;
         }
     }
 }
-
-/// Alinha o endereço para cima.
-fn align_up(addr: usize, align: usize) -> usize {
-    (addr + align - 1) & !(align - 1)
-}
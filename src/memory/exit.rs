@@ -0,0 +1,165 @@
+//! Saída dos Boot Services (ExitBootServices)
+//!
+//! `ExitBootServices` falha se o mapa de memória mudou desde a leitura da
+//! `map_key`; por isso há uma segunda tentativa com a chave relida. Se ambas
+//! falharem, o estado do firmware é desconhecido: registramos o diagnóstico na
+//! serial (que continua funcionando) e pedimos um reset a frio em vez de
+//! travar a máquina em silêncio. A espera antes do reset é um laço de
+//! `spin_loop`: depois de uma chamada com falha o firmware só aceita
+//! `GetMemoryMap` e `ExitBootServices`.
+
+use alloc::format;
+
+use crate::uefi::{
+    table::{
+        boot::{MemoryDescriptor, MemoryType},
+        runtime::ResetType,
+    },
+    BootServices, Handle, Status,
+};
+
+/// Chamadas a `ExitBootServices` antes de desistir.
+pub const EXIT_ATTEMPTS: usize = 2;
+
+/// Espera entre o diagnóstico e o reset (microssegundos), para dar tempo de
+/// ler a serial.
+pub const EXIT_FAILURE_RESET_DELAY_US: usize = 5_000_000;
+
+/// Operações do firmware usadas na saída dos Boot Services.
+pub trait BootExit {
+    /// Relê o mapa de memória: `(map_key, número de descritores)`.
+    fn memory_map_key(&mut self) -> (usize, usize);
+
+    /// Chama `ExitBootServices` com `map_key`.
+    fn exit(&mut self, map_key: usize) -> Status;
+
+    /// Escreve uma linha de diagnóstico na serial.
+    fn diagnostic(&mut self, line: &str);
+
+    /// Aguarda `microseconds` sem usar os Boot Services.
+    fn delay(&mut self, microseconds: usize);
+
+    /// Reinicia a máquina (a implementação real não retorna).
+    fn reset(&mut self);
+}
+
+/// `BootExit` sobre os Boot Services reais.
+///
+/// O buffer do mapa é alocado na criação: alocar entre a leitura do mapa e o
+/// `ExitBootServices` invalidaria a chave.
+pub struct FirmwareExit<'a> {
+    bs:           &'a BootServices,
+    image_handle: Handle,
+    buffer:       *mut u8,
+    buffer_size:  usize,
+}
+
+impl<'a> FirmwareExit<'a> {
+    pub fn new(bs: &'a BootServices, image_handle: Handle) -> Self {
+        let mut map_size = 0;
+        let mut map_key = 0;
+        let mut descriptor_size = 0;
+        let mut descriptor_version = 0;
+
+        let _ = unsafe {
+            (bs.get_memory_map_f)(
+                &mut map_size,
+                core::ptr::null_mut(),
+                &mut map_key,
+                &mut descriptor_size,
+                &mut descriptor_version,
+            )
+        };
+
+        // Margem para os descritores criados pela própria alocação
+        let buffer_size = map_size + descriptor_size * 8;
+        let buffer = bs
+            .allocate_pool(MemoryType::LoaderData, buffer_size)
+            .unwrap_or(core::ptr::null_mut());
+
+        Self {
+            bs,
+            image_handle,
+            buffer,
+            buffer_size: if buffer.is_null() { 0 } else { buffer_size },
+        }
+    }
+}
+
+impl BootExit for FirmwareExit<'_> {
+    fn memory_map_key(&mut self) -> (usize, usize) {
+        let mut map_size = self.buffer_size;
+        let mut map_key = 0;
+        let mut descriptor_size = 0;
+        let mut descriptor_version = 0;
+
+        let _ = unsafe {
+            (self.bs.get_memory_map_f)(
+                &mut map_size,
+                self.buffer as *mut MemoryDescriptor,
+                &mut map_key,
+                &mut descriptor_size,
+                &mut descriptor_version,
+            )
+        };
+
+        let descriptors = map_size.checked_div(descriptor_size).unwrap_or(0);
+        (map_key, descriptors)
+    }
+
+    fn exit(&mut self, map_key: usize) -> Status {
+        let status = self.bs.exit_boot_services(self.image_handle, map_key);
+        if !status.is_error() {
+            crate::uefi::mark_boot_services_exited();
+        }
+        status
+    }
+
+    fn diagnostic(&mut self, line: &str) {
+        crate::arch::x86::serial::serial_print(line);
+        crate::arch::x86::serial::serial_print("\r\n");
+    }
+
+    fn delay(&mut self, microseconds: usize) {
+        // Após um `ExitBootServices` com falha só `GetMemoryMap` e o próprio
+        // `ExitBootServices` podem ser chamados: nada de `Stall`
+        for _ in 0..microseconds.saturating_mul(crate::panic::SPINS_PER_US) {
+            core::hint::spin_loop();
+        }
+    }
+
+    fn reset(&mut self) {
+        crate::uefi::system_table()
+            .runtime_services()
+            .reset_system(ResetType::Cold, Status::ABORTED);
+    }
+}
+
+/// Sai dos Boot Services, tentando até `EXIT_ATTEMPTS` vezes.
+///
+/// Retorna `true` em caso de sucesso. Se todas as tentativas falharem,
+/// registra a `map_key`, o número de descritores e o status da última
+/// tentativa, espera `EXIT_FAILURE_RESET_DELAY_US` e pede um reset a frio;
+/// retorna `false` apenas se o reset voltar (só em simulações).
+pub fn exit_boot_services_or_reset<B: BootExit + ?Sized>(boot: &mut B) -> bool {
+    let mut last = (0, 0, Status::SUCCESS);
+
+    for _ in 0..EXIT_ATTEMPTS {
+        let (map_key, descriptors) = boot.memory_map_key();
+        let status = boot.exit(map_key);
+        if !status.is_error() {
+            return true;
+        }
+        last = (map_key, descriptors, status);
+    }
+
+    let (map_key, descriptors, status) = last;
+    boot.diagnostic(&format!(
+        "[FAIL] ExitBootServices falhou {} vezes: map_key={:#x} descritores={} status={:?}",
+        EXIT_ATTEMPTS, map_key, descriptors, status
+    ));
+    boot.diagnostic("Reiniciando em 5 segundos...");
+    boot.delay(EXIT_FAILURE_RESET_DELAY_US);
+    boot.reset();
+    false
+}
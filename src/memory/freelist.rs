@@ -0,0 +1,232 @@
+//! Alocador Heap com Lista Livre
+//!
+//! Alternativa ao `BumpAllocator` (feature `freelist_allocator`): blocos
+//! liberados voltam para uma lista ordenada por endereço e são fundidos com
+//! os vizinhos, então ciclos de recarga da configuração não esgotam nem
+//! fragmentam a arena.
+//!
+//! Todo bloco, livre ou alocado, começa e termina em múltiplos de
+//! `MIN_BLOCK_SIZE`: qualquer sobra de uma alocação cabe o cabeçalho de um
+//! bloco livre.
+
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    cell::UnsafeCell,
+    mem,
+    ptr::null_mut,
+};
+
+use super::bump_allocator::shrinking_heap;
+use crate::core::{align::align_up, error::Result};
+
+/// Granularidade (tamanho e alinhamento mínimos) dos blocos.
+pub const MIN_BLOCK_SIZE: usize = mem::size_of::<FreeBlock>();
+
+/// Cabeçalho gravado no início de cada bloco livre.
+struct FreeBlock {
+    size: usize,
+    next: *mut FreeBlock,
+}
+
+/// Alocador com lista livre ordenada e fusão de blocos adjacentes.
+pub struct FreeListAllocator {
+    head:     UnsafeCell<*mut FreeBlock>,
+    capacity: UnsafeCell<usize>,
+}
+
+// SAFETY: O Bootloader UEFI roda em um único core/thread durante o boot
+// services.
+unsafe impl Sync for FreeListAllocator {}
+
+impl Default for FreeListAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FreeListAllocator {
+    pub const fn new() -> Self {
+        Self {
+            head:     UnsafeCell::new(null_mut()),
+            capacity: UnsafeCell::new(0),
+        }
+    }
+
+    /// Inicializa o alocador com um bloco de memória, recortado para os
+    /// limites de `MIN_BLOCK_SIZE`.
+    ///
+    /// # Panics
+    /// Se `heap_start` for nulo, se o bloco não couber um bloco livre ou se
+    /// `heap_start + heap_size` estourar o espaço de endereçamento.
+    ///
+    /// # Safety
+    /// O chamador deve garantir que o intervalo de memória [heap_start,
+    /// heap_start + heap_size) é válido e não está em uso.
+    pub unsafe fn init(&self, heap_start: usize, heap_size: usize) {
+        assert!(heap_start != 0, "FreeListAllocator::init: heap_start nulo");
+        let heap_end = heap_start.checked_add(heap_size).unwrap_or_else(|| {
+            panic!(
+                "FreeListAllocator::init: {:#x} + {:#x} estoura o espaco de enderecamento",
+                heap_start, heap_size
+            )
+        });
+
+        let start = align_up(heap_start as u64, MIN_BLOCK_SIZE as u64).unwrap_or(u64::MAX) as usize;
+        let end = heap_end & !(MIN_BLOCK_SIZE - 1);
+        assert!(
+            end > start,
+            "FreeListAllocator::init: heap_size {:#x} menor que um bloco",
+            heap_size
+        );
+
+        *self.head.get() = null_mut();
+        *self.capacity.get() = end - start;
+        self.insert(start, end - start);
+    }
+
+    /// Inicializa o alocador tentando Heaps cada vez menores (ver
+    /// `BumpAllocator::init_with_fallback`). Retorna o tamanho final.
+    ///
+    /// # Safety
+    /// O bloco devolvido por `alloc` deve ser válido para o tamanho pedido e
+    /// não estar em uso.
+    pub unsafe fn init_with_fallback<F>(
+        &self,
+        requested: usize,
+        min_size: usize,
+        alloc: F,
+    ) -> Result<usize>
+    where
+        F: FnMut(usize) -> Option<usize>,
+    {
+        let (start, size) = shrinking_heap(requested, min_size, alloc)?;
+        self.init(start, size);
+        Ok(size)
+    }
+
+    /// Tamanho do Heap gerenciado (0 se não inicializado).
+    pub fn capacity(&self) -> usize {
+        unsafe { *self.capacity.get() }
+    }
+
+    /// Soma dos blocos livres.
+    pub fn free_bytes(&self) -> usize {
+        self.blocks().map(|(_, size)| size).sum()
+    }
+
+    /// Número de blocos na lista livre (1 com a arena toda livre).
+    pub fn free_block_count(&self) -> usize {
+        self.blocks().count()
+    }
+
+    /// Percorre a lista livre como pares `(endereço, tamanho)`.
+    fn blocks(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let mut cur = unsafe { *self.head.get() };
+        core::iter::from_fn(move || {
+            if cur.is_null() {
+                return None;
+            }
+            let block = unsafe { &*cur };
+            let item = (cur as usize, block.size);
+            cur = block.next;
+            Some(item)
+        })
+    }
+
+    /// Tamanho efetivo de uma alocação: ao menos um byte, arredondado para
+    /// `MIN_BLOCK_SIZE`. `alloc` e `dealloc` precisam concordar nele.
+    fn block_size(layout: &Layout) -> Option<usize> {
+        layout
+            .size()
+            .max(1)
+            .checked_next_multiple_of(MIN_BLOCK_SIZE)
+    }
+
+    /// Devolve `[addr, addr + size)` à lista, fundindo com os vizinhos
+    /// adjacentes.
+    ///
+    /// # Safety
+    /// O intervalo deve estar dentro da arena, alinhado a `MIN_BLOCK_SIZE` e
+    /// fora de uso.
+    unsafe fn insert(&self, addr: usize, size: usize) {
+        let mut prev: *mut FreeBlock = null_mut();
+        let mut next = *self.head.get();
+        while !next.is_null() && (next as usize) < addr {
+            prev = next;
+            next = (*next).next;
+        }
+
+        assert!(
+            (prev.is_null() || prev as usize + (*prev).size <= addr)
+                && (next.is_null() || addr + size <= next as usize),
+            "FreeListAllocator: bloco em {:#x} liberado duas vezes",
+            addr
+        );
+
+        let block = addr as *mut FreeBlock;
+        block.write(FreeBlock { size, next });
+
+        if !next.is_null() && addr + size == next as usize {
+            (*block).size += (*next).size;
+            (*block).next = (*next).next;
+        }
+
+        if prev.is_null() {
+            *self.head.get() = block;
+        } else if prev as usize + (*prev).size == addr {
+            (*prev).size += (*block).size;
+            (*prev).next = (*block).next;
+        } else {
+            (*prev).next = block;
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for FreeListAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let Some(size) = Self::block_size(&layout) else {
+            return null_mut();
+        };
+        let align = layout.align().max(MIN_BLOCK_SIZE) as u64;
+
+        // First-fit: o primeiro bloco onde a alocação alinhada couber
+        let mut prev: *mut FreeBlock = null_mut();
+        let mut cur = *self.head.get();
+        while !cur.is_null() {
+            let block_start = cur as usize;
+            let block_end = block_start + (*cur).size;
+            let fit = align_up(block_start as u64, align)
+                .map(|start| start as usize)
+                .and_then(|start| Some((start, start.checked_add(size)?)))
+                .filter(|&(_, end)| end <= block_end);
+
+            if let Some((start, end)) = fit {
+                if prev.is_null() {
+                    *self.head.get() = (*cur).next;
+                } else {
+                    (*prev).next = (*cur).next;
+                }
+
+                // As sobras antes e depois da alocação voltam para a lista
+                if end < block_end {
+                    self.insert(end, block_end - end);
+                }
+                if start > block_start {
+                    self.insert(block_start, start - block_start);
+                }
+                return start as *mut u8;
+            }
+
+            prev = cur;
+            cur = (*cur).next;
+        }
+
+        null_mut() // OOM ou não inicializado
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if let Some(size) = Self::block_size(&layout) {
+            self.insert(ptr as usize, size);
+        }
+    }
+}
@@ -2,6 +2,10 @@
 //!
 //! Centraliza as constantes de onde o Kernel, Stack e Heap serão carregados.
 
+use alloc::vec::Vec;
+
+use crate::core::error::{BootError, MemoryError, Result};
+
 /// Endereço físico onde tentaremos carregar o Kernel (se possível).
 pub const KERNEL_LOAD_ADDR: u64 = 0x100_000;
 
@@ -40,3 +44,73 @@ pub struct BootLayout {
     /// Base da pilha (endereço mais baixo).
     pub stack_bottom: u64,
 }
+
+/// Região física colocada pelo loader (kernel, módulo, BootInfo...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlacedRegion {
+    /// Nome para diagnóstico (ex: "kernel", "module").
+    pub name: &'static str,
+    /// Início físico.
+    pub base: u64,
+    /// Tamanho em bytes.
+    pub size: u64,
+}
+
+impl PlacedRegion {
+    /// Fim exclusivo da região.
+    pub fn end(&self) -> u64 {
+        self.base.saturating_add(self.size)
+    }
+
+    /// Verifica se as duas regiões compartilham ao menos um byte.
+    pub fn overlaps(&self, other: &PlacedRegion) -> bool {
+        self.base < other.end() && other.base < self.end()
+    }
+}
+
+/// Registro das regiões colocadas antes do handoff.
+///
+/// Kernel, módulos, BootInfo e stack são alocados de forma independente; um
+/// bug de posicionamento faria um sobrescrever o outro em silêncio. Cada
+/// região é conferida contra as anteriores no momento do registro.
+#[derive(Debug, Default)]
+pub struct OverlapChecker {
+    regions: Vec<PlacedRegion>,
+}
+
+impl OverlapChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registra uma região, falhando se ela sobrepuser uma já registrada.
+    ///
+    /// Regiões vazias são ignoradas.
+    pub fn record(&mut self, name: &'static str, base: u64, size: u64) -> Result<()> {
+        if size == 0 {
+            return Ok(());
+        }
+
+        let region = PlacedRegion { name, base, size };
+        if let Some(other) = self.regions.iter().find(|r| r.overlaps(&region)) {
+            log::error!(
+                "Layout: '{}' [{:#x}..{:#x}) sobrepoe '{}' [{:#x}..{:#x})",
+                region.name,
+                region.base,
+                region.end(),
+                other.name,
+                other.base,
+                other.end()
+            );
+            return Err(BootError::Memory(MemoryError::RegionOverlap));
+        }
+
+        self.regions.push(region);
+        Ok(())
+    }
+
+    /// Regiões registradas até agora, na ordem de registro.
+    pub fn regions(&self) -> &[PlacedRegion] {
+        &self.regions
+    }
+}
@@ -1,7 +1,15 @@
 //! Abstração e Sanitização do Mapa de Memória
 
+use core::fmt;
+
 use super::region::{MemoryRegionKind, PhysicalMemoryRegion};
-use crate::uefi::table::boot::{MemoryDescriptor, MemoryType};
+use crate::{
+    core::{
+        error::{BootError, MemoryError, Result},
+        handoff::{MemoryMapEntry, MemoryType as HandoffMemoryType},
+    },
+    uefi::table::boot::{MemoryDescriptor, MemoryType},
+};
 
 pub struct MemoryMapIter<'a> {
     descriptors: core::slice::Iter<'a, MemoryDescriptor>,
@@ -48,3 +56,115 @@ impl<'a> Iterator for MemoryMapIter<'a> {
         None
     }
 }
+
+/// Confere que `[start, end)` está inteiramente coberto por regiões `Usable`.
+///
+/// Deve preceder qualquer alocação em endereço fixo (`allocate_at`), como a
+/// de kernels que exigem um endereço de carga: pedir ao firmware um intervalo
+/// reservado falha na melhor das hipóteses e sobrescreve tabelas do firmware
+/// na pior.
+///
+/// Retorna `RegionOverlap` se o intervalo tocar uma região não utilizável e
+/// `InvalidAddress` se cair em um buraco do mapa.
+pub fn check_range_usable(
+    regions: impl IntoIterator<Item = PhysicalMemoryRegion>,
+    start: u64,
+    end: u64,
+) -> Result<()> {
+    if end <= start {
+        return Err(BootError::Memory(MemoryError::InvalidSize));
+    }
+
+    let mut usable = alloc::vec::Vec::new();
+    for region in regions {
+        let overlaps = region.start_addr() < end && start < region.end_addr();
+        if !overlaps {
+            continue;
+        }
+        if region.kind != MemoryRegionKind::Usable {
+            log::error!(
+                "Memoria: [{:#x}..{:#x}) sobrepoe regiao {:?} [{:#x}..{:#x})",
+                start,
+                end,
+                region.kind,
+                region.start_addr(),
+                region.end_addr()
+            );
+            return Err(BootError::Memory(MemoryError::RegionOverlap));
+        }
+        usable.push(region);
+    }
+
+    // As regiões utilizáveis precisam cobrir o intervalo sem buracos
+    usable.sort_unstable_by_key(|r| r.start_addr());
+    let mut cursor = start;
+    for region in &usable {
+        if region.start_addr() > cursor {
+            break;
+        }
+        cursor = cursor.max(region.end_addr());
+    }
+
+    if cursor < end {
+        log::error!(
+            "Memoria: [{:#x}..{:#x}) fora do mapa a partir de {:#x}",
+            start,
+            end,
+            cursor
+        );
+        return Err(BootError::Memory(MemoryError::InvalidAddress));
+    }
+
+    Ok(())
+}
+
+/// Maior endereço físico (exclusivo) coberto por RAM utilizável.
+///
+/// Só entradas `Usable` contam: firmwares costumam reportar MMIO e janelas
+/// PCI muito acima da RAM, o que faria um kernel dimensionar seu alocador
+/// físico para terabytes inexistentes. Retorna 0 se não houver RAM livre.
+pub fn max_usable_phys(entries: &[MemoryMapEntry]) -> u64 {
+    entries
+        .iter()
+        .filter(|entry| entry.typ == HandoffMemoryType::Usable)
+        .map(|entry| entry.base.saturating_add(entry.len))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Cabeçalho do CSV do mapa de memória (`dump_memory_map: csv`).
+pub const CSV_HEADER: &str = "base,len,type";
+
+/// Linha `base,len,type` do CSV do mapa de memória, ex:
+/// `0x100000,0x7F00000,Usable`. Formata direto no destino, sem alocar.
+pub struct CsvRow<'a>(pub &'a MemoryMapEntry);
+
+impl fmt::Display for CsvRow<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:X},0x{:X},{:?}", self.0.base, self.0.len, self.0.typ)
+    }
+}
+
+/// Total de RAM utilizável (entradas `Usable`) em `entries`.
+pub fn usable_ram(entries: &[MemoryMapEntry]) -> u64 {
+    entries
+        .iter()
+        .filter(|entry| entry.typ == HandoffMemoryType::Usable)
+        .fold(0u64, |total, entry| total.saturating_add(entry.len))
+}
+
+/// Confere a `min_memory` de uma entrada contra a RAM utilizável medida no
+/// mapa de memória. Retorna `OutOfMemory` se faltar RAM.
+pub fn check_min_memory(usable: u64, min_memory: Option<u64>) -> Result<()> {
+    match min_memory {
+        Some(required) if usable < required => {
+            log::error!(
+                "Memoria: entrada exige {} MiB de RAM, ha {} MiB utilizaveis",
+                required / (1024 * 1024),
+                usable / (1024 * 1024)
+            );
+            Err(BootError::Memory(MemoryError::OutOfMemory))
+        },
+        _ => Ok(()),
+    }
+}
@@ -6,6 +6,8 @@
 // Módulos internos (privados ou públicos conforme necessidade)
 pub mod allocator;
 pub mod bump_allocator;
+pub mod exit;
+pub mod freelist;
 pub mod handoff;
 pub mod layout;
 pub mod map;
@@ -15,6 +17,7 @@ pub mod region;
 // Re-exports para facilitar o uso no `main.rs`
 pub use allocator::{FrameAllocator, UefiFrameAllocator};
 pub use bump_allocator::BumpAllocator;
+pub use freelist::FreeListAllocator;
 pub use handoff::BootInfo;
 pub use paging::PageTableManager;
 
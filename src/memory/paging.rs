@@ -39,13 +39,16 @@
 //! - Transformar duplicação de código (obter/criar table entries) em helper
 //!   `get_or_create_table(level, idx)` para reduzir repetição.
 //! - Suportar flags adicionais (NX, PAT, user/supervisor, cache attribs).
-//! - Registrar métricas (quantos frames alocados para page tables) para
-//!   debugging.
 //! - Tratar erros com variantes mais específicas de `BootError` em vez de
 //!   `expect`.
 
+use alloc::vec::Vec;
+
 use super::allocator::FrameAllocator;
-use crate::core::error::{BootError, MemoryError, Result};
+use crate::core::{
+    align::{align_down, align_up, div_ceil_pages},
+    error::{BootError, MemoryError, Result},
+};
 
 /// Flags x86_64 para page table entries
 /// ====================================
@@ -66,6 +69,25 @@ const PAGE_GLOBAL: u64 = 1 << 8; // G - Global (não flush no CR3 reload)
 const PAGE_PAT: u64 = 1 << 12; // PAT (para huge pages; bit 7 em PT)
 const PAGE_NO_EXEC: u64 = 1 << 63; // NX - No Execute
 
+/// Atributos de cache do framebuffer.
+///
+/// Com o PAT padrão do firmware, PWT+PCD seleciona a entrada 3 (UC). Sem isso o
+/// framebuffer herdaria o write-back do identity map, e escritas em VRAM ficam
+/// presas no cache. O kernel pode reprogramar o PAT para write-combining.
+pub const FRAMEBUFFER_CACHE_FLAGS: u64 = PAGE_PWT | PAGE_PCD;
+
+/// Bit Global para as páginas do kernel.
+///
+/// O kernel é mapeado igual em todos os espaços de endereçamento; com G (e
+/// CR4.PGE) suas entradas de TLB sobrevivem às trocas de CR3.
+pub const KERNEL_GLOBAL_FLAGS: u64 = PAGE_GLOBAL;
+
+/// Bit NX para os segmentos do kernel sem `PF_X`.
+///
+/// Só pode ir para as tabelas com EFER.NXE ligado: sem ele o bit 63 é
+/// reservado e qualquer acesso à página gera page fault.
+pub const KERNEL_NO_EXEC_FLAGS: u64 = PAGE_NO_EXEC;
+
 /// Máscara para flags que devem ser preservadas ao converter huge page →
 /// páginas 4KiB. Inclui: Present, Writable, User, PWT, PCD, Accessed, Dirty,
 /// Global, NX NÃO inclui: PAGE_HUGE (será removida), PAGE_PAT (posição
@@ -90,7 +112,6 @@ const PAGE_PAT_4K: u64 = 1 << 7;
 const ADDR_MASK: u64 = 0x000F_FFFF_FFFF_F000;
 
 /// Tamanho de uma huge page (2MiB)
-#[allow(dead_code)]
 const HUGE_PAGE_SIZE: u64 = 2 * 1024 * 1024;
 
 /// Tamanho de uma página normal (4KiB)
@@ -104,6 +125,19 @@ const PAGE_SIZE: u64 = 4096;
 /// `PageTableManager`.
 pub struct PageTableManager {
     pml4_phys_addr: u64,
+    /// Deslocamento somado a um endereço físico para acessá-lo (0 enquanto o
+    /// identity map do firmware estiver ativo).
+    phys_offset:    u64,
+    /// Flags extras das páginas criadas por `map_kernel`.
+    kernel_flags:   u64,
+    /// Se `map_kernel_segment` marca segmentos não executáveis com NX.
+    enforce_nx:     bool,
+    /// Se `ensure_identity_map_4k` e `map_framebuffer` criam entradas
+    /// virtual == físico.
+    identity_pages: bool,
+    /// Todos os frames alocados para tabelas (PML4 inclusa), entregues ao
+    /// kernel para que possa reaproveitá-los.
+    table_frames:   Vec<u64>,
 }
 
 impl PageTableManager {
@@ -112,19 +146,55 @@ impl PageTableManager {
     /// - `allocator` é usado para alocar o frame que conterá a PML4.
     /// - A PML4 é zerada por segurança, evitando herdar lixo da RAM.
     pub fn new(allocator: &mut impl FrameAllocator) -> Result<Self> {
+        Self::with_phys_offset(allocator, 0)
+    }
+
+    /// Como `new`, mas acessa cada frame físico em `phys + phys_offset`.
+    ///
+    /// Permite operar sobre tabelas que não estão no identity map — por
+    /// exemplo, uma arena de frames em RAM comum nos testes do host, onde os
+    /// "endereços físicos" são deslocamentos dentro da arena.
+    pub fn with_phys_offset(
+        allocator: &mut (impl FrameAllocator + ?Sized),
+        phys_offset: u64,
+    ) -> Result<Self> {
         // 1) Aloca frame para a PML4 raiz
         let pml4 = allocator.allocate_frame(1)?;
 
+        let mut manager = Self {
+            pml4_phys_addr: pml4,
+            phys_offset,
+            kernel_flags: 0,
+            enforce_nx: false,
+            identity_pages: true,
+            table_frames: Vec::new(),
+        };
+        manager.table_frames.push(pml4);
+
         // 2) Zera a página (segurança: não herdar dados)
-        unsafe {
-            let ptr = pml4 as *mut u64;
-            // 512 entradas * 8 bytes = 4096 bytes
-            core::ptr::write_bytes(ptr, 0, 512);
-        }
+        manager.zero_frame(pml4);
 
-        Ok(Self {
-            pml4_phys_addr: pml4,
-        })
+        Ok(manager)
+    }
+
+    /// Marca (ou não) as páginas de `map_kernel` como globais
+    /// (`global_kernel_pages`). Desligado por padrão.
+    pub fn set_global_kernel_pages(&mut self, enabled: bool) {
+        self.kernel_flags = if enabled { KERNEL_GLOBAL_FLAGS } else { 0 };
+    }
+
+    /// Liga ou desliga o NX nos segmentos de dados do kernel
+    /// (`enforce_nx`). Desligado por padrão: o chamador só deve ligar numa
+    /// CPU com NX, e ligar EFER.NXE antes de carregar estas tabelas.
+    pub fn set_enforce_nx(&mut self, enabled: bool) {
+        self.enforce_nx = enabled;
+    }
+
+    /// Liga ou desliga as páginas avulsas de identity map que
+    /// `ensure_identity_map_4k` e `map_framebuffer` criam. Desligado quando o
+    /// kernel não quer a metade inferior mapeada (`map_mode: direct`).
+    pub fn set_identity_pages(&mut self, enabled: bool) {
+        self.identity_pages = enabled;
     }
 
     /// Retorna o endereço físico da PML4 (útil para carregar em CR3).
@@ -132,6 +202,35 @@ impl PageTableManager {
         self.pml4_phys_addr
     }
 
+    /// Frames físicos ocupados pelas tabelas, na ordem de alocação.
+    pub fn table_frames(&self) -> &[u64] {
+        &self.table_frames
+    }
+
+    /// Aloca e zera um frame para uma nova tabela, registrando-o em
+    /// `table_frames`.
+    fn allocate_table(&mut self, allocator: &mut (impl FrameAllocator + ?Sized)) -> Result<u64> {
+        let frame = allocator.allocate_frame(1)?;
+        self.zero_frame(frame);
+        self.table_frames.push(frame);
+        Ok(frame)
+    }
+
+    /// Acessa a tabela de 512 entradas no frame físico `phys`.
+    #[allow(clippy::mut_from_ref)]
+    fn table(&self, phys: u64) -> &'static mut [u64; 512] {
+        // Frames de page table vêm do `FrameAllocator` e têm 4KiB; o offset
+        // traduz o endereço físico para um endereço acessível.
+        unsafe { &mut *(phys.wrapping_add(self.phys_offset) as *mut [u64; 512]) }
+    }
+
+    /// Zera um frame físico de 4KiB.
+    fn zero_frame(&self, phys: u64) {
+        unsafe {
+            core::ptr::write_bytes(phys.wrapping_add(self.phys_offset) as *mut u8, 0, 4096);
+        }
+    }
+
     // ---------------------------------------------------------------------
     // Identity map (general-purpose)
     // ---------------------------------------------------------------------
@@ -146,11 +245,17 @@ impl PageTableManager {
     /// identity map para estruturas como page tables, BootInfo, etc. Huge
     /// pages impedem mapeamento granular, então esta função garante acesso
     /// correto.
+    ///
+    /// Não faz nada se `set_identity_pages(false)` tiver sido chamado.
     pub fn ensure_identity_map_4k(
         &mut self,
         phys_addr: u64,
         allocator: &mut (impl FrameAllocator + ?Sized),
     ) -> Result<()> {
+        if !self.identity_pages {
+            return Ok(());
+        }
+
         // No identity map, virt == phys
         self.map_page(
             phys_addr,
@@ -182,13 +287,13 @@ impl PageTableManager {
         max_phys_addr: u64,
         allocator: &mut (impl FrameAllocator + ?Sized),
     ) -> Result<()> {
-        const SIZE_2MIB: u64 = 0x20_0000;
-        let aligned_max = (max_phys_addr + SIZE_2MIB - 1) & !(SIZE_2MIB - 1);
+        let aligned_max = align_up(max_phys_addr, HUGE_PAGE_SIZE)
+            .ok_or(BootError::Memory(MemoryError::InvalidAddress))?;
 
         let mut phys = 0u64;
         while phys < aligned_max {
             self.map_huge_page(phys, phys, PAGE_PRESENT | PAGE_WRITABLE, allocator)?;
-            phys = phys.wrapping_add(SIZE_2MIB);
+            phys = phys.wrapping_add(HUGE_PAGE_SIZE);
         }
         Ok(())
     }
@@ -212,14 +317,14 @@ impl PageTableManager {
         hhdm_offset: u64,
         allocator: &mut (impl FrameAllocator + ?Sized),
     ) -> Result<()> {
-        const SIZE_2MIB: u64 = 0x20_0000;
-        let aligned_max = (max_phys_addr + SIZE_2MIB - 1) & !(SIZE_2MIB - 1);
+        let aligned_max = align_up(max_phys_addr, HUGE_PAGE_SIZE)
+            .ok_or(BootError::Memory(MemoryError::InvalidAddress))?;
 
         let mut phys = 0u64;
         while phys < aligned_max {
             let virt = hhdm_offset + phys;
             self.map_huge_page(phys, virt, PAGE_PRESENT | PAGE_WRITABLE, allocator)?;
-            phys = phys.wrapping_add(SIZE_2MIB);
+            phys = phys.wrapping_add(HUGE_PAGE_SIZE);
         }
 
         Ok(())
@@ -246,6 +351,38 @@ impl PageTableManager {
         Ok(())
     }
 
+    /// Mapeia o framebuffer em páginas 4KiB com `FRAMEBUFFER_CACHE_FLAGS`.
+    ///
+    /// A região é mapeada no identity map (salvo `set_identity_pages(false)`)
+    /// e em `hhdm_offset + addr`, se houver HHDM: são os caminhos pelos quais
+    /// o kernel acessa o framebuffer ao herdar estas tabelas. Huge pages que
+    /// cobrem a região são divididas.
+    pub fn map_framebuffer(
+        &mut self,
+        addr: u64,
+        size: u64,
+        hhdm_offset: Option<u64>,
+        allocator: &mut (impl FrameAllocator + ?Sized),
+    ) -> Result<()> {
+        if !addr.is_multiple_of(PAGE_SIZE) {
+            return Err(BootError::Memory(MemoryError::InvalidAlignment));
+        }
+
+        let flags = PAGE_PRESENT | PAGE_WRITABLE | FRAMEBUFFER_CACHE_FLAGS;
+        let pages = div_ceil_pages(size);
+
+        for i in 0..pages {
+            let phys = addr + i * PAGE_SIZE;
+            if self.identity_pages {
+                self.map_page(phys, phys, flags, allocator)?;
+            }
+            if let Some(offset) = hhdm_offset {
+                self.map_page(phys, offset + phys, flags, allocator)?;
+            }
+        }
+        Ok(())
+    }
+
     // ---------------------------------------------------------------------
     // Mapeamentos de Huge Page (2MiB)
     // ---------------------------------------------------------------------
@@ -266,33 +403,27 @@ impl PageTableManager {
         let pdpt_idx = ((virt >> 30) & 0x1FF) as usize;
         let pd_idx = ((virt >> 21) & 0x1FF) as usize;
 
-        let pml4 = unsafe { &mut *(self.pml4_phys_addr as *mut [u64; 512]) };
+        let pml4 = self.table(self.pml4_phys_addr);
 
         // PDPT
         let pdpt_addr = if pml4[pml4_idx] & PAGE_PRESENT != 0 {
             pml4[pml4_idx] & ADDR_MASK
         } else {
-            let new_pdpt = allocator.allocate_frame(1)?;
-            unsafe {
-                core::ptr::write_bytes(new_pdpt as *mut u8, 0, 4096);
-            }
+            let new_pdpt = self.allocate_table(allocator)?;
             pml4[pml4_idx] = new_pdpt | PAGE_PRESENT | PAGE_WRITABLE;
             new_pdpt
         };
-        let pdpt = unsafe { &mut *(pdpt_addr as *mut [u64; 512]) };
+        let pdpt = self.table(pdpt_addr);
 
         // PD
         let pd_addr = if pdpt[pdpt_idx] & PAGE_PRESENT != 0 {
             pdpt[pdpt_idx] & ADDR_MASK
         } else {
-            let new_pd = allocator.allocate_frame(1)?;
-            unsafe {
-                core::ptr::write_bytes(new_pd as *mut u8, 0, 4096);
-            }
+            let new_pd = self.allocate_table(allocator)?;
             pdpt[pdpt_idx] = new_pd | PAGE_PRESENT | PAGE_WRITABLE;
             new_pd
         };
-        let pd = unsafe { &mut *(pd_addr as *mut [u64; 512]) };
+        let pd = self.table(pd_addr);
 
         // Escrever entry de PD como huge page (2MiB)
         pd[pd_idx] = (phys & ADDR_MASK) | flags | PAGE_HUGE;
@@ -331,6 +462,7 @@ impl PageTableManager {
     /// # Returns
     /// O endereço físico da nova Page Table alocada.
     fn split_huge_page_to_pt(
+        &mut self,
         pd: &mut [u64; 512],
         pd_idx: usize,
         allocator: &mut (impl FrameAllocator + ?Sized),
@@ -346,8 +478,9 @@ impl PageTableManager {
         // Log: huge page será dividida
         let _huge_phys = huge_entry & ADDR_MASK;
 
-        // Extrair endereço base da huge page (alinhado a 2MiB)
-        let huge_phys_base = huge_entry & ADDR_MASK;
+        // Extrair endereço base da huge page (alinhado a 2MiB). O bit 12 é o
+        // PAT da huge page, não parte do endereço.
+        let huge_phys_base = align_down(huge_entry & ADDR_MASK, HUGE_PAGE_SIZE);
 
         // Extrair flags que devem ser preservadas
         let mut preserved_flags = huge_entry & PRESERVED_FLAGS_MASK;
@@ -360,19 +493,18 @@ impl PageTableManager {
         // Alocar frame para a nova Page Table
         // Se falhar, a huge page original permanece inalterada (rollback implícito)
         let new_pt_phys = allocator.allocate_frame(1)?;
+        self.table_frames.push(new_pt_phys);
 
         // Preencher TODAS as 512 entradas da PT
-        unsafe {
-            let pt = new_pt_phys as *mut [u64; 512];
+        let pt = self.table(new_pt_phys);
 
-            for i in 0..512 {
-                // Calcular endereço físico desta página de 4KiB
-                let page_phys = huge_phys_base + (i as u64 * PAGE_SIZE);
+        for (i, entry) in pt.iter_mut().enumerate() {
+            // Calcular endereço físico desta página de 4KiB
+            let page_phys = huge_phys_base + (i as u64 * PAGE_SIZE);
 
-                // Criar entrada com endereço + flags preservadas
-                // NÃO incluímos PAGE_HUGE (é uma página 4KiB agora)
-                (*pt)[i] = (page_phys & ADDR_MASK) | preserved_flags;
-            }
+            // Criar entrada com endereço + flags preservadas
+            // NÃO incluímos PAGE_HUGE (é uma página 4KiB agora)
+            *entry = (page_phys & ADDR_MASK) | preserved_flags;
         }
 
         // Substituir entrada de huge page pela nova PT
@@ -393,12 +525,44 @@ impl PageTableManager {
     ///
     /// - `phys` e `virt` devem estar alinhados a 4 KiB.
     /// - `pages` é o número de páginas de 4 KiB a mapear.
+    ///
+    /// Inclui `KERNEL_GLOBAL_FLAGS` se `set_global_kernel_pages(true)`.
     pub fn map_kernel(
         &mut self,
         phys: u64,
         virt: u64,
         pages: usize,
         allocator: &mut (impl FrameAllocator + ?Sized),
+    ) -> Result<()> {
+        self.map_kernel_with_flags(phys, virt, pages, self.kernel_flags, allocator)
+    }
+
+    /// Mapeia um segmento do kernel como `map_kernel`, somando
+    /// `KERNEL_NO_EXEC_FLAGS` quando o segmento não é executável e
+    /// `set_enforce_nx(true)`.
+    pub fn map_kernel_segment(
+        &mut self,
+        phys: u64,
+        virt: u64,
+        pages: usize,
+        executable: bool,
+        allocator: &mut (impl FrameAllocator + ?Sized),
+    ) -> Result<()> {
+        let mut flags = self.kernel_flags;
+        if self.enforce_nx && !executable {
+            flags |= KERNEL_NO_EXEC_FLAGS;
+        }
+        self.map_kernel_with_flags(phys, virt, pages, flags, allocator)
+    }
+
+    /// Como `map_kernel`, com `extra_flags` somadas a Present + Writable.
+    pub fn map_kernel_with_flags(
+        &mut self,
+        phys: u64,
+        virt: u64,
+        pages: usize,
+        extra_flags: u64,
+        allocator: &mut (impl FrameAllocator + ?Sized),
     ) -> Result<()> {
         if phys % 4096 != 0 || virt % 4096 != 0 {
             return Err(BootError::Memory(MemoryError::InvalidAlignment));
@@ -411,7 +575,7 @@ impl PageTableManager {
             self.map_page(
                 page_phys,
                 page_virt,
-                PAGE_PRESENT | PAGE_WRITABLE,
+                PAGE_PRESENT | PAGE_WRITABLE | extra_flags,
                 allocator,
             )?;
         }
@@ -438,53 +602,44 @@ impl PageTableManager {
         let pt_idx = ((virt >> 12) & 0x1FF) as usize;
 
         // PML4
-        let pml4 = unsafe { &mut *(self.pml4_phys_addr as *mut [u64; 512]) };
+        let pml4 = self.table(self.pml4_phys_addr);
 
         // PDPT
         let pdpt_addr = if pml4[pml4_idx] & PAGE_PRESENT != 0 {
             pml4[pml4_idx] & ADDR_MASK
         } else {
-            let new_pdpt = allocator.allocate_frame(1)?;
-            unsafe {
-                core::ptr::write_bytes(new_pdpt as *mut u8, 0, 4096);
-            }
+            let new_pdpt = self.allocate_table(allocator)?;
             pml4[pml4_idx] = new_pdpt | PAGE_PRESENT | PAGE_WRITABLE;
             new_pdpt
         };
-        let pdpt = unsafe { &mut *(pdpt_addr as *mut [u64; 512]) };
+        let pdpt = self.table(pdpt_addr);
 
         // PD
         let pd_addr = if pdpt[pdpt_idx] & PAGE_PRESENT != 0 {
             pdpt[pdpt_idx] & ADDR_MASK
         } else {
-            let new_pd = allocator.allocate_frame(1)?;
-            unsafe {
-                core::ptr::write_bytes(new_pd as *mut u8, 0, 4096);
-            }
+            let new_pd = self.allocate_table(allocator)?;
             pdpt[pdpt_idx] = new_pd | PAGE_PRESENT | PAGE_WRITABLE;
             new_pd
         };
-        let pd = unsafe { &mut *(pd_addr as *mut [u64; 512]) };
+        let pd = self.table(pd_addr);
 
         // PT (não queremos uma huge page aqui — garantimos PT normal)
         let pt_addr = if pd[pd_idx] & PAGE_PRESENT != 0 {
             // Se for huge page, precisamos fazer split para páginas 4KiB
             if pd[pd_idx] & PAGE_HUGE != 0 {
                 // Split atômico de huge page → 512 páginas de 4KiB
-                Self::split_huge_page_to_pt(pd, pd_idx, allocator)?
+                self.split_huge_page_to_pt(pd, pd_idx, allocator)?
             } else {
                 pd[pd_idx] & ADDR_MASK
             }
         } else {
-            let new_pt = allocator.allocate_frame(1)?;
-            unsafe {
-                core::ptr::write_bytes(new_pt as *mut u8, 0, 4096);
-            }
+            let new_pt = self.allocate_table(allocator)?;
             pd[pd_idx] = new_pt | PAGE_PRESENT | PAGE_WRITABLE;
             new_pt
         };
 
-        let pt = unsafe { &mut *(pt_addr as *mut [u64; 512]) };
+        let pt = self.table(pt_addr);
 
         // Entrada final: mapear a página
         pt[pt_idx] = (phys & ADDR_MASK) | flags;
@@ -521,46 +676,37 @@ impl PageTableManager {
         let pdpt_idx = ((SCRATCH_VIRT >> 30) & 0x1FF) as usize;
         let pd_idx = ((SCRATCH_VIRT >> 21) & 0x1FF) as usize;
 
-        let pml4 = unsafe { &mut *(self.pml4_phys_addr as *mut [u64; 512]) };
+        let pml4 = self.table(self.pml4_phys_addr);
 
         // PDPT
         let pdpt_addr = if pml4[pml4_idx] & PAGE_PRESENT != 0 {
             pml4[pml4_idx] & ADDR_MASK
         } else {
-            let new_pdpt = allocator.allocate_frame(1)?;
-            unsafe {
-                core::ptr::write_bytes(new_pdpt as *mut u8, 0, 4096);
-            }
+            let new_pdpt = self.allocate_table(allocator)?;
             pml4[pml4_idx] = new_pdpt | PAGE_PRESENT | PAGE_WRITABLE;
             new_pdpt
         };
-        let pdpt = unsafe { &mut *(pdpt_addr as *mut [u64; 512]) };
+        let pdpt = self.table(pdpt_addr);
 
         // PD
         let pd_addr = if pdpt[pdpt_idx] & PAGE_PRESENT != 0 {
             pdpt[pdpt_idx] & ADDR_MASK
         } else {
-            let new_pd = allocator.allocate_frame(1)?;
-            unsafe {
-                core::ptr::write_bytes(new_pd as *mut u8, 0, 4096);
-            }
+            let new_pd = self.allocate_table(allocator)?;
             pdpt[pdpt_idx] = new_pd | PAGE_PRESENT | PAGE_WRITABLE;
             new_pd
         };
-        let pd = unsafe { &mut *(pd_addr as *mut [u64; 512]) };
+        let pd = self.table(pd_addr);
 
         // PT: garantir que existe uma PT (não uma huge page).
         let pt_phys = if pd[pd_idx] & PAGE_PRESENT != 0 {
             if pd[pd_idx] & PAGE_HUGE != 0 {
                 // Huge page precisa ser dividida - usar função de split completo
-                Self::split_huge_page_to_pt(pd, pd_idx, allocator)?;
+                self.split_huge_page_to_pt(pd, pd_idx, allocator)?;
             }
             pd[pd_idx] & ADDR_MASK
         } else {
-            let new_pt = allocator.allocate_frame(1)?;
-            unsafe {
-                core::ptr::write_bytes(new_pt as *mut u8, 0, 4096);
-            }
+            let new_pt = self.allocate_table(allocator)?;
             pd[pd_idx] = new_pt | PAGE_PRESENT | PAGE_WRITABLE;
             new_pt
         };
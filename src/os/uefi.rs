@@ -4,9 +4,12 @@
 //! `src/uefi`. Utiliza `BootServices` para alocar memória física real.
 
 use super::{Os, OsMemoryEntry};
-use crate::uefi::{
-    system_table,
-    table::boot::{AllocateType, MemoryType},
+use crate::{
+    core::align::div_ceil_pages,
+    uefi::{
+        system_table,
+        table::boot::{AllocateType, MemoryType},
+    },
 };
 
 /// Driver do ambiente UEFI.
@@ -16,9 +19,8 @@ impl Os for UefiOs {
     fn alloc_zeroed_page_aligned(&self, size: usize) -> *mut u8 {
         let bs = system_table().boot_services();
 
-        // Calcular número de páginas (4KiB = 0x1000)
-        // (size + 4095) / 4096 faz o arredondamento para cima (ceil)
-        let pages = (size + 0xFFF) / 0x1000;
+        // Calcular número de páginas (4KiB = 0x1000), arredondando para cima
+        let pages = div_ceil_pages(size as u64) as usize;
 
         // Alocar usando UEFI Boot Services
         // Usamos `LoaderData` para indicar que esta memória contém dados do bootloader
@@ -27,6 +29,7 @@ impl Os for UefiOs {
             AllocateType::AllocateAnyPages,
             MemoryType::LoaderData,
             pages,
+            0,
         ) {
             Ok(addr) => {
                 let ptr = addr as *mut u8;
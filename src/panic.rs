@@ -10,6 +10,8 @@
 //! 1. **Diagnóstico:** Imprime o local (Arquivo:Linha) e a mensagem de erro.
 //! 2. **Logging:** Envia para Serial (COM1) para captura remota.
 //! 3. **Halt:** Trava a CPU (`hlt` loop) para preservar o estado da tela/logs.
+//!    Com `panic_reboot: yes`, espera `PANIC_REBOOT_DELAY_US` e reinicia a
+//!    frio.
 //!
 //! ## 🔍 Análise Crítica (Kernel Engineer's View)
 //!
@@ -20,9 +22,8 @@
 //!   final.
 //!
 //! ### ⚠️ Pontos de Atenção (UX & Debug)
-//! - **"Tijolo" Mode:** Atualmente o handler entra em loop infinito. O usuário
-//!   precisa desligar o PC no botão.
-//!   - *Correção:* Deveria esperar uma tecla e reiniciar (Reboot).
+//! - **"Tijolo" Mode:** Por padrão o handler entra em loop infinito. O usuário
+//!   precisa desligar o PC no botão (ou habilitar `panic_reboot`).
 //! - **Sem Backtrace:** Em erros complexos, apenas a linha do panic não é
 //!   suficiente.
 //!   - *Dificuldade:* Implementar stack unwinding em `no_std` é complexo e
@@ -38,9 +39,86 @@
 //!   - *Como:* Ler o estado da CPU (se possível via inline assembly) e
 //!     imprimir.
 
-use core::panic::PanicInfo;
+use core::{
+    panic::PanicInfo,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
-use crate::arch;
+use crate::{
+    arch,
+    uefi::{table::runtime::ResetType, Status},
+};
+
+/// Espera entre a mensagem de panic e o reset (microssegundos).
+pub const PANIC_REBOOT_DELAY_US: usize = 5_000_000;
+
+/// Iterações de `spin_loop` por microssegundo quando não há mais `Stall`
+/// (estimativa grosseira; só precisa dar tempo de ler a tela).
+pub(crate) const SPINS_PER_US: usize = 1_000;
+
+/// Reiniciar após o panic em vez de paralisar (`panic_reboot`).
+static PANIC_REBOOT: AtomicBool = AtomicBool::new(false);
+
+/// Bipe no PC speaker ao entrar no handler (`beep_on_error`).
+static PANIC_BEEP: AtomicBool = AtomicBool::new(false);
+
+/// Define o comportamento após o panic. Chamado logo após ler a configuração.
+pub fn set_panic_reboot(enabled: bool) {
+    PANIC_REBOOT.store(enabled, Ordering::Relaxed);
+}
+
+/// Liga o bipe de erro do handler. Chamado junto com `set_panic_reboot`.
+pub fn set_panic_beep(enabled: bool) {
+    PANIC_BEEP.store(enabled, Ordering::Relaxed);
+}
+
+/// Se o handler reinicia a máquina após o panic.
+pub fn panic_reboot_enabled() -> bool {
+    PANIC_REBOOT.load(Ordering::Relaxed)
+}
+
+/// Serviços do firmware usados para reiniciar após o panic.
+pub trait PanicReset {
+    /// Aguarda `microseconds`.
+    fn delay(&mut self, microseconds: usize);
+
+    /// Reset a frio (a implementação real não retorna).
+    fn reset(&mut self);
+}
+
+/// `PanicReset` sobre os serviços UEFI.
+pub struct FirmwareReset;
+
+impl PanicReset for FirmwareReset {
+    fn delay(&mut self, microseconds: usize) {
+        if crate::uefi::boot_services_active() {
+            crate::uefi::system_table().boot_services().stall(microseconds);
+        } else {
+            for _ in 0..microseconds.saturating_mul(SPINS_PER_US) {
+                core::hint::spin_loop();
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        // Runtime Services continuam válidos depois do ExitBootServices
+        crate::uefi::system_table()
+            .runtime_services()
+            .reset_system(ResetType::Cold, Status::ABORTED);
+    }
+}
+
+/// Espera `PANIC_REBOOT_DELAY_US` e pede o reset se `panic_reboot` estiver
+/// ligado. Retorna `false` sem fazer nada no modo padrão (paralisar).
+pub fn reboot_after_panic<R: PanicReset + ?Sized>(reset: &mut R) -> bool {
+    if !panic_reboot_enabled() {
+        return false;
+    }
+
+    reset.delay(PANIC_REBOOT_DELAY_US);
+    reset.reset();
+    true
+}
 
 pub fn panic_impl(info: &PanicInfo) -> ! {
     crate::println!("\n*** FATAL SYSTEM ERROR ***");
@@ -59,6 +137,19 @@ pub fn panic_impl(info: &PanicInfo) -> ! {
     let msg = info.message();
     crate::println!("Erro:  {}", msg);
 
+    if PANIC_BEEP.load(Ordering::Relaxed) {
+        let (freq, ms) = arch::x86::speaker::ERROR_BEEP;
+        arch::x86::speaker::beep(freq, ms);
+    }
+
+    if panic_reboot_enabled() {
+        crate::println!(
+            "Reiniciando em {} segundos...",
+            PANIC_REBOOT_DELAY_US / 1_000_000
+        );
+        reboot_after_panic(&mut FirmwareReset);
+    }
+
     crate::println!("Sistema paralisado.");
     loop {
         arch::hlt();
@@ -0,0 +1,210 @@
+//! Arquitetura Declarada pelo Kernel
+//!
+//! Um ESP com imagens de várias arquiteturas permite escolher, por engano, um
+//! kernel aarch64 num PC. Em vez de saltar para código que a CPU não entende,
+//! `check_kernel_arch` compara a arquitetura declarada no binário com a do
+//! próprio Ignite (fixada na compilação) e recusa a entrada com um erro que
+//! volta ao menu.
+//!
+//! A arquitetura vem do campo que cada formato reserva para isso:
+//! - **ELF** (Redstone/Limine): `e_machine`.
+//! - **Linux**: Setup Header "HdrS" (x86) ou o magic do `Image` de ARM64 e
+//!   RISC-V.
+//! - **Multiboot2**: `architecture` do cabeçalho.
+//! - **PE/COFF** (chainload): `Machine` do cabeçalho COFF.
+
+use core::fmt;
+
+use crate::{
+    config::Protocol,
+    core::error::{BootError, ProtocolError, Result},
+};
+
+/// Arquitetura de um kernel ou da CPU em que o Ignite roda.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KernelArch {
+    /// x86 de 32 bits (i386).
+    X86,
+    X86_64,
+    /// ARM de 32 bits.
+    Arm,
+    Aarch64,
+    RiscV64,
+    Mips,
+    /// Código de máquina sem correspondente acima (`e_machine`, `Machine`...).
+    Other(u32),
+}
+
+impl KernelArch {
+    /// Nome curto, como em triplas de compilação.
+    pub const fn name(self) -> &'static str {
+        match self {
+            KernelArch::X86 => "i386",
+            KernelArch::X86_64 => "x86_64",
+            KernelArch::Arm => "arm",
+            KernelArch::Aarch64 => "aarch64",
+            KernelArch::RiscV64 => "riscv64",
+            KernelArch::Mips => "mips",
+            KernelArch::Other(_) => "desconhecida",
+        }
+    }
+
+    /// Um kernel desta arquitetura roda em `cpu`? Kernels i386 (ex:
+    /// Multiboot2) entram em modo protegido numa CPU x86_64.
+    pub fn runs_on(self, cpu: KernelArch) -> bool {
+        self == cpu || (self == KernelArch::X86 && cpu == KernelArch::X86_64)
+    }
+}
+
+impl fmt::Display for KernelArch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KernelArch::Other(code) => write!(f, "desconhecida ({:#x})", code),
+            arch => f.write_str(arch.name()),
+        }
+    }
+}
+
+/// Arquitetura para a qual o Ignite foi compilado (a da CPU em execução).
+pub const fn host_arch() -> KernelArch {
+    if cfg!(target_arch = "x86_64") {
+        KernelArch::X86_64
+    } else if cfg!(target_arch = "x86") {
+        KernelArch::X86
+    } else if cfg!(target_arch = "aarch64") {
+        KernelArch::Aarch64
+    } else if cfg!(target_arch = "riscv64") {
+        KernelArch::RiscV64
+    } else {
+        KernelArch::Other(0)
+    }
+}
+
+// Códigos `e_machine` (ELF).
+const EM_386: u16 = 3;
+const EM_MIPS: u16 = 8;
+const EM_ARM: u16 = 40;
+const EM_X86_64: u16 = 62;
+const EM_AARCH64: u16 = 183;
+const EM_RISCV: u16 = 243;
+
+// Códigos `Machine` (PE/COFF).
+const IMAGE_FILE_MACHINE_I386: u16 = 0x014C;
+const IMAGE_FILE_MACHINE_ARMNT: u16 = 0x01C4;
+const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+const IMAGE_FILE_MACHINE_ARM64: u16 = 0xAA64;
+const IMAGE_FILE_MACHINE_RISCV64: u16 = 0x5064;
+
+/// Magic do `Image` de ARM64 ("ARM\x64" em 0x38) e de RISC-V ("RSC\x05" em
+/// 0x34), ver `Documentation/arch/*/booting.rst` do Linux.
+const ARM64_IMAGE_MAGIC: (usize, &[u8; 4]) = (0x38, b"ARM\x64");
+const RISCV_IMAGE_MAGIC: (usize, &[u8; 4]) = (0x34, b"RSC\x05");
+
+/// Multiboot2: magic do cabeçalho e área onde ele pode estar.
+const MB2_HEADER_MAGIC: u32 = 0xE852_50D6;
+const MB2_SEARCH_LIMIT: usize = 32 * 1024;
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn has_magic(data: &[u8], (offset, magic): (usize, &[u8; 4])) -> bool {
+    data.get(offset..offset + 4) == Some(&magic[..])
+}
+
+/// `e_machine` de um ELF.
+fn elf_arch(kernel_file: &[u8]) -> Option<KernelArch> {
+    if !kernel_file.starts_with(b"\x7fELF") {
+        return None;
+    }
+    Some(match read_u16(kernel_file, 18)? {
+        EM_386 => KernelArch::X86,
+        EM_MIPS => KernelArch::Mips,
+        EM_ARM => KernelArch::Arm,
+        EM_X86_64 => KernelArch::X86_64,
+        EM_AARCH64 => KernelArch::Aarch64,
+        EM_RISCV => KernelArch::RiscV64,
+        other => KernelArch::Other(other as u32),
+    })
+}
+
+/// `Machine` do cabeçalho COFF de uma imagem PE.
+fn pe_arch(kernel_file: &[u8]) -> Option<KernelArch> {
+    if !kernel_file.starts_with(b"MZ") {
+        return None;
+    }
+    let pe_offset = read_u32(kernel_file, 0x3C)? as usize;
+    if kernel_file.get(pe_offset..pe_offset.checked_add(4)?) != Some(&b"PE\0\0"[..]) {
+        return None;
+    }
+    Some(match read_u16(kernel_file, pe_offset + 4)? {
+        IMAGE_FILE_MACHINE_I386 => KernelArch::X86,
+        IMAGE_FILE_MACHINE_ARMNT => KernelArch::Arm,
+        IMAGE_FILE_MACHINE_AMD64 => KernelArch::X86_64,
+        IMAGE_FILE_MACHINE_ARM64 => KernelArch::Aarch64,
+        IMAGE_FILE_MACHINE_RISCV64 => KernelArch::RiscV64,
+        other => KernelArch::Other(other as u32),
+    })
+}
+
+/// Imagem Linux: `Image` de ARM64/RISC-V ou bzImage (x86).
+fn linux_arch(kernel_file: &[u8]) -> Option<KernelArch> {
+    if has_magic(kernel_file, ARM64_IMAGE_MAGIC) {
+        Some(KernelArch::Aarch64)
+    } else if has_magic(kernel_file, RISCV_IMAGE_MAGIC) {
+        Some(KernelArch::RiscV64)
+    } else if super::linux::is_bzimage(kernel_file) {
+        Some(KernelArch::X86_64)
+    } else {
+        None
+    }
+}
+
+/// `architecture` do cabeçalho Multiboot2 (alinhado a 8 bytes nos primeiros
+/// 32 KiB).
+fn multiboot2_arch(kernel_file: &[u8]) -> Option<KernelArch> {
+    let limit = kernel_file.len().min(MB2_SEARCH_LIMIT);
+    (0..limit)
+        .step_by(8)
+        .find(|&offset| read_u32(kernel_file, offset) == Some(MB2_HEADER_MAGIC))
+        .and_then(|offset| read_u32(kernel_file, offset + 4))
+        .map(|arch| match arch {
+            0 => KernelArch::X86,
+            4 => KernelArch::Mips,
+            other => KernelArch::Other(other),
+        })
+}
+
+/// Arquitetura declarada por `kernel_file` no formato de `protocol`, ou
+/// `None` se o binário não a informa.
+pub fn kernel_arch(protocol: Protocol, kernel_file: &[u8]) -> Option<KernelArch> {
+    match protocol {
+        Protocol::Redstone | Protocol::Limine => elf_arch(kernel_file),
+        Protocol::Linux => linux_arch(kernel_file).or_else(|| pe_arch(kernel_file)),
+        Protocol::Multiboot2 => multiboot2_arch(kernel_file).or_else(|| elf_arch(kernel_file)),
+        Protocol::EfiChainload => pe_arch(kernel_file),
+        Protocol::Unknown => None,
+    }
+}
+
+/// Recusa `kernel_file` se ele declara uma arquitetura que não roda nesta
+/// CPU (`host_arch`). Binários sem arquitetura reconhecível passam: o
+/// loader de cada protocolo valida o formato.
+pub fn check_kernel_arch(protocol: Protocol, kernel_file: &[u8]) -> Result<()> {
+    let cpu = host_arch();
+    match kernel_arch(protocol, kernel_file) {
+        Some(kernel) if !kernel.runs_on(cpu) => {
+            Err(BootError::Protocol(ProtocolError::ArchMismatch {
+                kernel,
+                cpu,
+            }))
+        },
+        _ => Ok(()),
+    }
+}
@@ -35,8 +35,8 @@ impl BootProtocol for ChainloadProtocol {
         // erro).
 
         // TODO: Implementar usando uefi::boot_services().load_image()
-        Err(crate::core::error::BootError::Generic(
-            "Chainload deve ser executado diretamente",
+        Err(crate::core::error::BootError::Protocol(
+            crate::core::error::ProtocolError::NoNativeLoader,
         ))
     }
 }
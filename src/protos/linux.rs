@@ -8,7 +8,7 @@ use alloc::vec::Vec;
 use super::{BootProtocol, KernelLaunchInfo};
 use crate::{
     core::{
-        error::{BootError, Result},
+        error::{BootError, ProtocolError, Result},
         types::LoadedFile,
     },
     memory::FrameAllocator,
@@ -33,6 +33,20 @@ struct LinuxSetupHeader {
                        * ... restante dos campos omitidos para brevidade do check ... */
 }
 
+/// Se `file_content` traz o Setup Header de um bzImage ("HdrS" em 0x202).
+pub fn is_bzimage(file_content: &[u8]) -> bool {
+    // Verifica tamanho mínimo
+    if file_content.len() < 0x202 + 4 {
+        return false;
+    }
+
+    // O offset 0x202 é fixo no protocolo de boot Linux x86
+    let magic_slice = &file_content[0x202..0x206];
+    let magic = u32::from_le_bytes(magic_slice.try_into().unwrap_or([0; 4]));
+
+    magic == LINUX_MAGIC
+}
+
 pub struct LinuxProtocol<'a> {
     #[allow(dead_code)]
     allocator: &'a mut dyn FrameAllocator,
@@ -50,17 +64,7 @@ impl<'a> BootProtocol for LinuxProtocol<'a> {
     }
 
     fn identify(&self, file_content: &[u8]) -> bool {
-        // Verifica tamanho mínimo
-        if file_content.len() < 0x202 + 4 {
-            return false;
-        }
-
-        // Verifica assinatura "HdrS" no offset 0x202
-        // O offset 0x202 é fixo no protocolo de boot Linux x86
-        let magic_slice = &file_content[0x202..0x206];
-        let magic = u32::from_le_bytes(magic_slice.try_into().unwrap_or([0; 4]));
-
-        magic == LINUX_MAGIC
+        is_bzimage(file_content)
     }
 
     fn load(
@@ -73,6 +77,6 @@ impl<'a> BootProtocol for LinuxProtocol<'a> {
     ) -> Result<KernelLaunchInfo> {
         // Implementação real seria aqui (parsing do setup header, alocação da zero
         // page, etc.)
-        Err(BootError::Generic("Linux boot ainda não implementado"))
+        Err(BootError::Protocol(ProtocolError::NotImplemented))
     }
 }
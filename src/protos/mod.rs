@@ -5,8 +5,15 @@
 
 use alloc::vec::Vec;
 
-use crate::core::{error::Result, types::LoadedFile};
+use crate::{
+    config::Protocol,
+    core::{
+        error::{BootError, ProtocolError, Result},
+        types::LoadedFile,
+    },
+};
 
+pub mod arch;
 pub mod chainload;
 pub mod linux;
 pub mod multiboot2;
@@ -62,60 +69,85 @@ pub trait BootProtocol {
     ) -> Result<KernelLaunchInfo>;
 }
 
-/// Tenta detectar e carregar um kernel usando todos os protocolos disponíveis.
+/// Adivinha o protocolo pela assinatura do binário.
+///
+/// A ordem importa: um bzImage com EFI stub também começa com "MZ".
+pub fn detect(kernel_file: &[u8]) -> Protocol {
+    if kernel_file.starts_with(b"\x7fELF") {
+        Protocol::Redstone
+    } else if linux::is_bzimage(kernel_file) {
+        Protocol::Linux
+    } else if kernel_file.starts_with(b"MZ") {
+        Protocol::EfiChainload
+    } else {
+        Protocol::Unknown
+    }
+}
+
+/// Decide o protocolo de uma entrada.
+///
+/// O `protocol:` explícito sempre vence; a detecção só é usada quando ele
+/// está ausente e `protocol_auto_detect` está ligado.
+pub fn resolve_protocol(requested: Protocol, kernel_file: &[u8], auto_detect: bool) -> Result<Protocol> {
+    if requested != Protocol::Unknown {
+        return Ok(requested);
+    }
+    if !auto_detect {
+        return Err(BootError::Protocol(ProtocolError::AutoDetectDisabled));
+    }
+    match detect(kernel_file) {
+        Protocol::Unknown => Err(BootError::Protocol(ProtocolError::UnknownFormat)),
+        protocol => Ok(protocol),
+    }
+}
+
+/// Carrega o kernel com o protocolo já decidido por `resolve_protocol`.
+///
+/// `options` só têm efeito nos protocolos com `BootInfo` (Redstone e
+/// Limine); nos demais, cada opção ligada gera um aviso.
+#[allow(clippy::too_many_arguments)]
 pub fn load_any(
     allocator: &mut dyn crate::memory::FrameAllocator, // FIX: dyn trait object
     page_table: &mut crate::memory::PageTableManager,
+    protocol: Protocol,
+    options: redstone::RedstoneOptions<'_>,
     kernel_file: &[u8],
     cmdline: Option<&str>,
     modules: Vec<LoadedFile>,
     memory_map_buffer: (u64, u64), // (ponteiro, contagem)
     framebuffer: Option<crate::core::handoff::FramebufferInfo>,
 ) -> Result<KernelLaunchInfo> {
-    // Lista de protocolos suportados
-    // Nota: Em um sistema real, você instanciaria isso de forma mais dinâmica
-    // ou passaria as dependências (alocador) via construtor.
-
-    // 1. Tentar Protocolo Nativo (Redstone/ELF)
-    let mut redstone = redstone::RedstoneProtocol::new(allocator, page_table);
-    if redstone.identify(kernel_file) {
-        crate::println!("[OK] Detectado Kernel Redstone/ELF.");
-        return redstone.load(
-            kernel_file,
-            cmdline,
-            modules,
-            memory_map_buffer,
-            framebuffer,
-        );
+    if !matches!(protocol, Protocol::Redstone | Protocol::Limine) {
+        for option in options.customized() {
+            log::warn!("{} ignorado: protocolo {:?} sem BootInfo", option, protocol);
+        }
     }
 
-    // 2. Tentar Linux
-    let mut linux = linux::LinuxProtocol::new(allocator);
-    if linux.identify(kernel_file) {
-        crate::println!("Detectado Kernel Linux (bzImage).");
-        return linux.load(
-            kernel_file,
-            cmdline,
-            modules,
-            memory_map_buffer,
-            framebuffer,
-        );
-    }
+    let mut loader: alloc::boxed::Box<dyn BootProtocol + '_> = match protocol {
+        // Limine usa o mesmo caminho ELF do protocolo nativo
+        Protocol::Redstone | Protocol::Limine => alloc::boxed::Box::new(
+            redstone::RedstoneProtocol::new(allocator, page_table).with_options(options),
+        ),
+        Protocol::Linux => alloc::boxed::Box::new(linux::LinuxProtocol::new(allocator)),
+        Protocol::Multiboot2 => {
+            alloc::boxed::Box::new(multiboot2::Multiboot2Protocol::new(allocator))
+        },
+        // Chainload é tratado antes, via LoadImage/StartImage
+        Protocol::EfiChainload | Protocol::Unknown => {
+            return Err(BootError::Protocol(ProtocolError::NoNativeLoader));
+        },
+    };
 
-    // 3. Tentar Multiboot2
-    let mut mb2 = multiboot2::Multiboot2Protocol::new(allocator);
-    if mb2.identify(kernel_file) {
-        crate::println!("Detectado Kernel Multiboot2.");
-        return mb2.load(
-            kernel_file,
-            cmdline,
-            modules,
-            memory_map_buffer,
-            framebuffer,
-        );
+    if !loader.identify(kernel_file) {
+        log::warn!("Kernel nao parece {}; usando mesmo assim", loader.name());
     }
+    crate::println!("Protocolo: {}", loader.name());
 
-    Err(crate::core::error::BootError::Generic(
-        "Formato de kernel desconhecido",
-    ))
+    loader.load(
+        kernel_file,
+        cmdline,
+        modules,
+        memory_map_buffer,
+        framebuffer,
+    )
 }
@@ -7,7 +7,7 @@ use alloc::vec::Vec;
 use super::{BootProtocol, KernelLaunchInfo};
 use crate::{
     core::{
-        error::{BootError, Result},
+        error::{BootError, ProtocolError, Result},
         types::LoadedFile,
     },
     memory::FrameAllocator,
@@ -47,6 +47,6 @@ impl<'a> BootProtocol for Multiboot2Protocol<'a> {
         _memory_map_buffer: (u64, u64),
         _framebuffer: Option<crate::core::handoff::FramebufferInfo>,
     ) -> Result<KernelLaunchInfo> {
-        Err(BootError::Generic("Multiboot2 ainda não implementado"))
+        Err(BootError::Protocol(ProtocolError::NotImplemented))
     }
 }
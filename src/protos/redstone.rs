@@ -52,19 +52,102 @@
 //!
 //! ------------------------------------------------------------------------------
 
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
+
+use goblin::elf::{program_header::PT_LOAD, Elf};
 
 use super::{BootProtocol, KernelLaunchInfo};
 use crate::{
+    config::{Entry, MapMode},
     core::{
-        error::Result,
-        handoff::{BootInfo, FramebufferInfo},
+        align::{align_down, align_up, div_ceil_pages},
+        error::{BootError, ElfError, Result},
+        handoff::{
+            BootInfo, FramebufferInfo, HandoffBlock, MemoryMapEntry, MemoryType, ModuleInfo,
+            TextConsoleInfo, MAP_MODE_DIRECT, MAP_MODE_IDENTITY,
+        },
         types::LoadedFile,
     },
     elf::ElfLoader,
-    memory::{FrameAllocator, PageTableManager},
+    memory::{
+        layout::{OverlapChecker, PAGE_SIZE},
+        map::max_usable_phys,
+        region::{MemoryRegionKind, PhysicalMemoryRegion},
+        FrameAllocator, PageTableManager,
+    },
+    uefi::{
+        proto::loaded_image::{LoadedImageProtocol, LOADED_IMAGE_PROTOCOL_GUID},
+        table::{boot::OPEN_PROTOCOL_GET_PROTOCOL, system::SimpleTextOutputProtocol},
+        Handle,
+    },
 };
 
+/// Base do Higher Half Direct Map: a memória física inteira aparece a partir
+/// daqui.
+pub const HHDM_BASE: u64 = 0xFFFF_8000_0000_0000;
+
+/// Endereço pelo qual o kernel enxerga o endereço físico `phys` logo após o
+/// salto: o próprio `phys` com identity map, senão o alias no HHDM
+/// (`MapMode::Direct`, onde a metade inferior não está mapeada).
+pub fn kernel_visible_addr(map_mode: MapMode, phys: u64) -> u64 {
+    if map_mode.identity() {
+        phys
+    } else {
+        HHDM_BASE + phys
+    }
+}
+
+/// Endereço para onde o trampolim salta no protocolo Redstone, fixado pelo
+/// linker script do Forge (independe do `e_entry` do ELF).
+pub const REDSTONE_KERNEL_ENTRY: u64 = 0xFFFF_FFFF_8000_0000;
+
+/// Opções de uma entrada que só chegam ao kernel pelo `BootInfo` (protocolos
+/// Redstone e Limine).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RedstoneOptions<'a> {
+    /// Chave `load_addr`.
+    pub load_addr:         Option<u64>,
+    /// Chave `pass_system_table`.
+    pub pass_system_table: bool,
+    /// Chave `pass_text_console`.
+    pub pass_text_console: bool,
+    /// Chave `map_mode`.
+    pub map_mode:          MapMode,
+    /// Pares `param`.
+    pub params:            &'a [(String, String)],
+    /// Saídas de vídeo além do framebuffer primário.
+    pub framebuffers:      &'a [FramebufferInfo],
+}
+
+impl<'a> RedstoneOptions<'a> {
+    /// Opções de `entry`, com as saídas de vídeo extras já ativadas.
+    pub fn from_entry(entry: &'a Entry, framebuffers: &'a [FramebufferInfo]) -> Self {
+        Self {
+            load_addr: entry.load_addr,
+            pass_system_table: entry.pass_system_table,
+            pass_text_console: entry.pass_text_console,
+            map_mode: entry.map_mode,
+            params: &entry.params,
+            framebuffers,
+        }
+    }
+
+    /// Nomes das opções fora do padrão, para avisar quando o protocolo
+    /// escolhido não tem `BootInfo` para entregá-las.
+    pub fn customized(&self) -> impl Iterator<Item = &'static str> {
+        [
+            ("load_addr", self.load_addr.is_some()),
+            ("pass_system_table", self.pass_system_table),
+            ("pass_text_console", self.pass_text_console),
+            ("map_mode", self.map_mode != MapMode::Both),
+            ("param", !self.params.is_empty()),
+            ("saidas de video extras", !self.framebuffers.is_empty()),
+        ]
+        .into_iter()
+        .filter_map(|(name, set)| set.then_some(name))
+    }
+}
+
 /// Implementa o protocolo de boot "nativo" do Redstone.
 ///
 /// `RedstoneProtocol` encapsula os recursos necessários para preparar o
@@ -85,8 +168,18 @@ use crate::{
 ///   estruturas diretamente em memória física); mantenha as invariantes e
 ///   documente TODOs.
 pub struct RedstoneProtocol<'a> {
-    allocator:  &'a mut dyn FrameAllocator,
-    page_table: &'a mut PageTableManager,
+    allocator:         &'a mut dyn FrameAllocator,
+    page_table:        &'a mut PageTableManager,
+    load_addr:         Option<u64>,
+    system_table_addr: u64,
+    params:            &'a [(String, String)],
+    /// Saídas de vídeo além do framebuffer primário.
+    framebuffers:      &'a [FramebufferInfo],
+    text_console:      TextConsoleInfo,
+    map_mode:          MapMode,
+    /// Imagem do bootloader (`(base, tamanho)`), mantida em identity map com
+    /// `MapMode::Direct` para o código do salto sobreviver à troca de CR3.
+    trampoline:        (u64, u64),
 }
 
 impl<'a> RedstoneProtocol<'a> {
@@ -108,9 +201,83 @@ impl<'a> RedstoneProtocol<'a> {
         Self {
             allocator,
             page_table,
+            load_addr: None,
+            system_table_addr: 0,
+            params: &[],
+            framebuffers: &[],
+            text_console: TextConsoleInfo::default(),
+            map_mode: MapMode::Both,
+            trampoline: (0, 0),
         }
     }
 
+    /// Aplica todas as `options` da entrada (ver os `with_*` abaixo).
+    pub fn with_options(self, options: RedstoneOptions<'a>) -> Self {
+        self.with_load_addr(options.load_addr)
+            .with_system_table(options.pass_system_table)
+            .with_text_console(options.pass_text_console)
+            .with_map_mode(options.map_mode)
+            .with_params(options.params)
+            .with_framebuffers(options.framebuffers)
+    }
+
+    /// Carrega o kernel no endereço físico `load_addr` (chave `load_addr` da
+    /// entrada) em vez de onde o alocador escolher.
+    pub fn with_load_addr(mut self, load_addr: Option<u64>) -> Self {
+        self.load_addr = load_addr;
+        self
+    }
+
+    /// Entrega a EFI System Table global ao kernel se `pass_system_table`
+    /// (chave da entrada) estiver ligado.
+    pub fn with_system_table(mut self, pass_system_table: bool) -> Self {
+        self.system_table_addr =
+            efi_system_table_addr(pass_system_table, crate::uefi::system_table_addr());
+        self
+    }
+
+    /// Captura o estado do ConOut para o kernel se `pass_text_console` (chave
+    /// da entrada) estiver ligado. Precisa rodar antes do `ExitBootServices`.
+    pub fn with_text_console(mut self, pass_text_console: bool) -> Self {
+        if pass_text_console {
+            self.text_console =
+                unsafe { capture_text_console(crate::uefi::system_table().con_out) };
+        }
+        self
+    }
+
+    /// Escolhe os mapeamentos da memória física (chave `map_mode`).
+    ///
+    /// `MapMode::Direct` precisa da imagem do bootloader em identity map; se
+    /// o LoadedImage não estiver disponível, volta para `MapMode::Both`.
+    pub fn with_map_mode(mut self, map_mode: MapMode) -> Self {
+        self.map_mode = map_mode;
+        if map_mode == MapMode::Direct {
+            match loader_image_range() {
+                Some(range) => self.trampoline = range,
+                None => {
+                    log::warn!("map_mode direct sem LoadedImage; usando both");
+                    self.map_mode = MapMode::Both;
+                },
+            }
+        }
+        self
+    }
+
+    /// Repassa ao kernel os pares `param` da entrada
+    /// (`BootInfo::params_addr`).
+    pub fn with_params(mut self, params: &'a [(String, String)]) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Repassa ao kernel outros displays além do framebuffer primário
+    /// (`BootInfo::framebuffers_addr`).
+    pub fn with_framebuffers(mut self, framebuffers: &'a [FramebufferInfo]) -> Self {
+        self.framebuffers = framebuffers;
+        self
+    }
+
     /// Prepara informações do framebuffer.
     ///
     /// Atualmente é um *stub seguro* que retorna um `FramebufferInfo` neutro.
@@ -131,6 +298,7 @@ impl<'a> RedstoneProtocol<'a> {
             height: 0,
             stride: 0,
             format: crate::core::handoff::PixelFormat::Rgb,
+            masks:  Default::default(),
         }
     }
 
@@ -169,6 +337,322 @@ impl<'a> RedstoneProtocol<'a> {
     }
 }
 
+/// Lê as dimensões do modo de texto atual e a posição do cursor de `con_out`.
+///
+/// Retorna tudo zerado se o protocolo for nulo ou `QueryMode` falhar.
+///
+/// # Safety
+/// `con_out` deve ser nulo ou apontar para um protocolo válido do firmware.
+pub unsafe fn capture_text_console(con_out: *mut SimpleTextOutputProtocol) -> TextConsoleInfo {
+    if con_out.is_null() || (*con_out).mode.is_null() {
+        return TextConsoleInfo::default();
+    }
+    let mode = &*(*con_out).mode;
+    let Ok(mode_number) = usize::try_from(mode.mode) else {
+        return TextConsoleInfo::default();
+    };
+
+    let mut columns = 0usize;
+    let mut rows = 0usize;
+    if ((*con_out).query_mode)(con_out, mode_number, &mut columns, &mut rows).is_error() {
+        return TextConsoleInfo::default();
+    }
+
+    TextConsoleInfo {
+        columns:       columns as u32,
+        rows:          rows as u32,
+        cursor_column: mode.cursor_column.max(0) as u32,
+        cursor_row:    mode.cursor_row.max(0) as u32,
+    }
+}
+
+/// Base e tamanho da imagem do bootloader em memória, via LoadedImage.
+fn loader_image_range() -> Option<(u64, u64)> {
+    let image = crate::uefi::image_handle();
+    let ptr = crate::uefi::system_table()
+        .boot_services()
+        .open_protocol(
+            image,
+            &LOADED_IMAGE_PROTOCOL_GUID,
+            image,
+            Handle::null(),
+            OPEN_PROTOCOL_GET_PROTOCOL,
+        )
+        .ok()?;
+    let loaded_image = unsafe { &*(ptr as *const LoadedImageProtocol) };
+    if loaded_image.image_base.is_null() || loaded_image.image_size == 0 {
+        return None;
+    }
+    Some((loaded_image.image_base as u64, loaded_image.image_size))
+}
+
+/// Monta os mapeamentos da memória física `[0, map_limit)` pedidos por
+/// `mode`.
+///
+/// Sem identity map completo (`MapMode::Direct`), só as páginas de
+/// `trampoline` (`(base, tamanho)`) ficam em virtual == físico: é onde roda
+/// o código entre a troca de CR3 e o salto para o kernel.
+pub fn map_physical_memory(
+    page_table: &mut PageTableManager,
+    allocator: &mut (impl FrameAllocator + ?Sized),
+    mode: MapMode,
+    map_limit: u64,
+    trampoline: (u64, u64),
+) -> Result<()> {
+    page_table.set_identity_pages(mode.identity());
+
+    if mode.identity() {
+        page_table.identity_map_range(map_limit, allocator)?;
+    } else if trampoline.1 != 0 {
+        let start = align_down(trampoline.0, PAGE_SIZE);
+        let pages = div_ceil_pages(trampoline.0 + trampoline.1 - start);
+        page_table.identity_map(start, pages as usize, allocator)?;
+    }
+
+    if mode.direct() {
+        page_table.map_hhdm(map_limit, HHDM_BASE, allocator)?;
+    }
+
+    Ok(())
+}
+
+/// Confere que algum segmento `PT_LOAD` de `kernel_file` cobre
+/// `REDSTONE_KERNEL_ENTRY`.
+///
+/// O salto do protocolo é para o endereço fixo: sem um segmento ali, o
+/// kernel saltaria para memória não mapeada e a máquina reiniciaria sem
+/// nenhuma mensagem.
+pub fn check_fixed_entry(kernel_file: &[u8]) -> Result<()> {
+    let elf = Elf::parse(kernel_file).map_err(|_| BootError::Elf(ElfError::ParseError))?;
+    let covered = elf.program_headers.iter().any(|ph| {
+        ph.p_type == PT_LOAD
+            && ph.p_vaddr <= REDSTONE_KERNEL_ENTRY
+            && REDSTONE_KERNEL_ENTRY - ph.p_vaddr < ph.p_memsz
+    });
+
+    if !covered {
+        log::error!(
+            "Redstone: nenhum segmento cobre a entrada fixa {:#x} (e_entry = {:#x})",
+            REDSTONE_KERNEL_ENTRY,
+            elf.entry
+        );
+        return Err(BootError::Elf(ElfError::InvalidEntryPoint));
+    }
+    Ok(())
+}
+
+/// Valor de `BootInfo::map_mode` para `mode`.
+pub fn map_mode_bits(mode: MapMode) -> u32 {
+    let mut bits = 0;
+    if mode.identity() {
+        bits |= MAP_MODE_IDENTITY;
+    }
+    if mode.direct() {
+        bits |= MAP_MODE_DIRECT;
+    }
+    bits
+}
+
+/// Valor de `BootInfo::efi_system_table_addr`: o endereço da System Table
+/// quando a entrada pede (`pass_system_table`), 0 caso contrário.
+pub fn efi_system_table_addr(pass_system_table: bool, system_table_addr: u64) -> u64 {
+    if pass_system_table {
+        system_table_addr
+    } else {
+        0
+    }
+}
+
+/// Entradas do mapa de memória do handoff (`(endereço, contagem)`).
+fn map_entries(memory_map_buffer: (u64, u64)) -> &'static [MemoryMapEntry] {
+    let (map_addr, entry_count) = memory_map_buffer;
+    if map_addr == 0 || entry_count == 0 {
+        return &[];
+    }
+
+    unsafe { core::slice::from_raw_parts(map_addr as *const MemoryMapEntry, entry_count as usize) }
+}
+
+/// Converte o mapa de memória do handoff em regiões, para as verificações de
+/// `memory::map`. Só `Usable` é memória livre; o resto conta como reservado.
+fn memory_regions(memory_map_buffer: (u64, u64)) -> Vec<PhysicalMemoryRegion> {
+    map_entries(memory_map_buffer)
+        .iter()
+        .map(|entry| PhysicalMemoryRegion {
+            start:      entry.base,
+            page_count: (entry.len / PAGE_SIZE) as usize,
+            kind:       if entry.typ == MemoryType::Usable {
+                MemoryRegionKind::Usable
+            } else {
+                MemoryRegionKind::Reserved
+            },
+        })
+        .collect()
+}
+
+/// Copia a linha de comando para frames físicos recém-alocados.
+///
+/// A string é gravada terminada em NUL, no início de uma região alinhada à
+/// página. Retorna `(endereço, comprimento sem o NUL)`; uma linha de comando
+/// ausente ou vazia resulta em `(0, 0)` e não aloca nada.
+pub fn stage_cmdline(
+    allocator: &mut dyn FrameAllocator,
+    cmdline: Option<&str>,
+) -> Result<(u64, u64)> {
+    match cmdline {
+        Some(s) if !s.is_empty() => Ok((stage_string(allocator, s)?, s.len() as u64)),
+        _ => Ok((0, 0)),
+    }
+}
+
+/// Grava o nome do bootloader (`meta::LOADER_NAME`) em um frame próprio e
+/// retorna seu endereço físico.
+pub fn stage_loader_name(allocator: &mut dyn FrameAllocator) -> Result<u64> {
+    stage_string(allocator, crate::core::config::meta::LOADER_NAME)
+}
+
+/// Monta o array de `ModuleInfo` entregue em `BootInfo::modules_addr`.
+///
+/// A cmdline de cada módulo é copiada como em `stage_cmdline`, e o array vai
+/// para frames próprios. Retorna `(endereço do array, número de módulos)`;
+/// sem módulos, `(0, 0)`.
+pub fn stage_modules(
+    allocator: &mut dyn FrameAllocator,
+    modules: &[LoadedFile],
+) -> Result<(u64, u64)> {
+    if modules.is_empty() {
+        return Ok((0, 0));
+    }
+
+    let mut records = Vec::with_capacity(modules.len());
+    for module in modules {
+        let (cmdline_addr, cmdline_len) = stage_cmdline(allocator, module.cmdline.as_deref())?;
+        records.push(ModuleInfo {
+            addr: module.ptr,
+            size: module.size as u64,
+            cmdline_addr,
+            cmdline_len,
+        });
+    }
+
+    let bytes = records.len() * core::mem::size_of::<ModuleInfo>();
+    let addr = allocator.allocate_frame(div_ceil_pages(bytes as u64) as usize)?;
+
+    // SAFETY: os frames recém-alocados cobrem `bytes`, e frames são
+    // alinhados à página (mais que o alinhamento de `ModuleInfo`).
+    unsafe {
+        core::ptr::copy_nonoverlapping(records.as_ptr(), addr as *mut ModuleInfo, records.len());
+    }
+
+    Ok((addr, records.len() as u64))
+}
+
+/// Monta o array de `FramebufferInfo` entregue em
+/// `BootInfo::framebuffers_addr`: `primary` primeiro, depois as saídas de
+/// `extra` com framebuffer próprio (endereços nulos ou repetidos ficam de
+/// fora). Retorna `(endereço do array, número de saídas)`; sem nenhuma,
+/// `(0, 0)`.
+pub fn stage_framebuffers(
+    allocator: &mut dyn FrameAllocator,
+    primary: FramebufferInfo,
+    extra: &[FramebufferInfo],
+) -> Result<(u64, u64)> {
+    let mut records: Vec<FramebufferInfo> = Vec::with_capacity(extra.len() + 1);
+    for fb in core::iter::once(&primary).chain(extra) {
+        if fb.addr != 0 && records.iter().all(|r| r.addr != fb.addr) {
+            records.push(*fb);
+        }
+    }
+    if records.is_empty() {
+        return Ok((0, 0));
+    }
+
+    let bytes = core::mem::size_of_val(records.as_slice());
+    let addr = allocator.allocate_frame(div_ceil_pages(bytes as u64) as usize)?;
+
+    // SAFETY: os frames recém-alocados cobrem `bytes` e são alinhados à página.
+    unsafe {
+        core::ptr::copy_nonoverlapping(
+            records.as_ptr(),
+            addr as *mut FramebufferInfo,
+            records.len(),
+        );
+    }
+
+    Ok((addr, records.len() as u64))
+}
+
+/// Serializa `params` como `chave\0valor\0`, par após par, na ordem dada.
+pub fn serialize_params(params: &[(String, String)]) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    for (key, value) in params {
+        buffer.extend_from_slice(key.as_bytes());
+        buffer.push(0);
+        buffer.extend_from_slice(value.as_bytes());
+        buffer.push(0);
+    }
+    buffer
+}
+
+/// Grava `serialize_params(params)` em frames próprios. Retorna `(endereço,
+/// número de pares)`; sem parâmetros, `(0, 0)`.
+pub fn stage_params(
+    allocator: &mut dyn FrameAllocator,
+    params: &[(String, String)],
+) -> Result<(u64, u64)> {
+    if params.is_empty() {
+        return Ok((0, 0));
+    }
+
+    let buffer = serialize_params(params);
+    let addr = allocator.allocate_frame(div_ceil_pages(buffer.len() as u64) as usize)?;
+
+    // SAFETY: os frames recém-alocados cobrem `buffer.len()` bytes.
+    unsafe {
+        core::ptr::copy_nonoverlapping(buffer.as_ptr(), addr as *mut u8, buffer.len());
+    }
+
+    Ok((addr, params.len() as u64))
+}
+
+/// Copia a lista de frames das page tables (`PageTableManager::table_frames`)
+/// para frames próprios. Retorna `(endereço do array, número de frames)`.
+pub fn stage_pagetable_frames(
+    allocator: &mut dyn FrameAllocator,
+    frames: &[u64],
+) -> Result<(u64, u64)> {
+    if frames.is_empty() {
+        return Ok((0, 0));
+    }
+
+    let bytes = core::mem::size_of_val(frames);
+    let addr = allocator.allocate_frame(div_ceil_pages(bytes as u64) as usize)?;
+
+    // SAFETY: os frames recém-alocados cobrem `bytes` e são alinhados à página.
+    unsafe {
+        core::ptr::copy_nonoverlapping(frames.as_ptr(), addr as *mut u64, frames.len());
+    }
+
+    Ok((addr, frames.len() as u64))
+}
+
+/// Copia `s` terminada em NUL para frames alocados para isso.
+fn stage_string(allocator: &mut dyn FrameAllocator, s: &str) -> Result<u64> {
+    let bytes = s.as_bytes();
+    let pages = div_ceil_pages(bytes.len() as u64 + 1) as usize;
+    let addr = allocator.allocate_frame(pages)?;
+
+    // SAFETY: o alocador acabou de reservar `pages` frames a partir de `addr`,
+    // o que cobre `bytes.len() + 1` bytes.
+    unsafe {
+        let dest = core::slice::from_raw_parts_mut(addr as *mut u8, bytes.len() + 1);
+        dest[..bytes.len()].copy_from_slice(bytes);
+        dest[bytes.len()] = 0;
+    }
+
+    Ok(addr)
+}
+
 impl<'a> BootProtocol for RedstoneProtocol<'a> {
     /// Nome do protocolo — usado para logs/diagnóstico.
     fn name(&self) -> &str {
@@ -201,7 +685,7 @@ impl<'a> BootProtocol for RedstoneProtocol<'a> {
     ///
     /// **Parâmetros**
     /// - `kernel_file`: bytes do binário do kernel (ELF).
-    /// - `_cmdline`: linha de comando (atualmente não utilizada).
+    /// - `cmdline`: linha de comando, copiada para um frame próprio.
     /// - `modules`: lista de módulos anexados (primeiro modul é tratado como
     ///   initrd).
     /// - `memory_map_buffer`: tupla `(addr, len)` apontando para o buffer do
@@ -214,11 +698,15 @@ impl<'a> BootProtocol for RedstoneProtocol<'a> {
     fn load(
         &mut self,
         kernel_file: &[u8],
-        _cmdline: Option<&str>,
+        cmdline: Option<&str>,
         modules: Vec<LoadedFile>,
         memory_map_buffer: (u64, u64),
         framebuffer: Option<crate::core::handoff::FramebufferInfo>,
     ) -> Result<KernelLaunchInfo> {
+        // O salto é para `REDSTONE_KERNEL_ENTRY`: recusar antes de montar
+        // qualquer tabela um kernel linkado em outro endereço.
+        check_fixed_entry(kernel_file)?;
+
         // ---------------------------
         // 1) Identity map de toda a memória física
         // ---------------------------
@@ -234,12 +722,9 @@ impl<'a> BootProtocol for RedstoneProtocol<'a> {
         // Adicionar margem de 256MB para alocações extras do UEFI
         // e arredondar para o próximo GB boundary
         const MARGIN: u64 = 256 * 1024 * 1024; // 256 MB
-        const GB_MASK: u64 = 0x3FFF_FFFF; // ~1GB
-        let map_limit = (max_phys_addr + MARGIN + GB_MASK) & !GB_MASK;
-
-        self.page_table
-            .identity_map_range(map_limit, self.allocator)
-            .expect("Falha ao criar identity map");
+        const SIZE_1GIB: u64 = 1024 * 1024 * 1024;
+        let map_limit =
+            align_up(max_phys_addr + MARGIN, SIZE_1GIB).expect("Falha ao criar identity map");
 
         // ---------------------------
         // 1.1) Mapear Higher Half Direct Map (HHDM)
@@ -247,11 +732,37 @@ impl<'a> BootProtocol for RedstoneProtocol<'a> {
         //
         // **Novo no Subprocesso de Memoria:** Mapeamos toda a RAM fisica em
         // 0xFFFF_8000_0000_0000. Isso permite que o kernel remova o identity map
-        // mais tarde e tenha isolamento total.
-        const HHDM_BASE: u64 = 0xFFFF_8000_0000_0000;
-        self.page_table
-            .map_hhdm(map_limit, HHDM_BASE, self.allocator)
-            .expect("Falha ao criar HHDM map");
+        // mais tarde e tenha isolamento total. `map_mode` decide quais dos
+        // dois mapeamentos são montados.
+        map_physical_memory(
+            self.page_table,
+            self.allocator,
+            self.map_mode,
+            map_limit,
+            self.trampoline,
+        )?;
+        let hhdm_offset = if self.map_mode.direct() { HHDM_BASE } else { 0 };
+
+        // ---------------------------
+        // 1.2) Framebuffer sem cache write-back
+        // ---------------------------
+        //
+        // O identity map e o HHDM usam huge pages write-back. Remapeamos o
+        // framebuffer com PWT/PCD para que o kernel, ao herdar estas tabelas,
+        // não desenhe através do cache. O mesmo vale para os displays extras.
+        let extra = self.framebuffers.iter().copied();
+        for fb in framebuffer
+            .into_iter()
+            .chain(extra)
+            .filter(|fb| fb.addr != 0 && fb.size != 0)
+        {
+            self.page_table.map_framebuffer(
+                fb.addr,
+                fb.size,
+                self.map_mode.direct().then_some(HHDM_BASE),
+                self.allocator,
+            )?;
+        }
 
         // ---------------------------
         // 2) Carregar segmentos ELF do kernel
@@ -269,7 +780,11 @@ impl<'a> BootProtocol for RedstoneProtocol<'a> {
         //
         // Se o kernel requer relocation/relro/relro-fixups, o loader é o local correto
         // para aplicar essas transformações.
-        let mut loader = ElfLoader::new(self.allocator, self.page_table);
+        let mut loader =
+            ElfLoader::new(self.allocator, self.page_table).with_mapped_limit(map_limit);
+        if let Some(base) = self.load_addr {
+            loader = loader.with_load_base(base, memory_regions(memory_map_buffer));
+        }
         let loaded_kernel = loader.load_kernel(kernel_file)?;
 
         // ---------------------------
@@ -287,11 +802,13 @@ impl<'a> BootProtocol for RedstoneProtocol<'a> {
         // 4) Alocar BootInfo (frame físico)
         // ---------------------------
         //
-        // Reservamos um frame físico (1 frame = 4 KiB, presumivelmente) para escrever a
-        // estrutura `BootInfo`. Em seguida passamos o endereço físico deste frame no
-        // registro `rdi` (convenção escolhida pelo protocolo Redstone).
-        let boot_info_phys = self.allocator.allocate_frame(1)?;
-        let boot_info_ptr = boot_info_phys as *mut BootInfo;
+        // Reservamos um frame físico (1 frame = 4 KiB, presumivelmente) para escrever o
+        // `HandoffHeader` seguido da estrutura `BootInfo`. Em seguida passamos o
+        // endereço físico do `BootInfo` no registro `rdi` (convenção escolhida pelo
+        // protocolo Redstone).
+        let handoff_phys = self.allocator.allocate_frame(1)?;
+        let handoff_ptr = handoff_phys as *mut HandoffBlock;
+        let boot_info_phys = handoff_phys + core::mem::offset_of!(HandoffBlock, info) as u64;
 
         // ---------------------------
         // 5) Preencher BootInfo
@@ -301,8 +818,8 @@ impl<'a> BootProtocol for RedstoneProtocol<'a> {
         // initrd.
         let fb_info = framebuffer.unwrap_or_else(|| self.prepare_framebuffer());
 
-        // Tratamos o primeiro módulo como initrd, se presente. Em futuros updates:
-        // - suportar múltiplos módulos com uma lista em BootInfo,
+        // Tratamos o primeiro módulo como initrd, se presente; a lista completa,
+        // com a cmdline de cada módulo, vai em `modules_addr`. Em futuros updates:
         // - validar assinaturas/hashe(s) do initrd,
         // - garantir alinhamento do initrd em páginas.
         let (initrd_addr, initrd_size) = if let Some(first_mod) = modules.first() {
@@ -311,6 +828,38 @@ impl<'a> BootProtocol for RedstoneProtocol<'a> {
             (0, 0)
         };
 
+        // Linha de comando em frame próprio: o kernel lê a string sem depender
+        // da memória do bootloader, que pode ser reutilizada após o handoff.
+        let (cmdline_addr, cmdline_len) = stage_cmdline(self.allocator, cmdline)?;
+        let loader_name_addr = stage_loader_name(self.allocator)?;
+        let (modules_addr, module_count) = stage_modules(self.allocator, &modules)?;
+        let (params_addr, params_count) = stage_params(self.allocator, self.params)?;
+        let (framebuffers_addr, framebuffers_count) =
+            stage_framebuffers(self.allocator, fb_info, self.framebuffers)?;
+
+        // O scratch slot foi a última alteração nas tabelas; a lista está completa.
+        let (pagetable_frames_addr, pagetable_frames_count) =
+            stage_pagetable_frames(self.allocator, self.page_table.table_frames())?;
+
+        // ACPI: RSDP validado para o kernel e um resumo da MADT (CPUs, IO APICs).
+        // SAFETY: o RSDP veio das Configuration Tables e ainda estamos em Boot
+        // Services, com a memória física em identity map.
+        let acpi_root = match crate::hardware::acpi::AcpiManager::get_rsdp_address() {
+            Ok(addr) => unsafe { crate::hardware::acpi::AcpiManager::validate_rsdp(addr) }
+                .unwrap_or_else(|e| {
+                    log::warn!("ACPI: RSDP em {:#x} rejeitado: {:?}", addr, e);
+                    crate::hardware::acpi::AcpiRoot::default()
+                }),
+            Err(_) => crate::hardware::acpi::AcpiRoot::default(),
+        };
+        let rsdp_addr = acpi_root.rsdp_addr;
+        let madt = if rsdp_addr != 0 {
+            unsafe { crate::hardware::acpi::AcpiManager::parse_madt(rsdp_addr) }
+                .unwrap_or_default()
+        } else {
+            crate::hardware::acpi::MadtInfo::default()
+        };
+
         let boot_info = BootInfo {
             // Versão/magic para validação pelo kernel.
             magic:   crate::core::handoff::BOOT_INFO_MAGIC,
@@ -326,7 +875,7 @@ impl<'a> BootProtocol for RedstoneProtocol<'a> {
             memory_map_len:  memory_map_buffer.1,
 
             // ACPI RSDP — obtido das Configuration Tables da UEFI
-            rsdp_addr: crate::hardware::acpi::AcpiManager::get_rsdp_address().unwrap_or(0),
+            rsdp_addr,
 
             // Informações fundamentais do kernel carregado.
             kernel_phys_addr: loaded_kernel.base_address,
@@ -340,21 +889,75 @@ impl<'a> BootProtocol for RedstoneProtocol<'a> {
             // IMPORTANTE: Endereço físico real, não virtual!
             cr3_phys: self.page_table.pml4_addr(),
 
-            hhdm_offset: HHDM_BASE,
-            hhdm_size:   map_limit,
+            hhdm_offset,
+            hhdm_size: if hhdm_offset != 0 { map_limit } else { 0 },
+
+            // Recursos da CPU, para o kernel não precisar sondar o CPUID de novo.
+            cpu_features: crate::arch::x86::cpuid::detect_features().bits(),
+
+            // Linha de comando (terminada em NUL; `cmdline_len` não conta o NUL).
+            cmdline_addr,
+            cmdline_len,
+
+            // Identificação do bootloader, para o kernel registrar em seus logs.
+            loader_name_addr,
+            loader_version: crate::core::config::meta::VERSION_CODE,
+
+            // Topologia de interrupções (MADT).
+            local_apic_addr: madt.local_apic_addr,
+            cpu_count: madt.cpu_count,
+            io_apic_count: madt.io_apic_count,
+            io_apic_addrs: madt.io_apic_addrs,
+
+            // Raiz ACPI já validada; o kernel pode percorrer a XSDT direto.
+            xsdt_addr: acpi_root.xsdt_addr,
+            acpi_table_count: acpi_root.table_count,
+
+            // Todos os módulos, com suas linhas de comando.
+            modules_addr,
+            module_count,
+
+            // Frames das page tables herdadas, para o kernel reaproveitar.
+            pagetable_frames_addr,
+            pagetable_frames_count,
+
+            // Só RAM utilizável; o identity map acima usa o mapa inteiro.
+            max_phys_addr: max_usable_phys(map_entries(memory_map_buffer)),
+
+            // Estado do firmware no momento do boot (Setup Mode conta como desligado).
+            secure_boot: crate::security::secure_boot::enforcement_required(),
+
+            efi_system_table_addr: self.system_table_addr,
+
+            // Pares `param` da entrada, além da cmdline.
+            params_addr,
+            params_count,
+
+            text_console: self.text_console,
+
+            map_mode: map_mode_bits(self.map_mode),
+
+            // Tri-estado, para o kernel reconhecer o Setup Mode.
+            secure_boot_state: crate::security::secure_boot::get_state().handoff_value(),
+
+            // Todas as saídas de vídeo; a primeira repete `framebuffer`.
+            framebuffers_addr,
+            framebuffers_count,
         };
 
+        debug_assert_eq!(boot_info.validate(), Ok(()));
+
         // ---------------------------
         // 6) Escrever BootInfo no frame alocado
         // ---------------------------
         //
         // Segurança: escrever em memória física requer `unsafe`. Garantimos:
-        // - `boot_info_ptr` aponta a um frame válido maior que sizeof(BootInfo).
+        // - `handoff_ptr` aponta a um frame válido maior que sizeof(HandoffBlock).
         // - BootInfo é `Copy`/plain-old-data (ou ao menos consistência de layout).
         //
         // Se for necessário limpar/validar a página antes de escrever, este é o lugar.
         unsafe {
-            core::ptr::write(boot_info_ptr, boot_info);
+            core::ptr::write(handoff_ptr, HandoffBlock::new(boot_info));
         }
 
         // ---------------------------
@@ -369,6 +972,63 @@ impl<'a> BootProtocol for RedstoneProtocol<'a> {
         let stack_bottom = self.allocator.allocate_frame(KERNEL_STACK_PAGES)?;
         // O stack cresce para baixo, então o stack pointer inicial é no TOPO do buffer
         let stack_top = stack_bottom + (KERNEL_STACK_PAGES as u64 * PAGE_SIZE);
+        // Sem identity map, o rsp físico não fica mapeado após a troca de CR3
+        let stack_pointer = kernel_visible_addr(self.map_mode, stack_top);
+
+        // ---------------------------
+        // 7.1) Conferir que nenhuma região entregue ao kernel se sobrepõe
+        // ---------------------------
+        let mut layout = OverlapChecker::new();
+        layout.record("kernel", loaded_kernel.base_address, loaded_kernel.size)?;
+        for module in &modules {
+            layout.record("module", module.ptr, module.size as u64)?;
+        }
+        if module_count != 0 {
+            // SAFETY: array gravado por `stage_modules` com `module_count` entradas.
+            let records = unsafe {
+                core::slice::from_raw_parts(
+                    modules_addr as *const ModuleInfo,
+                    module_count as usize,
+                )
+            };
+            layout.record(
+                "modules",
+                modules_addr,
+                core::mem::size_of_val(records) as u64,
+            )?;
+            for record in records.iter().filter(|r| r.cmdline_addr != 0) {
+                layout.record("module_cmdline", record.cmdline_addr, record.cmdline_len + 1)?;
+            }
+        }
+        layout.record("boot_info", handoff_phys, PAGE_SIZE)?;
+        layout.record(
+            "pagetable_frames",
+            pagetable_frames_addr,
+            pagetable_frames_count * 8,
+        )?;
+        if cmdline_addr != 0 {
+            layout.record("cmdline", cmdline_addr, cmdline_len + 1)?;
+        }
+        if framebuffers_addr != 0 {
+            layout.record(
+                "framebuffers",
+                framebuffers_addr,
+                framebuffers_count * core::mem::size_of::<FramebufferInfo>() as u64,
+            )?;
+        }
+        if params_addr != 0 {
+            layout.record(
+                "params",
+                params_addr,
+                serialize_params(self.params).len() as u64,
+            )?;
+        }
+        layout.record(
+            "loader_name",
+            loader_name_addr,
+            crate::core::config::meta::LOADER_NAME.len() as u64 + 1,
+        )?;
+        layout.record("stack", stack_bottom, KERNEL_STACK_PAGES as u64 * PAGE_SIZE)?;
 
         // ---------------------------
         // 8) Construir KernelLaunchInfo e retornar
@@ -380,8 +1040,10 @@ impl<'a> BootProtocol for RedstoneProtocol<'a> {
         Ok(KernelLaunchInfo {
             entry_point: loaded_kernel.entry_point,
             use_fixed_redstone_entry: true,
-            stack_pointer: Some(stack_top),
-            rdi: boot_info_phys,
+            stack_pointer: Some(stack_pointer),
+            // Como o rsp, o ponteiro do BootInfo precisa ser legível sem
+            // identity map
+            rdi: kernel_visible_addr(self.map_mode, boot_info_phys),
             rsi: 0,
             rdx: 0,
             rbx: 0,
@@ -3,9 +3,11 @@
 //! Verifica a saúde básica dos componentes antes de tentar carregar o kernel.
 //! Garante que arquivos essenciais existam para evitar pânico no meio do boot.
 
+use alloc::string::String;
+
 use crate::{
     config::Entry,
-    fs::{loader::FileLoader, FileSystem},
+    fs::{iso::open_iso_root, loader::FileLoader, FileSystem},
 };
 
 /// Resultado do diagnóstico.
@@ -16,29 +18,91 @@ pub enum HealthStatus {
     Critical(&'static str),
 }
 
+impl HealthStatus {
+    /// Mensagem associada ao status, se houver.
+    pub fn message(&self) -> Option<&'static str> {
+        match self {
+            HealthStatus::Healthy => None,
+            HealthStatus::Warning(msg) | HealthStatus::Critical(msg) => Some(msg),
+        }
+    }
+
+    /// Se o status deve impedir o boot.
+    ///
+    /// Avisos só são fatais com `strict_diagnostics` ativo (`strict`).
+    pub fn is_fatal(&self, strict: bool) -> bool {
+        match self {
+            HealthStatus::Healthy => false,
+            HealthStatus::Warning(_) => strict,
+            HealthStatus::Critical(_) => true,
+        }
+    }
+}
+
+/// Primeiro arquivo de `entry.requires` que não existe.
+fn missing_requirement<'e>(loader: &mut FileLoader<'_>, entry: &'e Entry) -> Option<&'e str> {
+    entry
+        .requires
+        .iter()
+        .find(|path| !loader.file_exists(path))
+        .map(String::as_str)
+}
+
+/// Pontuação de uma entrada sem nenhum problema detectado.
+pub const MAX_HEALTH_SCORE: u8 = 100;
+
+// Composição da pontuação: kernel + módulos + histórico = 100
+const KERNEL_SCORE: u8 = 60;
+const MODULES_SCORE: u8 = 30;
+const HISTORY_SCORE: u8 = 10;
+
+/// Penalidade por falha de boot recente.
+const FAILURE_PENALTY: u8 = 10;
+
 pub struct Diagnostics;
 
 impl Diagnostics {
     /// Executa bateria de testes na entrada selecionada.
     ///
-    /// Verifica se o kernel e os módulos (initrd) estão acessíveis.
+    /// Verifica se o kernel, os módulos (initrd) e os arquivos de `requires`
+    /// estão acessíveis.
     pub fn check_entry(fs: &mut dyn FileSystem, entry: &Entry) -> HealthStatus {
-        crate::println!("Executando diagnóstico em '{}'...", entry.name);
+        log::info!("Executando diagnóstico em '{}'...", entry.name);
 
         // Instancia um loader temporário para verificar arquivos
         let mut loader = FileLoader::new(fs);
 
+        if let Some(path) = missing_requirement(&mut loader, entry) {
+            log::error!("Requisito '{}' não encontrado.", path);
+            return HealthStatus::Critical("Arquivo requerido ausente");
+        }
+
+        // Entradas HTTP: kernel e módulos estão no servidor, baixados no boot
+        if let Some(base) = &entry.http_base {
+            log::info!("Kernel servido por '{}'.", base);
+            return HealthStatus::Healthy;
+        }
+
+        // Entradas ISO: kernel e módulos estão no ESP dentro da imagem
+        if let Some(iso_path) = &entry.iso_path {
+            if !loader.file_exists(iso_path) {
+                log::error!("Imagem '{}' não encontrada.", iso_path);
+                return HealthStatus::Critical("Imagem ISO ausente");
+            }
+            return Self::check_iso_entry(fs, iso_path, entry);
+        }
+
         // 1. Verificar existência do Kernel
         if !loader.file_exists(&entry.path) {
-            crate::println!("FALHA: Kernel '{}' não encontrado.", entry.path);
+            log::error!("Kernel '{}' não encontrado.", entry.path);
             return HealthStatus::Critical("Arquivo do Kernel ausente");
         }
-        crate::println!("[OK] Kernel encontrado.");
+        log::info!("Kernel encontrado.");
 
         // 2. Verificar Módulos (Aviso)
         for module in &entry.modules {
             if !loader.file_exists(&module.path) {
-                crate::println!("AVISO: Módulo '{}' não encontrado.", module.path);
+                log::warn!("Módulo '{}' não encontrado.", module.path);
                 // Não retorna Critical pois o kernel pode bootar sem alguns módulos, mas avisa
                 return HealthStatus::Warning("Módulo ausente");
             }
@@ -51,6 +115,70 @@ impl Diagnostics {
         HealthStatus::Healthy
     }
 
+    /// Monta o ESP de `iso_path` e verifica kernel e módulos dentro dele.
+    fn check_iso_entry(fs: &mut dyn FileSystem, iso_path: &str, entry: &Entry) -> HealthStatus {
+        let mut root = match open_iso_root(fs, iso_path) {
+            Ok(root) => root,
+            Err(e) => {
+                log::error!("Imagem '{}' não pôde ser montada: {}", iso_path, e);
+                return HealthStatus::Critical("Imagem ISO sem ESP legivel");
+            },
+        };
+        log::info!("Imagem ISO montada.");
+
+        if root.open_file(&entry.path).is_err() {
+            log::error!("Kernel '{}' não encontrado na imagem.", entry.path);
+            return HealthStatus::Critical("Kernel ausente na imagem ISO");
+        }
+        log::info!("Kernel encontrado na imagem.");
+
+        for module in &entry.modules {
+            if root.open_file(&module.path).is_err() {
+                log::warn!("Módulo '{}' não encontrado na imagem.", module.path);
+                return HealthStatus::Warning("Módulo ausente na imagem ISO");
+            }
+        }
+
+        HealthStatus::Healthy
+    }
+
+    /// Pontua a entrada de 0 a 100 para ordenar opções (ex: setups A/B).
+    ///
+    /// Kernel ou arquivo de `requires` ausente sempre resulta em 0. Com eles
+    /// presentes, a nota cai proporcionalmente aos módulos ausentes e a cada
+    /// falha recente (`recent_failures`, ver `PersistentState::failures_for`),
+    /// mas nunca abaixo de 1.
+    ///
+    /// Entradas `http_base` têm kernel e módulos no servidor, que não é
+    /// consultado aqui: como em `check_entry`, só `requires` é verificado.
+    pub fn score_entry(fs: &mut dyn FileSystem, entry: &Entry, recent_failures: u8) -> u8 {
+        let mut loader = FileLoader::new(fs);
+
+        let remote = entry.http_base.is_some();
+        let kernel_path = entry.iso_path.as_deref().unwrap_or(&entry.path);
+        if (!remote && !loader.file_exists(kernel_path))
+            || missing_requirement(&mut loader, entry).is_some()
+        {
+            return 0;
+        }
+
+        // Entradas ISO carregam os módulos dentro da imagem
+        let modules = if remote || entry.iso_path.is_some() || entry.modules.is_empty() {
+            MODULES_SCORE
+        } else {
+            let present = entry
+                .modules
+                .iter()
+                .filter(|m| loader.file_exists(&m.path))
+                .count();
+            (MODULES_SCORE as usize * present / entry.modules.len()) as u8
+        };
+
+        (KERNEL_SCORE + modules + HISTORY_SCORE)
+            .saturating_sub(recent_failures.saturating_mul(FAILURE_PENALTY))
+            .max(1)
+    }
+
     /// Verifica integridade do firmware.
     pub fn check_firmware() -> HealthStatus {
         let st = crate::uefi::system_table();
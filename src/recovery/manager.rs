@@ -3,26 +3,144 @@
 //! Decide qual kernel carregar baseado no histórico de falhas e input do
 //! usuário.
 
-use super::state::PersistentState;
+use super::state::{FirmwareVariables, PersistentState, VariableStore};
 use crate::{
     config::{BootConfig, Entry},
     ui::input::{InputManager, Key},
 };
 
-/// Limite de falhas antes de forçar o modo de recuperação.
-const MAX_FAILURES: u8 = 3;
+/// Quando desistir da entrada padrão, vindo de `recovery_max_attempts` e
+/// `recovery_attempt_decay`.
+///
+/// O contador é opcional: só o sistema operacional sabe que subiu e pode
+/// zerá-lo, então fica desligado até a configuração pedir.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecoveryPolicy {
+    /// Falhas consecutivas que disparam o fallback (0 desativa o contador).
+    pub max_attempts:  u8,
+    /// Segundos de espera por falha antes de repetir a entrada padrão.
+    pub attempt_decay: u32,
+}
+
+impl RecoveryPolicy {
+    pub fn from_config(config: &BootConfig) -> Self {
+        Self {
+            max_attempts:  config.recovery_max_attempts,
+            attempt_decay: config.recovery_attempt_decay,
+        }
+    }
+
+    /// `true` se as tentativas de boot automático são contadas.
+    pub fn enabled(&self) -> bool {
+        self.max_attempts > 0
+    }
+
+    /// `true` se `failed_attempts` falhas já exigem a entrada de recuperação.
+    pub fn should_fallback(&self, failed_attempts: u8) -> bool {
+        self.enabled() && failed_attempts >= self.max_attempts
+    }
+
+    /// Espera (segundos) antes de repetir a entrada padrão. Um contador
+    /// antigo na NVRAM não atrasa o boot com o contador desligado.
+    pub fn retry_delay(&self, failed_attempts: u8) -> u64 {
+        if !self.enabled() {
+            return 0;
+        }
+        self.attempt_decay as u64 * failed_attempts as u64
+    }
+}
 
-pub struct RecoveryManager {
-    state: PersistentState,
+/// O que fazer depois que a entrada escolhida falhou ao carregar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureAction {
+    /// Tentar a última entrada que chegou ao kernel (índice em
+    /// `config.entries`).
+    RetryLastGood(usize),
+    /// Sem alternativa automática: seguir para a recuperação.
+    Recovery,
 }
 
-impl RecoveryManager {
+/// Fallback para a última entrada válida (`state::get_last_good`) quando a
+/// entrada escolhida é recusada antes do salto: kernel ausente ou inválido,
+/// módulos, política de segurança, protocolo ou preparo do handoff.
+///
+/// A última boa é tentada uma única vez por boot; se ela também falhar, ou se
+/// for a própria entrada que falhou, o resultado é `Recovery`.
+#[derive(Debug, Default)]
+pub struct LastGoodFallback {
+    tried: bool,
+}
+
+impl LastGoodFallback {
     pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decide o próximo passo após a falha de `failed`, dado o nome gravado
+    /// como última entrada válida.
+    pub fn on_failure(
+        &mut self,
+        config: &BootConfig,
+        failed: &Entry,
+        last_good: Option<&str>,
+    ) -> FailureAction {
+        if self.tried {
+            return FailureAction::Recovery;
+        }
+
+        match last_good.and_then(|name| config.find_entry(name)) {
+            Some(idx) if config.entries[idx].name != failed.name => {
+                self.tried = true;
+                FailureAction::RetryLastGood(idx)
+            },
+            _ => FailureAction::Recovery,
+        }
+    }
+}
+
+pub struct RecoveryManager<S: VariableStore = FirmwareVariables> {
+    state:  PersistentState,
+    store:  S,
+    policy: RecoveryPolicy,
+}
+
+impl RecoveryManager {
+    pub fn new(config: &BootConfig) -> Self {
+        Self::with_store(FirmwareVariables, RecoveryPolicy::from_config(config))
+    }
+}
+
+impl<S: VariableStore> RecoveryManager<S> {
+    /// Gerenciador sobre um armazenamento arbitrário (testes).
+    pub fn with_store(store: S, policy: RecoveryPolicy) -> Self {
         Self {
-            state: PersistentState::load(),
+            state: PersistentState::load_from(&store),
+            store,
+            policy,
         }
     }
 
+    /// Falhas consecutivas registradas até agora.
+    pub fn failed_attempts(&self) -> u8 {
+        self.state.failed_attempts
+    }
+
+    /// `true` se o histórico de falhas já exige a entrada de recuperação.
+    pub fn needs_fallback(&self) -> bool {
+        self.policy.should_fallback(self.state.failed_attempts)
+    }
+
+    /// Registra uma tentativa de boot de `entry_idx`; conta como falha até
+    /// `mark_success`.
+    pub fn record_attempt(&mut self, entry_idx: usize) {
+        self.state.mark_attempt_in(&mut self.store, entry_idx);
+    }
+
+    /// Zera o contador de falhas, qualquer que seja `max_attempts`.
+    pub fn mark_success(&mut self) {
+        self.state.reset_in(&mut self.store);
+    }
+
     /// Verifica se o usuário está segurando a tecla de recuperação (R ou
     /// Shift).
     fn check_force_keys(&self) -> bool {
@@ -41,11 +159,27 @@ impl RecoveryManager {
     ///
     /// # Lógica
     /// 1. Se tecla 'R' pressionada -> Recovery.
-    /// 2. Se falhas consecutivas > 3 -> Recovery.
-    /// 3. Caso contrário -> Entrada Padrão (Config).
+    /// 2. Se falhas consecutivas >= `recovery_max_attempts` -> Recovery.
+    /// 3. Caso contrário -> Entrada Padrão (Config), após a espera de
+    ///    `recovery_attempt_decay` se houver falhas anteriores.
     pub fn select_entry<'a>(&mut self, config: &'a BootConfig) -> &'a Entry {
         let force_recovery = self.check_force_keys();
-        let too_many_failures = self.state.failed_attempts >= MAX_FAILURES;
+        self.select_entry_with(config, force_recovery, &mut |seconds| {
+            crate::uefi::system_table()
+                .boot_services()
+                .stall((seconds * 1_000_000) as usize);
+        })
+    }
+
+    /// Versão de `select_entry` com a tecla de recuperação já lida e a
+    /// espera de `recovery_attempt_decay` injetada (testes).
+    pub fn select_entry_with<'a>(
+        &mut self,
+        config: &'a BootConfig,
+        force_recovery: bool,
+        stall: &mut dyn FnMut(u64),
+    ) -> &'a Entry {
+        let too_many_failures = self.needs_fallback();
 
         if force_recovery || too_many_failures {
             if force_recovery {
@@ -67,13 +201,28 @@ impl RecoveryManager {
             crate::println!("AVISO: Nenhuma entrada de recuperação encontrada. Tentando padrão.");
         }
 
-        // Caminho feliz
-        let idx = config
-            .default_entry_idx
-            .min(config.entries.len().saturating_sub(1));
+        // Caminho feliz (primeira entrada se o índice padrão for inválido)
+        let idx = if config.default_entry_idx < config.entries.len() {
+            config.default_entry_idx
+        } else {
+            0
+        };
 
-        // Registra que estamos tentando esta entrada
-        self.state.mark_attempt(idx);
+        let delay = self.policy.retry_delay(self.state.failed_attempts);
+        if delay > 0 {
+            crate::println!(
+                "Recuperação: {} falha(s) anterior(es), aguardando {}s.",
+                self.state.failed_attempts,
+                delay
+            );
+            stall(delay);
+        }
+
+        // Registra que estamos tentando esta entrada (conta como falha até o
+        // sistema zerar o contador)
+        if self.policy.enabled() {
+            self.record_attempt(idx);
+        }
 
         &config.entries[idx]
     }
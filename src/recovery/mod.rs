@@ -7,11 +7,15 @@
 //! - **A/B Boot:** Detecção de falhas e fallback automático.
 //! - **Persistência:** Contagem de tentativas na NVRAM.
 //! - **Diagnóstico:** Verificação pré-boot de arquivos.
+//! - **Autoteste:** Verificação dos subsistemas do próprio bootloader.
+//! - **Tela de erro:** Falhas de diagnóstico exibidas no framebuffer.
 
 pub mod diagnostics;
 pub mod manager;
+pub mod screen;
+pub mod selftest;
 pub mod state;
 
 // Re-exports
 pub use diagnostics::Diagnostics;
-pub use manager::RecoveryManager;
+pub use manager::{FailureAction, LastGoodFallback, RecoveryManager, RecoveryPolicy};
@@ -0,0 +1,119 @@
+//! Tela de Erro Gráfica
+//!
+//! Quando o diagnóstico de uma entrada falha, o motivo aparece num cartão
+//! centralizado no framebuffer em vez de ficar só na serial: uma faixa de
+//! título na cor de erro do tema, a mensagem e uma dica de como seguir.
+
+use alloc::vec::Vec;
+
+use crate::{
+    ui::{graphics::GraphicsContext, Theme},
+    video::{Color, Rect},
+};
+
+/// Margem interna do cartão, em pixels.
+pub const CARD_PADDING: u32 = 12;
+
+/// Altura de uma linha de texto (fonte 8x16 mais espaçamento).
+const LINE_HEIGHT: u32 = 20;
+
+/// Largura de um glifo da fonte embutida.
+const GLYPH_WIDTH: u32 = 8;
+
+/// Dica padrão exibida no rodapé do cartão: a tecla devolve ao menu.
+pub const MENU_HINT: &str = "Pressione qualquer tecla para voltar ao menu";
+
+/// Quebra `message` em linhas de no máximo `cols` caracteres, respeitando
+/// as quebras explícitas (`\n`).
+fn wrap(message: &str, cols: usize) -> Vec<&str> {
+    let cols = cols.max(1);
+    let mut lines = Vec::new();
+    for line in message.lines() {
+        let mut rest = line;
+        while rest.chars().count() > cols {
+            let split = rest.char_indices().nth(cols).map_or(rest.len(), |(i, _)| i);
+            lines.push(&rest[..split]);
+            rest = &rest[split..];
+        }
+        lines.push(rest);
+    }
+    lines
+}
+
+/// Colunas de texto que cabem num cartão sobre uma tela de `screen_width`.
+fn max_cols(screen_width: u32) -> usize {
+    (screen_width.saturating_sub(4 * CARD_PADDING) / GLYPH_WIDTH).max(1) as usize
+}
+
+/// Retângulo ocupado pelo cartão de erro numa tela `width`x`height`.
+///
+/// O título fica em `(x + CARD_PADDING, y + CARD_PADDING)`, sobre a faixa
+/// `[y, y + title_bar_height())`.
+pub fn card_rect(width: u32, height: u32, title: &str, message: &str, hint: &str) -> Rect {
+    let cols = max_cols(width);
+    let lines = wrap(message, cols);
+    let text_cols = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .chain([title.chars().count(), hint.chars().count()])
+        .max()
+        .unwrap_or(0)
+        .min(cols) as u32;
+
+    let card_width = (text_cols * GLYPH_WIDTH + 2 * CARD_PADDING).min(width);
+    // Faixa do título + mensagem + linha em branco + dica
+    let card_height =
+        (title_bar_height() + (lines.len() as u32 + 2) * LINE_HEIGHT + CARD_PADDING).min(height);
+
+    Rect {
+        x:      (width - card_width) / 2,
+        y:      (height - card_height) / 2,
+        width:  card_width,
+        height: card_height,
+    }
+}
+
+/// Altura da faixa do título no topo do cartão.
+pub const fn title_bar_height() -> u32 {
+    16 + 2 * CARD_PADDING
+}
+
+/// Desenha o cartão de erro com as cores de `theme`: `title` na faixa de
+/// erro, `message` (quebrada na largura da tela) e `hint` no rodapé.
+///
+/// Só desenha; quem chama decide se espera uma tecla (ver [`MENU_HINT`]).
+pub fn show_error(fb: &mut GraphicsContext, theme: &Theme, title: &str, message: &str, hint: &str) {
+    show_card(fb, theme, theme.error, title, message, hint);
+}
+
+/// Como [`show_error`], mas com a faixa do título na cor `accent` (ex: o
+/// relatório do autoteste, que só fica vermelho se algo falhou).
+pub fn show_card(
+    fb: &mut GraphicsContext,
+    theme: &Theme,
+    accent: Color,
+    title: &str,
+    message: &str,
+    hint: &str,
+) {
+    let card = card_rect(fb.width(), fb.height(), title, message, hint);
+    let text_x = card.x + CARD_PADDING;
+
+    fb.clear(theme.background);
+    fb.fill_rect(card.x, card.y, card.width, card.height, theme.selected_bg);
+    fb.fill_rect(card.x, card.y, card.width, title_bar_height(), accent);
+    fb.draw_string(
+        text_x,
+        card.y + CARD_PADDING,
+        title,
+        theme.background,
+        Some(accent),
+    );
+
+    let mut y = card.y + title_bar_height() + CARD_PADDING / 2;
+    for line in wrap(message, max_cols(fb.width())) {
+        fb.draw_string(text_x, y, line, theme.foreground, None);
+        y += LINE_HEIGHT;
+    }
+    fb.draw_string(text_x, y + LINE_HEIGHT, hint, theme.highlight, None);
+}
@@ -0,0 +1,245 @@
+//! Autoteste do Bootloader
+//!
+//! Exercita, uma vez, cada subsistema de que o boot depende (alocação,
+//! serial, vídeo, leitura da config e RNG) e devolve um relatório com o
+//! resultado de cada um. Pensado para atendimento: o usuário roda pelo menu
+//! (tecla `T`) e informa as linhas `[FAIL]`.
+//!
+//! Cada verificação devolve `Result`; uma falha é registrada no relatório e
+//! as seguintes continuam rodando.
+
+use alloc::{format, string::String, vec::Vec};
+
+use crate::{
+    arch::x86::cpuid::{detect_features, CpuFeatures},
+    config::loader::CONFIG_FILENAMES,
+    core::error::{BootError, ConfigError, MemoryError, Result, VideoError},
+    fs::{read_to_string, FileSystem},
+    hardware::serial::{port_responds, NativePortIo},
+    uefi::{
+        table::boot::{AllocateType, MemoryType},
+        BootServices,
+    },
+    video::GopDriver,
+};
+
+/// Subsistemas verificados, na ordem de execução.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    Allocation,
+    Serial,
+    Video,
+    ConfigRead,
+    Rng,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 5] = [
+        Subsystem::Allocation,
+        Subsystem::Serial,
+        Subsystem::Video,
+        Subsystem::ConfigRead,
+        Subsystem::Rng,
+    ];
+
+    /// Nome exibido no relatório.
+    pub fn name(self) -> &'static str {
+        match self {
+            Subsystem::Allocation => "Alocacao",
+            Subsystem::Serial => "Serial",
+            Subsystem::Video => "Video",
+            Subsystem::ConfigRead => "Leitura da config",
+            Subsystem::Rng => "RNG",
+        }
+    }
+}
+
+/// Resultado de uma verificação.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckResult {
+    pub subsystem: Subsystem,
+    pub outcome:   Result<()>,
+}
+
+/// Relatório do autoteste.
+#[derive(Debug, Default)]
+pub struct SelfTestReport {
+    pub results: Vec<CheckResult>,
+}
+
+impl SelfTestReport {
+    /// Registra o resultado de um subsistema.
+    pub fn record(&mut self, subsystem: Subsystem, outcome: Result<()>) {
+        self.results.push(CheckResult { subsystem, outcome });
+    }
+
+    /// Se todas as verificações passaram.
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|r| r.outcome.is_ok())
+    }
+
+    /// Verificações que falharam.
+    pub fn failures(&self) -> impl Iterator<Item = &CheckResult> {
+        self.results.iter().filter(|r| r.outcome.is_err())
+    }
+
+    /// Linha de resumo, usada como título da tela do relatório.
+    pub fn summary(&self) -> String {
+        format!(
+            "Autoteste: {} de {} verificacoes falharam",
+            self.failures().count(),
+            self.results.len()
+        )
+    }
+
+    /// Uma linha `[OK]`/`[FAIL]` por subsistema, separadas por `\n`.
+    pub fn details(&self) -> String {
+        let mut text = String::new();
+        for result in &self.results {
+            let line = match result.outcome {
+                Ok(()) => format!("[OK] {}", result.subsystem.name()),
+                Err(e) => format!("[FAIL] {}: {:?}", result.subsystem.name(), e),
+            };
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(&line);
+        }
+        text
+    }
+}
+
+/// Operações exercitadas pelo autoteste.
+pub trait SelfTestOs {
+    /// Aloca uma página, escreve um padrão, confere e libera.
+    fn check_allocation(&mut self) -> Result<()>;
+
+    /// Confere que a porta serial ativa responde.
+    fn check_serial(&mut self) -> Result<()>;
+
+    /// Reaplica o modo de vídeo atual e confere o framebuffer.
+    fn check_video(&mut self) -> Result<()>;
+
+    /// Lê o arquivo de configuração do volume de boot.
+    fn check_config_read(&mut self) -> Result<()>;
+
+    /// Obtém valores do gerador de números aleatórios.
+    fn check_rng(&mut self) -> Result<()>;
+}
+
+/// Executa todas as verificações e monta o relatório.
+pub fn run<O: SelfTestOs + ?Sized>(os: &mut O) -> SelfTestReport {
+    let mut report = SelfTestReport::default();
+
+    for subsystem in Subsystem::ALL {
+        let outcome = match subsystem {
+            Subsystem::Allocation => os.check_allocation(),
+            Subsystem::Serial => os.check_serial(),
+            Subsystem::Video => os.check_video(),
+            Subsystem::ConfigRead => os.check_config_read(),
+            Subsystem::Rng => os.check_rng(),
+        };
+        report.record(subsystem, outcome);
+    }
+
+    report
+}
+
+/// Padrão gravado na página de teste de alocação.
+const ALLOC_PATTERN: u64 = 0xA5A5_5A5A_DEAD_BEEF;
+
+/// Tentativas do RDRAND antes de considerar o gerador falho (recomendação
+/// da Intel).
+const RDRAND_RETRIES: usize = 10;
+
+/// `SelfTestOs` sobre os Boot Services e o volume de boot reais.
+pub struct FirmwareSelfTest<'a> {
+    bs: &'a BootServices,
+    fs: &'a mut dyn FileSystem,
+}
+
+impl<'a> FirmwareSelfTest<'a> {
+    pub fn new(bs: &'a BootServices, fs: &'a mut dyn FileSystem) -> Self {
+        Self { bs, fs }
+    }
+}
+
+impl SelfTestOs for FirmwareSelfTest<'_> {
+    fn check_allocation(&mut self) -> Result<()> {
+        let page = self
+            .bs
+            .allocate_pages(AllocateType::AllocateAnyPages, MemoryType::LoaderData, 1, 0)
+            .map_err(|_| BootError::Memory(MemoryError::AllocationFailed))?;
+
+        let ptr = page as *mut u64;
+        let intact = unsafe {
+            ptr.write_volatile(ALLOC_PATTERN);
+            ptr.read_volatile() == ALLOC_PATTERN
+        };
+        let _ = self.bs.free_pages(page, 1);
+
+        if intact {
+            Ok(())
+        } else {
+            Err(BootError::Memory(MemoryError::InvalidAddress))
+        }
+    }
+
+    fn check_serial(&mut self) -> Result<()> {
+        if port_responds(&mut NativePortIo, crate::arch::x86::serial::port_base()) {
+            Ok(())
+        } else {
+            Err(BootError::Generic("porta serial nao responde"))
+        }
+    }
+
+    fn check_video(&mut self) -> Result<()> {
+        let info = GopDriver::new(self.bs)?.set_mode(None)?;
+        if info.addr == 0 || info.width == 0 || info.height == 0 {
+            return Err(BootError::Video(VideoError::InitializationFailed));
+        }
+        Ok(())
+    }
+
+    fn check_config_read(&mut self) -> Result<()> {
+        let mut root = self.fs.root()?;
+        let mut last_err = BootError::Config(ConfigError::NotFound);
+
+        for filename in CONFIG_FILENAMES {
+            match root.open_file(filename) {
+                Ok(mut file) => return read_to_string(file.as_mut()).map(|_| ()),
+                Err(e) => last_err = e,
+            }
+        }
+
+        Err(last_err)
+    }
+
+    fn check_rng(&mut self) -> Result<()> {
+        if !detect_features().contains(CpuFeatures::RDRAND) {
+            return Err(BootError::Generic("RDRAND indisponivel"));
+        }
+
+        let first = unsafe { rdrand64() }.ok_or(BootError::Generic("RDRAND falhou"))?;
+        let second = unsafe { rdrand64() }.ok_or(BootError::Generic("RDRAND falhou"))?;
+        if first == second {
+            return Err(BootError::Generic("RDRAND repetiu o valor"));
+        }
+        Ok(())
+    }
+}
+
+/// Lê 64 bits do RDRAND, tentando até `RDRAND_RETRIES` vezes.
+///
+/// # Safety
+/// A CPU precisa suportar RDRAND.
+#[target_feature(enable = "rdrand")]
+unsafe fn rdrand64() -> Option<u64> {
+    let mut value = 0;
+    for _ in 0..RDRAND_RETRIES {
+        if core::arch::x86_64::_rdrand64_step(&mut value) == 1 {
+            return Some(value);
+        }
+    }
+    None
+}
@@ -3,11 +3,18 @@
 //! Gerencia as variáveis de ambiente UEFI para rastrear falhas de boot
 //! e tentativas de recuperação entre reinicializações.
 
+use alloc::string::String;
 use core::mem::size_of;
 
-use crate::uefi::{
-    base::{Guid, Status},
-    system_table,
+use crate::{
+    core::error::{BootError, Result},
+    uefi::{
+        base::Guid,
+        system_table,
+        table::runtime::{
+            VARIABLE_BOOTSERVICE_ACCESS, VARIABLE_NON_VOLATILE, VARIABLE_RUNTIME_ACCESS,
+        },
+    },
 };
 
 /// GUID da variável de estado do Ignite (Vendor GUID).
@@ -20,13 +27,137 @@ pub const IGNITE_VENDOR_GUID: Guid = Guid::new(
 );
 
 /// Nome da variável de estado.
-const STATE_VAR_NAME: [u16; 12] = [
-    'I' as u16, 'g' as u16, 'n' as u16, 'B' as u16, 'o' as u16, 'o' as u16, 't' as u16, 'S' as u16,
-    't' as u16, 'a' as u16, 't' as u16, 0,
-];
+const STATE_VAR_NAME: &str = "IgnBootStat";
 
 /// Atributos da variável (Non-Volatile + BootService + Runtime).
-const VAR_ATTR: u32 = 0x00000007;
+const VAR_ATTR: u32 = VARIABLE_NON_VOLATILE | VARIABLE_BOOTSERVICE_ACCESS | VARIABLE_RUNTIME_ACCESS;
+
+/// Nome da variável com a última entrada que chegou ao handoff.
+pub const LAST_GOOD_VAR_NAME: &str = "IgnLastGood";
+
+/// Atributos da variável de último boot (Non-Volatile + BootService).
+/// O kernel não precisa enxergá-la, então não expomos acesso em runtime.
+pub const LAST_GOOD_ATTR: u32 = VARIABLE_NON_VOLATILE | VARIABLE_BOOTSERVICE_ACCESS;
+
+/// Tamanho máximo (bytes UTF-8) do nome de entrada persistido.
+pub const MAX_LAST_GOOD_LEN: usize = 128;
+
+/// Nome da variável com a entrada a iniciar uma única vez no próximo boot.
+/// Usa os mesmos atributos e limite de tamanho de `LAST_GOOD_VAR_NAME`.
+pub const BOOT_ONCE_VAR_NAME: &str = "IgnBootOnce";
+
+/// Armazenamento de variáveis persistentes (NVRAM).
+///
+/// Em produção é implementado sobre os Runtime Services do firmware; nos
+/// testes, por um armazenamento em memória.
+pub trait VariableStore {
+    /// Lê a variável para `buf`. Retorna o número de bytes lidos, ou `None`
+    /// se ela não existir ou não couber no buffer.
+    fn read(&self, name: &str, guid: &Guid, buf: &mut [u8]) -> Option<usize>;
+
+    /// Cria ou sobrescreve a variável com os atributos fornecidos.
+    fn write(&mut self, name: &str, guid: &Guid, attributes: u32, data: &[u8]) -> Result<()>;
+}
+
+/// `VariableStore` apoiado em `RuntimeServices::{get,set}_variable`.
+pub struct FirmwareVariables;
+
+impl VariableStore for FirmwareVariables {
+    fn read(&self, name: &str, guid: &Guid, buf: &mut [u8]) -> Option<usize> {
+        let rt = system_table().runtime_services();
+        rt.get_variable(name, guid, buf).ok().map(|(size, _)| size)
+    }
+
+    fn write(&mut self, name: &str, guid: &Guid, attributes: u32, data: &[u8]) -> Result<()> {
+        let rt = system_table().runtime_services();
+        Ok(rt.set_variable(name, guid, attributes, data)?)
+    }
+}
+
+/// Registra `name` como a última entrada que chegou ao handoff.
+///
+/// Deve ser chamado antes de `exit_boot_services`: depois disso só restam os
+/// Runtime Services e não temos mais chance de voltar ao bootloader.
+pub fn set_last_good(name: &str) -> Result<()> {
+    set_last_good_in(&mut FirmwareVariables, name)
+}
+
+/// Lê a última entrada registrada por `set_last_good`, se houver.
+pub fn get_last_good() -> Option<String> {
+    get_last_good_in(&FirmwareVariables)
+}
+
+/// Versão de `set_last_good` sobre um armazenamento arbitrário.
+pub fn set_last_good_in<S: VariableStore + ?Sized>(store: &mut S, name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > MAX_LAST_GOOD_LEN {
+        return Err(BootError::Generic(
+            "Nome de entrada invalido para last-good",
+        ));
+    }
+
+    store.write(
+        LAST_GOOD_VAR_NAME,
+        &IGNITE_VENDOR_GUID,
+        LAST_GOOD_ATTR,
+        name.as_bytes(),
+    )
+}
+
+/// Versão de `get_last_good` sobre um armazenamento arbitrário.
+pub fn get_last_good_in<S: VariableStore + ?Sized>(store: &S) -> Option<String> {
+    let mut buf = [0u8; MAX_LAST_GOOD_LEN];
+    let len = store.read(LAST_GOOD_VAR_NAME, &IGNITE_VENDOR_GUID, &mut buf)?;
+
+    if len == 0 || len > buf.len() {
+        return None;
+    }
+
+    core::str::from_utf8(&buf[..len]).ok().map(String::from)
+}
+
+/// Pede que a entrada `name` seja iniciada no próximo boot, sem menu.
+///
+/// O pedido vale uma vez: `take_boot_once` o apaga ao ler. Entradas ocultas
+/// (`hidden: yes`) também podem ser escolhidas assim.
+pub fn set_boot_once(name: &str) -> Result<()> {
+    set_boot_once_in(&mut FirmwareVariables, name)
+}
+
+/// Lê e apaga o pedido de `set_boot_once`, se houver.
+pub fn take_boot_once() -> Option<String> {
+    take_boot_once_in(&mut FirmwareVariables)
+}
+
+/// Versão de `set_boot_once` sobre um armazenamento arbitrário.
+pub fn set_boot_once_in<S: VariableStore + ?Sized>(store: &mut S, name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > MAX_LAST_GOOD_LEN {
+        return Err(BootError::Generic("Nome de entrada invalido para boot-once"));
+    }
+
+    store.write(
+        BOOT_ONCE_VAR_NAME,
+        &IGNITE_VENDOR_GUID,
+        LAST_GOOD_ATTR,
+        name.as_bytes(),
+    )
+}
+
+/// Versão de `take_boot_once` sobre um armazenamento arbitrário.
+pub fn take_boot_once_in<S: VariableStore + ?Sized>(store: &mut S) -> Option<String> {
+    let mut buf = [0u8; MAX_LAST_GOOD_LEN];
+    let len = store.read(BOOT_ONCE_VAR_NAME, &IGNITE_VENDOR_GUID, &mut buf)?;
+
+    // Gravar vazio apaga a variável (SetVariable com DataSize 0)
+    if let Err(e) = store.write(BOOT_ONCE_VAR_NAME, &IGNITE_VENDOR_GUID, LAST_GOOD_ATTR, &[]) {
+        log::warn!("Boot-once: falha ao apagar pedido: {:?}", e);
+    }
+
+    if len == 0 || len > buf.len() {
+        return None;
+    }
+
+    core::str::from_utf8(&buf[..len]).ok().map(String::from)
+}
 
 /// Estrutura persistida na NVRAM.
 #[repr(C, packed)]
@@ -45,55 +176,62 @@ pub struct PersistentState {
 impl PersistentState {
     /// Tenta carregar o estado da NVRAM.
     pub fn load() -> Self {
-        let rt = system_table().runtime_services();
+        Self::load_from(&FirmwareVariables)
+    }
 
-        // Como não temos um wrapper "safe" completo para GetVariable com alocação no
-        // core uefi ainda, usamos um buffer fixo na stack.
+    /// Versão de `load` sobre um armazenamento arbitrário.
+    pub fn load_from<S: VariableStore + ?Sized>(store: &S) -> Self {
         let mut data = [0u8; size_of::<PersistentState>()];
-        let mut size = data.len();
-        let mut attr = 0u32;
-
-        let status = unsafe {
-            (rt.get_variable)(
-                STATE_VAR_NAME.as_ptr(),
-                &IGNITE_VENDOR_GUID,
-                &mut attr,
-                &mut size,
-                data.as_mut_ptr() as *mut core::ffi::c_void,
-            )
-        };
 
-        if status == Status::SUCCESS && size == size_of::<PersistentState>() {
-            let state: PersistentState = unsafe { core::ptr::read(data.as_ptr() as *const _) };
-            // TODO: Validar checksum
-            state
-        } else {
+        match store.read(STATE_VAR_NAME, &IGNITE_VENDOR_GUID, &mut data) {
+            Some(size) if size == size_of::<PersistentState>() => {
+                let state: PersistentState = unsafe { core::ptr::read(data.as_ptr() as *const _) };
+                // TODO: Validar checksum
+                state
+            },
             // Se não existir ou erro, retorna estado limpo
-            Self::default()
+            _ => Self::default(),
         }
     }
 
     /// Salva o estado atual na NVRAM.
     pub fn save(&self) {
-        let rt = system_table().runtime_services();
-        let size = size_of::<PersistentState>();
-
-        unsafe {
-            (rt.set_variable)(
-                STATE_VAR_NAME.as_ptr(),
-                &IGNITE_VENDOR_GUID,
-                VAR_ATTR,
-                size,
-                self as *const _ as *mut core::ffi::c_void,
-            );
+        self.save_to(&mut FirmwareVariables);
+    }
+
+    /// Versão de `save` sobre um armazenamento arbitrário.
+    pub fn save_to<S: VariableStore + ?Sized>(&self, store: &mut S) {
+        let bytes = unsafe {
+            core::slice::from_raw_parts(
+                self as *const Self as *const u8,
+                size_of::<PersistentState>(),
+            )
+        };
+
+        let _ = store.write(STATE_VAR_NAME, &IGNITE_VENDOR_GUID, VAR_ATTR, bytes);
+    }
+
+    /// Falhas consecutivas atribuídas à entrada `entry_idx`.
+    ///
+    /// Só a última entrada tentada acumula falhas; as demais retornam 0.
+    pub fn failures_for(&self, entry_idx: usize) -> u8 {
+        if self.last_entry_idx as usize == entry_idx {
+            self.failed_attempts
+        } else {
+            0
         }
     }
 
     /// Registra uma nova tentativa de boot.
     pub fn mark_attempt(&mut self, entry_idx: usize) {
+        self.mark_attempt_in(&mut FirmwareVariables, entry_idx);
+    }
+
+    /// Versão de `mark_attempt` sobre um armazenamento arbitrário.
+    pub fn mark_attempt_in<S: VariableStore + ?Sized>(&mut self, store: &mut S, entry_idx: usize) {
         self.failed_attempts = self.failed_attempts.saturating_add(1);
         self.last_entry_idx = entry_idx as u8;
-        self.save();
+        self.save_to(store);
     }
 
     /// Reseta o contador de falhas (chamar após boot com sucesso, via OS agent
@@ -101,8 +239,13 @@ impl PersistentState {
     /// do sucesso, o que é difícil. Geralmente o OS que limpa essa flag
     /// após subir o Init System.
     pub fn reset(&mut self) {
+        self.reset_in(&mut FirmwareVariables);
+    }
+
+    /// Versão de `reset` sobre um armazenamento arbitrário.
+    pub fn reset_in<S: VariableStore + ?Sized>(&mut self, store: &mut S) {
         self.failed_attempts = 0;
         self.flags = 0;
-        self.save();
+        self.save_to(store);
     }
 }
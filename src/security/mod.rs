@@ -4,16 +4,19 @@
 //! - Detecção de Secure Boot
 //! - Medição TPM (Trusted Boot)
 //! - Políticas de execução
+//! - Limpeza de segredos (senhas, chaves) após o uso
 
 pub mod policy;
 pub mod secure_boot;
 pub mod tpm;
+pub mod zeroize;
 // pub mod verify; // Futuro: Verificação PE/COFF manual se necessário
 
 // Re-exports
 pub use policy::{PolicyAction, SecurityPolicy};
 pub use secure_boot::{SecureBootState, get_state};
 pub use tpm::measure_binary;
+pub use zeroize::Zeroizing;
 
 /// Função helper para validar e medir um arquivo carregado.
 pub fn validate_and_measure(
@@ -4,9 +4,12 @@
 //! Referência: UEFI Spec 2.10, Seção 3.3 (Global Variables)
 
 
-use crate::uefi::{
-    base::{Guid, Status},
-    system_table,
+use crate::{
+    core::handoff::{
+        SECURE_BOOT_STATE_DISABLED, SECURE_BOOT_STATE_ENABLED, SECURE_BOOT_STATE_SETUP,
+    },
+    recovery::state::{FirmwareVariables, VariableStore},
+    uefi::base::Guid,
 };
 
 /// GUID para Variáveis Globais EFI (EfiGlobalVariable).
@@ -31,68 +34,51 @@ pub enum SecureBootState {
     Unknown,
 }
 
+impl SecureBootState {
+    /// Valor entregue ao kernel em `BootInfo::secure_boot_state`. Um estado
+    /// ilegível vai como desligado, igual a `BootInfo::secure_boot`.
+    pub fn handoff_value(self) -> u8 {
+        match self {
+            SecureBootState::Enforced => SECURE_BOOT_STATE_ENABLED,
+            SecureBootState::SetupMode => SECURE_BOOT_STATE_SETUP,
+            SecureBootState::Disabled | SecureBootState::Unknown => SECURE_BOOT_STATE_DISABLED,
+        }
+    }
+}
+
 /// Verifica o estado do Secure Boot.
 pub fn get_state() -> SecureBootState {
-    let rt = system_table().runtime_services();
-
-    // Nome das variáveis (UCS-2)
-    // "SecureBoot\0"
-    let sb_name: [u16; 11] = [
-        'S' as u16, 'e' as u16, 'c' as u16, 'u' as u16, 'r' as u16, 'e' as u16, 'B' as u16,
-        'o' as u16, 'o' as u16, 't' as u16, 0,
-    ];
-
-    // "SetupMode\0"
-    let sm_name: [u16; 10] = [
-        'S' as u16, 'e' as u16, 't' as u16, 'u' as u16, 'p' as u16, 'M' as u16, 'o' as u16,
-        'd' as u16, 'e' as u16, 0,
-    ];
+    get_state_in(&FirmwareVariables)
+}
 
-    let mut data: u8 = 0;
-    let mut data_size = 1;
-    let mut attr = 0u32;
+/// Versão de `get_state` sobre um armazenamento de variáveis arbitrário.
+pub fn get_state_in<S: VariableStore + ?Sized>(store: &S) -> SecureBootState {
+    let mut data = [0u8; 1];
 
     // 1. Verificar SetupMode
     // Se SetupMode == 1, o Secure Boot não está operando normalmente (está
     // aprendendo chaves).
-    let status_sm = unsafe {
-        (rt.get_variable)(
-            sm_name.as_ptr(),
-            &EFI_GLOBAL_VARIABLE,
-            &mut attr,
-            &mut data_size,
-            &mut data as *mut u8 as *mut core::ffi::c_void,
-        )
-    };
-
-    if status_sm == Status::SUCCESS && data == 1 {
-        return SecureBootState::SetupMode;
+    if let Some(1) = store.read("SetupMode", &EFI_GLOBAL_VARIABLE, &mut data) {
+        if data[0] == 1 {
+            return SecureBootState::SetupMode;
+        }
     }
 
     // 2. Verificar SecureBoot
-    let status_sb = unsafe {
-        (rt.get_variable)(
-            sb_name.as_ptr(),
-            &EFI_GLOBAL_VARIABLE,
-            &mut attr,
-            &mut data_size,
-            &mut data as *mut u8 as *mut core::ffi::c_void,
-        )
-    };
-
-    match status_sb {
-        Status::SUCCESS => {
-            if data == 1 {
-                SecureBootState::Enforced
-            } else {
-                SecureBootState::Disabled
-            }
-        },
-        _ => SecureBootState::Unknown,
+    match store.read("SecureBoot", &EFI_GLOBAL_VARIABLE, &mut data) {
+        Some(1) if data[0] == 1 => SecureBootState::Enforced,
+        Some(_) => SecureBootState::Disabled,
+        None => SecureBootState::Unknown,
     }
 }
 
 /// Verifica se devemos exigir assinaturas digitais.
 pub fn enforcement_required() -> bool {
-    matches!(get_state(), SecureBootState::Enforced)
+    enforcement_required_in(&FirmwareVariables)
+}
+
+/// Versão de `enforcement_required` sobre um armazenamento arbitrário. É o
+/// valor entregue ao kernel em `BootInfo::secure_boot`.
+pub fn enforcement_required_in<S: VariableStore + ?Sized>(store: &S) -> bool {
+    matches!(get_state_in(store), SecureBootState::Enforced)
 }
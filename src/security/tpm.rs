@@ -6,13 +6,30 @@
 //!
 //! Referência: TCG EFI Protocol Specification
 
+use alloc::vec::Vec;
 use core::ffi::c_void;
 
-use crate::uefi::{
-    base::{Guid, Status},
-    system_table,
+use crate::{
+    core::error::Result,
+    uefi::{
+        base::{Guid, Status},
+        system_table,
+    },
 };
 
+/// PCR que recebe a medição do arquivo de configuração (política de boot).
+pub const CONFIG_PCR: u32 = 8;
+
+/// Tipo de evento `EV_IPL`: dados medidos pelo bootloader (TCG PC Client).
+pub const EV_IPL: u32 = 0x0000_000D;
+
+/// `sizeof(EFI_TCG2_EVENT_HEADER)`: HeaderSize, HeaderVersion, PCRIndex e
+/// EventType, sem padding.
+const TCG2_EVENT_HEADER_SIZE: u32 = 14;
+
+/// Versão do cabeçalho do evento exigida pelo protocolo.
+const TCG2_EVENT_HEADER_VERSION: u16 = 1;
+
 /// GUID do Protocolo TCG2 (TPM 2.0).
 pub const EFI_TCG2_PROTOCOL_GUID: Guid = Guid::new(
     0x607f766c,
@@ -21,14 +38,29 @@ pub const EFI_TCG2_PROTOCOL_GUID: Guid = Guid::new(
     [0x93, 0x0b, 0xe4, 0xd7, 0x6d, 0xb2, 0x72, 0x0f],
 );
 
-/// Evento de Log TCG.
+/// `EFI_TCG2_EVENT`: tamanho total, cabeçalho e dados do evento, que seguem
+/// a estrutura (tamanho variável; ver `tcg2_event`).
 #[repr(C, packed)]
-struct TcgPcrEvent {
-    pcr_index:  u32,
-    event_type: u32,
-    digest:     [u8; 20], // SHA1 legado (apenas placeholder para estrutura)
-    event_size: u32,
-    event:      [u8; 1], // Tamanho variável
+struct Tcg2Event {
+    size:           u32,
+    header_size:    u32,
+    header_version: u16,
+    pcr_index:      u32,
+    event_type:     u32,
+}
+
+/// Monta os bytes de um `EFI_TCG2_EVENT` para `pcr_index`, com
+/// `description` como dado do evento (o que aparece no log de eventos).
+pub fn tcg2_event(pcr_index: u32, event_type: u32, description: &str) -> Vec<u8> {
+    let size = 4 + TCG2_EVENT_HEADER_SIZE as usize + description.len();
+    let mut event = Vec::with_capacity(size);
+    event.extend_from_slice(&(size as u32).to_le_bytes());
+    event.extend_from_slice(&TCG2_EVENT_HEADER_SIZE.to_le_bytes());
+    event.extend_from_slice(&TCG2_EVENT_HEADER_VERSION.to_le_bytes());
+    event.extend_from_slice(&pcr_index.to_le_bytes());
+    event.extend_from_slice(&event_type.to_le_bytes());
+    event.extend_from_slice(description.as_bytes());
+    event
 }
 
 /// Protocolo EFI TCG2.
@@ -39,10 +71,10 @@ struct EfiTcg2Protocol {
         extern "efiapi" fn(*mut EfiTcg2Protocol, u32, *mut u64, *mut u64, *mut bool) -> Status,
     hash_log_extend_event: extern "efiapi" fn(
         *mut EfiTcg2Protocol,
-        u64,                // Flags
-        u64,                // DataToHash
-        u64,                // DataToHashLen
-        *const TcgPcrEvent, // EfiTcg2Event
+        u64,              // Flags
+        u64,              // DataToHash
+        u64,              // DataToHashLen
+        *const Tcg2Event, // EfiTcg2Event
     ) -> Status,
     submit_command:
         extern "efiapi" fn(*mut EfiTcg2Protocol, u32, *const u8, u32, *mut u8) -> Status,
@@ -63,22 +95,11 @@ pub fn measure_binary(
     pcr_index: u32,
     description: &str,
 ) -> crate::core::error::Result<()> {
-    let bs = system_table().boot_services();
-
-    // Tenta localizar o protocolo TPM2
-    let _protocol_ptr = match bs.locate_protocol(&EFI_TCG2_PROTOCOL_GUID) {
-        Ok(ptr) => ptr as *mut EfiTcg2Protocol,
-        Err(_) => return Ok(()), // TPM não presente é OK (apenas ignora medição)
-    };
-
-    // Em uma implementação real, construiríamos a estrutura EFI_TCG2_EVENT
-    // corretamente. Como ela é complexa e de tamanho variável, simplificamos
-    // aqui assumindo que o firmware vai calcular o hash do buffer `data` para
-    // nós.
-
-    // Nota: A função HashLogExtendEvent exige uma estrutura de evento complexa.
-    // Para este nível de abstração, sinalizamos apenas que o TPM está disponível.
-    // A implementação completa exigiria alocação dinâmica para o evento TCG.
+    let mut tpm = FirmwareTpm;
+    // TPM não presente é OK (apenas ignora medição)
+    if !tpm.available() {
+        return Ok(());
+    }
 
     crate::println!(
         "TPM2 detectado. Medição de {} bytes no PCR[{}] ('{}').",
@@ -86,8 +107,68 @@ pub fn measure_binary(
         pcr_index,
         description
     );
+    tpm.extend(pcr_index, data, description)
+}
+
+/// Destino das medições: o TPM do firmware ou, nos testes, um registro em
+/// memória.
+pub trait PcrExtender {
+    /// Se há um TPM para receber medições.
+    fn available(&self) -> bool;
+
+    /// Estende `pcr_index` com o hash de `data` em cada banco ativo
+    /// (SHA-256 inclusive), registrando `description` no log de eventos.
+    fn extend(&mut self, pcr_index: u32, data: &[u8], description: &str) -> Result<()>;
+}
+
+/// `PcrExtender` sobre o protocolo TCG2 do firmware.
+pub struct FirmwareTpm;
+
+impl PcrExtender for FirmwareTpm {
+    fn available(&self) -> bool {
+        system_table()
+            .boot_services()
+            .locate_protocol(&EFI_TCG2_PROTOCOL_GUID)
+            .is_ok()
+    }
+
+    fn extend(&mut self, pcr_index: u32, data: &[u8], description: &str) -> Result<()> {
+        let protocol = system_table()
+            .boot_services()
+            .locate_protocol(&EFI_TCG2_PROTOCOL_GUID)?
+            as *mut EfiTcg2Protocol;
+
+        // O firmware calcula o hash de `data` em cada banco PCR ativo, estende
+        // o PCR e acrescenta o evento ao log TCG
+        let event = tcg2_event(pcr_index, EV_IPL, description);
+        // SAFETY: `protocol` veio do `LocateProtocol`; `data` e `event` vivem
+        // até o retorno da chamada.
+        let status = unsafe {
+            ((*protocol).hash_log_extend_event)(
+                protocol,
+                0,
+                data.as_ptr() as u64,
+                data.len() as u64,
+                event.as_ptr() as *const Tcg2Event,
+            )
+        };
+        status.to_result()?;
+        Ok(())
+    }
+}
+
+/// Mede os bytes brutos do arquivo de configuração em `CONFIG_PCR`, antes do
+/// parse, para que a atestação cubra a política de boot.
+pub fn measure_config(data: &[u8]) -> Result<()> {
+    measure_config_with(&mut FirmwareTpm, data)
+}
 
-    // TODO: Implementar construção de Tcg2Event e chamar hash_log_extend_event
+/// Versão de `measure_config` sobre um `PcrExtender` arbitrário. Sem TPM, não
+/// faz nada.
+pub fn measure_config_with<T: PcrExtender + ?Sized>(tpm: &mut T, data: &[u8]) -> Result<()> {
+    if !tpm.available() {
+        return Ok(());
+    }
 
-    Ok(())
+    tpm.extend(CONFIG_PCR, data, "ignite.cfg")
 }
@@ -0,0 +1,59 @@
+//! Limpeza de Segredos em Memória
+//!
+//! Senhas e chaves lidas pelo bootloader ficam no heap, que é entregue ao
+//! kernel como memória comum. `Zeroizing` garante que o buffer seja zerado
+//! assim que sair de escopo, antes que alguém possa reaproveitá-lo.
+
+use core::{
+    ops::{Deref, DerefMut},
+    sync::atomic::{compiler_fence, Ordering},
+};
+
+/// Zera `buf` com escritas voláteis, que o compilador não pode descartar
+/// mesmo que o buffer não seja lido depois.
+pub fn zeroize(buf: &mut [u8]) {
+    let ptr = buf.as_mut_ptr();
+    for i in 0..buf.len() {
+        // SAFETY: `i < buf.len()`, dentro da fatia emprestada.
+        unsafe { core::ptr::write_volatile(ptr.add(i), 0) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Guarda que zera o conteúdo de `T` ao ser descartada.
+///
+/// Funciona com qualquer buffer de bytes (`Vec<u8>`, `[u8; N]`, `&mut [u8]`).
+pub struct Zeroizing<T: AsMut<[u8]>>(T);
+
+impl<T: AsMut<[u8]>> Zeroizing<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: AsMut<[u8]>> Deref for Zeroizing<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: AsMut<[u8]>> DerefMut for Zeroizing<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: AsMut<[u8]>> Drop for Zeroizing<T> {
+    fn drop(&mut self) {
+        zeroize(self.0.as_mut());
+    }
+}
+
+impl<T: AsMut<[u8]>> core::fmt::Debug for Zeroizing<T> {
+    // Nunca imprimir o segredo em logs
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Zeroizing(..)")
+    }
+}
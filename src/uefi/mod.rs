@@ -14,6 +14,8 @@ pub mod proto;
 pub mod status;
 pub mod table;
 
+use core::sync::atomic::{AtomicBool, Ordering};
+
 // Re-exports para facilitar o uso em todo o projeto
 pub use base::{Boolean, Char16, Event, Guid, Handle};
 pub use status::{Result, Status};
@@ -26,6 +28,9 @@ static mut SYSTEM_TABLE: *mut SystemTable = core::ptr::null_mut();
 /// Handle da Imagem do Bootloader.
 static mut IMAGE_HANDLE: Handle = Handle(core::ptr::null_mut());
 
+/// Falso depois de um `ExitBootServices` bem-sucedido.
+static BOOT_SERVICES_ACTIVE: AtomicBool = AtomicBool::new(true);
+
 /// Inicializa o subsistema UEFI.
 ///
 /// Deve ser chamado logo no início da função `efi_main`.
@@ -63,7 +68,23 @@ pub fn system_table() -> &'static mut SystemTable {
     }
 }
 
+/// Endereço físico da System Table global, ou 0 antes de `init()`.
+pub fn system_table_addr() -> u64 {
+    unsafe { SYSTEM_TABLE as u64 }
+}
+
 /// Retorna o Handle da imagem do Bootloader.
 pub fn image_handle() -> Handle {
     unsafe { IMAGE_HANDLE }
 }
+
+/// Se os Boot Services ainda estão disponíveis (antes do ExitBootServices).
+pub fn boot_services_active() -> bool {
+    BOOT_SERVICES_ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Registra que o ExitBootServices foi concluído. Runtime Services continuam
+/// válidos.
+pub fn mark_boot_services_exited() {
+    BOOT_SERVICES_ACTIVE.store(false, Ordering::Relaxed);
+}
@@ -1,6 +1,7 @@
 //! Protocolos de Console (Vídeo, Texto)
 
 pub mod gop;
+pub mod text_input_ex;
 
 // Re-exporta o GOP para facilitar o uso
 pub use gop::GraphicsOutputProtocol;
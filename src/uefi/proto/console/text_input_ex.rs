@@ -0,0 +1,57 @@
+//! Simple Text Input Ex Protocol
+//!
+//! Versão estendida do `SimpleTextInput`: além da tecla, informa o estado dos
+//! modificadores (Shift, Ctrl, Alt, Logo) e dos toggles (Caps/Num Lock).
+//! Referência: UEFI Spec 2.10, Seção 12.2
+
+use crate::uefi::{
+    base::{Boolean, Event, Guid, Status},
+    table::system::InputKey,
+};
+
+/// GUID do Protocolo Simple Text Input Ex.
+pub const SIMPLE_TEXT_INPUT_EX_PROTOCOL_GUID: Guid = Guid::new(
+    0xdd9e7534,
+    0x7762,
+    0x4698,
+    [0x8c, 0x14, 0xf5, 0x85, 0x17, 0x2a, 0x4e, 0x3a],
+);
+
+// Bits de `KeyState::key_shift_state`
+pub const SHIFT_STATE_VALID: u32 = 0x8000_0000;
+pub const RIGHT_SHIFT_PRESSED: u32 = 0x0000_0001;
+pub const LEFT_SHIFT_PRESSED: u32 = 0x0000_0002;
+pub const RIGHT_CONTROL_PRESSED: u32 = 0x0000_0004;
+pub const LEFT_CONTROL_PRESSED: u32 = 0x0000_0008;
+pub const RIGHT_ALT_PRESSED: u32 = 0x0000_0010;
+pub const LEFT_ALT_PRESSED: u32 = 0x0000_0020;
+pub const RIGHT_LOGO_PRESSED: u32 = 0x0000_0040;
+pub const LEFT_LOGO_PRESSED: u32 = 0x0000_0080;
+
+/// Estado dos modificadores no momento da tecla.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct KeyState {
+    pub key_shift_state:  u32,
+    pub key_toggle_state: u8,
+}
+
+/// Tecla com estado de modificadores.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct KeyData {
+    pub key:       InputKey,
+    pub key_state: KeyState,
+}
+
+/// A Interface do Protocolo Simple Text Input Ex.
+#[repr(C)]
+pub struct SimpleTextInputExProtocol {
+    pub reset:                 extern "efiapi" fn(*mut Self, Boolean) -> Status,
+    pub read_key_stroke_ex:    extern "efiapi" fn(*mut Self, *mut KeyData) -> Status,
+    pub wait_for_key_ex:       Event,
+    pub set_state:             extern "efiapi" fn(*mut Self, *const u8) -> Status,
+    // Notificações de tecla não são usadas pelo bootloader
+    pub register_key_notify:   usize,
+    pub unregister_key_notify: usize,
+}
@@ -0,0 +1,93 @@
+//! Device Path Protocol
+//!
+//! Sequência de nós (tipo, subtipo, tamanho, dados) que descreve o caminho
+//! físico até um dispositivo: raiz PCI, controlador, disco, partição...
+//! Referência: UEFI Spec 2.10, Seção 10
+
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::uefi::base::Guid;
+
+pub const DEVICE_PATH_PROTOCOL_GUID: Guid = Guid::new(
+    0x09576e91,
+    0x6d3f,
+    0x11d2,
+    [0x8e, 0x39, 0x00, 0xa0, 0xc9, 0x69, 0x72, 0x3b],
+);
+
+/// Cabeçalho comum a todos os nós.
+#[repr(C)]
+pub struct DevicePathProtocol {
+    pub node_type: u8,
+    pub sub_type:  u8,
+    pub length:    [u8; 2],
+}
+
+/// Tipo e subtipo do nó que encerra o caminho inteiro.
+const END_TYPE: u8 = 0x7F;
+const END_ENTIRE_SUBTYPE: u8 = 0xFF;
+
+/// Limite de bytes percorridos, contra caminhos corrompidos sem nó de fim.
+pub const MAX_DEVICE_PATH_SIZE: usize = 4096;
+
+/// Bytes do caminho em `ptr`, incluindo o nó de fim.
+///
+/// Retorna `None` se um nó tiver tamanho inválido ou o fim não aparecer em
+/// `MAX_DEVICE_PATH_SIZE` bytes.
+///
+/// # Safety
+/// `ptr` deve apontar para um Device Path válido do firmware.
+pub unsafe fn device_path_bytes<'a>(ptr: *const DevicePathProtocol) -> Option<&'a [u8]> {
+    let base = ptr as *const u8;
+    let mut offset = 0usize;
+
+    while offset + 4 <= MAX_DEVICE_PATH_SIZE {
+        let node = base.add(offset);
+        let len = u16::from_le_bytes([*node.add(2), *node.add(3)]) as usize;
+        if len < 4 {
+            return None;
+        }
+        offset += len;
+        if *node == END_TYPE && *node.add(1) == END_ENTIRE_SUBTYPE {
+            return Some(core::slice::from_raw_parts(base, offset));
+        }
+    }
+
+    None
+}
+
+/// Texto estável para `path`: cada nó como `TTSS(dados)` em hexadecimal,
+/// separados por `/`.
+///
+/// Não é o formato legível da spec (`PciRoot(0x0)/...`), mas depende só do
+/// conteúdo do caminho, então serve para ordenar dispositivos de forma
+/// reprodutível entre boots.
+pub fn path_key(path: &[u8]) -> Option<String> {
+    let mut key = String::new();
+    let mut rest = path;
+
+    loop {
+        if rest.len() < 4 {
+            return None;
+        }
+        let len = u16::from_le_bytes([rest[2], rest[3]]) as usize;
+        if len < 4 || len > rest.len() {
+            return None;
+        }
+        if rest[0] == END_TYPE && rest[1] == END_ENTIRE_SUBTYPE {
+            return Some(key);
+        }
+
+        if !key.is_empty() {
+            key.push('/');
+        }
+        let _ = write!(key, "{:02x}{:02x}(", rest[0], rest[1]);
+        for byte in &rest[4..len] {
+            let _ = write!(key, "{:02x}", byte);
+        }
+        key.push(')');
+
+        rest = &rest[len..];
+    }
+}
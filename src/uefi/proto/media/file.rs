@@ -7,6 +7,7 @@ use core::ffi::c_void;
 
 use crate::uefi::{
     base::{Char16, Guid, Status},
+    table::runtime::Time,
     Result,
 };
 
@@ -21,6 +22,29 @@ pub const FILE_INFO_GUID: Guid = Guid::new(
     [0x8e, 0x39, 0x00, 0xa0, 0xc9, 0x69, 0x72, 0x3b],
 );
 
+// Atributos de `FileInfo::attribute`
+pub const FILE_READ_ONLY: u64 = 0x0000000000000001;
+pub const FILE_HIDDEN: u64 = 0x0000000000000002;
+pub const FILE_SYSTEM: u64 = 0x0000000000000004;
+pub const FILE_DIRECTORY: u64 = 0x0000000000000010;
+pub const FILE_ARCHIVE: u64 = 0x0000000000000020;
+
+/// Cabeçalho de `EFI_FILE_INFO`.
+///
+/// O nome do arquivo (UCS-2, terminado em NUL) segue imediatamente a
+/// estrutura, por isso o tamanho total só é conhecido em tempo de execução.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct FileInfo {
+    pub size:              u64,
+    pub file_size:         u64,
+    pub physical_size:     u64,
+    pub create_time:       Time,
+    pub last_access_time:  Time,
+    pub modification_time: Time,
+    pub attribute:         u64,
+}
+
 #[repr(C)]
 pub struct FileProtocol {
     pub revision:     u64,
@@ -0,0 +1,191 @@
+//! Load File 2 Protocol (initrd para o EFI stub do Linux)
+//!
+//! Kernels Linux com EFI stub (5.8+) não leem mais o initrd de um ponteiro
+//! nos boot params: procuram um handle cujo Device Path seja o nó Vendor
+//! `LINUX_EFI_INITRD_MEDIA_GUID` e pedem os bytes pelo `LoadFile2` dele.
+//! `InitrdMedia` instala esse par servindo um initrd já carregado na memória.
+//! Referência: UEFI Spec 2.10, Seção 13.2
+
+use alloc::boxed::Box;
+use core::ffi::c_void;
+
+use crate::uefi::{
+    base::{Guid, Handle, Status},
+    proto::device_path::{DevicePathProtocol, DEVICE_PATH_PROTOCOL_GUID},
+    table::boot::BootServices,
+    Result,
+};
+
+pub const LOAD_FILE2_PROTOCOL_GUID: Guid = Guid::new(
+    0x4006c0c1,
+    0xfcb3,
+    0x403e,
+    [0x99, 0x6d, 0x4a, 0x6c, 0x87, 0x24, 0xe0, 0x6d],
+);
+
+/// GUID do nó Vendor que o EFI stub do Linux procura.
+pub const LINUX_EFI_INITRD_MEDIA_GUID: Guid = Guid::new(
+    0x5568e427,
+    0x68fc,
+    0x4f3d,
+    [0xac, 0x74, 0xca, 0x55, 0x52, 0x31, 0xcc, 0x68],
+);
+
+/// Assinatura de `LoadFile` (`BootPolicy` é um BOOLEAN de 1 byte).
+pub type LoadFileFn = unsafe extern "efiapi" fn(
+    *mut LoadFile2Protocol,
+    *const DevicePathProtocol,
+    u8,
+    *mut usize,
+    *mut c_void,
+) -> Status;
+
+#[repr(C)]
+pub struct LoadFile2Protocol {
+    pub load_file: LoadFileFn,
+}
+
+/// Device Path `VenMedia(LINUX_EFI_INITRD_MEDIA_GUID)/End`.
+#[repr(C)]
+pub struct InitrdDevicePath {
+    pub vendor: DevicePathProtocol,
+    pub guid:   Guid,
+    pub end:    DevicePathProtocol,
+}
+
+/// Tipo e subtipo do nó Vendor de mídia.
+const MEDIA_DEVICE_PATH: u8 = 0x04;
+const MEDIA_VENDOR_DP: u8 = 0x03;
+
+impl InitrdDevicePath {
+    pub const fn new() -> Self {
+        Self {
+            vendor: DevicePathProtocol {
+                node_type: MEDIA_DEVICE_PATH,
+                sub_type:  MEDIA_VENDOR_DP,
+                length:    [20, 0],
+            },
+            guid:   LINUX_EFI_INITRD_MEDIA_GUID,
+            end:    DevicePathProtocol {
+                node_type: 0x7F,
+                sub_type:  0xFF,
+                length:    [4, 0],
+            },
+        }
+    }
+}
+
+impl Default for InitrdDevicePath {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Instância de `LoadFile2` que entrega `len` bytes a partir de `data`.
+///
+/// O protocolo vem primeiro: o firmware passa `this` apontando para ele, e o
+/// callback recupera o resto da struct a partir do mesmo ponteiro.
+#[repr(C)]
+pub struct InitrdLoadFile2 {
+    pub protocol: LoadFile2Protocol,
+    pub data:     *const u8,
+    pub len:      usize,
+}
+
+impl InitrdLoadFile2 {
+    pub fn new(data: *const u8, len: usize) -> Self {
+        Self {
+            protocol: LoadFile2Protocol {
+                load_file: initrd_load_file,
+            },
+            data,
+            len,
+        }
+    }
+}
+
+/// `LoadFile` do initrd: com `buffer` nulo ou pequeno demais, responde só o
+/// tamanho (`BUFFER_TOO_SMALL`); senão copia os bytes.
+unsafe extern "efiapi" fn initrd_load_file(
+    this: *mut LoadFile2Protocol,
+    _file_path: *const DevicePathProtocol,
+    boot_policy: u8,
+    buffer_size: *mut usize,
+    buffer: *mut c_void,
+) -> Status {
+    // LoadFile2 não aceita BootPolicy (ver Seção 13.2)
+    if boot_policy != 0 {
+        return Status::UNSUPPORTED;
+    }
+    if this.is_null() || buffer_size.is_null() {
+        return Status::INVALID_PARAMETER;
+    }
+
+    let initrd = &*(this as *const InitrdLoadFile2);
+    if buffer.is_null() || *buffer_size < initrd.len {
+        *buffer_size = initrd.len;
+        return Status::BUFFER_TOO_SMALL;
+    }
+
+    core::ptr::copy_nonoverlapping(initrd.data, buffer as *mut u8, initrd.len);
+    *buffer_size = initrd.len;
+    Status::SUCCESS
+}
+
+/// Handle com o Device Path do initrd e o `LoadFile2` que o serve.
+///
+/// O initrd (e esta struct) precisa continuar válido enquanto o protocolo
+/// estiver instalado; `uninstall` remove os dois protocolos.
+pub struct InitrdMedia {
+    handle:      Handle,
+    device_path: Box<InitrdDevicePath>,
+    load_file:   Box<InitrdLoadFile2>,
+}
+
+impl InitrdMedia {
+    /// Instala o initrd de `len` bytes em `data` num handle novo.
+    pub fn install(bs: &BootServices, data: *const u8, len: usize) -> Result<Self> {
+        let mut media = Self {
+            handle:      Handle::null(),
+            device_path: Box::new(InitrdDevicePath::new()),
+            load_file:   Box::new(InitrdLoadFile2::new(data, len)),
+        };
+
+        // O Device Path cria o handle; o LoadFile2 entra no mesmo handle.
+        // SAFETY: as duas estruturas ficam no heap, presas em `media`, até
+        // `uninstall`.
+        let device_path = &mut *media.device_path as *mut InitrdDevicePath as *mut c_void;
+        let load_file = &mut *media.load_file as *mut InitrdLoadFile2 as *mut c_void;
+        unsafe {
+            bs.install_protocol(&mut media.handle, &DEVICE_PATH_PROTOCOL_GUID, device_path)?;
+            if let Err(e) =
+                bs.install_protocol(&mut media.handle, &LOAD_FILE2_PROTOCOL_GUID, load_file)
+            {
+                let _ =
+                    bs.uninstall_protocol(media.handle, &DEVICE_PATH_PROTOCOL_GUID, device_path);
+                return Err(e);
+            }
+        }
+
+        Ok(media)
+    }
+
+    /// Remove os protocolos instalados por `install`.
+    ///
+    /// Se o firmware recusar a remoção, o handle ainda aponta para as
+    /// estruturas: elas são vazadas em vez de liberadas.
+    pub fn uninstall(mut self, bs: &BootServices) -> Result<()> {
+        let load_file = &mut *self.load_file as *mut InitrdLoadFile2 as *mut c_void;
+        let device_path = &mut *self.device_path as *mut InitrdDevicePath as *mut c_void;
+        let result = unsafe {
+            bs.uninstall_protocol(self.handle, &LOAD_FILE2_PROTOCOL_GUID, load_file)
+                .and_then(|()| {
+                    bs.uninstall_protocol(self.handle, &DEVICE_PATH_PROTOCOL_GUID, device_path)
+                })
+        };
+        if result.is_err() {
+            core::mem::forget(self);
+        }
+        result
+    }
+}
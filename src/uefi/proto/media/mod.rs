@@ -2,7 +2,9 @@
 
 pub mod file;
 pub mod fs;
+pub mod load_file;
 
 // Re-exports
 pub use file::FileProtocol;
 pub use fs::SimpleFileSystemProtocol;
+pub use load_file::{InitrdMedia, LoadFile2Protocol};
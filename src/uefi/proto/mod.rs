@@ -2,5 +2,7 @@
 // Contém definições de protocolos UEFI usados para comunicação com o firmware.
 // Referência: UEFI Spec 2.10, Seção 9
 pub mod console;
+pub mod device_path;
 pub mod loaded_image;
 pub mod media;
+pub mod network;
@@ -0,0 +1,145 @@
+//! HTTP Protocol e Service Binding
+//!
+//! O firmware expõe um `EFI_HTTP_SERVICE_BINDING_PROTOCOL` por interface de
+//! rede; cada instância de `EFI_HTTP_PROTOCOL` é criada com `CreateChild`.
+//! Nomes de host são resolvidos pelo próprio driver HTTP, que usa o DNS4 do
+//! firmware.
+//! Referência: UEFI Spec 2.10, Seções 11.6 e 29.6
+
+use core::ffi::c_void;
+
+use crate::uefi::{
+    base::{Boolean, Event, Guid, Handle, Status},
+    Result,
+};
+
+pub const HTTP_SERVICE_BINDING_PROTOCOL_GUID: Guid = Guid::new(
+    0xbdc8e6af,
+    0xd9bc,
+    0x4379,
+    [0xa7, 0x2a, 0xe0, 0xc4, 0xe7, 0x5d, 0xae, 0x1c],
+);
+
+pub const HTTP_PROTOCOL_GUID: Guid = Guid::new(
+    0x7a59b29b,
+    0x910b,
+    0x4171,
+    [0x82, 0x42, 0xa8, 0x5a, 0x0d, 0xf2, 0x5b, 0x5b],
+);
+
+pub const DNS4_SERVICE_BINDING_PROTOCOL_GUID: Guid = Guid::new(
+    0xb625b186,
+    0xe063,
+    0x44f7,
+    [0x89, 0x05, 0x6a, 0x74, 0xdc, 0x6f, 0x52, 0xb4],
+);
+
+/// `EFI_HTTP_VERSION`: HTTP/1.1.
+pub const HTTP_VERSION_11: u32 = 1;
+
+/// `EFI_HTTP_METHOD`: GET.
+pub const HTTP_METHOD_GET: u32 = 0;
+
+/// Código HTTP de cada valor de `EFI_HTTP_STATUS_CODE` (0 = não suportado).
+const STATUS_CODES: [u16; 43] = [
+    0, 100, 101, 200, 201, 202, 203, 204, 205, 206, 300, 301, 302, 303, 304, 305, 307, 400, 401,
+    402, 403, 404, 405, 406, 407, 408, 409, 410, 411, 412, 413, 414, 415, 416, 417, 500, 501, 502,
+    503, 504, 505, 308, 429,
+];
+
+/// Converte `EFI_HTTP_STATUS_CODE` no código numérico; 0 se desconhecido.
+pub fn status_code(value: u32) -> u16 {
+    STATUS_CODES.get(value as usize).copied().unwrap_or(0)
+}
+
+/// `EFI_SERVICE_BINDING_PROTOCOL`, comum a todos os protocolos de rede.
+#[repr(C)]
+pub struct ServiceBindingProtocol {
+    pub create_child:  extern "efiapi" fn(*mut Self, *mut Handle) -> Status,
+    pub destroy_child: extern "efiapi" fn(*mut Self, Handle) -> Status,
+}
+
+impl ServiceBindingProtocol {
+    /// Cria um handle filho com uma nova instância do protocolo.
+    pub fn create_child(&mut self) -> Result<Handle> {
+        let mut handle = Handle::null();
+        (self.create_child)(self, &mut handle).to_result_with(handle)
+    }
+
+    /// Destrói um filho criado por `create_child`, com a instância dele.
+    pub fn destroy_child(&mut self, child: Handle) -> Result<()> {
+        (self.destroy_child)(self, child).to_result()
+    }
+}
+
+/// `EFI_HTTPv4_ACCESS_POINT`.
+#[repr(C)]
+pub struct HttpV4AccessPoint {
+    pub use_default_address: Boolean,
+    pub local_address:       [u8; 4],
+    pub local_subnet:        [u8; 4],
+    pub local_port:          u16,
+}
+
+/// `EFI_HTTP_CONFIG_DATA` (só IPv4).
+#[repr(C)]
+pub struct HttpConfigData {
+    pub http_version:          u32,
+    pub timeout_millisec:      u32,
+    pub local_address_is_ipv6: Boolean,
+    pub access_point:          *mut HttpV4AccessPoint,
+}
+
+/// `EFI_HTTP_REQUEST_DATA`.
+#[repr(C)]
+pub struct HttpRequestData {
+    pub method: u32,
+    /// URL em UCS-2 terminada em NUL.
+    pub url:    *const u16,
+}
+
+/// `EFI_HTTP_RESPONSE_DATA`.
+#[repr(C)]
+pub struct HttpResponseData {
+    /// Valor de `EFI_HTTP_STATUS_CODE` (ver `status_code`).
+    pub status_code: u32,
+}
+
+/// `EFI_HTTP_HEADER`: nome e valor ASCII terminados em NUL.
+#[repr(C)]
+pub struct HttpHeader {
+    pub field_name:  *mut u8,
+    pub field_value: *mut u8,
+}
+
+/// `EFI_HTTP_MESSAGE`. `data` aponta para `HttpRequestData` no envio e para
+/// `HttpResponseData` na resposta (nulo ao ler só o corpo).
+#[repr(C)]
+pub struct HttpMessage {
+    pub data:         *mut c_void,
+    pub header_count: usize,
+    pub headers:      *mut HttpHeader,
+    pub body_length:  usize,
+    pub body:         *mut c_void,
+}
+
+/// `EFI_HTTP_TOKEN`: `event` é sinalizado quando a operação termina e
+/// `status` recebe o resultado.
+#[repr(C)]
+pub struct HttpToken {
+    pub event:   Event,
+    pub status:  Status,
+    pub message: *mut HttpMessage,
+}
+
+/// A Interface do Protocolo HTTP.
+#[repr(C)]
+pub struct HttpProtocol {
+    // Modo de operação não é consultado pelo bootloader
+    pub get_mode_data: usize,
+    pub configure:     extern "efiapi" fn(*mut Self, *const HttpConfigData) -> Status,
+    pub request:       extern "efiapi" fn(*mut Self, *mut HttpToken) -> Status,
+    pub cancel:        extern "efiapi" fn(*mut Self, *mut HttpToken) -> Status,
+    pub response:      extern "efiapi" fn(*mut Self, *mut HttpToken) -> Status,
+    pub poll:          extern "efiapi" fn(*mut Self) -> Status,
+}
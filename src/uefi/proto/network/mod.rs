@@ -0,0 +1,6 @@
+//! Protocolos de Rede (HTTP, DNS)
+
+pub mod http;
+
+// Re-exports
+pub use http::{HttpProtocol, ServiceBindingProtocol};
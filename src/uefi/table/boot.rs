@@ -87,6 +87,29 @@ pub struct MemoryDescriptor {
     pub attribute:       u64,
 }
 
+/// Assinatura de `EFI_BOOT_SERVICES.AllocatePages`.
+pub type AllocatePagesFn =
+    unsafe extern "efiapi" fn(AllocateType, MemoryType, usize, *mut u64) -> Status;
+
+/// Chama `allocate_pages_f` com o endereço de entrada adequado a `ty`.
+///
+/// `AllocatePages` usa o mesmo ponteiro como entrada e saída: para
+/// `AllocateAddress` é o endereço exato pedido e para `AllocateMaxAddress` o
+/// limite superior; para `AllocateAnyPages` o valor é ignorado e passamos 0.
+pub fn call_allocate_pages(
+    allocate_pages_f: AllocatePagesFn,
+    ty: AllocateType,
+    memory_type: MemoryType,
+    pages: usize,
+    address: u64,
+) -> Result<u64> {
+    let mut addr = match ty {
+        AllocateType::AllocateAnyPages => 0,
+        _ => address,
+    };
+    unsafe { (allocate_pages_f)(ty, memory_type, pages, &mut addr).to_result_with(addr) }
+}
+
 // --- Tabela BootServices (FFI) ---
 
 #[repr(C)]
@@ -98,8 +121,7 @@ pub struct BootServices {
     pub restore_tpl_f: unsafe extern "efiapi" fn(usize),
 
     // Memory Services
-    pub allocate_pages_f:
-        unsafe extern "efiapi" fn(AllocateType, MemoryType, usize, *mut u64) -> Status,
+    pub allocate_pages_f: AllocatePagesFn,
     pub free_pages_f:     unsafe extern "efiapi" fn(u64, usize) -> Status,
     pub get_memory_map_f: unsafe extern "efiapi" fn(
         *mut usize,
@@ -199,17 +221,17 @@ pub struct BootServices {
 
 impl BootServices {
     /// Aloca páginas de memória física.
+    ///
+    /// `address` é o endereço pedido (`AllocateAddress`) ou o limite superior
+    /// (`AllocateMaxAddress`); é ignorado com `AllocateAnyPages`.
     pub fn allocate_pages(
         &self,
         ty: AllocateType,
         memory_type: MemoryType,
         pages: usize,
+        address: u64,
     ) -> Result<u64> {
-        let mut addr = 0;
-        // Nota: Para AllocateAddress, addr deve ser preenchido pelo chamador.
-        // Se usar AllocateAddress, certifique-se de chamar allocate_at ou configurar
-        // addr.
-        unsafe { (self.allocate_pages_f)(ty, memory_type, pages, &mut addr).to_result_with(addr) }
+        call_allocate_pages(self.allocate_pages_f, ty, memory_type, pages, address)
     }
 
     /// Aloca páginas em um endereço específico.
@@ -219,11 +241,7 @@ impl BootServices {
         pages: usize,
         target_addr: u64,
     ) -> Result<u64> {
-        let mut addr = target_addr;
-        unsafe {
-            (self.allocate_pages_f)(AllocateType::AllocateAddress, memory_type, pages, &mut addr)
-                .to_result_with(addr)
-        }
+        self.allocate_pages(AllocateType::AllocateAddress, memory_type, pages, target_addr)
     }
 
     /// Libera páginas de memória.
@@ -251,6 +269,40 @@ impl BootServices {
         }
     }
 
+    /// Instala `interface` como `protocol` em `handle`. Com `handle` nulo, o
+    /// firmware cria um handle novo e o devolve nele.
+    ///
+    /// # Safety
+    /// `interface` deve apontar para uma estrutura do layout de `protocol` e
+    /// continuar válida até ser removida com `uninstall_protocol`.
+    pub unsafe fn install_protocol(
+        &self,
+        handle: &mut Handle,
+        protocol: &Guid,
+        interface: *mut c_void,
+    ) -> Result<()> {
+        (self.install_protocol_interface_f)(
+            handle,
+            protocol,
+            InterfaceType::NativeInterface,
+            interface,
+        )
+        .to_result()
+    }
+
+    /// Remove de `handle` o `protocol` instalado com `interface`.
+    ///
+    /// # Safety
+    /// `interface` deve ser o ponteiro passado a `install_protocol`.
+    pub unsafe fn uninstall_protocol(
+        &self,
+        handle: Handle,
+        protocol: &Guid,
+        interface: *mut c_void,
+    ) -> Result<()> {
+        (self.uninstall_protocol_interface_f)(handle, protocol, interface).to_result()
+    }
+
     /// Abre um protocolo em um handle específico.
     pub fn open_protocol(
         &self,
@@ -27,6 +27,64 @@ pub struct Time {
     pub pad2:       u8,
 }
 
+// Atributos de variáveis UEFI (Spec 2.10, Seção 8.2)
+pub const VARIABLE_NON_VOLATILE: u32 = 0x00000001;
+pub const VARIABLE_BOOTSERVICE_ACCESS: u32 = 0x00000002;
+pub const VARIABLE_RUNTIME_ACCESS: u32 = 0x00000004;
+
+/// Comprimento máximo (em caracteres) de nomes aceitos pelos wrappers de
+/// variáveis. Nomes reais da spec são curtos ("SecureBoot", "BootOrder").
+pub const MAX_VARIABLE_NAME_LEN: usize = 64;
+
+/// Falhas dos wrappers seguros de variáveis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableError {
+    /// O buffer fornecido é pequeno demais. Contém o tamanho necessário em
+    /// bytes, para que o chamador possa realocar e tentar de novo.
+    BufferTooSmall(usize),
+    /// Nome vazio, longo demais ou com caracteres fora do UCS-2.
+    InvalidName,
+    /// Qualquer outro erro do firmware (ex: `NOT_FOUND`).
+    Firmware(Status),
+}
+
+impl VariableError {
+    /// Status UEFI equivalente ao erro.
+    pub fn status(&self) -> Status {
+        match self {
+            VariableError::BufferTooSmall(_) => Status::BUFFER_TOO_SMALL,
+            VariableError::InvalidName => Status::INVALID_PARAMETER,
+            VariableError::Firmware(s) => *s,
+        }
+    }
+}
+
+/// Converte um nome de variável para UCS-2 terminado em NUL.
+///
+/// O firmware só aceita UCS-2, então caracteres fora do BMP (que exigiriam
+/// surrogates em UTF-16) e NULs internos são rejeitados.
+pub fn encode_variable_name(
+    name: &str,
+) -> core::result::Result<[Char16; MAX_VARIABLE_NAME_LEN + 1], VariableError> {
+    let mut out = [0 as Char16; MAX_VARIABLE_NAME_LEN + 1];
+    let mut len = 0;
+
+    for c in name.chars() {
+        let code = c as u32;
+        if code == 0 || code > 0xFFFF || len == MAX_VARIABLE_NAME_LEN {
+            return Err(VariableError::InvalidName);
+        }
+        out[len] = code as Char16;
+        len += 1;
+    }
+
+    if len == 0 {
+        return Err(VariableError::InvalidName);
+    }
+
+    Ok(out)
+}
+
 #[repr(u32)]
 #[derive(Debug, Copy, Clone)]
 pub enum ResetType {
@@ -88,4 +146,60 @@ impl RuntimeServices {
         let mut time = Time::default();
         unsafe { (self.get_time)(&mut time, core::ptr::null_mut()).to_result_with(time) }
     }
+
+    /// Lê uma variável UEFI para `buf`.
+    ///
+    /// # Retorna
+    /// * `Ok((tamanho, atributos))` com o número de bytes escritos em `buf`.
+    /// * `Err(VariableError::BufferTooSmall(n))` se `buf` tiver menos que `n`
+    ///   bytes.
+    pub fn get_variable(
+        &self,
+        name: &str,
+        guid: &Guid,
+        buf: &mut [u8],
+    ) -> core::result::Result<(usize, u32), VariableError> {
+        let name_ucs2 = encode_variable_name(name)?;
+        let mut size = buf.len();
+        let mut attributes = 0u32;
+
+        let status = unsafe {
+            (self.get_variable)(
+                name_ucs2.as_ptr(),
+                guid,
+                &mut attributes,
+                &mut size,
+                buf.as_mut_ptr() as *mut c_void,
+            )
+        };
+
+        match status {
+            Status::SUCCESS => Ok((size, attributes)),
+            Status::BUFFER_TOO_SMALL => Err(VariableError::BufferTooSmall(size)),
+            s => Err(VariableError::Firmware(s)),
+        }
+    }
+
+    /// Cria, sobrescreve ou (com `data` vazio) apaga uma variável UEFI.
+    pub fn set_variable(
+        &self,
+        name: &str,
+        guid: &Guid,
+        attributes: u32,
+        data: &[u8],
+    ) -> core::result::Result<(), VariableError> {
+        let name_ucs2 = encode_variable_name(name)?;
+
+        let status = unsafe {
+            (self.set_variable)(
+                name_ucs2.as_ptr(),
+                guid,
+                attributes,
+                data.len(),
+                data.as_ptr() as *mut c_void,
+            )
+        };
+
+        status.to_result().map_err(VariableError::Firmware)
+    }
 }
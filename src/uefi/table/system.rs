@@ -67,7 +67,7 @@ pub struct SimpleTextOutputProtocol {
 /// Usada para localizar ACPI (RSDP), SMBIOS, Device Tree, etc.
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
-pub struct ConfigurationTable {
+pub struct ConfigurationTableEntry {
     pub vendor_guid:  Guid,
     pub vendor_table: *mut c_void,
 }
@@ -92,7 +92,7 @@ pub struct SystemTable {
     pub boot_services:    *mut BootServices,
 
     pub number_of_table_entries: usize,
-    pub configuration_table:     *mut ConfigurationTable,
+    pub configuration_table:     *mut ConfigurationTableEntry,
 }
 
 impl SystemTable {
@@ -114,23 +114,29 @@ impl SystemTable {
         unsafe { &*self.runtime_services }
     }
 
-    /// Procura uma tabela de configuração pelo GUID (ex: ACPI).
+    /// Entradas da Tabela de Configuração publicadas pelo firmware.
+    ///
+    /// Vazio se o ponteiro for nulo ou a contagem for zero.
+    pub fn config_entries(&self) -> &[ConfigurationTableEntry] {
+        if self.configuration_table.is_null() || self.number_of_table_entries == 0 {
+            return &[];
+        }
+        unsafe {
+            core::slice::from_raw_parts(self.configuration_table, self.number_of_table_entries)
+        }
+    }
+
+    /// Procura uma tabela de configuração pelo GUID (ex: ACPI, SMBIOS).
     ///
     /// # Argumentos
     /// * `guid` - O GUID da tabela desejada (ex: `config::ACPI_20_TABLE_GUID`).
     ///
     /// # Retorna
     /// `Option<*mut c_void>` apontando para a estrutura física da tabela.
-    pub fn get_configuration_table(&self, guid: &Guid) -> Option<*mut c_void> {
-        let tables = unsafe {
-            core::slice::from_raw_parts(self.configuration_table, self.number_of_table_entries)
-        };
-
-        for table in tables {
-            if table.vendor_guid == *guid {
-                return Some(table.vendor_table);
-            }
-        }
-        None
+    pub fn find_config_table(&self, guid: &Guid) -> Option<*mut c_void> {
+        self.config_entries()
+            .iter()
+            .find(|entry| entry.vendor_guid == *guid)
+            .map(|entry| entry.vendor_table)
     }
 }
@@ -0,0 +1,74 @@
+//! Console de Texto sobre o Framebuffer
+//!
+//! Sink de vídeo dos logs (`core::logging::attach_video_sink`): escreve as
+//! linhas com a fonte embutida, da esquerda para a direita e de cima para
+//! baixo. Sem rolagem: ao passar da última linha, limpa a tela e recomeça
+//! do topo.
+
+use super::{graphics::GraphicsContext, theme::Theme};
+use crate::core::logging::LogWriter;
+
+/// Console de texto com cursor próprio.
+pub struct FramebufferConsole {
+    gfx:    GraphicsContext<'static>,
+    theme:  Theme,
+    column: u32,
+    row:    u32,
+}
+
+impl FramebufferConsole {
+    /// Cria o console sobre `gfx` e limpa a tela.
+    pub fn new(mut gfx: GraphicsContext<'static>) -> Self {
+        let theme = Theme::default();
+        gfx.clear(theme.background);
+        Self {
+            gfx,
+            theme,
+            column: 0,
+            row: 0,
+        }
+    }
+
+    fn columns(&self) -> u32 {
+        self.gfx.width() / 8
+    }
+
+    fn rows(&self) -> u32 {
+        self.gfx.height() / 16
+    }
+
+    fn new_line(&mut self) {
+        self.column = 0;
+        self.row += 1;
+        if self.row >= self.rows() {
+            self.row = 0;
+            self.gfx.clear(self.theme.background);
+        }
+    }
+}
+
+impl LogWriter for FramebufferConsole {
+    fn write_char(&mut self, c: char) {
+        if c == '\n' {
+            self.new_line();
+            return;
+        }
+        if self.column >= self.columns() {
+            self.new_line();
+        }
+        self.gfx.draw_char(
+            self.column * 8,
+            self.row * 16,
+            c,
+            self.theme.foreground,
+            Some(self.theme.background),
+        );
+        self.column += 1;
+    }
+
+    fn write_str(&mut self, s: &str) {
+        for c in s.chars() {
+            self.write_char(c);
+        }
+    }
+}
@@ -5,6 +5,13 @@
 //!
 //! Cada glifo tem 8 pixels de largura e 16 de altura.
 //! Cada byte no array representa uma linha horizontal do glifo.
+//!
+//! Uma fonte PSF opcional (`font_path`) complementa a tabela fora do ASCII.
+
+use super::psf::PsfFont;
+
+/// Glifo usado quando nenhuma fonte tem o caractere (caixa vazia).
+const BOX_GLYPH: usize = 0x7F;
 
 /// Fonte Bitmap 8x16.
 #[derive(Debug, Clone, Copy)]
@@ -13,14 +20,13 @@ pub struct BitFont {
     pub height: u32,
     /// Largura do glifo em pixels.
     pub width:  u32,
+    /// Fonte extra consultada para caracteres fora do ASCII.
+    extra:      Option<&'static PsfFont>,
 }
 
 impl Default for BitFont {
     fn default() -> Self {
-        Self {
-            height: 16,
-            width:  8,
-        }
+        Self::new()
     }
 }
 
@@ -30,26 +36,30 @@ impl BitFont {
         Self {
             height: 16,
             width:  8,
+            extra:  None,
+        }
+    }
+
+    /// Fonte embutida complementada por `extra` fora do ASCII.
+    pub const fn with_extra(extra: &'static PsfFont) -> Self {
+        Self {
+            extra: Some(extra),
+            ..Self::new()
         }
     }
 
-    /// Obtém o bitmap (16 bytes) para um caractere ASCII.
+    /// Obtém o bitmap (16 bytes) para um caractere.
     ///
-    /// O índice é mapeado diretamente para a tabela ASCII.
-    /// Caracteres não suportados (>127) são mapeados para '?'.
+    /// ASCII vem da tabela embutida; os demais, da fonte extra. Caracteres
+    /// que nenhuma das duas tem são desenhados como uma caixa.
     pub fn get_glyph(&self, c: char) -> &'static [u8; 16] {
-        let idx = if c.is_ascii() {
-            c as usize
-        } else {
-            '?' as usize
-        };
-
-        // Proteção extra contra estouro de índice
-        if idx >= FONT_DATA.len() {
-            return &FONT_DATA['?' as usize];
+        if c.is_ascii() {
+            return &FONT_DATA[c as usize];
         }
 
-        &FONT_DATA[idx]
+        self.extra
+            .and_then(|font| font.glyph(c))
+            .unwrap_or(&FONT_DATA[BOX_GLYPH])
     }
 }
 
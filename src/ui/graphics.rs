@@ -7,7 +7,7 @@
 use super::font::BitFont;
 use crate::{
     core::handoff::FramebufferInfo,
-    video::Color,
+    video::{darken_rect_bytes, fill_rect_bytes, Color, Rect},
 };
 
 /// Contexto gráfico para desenho.
@@ -33,14 +33,14 @@ impl<'a> GraphicsContext<'a> {
         }
     }
 
+    /// Substitui a fonte usada por `draw_char`/`draw_string`.
+    pub fn set_font(&mut self, font: BitFont) {
+        self.font = font;
+    }
+
     /// Limpa a tela com uma cor.
     pub fn clear(&mut self, color: Color) {
-        // Otimização: preencher linha a linha ou usar memset se a cor for preta
-        for y in 0..self.info.height {
-            for x in 0..self.info.width {
-                self.put_pixel(x, y, color);
-            }
-        }
+        self.fill_rect(0, 0, self.info.width, self.info.height, color);
     }
 
     /// Desenha um único pixel.
@@ -58,6 +58,14 @@ impl<'a> GraphicsContext<'a> {
             return;
         }
 
+        // Escrita direta na VRAM
+        let pixel = self.pixel_bytes(color);
+        self.buffer[byte_offset..byte_offset + 3].copy_from_slice(&pixel);
+        // self.buffer[byte_offset + 3] = 0; // Padding/Alpha (ignorado)
+    }
+
+    /// Bytes do pixel, na ordem em que vão para a VRAM.
+    fn pixel_bytes(&self, color: Color) -> [u8; 3] {
         // Mapeia componentes de cor baseado no formato do vídeo
         let (r, g, b) = match self.info.format {
             // PixelFormat::Rgb
@@ -67,21 +75,24 @@ impl<'a> GraphicsContext<'a> {
             // Fallback para BGR
             _ => (color.b, color.g, color.r),
         };
-
-        // Escrita direta na VRAM
-        self.buffer[byte_offset] = b;
-        self.buffer[byte_offset + 1] = g;
-        self.buffer[byte_offset + 2] = r;
-        // self.buffer[byte_offset + 3] = 0; // Padding/Alpha (ignorado)
+        [b, g, r]
     }
 
-    /// Desenha um retângulo preenchido.
+    /// Desenha um retângulo preenchido, linha a linha respeitando `stride`.
     pub fn fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, color: Color) {
-        for dy in 0..h {
-            for dx in 0..w {
-                self.put_pixel(x + dx, y + dy, color);
-            }
-        }
+        let pixel = self.pixel_bytes(color);
+        fill_rect_bytes(
+            self.buffer,
+            self.info.stride,
+            (self.info.width, self.info.height),
+            Rect {
+                x,
+                y,
+                width: w,
+                height: h,
+            },
+            pixel,
+        );
     }
 
     /// Desenha um caractere usando a fonte embutida.
@@ -123,3 +134,15 @@ impl<'a> GraphicsContext<'a> {
         self.info.height
     }
 }
+
+/// Escurece a área `rect` já desenhada em `fb`, multiplicando cada canal por
+/// `factor` (0.0 a 1.0). Usado para dar contraste ao menu sobre o wallpaper.
+pub fn blend_darken(fb: &mut GraphicsContext, rect: Rect, factor: f32) {
+    darken_rect_bytes(
+        fb.buffer,
+        fb.info.stride,
+        (fb.info.width, fb.info.height),
+        rect,
+        factor,
+    );
+}
@@ -2,9 +2,20 @@
 //!
 //! Abstrai o protocolo `SimpleTextInput` do UEFI para eventos de alto nível.
 //! Permite navegação nos menus e detecção de teclas de recuperação.
+//!
+//! Quando o firmware expõe `SimpleTextInputEx` no handle do console, ele é
+//! usado no lugar do básico para reportar também Ctrl/Shift/Alt.
+
+use bitflags::bitflags;
 
 use crate::uefi::{
+    Handle,
     Status,
+    proto::console::text_input_ex::{
+        KeyData, LEFT_ALT_PRESSED, LEFT_CONTROL_PRESSED, LEFT_LOGO_PRESSED, LEFT_SHIFT_PRESSED,
+        RIGHT_ALT_PRESSED, RIGHT_CONTROL_PRESSED, RIGHT_LOGO_PRESSED, RIGHT_SHIFT_PRESSED,
+        SHIFT_STATE_VALID, SIMPLE_TEXT_INPUT_EX_PROTOCOL_GUID, SimpleTextInputExProtocol,
+    },
     system_table,
     table::system::{InputKey, SimpleTextInputProtocol},
 };
@@ -23,8 +34,90 @@ pub enum Key {
     Unknown,
 }
 
+bitflags! {
+    /// Modificadores pressionados junto com a tecla.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct Modifiers: u8 {
+        const SHIFT = 1 << 0;
+        const CTRL  = 1 << 1;
+        const ALT   = 1 << 2;
+        const LOGO  = 1 << 3;
+    }
+}
+
+/// Tecla com estado dos modificadores.
+///
+/// Sem `SimpleTextInputEx`, `modifiers` é sempre vazio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub key:       Key,
+    pub modifiers: Modifiers,
+}
+
+/// Converte uma tecla do protocolo básico.
+pub fn map_input_key(key: InputKey) -> Key {
+    // Scan codes UEFI (Spec 12.3)
+    match key.scan_code {
+        0x01 => Key::Up,
+        0x02 => Key::Down,
+        0x03 => Key::Right,
+        0x04 => Key::Left,
+        0x17 => Key::Escape,
+        0 => {
+            // Se scan_code é 0, usamos unicode_char
+            match key.unicode_char {
+                13 => Key::Enter,    // Carriage Return
+                8 => Key::Backspace, // Backspace
+                c if c > 0 => Key::Char(char::from_u32(c as u32).unwrap_or('?')),
+                _ => Key::Unknown,
+            }
+        },
+        _ => Key::Unknown,
+    }
+}
+
+/// Converte uma tecla do protocolo Ex, incluindo modificadores.
+///
+/// Alguns firmwares entregam Ctrl+letra como caractere de controle
+/// (Ctrl+C = 0x03); nesse caso a letra é recuperada para que `Char('c')`
+/// com `CTRL` seja reportado de forma uniforme.
+pub fn map_key_data(data: &KeyData) -> KeyEvent {
+    let shift_state = data.key_state.key_shift_state;
+    let mut modifiers = Modifiers::empty();
+
+    if shift_state & SHIFT_STATE_VALID != 0 {
+        modifiers.set(
+            Modifiers::SHIFT,
+            shift_state & (LEFT_SHIFT_PRESSED | RIGHT_SHIFT_PRESSED) != 0,
+        );
+        modifiers.set(
+            Modifiers::CTRL,
+            shift_state & (LEFT_CONTROL_PRESSED | RIGHT_CONTROL_PRESSED) != 0,
+        );
+        modifiers.set(
+            Modifiers::ALT,
+            shift_state & (LEFT_ALT_PRESSED | RIGHT_ALT_PRESSED) != 0,
+        );
+        modifiers.set(
+            Modifiers::LOGO,
+            shift_state & (LEFT_LOGO_PRESSED | RIGHT_LOGO_PRESSED) != 0,
+        );
+    }
+
+    let key = match (data.key.scan_code, data.key.unicode_char) {
+        (0, c @ 1..=26) if modifiers.contains(Modifiers::CTRL) => {
+            Key::Char((b'a' + (c as u8 - 1)) as char)
+        },
+        _ => map_input_key(data.key),
+    };
+
+    KeyEvent { key, modifiers }
+}
+
 pub struct InputManager {
-    protocol: *mut SimpleTextInputProtocol,
+    protocol:    *mut SimpleTextInputProtocol,
+    /// Protocolo estendido, se o firmware oferecer (nulo caso contrário).
+    protocol_ex: *mut SimpleTextInputExProtocol,
 }
 
 impl InputManager {
@@ -33,19 +126,50 @@ impl InputManager {
         let st = system_table();
         // O cast é seguro aqui pois sabemos que con_in segue a ABI do SimpleTextInput
         let protocol = st.con_in;
-        Self { protocol }
+
+        // Ex é opcional: firmwares antigos só expõem o protocolo básico
+        let protocol_ex = st
+            .boot_services()
+            .open_protocol(
+                st.console_in_handle,
+                &SIMPLE_TEXT_INPUT_EX_PROTOCOL_GUID,
+                crate::uefi::image_handle(),
+                Handle::null(),
+                crate::uefi::table::boot::OPEN_PROTOCOL_GET_PROTOCOL,
+            )
+            .map(|ptr| ptr as *mut SimpleTextInputExProtocol)
+            .unwrap_or(core::ptr::null_mut());
+
+        Self {
+            protocol,
+            protocol_ex,
+        }
     }
 
     /// Verifica se há uma tecla pressionada (não bloqueante).
     pub fn poll(&self) -> Option<Key> {
-        let mut key = InputKey::default();
+        self.poll_event().map(|event| event.key)
+    }
 
+    /// Como `poll`, mas inclui os modificadores quando disponíveis.
+    pub fn poll_event(&self) -> Option<KeyEvent> {
         unsafe {
+            if !self.protocol_ex.is_null() {
+                let mut data = KeyData::default();
+                let status = ((*self.protocol_ex).read_key_stroke_ex)(self.protocol_ex, &mut data);
+                return (status == Status::SUCCESS).then(|| map_key_data(&data));
+            }
+
+            let mut key = InputKey::default();
+
             // Chama ReadKeyStroke (função FFI)
             let status = ((*self.protocol).read_key_stroke)(self.protocol, &mut key);
 
             if status == Status::SUCCESS {
-                Some(self.map_uefi_key(key))
+                Some(KeyEvent {
+                    key:       map_input_key(key),
+                    modifiers: Modifiers::empty(),
+                })
             } else {
                 None
             }
@@ -55,16 +179,25 @@ impl InputManager {
     /// Aguarda uma tecla (bloqueante).
     /// Usa `bs->wait_for_event` para economizar CPU em vez de spinloop.
     pub fn wait_for_key(&self) -> Key {
+        self.wait_for_key_event().key
+    }
+
+    /// Como `wait_for_key`, mas inclui os modificadores quando disponíveis.
+    pub fn wait_for_key_event(&self) -> KeyEvent {
         let bs = system_table().boot_services();
 
         loop {
-            if let Some(k) = self.poll() {
+            if let Some(k) = self.poll_event() {
                 return k;
             }
 
             // Aguarda evento de teclado (interrupção/sinal)
             unsafe {
-                let event = (*self.protocol).wait_for_key;
+                let event = if self.protocol_ex.is_null() {
+                    (*self.protocol).wait_for_key
+                } else {
+                    (*self.protocol_ex).wait_for_key_ex
+                };
                 let mut index = 0;
                 // Wait for event (bloqueia CPU até interrupção)
                 // OBS: wait_for_event_f foi adicionado na refatoração do boot.rs
@@ -72,25 +205,4 @@ impl InputManager {
             }
         }
     }
-
-    fn map_uefi_key(&self, key: InputKey) -> Key {
-        // Scan codes UEFI (Spec 12.3)
-        match key.scan_code {
-            0x01 => Key::Up,
-            0x02 => Key::Down,
-            0x03 => Key::Right,
-            0x04 => Key::Left,
-            0x17 => Key::Escape,
-            0 => {
-                // Se scan_code é 0, usamos unicode_char
-                match key.unicode_char {
-                    13 => Key::Enter,    // Carriage Return
-                    8 => Key::Backspace, // Backspace
-                    c if c > 0 => Key::Char(char::from_u32(c as u32).unwrap_or('?')),
-                    _ => Key::Unknown,
-                }
-            },
-            _ => Key::Unknown,
-        }
-    }
 }
@@ -2,45 +2,340 @@
 //!
 //! Renderiza as opções de boot e gerencia a navegação.
 //! Protegido contra resoluções extremas ou listas vazias.
+//!
+//! Com `timeout` configurado, um contador regressivo (em passos de
+//! `TIMEOUT_TICK_MS`) roda até a primeira tecla; ao chegar a zero, o menu
+//! devolve a ação de `timeout_action`. Com `timeout: infinite` não há
+//! contador.
+//!
+//! A chave `console` decide se o menu é desenhado no framebuffer, ecoado
+//! como texto na serial, ou ambos.
 
 use super::{
-    graphics::GraphicsContext,
+    font::BitFont,
+    graphics::{blend_darken, GraphicsContext},
     input::{InputManager, Key},
     theme::Theme,
 };
+use alloc::{format, string::String, vec::Vec};
+
 use crate::{
-    config::{BootConfig, Entry},
-    core::handoff::FramebufferInfo,
+    config::{types::TIMEOUT_TICK_MS, BootConfig, Entry, Protocol, TimeoutAction},
+    core::handoff::{FramebufferInfo, PixelFormat},
+    recovery::diagnostics::MAX_HEALTH_SCORE,
+    video::{Color, Rect},
 };
 
+/// Resultado do menu.
+#[derive(Debug)]
+pub enum MenuAction<'a> {
+    /// Iniciar a entrada escolhida.
+    Boot(&'a Entry),
+    /// Parar a máquina.
+    Halt,
+    /// Reiniciar o sistema.
+    Reboot,
+    /// Rodar o autoteste (`recovery::selftest`) e voltar ao menu.
+    SelfTest,
+}
+
+/// Intervalo entre leituras do teclado durante o contador (50ms).
+const COUNTDOWN_POLL_US: usize = 50_000;
+
+/// Tempo que um aviso do diagnóstico fica na tela antes do boot seguir (2s).
+const WARNING_DISPLAY_US: usize = 2_000_000;
+
+/// Caracteres da cmdline exibidos no rodapé antes de truncar com `...`.
+pub const FOOTER_CMDLINE_MAX: usize = 60;
+
+/// Altura reservada no fim da tela para o rodapé (detalhes, contador e
+/// teclas).
+pub const FOOTER_HEIGHT: u32 = 110;
+
+/// Coordenada Y da primeira linha da lista de entradas.
+pub const LIST_TOP: u32 = 100;
+
+/// Espaço vertical entre linhas da lista, além da altura da fonte.
+const ROW_SPACING: u32 = 4;
+
+/// Trecho da lista de entradas que cabe na tela.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListWindow {
+    /// Posição (entre as entradas visíveis) da primeira linha desenhada.
+    pub first:      usize,
+    /// Quantidade de linhas desenhadas.
+    pub rows:       usize,
+    /// Há entradas acima da janela (indicador `^`).
+    pub more_above: bool,
+    /// Há entradas abaixo da janela (indicador `v`).
+    pub more_below: bool,
+}
+
+/// Mostra um aviso não crítico na tela e pausa brevemente antes de seguir.
+///
+/// # Safety
+/// `fb_ptr` deve apontar para o framebuffer descrito por `fb_info`, e os
+/// Boot Services ainda devem estar ativos (a pausa usa `Stall`).
+pub unsafe fn show_warning(fb_ptr: u64, fb_info: FramebufferInfo, title: &str, msg: &str) {
+    let mut ctx = GraphicsContext::new(fb_ptr, fb_info);
+    let theme = Theme::default();
+
+    ctx.clear(theme.background);
+    let width = ctx.width();
+    let y = ctx.height() / 2;
+    for (line, color, dy) in [(title, theme.highlight, 0), (msg, theme.warning, 20)] {
+        let x = width.saturating_sub(line.chars().count() as u32 * 8) / 2;
+        ctx.draw_string(x, y + dy, line, color, None);
+    }
+
+    crate::uefi::system_table()
+        .boot_services()
+        .stall(WARNING_DISPLAY_US);
+}
+
 pub struct Menu<'a> {
     config:         &'a BootConfig,
     theme:          Theme,
     selected_index: usize,
-    input:          InputManager,
+    /// Índices em `config.entries` das entradas exibidas (sem `hidden`).
+    visible:        Vec<usize>,
+    health:         Vec<u8>,
+    /// Passos restantes até o timeout; `None` após qualquer tecla.
+    countdown:      Option<u32>,
+    font:           BitFont,
+    /// Posição (entre as entradas visíveis) da primeira linha da lista.
+    scroll:         usize,
 }
 
 impl<'a> Menu<'a> {
     pub fn new(config: &'a BootConfig) -> Self {
         // Garante que o índice selecionado é válido, mesmo se a lista mudou
-        let max_index = if config.entries.is_empty() {
-            0
-        } else {
-            config.entries.len() - 1
-        };
-        let selected_index = config.default_entry_idx.min(max_index);
+        let selected_index = config
+            .default_entry_idx
+            .min(config.entries.len().saturating_sub(1));
+        if selected_index != config.default_entry_idx && !config.entries.is_empty() {
+            log::warn!(
+                "Menu: default_entry {} fora da lista ({} entradas), usando {}",
+                config.default_entry_idx + 1,
+                config.entries.len(),
+                selected_index + 1
+            );
+        }
 
         Self {
             config,
             theme: Theme::default(),
             selected_index,
-            input: InputManager::new(),
+            visible: (0..config.entries.len())
+                .filter(|&i| !config.entries[i].hidden)
+                .collect(),
+            health: Vec::new(),
+            countdown: config.timeout.ticks().filter(|&ticks| ticks > 0),
+            font: BitFont::new(),
+            scroll: 0,
+        }
+    }
+
+    /// Usa `font` (ex: com uma fonte PSF extra) para desenhar o menu.
+    pub fn with_font(mut self, font: BitFont) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// Desliga o contador regressivo: o menu espera uma tecla.
+    pub fn without_countdown(mut self) -> Self {
+        self.countdown = None;
+        self
+    }
+
+    /// Avança o contador em um passo (`TIMEOUT_TICK_MS`) sem tecla
+    /// pressionada.
+    ///
+    /// Retorna a ação de `timeout_action` quando o contador chega a zero.
+    pub fn tick(&mut self) -> Option<MenuAction<'a>> {
+        let remaining = self.countdown?.saturating_sub(1);
+        self.countdown = Some(remaining);
+
+        if remaining > 0 {
+            return None;
+        }
+
+        Some(match self.config.timeout_action {
+            TimeoutAction::Boot => match self.selected_entry() {
+                Some(entry) => MenuAction::Boot(entry),
+                None => MenuAction::Halt,
+            },
+            TimeoutAction::Halt => MenuAction::Halt,
+            TimeoutAction::Reboot => MenuAction::Reboot,
+        })
+    }
+
+    /// Espera até um passo do contador por uma tecla.
+    fn poll_for_tick(input: &InputManager) -> Option<Key> {
+        let bs = crate::uefi::system_table().boot_services();
+        for _ in 0..(TIMEOUT_TICK_MS as usize * 1000).div_ceil(COUNTDOWN_POLL_US) {
+            if let Some(key) = input.poll() {
+                return Some(key);
+            }
+            bs.stall(COUNTDOWN_POLL_US);
+        }
+        None
+    }
+
+    /// Exibe um indicador de saúde ao lado de cada entrada.
+    ///
+    /// `scores` segue a ordem de `config.entries` (ver
+    /// `Diagnostics::score_entry`).
+    pub fn with_health(mut self, scores: Vec<u8>) -> Self {
+        self.health = scores;
+        self
+    }
+
+    /// Se o menu é desenhado no framebuffer.
+    pub fn video_enabled(&self) -> bool {
+        self.config.console.video()
+    }
+
+    /// Se o menu é ecoado na serial a cada mudança de seleção.
+    pub fn serial_echo_enabled(&self) -> bool {
+        self.config.console.serial()
+    }
+
+    /// Se o menu pedido no framebuffer precisa ir para a serial porque
+    /// `format` não tem framebuffer linear.
+    pub fn text_fallback(&self, format: PixelFormat) -> bool {
+        self.video_enabled() && !format.is_linear()
+    }
+
+    /// Versão em texto do menu, uma linha por entrada, com a seleção
+    /// marcada por `>`.
+    pub fn serial_lines(&self) -> Vec<String> {
+        self.visible
+            .iter()
+            .map(|&i| {
+                let prefix = if i == self.selected_index { '>' } else { ' ' };
+                format!("{} {}. {}", prefix, i + 1, self.config.entries[i].name)
+            })
+            .collect()
+    }
+
+    /// Rodapé com o que a entrada selecionada vai executar: caminho,
+    /// protocolo e cmdline (truncada em `FOOTER_CMDLINE_MAX` caracteres).
+    /// Vazio se não houver entradas.
+    pub fn footer_lines(&self) -> Vec<String> {
+        let Some(entry) = self.selected_entry() else {
+            return Vec::new();
+        };
+
+        let protocol = match entry.protocol {
+            Protocol::Unknown => String::from("auto"),
+            protocol => format!("{:?}", protocol),
+        };
+        let cmdline = entry.cmdline.as_deref().unwrap_or("-");
+        let cmdline = match cmdline.char_indices().nth(FOOTER_CMDLINE_MAX) {
+            Some((cut, _)) => format!("{}...", &cmdline[..cut]),
+            None => String::from(cmdline),
+        };
+
+        alloc::vec![
+            format!("Caminho: {}", entry.path),
+            format!("Protocolo: {}", protocol),
+            format!("Cmdline: {}", cmdline),
+        ]
+    }
+
+    /// Linhas da lista que cabem numa tela de `height` pixels, entre o
+    /// cabeçalho e o rodapé.
+    pub fn list_rows(&self, height: u32) -> usize {
+        let row_height = self.font.height + ROW_SPACING;
+        (height.saturating_sub(LIST_TOP + FOOTER_HEIGHT) / row_height) as usize
+    }
+
+    /// Rola a lista para manter a seleção dentro de `rows` linhas e devolve
+    /// o trecho a desenhar.
+    ///
+    /// A janela só se move quando a seleção sai dela, como numa lista com
+    /// barra de rolagem.
+    pub fn scroll_to_selection(&mut self, rows: usize) -> ListWindow {
+        let len = self.visible.len();
+        let rows = rows.min(len);
+        if let Some(pos) = self.visible.iter().position(|&i| i == self.selected_index) {
+            if pos < self.scroll {
+                self.scroll = pos;
+            } else if pos >= self.scroll + rows {
+                self.scroll = pos + 1 - rows;
+            }
+        }
+        self.scroll = self.scroll.min(len - rows);
+
+        ListWindow {
+            first: self.scroll,
+            rows,
+            more_above: self.scroll > 0,
+            more_below: self.scroll + rows < len,
+        }
+    }
+
+    /// Índice em `config.entries` da entrada selecionada.
+    pub fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    /// Entrada selecionada, ou `None` se a configuração não tiver entradas.
+    pub fn selected_entry(&self) -> Option<&'a Entry> {
+        self.config.entries.get(self.selected_index)
+    }
+
+    /// Seleciona a próxima entrada visível (seta para baixo). Sem entradas
+    /// visíveis, não faz nada.
+    pub fn select_next(&mut self) {
+        self.step_selection(true);
+    }
+
+    /// Seleciona a entrada visível anterior (seta para cima). Sem entradas
+    /// visíveis, não faz nada.
+    pub fn select_previous(&mut self) {
+        self.step_selection(false);
+    }
+
+    /// Move a seleção para a entrada visível seguinte (ou anterior), em
+    /// ciclo. Uma seleção oculta (via `default_entry`) vai para a primeira
+    /// (ou última) visível.
+    fn step_selection(&mut self, forward: bool) {
+        let len = self.visible.len();
+        if len == 0 {
+            return;
+        }
+
+        let pos = self.visible.iter().position(|&i| i == self.selected_index);
+        let next = match (pos, forward) {
+            (Some(p), true) => (p + 1) % len,
+            (Some(p), false) => (p + len - 1) % len,
+            (None, true) => 0,
+            (None, false) => len - 1,
+        };
+        self.selected_index = self.visible[next];
+    }
+
+    fn echo_serial(&self) {
+        crate::println!("--- Ignite Bootloader ---");
+        for line in self.serial_lines() {
+            crate::println!("{}", line);
+        }
+        for line in self.footer_lines() {
+            crate::println!("  {}", line);
         }
     }
 
     /// Executa o loop do menu.
-    pub unsafe fn run(&mut self, fb_ptr: u64, fb_info: FramebufferInfo) -> &'a Entry {
+    ///
+    /// # Safety
+    /// `fb_ptr` deve apontar para o framebuffer descrito por `fb_info`, e os
+    /// Boot Services ainda devem estar ativos (entrada de teclado e timer).
+    pub unsafe fn run(&mut self, fb_ptr: u64, fb_info: FramebufferInfo) -> MenuAction<'a> {
         let mut ctx = GraphicsContext::new(fb_ptr, fb_info);
+        ctx.set_font(self.font);
+        let input = InputManager::new();
 
         // Se não houver entradas (o que o Default previne, mas por segurança), trava.
         if self.config.entries.is_empty() {
@@ -50,34 +345,68 @@ impl<'a> Menu<'a> {
             }
         }
 
+        let text_only = self.text_fallback(fb_info.format);
+        if text_only {
+            crate::println!("AVISO: Video sem framebuffer linear. Menu apenas em texto.");
+        }
+        let mut echoed_index = None;
+
         loop {
-            self.draw(&mut ctx);
-
-            match self.input.wait_for_key() {
-                Key::Up => {
-                    if self.selected_index > 0 {
-                        self.selected_index -= 1;
-                    } else {
-                        self.selected_index = self.config.entries.len() - 1;
-                    }
-                },
-                Key::Down => {
-                    if self.selected_index < self.config.entries.len() - 1 {
-                        self.selected_index += 1;
-                    } else {
-                        self.selected_index = 0;
-                    }
-                },
+            if self.video_enabled() && !text_only {
+                self.draw(&mut ctx);
+            }
+            if (self.serial_echo_enabled() || text_only)
+                && echoed_index != Some(self.selected_index)
+            {
+                self.echo_serial();
+                echoed_index = Some(self.selected_index);
+            }
+
+            let key = if self.countdown.is_some() {
+                match Self::poll_for_tick(&input) {
+                    Some(key) => {
+                        // Qualquer tecla cancela o contador
+                        self.countdown = None;
+                        key
+                    },
+                    None => {
+                        if let Some(action) = self.tick() {
+                            return action;
+                        }
+                        continue;
+                    },
+                }
+            } else {
+                input.wait_for_key()
+            };
+
+            match key {
+                Key::Up => self.select_previous(),
+                Key::Down => self.select_next(),
                 Key::Enter => {
-                    return &self.config.entries[self.selected_index];
+                    if let Some(entry) = self.selected_entry() {
+                        return MenuAction::Boot(entry);
+                    }
                 },
+                Key::Char('t' | 'T') => return MenuAction::SelfTest,
                 _ => {}, // Ignorar outras teclas
             }
         }
     }
 
-    fn draw(&self, ctx: &mut GraphicsContext) {
-        ctx.clear(self.theme.background);
+    /// Cor usada para limpar a tela: `background_color` se não houver
+    /// wallpaper, senão o fundo do tema.
+    pub fn background(&self) -> Color {
+        match (&self.config.wallpaper, self.config.background_color) {
+            (None, Some(rgb)) => Color::from_rgb(rgb),
+            _ => self.theme.background,
+        }
+    }
+
+    /// Desenha um quadro do menu em `ctx`, rolando a lista até a seleção.
+    pub fn draw(&mut self, ctx: &mut GraphicsContext) {
+        let window = self.scroll_to_selection(self.list_rows(ctx.height()));
+        ctx.clear(self.background());
 
         let width = ctx.width();
         let height = ctx.height();
@@ -99,15 +428,25 @@ impl<'a> Menu<'a> {
         ctx.draw_string(title_x, 30, title, self.theme.highlight, None);
 
         // --- Lista de Entradas ---
-        let start_y = 100;
-        let line_height = 20;
-
-        for (i, entry) in self.config.entries.iter().enumerate() {
-            let y = start_y + (i as u32 * line_height);
-            // Evita desenhar fora da tela verticalmente
-            if y + line_height > height {
-                break;
-            }
+        let start_y = LIST_TOP;
+        let line_height = self.font.height + ROW_SPACING;
+
+        // Escurece o fundo sob a lista para o texto se destacar
+        if self.config.menu_dim < 1.0 && window.rows > 0 {
+            let area = Rect {
+                x:      40,
+                y:      start_y - 10,
+                width:  width.saturating_sub(80),
+                height: (window.rows as u32 * line_height + 16)
+                    .min(height.saturating_sub(FOOTER_HEIGHT + start_y - 10)),
+            };
+            blend_darken(ctx, area, self.config.menu_dim);
+        }
+
+        let shown = &self.visible[window.first..window.first + window.rows];
+        for (row, &i) in shown.iter().enumerate() {
+            let entry = &self.config.entries[i];
+            let y = start_y + (row as u32 * line_height);
 
             let is_selected = i == self.selected_index;
 
@@ -135,10 +474,37 @@ impl<'a> Menu<'a> {
             let prefix = if is_selected { "> " } else { "  " };
             ctx.draw_string(60, y, prefix, fg, None);
             ctx.draw_string(80, y, &entry.name, fg, None);
+
+            if let Some(&score) = self.health.get(i) {
+                let (badge, color) = match score {
+                    0 => ("[X]", self.theme.error),
+                    MAX_HEALTH_SCORE => ("[OK]", self.theme.comment),
+                    _ => ("[!]", self.theme.warning),
+                };
+                let badge_x = 80 + (entry.name.len() as u32 + 1) * 8;
+                if badge_x + 32 < width {
+                    ctx.draw_string(badge_x, y, badge, color, None);
+                }
+            }
+        }
+
+        // Indicadores de rolagem na primeira e na última linha
+        let arrow_x = width.saturating_sub(70);
+        if window.more_above {
+            ctx.draw_string(arrow_x, start_y, "^", self.theme.comment, None);
+        }
+        if window.more_below {
+            let y = start_y + (window.rows.saturating_sub(1) as u32 * line_height);
+            ctx.draw_string(arrow_x, y, "v", self.theme.comment, None);
         }
 
         // --- Rodapé ---
-        let footer = "Setas: Navegar | Enter: Selecionar";
+        for (row, line) in self.footer_lines().iter().enumerate() {
+            let y = height.saturating_sub(FOOTER_HEIGHT) + row as u32 * 16;
+            ctx.draw_string(60, y, line, self.theme.comment, None);
+        }
+
+        let footer = "Setas: Navegar | Enter: Selecionar | T: Autoteste";
         let footer_len_px = footer.len() as u32 * 8;
         let footer_x = if width > footer_len_px {
             (width - footer_len_px) / 2
@@ -149,5 +515,17 @@ impl<'a> Menu<'a> {
         if height > 30 {
             ctx.draw_string(footer_x, height - 30, footer, self.theme.comment, None);
         }
+
+        if let Some(ticks) = self.countdown.filter(|_| height > 50) {
+            let verb = match self.config.timeout_action {
+                TimeoutAction::Boot => "Iniciando",
+                TimeoutAction::Halt => "Parando",
+                TimeoutAction::Reboot => "Reiniciando",
+            };
+            let ms = ticks * TIMEOUT_TICK_MS;
+            let msg = format!("{} em {}.{}s", verb, ms / 1000, ms % 1000 / 100);
+            let msg_x = width.saturating_sub(msg.len() as u32 * 8) / 2;
+            ctx.draw_string(msg_x, height - 50, &msg, self.theme.comment, None);
+        }
     }
 }
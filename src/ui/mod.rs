@@ -3,12 +3,14 @@
 //! Gerencia a interação gráfica com o usuário, incluindo menu de boot,
 //! renderização de fontes e tratamento de entrada.
 
+pub mod console;
 pub mod font;
 pub mod graphics;
 pub mod input;
 pub mod menu;
+pub mod psf;
 pub mod theme;
 
 // Re-exports
-pub use menu::Menu;
+pub use menu::{Menu, MenuAction};
 pub use theme::Theme;
@@ -0,0 +1,201 @@
+//! Fontes PC Screen Font (PSF1/PSF2)
+//!
+//! Formato usado pelo console do Linux. Permite carregar uma fonte extra
+//! (`font_path`) com glifos fora do ASCII, como os acentos do português,
+//! que a fonte embutida não tem.
+//!
+//! Os glifos são convertidos para o formato da fonte embutida (8x16, um byte
+//! por linha); fontes maiores que isso são rejeitadas.
+
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use crate::core::error::{BootError, FileSystemError, Result};
+
+/// Magic do PSF1.
+pub const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+
+/// Magic do PSF2.
+pub const PSF2_MAGIC: [u8; 4] = [0x72, 0xb5, 0x4a, 0x86];
+
+// Flags do cabeçalho PSF1
+const PSF1_MODE512: u8 = 0x01;
+const PSF1_MODEHASTAB: u8 = 0x02;
+const PSF1_MODESEQ: u8 = 0x04;
+
+// Tabela Unicode do PSF1 (UCS-2)
+const PSF1_SEPARATOR: u16 = 0xFFFF;
+const PSF1_STARTSEQ: u16 = 0xFFFE;
+
+// Tabela Unicode do PSF2 (UTF-8)
+const PSF2_HAS_UNICODE_TABLE: u32 = 0x01;
+const PSF2_SEPARATOR: u8 = 0xFF;
+const PSF2_STARTSEQ: u8 = 0xFE;
+
+/// Maior glifo aceito (dimensões da fonte embutida).
+const MAX_WIDTH: u32 = 8;
+const MAX_HEIGHT: u32 = 16;
+
+/// Fonte PSF já convertida para glifos 8x16.
+#[derive(Debug, Clone)]
+pub struct PsfFont {
+    /// Altura original dos glifos.
+    pub height: u32,
+    /// Largura original dos glifos.
+    pub width:  u32,
+    glyphs:     Vec<[u8; 16]>,
+    /// Codepoint -> índice do glifo. Vazio quando a fonte não tem tabela
+    /// Unicode; nesse caso o codepoint é o próprio índice.
+    unicode:    BTreeMap<char, usize>,
+}
+
+fn read_u32(data: &[u8], off: usize) -> u32 {
+    u32::from_le_bytes([data[off], data[off + 1], data[off + 2], data[off + 3]])
+}
+
+fn invalid_size() -> BootError {
+    BootError::FileSystem(FileSystemError::InvalidSize)
+}
+
+impl PsfFont {
+    /// Interpreta um arquivo PSF1 ou PSF2.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.starts_with(&PSF2_MAGIC) {
+            Self::parse_psf2(data)
+        } else if data.starts_with(&PSF1_MAGIC) {
+            Self::parse_psf1(data)
+        } else {
+            Err(BootError::FileSystem(FileSystemError::InvalidSignature))
+        }
+    }
+
+    fn parse_psf1(data: &[u8]) -> Result<Self> {
+        if data.len() < 4 {
+            return Err(invalid_size());
+        }
+
+        let mode = data[2];
+        let height = data[3] as u32;
+        let count = if mode & PSF1_MODE512 != 0 { 512 } else { 256 };
+
+        let mut font = Self::from_bitmaps(data, 4, count, height as usize, 8, height)?;
+
+        if mode & (PSF1_MODEHASTAB | PSF1_MODESEQ) != 0 {
+            let table = &data[4 + count * height as usize..];
+            let mut glyph = 0;
+            let mut in_sequence = false;
+
+            for pair in table.as_chunks::<2>().0 {
+                match u16::from_le_bytes(*pair) {
+                    PSF1_SEPARATOR => {
+                        glyph += 1;
+                        in_sequence = false;
+                    },
+                    PSF1_STARTSEQ => in_sequence = true,
+                    unit if !in_sequence && glyph < count => {
+                        if let Some(c) = char::from_u32(unit as u32) {
+                            font.unicode.entry(c).or_insert(glyph);
+                        }
+                    },
+                    _ => {},
+                }
+            }
+        }
+
+        Ok(font)
+    }
+
+    fn parse_psf2(data: &[u8]) -> Result<Self> {
+        if data.len() < 32 {
+            return Err(invalid_size());
+        }
+
+        let header_size = read_u32(data, 8) as usize;
+        let flags = read_u32(data, 12);
+        let count = read_u32(data, 16) as usize;
+        let glyph_size = read_u32(data, 20) as usize;
+        let height = read_u32(data, 24);
+        let width = read_u32(data, 28);
+
+        let mut font = Self::from_bitmaps(data, header_size, count, glyph_size, width, height)?;
+
+        if flags & PSF2_HAS_UNICODE_TABLE != 0 {
+            let table = &data[header_size + count * glyph_size..];
+            // Sequências (após 0xFE) combinam vários codepoints em um glifo;
+            // só os codepoints isolados interessam aqui.
+            for (glyph, entry) in table
+                .split(|&b| b == PSF2_SEPARATOR)
+                .take(count)
+                .enumerate()
+            {
+                let singles = entry.split(|&b| b == PSF2_STARTSEQ).next().unwrap_or(&[]);
+                if let Ok(text) = core::str::from_utf8(singles) {
+                    for c in text.chars() {
+                        font.unicode.entry(c).or_insert(glyph);
+                    }
+                }
+            }
+        }
+
+        Ok(font)
+    }
+
+    /// Converte `count` bitmaps a partir de `offset` para glifos 8x16.
+    fn from_bitmaps(
+        data: &[u8],
+        offset: usize,
+        count: usize,
+        glyph_size: usize,
+        width: u32,
+        height: u32,
+    ) -> Result<Self> {
+        if width == 0 || width > MAX_WIDTH || height == 0 || height > MAX_HEIGHT {
+            return Err(invalid_size());
+        }
+        if count == 0 || glyph_size < height as usize {
+            return Err(invalid_size());
+        }
+
+        let end = count
+            .checked_mul(glyph_size)
+            .and_then(|len| len.checked_add(offset))
+            .ok_or_else(invalid_size)?;
+        if end > data.len() {
+            return Err(invalid_size());
+        }
+
+        let glyphs = data[offset..end]
+            .chunks_exact(glyph_size)
+            .map(|bitmap| {
+                let mut glyph = [0u8; 16];
+                glyph[..height as usize].copy_from_slice(&bitmap[..height as usize]);
+                glyph
+            })
+            .collect();
+
+        Ok(Self {
+            height,
+            width,
+            glyphs,
+            unicode: BTreeMap::new(),
+        })
+    }
+
+    /// Número de glifos na fonte.
+    pub fn glyph_count(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    /// Índice do glifo que representa `c`, se a fonte tiver um.
+    pub fn glyph_index(&self, c: char) -> Option<usize> {
+        if self.unicode.is_empty() {
+            let idx = c as usize;
+            return (idx < self.glyphs.len()).then_some(idx);
+        }
+        self.unicode.get(&c).copied()
+    }
+
+    /// Bitmap 8x16 de `c`, se a fonte tiver um.
+    pub fn glyph(&self, c: char) -> Option<&[u8; 16]> {
+        self.glyph_index(c).map(|idx| &self.glyphs[idx])
+    }
+}
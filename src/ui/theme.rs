@@ -2,7 +2,7 @@
 //!
 //! Padroniza a aparência da interface gráfica.
 
-use crate::video::Color;
+use crate::{config::BootConfig, video::Color};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Theme {
@@ -12,6 +12,8 @@ pub struct Theme {
     pub comment:     Color,
     pub selected_bg: Color,
     pub selected_fg: Color,
+    pub warning:     Color,
+    pub error:       Color,
 }
 
 impl Default for Theme {
@@ -23,6 +25,20 @@ impl Default for Theme {
             comment:     Color::new(108, 112, 134), // Grey
             selected_bg: Color::new(49, 50, 68), // Surface0
             selected_fg: Color::new(250, 179, 135), // Peach/Orange
+            warning:     Color::new(249, 226, 175), // Yellow
+            error:       Color::new(243, 139, 168), // Red
         }
     }
 }
+
+impl Theme {
+    /// Tema padrão com o fundo trocado por `background_color`, quando
+    /// configurado.
+    pub fn from_config(config: &BootConfig) -> Self {
+        let mut theme = Self::default();
+        if let Some(rgb) = config.background_color {
+            theme.background = Color::from_rgb(rgb);
+        }
+        theme
+    }
+}
@@ -4,8 +4,8 @@
 //! desenhados. Esta estrutura é projetada para ser serializável e enviada ao
 //! Kernel via `BootInfo`.
 
-
 use super::pixel::{Color, PixelFormat};
+use crate::core::handoff::PixelMasks;
 
 /// Informações cruas do Framebuffer para Handoff (compatível com C).
 #[repr(C)]
@@ -23,6 +23,125 @@ pub struct FramebufferInfo {
     pub stride: u32,
     /// Formato dos pixels.
     pub format: PixelFormat,
+    /// Máscaras dos canais (só em `PixelFormat::Bitmask`).
+    pub masks:  PixelMasks,
+}
+
+impl FramebufferInfo {
+    /// Bytes necessários para `height` linhas de `stride` pixels de 4 bytes.
+    pub fn expected_size(&self) -> usize {
+        self.stride as usize * self.height as usize * 4
+    }
+
+    /// Ajusta `height` às linhas que cabem inteiras em `size`.
+    ///
+    /// O `size` vem do firmware; se for menor que o calculado, confiamos nele
+    /// e não na geometria, para nunca escrever além da VRAM. Retorna `true` se
+    /// houve ajuste.
+    pub fn clamp_to_size(&mut self) -> bool {
+        if self.size >= self.expected_size() {
+            return false;
+        }
+
+        let row_bytes = self.stride as usize * 4;
+        self.height = self.size.checked_div(row_bytes).unwrap_or(0) as u32;
+        true
+    }
+}
+
+impl From<FramebufferInfo> for crate::core::handoff::FramebufferInfo {
+    fn from(info: FramebufferInfo) -> Self {
+        Self {
+            addr:   info.addr,
+            size:   info.size as u64,
+            width:  info.width,
+            height: info.height,
+            stride: info.stride,
+            format: info.format.into(),
+            masks:  info.masks,
+        }
+    }
+}
+
+/// Retângulo em pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x:      u32,
+    pub y:      u32,
+    pub width:  u32,
+    pub height: u32,
+}
+
+/// Preenche `rect` em `buffer` com `pixel`, linha a linha.
+///
+/// Cada linha começa em `y * stride`, então o padding entre `width` e
+/// `stride` nunca é tocado. O retângulo é recortado em `bounds`
+/// (largura, altura visíveis) e nada é escrito além do fim de `buffer`. Só os
+/// três bytes de cor são escritos; o byte reservado fica como está.
+pub fn fill_rect_bytes(
+    buffer: &mut [u8],
+    stride: u32,
+    bounds: (u32, u32),
+    rect: Rect,
+    pixel: [u8; 3],
+) {
+    let x_end = rect.x.saturating_add(rect.width).min(bounds.0);
+    let y_end = rect.y.saturating_add(rect.height).min(bounds.1);
+    if rect.x >= x_end {
+        return;
+    }
+
+    for row in rect.y..y_end {
+        let line_start = row as usize * stride as usize;
+        let start = (line_start + rect.x as usize) * 4;
+        let end = ((line_start + x_end as usize) * 4).min(buffer.len());
+        let Some(line) = buffer.get_mut(start..end) else {
+            break;
+        };
+        for px in line.as_chunks_mut::<4>().0 {
+            px[..3].copy_from_slice(&pixel);
+        }
+    }
+}
+
+/// Multiplica os canais de cor dos pixels de `rect` por `factor`.
+///
+/// `factor` é limitado a `0.0..=1.0`: 1.0 mantém os pixels e 0.0 os apaga.
+/// Segue as mesmas regras de recorte de `fill_rect_bytes`, e como os três
+/// canais recebem o mesmo fator, a ordem RGB/BGR não importa.
+pub fn darken_rect_bytes(
+    buffer: &mut [u8],
+    stride: u32,
+    bounds: (u32, u32),
+    rect: Rect,
+    factor: f32,
+) {
+    let factor = factor.clamp(0.0, 1.0);
+    if factor >= 1.0 {
+        return;
+    }
+
+    // Ponto fixo 8.8 para não usar float por canal
+    let scale = (factor * 256.0) as u32;
+    let x_end = rect.x.saturating_add(rect.width).min(bounds.0);
+    let y_end = rect.y.saturating_add(rect.height).min(bounds.1);
+    if rect.x >= x_end {
+        return;
+    }
+
+    for row in rect.y..y_end {
+        let line_start = row as usize * stride as usize;
+        let start = (line_start + rect.x as usize) * 4;
+        let end = ((line_start + x_end as usize) * 4).min(buffer.len());
+        let Some(line) = buffer.get_mut(start..end) else {
+            break;
+        };
+        for px in line.as_chunks_mut::<4>().0 {
+            for channel in &mut px[..3] {
+                *channel = ((*channel as u32 * scale) >> 8) as u8;
+            }
+        }
+    }
 }
 
 /// Um wrapper seguro em torno da VRAM para operações de desenho no Bootloader.
@@ -48,12 +167,33 @@ impl<'a> Framebuffer<'a> {
 
     /// Preenche a tela inteira com uma cor.
     pub fn clear(&mut self, color: Color) {
-        // Otimização: Se for preto/branco, podemos usar memset rápido
-        // Aqui usamos a implementação pixel-a-pixel para correção garantida
-        for y in 0..self.info.height {
-            for x in 0..self.info.width {
-                self.draw_pixel(x, y, color);
-            }
+        self.fill_rect(0, 0, self.info.width, self.info.height, color);
+    }
+
+    /// Preenche um retângulo, recortado à área visível e a `size`.
+    pub fn fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, color: Color) {
+        let pixel = self.pixel_bytes(color);
+        let buffer = unsafe { core::slice::from_raw_parts_mut(self.base_addr, self.info.size) };
+        fill_rect_bytes(
+            buffer,
+            self.info.stride,
+            (self.info.width, self.info.height),
+            Rect {
+                x,
+                y,
+                width: w,
+                height: h,
+            },
+            pixel,
+        );
+    }
+
+    /// Bytes de cor na ordem do formato do framebuffer.
+    fn pixel_bytes(&self, color: Color) -> [u8; 3] {
+        match self.info.format {
+            PixelFormat::RgbReserved8Bit => [color.r, color.g, color.b],
+            // BGR e o fallback genérico (Bitmask complexo omitido)
+            _ => [color.b, color.g, color.r],
         }
     }
 
@@ -66,29 +206,14 @@ impl<'a> Framebuffer<'a> {
 
         let pixel_offset = (y as usize * self.info.stride as usize) + x as usize;
         let byte_offset = pixel_offset * 4; // 4 bytes por pixel
+        if byte_offset + 4 > self.info.size {
+            return;
+        }
 
+        // Escreve os bytes na ordem correta baseada no formato
+        let pixel = self.pixel_bytes(color);
         unsafe {
-            let ptr = self.base_addr.add(byte_offset);
-
-            // Escreve os bytes na ordem correta baseada no formato
-            match self.info.format {
-                PixelFormat::RgbReserved8Bit => {
-                    ptr.add(0).write(color.r);
-                    ptr.add(1).write(color.g);
-                    ptr.add(2).write(color.b);
-                },
-                PixelFormat::BgrReserved8Bit => {
-                    ptr.add(0).write(color.b);
-                    ptr.add(1).write(color.g);
-                    ptr.add(2).write(color.r);
-                },
-                _ => {
-                    // Fallback genérico ou Bitmask complexo omitido para brevidade
-                    ptr.add(0).write(color.b);
-                    ptr.add(1).write(color.g);
-                    ptr.add(2).write(color.r);
-                },
-            }
+            core::ptr::copy_nonoverlapping(pixel.as_ptr(), self.base_addr.add(byte_offset), 3);
         }
     }
 
@@ -97,3 +222,24 @@ impl<'a> Framebuffer<'a> {
         self.info
     }
 }
+
+/// Cores das barras do padrão de teste, da esquerda para a direita.
+const TEST_PATTERN_BARS: [Color; 4] = [Color::RED, Color::GREEN, Color::BLUE, Color::WHITE];
+
+/// Pinta barras verticais (vermelho, verde, azul, branco) em toda a tela.
+///
+/// Usado no bring-up de kernels: se as barras aparecerem com cores trocadas ou
+/// inclinadas, `format` ou `stride` do handoff estão errados. A última barra
+/// absorve o resto da divisão para cobrir a largura inteira.
+pub fn draw_test_pattern(fb: &mut Framebuffer) {
+    let width = fb.info.width;
+    let height = fb.info.height;
+    let bar_width = (width / TEST_PATTERN_BARS.len() as u32).max(1);
+
+    for y in 0..height {
+        for x in 0..width {
+            let bar = ((x / bar_width) as usize).min(TEST_PATTERN_BARS.len() - 1);
+            fb.draw_pixel(x, y, TEST_PATTERN_BARS[bar]);
+        }
+    }
+}
@@ -3,14 +3,25 @@
 //! Interage com o firmware UEFI para configurar vídeo e acessar framebuffer
 //! nativo.
 
+use alloc::vec::Vec;
+
 use super::{
     framebuffer::{Framebuffer, FramebufferInfo},
-    mode::VideoMode,
-    pixel::PixelFormat,
+    mode::{select_mode, VideoMode, VideoModeInfo},
+    pixel::{Color, PixelFormat},
 };
 use crate::{
-    core::error::{BootError, Result, VideoError},
-    uefi::BootServices,
+    core::{
+        error::{BootError, Result, VideoError},
+        handoff::PixelMasks,
+    },
+    uefi::{
+        proto::console::gop::{
+            GraphicsOutputModeInformation, PixelBitmask, PixelFormat as GopPixelFormat,
+        },
+        table::boot::{LocateSearchType, OPEN_PROTOCOL_GET_PROTOCOL},
+        BootServices,
+    },
 };
 
 // GUID do Protocolo GOP: {9042A9DE-23DC-4A38-96FB-7ADED080516A}
@@ -21,8 +32,24 @@ pub const GRAPHICS_OUTPUT_PROTOCOL_GUID: crate::uefi::base::Guid = crate::uefi::
     [0x96, 0xfb, 0x7a, 0xde, 0xd0, 0x80, 0x51, 0x6a],
 );
 
+/// Resolução preferida por `reset_to_safe_mode`: suportada por praticamente
+/// todo monitor e firmware.
+pub const SAFE_MODE_RESOLUTION: (usize, usize) = (800, 600);
+
+/// Operações do GOP usadas para restaurar o vídeo.
+pub trait ModeControl {
+    /// Modos de vídeo que o firmware oferece.
+    fn modes(&self) -> Result<Vec<VideoMode>>;
+
+    /// Ativa o modo `id` e retorna o framebuffer resultante.
+    fn activate(&mut self, id: u32) -> Result<FramebufferInfo>;
+
+    /// Endereço do framebuffer no modo atual, sem trocar de modo (0 se o
+    /// firmware não informa).
+    fn current_framebuffer_addr(&self) -> u64;
+}
+
 pub struct GopDriver<'a> {
-    #[allow(dead_code)]
     boot_services: &'a BootServices,
     gop_interface: *mut crate::uefi::proto::console::gop::GraphicsOutputProtocol,
 }
@@ -42,40 +69,156 @@ impl<'a> GopDriver<'a> {
         })
     }
 
+    /// Um driver para cada handle com GOP, exceto o do console primário
+    /// (`GopDriver::new`), já configurado por `init_video`.
+    ///
+    /// Sem outras saídas, retorna uma lista vazia.
+    pub fn secondary_outputs(boot_services: &'a BootServices) -> Result<Vec<Self>> {
+        let primary = boot_services
+            .locate_protocol(&GRAPHICS_OUTPUT_PROTOCOL_GUID)
+            .unwrap_or(core::ptr::null_mut());
+        let mut count = 0usize;
+        let mut buffer: *mut crate::uefi::Handle = core::ptr::null_mut();
+
+        let handles = unsafe {
+            (boot_services.locate_handle_buffer_f)(
+                LocateSearchType::ByProtocol,
+                &GRAPHICS_OUTPUT_PROTOCOL_GUID,
+                core::ptr::null_mut(),
+                &mut count,
+                &mut buffer,
+            )
+            .to_result()
+            .map_err(|_| BootError::Video(VideoError::GopNotSupported))?;
+
+            // O buffer pertence ao pool do firmware: copiamos e liberamos
+            let handles = core::slice::from_raw_parts(buffer, count).to_vec();
+            let _ = boot_services.free_pool(buffer as *mut u8);
+            handles
+        };
+
+        Ok(handles
+            .into_iter()
+            .filter_map(|handle| {
+                boot_services
+                    .open_protocol(
+                        handle,
+                        &GRAPHICS_OUTPUT_PROTOCOL_GUID,
+                        crate::uefi::image_handle(),
+                        crate::uefi::Handle::null(),
+                        OPEN_PROTOCOL_GET_PROTOCOL,
+                    )
+                    .ok()
+            })
+            .filter(|&interface| interface != primary)
+            .map(|interface| Self {
+                boot_services,
+                gop_interface: interface
+                    as *mut crate::uefi::proto::console::gop::GraphicsOutputProtocol,
+            })
+            .collect())
+    }
+
     fn get_current_mode_info(&self) -> Result<FramebufferInfo> {
         unsafe {
             let gop = &*self.gop_interface;
             let mode = &*gop.mode;
-            let info = &*mode.info;
-
-            Ok(FramebufferInfo {
-                addr: mode.frame_buffer_base,
-                size: mode.frame_buffer_size,
-                width: info.horizontal_resolution,
-                height: info.vertical_resolution,
-                stride: info.pixels_per_scan_line,
-                format: match info.pixel_format {
-                    crate::uefi::proto::console::gop::PixelFormat::PixelRedGreenBlueReserved8BitPerColor => PixelFormat::RgbReserved8Bit,
-                    crate::uefi::proto::console::gop::PixelFormat::PixelBlueGreenRedReserved8BitPerColor => PixelFormat::BgrReserved8Bit,
-                    crate::uefi::proto::console::gop::PixelFormat::PixelBitMask => PixelFormat::Bitmask,
-                    _ => PixelFormat::BltOnly,
-                },
-            })
+
+            Ok(mode_framebuffer_info(
+                mode.frame_buffer_base,
+                mode.frame_buffer_size,
+                &*mode.info,
+            ))
         }
     }
 
+    /// Lista os modos de vídeo que o firmware oferece.
     pub fn query_modes(&self) -> Result<impl Iterator<Item = VideoMode>> {
-        Ok(core::iter::empty())
+        let mut modes = Vec::new();
+
+        unsafe {
+            let gop = self.gop_interface;
+            let max_mode = (*(*gop).mode).max_mode;
+
+            for id in 0..max_mode {
+                let mut size = 0usize;
+                let mut info_ptr: *mut GraphicsOutputModeInformation = core::ptr::null_mut();
+
+                if ((*gop).query_mode)(gop, id, &mut size, &mut info_ptr).is_error()
+                    || info_ptr.is_null()
+                {
+                    continue;
+                }
+
+                let info = &*info_ptr;
+                modes.push(VideoMode {
+                    id,
+                    info: VideoModeInfo {
+                        width:  info.horizontal_resolution as usize,
+                        height: info.vertical_resolution as usize,
+                        stride: info.pixels_per_scan_line as usize,
+                        format: convert_pixel_format(info.pixel_format),
+                        bpp:    bits_per_pixel(info.pixel_format, &info.pixel_information),
+                    },
+                });
+
+                // O buffer de QueryMode é alocado pelo firmware
+                let _ = self.boot_services.free_pool(info_ptr as *mut u8);
+            }
+        }
+
+        Ok(modes.into_iter())
     }
 
-    pub fn set_mode(&mut self, _mode_id: Option<u32>) -> Result<FramebufferInfo> {
-        self.get_current_mode_info()
+    /// Configura o modo de vídeo.
+    ///
+    /// Com `requested = Some((largura, altura, bpp))`, procura um modo com
+    /// essa resolução que forneça os bits pedidos. Se nenhum servir, mantém o
+    /// modo atual do firmware.
+    pub fn set_mode(&mut self, requested: Option<(u32, u32, u32)>) -> Result<FramebufferInfo> {
+        if let Some((width, height, bpp)) = requested {
+            match select_mode(self.query_modes()?, width, height, bpp) {
+                Ok(mode) => self.set_mode_id(mode.id)?,
+                Err(_) => {
+                    log::warn!(
+                        "Video: modo {}x{}x{} indisponivel, mantendo modo atual",
+                        width,
+                        height,
+                        bpp
+                    );
+                },
+            }
+        }
+
+        // O tamanho informado pelo firmware limita a área desenhável
+        let mut info = self.get_current_mode_info()?;
+        let expected = info.expected_size();
+        if info.clamp_to_size() {
+            log::warn!(
+                "Video: framebuffer de {} bytes, esperado {}; limitando a {} linhas",
+                info.size,
+                expected,
+                info.height
+            );
+        }
+
+        Ok(info)
+    }
+
+    fn set_mode_id(&mut self, id: u32) -> Result<()> {
+        unsafe {
+            let gop = self.gop_interface;
+            ((*gop).set_mode)(gop, id)
+                .to_result()
+                .map_err(|_| BootError::Video(VideoError::ModeSetFailed))
+        }
     }
 
     /// # Safety
     /// Retorna uma estrutura que escreve diretamente na VRAM.
     pub unsafe fn get_framebuffer(&mut self) -> Result<Framebuffer<'_>> {
-        let info = self.get_current_mode_info()?;
+        let mut info = self.get_current_mode_info()?;
+        info.clamp_to_size();
 
         if info.addr == 0 || info.width == 0 || info.height == 0 {
             return Err(BootError::Video(VideoError::InitializationFailed));
@@ -84,3 +227,158 @@ impl<'a> GopDriver<'a> {
         Ok(Framebuffer::new(info.addr, info))
     }
 }
+
+impl ModeControl for GopDriver<'_> {
+    fn modes(&self) -> Result<Vec<VideoMode>> {
+        Ok(self.query_modes()?.collect())
+    }
+
+    fn activate(&mut self, id: u32) -> Result<FramebufferInfo> {
+        self.set_mode_id(id)?;
+        let mut info = self.get_current_mode_info()?;
+        info.clamp_to_size();
+        Ok(info)
+    }
+
+    fn current_framebuffer_addr(&self) -> u64 {
+        unsafe { (*(*self.gop_interface).mode).frame_buffer_base }
+    }
+}
+
+/// Modo conservador entre `modes`: 800x600 com framebuffer linear, se
+/// existir; senão o modo 0, que a spec garante estar presente.
+pub fn safe_mode_id(modes: &[VideoMode]) -> u32 {
+    let (width, height) = SAFE_MODE_RESOLUTION;
+    modes
+        .iter()
+        .find(|m| {
+            m.info.width == width
+                && m.info.height == height
+                && m.info.format != PixelFormat::BltOnly
+        })
+        .map_or(0, |m| m.id)
+}
+
+/// Melhor modo entre `modes`: o de maior resolução com framebuffer linear.
+pub fn best_mode_id(modes: &[VideoMode]) -> Option<u32> {
+    modes
+        .iter()
+        .filter(|m| m.info.format != PixelFormat::BltOnly)
+        .max_by_key(|m| m.info.width * m.info.height)
+        .map(|m| m.id)
+}
+
+/// Ativa o melhor modo (`best_mode_id`) de cada saída e retorna os
+/// framebuffers resultantes, na ordem de `outputs`.
+///
+/// Uma saída cujo framebuffer atual é o do console primário (`primary_addr`)
+/// ou o de uma anterior é o mesmo display exposto em outro handle: fica de
+/// fora antes de trocar de modo, que mudaria a resolução já entregue. Saídas
+/// sem modo linear ou que falham ao trocar de modo também ficam de fora.
+pub fn activate_best_modes<G: ModeControl>(
+    outputs: &mut [G],
+    primary_addr: u64,
+) -> Vec<FramebufferInfo> {
+    let mut framebuffers: Vec<FramebufferInfo> = Vec::new();
+    let taken = |framebuffers: &[FramebufferInfo], addr: u64| {
+        addr == primary_addr || framebuffers.iter().any(|fb| fb.addr == addr)
+    };
+    for output in outputs {
+        let current = output.current_framebuffer_addr();
+        if current != 0 && taken(&framebuffers, current) {
+            continue;
+        }
+        let Some(id) = output.modes().ok().and_then(|modes| best_mode_id(&modes)) else {
+            continue;
+        };
+        match output.activate(id) {
+            Ok(info) if info.addr != 0 && !taken(&framebuffers, info.addr) => {
+                framebuffers.push(info)
+            },
+            Ok(_) => {},
+            Err(e) => log::warn!("Video: saida extra ignorada (modo {}): {:?}", id, e),
+        }
+    }
+    framebuffers
+}
+
+/// Volta `gop` a um modo conservador (`safe_mode_id`) e limpa a tela.
+///
+/// # Safety
+/// O framebuffer informado por `gop.activate` deve estar mapeado.
+pub unsafe fn reset_to_safe_mode_with<G: ModeControl + ?Sized>(
+    gop: &mut G,
+) -> Result<FramebufferInfo> {
+    let id = safe_mode_id(&gop.modes()?);
+    let info = gop.activate(id)?;
+
+    if info.addr != 0 && info.format != PixelFormat::BltOnly {
+        Framebuffer::new(info.addr, info).clear(Color::BLACK);
+    }
+
+    Ok(info)
+}
+
+/// Restaura o vídeo depois que uma aplicação carregada deixou o GOP num modo
+/// inutilizável (ex: chainload que falhou e voltou ao menu).
+pub fn reset_to_safe_mode() -> Result<FramebufferInfo> {
+    let bs = crate::uefi::system_table().boot_services();
+    let mut driver = GopDriver::new(bs)?;
+    unsafe { reset_to_safe_mode_with(&mut driver) }
+}
+
+/// Framebuffer descrito por um modo GOP com a VRAM em `addr` (`size` bytes).
+///
+/// Em `PixelBitMask` as máscaras do firmware são copiadas; nos outros
+/// formatos ficam zeradas, já que a ordem dos canais vem do próprio formato.
+pub fn mode_framebuffer_info(
+    addr: u64,
+    size: usize,
+    info: &GraphicsOutputModeInformation,
+) -> FramebufferInfo {
+    let masks = match info.pixel_format {
+        GopPixelFormat::PixelBitMask => PixelMasks {
+            red:      info.pixel_information.red_mask,
+            green:    info.pixel_information.green_mask,
+            blue:     info.pixel_information.blue_mask,
+            reserved: info.pixel_information.reserved_mask,
+        },
+        _ => PixelMasks::default(),
+    };
+
+    FramebufferInfo {
+        addr,
+        size,
+        width: info.horizontal_resolution,
+        height: info.vertical_resolution,
+        stride: info.pixels_per_scan_line,
+        format: convert_pixel_format(info.pixel_format),
+        masks,
+    }
+}
+
+/// Converte o formato de pixel do GOP para o formato agnóstico do Ignite.
+fn convert_pixel_format(format: GopPixelFormat) -> PixelFormat {
+    match format {
+        GopPixelFormat::PixelRedGreenBlueReserved8BitPerColor => PixelFormat::RgbReserved8Bit,
+        GopPixelFormat::PixelBlueGreenRedReserved8BitPerColor => PixelFormat::BgrReserved8Bit,
+        GopPixelFormat::PixelBitMask => PixelFormat::Bitmask,
+        _ => PixelFormat::BltOnly,
+    }
+}
+
+/// Bits por pixel de um modo GOP.
+///
+/// Formatos 8-bit-por-cor ocupam 32 bits; em `PixelBitMask` o pixel vai até o
+/// bit mais alto usado pelas máscaras. `BltOnly` não tem framebuffer linear.
+fn bits_per_pixel(format: GopPixelFormat, mask: &PixelBitmask) -> u32 {
+    match format {
+        GopPixelFormat::PixelRedGreenBlueReserved8BitPerColor
+        | GopPixelFormat::PixelBlueGreenRedReserved8BitPerColor => 32,
+        GopPixelFormat::PixelBitMask => {
+            let all = mask.red_mask | mask.green_mask | mask.blue_mask | mask.reserved_mask;
+            u32::BITS - all.leading_zeros()
+        },
+        _ => 0,
+    }
+}
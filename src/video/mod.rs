@@ -20,18 +20,16 @@
 //!   serem passadas para o Kernel sem dependência de UEFI.
 //!
 //! ### ⚠️ Pontos de Atenção (Riscos e Dívida)
-//! - **Hardcoded Auto-Detect:** A função `init_video` ignora preferências de
-//!   resolução. Se o monitor reportar EDID errado, ficamos presos em resolução
-//!   ruim.
-//!   - *Correção:* Permitir override via `ignite.cfg` (ex: `video_mode =
-//!     "1920x1080"`).
+//! - **Auto-Detect por Padrão:** Sem `resolution` no `ignite.cfg`, o modo do
+//!   firmware é mantido. Se o monitor reportar EDID errado, o usuário precisa
+//!   configurar a resolução manualmente.
 //! - **Performance de Escrita:** Desenhar pixel a pixel no Framebuffer UEFI é
 //!   lento (uncached write-combining memory).
 //!   - *Mitigação:* A UI deve usar Double Buffering em RAM e fazer *Dirty Rect
 //!     Blit*.
 //!
 //! ## 🛠️ TODOs e Roadmap
-//! - [ ] **TODO: (Config)** Implementar seleção de resolução baseada em
+//! - [x] **TODO: (Config)** Implementar seleção de resolução baseada em
 //!   `ignite.cfg`.
 //! - [ ] **TODO: (Driver)** Analisar suporte a múltiplos monitores (GOP
 //!   geralmente só expõe o primário).
@@ -42,22 +40,26 @@ pub mod mode;
 pub mod pixel;
 
 // Re-exportações para facilitar o uso no `main.rs`
-pub use framebuffer::{Framebuffer, FramebufferInfo};
+pub use framebuffer::{
+    darken_rect_bytes, draw_test_pattern, fill_rect_bytes, Framebuffer, FramebufferInfo, Rect,
+};
 pub use gop::GopDriver;
-pub use mode::{VideoMode, VideoModeInfo};
+pub use mode::{normalize_bpp, select_mode, VideoMode, VideoModeInfo, DEFAULT_BPP};
 pub use pixel::{Color, PixelFormat};
 
 use crate::core::error::Result;
 
-/// Inicializa o vídeo na melhor resolução possível e limpa a tela.
-/// Retorna o driver GOP e o Framebuffer ativo.
+/// Inicializa o vídeo e retorna o driver GOP e o Framebuffer ativo.
+///
+/// `resolution` (largura, altura, bpp) vem de `ignite.cfg`; sem ela, mantém o
+/// modo escolhido pelo firmware (geralmente o nativo do monitor).
 pub fn init_video(
     boot_services: &crate::uefi::BootServices,
+    resolution: Option<(u32, u32, u32)>,
 ) -> Result<(GopDriver<'_>, FramebufferInfo)> {
     let mut driver = GopDriver::new(boot_services)?;
 
-    // Auto-detecta e configura a melhor resolução (geralmente nativa do monitor)
-    let fb_info = driver.set_mode(None)?;
+    let fb_info = driver.set_mode(resolution)?;
 
     // (Opcional) Limpar a tela ou desenhar logo aqui
     // let mut fb = unsafe { driver.get_framebuffer()? };
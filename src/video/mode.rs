@@ -3,6 +3,10 @@
 //! Encapsula as informações retornadas pelo GOP sobre o que o monitor suporta.
 
 use super::pixel::PixelFormat;
+use crate::core::error::{BootError, Result, VideoError};
+
+/// Profundidade de cor padrão quando `resolution` não especifica bpp.
+pub const DEFAULT_BPP: u32 = 32;
 
 /// Informações sobre um modo de vídeo específico.
 #[derive(Debug, Clone, Copy)]
@@ -16,6 +20,8 @@ pub struct VideoModeInfo {
     pub stride: usize,
     /// Formato dos pixels.
     pub format: PixelFormat,
+    /// Bits por pixel efetivos no framebuffer (0 para `BltOnly`).
+    pub bpp:    u32,
 }
 
 /// Identificador de um modo de vídeo.
@@ -33,3 +39,42 @@ impl VideoMode {
         self.info.stride * self.info.height * 4 // 4 bytes por pixel (32-bit color)
     }
 }
+
+/// Normaliza o bpp pedido na configuração: `(bpp efetivo, ajustado)`.
+///
+/// O GOP trabalha, na prática, só com pixels de 32 bits; pedidos de 16 ou 24
+/// bpp são atendidos com 32 e `ajustado` vem `true` para quem chama avisar.
+/// Outros valores não são suportados.
+pub fn normalize_bpp(requested: u32) -> Result<(u32, bool)> {
+    match requested {
+        32 => Ok((32, false)),
+        16 | 24 => Ok((32, true)),
+        _ => Err(BootError::Video(VideoError::UnsupportedMode)),
+    }
+}
+
+/// Escolhe, entre `modes`, o modo com a resolução exata pedida e que forneça
+/// pelo menos `requested_bpp` bits por pixel (após `normalize_bpp`).
+pub fn select_mode<I>(modes: I, width: u32, height: u32, requested_bpp: u32) -> Result<VideoMode>
+where
+    I: IntoIterator<Item = VideoMode>,
+{
+    let (bpp, clamped) = normalize_bpp(requested_bpp)?;
+    if clamped {
+        log::warn!(
+            "Video: {} bpp nao suportado pelo GOP, usando {} bpp",
+            requested_bpp,
+            bpp
+        );
+    }
+
+    modes
+        .into_iter()
+        .find(|m| {
+            m.info.width == width as usize
+                && m.info.height == height as usize
+                && m.info.format != PixelFormat::BltOnly
+                && m.info.bpp >= bpp
+        })
+        .ok_or(BootError::Video(VideoError::ResolutionMismatch))
+}
@@ -20,6 +20,19 @@ pub enum PixelFormat {
     BltOnly,
 }
 
+impl From<PixelFormat> for crate::core::handoff::PixelFormat {
+    /// Formato entregue ao kernel e à UI. `BltOnly` é preservado: quem
+    /// consome confere `is_linear()` antes de escrever no framebuffer.
+    fn from(format: PixelFormat) -> Self {
+        match format {
+            PixelFormat::RgbReserved8Bit => Self::Rgb,
+            PixelFormat::BgrReserved8Bit => Self::Bgr,
+            PixelFormat::Bitmask => Self::Bitmask,
+            PixelFormat::BltOnly => Self::BltOnly,
+        }
+    }
+}
+
 /// Representa uma cor RGBA independente de hardware.
 #[derive(Debug, Clone, Copy)]
 pub struct Color {
@@ -64,4 +77,9 @@ impl Color {
     pub fn new(r: u8, g: u8, b: u8) -> Self {
         Self { r, g, b, a: 255 }
     }
+
+    /// Cor opaca a partir de `0xRRGGBB`.
+    pub fn from_rgb(rgb: u32) -> Self {
+        Self::new((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+    }
 }
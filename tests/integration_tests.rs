@@ -129,26 +129,20 @@ fn test_path_resolution() {
 /// Testa cálculo de alinhamento
 #[test]
 fn test_alignment_calculations() {
-    fn align_up(addr: u64, align: u64) -> u64 {
-        (addr + align - 1) & !(align - 1)
-    }
-
-    fn align_down(addr: u64, align: u64) -> u64 {
-        addr & !(align - 1)
-    }
+    use ignite::core::align::{align_down, align_up};
 
     // Testar alinhamento de 4K
-    assert_eq!(align_up(0x1000, 0x1000), 0x1000);
-    assert_eq!(align_up(0x1001, 0x1000), 0x2000);
-    assert_eq!(align_up(0x1FFF, 0x1000), 0x2000);
+    assert_eq!(align_up(0x1000, 0x1000), Some(0x1000));
+    assert_eq!(align_up(0x1001, 0x1000), Some(0x2000));
+    assert_eq!(align_up(0x1FFF, 0x1000), Some(0x2000));
 
     assert_eq!(align_down(0x1000, 0x1000), 0x1000);
     assert_eq!(align_down(0x1FFF, 0x1000), 0x1000);
     assert_eq!(align_down(0x2000, 0x1000), 0x2000);
 
     // Testar alinhamento de 2M (huge pages)
-    assert_eq!(align_up(0x200000, 0x200000), 0x200000);
-    assert_eq!(align_up(0x200001, 0x200000), 0x400000);
+    assert_eq!(align_up(0x200000, 0x200000), Some(0x200000));
+    assert_eq!(align_up(0x200001, 0x200000), Some(0x400000));
 }
 
 /// Testa conversão de tipos de memória UEFI
@@ -0,0 +1,405 @@
+//! Infraestrutura dos testes em QEMU
+//!
+//! Compila o `ignite.efi` para `x86_64-unknown-uefi`, monta imagens FAT com
+//! o mtools e dá boot nelas no QEMU com OVMF, guardando tudo o que o Ignite
+//! escreve na COM1.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::OnceLock,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Variável que habilita os testes (`IGNITE_QEMU_TESTS=1`).
+pub const ENABLE_VAR: &str = "IGNITE_QEMU_TESTS";
+
+/// Tempo máximo de espera pelas marcas na serial, por boot.
+pub const BOOT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Início da linha de resultado do kernel stub (`tests/qemu/stub-kernel`).
+pub const STUB_REPORT_PREFIX: &str = "STUB-KERNEL ";
+
+/// Valor que o kernel stub escreve em `stub_magic` ("STUBKERN" em ASCII).
+pub const STUB_MAGIC: u64 = 0x5354_5542_4B45_524E;
+
+/// Porta do `isa-debug-exit`, por onde o kernel stub desliga o QEMU.
+const DEBUG_EXIT_PORT: u16 = 0xF4;
+
+/// Tamanho da imagem FAT: 64 MiB em setores de 512 bytes.
+const IMAGE_SECTORS: u64 = 128 * 1024;
+
+/// Locais comuns do firmware OVMF nas distribuições, como `(CODE, VARS)`.
+const OVMF_CANDIDATES: &[(&str, &str)] = &[
+    (
+        "/usr/share/OVMF/OVMF_CODE.fd",
+        "/usr/share/OVMF/OVMF_VARS.fd",
+    ),
+    (
+        "/usr/share/OVMF/OVMF_CODE_4M.fd",
+        "/usr/share/OVMF/OVMF_VARS_4M.fd",
+    ),
+    (
+        "/usr/share/edk2/ovmf/OVMF_CODE.fd",
+        "/usr/share/edk2/ovmf/OVMF_VARS.fd",
+    ),
+    (
+        "/usr/share/edk2/x64/OVMF_CODE.fd",
+        "/usr/share/edk2/x64/OVMF_VARS.fd",
+    ),
+    ("/usr/share/qemu/OVMF.fd", ""),
+];
+
+/// Ambiente de teste: QEMU, firmware e o `ignite.efi` já compilado.
+pub struct QemuEnv {
+    qemu:      PathBuf,
+    ovmf_code: PathBuf,
+    /// Modelo das variáveis UEFI; cada boot usa uma cópia. Sem ele, o
+    /// firmware é carregado com `-bios` (imagem única, ex.: `OVMF.fd`).
+    ovmf_vars: Option<PathBuf>,
+    efi:       PathBuf,
+    workdir:   PathBuf,
+}
+
+/// Prepara o ambiente, ou `None` (teste ignorado) sem `IGNITE_QEMU_TESTS=1`.
+///
+/// Com a variável definida, a falta de QEMU, OVMF ou mtools é erro: quem
+/// pediu os testes espera que rodem.
+pub fn setup(test: &str) -> Option<QemuEnv> {
+    if env::var(ENABLE_VAR).as_deref() != Ok("1") {
+        eprintln!(
+            "{}: ignorado (defina {}=1 para rodar em QEMU)",
+            test, ENABLE_VAR
+        );
+        return None;
+    }
+
+    let qemu =
+        env::var_os("QEMU").map_or_else(|| PathBuf::from("qemu-system-x86_64"), PathBuf::from);
+    require_tool(&qemu, "--version");
+    require_tool(Path::new("mformat"), "--version");
+
+    let (ovmf_code, ovmf_vars) = find_ovmf();
+    let workdir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("qemu")
+        .join(test);
+    let _ = fs::remove_dir_all(&workdir);
+    fs::create_dir_all(&workdir).expect("falha ao criar o diretório de trabalho");
+
+    Some(QemuEnv {
+        qemu,
+        ovmf_code,
+        ovmf_vars,
+        efi: build_ignite().to_path_buf(),
+        workdir,
+    })
+}
+
+fn require_tool(program: &Path, arg: &str) {
+    let found = Command::new(program)
+        .arg(arg)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok();
+    assert!(found, "{} não encontrado no PATH", program.display());
+}
+
+/// `OVMF_CODE`/`OVMF_VARS` do ambiente ou o primeiro candidato instalado.
+fn find_ovmf() -> (PathBuf, Option<PathBuf>) {
+    if let Some(code) = env::var_os("OVMF_CODE") {
+        return (
+            PathBuf::from(code),
+            env::var_os("OVMF_VARS").map(PathBuf::from),
+        );
+    }
+
+    OVMF_CANDIDATES
+        .iter()
+        .find(|(code, _)| Path::new(code).exists())
+        .map(|&(code, vars)| {
+            let vars = Some(PathBuf::from(vars)).filter(|vars| vars.exists());
+            (PathBuf::from(code), vars)
+        })
+        .expect("OVMF não encontrado; defina OVMF_CODE (e OVMF_VARS)")
+}
+
+/// Diretório de build usado pelo `cargo` deste teste.
+fn target_dir() -> PathBuf {
+    env::var_os("CARGO_TARGET_DIR").map_or_else(
+        || Path::new(env!("CARGO_MANIFEST_DIR")).join("target"),
+        PathBuf::from,
+    )
+}
+
+/// Roda `cargo build` em `dir` com `args`, no diretório de build comum.
+fn cargo_build(dir: &Path, args: &[&str]) {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let status = Command::new(cargo)
+        .current_dir(dir)
+        .arg("build")
+        .args(args)
+        .arg("--target-dir")
+        .arg(target_dir())
+        .status()
+        .expect("falha ao executar o cargo");
+    assert!(status.success(), "cargo build {:?} falhou", args);
+}
+
+/// Compila o binário UEFI uma única vez por execução e devolve o caminho do
+/// `ignite.efi`.
+pub fn build_ignite() -> &'static Path {
+    static EFI: OnceLock<PathBuf> = OnceLock::new();
+
+    EFI.get_or_init(|| {
+        cargo_build(
+            Path::new(env!("CARGO_MANIFEST_DIR")),
+            &["--bin", "ignite", "--target", "x86_64-unknown-uefi"],
+        );
+        target_dir().join("x86_64-unknown-uefi/debug/ignite.efi")
+    })
+}
+
+/// Compila `tests/qemu/stub-kernel` (alvo `x86_64-unknown-none`) uma única
+/// vez por execução e devolve o ELF.
+pub fn build_stub_kernel() -> &'static Path {
+    static ELF: OnceLock<PathBuf> = OnceLock::new();
+
+    ELF.get_or_init(|| {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/qemu/stub-kernel");
+        cargo_build(&dir, &["--release"]);
+        target_dir().join("x86_64-unknown-none/release/stub-kernel")
+    })
+}
+
+/// Conteúdo de uma imagem FAT de boot. O `ignite.efi` vai sempre em
+/// `EFI/BOOT/BOOTX64.EFI`.
+#[derive(Default)]
+pub struct EspImage {
+    files: Vec<(String, Vec<u8>)>,
+}
+
+impl EspImage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Grava `ignite.cfg` em `EFI/BOOT/`, o primeiro caminho procurado pelo
+    /// Ignite.
+    pub fn config(self, text: &str) -> Self {
+        self.file("EFI/BOOT/ignite.cfg", text.as_bytes())
+    }
+
+    /// Acrescenta um arquivo em `path` (relativo à raiz, separado por `/`).
+    pub fn file(mut self, path: &str, data: &[u8]) -> Self {
+        self.files.push((path.to_string(), data.to_vec()));
+        self
+    }
+
+    /// Formata a imagem em `env` e copia os arquivos com o mtools.
+    fn build(&self, env: &QemuEnv) -> PathBuf {
+        let image = env.workdir.join("esp.img");
+        fs::File::create(&image)
+            .and_then(|file| file.set_len(IMAGE_SECTORS * 512))
+            .expect("falha ao criar a imagem");
+        mtools(
+            &["mformat", "-i"],
+            &image,
+            &[
+                "-T",
+                &IMAGE_SECTORS.to_string(),
+                "-h",
+                "64",
+                "-s",
+                "32",
+                "::",
+            ],
+        );
+
+        let efi = fs::read(&env.efi).expect("falha ao ler o ignite.efi");
+        let boot = ("EFI/BOOT/BOOTX64.EFI".to_string(), efi);
+
+        let mut dirs = BTreeSet::new();
+        for (index, (path, data)) in std::iter::once(&boot).chain(&self.files).enumerate() {
+            // Diretórios intermediários, na ordem, sem repetir
+            let parts: Vec<&str> = path.split('/').collect();
+            for depth in 1..parts.len() {
+                let dir = parts[..depth].join("/");
+                if dirs.insert(dir.clone()) {
+                    mtools(&["mmd", "-i"], &image, &[&format!("::/{}", dir)]);
+                }
+            }
+
+            let staged = env.workdir.join(format!("file{}", index));
+            fs::write(&staged, data).expect("falha ao preparar arquivo");
+            mtools(
+                &["mcopy", "-i"],
+                &image,
+                &[staged.to_str().unwrap(), &format!("::/{}", path)],
+            );
+        }
+
+        image
+    }
+}
+
+fn mtools(command: &[&str], image: &Path, args: &[&str]) {
+    let status = Command::new(command[0])
+        .args(&command[1..])
+        .arg(image)
+        .args(args)
+        .status()
+        .unwrap_or_else(|e| panic!("falha ao executar {}: {}", command[0], e));
+    assert!(status.success(), "{} {:?} falhou", command[0], args);
+}
+
+/// Saída da COM1 durante um boot.
+pub struct SerialLog(pub String);
+
+impl SerialLog {
+    /// Falha o teste, mostrando o log, se `marker` não apareceu.
+    #[track_caller]
+    pub fn assert_contains(&self, marker: &str) {
+        assert!(
+            self.0.contains(marker),
+            "marca {:?} ausente na serial:\n{}",
+            marker,
+            self.0
+        );
+    }
+
+    /// Pares `chave=valor` da linha `STUB-KERNEL` escrita pelo kernel stub,
+    /// ou `None` se o stub não chegou a rodar.
+    pub fn stub_report(&self) -> Option<BTreeMap<&str, &str>> {
+        let line = self
+            .0
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(STUB_REPORT_PREFIX))?;
+        Some(
+            line.split_whitespace()
+                .filter_map(|pair| pair.split_once('='))
+                .collect(),
+        )
+    }
+}
+
+impl QemuEnv {
+    /// Dá boot em `image` e espera até todas as `markers` aparecerem na
+    /// serial, o QEMU terminar ou `BOOT_TIMEOUT` passar. A máquina é
+    /// desligada em seguida.
+    pub fn boot(&self, image: &EspImage, markers: &[&str]) -> SerialLog {
+        let disk = image.build(self);
+        let serial = self.workdir.join("serial.log");
+
+        let mut qemu = self.spawn(&disk, &serial);
+        let deadline = Instant::now() + BOOT_TIMEOUT;
+        let log = loop {
+            let log = fs::read_to_string(&serial).unwrap_or_default();
+            let exited = qemu.try_wait().ok().flatten().is_some();
+            if exited || Instant::now() >= deadline || markers.iter().all(|m| log.contains(m)) {
+                break log;
+            }
+            thread::sleep(Duration::from_millis(100));
+        };
+
+        let _ = qemu.kill();
+        let _ = qemu.wait();
+        SerialLog(log)
+    }
+
+    fn spawn(&self, disk: &Path, serial: &Path) -> Child {
+        let mut command = Command::new(&self.qemu);
+        command
+            .args([
+                "-machine",
+                "q35",
+                "-m",
+                "512M",
+                "-display",
+                "none",
+                "-no-reboot",
+            ])
+            .args(["-net", "none"])
+            .arg("-device")
+            .arg(format!(
+                "isa-debug-exit,iobase={:#x},iosize=0x04",
+                DEBUG_EXIT_PORT
+            ))
+            .arg("-serial")
+            .arg(format!("file:{}", serial.display()));
+
+        match &self.ovmf_vars {
+            Some(template) => {
+                let vars = self.workdir.join("OVMF_VARS.fd");
+                fs::copy(template, &vars).expect("falha ao copiar OVMF_VARS");
+                command
+                    .arg("-drive")
+                    .arg(pflash(&self.ovmf_code, true))
+                    .arg("-drive")
+                    .arg(pflash(&vars, false));
+            },
+            None => {
+                command.arg("-bios").arg(&self.ovmf_code);
+            },
+        }
+
+        command
+            .arg("-drive")
+            .arg(format!("format=raw,file={}", disk.display()))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("falha ao iniciar o QEMU")
+    }
+}
+
+fn pflash(path: &Path, readonly: bool) -> String {
+    format!(
+        "if=pflash,format=raw,readonly={},file={}",
+        if readonly { "on" } else { "off" },
+        path.display()
+    )
+}
+
+/// Kernel mínimo gerado aqui mesmo, sem toolchain extra: um único `PT_LOAD` em
+/// `REDSTONE_KERNEL_ENTRY` com `cli; hlt; jmp` (volta ao `hlt`).
+pub fn halt_kernel() -> Vec<u8> {
+    const CODE: [u8; 4] = [0xFA, 0xF4, 0xEB, 0xFD];
+    const CODE_OFFSET: u64 = 0x1000;
+    let entry = ignite::protos::redstone::REDSTONE_KERNEL_ENTRY;
+
+    let mut elf = Vec::new();
+
+    // e_ident: magic, ELFCLASS64, little endian, versão 1
+    elf.extend_from_slice(&[0x7F, b'E', b'L', b'F', 2, 1, 1, 0]);
+    elf.extend_from_slice(&[0u8; 8]);
+    elf.extend_from_slice(&2u16.to_le_bytes()); // e_type = ET_EXEC
+    elf.extend_from_slice(&0x3Eu16.to_le_bytes()); // e_machine = x86_64
+    elf.extend_from_slice(&1u32.to_le_bytes()); // e_version
+    elf.extend_from_slice(&entry.to_le_bytes()); // e_entry
+    elf.extend_from_slice(&64u64.to_le_bytes()); // e_phoff
+    elf.extend_from_slice(&0u64.to_le_bytes()); // e_shoff
+    elf.extend_from_slice(&0u32.to_le_bytes()); // e_flags
+    elf.extend_from_slice(&64u16.to_le_bytes()); // e_ehsize
+    elf.extend_from_slice(&56u16.to_le_bytes()); // e_phentsize
+    elf.extend_from_slice(&1u16.to_le_bytes()); // e_phnum
+    elf.extend_from_slice(&64u16.to_le_bytes()); // e_shentsize
+    elf.extend_from_slice(&0u16.to_le_bytes()); // e_shnum
+    elf.extend_from_slice(&0u16.to_le_bytes()); // e_shstrndx
+
+    elf.extend_from_slice(&1u32.to_le_bytes()); // p_type = PT_LOAD
+    elf.extend_from_slice(&5u32.to_le_bytes()); // p_flags = R+X
+    elf.extend_from_slice(&CODE_OFFSET.to_le_bytes()); // p_offset
+    elf.extend_from_slice(&entry.to_le_bytes()); // p_vaddr
+    elf.extend_from_slice(&0x20_0000u64.to_le_bytes()); // p_paddr
+    elf.extend_from_slice(&(CODE.len() as u64).to_le_bytes()); // p_filesz
+    elf.extend_from_slice(&0x1000u64.to_le_bytes()); // p_memsz
+    elf.extend_from_slice(&0x1000u64.to_le_bytes()); // p_align
+
+    elf.resize(CODE_OFFSET as usize, 0);
+    elf.extend_from_slice(&CODE);
+    elf
+}
@@ -0,0 +1,99 @@
+//! Testes de Boot em QEMU/OVMF
+//!
+//! Cada teste compila o `ignite.efi`, monta uma imagem FAT com um
+//! `ignite.cfg` conhecido, dá boot nela no QEMU com OVMF e confere as marcas
+//! que o Ignite escreve na serial.
+//!
+//! Ficam desligados por padrão (máquinas sem QEMU passam direto). Para rodar:
+//!
+//! ```bash
+//! IGNITE_QEMU_TESTS=1 cargo test --test qemu
+//! ```
+//!
+//! `test_handoff_reaches_stub_kernel` compila também o kernel de
+//! `tests/qemu/stub-kernel`, o que pede o alvo `x86_64-unknown-none`
+//! (instalado pelo `rust-toolchain.toml`).
+//!
+//! Requer `qemu-system-x86_64`, mtools e OVMF. `QEMU`, `OVMF_CODE` e
+//! `OVMF_VARS` sobrescrevem os caminhos detectados.
+
+mod harness;
+
+use harness::EspImage;
+
+/// Config que dá boot direto (sem menu) em `boot/forge`.
+const FORGE_CONFIG: &str = "\
+timeout: 0
+quiet: yes
+serial: yes
+
+/Stub
+    protocol: redstone
+    path: boot():/boot/forge
+";
+
+/// Testa o caminho normal: heap, config e mapa de memória até o salto
+#[test]
+fn test_boot_halt_kernel() {
+    let Some(env) = harness::setup("boot_halt_kernel") else {
+        return;
+    };
+
+    let image = EspImage::new()
+        .config(FORGE_CONFIG)
+        .file("boot/forge", &harness::halt_kernel());
+    let serial = env.boot(&image, &["[JUMP]"]);
+
+    serial.assert_contains("Ignite Bootloader Iniciando...");
+    serial.assert_contains("[OK] Heap inicializada");
+    serial.assert_contains("Carregando config: EFI/BOOT/ignite.cfg");
+    serial.assert_contains("Memory map:");
+    serial.assert_contains("[JUMP]");
+}
+
+/// Testa o handoff completo: `RedstoneProtocol::load`, paginação e
+/// `jump_to_kernel_redstone` até o kernel stub ler um `BootInfo` válido
+#[test]
+fn test_handoff_reaches_stub_kernel() {
+    use ignite::core::handoff::{BOOT_INFO_MAGIC, BOOT_INFO_VERSION};
+
+    let Some(env) = harness::setup("handoff_stub_kernel") else {
+        return;
+    };
+
+    let kernel = std::fs::read(harness::build_stub_kernel()).expect("falha ao ler o kernel stub");
+    let image = EspImage::new()
+        .config(FORGE_CONFIG)
+        .file("boot/forge", &kernel);
+    let serial = env.boot(&image, &[harness::STUB_REPORT_PREFIX]);
+
+    let report = serial
+        .stub_report()
+        .unwrap_or_else(|| panic!("kernel stub não respondeu:\n{}", serial.0));
+    let hex = |key: &str| u64::from_str_radix(report[key].trim_start_matches("0x"), 16).unwrap();
+    let dec = |key: &str| report[key].parse::<u64>().unwrap();
+
+    assert_eq!(hex("stub_magic"), harness::STUB_MAGIC);
+    assert_eq!(hex("magic"), BOOT_INFO_MAGIC);
+    assert_eq!(dec("version"), BOOT_INFO_VERSION as u64);
+    assert_ne!(hex("fb_addr"), 0);
+    assert!(dec("fb_width") > 0 && dec("fb_height") > 0);
+
+    // Um mapa de memória do OVMF tem dezenas de entradas, não milhares
+    let entries = dec("memory_map_len");
+    assert!((1..4096).contains(&entries), "memory_map_len = {}", entries);
+}
+
+/// Testa que um ESP sem `ignite.cfg` cai no modo Recovery
+#[test]
+fn test_missing_config_enters_recovery() {
+    let Some(env) = harness::setup("missing_config") else {
+        return;
+    };
+
+    let serial = env.boot(&EspImage::new(), &["Ativando modo Recovery"]);
+
+    serial.assert_contains("[OK] Heap inicializada");
+    serial.assert_contains("Nenhum arquivo de configuração encontrado.");
+    serial.assert_contains("Ativando modo Recovery");
+}
@@ -0,0 +1,7 @@
+//! Liga o kernel em `REDSTONE_KERNEL_ENTRY` com `linker.ld`.
+
+fn main() {
+    let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    println!("cargo:rustc-link-arg=-T{}/linker.ld", dir);
+    println!("cargo:rerun-if-changed=linker.ld");
+}
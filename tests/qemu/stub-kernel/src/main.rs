@@ -0,0 +1,105 @@
+//! Kernel Stub para Testes de Handoff
+//!
+//! Recebe o `BootInfo` do Ignite em `rdi` (protocolo Redstone), escreve na
+//! COM1 uma linha com os campos que o teste confere e desliga o QEMU pelo
+//! `isa-debug-exit`. Usa a própria `src/core/handoff.rs` do Ignite, então o
+//! layout lido aqui é sempre o que o bootloader grava.
+//!
+//! A linha tem o formato:
+//!
+//! ```text
+//! STUB-KERNEL stub_magic=0x... magic=0x... version=N fb_addr=0x... ...
+//! ```
+
+#![no_std]
+#![no_main]
+
+use core::fmt::{self, Write};
+
+#[path = "../../../../src/core/handoff.rs"]
+#[allow(dead_code)]
+mod handoff;
+
+use handoff::BootInfo;
+
+/// Marca o início da linha de resultado na serial.
+const REPORT_PREFIX: &str = "STUB-KERNEL";
+
+/// Prova de que o código do stub rodou ("STUBKERN" em ASCII).
+const STUB_MAGIC: u64 = 0x5354_5542_4B45_524E;
+
+/// COM1, já inicializada pelo Ignite.
+const COM1: u16 = 0x3F8;
+
+/// Line Status Register: transmissor pronto (bit 5).
+const LSR_THR_EMPTY: u8 = 1 << 5;
+
+/// Porta do `isa-debug-exit` configurado pelo harness.
+const DEBUG_EXIT_PORT: u16 = 0xF4;
+
+unsafe fn outb(port: u16, value: u8) {
+    core::arch::asm!("out dx, al", in("dx") port, in("al") value, options(nomem, nostack));
+}
+
+unsafe fn inb(port: u16) -> u8 {
+    let value: u8;
+    core::arch::asm!("in al, dx", in("dx") port, out("al") value, options(nomem, nostack));
+    value
+}
+
+/// Escrita na COM1 por polling.
+struct Serial;
+
+impl Write for Serial {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for byte in s.bytes() {
+            unsafe {
+                while inb(COM1 + 5) & LSR_THR_EMPTY == 0 {
+                    core::hint::spin_loop();
+                }
+                outb(COM1, byte);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Ponto de entrada, em `REDSTONE_KERNEL_ENTRY` (ver `linker.ld`).
+///
+/// O harness usa o `map_mode` padrão, com identity map: o endereço físico
+/// recebido em `rdi` pode ser lido direto.
+#[no_mangle]
+#[link_section = ".text.entry"]
+extern "C" fn _start(boot_info: *const BootInfo) -> ! {
+    let info = unsafe { &*boot_info };
+
+    let _ = writeln!(
+        Serial,
+        "{} stub_magic={:#x} magic={:#x} version={} fb_addr={:#x} fb_width={} fb_height={} \
+         memory_map_len={}",
+        REPORT_PREFIX,
+        STUB_MAGIC,
+        info.magic,
+        info.version,
+        info.framebuffer.addr,
+        info.framebuffer.width,
+        info.framebuffer.height,
+        info.memory_map_len,
+    );
+
+    exit_qemu()
+}
+
+/// Encerra o QEMU (código de saída `(0 << 1) | 1`).
+fn exit_qemu() -> ! {
+    unsafe { outb(DEBUG_EXIT_PORT, 0) };
+    loop {
+        unsafe { core::arch::asm!("cli", "hlt") };
+    }
+}
+
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    let _ = writeln!(Serial, "{} panic", REPORT_PREFIX);
+    exit_qemu()
+}
@@ -0,0 +1,164 @@
+//! Testes Unitários para o módulo de arquitetura
+//!
+//! Testa a decodificação dos bits de CPUID com um provider simulado.
+
+#![no_std]
+#![cfg(test)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use ignite::arch::x86::cpuid::{detect_features_with, CpuFeatures, CpuidProvider, CpuidResult};
+
+/// CPU simulada: responde folhas conhecidas e zera o resto.
+struct MockCpuid {
+    leaves: Vec<(u32, CpuidResult)>,
+}
+
+impl CpuidProvider for MockCpuid {
+    fn cpuid(&self, leaf: u32, _subleaf: u32) -> CpuidResult {
+        self.leaves
+            .iter()
+            .find(|(l, _)| *l == leaf)
+            .map(|(_, r)| *r)
+            .unwrap_or_default()
+    }
+}
+
+fn regs(eax: u32, ebx: u32, ecx: u32, edx: u32) -> CpuidResult {
+    CpuidResult { eax, ebx, ecx, edx }
+}
+
+/// Testa decodificação das folhas 1, 7 e 0x80000001
+#[test]
+fn test_cpuid_feature_bits() {
+    let cpu = MockCpuid {
+        leaves: alloc::vec![
+            (0x0000_0000, regs(0x0D, 0, 0, 0)),
+            // SSE (edx 25), SSE2 (edx 26), PCID (ecx 17), AVX (ecx 28), RDRAND (ecx 30)
+            (
+                0x0000_0001,
+                regs(
+                    0,
+                    0,
+                    (1 << 17) | (1 << 28) | (1 << 30),
+                    (1 << 25) | (1 << 26)
+                ),
+            ),
+            // AVX2 (ebx 5), SMEP (ebx 7)
+            (0x0000_0007, regs(0, (1 << 5) | (1 << 7), 0, 0)),
+            (0x8000_0000, regs(0x8000_0008, 0, 0, 0)),
+            // NX (edx 20), páginas de 1 GiB (edx 26)
+            (0x8000_0001, regs(0, 0, 0, (1 << 20) | (1 << 26))),
+        ],
+    };
+
+    let features = detect_features_with(&cpu);
+
+    assert_eq!(
+        features,
+        CpuFeatures::SSE
+            | CpuFeatures::SSE2
+            | CpuFeatures::PCID
+            | CpuFeatures::AVX
+            | CpuFeatures::RDRAND
+            | CpuFeatures::AVX2
+            | CpuFeatures::SMEP
+            | CpuFeatures::NX
+            | CpuFeatures::PAGE_1GB
+    );
+}
+
+/// Testa que folhas acima do máximo reportado são ignoradas
+#[test]
+fn test_cpuid_respects_max_leaf() {
+    let cpu = MockCpuid {
+        leaves: alloc::vec![
+            // Máximo básico = 1: folha 7 não deve ser consultada
+            (0x0000_0000, regs(0x01, 0, 0, 0)),
+            (0x0000_0001, regs(0, 0, 1 << 30, 0)),
+            (0x0000_0007, regs(0, 1 << 5, 0, 0)),
+            // Sem folhas estendidas
+            (0x8000_0000, regs(0x8000_0000, 0, 0, 0)),
+            (0x8000_0001, regs(0, 0, 0, 1 << 20)),
+        ],
+    };
+
+    let features = detect_features_with(&cpu);
+
+    assert!(features.contains(CpuFeatures::RDRAND));
+    assert!(!features.contains(CpuFeatures::AVX2));
+    assert!(!features.contains(CpuFeatures::NX));
+}
+
+/// Testa que os bits da ABI de handoff permanecem estáveis
+#[test]
+fn test_cpu_features_abi_bits() {
+    assert_eq!(CpuFeatures::SSE.bits(), 1 << 0);
+    assert_eq!(CpuFeatures::RDRAND.bits(), 1 << 9);
+    assert_eq!(CpuFeatures::PCID.bits(), 1 << 11);
+    assert_eq!(CpuFeatures::NX.bits(), 1 << 12);
+    assert_eq!(CpuFeatures::PAGE_1GB.bits(), 1 << 13);
+}
+
+/// Portas simuladas: registra cada escrita e devolve o último valor escrito
+/// na porta 0x61.
+struct MockPorts {
+    writes:  Vec<(u16, u8)>,
+    control: u8,
+}
+
+impl ignite::hardware::serial::PortIo for MockPorts {
+    unsafe fn read(&mut self, port: u16) -> u8 {
+        assert_eq!(port, 0x61);
+        self.control
+    }
+
+    unsafe fn write(&mut self, port: u16, value: u8) {
+        if port == 0x61 {
+            self.control = value;
+        }
+        self.writes.push((port, value));
+    }
+}
+
+/// Testa o bipe: divisor do PIT para 1000 Hz e speaker ligado e desligado na
+/// porta 0x61 sem mexer nos outros bits.
+#[test]
+fn test_speaker_beep_programs_pit_and_gates_speaker() {
+    use ignite::arch::x86::speaker::{beep_with, pit_divisor};
+
+    assert_eq!(pit_divisor(1000), 1193);
+    assert_eq!(pit_divisor(1), u16::MAX);
+    assert_eq!(pit_divisor(2_000_000), 1);
+
+    let mut ports = MockPorts {
+        writes:  Vec::new(),
+        control: 0b1010_0000,
+    };
+    let mut waited = 0;
+    beep_with(&mut ports, 1000, 50, &mut |us| waited += us);
+
+    let [low, high] = 1193u16.to_le_bytes();
+    assert_eq!(
+        ports.writes,
+        alloc::vec![
+            (0x43, 0xB6),
+            (0x42, low),
+            (0x42, high),
+            (0x61, 0b1010_0011),
+            (0x61, 0b1010_0000),
+        ]
+    );
+    assert_eq!(waited, 50_000);
+
+    // Frequência ou duração zero: nada é tocado
+    let mut silent = MockPorts {
+        writes:  Vec::new(),
+        control: 0,
+    };
+    beep_with(&mut silent, 0, 100, &mut |_| {});
+    beep_with(&mut silent, 1000, 0, &mut |_| {});
+    assert!(silent.writes.is_empty());
+}
@@ -300,3 +300,430 @@ fn test_case_insensitive() {
     assert!(eq_ignore_case("TeSt", "TeSt"));
     assert!(!eq_ignore_case("test", "other"));
 }
+
+/// Testa a chave global `video_test_pattern`
+#[test]
+fn test_parse_video_test_pattern() {
+    use ignite::config::parser::Parser;
+
+    let config = Parser::new().parse("video_test_pattern: yes\n").unwrap();
+    assert!(config.video_test_pattern);
+
+    let config = Parser::new().parse("timeout: 3\n").unwrap();
+    assert!(!config.video_test_pattern);
+}
+
+/// Testa parsing de resolução com e sem bpp
+#[test]
+fn test_parse_resolution_full() {
+    use ignite::config::parser::{parse_resolution_full, Parser};
+
+    assert_eq!(parse_resolution_full("1920x1080"), Some((1920, 1080, 32)));
+    assert_eq!(parse_resolution_full("1920x1080x24"), Some((1920, 1080, 24)));
+    assert_eq!(parse_resolution_full("800X600x16"), Some((800, 600, 16)));
+    assert_eq!(parse_resolution_full("1920"), None);
+    assert_eq!(parse_resolution_full("0x600"), None);
+    assert_eq!(parse_resolution_full("1920x1080x32x1"), None);
+
+    let config = Parser::new().parse("resolution: 1280x720\n").unwrap();
+    assert_eq!(config.resolution, Some((1280, 720, 32)));
+}
+
+/// NVRAM em memória para os testes do cache de configuração.
+#[derive(Default)]
+struct CacheStore {
+    data: Option<Vec<u8>>,
+}
+
+impl ignite::recovery::state::VariableStore for CacheStore {
+    fn read(&self, _name: &str, _guid: &ignite::uefi::base::Guid, buf: &mut [u8]) -> Option<usize> {
+        let data = self.data.as_ref()?;
+        buf.get_mut(..data.len())?.copy_from_slice(data);
+        Some(data.len())
+    }
+
+    fn write(
+        &mut self,
+        _name: &str,
+        _guid: &ignite::uefi::base::Guid,
+        _attributes: u32,
+        data: &[u8],
+    ) -> ignite::core::error::Result<()> {
+        self.data = Some(data.to_vec());
+        Ok(())
+    }
+}
+
+/// Testa o CRC32 IEEE com o vetor de verificação padrão
+#[test]
+fn test_crc32_check_value() {
+    use ignite::core::crc32::crc32;
+
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    assert_eq!(crc32(b""), 0);
+}
+
+/// Testa que um CRC igual usa o cache sem chamar o parser
+#[test]
+fn test_config_cache_hit_skips_parser() {
+    use ignite::config::{cache::load_cached, parser::Parser};
+
+    let content = "config_cache: yes\ntimeout: 3\nresolution: 1280x720\n\n/Redstone\n    protocol: redstone\n    path: boot():/forge\n    module_path: boot():/initfs\n    cmdline: quiet\n";
+    let mut store = CacheStore::default();
+
+    // Primeiro boot: parse real, cache gravado
+    let mut parsed = false;
+    let first = load_cached(&mut store, content, |c| {
+        parsed = true;
+        Parser::new().parse(c)
+    })
+    .unwrap();
+    assert!(parsed);
+    assert!(store.data.is_some());
+
+    // Segundo boot: mesmo arquivo, parser não é invocado
+    let mut parsed = false;
+    let cached = load_cached(&mut store, content, |c| {
+        parsed = true;
+        Parser::new().parse(c)
+    })
+    .unwrap();
+    assert!(!parsed);
+
+    assert_eq!(cached.timeout, first.timeout);
+    assert_eq!(cached.resolution, Some((1280, 720, 32)));
+    assert_eq!(cached.entries.len(), 1);
+    let entry = &cached.entries[0];
+    assert_eq!(entry.name, "Redstone");
+    assert_eq!(entry.protocol, ignite::config::Protocol::Redstone);
+    assert_eq!(entry.path, "boot():/forge");
+    assert_eq!(entry.cmdline.as_deref(), Some("quiet"));
+    assert_eq!(entry.modules[0].path, "boot():/initfs");
+}
+
+/// Testa que um arquivo alterado invalida o cache e é interpretado de novo
+#[test]
+fn test_config_cache_miss_reparses() {
+    use ignite::config::{cache::load_cached, parser::Parser};
+
+    let mut store = CacheStore::default();
+    let original = "config_cache: yes\ntimeout: 3\n/A\n    path: boot():/a\n";
+    load_cached(&mut store, original, |c| Parser::new().parse(c)).unwrap();
+
+    let changed = "config_cache: yes\ntimeout: 9\n/A\n    path: boot():/a\n";
+    let mut parsed = false;
+    let config = load_cached(&mut store, changed, |c| {
+        parsed = true;
+        Parser::new().parse(c)
+    })
+    .unwrap();
+
+    assert!(parsed);
+    assert_eq!(config.timeout, ignite::config::Timeout::Millis(9000));
+
+    // Sem `config_cache`, nada é gravado
+    let mut empty = CacheStore::default();
+    load_cached(&mut empty, "timeout: 1\n", |c| Parser::new().parse(c)).unwrap();
+    assert!(empty.data.is_none());
+}
+
+/// Testa a chave global `timeout_action`
+#[test]
+fn test_parse_timeout_action() {
+    use ignite::config::{parser::Parser, TimeoutAction};
+
+    let cases = [
+        ("boot", TimeoutAction::Boot),
+        ("halt", TimeoutAction::Halt),
+        ("Reboot", TimeoutAction::Reboot),
+    ];
+    for (value, expected) in cases {
+        let text = alloc::format!("timeout_action: {}\n", value);
+        assert_eq!(Parser::new().parse(&text).unwrap().timeout_action, expected);
+    }
+
+    // Ausente ou inválido mantém o padrão
+    let config = Parser::new().parse("timeout: 3\n").unwrap();
+    assert_eq!(config.timeout_action, TimeoutAction::Boot);
+    let config = Parser::new().parse("timeout_action: shutdown\n").unwrap();
+    assert_eq!(config.timeout_action, TimeoutAction::Boot);
+}
+
+/// Testa a chave global `console`
+#[test]
+fn test_parse_console() {
+    use ignite::config::{parser::Parser, ConsoleMode};
+
+    let config = Parser::new().parse("console: serial\n").unwrap();
+    assert_eq!(config.console, ConsoleMode::Serial);
+    assert!(config.console.serial());
+    assert!(!config.console.video());
+
+    let config = Parser::new().parse("console: Video\n").unwrap();
+    assert_eq!(config.console, ConsoleMode::Video);
+    assert!(!config.console.serial());
+    assert!(config.console.video());
+
+    // Ausente ou inválido mantém os dois destinos
+    for text in ["timeout: 3\n", "console: lcd\n", "console: both\n"] {
+        let config = Parser::new().parse(text).unwrap();
+        assert_eq!(config.console, ConsoleMode::Both);
+        assert!(config.console.serial() && config.console.video());
+    }
+}
+
+/// Testa que `module_cmdline` se aplica ao último `module_path`
+#[test]
+fn test_parse_module_cmdline() {
+    use ignite::config::parser::Parser;
+
+    let content = "/Redstone\n    path: boot():/forge\n    module_path: boot():/initfs\n    module_cmdline: initrd\n    module_path: boot():/ucode\n    module_path: boot():/drv\n    module_cmdline: driver\n";
+    let config = Parser::new().parse(content).unwrap();
+    let modules = &config.entries[0].modules;
+
+    assert_eq!(modules.len(), 3);
+    assert_eq!(modules[0].cmdline.as_deref(), Some("initrd"));
+    assert_eq!(modules[1].cmdline, None);
+    assert_eq!(modules[2].cmdline.as_deref(), Some("driver"));
+}
+
+/// Testa a remoção de comentários no fim das linhas de opção
+#[test]
+fn test_parse_trailing_comments() {
+    use ignite::config::parser::Parser;
+
+    let content = "background_color: #102030 # azul\n/Rapida\n    path: boot():/forge # kernel\n    cmdline: quiet # note\n/Aspas\n    path: boot():/forge\n    cmdline: \"quiet # keep\"\n/Sem espaco\n    path: boot():/forge\n    cmdline: init=/sbin/init#1\n";
+    let config = Parser::new().parse(content).unwrap();
+
+    assert_eq!(config.background_color, Some(0x102030));
+    assert_eq!(config.entries[0].path, "boot():/forge");
+    assert_eq!(config.entries[0].cmdline.as_deref(), Some("quiet"));
+    assert_eq!(config.entries[1].cmdline.as_deref(), Some("quiet # keep"));
+    // `#` colado ao texto faz parte do valor
+    assert_eq!(config.entries[2].cmdline.as_deref(), Some("init=/sbin/init#1"));
+}
+
+/// Testa os limites de aninhamento e de quantidade de entradas do parser
+#[test]
+fn test_parser_depth_and_entry_limits() {
+    use ignite::{
+        config::parser::Parser,
+        core::{
+            config::limits::MAX_ENTRY_DEPTH,
+            error::{BootError, ConfigError},
+        },
+    };
+
+    // Exatamente no limite padrão
+    let deepest = alloc::format!(
+        "{}Fundo\n    path: boot():/forge\n",
+        "/".repeat(MAX_ENTRY_DEPTH)
+    );
+    assert_eq!(Parser::new().parse(&deepest).unwrap().entries.len(), 1);
+
+    let too_deep = alloc::format!("/{}", deepest);
+    assert_eq!(
+        Parser::new().parse(&too_deep).err(),
+        Some(BootError::ConfigLine {
+            line:  1,
+            error: ConfigError::NestingTooDeep,
+        })
+    );
+
+    let nested =
+        "/Pai\n    path: boot():/a\n//Filho\n    path: boot():/b\n///Neto\n    path: boot():/c\n";
+    let parsed = Parser::with_limits(3, 8).parse(nested).unwrap();
+    assert_eq!(parsed.entries.len(), 3);
+    assert_eq!(
+        Parser::with_limits(2, 8).parse(nested).err(),
+        Some(BootError::ConfigLine {
+            line:  5,
+            error: ConfigError::NestingTooDeep,
+        })
+    );
+
+    // Três entradas passam com limite 3; a quarta é recusada
+    let four = "/A\n    path: boot():/a\n/B\n    path: boot():/b\n/C\n    path: boot():/c\n/D\n    path: boot():/d\n";
+    let three = &four[..four.find("/D").unwrap()];
+    let parsed = Parser::with_limits(8, 3).parse(three).unwrap();
+    assert_eq!(parsed.entries.len(), 3);
+    assert_eq!(
+        Parser::with_limits(8, 3).parse(four).err(),
+        Some(BootError::ConfigLine {
+            line:  7,
+            error: ConfigError::TooManyEntries,
+        })
+    );
+}
+
+/// Testa o dump da configuração interpretada
+#[test]
+fn test_config_dump() {
+    use alloc::format;
+    use ignite::config::parser::Parser;
+
+    let content = "timeout: 3\ndefault_entry: 2\ndump_config: yes\n/Redstone\n    protocol: redstone\n    path: boot():/forge\n    module_path: boot():/initfs\n/Linux\n    protocol: linux\n    path: boot():/vmlinuz\n    cmdline: quiet\n";
+    let config = Parser::new().parse(content).unwrap();
+    assert!(config.dump_config);
+
+    let mut lines = Vec::new();
+    config.dump_with(|line| lines.push(format!("{}", line)));
+
+    let has = |expected: &str| lines.iter().any(|line| line == expected);
+    assert!(has("timeout: 3"));
+    assert!(has("default_entry: 1 (Linux)"));
+    assert!(has("entries: 2"));
+    assert!(has("/Redstone"));
+    assert!(has("  module_path: boot():/initfs [-]"));
+    assert!(has("/Linux"));
+    assert!(has("  cmdline: quiet"));
+
+    // Determinístico: o mesmo dump produz as mesmas linhas
+    let mut again = Vec::new();
+    config.dump_with(|line| again.push(format!("{}", line)));
+    assert_eq!(lines, again);
+}
+
+/// Sistema de arquivos em memória com um único `ignite.cfg`.
+struct ConfigFs {
+    name:    &'static str,
+    content: &'static str,
+}
+
+struct ConfigDir(&'static str);
+
+struct ConfigFile {
+    data: &'static [u8],
+    pos:  usize,
+}
+
+impl ignite::fs::File for ConfigFile {
+    fn read(&mut self, buf: &mut [u8]) -> ignite::core::error::Result<usize> {
+        let n = buf.len().min(self.data.len() - self.pos);
+        buf[..n].copy_from_slice(&self.data[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> ignite::core::error::Result<usize> {
+        Ok(0)
+    }
+
+    fn seek(&mut self, offset: u64) -> ignite::core::error::Result<u64> {
+        self.pos = offset as usize;
+        Ok(offset)
+    }
+
+    fn metadata(&self) -> ignite::core::error::Result<ignite::fs::Metadata> {
+        Ok(ignite::fs::Metadata {
+            size:        self.data.len() as u64,
+            is_dir:      false,
+            is_readonly: true,
+        })
+    }
+}
+
+impl ignite::fs::Directory for ConfigDir {
+    fn open_file(
+        &mut self,
+        path: &str,
+    ) -> ignite::core::error::Result<alloc::boxed::Box<dyn ignite::fs::File>> {
+        if path == "boot/ignite.cfg" {
+            Ok(alloc::boxed::Box::new(ConfigFile {
+                data: self.0.as_bytes(),
+                pos:  0,
+            }))
+        } else {
+            Err(ignite::core::error::BootError::FileSystem(
+                ignite::core::error::FileSystemError::FileNotFound,
+            ))
+        }
+    }
+
+    fn open_dir(
+        &mut self,
+        _path: &str,
+    ) -> ignite::core::error::Result<alloc::boxed::Box<dyn ignite::fs::Directory>> {
+        Err(ignite::core::error::BootError::FileSystem(
+            ignite::core::error::FileSystemError::FileNotFound,
+        ))
+    }
+
+    fn list(&mut self) -> ignite::core::error::Result<Vec<String>> {
+        Ok(alloc::vec![String::from("boot/ignite.cfg")])
+    }
+}
+
+impl ignite::fs::FileSystem for ConfigFs {
+    fn root(&mut self) -> ignite::core::error::Result<alloc::boxed::Box<dyn ignite::fs::Directory>> {
+        Ok(alloc::boxed::Box::new(ConfigDir(self.content)))
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+}
+
+/// Testa que `config_device: root()` leva o carregador ao pool RedstoneFS, e
+/// não ao ESP
+#[test]
+fn test_root_config_path_reads_redstonefs() {
+    use ignite::{
+        config::{
+            loader::read_configuration, parser::Parser, path::resolve_config_device, ConfigPath,
+            FsTarget,
+        },
+        core::error::{BootError, ConfigError},
+    };
+
+    let mut esp = ConfigFs {
+        name:    "ESP",
+        content: "config_device: root()\n",
+    };
+    let mut rfs = ConfigFs {
+        name:    "RFS",
+        content: "timeout: 9\n/Redstone\n    path: root():/boot/forge\n",
+    };
+
+    let redirect = Parser::new().parse(esp.content).unwrap();
+    let device = redirect.config_device.unwrap();
+    let path = ConfigPath::parse(&alloc::format!("{}:/", device)).unwrap();
+
+    let mut target = resolve_config_device(&device, &mut esp, Some(&mut rfs)).unwrap();
+    assert!(matches!(target, FsTarget::RedstoneFs(_)));
+    let (filename, content) = read_configuration(&mut target).unwrap().unwrap();
+    assert_eq!(filename, "boot/ignite.cfg");
+    assert_eq!(target.fs().name(), "RFS");
+    assert_eq!(
+        Parser::new().parse(&content).unwrap().timeout,
+        ignite::config::Timeout::Millis(9000)
+    );
+
+    // Sem pool montado, `root()` é recusado com o motivo em vez de cair
+    // silenciosamente no ESP
+    assert!(path.resolve(&mut esp, None).is_none());
+    assert_eq!(
+        resolve_config_device(&device, &mut esp, None).err(),
+        Some(BootError::Config(ConfigError::Invalid(
+            "config_device: dispositivo nao montado"
+        )))
+    );
+    assert_eq!(
+        resolve_config_device("disco()", &mut esp, None).err(),
+        Some(BootError::Config(ConfigError::Invalid(
+            "config_device: dispositivo desconhecido"
+        )))
+    );
+    assert_eq!(
+        Parser::new()
+            .parse("config_device: disco()\n")
+            .unwrap()
+            .config_device,
+        None
+    );
+    let boot = ConfigPath::parse("boot():/").unwrap();
+    assert!(matches!(
+        boot.resolve(&mut esp, Some(&mut rfs)),
+        Some(FsTarget::Esp(_))
+    ));
+}
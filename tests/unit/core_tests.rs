@@ -0,0 +1,208 @@
+//! Testes Unitários para o núcleo (core)
+//!
+//! Testa as variantes estruturadas de `BootError` e suas mensagens e o buffer
+//! de logs de início.
+
+#![no_std]
+#![cfg(test)]
+
+extern crate alloc;
+
+use ignite::core::error::{
+    BootError, ConfigError, ElfError, ErrorPath, FileSystemError, MemoryError, ProtocolError,
+    Result, MAX_ERROR_PATH,
+};
+
+fn fail(error: BootError) -> Result<()> {
+    Err(error)
+}
+
+/// Testa que cada variante estruturada volta intacta de um `Result` e
+/// produz a mensagem esperada
+#[test]
+fn test_structured_errors_round_trip() {
+    let cases = [
+        (
+            BootError::ConfigLine {
+                line:  12,
+                error: ConfigError::InvalidKey,
+            },
+            "Config Error: InvalidKey (linha 12)",
+        ),
+        (
+            BootError::file("/boot/initrd", FileSystemError::FileNotFound),
+            "FS Error: FileNotFound (/boot/initrd)",
+        ),
+        (
+            BootError::ElfSegment {
+                index: 3,
+                error: ElfError::SegmentCopyError,
+            },
+            "ELF Error: SegmentCopyError (segmento 3)",
+        ),
+        (
+            BootError::MemoryRegion {
+                addr:  0x10_0000,
+                size:  0x2000,
+                error: MemoryError::AllocationFailed,
+            },
+            "Memory Error: AllocationFailed ([0x100000..0x102000))",
+        ),
+        (
+            ProtocolError::UnknownFormat.into(),
+            "Protocol Error: UnknownFormat",
+        ),
+        (BootError::Generic("stub"), "Generic Error: stub"),
+    ];
+
+    for (error, message) in cases {
+        assert_eq!(fail(error), Err(error));
+        assert_eq!(alloc::format!("{}", error), message);
+    }
+
+    // O Debug mostra o caminho como texto, não como bytes
+    assert_eq!(
+        alloc::format!("{:?}", BootError::file("/k", FileSystemError::ReadError)),
+        "File { path: \"/k\", kind: ReadError }"
+    );
+    assert!(BootError::MemoryRegion {
+        addr:  0,
+        size:  1,
+        error: MemoryError::OutOfMemory,
+    }
+    .is_memory());
+    assert!(!BootError::Protocol(ProtocolError::NotImplemented).is_memory());
+}
+
+/// Testa o corte de caminhos longos sem partir caracteres UTF-8
+#[test]
+fn test_error_path_truncates_on_char_boundary() {
+    let long = "/".repeat(MAX_ERROR_PATH - 1) + "é";
+    let path = ErrorPath::new(&long);
+    assert_eq!(path.as_str().len(), MAX_ERROR_PATH - 1);
+    assert!(path.as_str().chars().all(|c| c == '/'));
+
+    assert_eq!(ErrorPath::new("/boot/kernel").as_str(), "/boot/kernel");
+}
+
+/// Testa que as linhas guardadas antes do sink de vídeo são reproduzidas em
+/// ordem, com as mais antigas descartadas quando o anel enche
+#[test]
+fn test_early_log_buffer_replays_in_order() {
+    use alloc::string::String;
+
+    use ignite::core::logging::{EarlyLogBuffer, LogWriter, EARLY_LOG_LINES, EARLY_LOG_LINE_LEN};
+
+    /// Sink que só acumula o texto recebido.
+    #[derive(Default)]
+    struct Recorder(String);
+
+    impl LogWriter for Recorder {
+        fn write_char(&mut self, c: char) {
+            self.0.push(c);
+        }
+
+        fn write_str(&mut self, s: &str) {
+            self.0.push_str(s);
+        }
+    }
+
+    let mut buffer = EarlyLogBuffer::new();
+    buffer.push("[INFO] heap pronta");
+    buffer.push_fmt(format_args!("[WARN] config: {}", 3));
+    assert_eq!(buffer.len(), 2);
+    assert!(buffer
+        .lines()
+        .eq(["[INFO] heap pronta", "[WARN] config: 3"]));
+
+    let mut sink = Recorder::default();
+    buffer.replay(&mut sink);
+    assert_eq!(sink.0, "[INFO] heap pronta\n[WARN] config: 3\n");
+    assert!(buffer.is_empty());
+
+    // Anel cheio: só as últimas EARLY_LOG_LINES sobrevivem
+    for i in 0..EARLY_LOG_LINES + 3 {
+        buffer.push_fmt(format_args!("linha {}", i));
+    }
+    assert_eq!(buffer.len(), EARLY_LOG_LINES);
+    assert_eq!(buffer.lines().next(), Some("linha 3"));
+    assert_eq!(
+        buffer.lines().last(),
+        Some(alloc::format!("linha {}", EARLY_LOG_LINES + 2).as_str())
+    );
+
+    // Linhas longas são truncadas sem partir caracteres
+    let mut buffer = EarlyLogBuffer::new();
+    let long: String = core::iter::once('a')
+        .chain(core::iter::repeat_n('é', EARLY_LOG_LINE_LEN))
+        .collect();
+    buffer.push(&long);
+    let line = buffer.lines().next().unwrap();
+    assert_eq!(line.len(), EARLY_LOG_LINE_LEN - 1);
+    assert!(line.ends_with('é'));
+}
+
+/// Testa os formatos de `log_format` com um relógio simulado
+#[test]
+fn test_log_format_prefixes() {
+    use alloc::string::String;
+
+    use ignite::{
+        config::parser::Parser,
+        core::logging::{write_record, LogFormat},
+    };
+    use log::Level;
+
+    fn line(format: LogFormat, level: Level, clock: Option<(u8, u8, u8)>) -> String {
+        let mut out = String::new();
+        let mut reads = 0;
+        write_record(
+            &mut out,
+            format,
+            level,
+            format_args!("kernel em {:#x}", 0x20_0000),
+            &mut || {
+                reads += 1;
+                clock
+            },
+        )
+        .unwrap();
+        // O relógio só é lido em `Timestamped`
+        assert_eq!(reads, (format == LogFormat::Timestamped) as usize);
+        out
+    }
+
+    assert_eq!(
+        line(LogFormat::Leveled, Level::Warn, None),
+        "[WARN] kernel em 0x200000\n"
+    );
+    assert_eq!(
+        line(LogFormat::Plain, Level::Warn, None),
+        "kernel em 0x200000\n"
+    );
+    assert_eq!(
+        line(LogFormat::Timestamped, Level::Info, Some((8, 5, 9))),
+        "08:05:09 [INFO] kernel em 0x200000\n"
+    );
+    // RTC indisponível: cai para o formato com nível
+    assert_eq!(
+        line(LogFormat::Timestamped, Level::Error, None),
+        "[ERROR] kernel em 0x200000\n"
+    );
+
+    // `log_format` explícito prevalece sobre `verbose`
+    let parse = |text: &str| Parser::new().parse(text).unwrap().effective_log_format();
+    assert_eq!(parse("/A\n    path: boot():/a\n"), LogFormat::Leveled);
+    assert_eq!(
+        parse("verbose: yes\n/A\n    path: boot():/a\n"),
+        LogFormat::Timestamped
+    );
+    assert_eq!(
+        parse("verbose: yes\nlog_format: plain\n/A\n    path: boot():/a\n"),
+        LogFormat::Plain
+    );
+    assert_eq!(
+        parse("log_format: xml\n/A\n    path: boot():/a\n"),
+        LogFormat::Leveled
+    );
+}
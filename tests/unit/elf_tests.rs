@@ -351,3 +351,327 @@ fn test_little_endian_conversion() {
         0x1234567890ABCDEF
     );
 }
+
+/// Monta um ELF64 x86_64 mínimo apenas com os `PT_LOAD` informados
+/// (`p_vaddr`, `p_paddr`, `p_memsz`).
+fn build_elf(segments: &[(u64, u64, u64)]) -> Vec<u8> {
+    let mut elf = Vec::new();
+
+    // e_ident: magic, ELFCLASS64, little endian, versão 1
+    elf.extend_from_slice(&[0x7F, b'E', b'L', b'F', 2, 1, 1, 0]);
+    elf.extend_from_slice(&[0u8; 8]);
+    elf.extend_from_slice(&2u16.to_le_bytes()); // e_type = ET_EXEC
+    elf.extend_from_slice(&0x3Eu16.to_le_bytes()); // e_machine = x86_64
+    elf.extend_from_slice(&1u32.to_le_bytes()); // e_version
+    elf.extend_from_slice(&segments.first().map_or(0, |s| s.0).to_le_bytes()); // e_entry
+    elf.extend_from_slice(&64u64.to_le_bytes()); // e_phoff
+    elf.extend_from_slice(&0u64.to_le_bytes()); // e_shoff
+    elf.extend_from_slice(&0u32.to_le_bytes()); // e_flags
+    elf.extend_from_slice(&64u16.to_le_bytes()); // e_ehsize
+    elf.extend_from_slice(&56u16.to_le_bytes()); // e_phentsize
+    elf.extend_from_slice(&(segments.len() as u16).to_le_bytes()); // e_phnum
+    elf.extend_from_slice(&64u16.to_le_bytes()); // e_shentsize
+    elf.extend_from_slice(&0u16.to_le_bytes()); // e_shnum
+    elf.extend_from_slice(&0u16.to_le_bytes()); // e_shstrndx
+
+    for &(vaddr, paddr, memsz) in segments {
+        elf.extend_from_slice(&1u32.to_le_bytes()); // p_type = PT_LOAD
+        elf.extend_from_slice(&5u32.to_le_bytes()); // p_flags = R+X
+        elf.extend_from_slice(&0u64.to_le_bytes()); // p_offset
+        elf.extend_from_slice(&vaddr.to_le_bytes());
+        elf.extend_from_slice(&paddr.to_le_bytes());
+        elf.extend_from_slice(&0u64.to_le_bytes()); // p_filesz
+        elf.extend_from_slice(&memsz.to_le_bytes());
+        elf.extend_from_slice(&0x1000u64.to_le_bytes()); // p_align
+    }
+
+    elf
+}
+
+/// Testa o passo prévio que compara os segmentos com a memória mapeada
+#[test]
+fn test_load_bounds_against_mapped_limit() {
+    use ignite::{
+        core::error::{BootError, ElfError},
+        elf::LoadBounds,
+    };
+
+    const MAPPED_LIMIT: u64 = 0x4000_0000; // 1 GiB de identity map
+
+    // Kernel higher-half com p_paddr baixo: cabe no mapa
+    let kernel = build_elf(&[
+        (0xFFFF_FFFF_8000_0000, 0x20_0000, 0x1000),
+        (0xFFFF_FFFF_8000_2000, 0x20_2000, 0x2000),
+    ]);
+    let bounds = LoadBounds::from_bytes(&kernel).unwrap();
+    assert_eq!(bounds.phys_end, 0x20_4000);
+    assert_eq!(bounds.lower_virt_end, 0);
+    assert_eq!(bounds.virt_end, 0xFFFF_FFFF_8000_4000);
+    assert!(bounds.check_mapped_limit(MAPPED_LIMIT).is_ok());
+
+    // Segmento identity-mapped além do limite é rejeitado
+    let beyond = build_elf(&[
+        (0x20_0000, 0x20_0000, 0x1000),
+        (0x8000_0000, 0x8000_0000, 0x1000),
+    ]);
+    let bounds = LoadBounds::from_bytes(&beyond).unwrap();
+    assert_eq!(
+        bounds.check_mapped_limit(MAPPED_LIMIT),
+        Err(BootError::Elf(ElfError::SegmentOutsideMappedMemory))
+    );
+
+    // p_vaddr + p_memsz com overflow é formato inválido
+    let overflow = build_elf(&[(u64::MAX - 0xFFF, 0, 0x2000)]);
+    assert_eq!(
+        LoadBounds::from_bytes(&overflow),
+        Err(BootError::Elf(ElfError::InvalidFormat))
+    );
+}
+
+/// Testa a rejeição de segmentos `PT_LOAD` com endereços virtuais em comum
+#[test]
+fn test_segment_overlap_check() {
+    use goblin::elf::Elf;
+    use ignite::{
+        core::error::{BootError, ElfError},
+        elf::check_segment_overlap,
+    };
+
+    // Segmentos adjacentes (fim exclusivo encosta no início do próximo)
+    let disjoint = build_elf(&[
+        (0xFFFF_FFFF_8000_0000, 0x20_0000, 0x2000),
+        (0xFFFF_FFFF_8000_2000, 0x20_2000, 0x1000),
+    ]);
+    assert!(check_segment_overlap(&Elf::parse(&disjoint).unwrap()).is_ok());
+
+    // O segundo começa antes do fim do primeiro
+    let overlapping = build_elf(&[
+        (0xFFFF_FFFF_8000_0000, 0x20_0000, 0x2000),
+        (0xFFFF_FFFF_8000_1800, 0x20_1800, 0x1000),
+    ]);
+    assert_eq!(
+        check_segment_overlap(&Elf::parse(&overlapping).unwrap()),
+        Err(BootError::Elf(ElfError::OverlappingSegments))
+    );
+
+    // Segmentos vazios não ocupam endereço algum
+    let empty = build_elf(&[
+        (0xFFFF_FFFF_8000_0000, 0x20_0000, 0x2000),
+        (0xFFFF_FFFF_8000_1000, 0x20_1000, 0),
+    ]);
+    assert!(check_segment_overlap(&Elf::parse(&empty).unwrap()).is_ok());
+}
+
+/// Testa que `load_addr` reserva exatamente o endereço pedido, e só em
+/// memória utilizável
+#[test]
+fn test_load_addr_reserves_exact_base() {
+    use ignite::{
+        config::parser::Parser,
+        core::error::{BootError, MemoryError, Result},
+        elf::FixedPlacement,
+        memory::{
+            region::{MemoryRegionKind, PhysicalMemoryRegion},
+            FrameAllocator,
+        },
+    };
+
+    /// Alocador que só registra os pedidos de endereço fixo.
+    #[derive(Default)]
+    struct RecordingAllocator {
+        fixed: Vec<(u64, usize)>,
+    }
+
+    impl FrameAllocator for RecordingAllocator {
+        fn allocate_frame(&mut self, _count: usize) -> Result<u64> {
+            panic!("load_addr não deveria usar allocate_frame");
+        }
+
+        fn allocate_at(&mut self, addr: u64, count: usize) -> Result<u64> {
+            self.fixed.push((addr, count));
+            Ok(addr)
+        }
+    }
+
+    let config = Parser::new()
+        .parse("/Fixo\n    path: boot():/forge\n    load_addr: 0x200000\n")
+        .unwrap();
+    let base = config.entries[0]
+        .load_addr
+        .expect("load_addr deveria ser lido");
+    assert_eq!(base, 0x20_0000);
+
+    // Dois segmentos com um buraco de uma página entre eles
+    let kernel = build_elf(&[
+        (0xFFFF_FFFF_8000_0000, 0x20_0000, 0x1000),
+        (0xFFFF_FFFF_8000_2000, 0x20_2000, 0x1800),
+    ]);
+
+    let usable = [PhysicalMemoryRegion {
+        start:      0x10_0000,
+        page_count: 0x300,
+        kind:       MemoryRegionKind::Usable,
+    }];
+    let mut allocator = RecordingAllocator::default();
+    let placement = FixedPlacement::reserve_bytes(&kernel, base, &usable, &mut allocator).unwrap();
+    assert_eq!(allocator.fixed, [(0x20_0000, 4)]);
+    assert_eq!(placement.phys_base, 0x20_0000);
+    assert_eq!(placement.virt_base, 0xFFFF_FFFF_8000_0000);
+    assert!(!placement.relocated);
+
+    // Firmware reservou a página logo após a base: nada é alocado
+    let reserved = [
+        PhysicalMemoryRegion {
+            start:      0x10_0000,
+            page_count: 0x101,
+            kind:       MemoryRegionKind::Usable,
+        },
+        PhysicalMemoryRegion {
+            start:      0x20_1000,
+            page_count: 0x10,
+            kind:       MemoryRegionKind::Reserved,
+        },
+    ];
+    let mut allocator = RecordingAllocator::default();
+    assert_eq!(
+        FixedPlacement::reserve_bytes(&kernel, base, &reserved, &mut allocator),
+        Err(BootError::Memory(MemoryError::RegionOverlap))
+    );
+    assert!(allocator.fixed.is_empty());
+}
+
+/// Acrescenta a `elf` (saída de `build_elf`) um `PT_NOTE` com a nota de
+/// kernel relocável.
+fn add_relocatable_note(elf: &mut Vec<u8>) {
+    let phnum = u16::from_le_bytes([elf[56], elf[57]]);
+    elf[56..58].copy_from_slice(&(phnum + 1).to_le_bytes());
+
+    // A nota fica logo depois do novo program header
+    let note_offset = (elf.len() + 56) as u64;
+    let mut note = Vec::new();
+    note.extend_from_slice(&9u32.to_le_bytes()); // n_namesz ("Redstone\0")
+    note.extend_from_slice(&0u32.to_le_bytes()); // n_descsz
+    note.extend_from_slice(&1u32.to_le_bytes()); // n_type = NT_REDSTONE_RELOCATABLE
+    note.extend_from_slice(b"Redstone\0\0\0\0");
+
+    elf.extend_from_slice(&4u32.to_le_bytes()); // p_type = PT_NOTE
+    elf.extend_from_slice(&4u32.to_le_bytes()); // p_flags = R
+    elf.extend_from_slice(&note_offset.to_le_bytes()); // p_offset
+    elf.extend_from_slice(&0u64.to_le_bytes()); // p_vaddr
+    elf.extend_from_slice(&0u64.to_le_bytes()); // p_paddr
+    elf.extend_from_slice(&(note.len() as u64).to_le_bytes()); // p_filesz
+    elf.extend_from_slice(&(note.len() as u64).to_le_bytes()); // p_memsz
+    elf.extend_from_slice(&4u64.to_le_bytes()); // p_align
+    elf.extend_from_slice(&note);
+}
+
+/// Testa que um kernel relocável cuja base pedida está ocupada vai para
+/// outra base escolhida pelo alocador, e que sem a nota a falha continua
+#[test]
+fn test_load_addr_falls_back_for_relocatable_kernel() {
+    use goblin::elf::Elf;
+    use ignite::{
+        core::error::{BootError, MemoryError, Result},
+        elf::{is_relocatable, FixedPlacement},
+        memory::{
+            region::{MemoryRegionKind, PhysicalMemoryRegion},
+            FrameAllocator,
+        },
+    };
+
+    const ALTERNATE: u64 = 0x80_0000;
+
+    /// Alocador sem nada livre na base fixa; `allocate_frame` devolve
+    /// sempre `ALTERNATE`.
+    #[derive(Default)]
+    struct BusyAllocator {
+        any: Vec<usize>,
+    }
+
+    impl FrameAllocator for BusyAllocator {
+        fn allocate_frame(&mut self, count: usize) -> Result<u64> {
+            self.any.push(count);
+            Ok(ALTERNATE)
+        }
+
+        fn allocate_at(&mut self, _addr: u64, _count: usize) -> Result<u64> {
+            Err(BootError::Memory(MemoryError::AllocationFailed))
+        }
+    }
+
+    let segments = [
+        (0xFFFF_FFFF_8000_0000, 0x20_0000, 0x1000),
+        (0xFFFF_FFFF_8000_2000, 0x20_2000, 0x1800),
+    ];
+    let plain = build_elf(&segments);
+    let mut relocatable = build_elf(&segments);
+    add_relocatable_note(&mut relocatable);
+
+    assert!(!is_relocatable(&Elf::parse(&plain).unwrap(), &plain));
+    assert!(is_relocatable(
+        &Elf::parse(&relocatable).unwrap(),
+        &relocatable
+    ));
+
+    // A base pedida cai em memória reservada pelo firmware
+    let regions = [
+        PhysicalMemoryRegion {
+            start:      0x10_0000,
+            page_count: 0x101,
+            kind:       MemoryRegionKind::Usable,
+        },
+        PhysicalMemoryRegion {
+            start:      0x20_1000,
+            page_count: 0x10,
+            kind:       MemoryRegionKind::Reserved,
+        },
+    ];
+
+    let mut allocator = BusyAllocator::default();
+    assert_eq!(
+        FixedPlacement::reserve_bytes(&plain, 0x20_0000, &regions, &mut allocator),
+        Err(BootError::Memory(MemoryError::RegionOverlap))
+    );
+    assert!(allocator.any.is_empty());
+
+    let placement =
+        FixedPlacement::reserve_bytes(&relocatable, 0x20_0000, &regions, &mut allocator).unwrap();
+    assert!(placement.relocated);
+    assert_eq!(placement.phys_base, ALTERNATE);
+    assert_eq!(placement.virt_base, 0xFFFF_FFFF_8000_0000);
+    assert_eq!(placement.pages, 4);
+    assert_eq!(allocator.any, [4]);
+}
+
+/// Testa que o protocolo Redstone recusa kernels cujos segmentos não cobrem
+/// o endereço fixo do salto
+#[test]
+fn test_redstone_fixed_entry_must_be_covered() {
+    use ignite::{
+        core::error::{BootError, ElfError},
+        protos::redstone::{check_fixed_entry, REDSTONE_KERNEL_ENTRY},
+    };
+
+    let forge = build_elf(&[(REDSTONE_KERNEL_ENTRY, 0x20_0000, 0x2000)]);
+    assert!(check_fixed_entry(&forge).is_ok());
+
+    // Entrada no meio do segundo segmento
+    let split = build_elf(&[
+        (REDSTONE_KERNEL_ENTRY - 0x1000, 0x20_0000, 0x1000),
+        (REDSTONE_KERNEL_ENTRY, 0x20_1000, 0x1000),
+    ]);
+    assert!(check_fixed_entry(&split).is_ok());
+
+    // Linkado em outro endereço: o salto cairia em memória não mapeada
+    for segments in [
+        &[(0xFFFF_FFFF_C000_0000, 0x20_0000, 0x2000)][..],
+        &[(0x20_0000, 0x20_0000, 0x2000)][..],
+        // Termina exatamente onde a entrada começa
+        &[(REDSTONE_KERNEL_ENTRY - 0x1000, 0x20_0000, 0x1000)][..],
+    ] {
+        assert_eq!(
+            check_fixed_entry(&build_elf(segments)),
+            Err(BootError::Elf(ElfError::InvalidEntryPoint))
+        );
+    }
+}
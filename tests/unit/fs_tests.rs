@@ -352,3 +352,1492 @@ fn test_block_cache() {
     cache.put(2, alloc::vec![7, 8, 9]);
     assert_eq!(cache.cache.len(), 2); // Evicted one
 }
+
+/// Recalcula os CRC32 do array de entradas (LBA 2, quatro de 128 bytes) e
+/// do cabeçalho GPT em `gpt`.
+fn seal_gpt(data: &mut [u8], gpt: usize, entries: usize) {
+    use ignite::core::crc32::crc32;
+
+    let entries_crc = crc32(&data[entries..entries + 4 * 128]);
+    data[gpt + 88..gpt + 92].copy_from_slice(&entries_crc.to_le_bytes());
+    data[gpt + 16..gpt + 20].fill(0);
+    let header_crc = crc32(&data[gpt..gpt + 92]);
+    data[gpt + 16..gpt + 20].copy_from_slice(&header_crc.to_le_bytes());
+}
+
+/// Monta o início de uma ISO híbrida com o ESP a partir de `esp_lba`.
+fn build_hybrid_iso(esp_lba: u64) -> Vec<u8> {
+    let mut image = alloc::vec![0u8; 36 * 1024];
+
+    // Primary Volume Descriptor ISO9660 (setor 16 de 2048 bytes)
+    image[16 * 2048] = 1;
+    image[16 * 2048 + 1..16 * 2048 + 6].copy_from_slice(b"CD001");
+
+    // Cabeçalho GPT na LBA 1
+    let gpt = 512;
+    image[gpt..gpt + 8].copy_from_slice(b"EFI PART");
+    image[gpt + 12..gpt + 16].copy_from_slice(&92u32.to_le_bytes());
+    image[gpt + 24..gpt + 32].copy_from_slice(&1u64.to_le_bytes());
+    image[gpt + 72..gpt + 80].copy_from_slice(&2u64.to_le_bytes());
+    image[gpt + 80..gpt + 84].copy_from_slice(&4u32.to_le_bytes());
+    image[gpt + 84..gpt + 88].copy_from_slice(&128u32.to_le_bytes());
+
+    // Entrada 0: partição de dados qualquer; entrada 1: ESP
+    let entries = 2 * 512;
+    image[entries] = 0xAA;
+    image[entries + 32..entries + 40].copy_from_slice(&64u64.to_le_bytes());
+    image[entries + 40..entries + 48].copy_from_slice(&127u64.to_le_bytes());
+
+    let esp = entries + 128;
+    image[esp..esp + 16].copy_from_slice(&[
+        0x28, 0x73, 0x2a, 0xc1, 0x1f, 0xf8, 0xd2, 0x11, 0xba, 0x4b, 0x00, 0xa0, 0xc9, 0x3e, 0xc9,
+        0x3b,
+    ]);
+    image[esp + 32..esp + 40].copy_from_slice(&esp_lba.to_le_bytes());
+    image[esp + 40..esp + 48].copy_from_slice(&(esp_lba + 2047).to_le_bytes());
+
+    seal_gpt(&mut image, gpt, entries);
+    image
+}
+
+/// Testa a detecção do ESP em 2 MiB dentro de uma ISO híbrida
+#[test]
+fn test_iso_esp_offset_detection() {
+    use ignite::fs::iso::find_esp_offset;
+
+    let image = build_hybrid_iso(4096);
+    assert_eq!(find_esp_offset(&image).unwrap(), 2 * 1024 * 1024);
+}
+
+/// Testa a rejeição de arquivos que não são imagens ISO/GPT
+#[test]
+fn test_iso_rejects_non_gpt_image() {
+    use ignite::{
+        core::error::{BootError, FileSystemError},
+        fs::iso::find_esp_offset,
+    };
+
+    // ELF qualquer, sem cabeçalho GPT
+    let mut not_iso = alloc::vec![0u8; 4096];
+    not_iso[..4].copy_from_slice(b"\x7fELF");
+    assert!(matches!(
+        find_esp_offset(&not_iso),
+        Err(BootError::FileSystem(FileSystemError::InvalidSignature))
+    ));
+
+    // Buffer truncado antes do cabeçalho GPT
+    assert!(find_esp_offset(&[0u8; 100]).is_err());
+
+    // GPT válida, mas sem ESP
+    let mut no_esp = build_hybrid_iso(4096);
+    no_esp[2 * 512 + 128] = 0;
+    seal_gpt(&mut no_esp, 512, 2 * 512);
+    assert_eq!(
+        find_esp_offset(&no_esp),
+        Err(BootError::FileSystem(FileSystemError::UnsupportedFsType))
+    );
+
+    // Cabeçalho ou entradas alterados sem recalcular o CRC, como num disco
+    let mut bad_header = build_hybrid_iso(4096);
+    bad_header[512 + 80] = 8;
+    assert_eq!(
+        find_esp_offset(&bad_header),
+        Err(BootError::FileSystem(FileSystemError::ChecksumMismatch))
+    );
+    let mut bad_entries = build_hybrid_iso(4096);
+    bad_entries[2 * 512 + 128 + 32] = 1;
+    assert_eq!(
+        find_esp_offset(&bad_entries),
+        Err(BootError::FileSystem(FileSystemError::ChecksumMismatch))
+    );
+}
+
+/// Disco em memória com tamanho de setor configurável (512 ou 4Kn).
+struct SectorDisk {
+    data:  Vec<u8>,
+    block: usize,
+}
+
+impl ignite::fs::dev::BlockDevice for SectorDisk {
+    fn read_blocks(&mut self, lba: u64, buf: &mut [u8]) -> ignite::core::error::Result<()> {
+        let start = lba as usize * self.block;
+        buf.copy_from_slice(&self.data[start..start + buf.len()]);
+        Ok(())
+    }
+
+    fn write_blocks(&mut self, _lba: u64, _buf: &[u8]) -> ignite::core::error::Result<()> {
+        Ok(())
+    }
+
+    fn block_size(&self) -> u64 {
+        self.block as u64
+    }
+
+    fn num_blocks(&self) -> u64 {
+        (self.data.len() / self.block) as u64
+    }
+}
+
+/// Monta um disco GPT com MBR protetor e uma partição ESP em
+/// `[esp_first, esp_last]`, com os CRCs corretos.
+fn build_gpt_disk(block: usize, esp_first: u64, esp_last: u64) -> SectorDisk {
+    use ignite::fs::gpt::ESP_TYPE_GUID;
+
+    let mut data = alloc::vec![0u8; 8 * block];
+
+    // MBR protetor: uma entrada 0xEE e a assinatura 55 AA
+    data[446 + 4] = 0xEE;
+    data[510] = 0x55;
+    data[511] = 0xAA;
+
+    // Entradas na LBA 2: quatro de 128 bytes, só a primeira em uso
+    let entries = 2 * block;
+    data[entries..entries + 16].copy_from_slice(&ESP_TYPE_GUID);
+    data[entries + 16] = 0x42;
+    data[entries + 32..entries + 40].copy_from_slice(&esp_first.to_le_bytes());
+    data[entries + 40..entries + 48].copy_from_slice(&esp_last.to_le_bytes());
+
+    let gpt = block;
+    data[gpt..gpt + 8].copy_from_slice(b"EFI PART");
+    data[gpt + 8..gpt + 12].copy_from_slice(&0x0001_0000u32.to_le_bytes());
+    data[gpt + 12..gpt + 16].copy_from_slice(&92u32.to_le_bytes());
+    data[gpt + 24..gpt + 32].copy_from_slice(&1u64.to_le_bytes());
+    data[gpt + 32..gpt + 40].copy_from_slice(&7u64.to_le_bytes());
+    data[gpt + 72..gpt + 80].copy_from_slice(&2u64.to_le_bytes());
+    data[gpt + 80..gpt + 84].copy_from_slice(&4u32.to_le_bytes());
+    data[gpt + 84..gpt + 88].copy_from_slice(&128u32.to_le_bytes());
+    seal_gpt(&mut data, gpt, entries);
+
+    SectorDisk { data, block }
+}
+
+/// Testa a validação do cabeçalho GPT primário e a leitura dos LBAs reais,
+/// inclusive em discos 4Kn
+#[test]
+fn test_gpt_primary_header_crc() {
+    use ignite::{
+        core::error::{BootError, FileSystemError},
+        fs::{
+            dev::BlockDevice,
+            gpt::{find_partition, partitions, primary_header, PartitionDevice, ESP_TYPE_GUID},
+        },
+    };
+
+    for block in [512, 4096] {
+        let mut disk = build_gpt_disk(block, 3, 6);
+        let header = primary_header(&mut disk).unwrap();
+        assert_eq!(header.my_lba, 1);
+        assert_eq!(header.entries_lba, 2);
+
+        let parts = partitions(&mut disk, &header).unwrap();
+        assert_eq!(parts.len(), 1);
+        assert_eq!((parts[0].first_lba, parts[0].last_lba), (3, 6));
+
+        // A janela da partição começa no LBA declarado
+        let esp = find_partition(&mut disk, &ESP_TYPE_GUID).unwrap();
+        let mut device = PartitionDevice::new(disk, esp);
+        assert_eq!(device.num_blocks(), 4);
+        assert_eq!(device.block_size(), block as u64);
+        let mut buf = alloc::vec![0u8; block];
+        assert!(device.read_blocks(4, &mut buf).is_err());
+    }
+
+    // HeaderCRC32 corrompido
+    let mut disk = build_gpt_disk(512, 3, 6);
+    disk.data[512 + 16] ^= 0xFF;
+    assert_eq!(
+        primary_header(&mut disk),
+        Err(BootError::FileSystem(FileSystemError::ChecksumMismatch))
+    );
+
+    // Campo coberto pelo CRC alterado sem recalcular
+    let mut disk = build_gpt_disk(512, 3, 6);
+    disk.data[512 + 40] = 0x22;
+    assert_eq!(
+        primary_header(&mut disk),
+        Err(BootError::FileSystem(FileSystemError::ChecksumMismatch))
+    );
+
+    // Entrada alterada: o CRC do array não confere mais
+    let mut disk = build_gpt_disk(512, 3, 6);
+    disk.data[2 * 512 + 32] = 5;
+    let header = primary_header(&mut disk).unwrap();
+    assert_eq!(
+        partitions(&mut disk, &header),
+        Err(BootError::FileSystem(FileSystemError::ChecksumMismatch))
+    );
+
+    // Sem MBR protetor o disco não é tratado como GPT
+    let mut disk = build_gpt_disk(512, 3, 6);
+    disk.data[446 + 4] = 0x83;
+    assert_eq!(
+        primary_header(&mut disk),
+        Err(BootError::FileSystem(FileSystemError::UnsupportedFsType))
+    );
+
+    // Com CRC válido: entrada fora de 128 * 2^n ou grande demais, e tabela
+    // acima do limite, são recusadas antes de alocar o array
+    for (num_entries, entry_size) in [(4u32, 136u32), (4, 384), (4, 2048), (1024, 1024)] {
+        let mut disk = build_gpt_disk(512, 3, 6);
+        disk.data[512 + 80..512 + 84].copy_from_slice(&num_entries.to_le_bytes());
+        disk.data[512 + 84..512 + 88].copy_from_slice(&entry_size.to_le_bytes());
+        seal_gpt(&mut disk.data, 512, 2 * 512);
+        assert_eq!(
+            primary_header(&mut disk),
+            Err(BootError::FileSystem(FileSystemError::InvalidSize)),
+            "{} x {}",
+            num_entries,
+            entry_size
+        );
+    }
+}
+
+/// Arquivo em memória que conta as chamadas a `metadata()`.
+struct CountingFile {
+    len:            u64,
+    metadata_calls: alloc::rc::Rc<core::cell::Cell<usize>>,
+    fast_size:      bool,
+}
+
+impl ignite::fs::vfs::File for CountingFile {
+    fn read(&mut self, _buf: &mut [u8]) -> ignite::core::error::Result<usize> {
+        Ok(0)
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> ignite::core::error::Result<usize> {
+        Ok(0)
+    }
+
+    fn seek(&mut self, offset: u64) -> ignite::core::error::Result<u64> {
+        Ok(offset)
+    }
+
+    fn metadata(&self) -> ignite::core::error::Result<ignite::fs::vfs::Metadata> {
+        self.metadata_calls.set(self.metadata_calls.get() + 1);
+        Ok(ignite::fs::vfs::Metadata {
+            size:        self.len,
+            is_dir:      false,
+            is_readonly: true,
+        })
+    }
+
+    fn size(&mut self) -> ignite::core::error::Result<u64> {
+        if self.fast_size {
+            Ok(self.len)
+        } else {
+            Ok(self.metadata()?.size)
+        }
+    }
+}
+
+/// Testa que `size()` usa `metadata()` por padrão e o atalho quando existe
+#[test]
+fn test_file_size_without_metadata() {
+    use alloc::{boxed::Box, rc::Rc};
+    use core::cell::Cell;
+
+    use ignite::fs::{dev::BlockDevice, iso::FileBlockDevice, vfs::File};
+
+    /// Backend sem atalho: só implementa o obrigatório.
+    struct PlainFile(CountingFile);
+
+    impl File for PlainFile {
+        fn read(&mut self, buf: &mut [u8]) -> ignite::core::error::Result<usize> {
+            self.0.read(buf)
+        }
+
+        fn write(&mut self, buf: &[u8]) -> ignite::core::error::Result<usize> {
+            self.0.write(buf)
+        }
+
+        fn seek(&mut self, offset: u64) -> ignite::core::error::Result<u64> {
+            self.0.seek(offset)
+        }
+
+        fn metadata(&self) -> ignite::core::error::Result<ignite::fs::vfs::Metadata> {
+            self.0.metadata()
+        }
+    }
+
+    // Implementação padrão: delega para metadata()
+    let calls = Rc::new(Cell::new(0));
+    let mut plain = PlainFile(CountingFile {
+        len:            4096,
+        metadata_calls: calls.clone(),
+        fast_size:      false,
+    });
+    assert_eq!(plain.size().unwrap(), 4096);
+    assert_eq!(calls.get(), 1);
+
+    // Backend com atalho: o loopback de ISO obtém o tamanho sem metadata()
+    let calls = Rc::new(Cell::new(0));
+    let file = CountingFile {
+        len:            64 * 1024,
+        metadata_calls: calls.clone(),
+        fast_size:      true,
+    };
+    let dev = FileBlockDevice::new(Box::new(file), 32 * 1024).unwrap();
+    assert_eq!(dev.num_blocks(), 64);
+    assert_eq!(calls.get(), 0);
+}
+
+/// Firmware simulado: o dispositivo de boot nunca abre o SimpleFileSystem,
+/// um segundo handle abre normalmente.
+struct FlakyVolumes {
+    device:   ignite::uefi::Handle,
+    esp:      ignite::uefi::Handle,
+    opens:    Vec<ignite::uefi::Handle>,
+    delays:   usize,
+    protocol: *mut ignite::uefi::proto::media::fs::SimpleFileSystemProtocol,
+}
+
+impl ignite::fs::uefi::VolumeProvider for FlakyVolumes {
+    fn open_volume(
+        &mut self,
+        handle: ignite::uefi::Handle,
+    ) -> ignite::core::error::Result<*mut ignite::uefi::proto::media::fs::SimpleFileSystemProtocol>
+    {
+        use ignite::core::error::{BootError, FileSystemError};
+
+        self.opens.push(handle);
+        if handle == self.esp {
+            Ok(self.protocol)
+        } else {
+            Err(BootError::FileSystem(FileSystemError::VolumeOpenError))
+        }
+    }
+
+    fn volume_handles(&mut self) -> ignite::core::error::Result<Vec<ignite::uefi::Handle>> {
+        Ok(alloc::vec![self.device, self.esp])
+    }
+
+    fn device_path(&mut self, _handle: ignite::uefi::Handle) -> Option<alloc::string::String> {
+        None
+    }
+
+    fn delay(&mut self, _microseconds: usize) {
+        self.delays += 1;
+    }
+}
+
+/// Testa a retentativa no dispositivo de boot e o fallback para outro ESP
+#[test]
+fn test_open_boot_volume_falls_back_to_other_esp() {
+    use ignite::{
+        fs::uefi::{open_boot_volume, SFS_OPEN_ATTEMPTS},
+        uefi::Handle,
+    };
+
+    let mut firmware = FlakyVolumes {
+        device:   Handle(0x1000 as *mut _),
+        esp:      Handle(0x2000 as *mut _),
+        opens:    Vec::new(),
+        delays:   0,
+        protocol: 0x3000 as *mut _,
+    };
+    let device = firmware.device;
+
+    let (handle, protocol) = open_boot_volume(&mut firmware, device).unwrap();
+
+    assert!(handle == firmware.esp);
+    assert_eq!(protocol, firmware.protocol);
+
+    // Todas as tentativas no dispositivo de boot, que não é reaberto na busca
+    let device_opens = firmware.opens.iter().filter(|&&h| h == device).count();
+    assert_eq!(device_opens, SFS_OPEN_ATTEMPTS);
+    assert_eq!(firmware.opens.len(), SFS_OPEN_ATTEMPTS + 1);
+    assert_eq!(firmware.delays, SFS_OPEN_ATTEMPTS - 1);
+}
+
+/// Arquivo que devolve leituras curtas e 0 bytes nas chamadas em `zero_at`.
+struct StutteringFile {
+    data:    Vec<u8>,
+    pos:     usize,
+    calls:   usize,
+    zero_at: Vec<usize>,
+}
+
+impl ignite::fs::vfs::File for StutteringFile {
+    fn read(&mut self, buf: &mut [u8]) -> ignite::core::error::Result<usize> {
+        self.calls += 1;
+        if self.zero_at.contains(&self.calls) {
+            return Ok(0);
+        }
+        let n = buf.len().min(3).min(self.data.len() - self.pos);
+        buf[..n].copy_from_slice(&self.data[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> ignite::core::error::Result<usize> {
+        Ok(0)
+    }
+
+    fn seek(&mut self, offset: u64) -> ignite::core::error::Result<u64> {
+        Ok(offset)
+    }
+
+    fn metadata(&self) -> ignite::core::error::Result<ignite::fs::vfs::Metadata> {
+        Ok(ignite::fs::vfs::Metadata {
+            size:        self.data.len() as u64,
+            is_dir:      false,
+            is_readonly: true,
+        })
+    }
+}
+
+/// Testa que uma leitura vazia transitória não interrompe `read_exact`
+#[test]
+fn test_read_exact_retries_spurious_zero_read() {
+    use ignite::{
+        core::error::{BootError, FileSystemError},
+        fs::{read_exact_with, READ_ZERO_RETRIES},
+    };
+
+    let data: Vec<u8> = (0..10u8).collect();
+    let mut file = StutteringFile {
+        data:    data.clone(),
+        pos:     0,
+        calls:   0,
+        zero_at: alloc::vec![2],
+    };
+    let mut stalls = 0;
+    let mut buf = [0u8; 10];
+    read_exact_with(&mut file, &mut buf, &mut |_| stalls += 1).unwrap();
+    assert_eq!(&buf[..], &data[..]);
+    assert_eq!(stalls, 1);
+
+    // Arquivo menor que o buffer: só leituras vazias depois do fim
+    let mut file = StutteringFile {
+        data:    data.clone(),
+        pos:     0,
+        calls:   0,
+        zero_at: Vec::new(),
+    };
+    let mut stalls = 0;
+    let mut buf = [0u8; 12];
+    assert_eq!(
+        read_exact_with(&mut file, &mut buf, &mut |_| stalls += 1),
+        Err(BootError::FileSystem(FileSystemError::Truncated))
+    );
+    assert_eq!(stalls, READ_ZERO_RETRIES);
+}
+
+/// Disco em memória com setores de 512 bytes.
+struct MemDisk(Vec<u8>);
+
+impl ignite::fs::dev::BlockDevice for MemDisk {
+    fn read_blocks(&mut self, lba: u64, buf: &mut [u8]) -> ignite::core::error::Result<()> {
+        let start = lba as usize * 512;
+        buf.copy_from_slice(&self.0[start..start + buf.len()]);
+        Ok(())
+    }
+
+    fn write_blocks(&mut self, _lba: u64, _buf: &[u8]) -> ignite::core::error::Result<()> {
+        Ok(())
+    }
+
+    fn block_size(&self) -> u64 {
+        512
+    }
+
+    fn num_blocks(&self) -> u64 {
+        self.0.len() as u64 / 512
+    }
+}
+
+/// Imagem ext2 sintética com blocos de 1 KiB:
+///
+/// - bloco 1: superbloco; 2: descritores; 3-4: tabela de inodes
+/// - `/` (inode 2) → `boot/` (12) → `vmlinuz` (13, blocos 7-8), `big` (14, 12
+///   blocos diretos + 2 via indireto simples) e `ext4` (15, com extents)
+fn build_ext2_image() -> Vec<u8> {
+    const BS: usize = 1024;
+    let mut img = alloc::vec![0u8; 32 * BS];
+
+    fn put16(img: &mut [u8], off: usize, v: u16) {
+        img[off..off + 2].copy_from_slice(&v.to_le_bytes());
+    }
+    fn put32(img: &mut [u8], off: usize, v: u32) {
+        img[off..off + 4].copy_from_slice(&v.to_le_bytes());
+    }
+    fn inode(img: &mut [u8], n: usize, mode: u16, size: u32, flags: u32, blocks: &[u32]) {
+        let off = 3 * 1024 + (n - 1) * 128;
+        put16(img, off, mode);
+        put32(img, off + 4, size);
+        put32(img, off + 32, flags);
+        for (i, &b) in blocks.iter().enumerate() {
+            put32(img, off + 40 + i * 4, b);
+        }
+    }
+    fn dir(img: &mut [u8], block: usize, entries: &[(u32, &str)]) {
+        let mut off = block * 1024;
+        for (i, &(ino, name)) in entries.iter().enumerate() {
+            let rec_len = if i + 1 == entries.len() {
+                block * 1024 + 1024 - off
+            } else {
+                (8 + name.len()).next_multiple_of(4)
+            };
+            put32(img, off, ino);
+            put16(img, off + 4, rec_len as u16);
+            img[off + 6] = name.len() as u8;
+            img[off + 8..off + 8 + name.len()].copy_from_slice(name.as_bytes());
+            off += rec_len;
+        }
+    }
+
+    // Superbloco
+    let sb = BS;
+    put32(&mut img, sb + 4, 32); // s_blocks_count
+    put32(&mut img, sb + 20, 1); // s_first_data_block
+    put32(&mut img, sb + 24, 0); // s_log_block_size (1 KiB)
+    put32(&mut img, sb + 32, 8192); // s_blocks_per_group
+    put32(&mut img, sb + 40, 16); // s_inodes_per_group
+    put16(&mut img, sb + 56, 0xEF53);
+    put32(&mut img, sb + 76, 1); // s_rev_level
+    put16(&mut img, sb + 88, 128); // s_inode_size
+
+    // Descritor do grupo 0: tabela de inodes no bloco 3
+    put32(&mut img, 2 * BS + 8, 3);
+
+    inode(&mut img, 2, 0x41ED, 1024, 0, &[5]);
+    inode(&mut img, 12, 0x41ED, 1024, 0, &[6]);
+    inode(&mut img, 13, 0x81A4, 1500, 0, &[7, 8]);
+    let mut big: Vec<u32> = (9..=20).collect();
+    big.push(21);
+    inode(&mut img, 14, 0x81A4, 13 * 1024 + 10, 0, &big);
+    inode(&mut img, 15, 0x81A4, 10, 0x0008_0000, &[]);
+
+    dir(&mut img, 5, &[(2, "."), (2, ".."), (12, "boot")]);
+    dir(
+        &mut img,
+        6,
+        &[
+            (12, "."),
+            (2, ".."),
+            (13, "vmlinuz"),
+            (14, "big"),
+            (15, "ext4"),
+        ],
+    );
+
+    // Conteúdo: byte = (offset no arquivo) % 251
+    for (i, b) in img[7 * BS..7 * BS + 1500].iter_mut().enumerate() {
+        *b = (i % 251) as u8;
+    }
+    put32(&mut img, 21 * BS, 22);
+    put32(&mut img, 21 * BS + 4, 23);
+    for (logical, block) in (0..12).zip(9..=20).chain([(12, 22), (13, 23)]) {
+        img[block * BS..block * BS + BS].fill(logical as u8 + 1);
+    }
+
+    img
+}
+
+/// Testa a leitura de um arquivo em blocos diretos via caminho de dois níveis
+#[test]
+fn test_ext2_read_direct_blocks() {
+    use ignite::fs::{ext2::Ext2FileSystem, read_to_bytes, FileSystem};
+
+    let mut fs = Ext2FileSystem::mount(MemDisk(build_ext2_image())).unwrap();
+    assert_eq!(fs.name(), "EXT2");
+
+    let mut root = fs.root().unwrap();
+    let mut file = root.open_file("/boot/vmlinuz").unwrap();
+    assert_eq!(file.size().unwrap(), 1500);
+
+    let data = read_to_bytes(file.as_mut()).unwrap();
+    assert_eq!(data.len(), 1500);
+    assert!(data.iter().enumerate().all(|(i, &b)| b == (i % 251) as u8));
+
+    // Leitura a partir do meio, atravessando a fronteira entre blocos
+    file.seek(1020).unwrap();
+    let mut buf = [0u8; 8];
+    assert_eq!(file.read(&mut buf).unwrap(), 8);
+    assert_eq!(buf[0], (1020 % 251) as u8);
+    assert_eq!(buf[7], (1027 % 251) as u8);
+
+    let mut boot = root.open_dir("boot").unwrap();
+    assert_eq!(boot.list().unwrap(), ["vmlinuz", "big", "ext4"]);
+}
+
+/// Testa blocos indiretos, arquivos ausentes e inodes com extents
+#[test]
+fn test_ext2_indirect_blocks_and_errors() {
+    use ignite::{
+        core::error::{BootError, FileSystemError},
+        fs::{ext2::Ext2FileSystem, read_to_bytes, FileSystem},
+    };
+
+    let mut fs = Ext2FileSystem::mount(MemDisk(build_ext2_image())).unwrap();
+    let mut root = fs.root().unwrap();
+
+    let mut big = root.open_file("boot/big").unwrap();
+    let data = read_to_bytes(big.as_mut()).unwrap();
+    assert_eq!(data.len(), 13 * 1024 + 10);
+    assert_eq!(data[11 * 1024], 12);
+    assert_eq!(data[12 * 1024], 13); // primeiro bloco via indireto
+    assert_eq!(data[13 * 1024 + 9], 14);
+
+    assert_eq!(
+        root.open_file("boot/missing").err(),
+        Some(BootError::FileSystem(FileSystemError::FileNotFound))
+    );
+    assert_eq!(
+        root.open_file("boot").err(),
+        Some(BootError::FileSystem(FileSystemError::NotRegularFile))
+    );
+
+    let mut ext4 = root.open_file("boot/ext4").unwrap();
+    let mut buf = [0u8; 4];
+    assert_eq!(
+        ext4.read(&mut buf),
+        Err(BootError::FileSystem(FileSystemError::UnsupportedFsType))
+    );
+
+    // Diretório com `i_size` absurdo é recusado antes de alocar
+    let mut huge = build_ext2_image();
+    huge[3 * 1024 + 11 * 128 + 4..3 * 1024 + 11 * 128 + 8].copy_from_slice(&u32::MAX.to_le_bytes());
+    let mut fs = Ext2FileSystem::mount(MemDisk(huge)).unwrap();
+    assert_eq!(
+        fs.root().unwrap().open_file("boot/vmlinuz").err(),
+        Some(BootError::FileSystem(FileSystemError::InvalidSize))
+    );
+
+    // Superbloco sem magic
+    let mut bad = build_ext2_image();
+    bad[1024 + 56] = 0;
+    assert!(Ext2FileSystem::mount(MemDisk(bad)).is_err());
+}
+
+/// Imagem FAT12 sintética com setores e clusters de 512 bytes:
+///
+/// - setor 0: BPB; 1-2: FATs; 3: raiz fixa (16 entradas); dados a partir do 4
+/// - `/BOOT` (cluster 2) → `vmlinuz-long-name` (nome longo, clusters 3, 4 e 6),
+///   `initrd.img` (vazio, minúsculas via `DIR_NTRes`) e `BROKEN.BIN` (cadeia de
+///   um cluster para 2000 bytes)
+fn build_fat12_image() -> Vec<u8> {
+    const SS: usize = 512;
+    let mut img = alloc::vec![0u8; 64 * SS];
+
+    fn put16(img: &mut [u8], off: usize, v: u16) {
+        img[off..off + 2].copy_from_slice(&v.to_le_bytes());
+    }
+    fn put32(img: &mut [u8], off: usize, v: u32) {
+        img[off..off + 4].copy_from_slice(&v.to_le_bytes());
+    }
+    fn set_fat(img: &mut [u8], cluster: usize, value: u16) {
+        let off = SS + cluster * 3 / 2;
+        let pair = u16::from_le_bytes([img[off], img[off + 1]]);
+        let pair = if cluster & 1 == 1 {
+            (pair & 0x000F) | (value << 4)
+        } else {
+            (pair & 0xF000) | value
+        };
+        put16(img, off, pair);
+    }
+    fn short(
+        img: &mut [u8],
+        off: usize,
+        name: &[u8; 11],
+        attr: u8,
+        nt: u8,
+        cluster: u16,
+        size: u32,
+    ) {
+        img[off..off + 11].copy_from_slice(name);
+        img[off + 11] = attr;
+        img[off + 12] = nt;
+        put16(img, off + 26, cluster);
+        put32(img, off + 28, size);
+    }
+    fn lfn(img: &mut [u8], off: usize, order: u8, checksum: u8, part: &[u16]) {
+        const OFFSETS: [usize; 13] = [1, 3, 5, 7, 9, 14, 16, 18, 20, 22, 24, 28, 30];
+        img[off] = order;
+        img[off + 11] = 0x0F;
+        img[off + 13] = checksum;
+        for (i, &o) in OFFSETS.iter().enumerate() {
+            let c = match i.cmp(&part.len()) {
+                core::cmp::Ordering::Less => part[i],
+                core::cmp::Ordering::Equal => 0,
+                core::cmp::Ordering::Greater => 0xFFFF,
+            };
+            put16(img, off + o, c);
+        }
+    }
+
+    // BPB
+    img[..3].copy_from_slice(&[0xEB, 0x3C, 0x90]);
+    put16(&mut img, 11, 512); // BPB_BytsPerSec
+    img[13] = 1; // BPB_SecPerClus
+    put16(&mut img, 14, 1); // BPB_RsvdSecCnt
+    img[16] = 2; // BPB_NumFATs
+    put16(&mut img, 17, 16); // BPB_RootEntCnt
+    put16(&mut img, 19, 64); // BPB_TotSec16
+    img[21] = 0xF8;
+    put16(&mut img, 22, 1); // BPB_FATSz16
+    img[510] = 0x55;
+    img[511] = 0xAA;
+
+    for (cluster, value) in [
+        (0, 0xFF8),
+        (1, 0xFFF),
+        (2, 0xFFF),
+        (3, 4),
+        (4, 6),
+        (6, 0xFFF),
+        (8, 0xFFF),
+    ] {
+        set_fat(&mut img, cluster, value);
+    }
+
+    let cluster = |c: usize| (c + 2) * SS;
+    let root = 3 * SS;
+    short(&mut img, root, b"IGNITE     ", 0x08, 0, 0, 0);
+    short(&mut img, root + 32, b"BOOT       ", 0x10, 0, 2, 0);
+
+    let boot = cluster(2);
+    short(&mut img, boot, b".          ", 0x10, 0, 2, 0);
+    short(&mut img, boot + 32, b"..         ", 0x10, 0, 0, 0);
+    let checksum = b"VMLINU~1   "
+        .iter()
+        .fold(0u8, |sum, &b| sum.rotate_right(1).wrapping_add(b));
+    let name: Vec<u16> = "vmlinuz-long-name".encode_utf16().collect();
+    lfn(&mut img, boot + 64, 0x42, checksum, &name[13..]);
+    lfn(&mut img, boot + 96, 0x01, checksum, &name[..13]);
+    short(&mut img, boot + 128, b"VMLINU~1   ", 0x20, 0, 3, 1300);
+    short(&mut img, boot + 160, b"INITRD  IMG", 0x20, 0x18, 0, 0);
+    short(&mut img, boot + 192, b"BROKEN  BIN", 0x20, 0, 8, 2000);
+
+    // Conteúdo: byte = (offset no arquivo) % 251
+    for (i, c) in [3, 4, 6].into_iter().enumerate() {
+        for (j, b) in img[cluster(c)..cluster(c) + SS].iter_mut().enumerate() {
+            *b = ((i * SS + j) % 251) as u8;
+        }
+    }
+
+    img
+}
+
+/// Testa nomes longos, nomes curtos e clusters fora de sequência em FAT12
+#[test]
+fn test_fat_read_long_name_file() {
+    use ignite::fs::{
+        fat32::{Fat32FileSystem, FatType},
+        read_to_bytes, FileSystem,
+    };
+
+    let mut fs = Fat32FileSystem::mount(MemDisk(build_fat12_image())).unwrap();
+    assert_eq!(fs.fat_type(), FatType::Fat12);
+
+    let mut root = fs.root().unwrap();
+    assert_eq!(root.list().unwrap(), ["BOOT"]);
+
+    let mut kernel = root.open_file("boot/vmlinuz-long-name").unwrap();
+    assert_eq!(kernel.size().unwrap(), 1300);
+    let data = read_to_bytes(kernel.as_mut()).unwrap();
+    assert_eq!(data.len(), 1300);
+    assert!(data.iter().enumerate().all(|(i, &b)| b == (i % 251) as u8));
+
+    // Leitura atravessando os clusters 4 e 6, que não são contíguos
+    kernel.seek(1020).unwrap();
+    let mut buf = [0u8; 8];
+    assert_eq!(kernel.read(&mut buf).unwrap(), 8);
+    assert_eq!(buf[0], (1020 % 251) as u8);
+    assert_eq!(buf[7], (1027 % 251) as u8);
+
+    // Nome curto, maiúsculas e prefixo de dispositivo levam ao mesmo arquivo
+    for path in ["/BOOT/VMLINU~1", "boot():/Boot/VMLINUZ-LONG-NAME"] {
+        assert_eq!(root.open_file(path).unwrap().size().unwrap(), 1300);
+    }
+
+    let mut boot = root.open_dir("boot").unwrap();
+    assert_eq!(
+        boot.list().unwrap(),
+        ["vmlinuz-long-name", "initrd.img", "BROKEN.BIN"]
+    );
+    let mut initrd = boot.open_file("initrd.img").unwrap();
+    assert_eq!(initrd.read(&mut buf).unwrap(), 0);
+}
+
+/// Testa arquivos ausentes, cadeias curtas e nomes longos órfãos
+#[test]
+fn test_fat_errors() {
+    use ignite::{
+        core::error::{BootError, FileSystemError},
+        fs::{fat32::Fat32FileSystem, FileSystem},
+    };
+
+    let mut fs = Fat32FileSystem::mount(MemDisk(build_fat12_image())).unwrap();
+    let mut root = fs.root().unwrap();
+
+    assert_eq!(
+        root.open_file("boot/missing").err(),
+        Some(BootError::FileSystem(FileSystemError::FileNotFound))
+    );
+    assert_eq!(
+        root.open_file("boot").err(),
+        Some(BootError::FileSystem(FileSystemError::NotRegularFile))
+    );
+    assert_eq!(
+        root.open_dir("boot/initrd.img").err(),
+        Some(BootError::FileSystem(FileSystemError::InvalidPath))
+    );
+    assert_eq!(
+        root.open_file("boot/BROKEN.BIN").err(),
+        Some(BootError::FileSystem(FileSystemError::Truncated))
+    );
+
+    // Nome curto alterado: o checksum das entradas LFN deixa de conferir
+    let mut orphan = build_fat12_image();
+    orphan[4 * 512 + 128 + 7] = b'2';
+    let mut fs = Fat32FileSystem::mount(MemDisk(orphan)).unwrap();
+    let mut boot = fs.root().unwrap().open_dir("boot").unwrap();
+    assert_eq!(boot.list().unwrap()[0], "VMLINU~2");
+
+    let mut bad = build_fat12_image();
+    bad[510] = 0;
+    assert!(matches!(
+        Fat32FileSystem::mount(MemDisk(bad)),
+        Err(BootError::FileSystem(FileSystemError::InvalidSignature))
+    ));
+}
+
+/// Arquivo em memória com leitura e seek reais.
+struct MemFile {
+    data: Vec<u8>,
+    pos:  usize,
+}
+
+impl ignite::fs::vfs::File for MemFile {
+    fn read(&mut self, buf: &mut [u8]) -> ignite::core::error::Result<usize> {
+        let n = buf.len().min(self.data.len().saturating_sub(self.pos));
+        buf[..n].copy_from_slice(&self.data[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> ignite::core::error::Result<usize> {
+        Ok(0)
+    }
+
+    fn seek(&mut self, offset: u64) -> ignite::core::error::Result<u64> {
+        self.pos = offset as usize;
+        Ok(offset)
+    }
+
+    fn metadata(&self) -> ignite::core::error::Result<ignite::fs::vfs::Metadata> {
+        Ok(ignite::fs::vfs::Metadata {
+            size:        self.data.len() as u64,
+            is_dir:      false,
+            is_readonly: true,
+        })
+    }
+}
+
+/// Partição de boot simulada com um único arquivo, a imagem ISO.
+struct IsoVolume {
+    path:  &'static str,
+    image: Vec<u8>,
+}
+
+impl ignite::fs::Directory for IsoVolume {
+    fn open_file(
+        &mut self,
+        path: &str,
+    ) -> ignite::core::error::Result<alloc::boxed::Box<dyn ignite::fs::File>> {
+        use ignite::core::error::{BootError, FileSystemError};
+
+        if path != self.path {
+            return Err(BootError::FileSystem(FileSystemError::FileNotFound));
+        }
+        Ok(alloc::boxed::Box::new(MemFile {
+            data: self.image.clone(),
+            pos:  0,
+        }))
+    }
+
+    fn open_dir(
+        &mut self,
+        _path: &str,
+    ) -> ignite::core::error::Result<alloc::boxed::Box<dyn ignite::fs::Directory>> {
+        Err(ignite::core::error::BootError::FileSystem(
+            ignite::core::error::FileSystemError::InvalidPath,
+        ))
+    }
+
+    fn list(&mut self) -> ignite::core::error::Result<Vec<String>> {
+        Ok(alloc::vec![String::from(self.path)])
+    }
+}
+
+impl ignite::fs::FileSystem for IsoVolume {
+    fn root(
+        &mut self,
+    ) -> ignite::core::error::Result<alloc::boxed::Box<dyn ignite::fs::Directory>> {
+        Ok(alloc::boxed::Box::new(IsoVolume {
+            path:  self.path,
+            image: self.image.clone(),
+        }))
+    }
+
+    fn name(&self) -> &str {
+        "ISO_VOLUME"
+    }
+}
+
+/// Testa o caminho de `iso_path`: abrir a imagem, montar o ESP FAT embutido
+/// e ler o kernel de dentro dele
+#[test]
+fn test_iso_path_reads_kernel_from_embedded_fat() {
+    use ignite::{
+        core::error::{BootError, FileSystemError},
+        fs::{iso::open_iso_root, read_to_bytes},
+    };
+
+    // ESP logo após os 36 KiB de cabeçalho da ISO
+    let mut image = build_hybrid_iso(72);
+    image.extend_from_slice(&build_fat12_image());
+    let mut volume = IsoVolume {
+        path:  "/images/live.iso",
+        image: image.clone(),
+    };
+
+    let mut root = open_iso_root(&mut volume, "/images/live.iso").unwrap();
+    let mut kernel = root.open_file("boot():/boot/vmlinuz-long-name").unwrap();
+    let data = read_to_bytes(kernel.as_mut()).unwrap();
+    assert_eq!(data.len(), 1300);
+    assert!(data.iter().enumerate().all(|(i, &b)| b == (i % 251) as u8));
+
+    // Falhas viram erro da entrada em vez de travar o boot
+    assert_eq!(
+        open_iso_root(&mut volume, "/images/other.iso").err(),
+        Some(BootError::FileSystem(FileSystemError::FileNotFound))
+    );
+    let mut no_fat = build_hybrid_iso(72);
+    no_fat.resize(no_fat.len() + 64 * 512, 0);
+    let mut volume = IsoVolume {
+        path:  "/images/live.iso",
+        image: no_fat,
+    };
+    assert_eq!(
+        open_iso_root(&mut volume, "/images/live.iso").err(),
+        Some(BootError::FileSystem(FileSystemError::InvalidSignature))
+    );
+}
+
+/// Testa a conversão de caminhos da config para UCS-2
+#[test]
+fn test_path_to_ucs2() {
+    use ignite::{
+        core::error::{BootError, FileSystemError},
+        fs::path::{from_ucs2, to_ucs2},
+    };
+
+    let expected: Vec<u16> = "\\EFI\\foo\0".chars().map(|c| c as u16).collect();
+    assert_eq!(to_ucs2("boot()/EFI/foo").unwrap(), expected);
+
+    // Ida e volta de um caminho ASCII
+    let units = to_ucs2("\\EFI\\ignite\\kernel.elf").unwrap();
+    assert_eq!(units.last(), Some(&0));
+    assert_eq!(from_ucs2(&units).unwrap(), "\\EFI\\ignite\\kernel.elf");
+
+    // Fora do BMP não cabe em UCS-2
+    assert_eq!(
+        to_ucs2("/EFI/\u{1F680}"),
+        Err(BootError::FileSystem(FileSystemError::InvalidPath))
+    );
+    assert!(from_ucs2(&[0x0041, 0xD83D, 0xDE80, 0]).is_err());
+}
+
+/// Testa a ordem dos volumes: boot primeiro, demais pelo device path
+#[test]
+fn test_disk_device_priority_sorts_by_device_path() {
+    use ignite::{
+        fs::uefi::disk_device_priority,
+        uefi::{
+            proto::device_path::{device_path_bytes, path_key},
+            Handle,
+        },
+    };
+
+    let boot = Handle(0x1000 as *mut _);
+    let sata = Handle(0x2000 as *mut _);
+    let nvme = Handle(0x3000 as *mut _);
+    let usb = Handle(0x4000 as *mut _);
+
+    // Firmware enumera fora de ordem, com o boot no meio
+    let path_of = |handle: Handle| {
+        let path = if handle == sata {
+            "PciRoot(0x0)/Pci(0x1F,0x2)/Sata(0x0,0x0,0x0)"
+        } else if handle == nvme {
+            "PciRoot(0x0)/Pci(0x1D,0x0)/NVMe(0x1,00-00)"
+        } else if handle == boot {
+            "PciRoot(0x0)/Pci(0x1,0x1)/Ata(0x0)"
+        } else {
+            return None;
+        };
+        Some(String::from(path))
+    };
+
+    let order = disk_device_priority(boot, alloc::vec![sata, usb, boot, nvme], path_of);
+    assert!(order == [boot, nvme, sata, usb]);
+
+    // Mesmo resultado qualquer que seja a ordem de enumeração
+    let order = disk_device_priority(boot, alloc::vec![nvme, boot, usb, sata], path_of);
+    assert!(order == [boot, nvme, sata, usb]);
+
+    // Texto do device path: Pci(0x1F,0x2) seguido do nó de fim
+    let raw = [0x01, 0x01, 0x06, 0x00, 0x02, 0x1F, 0x7F, 0xFF, 0x04, 0x00];
+    let bytes = unsafe { device_path_bytes(raw.as_ptr() as *const _) }.unwrap();
+    assert_eq!(bytes.len(), raw.len());
+    assert_eq!(path_key(bytes).as_deref(), Some("0101(021f)"));
+    assert_eq!(path_key(&raw[..4]), None);
+}
+
+/// Testa que um módulo acima de `MAX_MODULE_SIZE` é recusado antes de
+/// qualquer alocação e que um módulo dentro do limite é carregado
+#[test]
+fn test_module_size_limit() {
+    use ignite::{
+        core::{
+            config::limits::MAX_MODULE_SIZE,
+            error::{BootError, FileSystemError},
+        },
+        fs::loader::load_module_with,
+    };
+
+    let mut huge = CountingFile {
+        len:            MAX_MODULE_SIZE as u64 + 1,
+        metadata_calls: alloc::rc::Rc::new(core::cell::Cell::new(0)),
+        fast_size:      true,
+    };
+    let mut allocations = 0;
+    let result = load_module_with(
+        &mut huge,
+        None,
+        &mut |_| {
+            allocations += 1;
+            Err(BootError::Generic("nao deveria alocar"))
+        },
+        &mut |_| {},
+    );
+    assert_eq!(
+        result.unwrap_err(),
+        BootError::FileSystem(FileSystemError::FileTooLarge)
+    );
+    assert_eq!(allocations, 0);
+
+    let data: Vec<u8> = (0..10u8).collect();
+    let mut small = StutteringFile {
+        data:    data.clone(),
+        pos:     0,
+        calls:   0,
+        zero_at: Vec::new(),
+    };
+    let mut buffer = alloc::vec![0u8; data.len()];
+    let module = load_module_with(
+        &mut small,
+        Some(String::from("initfs")),
+        &mut |size| {
+            assert_eq!(size, 10);
+            Ok(buffer.as_mut_ptr())
+        },
+        &mut |_| {},
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(module.size, 10);
+    assert_eq!(module.cmdline.as_deref(), Some("initfs"));
+    assert_eq!(module.ptr, buffer.as_ptr() as u64);
+    assert_eq!(buffer, data);
+}
+
+/// Testa que kernel vazio ou acima de `MAX_KERNEL_SIZE` vira erro
+/// recuperável, sem alocar, e que um kernel válido é lido inteiro
+#[test]
+fn test_kernel_size_errors_are_recoverable() {
+    use ignite::{
+        core::{
+            config::limits::MAX_KERNEL_SIZE,
+            error::{BootError, FileSystemError},
+        },
+        fs::loader::load_kernel_with,
+    };
+
+    let mut allocations = 0;
+    for (len, expected) in [
+        (0, FileSystemError::InvalidSize),
+        (MAX_KERNEL_SIZE as u64 + 1, FileSystemError::FileTooLarge),
+    ] {
+        let mut file = CountingFile {
+            len,
+            metadata_calls: alloc::rc::Rc::new(core::cell::Cell::new(0)),
+            fast_size:      true,
+        };
+        let result = load_kernel_with(
+            &mut file,
+            &mut |_| {
+                allocations += 1;
+                Err(BootError::Generic("nao deveria alocar"))
+            },
+            &mut |_| {},
+        );
+        assert_eq!(result.unwrap_err(), BootError::FileSystem(expected));
+    }
+    assert_eq!(allocations, 0);
+
+    let data: Vec<u8> = (0..32u8).collect();
+    let mut kernel = StutteringFile {
+        data:    data.clone(),
+        pos:     0,
+        calls:   0,
+        zero_at: Vec::new(),
+    };
+    let mut buffer = alloc::vec![0u8; data.len()];
+    let loaded =
+        load_kernel_with(&mut kernel, &mut |_| Ok(buffer.as_mut_ptr()), &mut |_| {}).unwrap();
+    assert_eq!(loaded.size, 32);
+    assert_eq!(loaded.ptr, buffer.as_ptr() as u64);
+    assert_eq!(buffer, data);
+}
+
+/// Testa que `kernel_crc32`/`module_crc32` aceitam o arquivo íntegro e
+/// recusam um único byte alterado
+#[test]
+fn test_verify_crc32_detects_bit_rot() {
+    use ignite::{
+        config::parser::Parser,
+        core::error::{BootError, FileSystemError},
+        fs::loader::verify_crc32,
+    };
+
+    // CRC32 de "123456789" é 0xCBF43926
+    let content = "/Redstone\n    path: boot():/forge\n    kernel_crc32: cbf43926\n    \
+                   module_path: boot():/initfs\n    module_crc32: 0xCBF43926\n";
+    let config = Parser::new().parse(content).unwrap();
+    let entry = &config.entries[0];
+    assert_eq!(entry.kernel_crc32, Some(0xCBF4_3926));
+    assert_eq!(entry.modules[0].crc32, Some(0xCBF4_3926));
+
+    let mut data = *b"123456789";
+    assert!(verify_crc32(&data, entry.kernel_crc32).is_ok());
+    assert!(verify_crc32(&data, entry.modules[0].crc32).is_ok());
+
+    data[4] ^= 0x01;
+    assert_eq!(
+        verify_crc32(&data, entry.kernel_crc32),
+        Err(BootError::FileSystem(FileSystemError::ChecksumMismatch))
+    );
+
+    // Sem CRC declarado, nada é conferido
+    assert!(verify_crc32(&data, None).is_ok());
+}
+
+/// Testa `initrd_size`: tamanho divergente é recusado e o tamanho igual
+/// passa inalterado para o handoff
+#[test]
+fn test_verify_initrd_size() {
+    use ignite::{
+        config::parser::Parser,
+        core::error::{BootError, FileSystemError},
+        fs::loader::verify_size,
+    };
+
+    let content = "/Redstone\n    path: boot():/forge\n    module_path: boot():/initfs\n    \
+                   initrd_size: 4K\n";
+    let config = Parser::new().parse(content).unwrap();
+    let expected = config.entries[0].initrd_size;
+    assert_eq!(expected, Some(4096));
+
+    assert_eq!(verify_size(4096, expected), Ok(4096));
+    assert_eq!(
+        verify_size(4095, expected),
+        Err(BootError::FileSystem(FileSystemError::InvalidSize))
+    );
+    assert_eq!(
+        verify_size(8192, expected),
+        Err(BootError::FileSystem(FileSystemError::InvalidSize))
+    );
+
+    // Sem tamanho declarado, o lido é aceito como está
+    assert_eq!(verify_size(123, None), Ok(123));
+}
+
+/// Testa a conferência de `module_type` pelo magic: cpio newc e ELF são
+/// reconhecidos, e um blob desconhecido gera aviso (erro em modo estrito)
+#[test]
+fn test_verify_module_type_by_magic() {
+    use ignite::{
+        config::{parser::Parser, ModuleType},
+        core::error::{BootError, FileSystemError},
+        fs::loader::{detect_module_format, verify_module_type, ModuleFormat},
+    };
+
+    let content = "/Redstone\n    path: boot():/forge\n    module_path: boot():/initfs\n    \
+                   module_type: cpio\n    module_path: boot():/driver\n    module_type: ELF\n    \
+                   module_path: boot():/blob\n    module_type: initrd\n";
+    let config = Parser::new().parse(content).unwrap();
+    let modules = &config.entries[0].modules;
+    assert_eq!(modules[0].module_type, Some(ModuleType::Cpio));
+    assert_eq!(modules[1].module_type, Some(ModuleType::Elf));
+    assert_eq!(modules[2].module_type, Some(ModuleType::Initrd));
+
+    let cpio = b"070701000000010000...";
+    let elf = b"\x7FELF\x02\x01\x01\x00";
+    assert_eq!(detect_module_format(cpio), Some(ModuleFormat::Cpio));
+    assert_eq!(detect_module_format(elf), Some(ModuleFormat::Elf));
+    assert_eq!(
+        detect_module_format(&[0x1F, 0x8B, 0x08]),
+        Some(ModuleFormat::Gzip)
+    );
+
+    assert_eq!(
+        verify_module_type(cpio, modules[0].module_type, true),
+        Ok(true)
+    );
+    assert_eq!(
+        verify_module_type(elf, modules[1].module_type, true),
+        Ok(true)
+    );
+    assert_eq!(
+        verify_module_type(cpio, modules[2].module_type, true),
+        Ok(true)
+    );
+
+    // Blob desconhecido: só aviso, a menos que o modo seja estrito
+    let blob = b"\x00\x01\x02\x03";
+    assert_eq!(detect_module_format(blob), None);
+    assert_eq!(
+        verify_module_type(blob, modules[2].module_type, false),
+        Ok(false)
+    );
+    assert_eq!(
+        verify_module_type(blob, modules[2].module_type, true),
+        Err(BootError::FileSystem(FileSystemError::InvalidSignature))
+    );
+
+    // Sem tipo declarado, nada é conferido
+    assert_eq!(verify_module_type(blob, None, true), Ok(true));
+}
+
+/// Acrescenta a `archive` um registro newc com `name` e `data`.
+fn push_newc(archive: &mut Vec<u8>, name: &str, mode: u32, data: &[u8]) {
+    let fields = [
+        0,
+        mode,
+        0,
+        0,
+        1,
+        0,
+        data.len() as u32,
+        0,
+        0,
+        0,
+        0,
+        name.len() as u32 + 1,
+        0,
+    ];
+    archive.extend_from_slice(b"070701");
+    for field in fields {
+        archive.extend_from_slice(alloc::format!("{:08X}", field).as_bytes());
+    }
+    archive.extend_from_slice(name.as_bytes());
+    archive.push(0);
+    while !archive.len().is_multiple_of(4) {
+        archive.push(0);
+    }
+    archive.extend_from_slice(data);
+    while !archive.len().is_multiple_of(4) {
+        archive.push(0);
+    }
+}
+
+/// Testa a leitura de um cpio newc com dois arquivos: nomes, tamanhos,
+/// alinhamento do conteúdo e parada no `TRAILER!!!`
+#[test]
+fn test_cpio_newc_lists_files_until_trailer() {
+    use ignite::fs::cpio;
+
+    let mut archive = Vec::new();
+    // Nomes de tamanhos diferentes exercitam o preenchimento até 4 bytes
+    push_newc(&mut archive, "init", 0o100755, b"#!/bin/sh\n");
+    push_newc(&mut archive, "etc/hostname", 0o100644, b"redstone");
+    push_newc(&mut archive, "TRAILER!!!", 0, b"");
+    // Lixo depois do trailer (o cpio costuma completar até 512 bytes)
+    push_newc(&mut archive, "depois", 0o100644, b"x");
+
+    let mut entries = cpio::iter(&archive);
+    let first = entries.next().unwrap();
+    assert_eq!((first.name, first.size), ("init", 10));
+    assert_eq!(first.mode, 0o100755);
+    assert_eq!(first.offset % 4, 0);
+    assert_eq!(first.data(&archive), b"#!/bin/sh\n");
+
+    let second = entries.next().unwrap();
+    assert_eq!((second.name, second.size), ("etc/hostname", 8));
+    assert_eq!(second.offset % 4, 0);
+    assert_eq!(second.data(&archive), b"redstone");
+
+    assert!(entries.next().is_none());
+    assert!(entries.reached_trailer());
+    assert!(entries.next().is_none());
+
+    assert_eq!(
+        cpio::find(&archive, "/etc/hostname"),
+        Some(&b"redstone"[..])
+    );
+    assert_eq!(cpio::find(&archive, "depois"), None);
+
+    // Truncado no meio do segundo arquivo: só o primeiro aparece
+    let truncated = &archive[..second.offset + 2];
+    let mut entries = cpio::iter(truncated);
+    assert_eq!(entries.by_ref().count(), 1);
+    assert!(!entries.reached_trailer());
+}
+
+/// Resposta pronta de `MockHttp`, entregue em pedaços de `piece` bytes para
+/// cruzar as fronteiras dos chunks.
+struct MockResponse {
+    status:  u16,
+    headers: Vec<(&'static str, &'static str)>,
+    body:    Vec<u8>,
+    pos:     usize,
+    piece:   usize,
+}
+
+impl ignite::fs::http::HttpResponse for MockResponse {
+    fn status(&self) -> u16 {
+        self.status
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| *value)
+    }
+
+    fn read_raw(&mut self, buf: &mut [u8]) -> ignite::core::error::Result<usize> {
+        let n = (self.body.len() - self.pos).min(buf.len()).min(self.piece);
+        buf[..n].copy_from_slice(&self.body[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+type MockRoute = (
+    &'static str,
+    u16,
+    Vec<(&'static str, &'static str)>,
+    &'static [u8],
+);
+
+/// Servidor HTTP falso: URL -> (status, cabeçalhos, corpo bruto). URLs
+/// desconhecidas respondem 404.
+#[derive(Clone)]
+struct MockHttp {
+    routes: alloc::rc::Rc<Vec<MockRoute>>,
+}
+
+impl ignite::fs::http::HttpTransport for MockHttp {
+    fn get(
+        &mut self,
+        url: &str,
+    ) -> ignite::core::error::Result<alloc::boxed::Box<dyn ignite::fs::http::HttpResponse>> {
+        let (status, headers, body) = self
+            .routes
+            .iter()
+            .find(|(path, ..)| *path == url)
+            .map(|(_, status, headers, body)| (*status, headers.clone(), body.to_vec()))
+            .unwrap_or((404, Vec::new(), Vec::new()));
+        Ok(alloc::boxed::Box::new(MockResponse {
+            status,
+            headers,
+            body,
+            pos: 0,
+            piece: 5,
+        }))
+    }
+}
+
+/// Testa o boot por HTTP: corpo com Content-Length, corpo chunked (com
+/// extensão e trailer) e 404 como arquivo não encontrado.
+#[test]
+fn test_http_filesystem_content_length_chunked_and_404() {
+    use ignite::{
+        core::error::{BootError, FileSystemError},
+        fs::{
+            http::{join_url, ChunkedDecoder, HttpFileSystem},
+            read_exact_with, read_to_bytes, FileSystem,
+        },
+    };
+
+    let kernel: &[u8] = b"\x7fELF kernel image bytes";
+    let chunked: &[u8] =
+        b"7;ext=1\r\ninitrd \r\n10\r\ncontents split i\r\n4\r\nn 3\n\r\n0\r\nX-Sum: 1\r\n\r\n";
+    let fs = HttpFileSystem::new(
+        MockHttp {
+            routes: alloc::rc::Rc::new(alloc::vec![
+                (
+                    "http://10.0.2.2/boot/forge",
+                    200,
+                    alloc::vec![("Content-Length", "23")],
+                    kernel,
+                ),
+                (
+                    "http://10.0.2.2/boot/initrd",
+                    200,
+                    alloc::vec![("Transfer-Encoding", "chunked"), ("Content-Length", "999")],
+                    chunked,
+                ),
+                ("http://10.0.2.2/boot/broken", 500, Vec::new(), b""),
+            ]),
+        },
+        "http://10.0.2.2/boot/",
+    );
+    let mut root = fs.unwrap().root().unwrap();
+
+    // Content-Length: lido direto no buffer do chamador
+    let mut file = root.open_file("boot():/forge").unwrap();
+    assert_eq!(file.size().unwrap(), kernel.len() as u64);
+    let mut buf = alloc::vec![0u8; kernel.len()];
+    read_exact_with(file.as_mut(), &mut buf, &mut |_| {}).unwrap();
+    assert_eq!(buf, kernel);
+
+    // Chunked: `chunked` prevalece sobre o Content-Length
+    let mut file = root.open_file("/initrd").unwrap();
+    assert_eq!(
+        read_to_bytes(file.as_mut()).unwrap(),
+        b"initrd contents split in 3\n"
+    );
+
+    assert!(matches!(
+        root.open_file("missing").err(),
+        Some(BootError::File {
+            kind: FileSystemError::FileNotFound,
+            ..
+        })
+    ));
+    assert!(root.open_file("broken").is_err());
+    assert!(HttpFileSystem::new(
+        MockHttp {
+            routes: alloc::rc::Rc::new(Vec::new()),
+        },
+        "ftp://x"
+    )
+    .is_err());
+
+    // Chunked malformado e truncado
+    let mut bad = *b"zz\r\n";
+    assert!(ChunkedDecoder::new().decode_in_place(&mut bad).is_err());
+    let mut decoder = ChunkedDecoder::new();
+    let mut partial = *b"3\r\nabc\r\n";
+    assert_eq!(decoder.decode_in_place(&mut partial).unwrap(), 3);
+    assert!(!decoder.is_done());
+
+    assert_eq!(
+        join_url("http://srv/boot/", "boot():\\EFI\\forge"),
+        "http://srv/boot/EFI/forge"
+    );
+}
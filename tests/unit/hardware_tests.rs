@@ -0,0 +1,290 @@
+//! Testes Unitários para o módulo de hardware
+//!
+//! Testa a leitura das tabelas ACPI sobre tabelas sintéticas em RAM e a
+//! detecção da porta serial sobre um barramento de I/O simulado.
+
+#![no_std]
+#![cfg(test)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use ignite::hardware::{
+    acpi::{AcpiManager, AcpiRoot},
+    rtc::{read_time_with, MAX_UIP_POLLS},
+    serial::{detect_port_with, PortIo, COM_PORTS},
+};
+
+/// Soma os bytes de uma tabela e grava o checksum em `offset`.
+fn fix_checksum(table: &mut [u8], offset: usize) {
+    table[offset] = 0;
+    let sum = table.iter().fold(0u8, |acc, b| acc.wrapping_add(*b));
+    table[offset] = 0u8.wrapping_sub(sum);
+}
+
+/// Cabeçalho SDT comum (36 bytes) com o tamanho total já preenchido.
+fn sdt_header(signature: &[u8; 4], length: usize) -> Vec<u8> {
+    let mut t = alloc::vec![0u8; 36];
+    t[0..4].copy_from_slice(signature);
+    t[4..8].copy_from_slice(&(length as u32).to_le_bytes());
+    t[8] = 1;
+    t[10..16].copy_from_slice(b"IGNITE");
+    t
+}
+
+/// MADT com dois Local APICs habilitados e um IO APIC.
+fn build_madt() -> Vec<u8> {
+    let mut entries = Vec::new();
+    for apic_id in 0..2u8 {
+        entries.extend_from_slice(&[0, 8, apic_id, apic_id]);
+        entries.extend_from_slice(&1u32.to_le_bytes());
+    }
+    entries.extend_from_slice(&[1, 12, 4, 0]);
+    entries.extend_from_slice(&0xFEC0_0000u32.to_le_bytes());
+    entries.extend_from_slice(&0u32.to_le_bytes());
+
+    let len = 44 + entries.len();
+    let mut madt = sdt_header(b"APIC", len);
+    madt.extend_from_slice(&0xFEE0_0000u32.to_le_bytes());
+    madt.extend_from_slice(&1u32.to_le_bytes());
+    madt.extend_from_slice(&entries);
+    fix_checksum(&mut madt, 9);
+    madt
+}
+
+/// Tabela raiz (RSDT ou XSDT) apontando para `tables`.
+fn build_root(signature: &[u8; 4], tables: &[u64], entry_size: usize) -> Vec<u8> {
+    let mut root = sdt_header(signature, 36 + tables.len() * entry_size);
+    for &addr in tables {
+        root.extend_from_slice(&addr.to_le_bytes()[..entry_size]);
+    }
+    fix_checksum(&mut root, 9);
+    root
+}
+
+/// RSDP com a revisão e os ponteiros informados.
+fn build_rsdp(revision: u8, rsdt: u32, xsdt: u64) -> Vec<u8> {
+    let mut rsdp = alloc::vec![0u8; 36];
+    rsdp[0..8].copy_from_slice(b"RSD PTR ");
+    rsdp[9..15].copy_from_slice(b"IGNITE");
+    rsdp[15] = revision;
+    rsdp[16..20].copy_from_slice(&rsdt.to_le_bytes());
+    rsdp[20..24].copy_from_slice(&36u32.to_le_bytes());
+    rsdp[24..32].copy_from_slice(&xsdt.to_le_bytes());
+    fix_checksum(&mut rsdp[..20], 8);
+    fix_checksum(&mut rsdp, 32);
+    rsdp
+}
+
+/// Testa a enumeração da MADT via XSDT (ACPI 2.0+)
+#[test]
+fn test_parse_madt_via_xsdt() {
+    let facp = sdt_header(b"FACP", 36);
+    let madt = build_madt();
+    let xsdt = build_root(b"XSDT", &[facp.as_ptr() as u64, madt.as_ptr() as u64], 8);
+    let rsdp = build_rsdp(2, 0, xsdt.as_ptr() as u64);
+
+    let info = unsafe { AcpiManager::parse_madt(rsdp.as_ptr() as u64) }.unwrap();
+
+    assert_eq!(info.cpu_count, 2);
+    assert_eq!(info.io_apic_count, 1);
+    assert_eq!(info.io_apic_addrs[0], 0xFEC0_0000);
+    assert!(info.io_apic_addrs[1..].iter().all(|&a| a == 0));
+    assert_eq!(info.local_apic_addr, 0xFEE0_0000);
+}
+
+/// Testa que a ausência da MADT gera erro
+#[test]
+fn test_parse_madt_missing_table() {
+    let facp = sdt_header(b"FACP", 36);
+    let xsdt = build_root(b"XSDT", &[facp.as_ptr() as u64], 8);
+    let rsdp = build_rsdp(2, 0, xsdt.as_ptr() as u64);
+
+    assert!(unsafe { AcpiManager::parse_madt(rsdp.as_ptr() as u64) }.is_err());
+}
+
+/// Soma dos bytes, como no checksum ACPI (válido quando 0).
+fn byte_sum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |acc, b| acc.wrapping_add(*b))
+}
+
+/// Testa os checksums v1 (20 bytes) e v2 (36 bytes) de um RSDP sintético
+#[test]
+fn test_rsdp_checksums() {
+    let xsdt = build_root(b"XSDT", &[0x1000, 0x2000, 0x3000], 8);
+    let rsdp = build_rsdp(2, 0, xsdt.as_ptr() as u64);
+
+    assert_eq!(byte_sum(&rsdp[..20]), 0);
+    assert_eq!(byte_sum(&rsdp), 0);
+
+    let root = unsafe { AcpiManager::validate_rsdp(rsdp.as_ptr() as u64) }.unwrap();
+    assert_eq!(
+        root,
+        AcpiRoot {
+            rsdp_addr:   rsdp.as_ptr() as u64,
+            xsdt_addr:   xsdt.as_ptr() as u64,
+            table_count: 3,
+        }
+    );
+}
+
+/// Testa a rejeição de RSDP com checksum v1 ou v2 corrompido
+#[test]
+fn test_rsdp_corrupt_checksum_rejected() {
+    let xsdt = build_root(b"XSDT", &[], 8);
+
+    // Byte dentro da faixa v1: ambos os checksums quebram
+    let mut rsdp = build_rsdp(2, 0, xsdt.as_ptr() as u64);
+    rsdp[10] ^= 0xFF;
+    assert!(unsafe { AcpiManager::validate_rsdp(rsdp.as_ptr() as u64) }.is_err());
+
+    // Byte reservado (só coberto pelo checksum estendido)
+    let mut rsdp = build_rsdp(2, 0, xsdt.as_ptr() as u64);
+    rsdp[33] = 0x5A;
+    assert_eq!(byte_sum(&rsdp[..20]), 0);
+    assert!(unsafe { AcpiManager::validate_rsdp(rsdp.as_ptr() as u64) }.is_err());
+
+    // Em ACPI 1.0 a parte estendida não é verificada
+    let mut rsdp = build_rsdp(0, 0, 0);
+    rsdp[33] = 0x5A;
+    let root = unsafe { AcpiManager::validate_rsdp(rsdp.as_ptr() as u64) }.unwrap();
+    assert_eq!(root.xsdt_addr, 0);
+
+    // Assinatura inválida
+    let mut rsdp = build_rsdp(2, 0, xsdt.as_ptr() as u64);
+    rsdp[0] = b'X';
+    assert!(unsafe { AcpiManager::validate_rsdp(rsdp.as_ptr() as u64) }.is_err());
+}
+
+/// Testa que uma XSDT corrompida é descartada sem invalidar o RSDP
+#[test]
+fn test_xsdt_corrupt_checksum_ignored() {
+    let mut xsdt = build_root(b"XSDT", &[0x1000], 8);
+    xsdt[36] ^= 0x01;
+    let rsdp = build_rsdp(2, 0, xsdt.as_ptr() as u64);
+
+    let root = unsafe { AcpiManager::validate_rsdp(rsdp.as_ptr() as u64) }.unwrap();
+    assert_eq!(root.rsdp_addr, rsdp.as_ptr() as u64);
+    assert_eq!(root.xsdt_addr, 0);
+    assert_eq!(root.table_count, 0);
+}
+
+/// Barramento de I/O onde só os UARTs em `present` guardam o scratch register.
+struct MockPorts {
+    present: Vec<u16>,
+    scratch: [u8; 4],
+}
+
+impl MockPorts {
+    fn new(present: &[u16]) -> Self {
+        Self {
+            present: present.to_vec(),
+            scratch: [0; 4],
+        }
+    }
+
+    fn slot(&self, port: u16) -> Option<usize> {
+        let base = port - 7;
+        if !self.present.contains(&base) {
+            return None;
+        }
+        COM_PORTS.iter().position(|&b| b == base)
+    }
+}
+
+impl PortIo for MockPorts {
+    unsafe fn read(&mut self, port: u16) -> u8 {
+        match self.slot(port) {
+            Some(i) => self.scratch[i],
+            None => 0xFF,
+        }
+    }
+
+    unsafe fn write(&mut self, port: u16, value: u8) {
+        if let Some(i) = self.slot(port) {
+            self.scratch[i] = value;
+        }
+    }
+}
+
+/// Testa que a detecção escolhe COM2 quando COM1 não responde
+#[test]
+fn test_serial_detect_falls_back_to_com2() {
+    let mut io = MockPorts::new(&[0x2F8, 0x3E8]);
+    assert_eq!(detect_port_with(&mut io), 0x2F8);
+
+    // COM1 presente tem prioridade
+    let mut io = MockPorts::new(&[0x3F8, 0x2F8]);
+    assert_eq!(detect_port_with(&mut io), 0x3F8);
+
+    // Nenhuma porta responde: COM1 por padrão
+    let mut io = MockPorts::new(&[]);
+    assert_eq!(detect_port_with(&mut io), 0x3F8);
+}
+
+/// CMOS simulado: registradores indexados pela porta 0x70, com o bit UIP
+/// ligado nas primeiras `busy_reads` leituras do status A.
+struct MockCmos {
+    registers:  [u8; 0x0C],
+    index:      u8,
+    busy_reads: usize,
+}
+
+impl PortIo for MockCmos {
+    unsafe fn read(&mut self, port: u16) -> u8 {
+        assert_eq!(port, 0x71);
+        let value = self.registers[self.index as usize];
+        if self.index == 0x0A && self.busy_reads > 0 {
+            self.busy_reads -= 1;
+            return value | 0x80;
+        }
+        value
+    }
+
+    unsafe fn write(&mut self, port: u16, value: u8) {
+        assert_eq!(port, 0x70);
+        self.index = value & 0x7F;
+    }
+}
+
+/// Testa a decodificação BCD do RTC e a espera pelo fim da atualização
+#[test]
+fn test_rtc_decodes_bcd_after_update() {
+    let mut registers = [0u8; 0x0C];
+    registers[0x00] = 0x59; // segundos
+    registers[0x02] = 0x07; // minutos
+    registers[0x04] = 0x23; // horas
+    registers[0x07] = 0x16;
+    registers[0x08] = 0x10;
+    registers[0x09] = 0x26;
+    registers[0x0B] = 0x02; // BCD, 24 horas
+
+    let mut io = MockCmos {
+        registers,
+        index: 0,
+        busy_reads: 5,
+    };
+    let time = read_time_with(&mut io).unwrap();
+    assert_eq!(io.busy_reads, 0);
+    assert_eq!((time.hour, time.minute, time.second), (23, 7, 59));
+    assert_eq!((time.year, time.month, time.day), (2026, 10, 16));
+
+    // 12 horas com bit de PM: 0x81 = 1 PM
+    registers[0x04] = 0x81;
+    registers[0x0B] = 0x00;
+    let mut io = MockCmos {
+        registers,
+        index: 0,
+        busy_reads: 0,
+    };
+    assert_eq!(read_time_with(&mut io).unwrap().hour, 13);
+
+    // Atualização que nunca termina: desiste em vez de travar
+    let mut io = MockCmos {
+        registers,
+        index: 0,
+        busy_reads: MAX_UIP_POLLS,
+    };
+    assert!(read_time_with(&mut io).is_none());
+}
@@ -32,17 +32,47 @@ fn test_address_alignment() {
 /// Testa cálculo de número de páginas
 #[test]
 fn test_pages_calculation() {
-    fn bytes_to_pages(bytes: usize) -> usize {
-        (bytes + 4095) / 4096
+    use ignite::core::align::div_ceil_pages;
+
+    assert_eq!(div_ceil_pages(0), 0);
+    assert_eq!(div_ceil_pages(1), 1);
+    assert_eq!(div_ceil_pages(4095), 1);
+    assert_eq!(div_ceil_pages(4096), 1);
+    assert_eq!(div_ceil_pages(4097), 2);
+    assert_eq!(div_ceil_pages(8192), 2);
+    assert_eq!(div_ceil_pages(8193), 3);
+}
+
+/// Testa `align_up`/`align_down` em 4 KiB e 2 MiB, inclusive perto de
+/// `u64::MAX`
+#[test]
+fn test_align_helpers() {
+    use ignite::core::align::{align_down, align_up, div_ceil_pages};
+
+    const SIZE_4KIB: u64 = 0x1000;
+    const SIZE_2MIB: u64 = 0x20_0000;
+
+    assert_eq!(align_up(0x1234, SIZE_4KIB), Some(0x2000));
+    assert_eq!(align_down(0x1234, SIZE_4KIB), 0x1000);
+    assert_eq!(align_up(0x20_0001, SIZE_2MIB), Some(0x40_0000));
+    assert_eq!(align_down(0x3F_FFFF, SIZE_2MIB), 0x20_0000);
+
+    // Valores já alinhados não mudam
+    for value in [0, SIZE_4KIB, SIZE_2MIB, 0x4000_0000] {
+        assert_eq!(align_up(value, SIZE_4KIB), Some(value));
+        assert_eq!(align_down(value, SIZE_4KIB), value);
     }
 
-    assert_eq!(bytes_to_pages(0), 0);
-    assert_eq!(bytes_to_pages(1), 1);
-    assert_eq!(bytes_to_pages(4095), 1);
-    assert_eq!(bytes_to_pages(4096), 1);
-    assert_eq!(bytes_to_pages(4097), 2);
-    assert_eq!(bytes_to_pages(8192), 2);
-    assert_eq!(bytes_to_pages(8193), 3);
+    // Perto do topo: a última página alinhada ainda cabe, a seguinte não
+    assert_eq!(align_up(u64::MAX - 0xFFF, SIZE_4KIB), Some(u64::MAX - 0xFFF));
+    assert_eq!(align_up(u64::MAX - 0xFFE, SIZE_4KIB), None);
+    assert_eq!(align_up(u64::MAX, SIZE_2MIB), None);
+    assert_eq!(align_down(u64::MAX, SIZE_2MIB), u64::MAX - (SIZE_2MIB - 1));
+    assert_eq!(div_ceil_pages(u64::MAX), 1 << 52);
+
+    // Utilizável em contexto `const`
+    const ALIGNED: Option<u64> = align_up(0x1001, SIZE_4KIB);
+    assert_eq!(ALIGNED, Some(0x2000));
 }
 
 /// Testa extração de índices de página
@@ -342,3 +372,904 @@ fn test_memory_fragmentation() {
 
     assert!((frag - 0.7).abs() < 0.01); // ~70% livre
 }
+
+// ---------------------------------------------------------------------------
+// PageTableManager sobre uma arena de frames no host
+// ---------------------------------------------------------------------------
+
+mod paging_harness {
+    use alloc::vec::Vec;
+
+    use ignite::{
+        core::error::Result,
+        memory::{FrameAllocator, PageTableManager},
+    };
+
+    pub const PRESENT: u64 = 1 << 0;
+    pub const WRITABLE: u64 = 1 << 1;
+    pub const PWT: u64 = 1 << 3;
+    pub const PCD: u64 = 1 << 4;
+    pub const HUGE: u64 = 1 << 7;
+    pub const GLOBAL: u64 = 1 << 8;
+    pub const HUGE_PAT: u64 = 1 << 12;
+    pub const PAT_4K: u64 = 1 << 7;
+    pub const NO_EXEC: u64 = 1 << 63;
+    pub const ADDR_MASK: u64 = 0x000F_FFFF_FFFF_F000;
+
+    /// "Endereço físico" do primeiro frame da arena.
+    pub const ARENA_PHYS_BASE: u64 = 0x10_0000;
+
+    #[repr(C, align(4096))]
+    pub struct Frame(pub [u8; 4096]);
+
+    /// Alocador cujos endereços físicos são índices na arena
+    /// (`ARENA_PHYS_BASE + i * 4096`).
+    pub struct MockFrameAllocator {
+        pub frames: Vec<Frame>,
+        pub used:   usize,
+    }
+
+    impl MockFrameAllocator {
+        pub fn new(count: usize) -> Self {
+            let mut frames = Vec::with_capacity(count);
+            for _ in 0..count {
+                // Lixo proposital: o gerenciador deve zerar o que aloca
+                frames.push(Frame([0xA5; 4096]));
+            }
+            Self { frames, used: 0 }
+        }
+
+        /// Offset que traduz endereço físico da arena para ponteiro do host.
+        pub fn phys_offset(&self) -> u64 {
+            (self.frames.as_ptr() as u64).wrapping_sub(ARENA_PHYS_BASE)
+        }
+
+        /// Lê a tabela no endereço físico `phys`.
+        pub fn table(&self, phys: u64) -> &[u64; 512] {
+            let idx = ((phys - ARENA_PHYS_BASE) / 4096) as usize;
+            assert!(idx < self.used, "frame 0x{:x} não foi alocado", phys);
+            unsafe { &*(self.frames[idx].0.as_ptr() as *const [u64; 512]) }
+        }
+
+        pub fn table_mut(&mut self, phys: u64) -> &mut [u64; 512] {
+            let idx = ((phys - ARENA_PHYS_BASE) / 4096) as usize;
+            assert!(idx < self.used, "frame 0x{:x} não foi alocado", phys);
+            unsafe { &mut *(self.frames[idx].0.as_mut_ptr() as *mut [u64; 512]) }
+        }
+
+        /// Percorre PML4 → PDPT → PD e devolve (endereço do PD, índice).
+        pub fn walk_pd(&self, pml4: u64, virt: u64) -> (u64, usize) {
+            let pml4e = self.table(pml4)[((virt >> 39) & 0x1FF) as usize];
+            assert!(pml4e & PRESENT != 0);
+            let pdpte = self.table(pml4e & ADDR_MASK)[((virt >> 30) & 0x1FF) as usize];
+            assert!(pdpte & PRESENT != 0);
+            (pdpte & ADDR_MASK, ((virt >> 21) & 0x1FF) as usize)
+        }
+
+        /// Percorre até a PT e devolve a entrada final de 4KiB.
+        pub fn walk_4k(&self, pml4: u64, virt: u64) -> u64 {
+            let (pd, pd_idx) = self.walk_pd(pml4, virt);
+            let pde = self.table(pd)[pd_idx];
+            assert!(pde & PRESENT != 0);
+            assert!(pde & HUGE == 0, "esperava PT, encontrou huge page");
+            self.table(pde & ADDR_MASK)[((virt >> 12) & 0x1FF) as usize]
+        }
+    }
+
+    impl FrameAllocator for MockFrameAllocator {
+        fn allocate_frame(&mut self, count: usize) -> Result<u64> {
+            assert!(self.used + count <= self.frames.len(), "arena esgotada");
+            let phys = ARENA_PHYS_BASE + (self.used as u64 * 4096);
+            self.used += count;
+            Ok(phys)
+        }
+
+        fn allocate_at(&mut self, addr: u64, _count: usize) -> Result<u64> {
+            Ok(addr)
+        }
+    }
+
+    pub fn new_manager(allocator: &mut MockFrameAllocator) -> PageTableManager {
+        let offset = allocator.phys_offset();
+        PageTableManager::with_phys_offset(allocator, offset).unwrap()
+    }
+}
+
+/// Testa map_page (via map_kernel) criando a hierarquia completa
+#[test]
+fn test_paging_map_kernel_creates_tables() {
+    use paging_harness::*;
+
+    let mut allocator = MockFrameAllocator::new(16);
+    let mut manager = new_manager(&mut allocator);
+
+    let virt = 0xFFFF_FFFF_8000_0000;
+    manager
+        .map_kernel(0x40_0000, virt, 2, &mut allocator)
+        .unwrap();
+
+    // PML4 + PDPT + PD + PT
+    assert_eq!(allocator.used, 4);
+
+    let pml4 = manager.pml4_addr();
+    assert_eq!(
+        allocator.walk_4k(pml4, virt),
+        0x40_0000 | PRESENT | WRITABLE
+    );
+    assert_eq!(
+        allocator.walk_4k(pml4, virt + 4096),
+        0x40_1000 | PRESENT | WRITABLE
+    );
+    assert_eq!(allocator.walk_4k(pml4, virt + 2 * 4096), 0);
+
+    // Tabelas novas são zeradas apesar do lixo na arena
+    assert_eq!(allocator.table(pml4).iter().filter(|&&e| e != 0).count(), 1);
+}
+
+/// Testa o bit Global nas páginas do kernel conforme `global_kernel_pages`
+#[test]
+fn test_paging_global_kernel_pages() {
+    use ignite::config::parser::Parser;
+    use paging_harness::*;
+
+    let virt = 0xFFFF_FFFF_8000_0000;
+
+    for (text, expected) in [("timeout: 3\n", GLOBAL), ("global_kernel_pages: no\n", 0)] {
+        let config = Parser::new().parse(text).unwrap();
+
+        let mut allocator = MockFrameAllocator::new(16);
+        let mut manager = new_manager(&mut allocator);
+        manager.set_global_kernel_pages(config.global_kernel_pages);
+        manager
+            .map_kernel(0x40_0000, virt, 2, &mut allocator)
+            .unwrap();
+
+        let pml4 = manager.pml4_addr();
+        for page in 0..2u64 {
+            assert_eq!(
+                allocator.walk_4k(pml4, virt + page * 4096),
+                (0x40_0000 + page * 4096) | PRESENT | WRITABLE | expected
+            );
+        }
+
+        // Tabelas intermediárias nunca são globais
+        assert_eq!(
+            allocator.table(pml4).iter().find(|&&e| e != 0).unwrap() & GLOBAL,
+            0
+        );
+    }
+}
+
+/// Testa o NX nos segmentos do kernel conforme `enforce_nx`: dados levam NX
+/// por padrão e nenhum segmento leva com `enforce_nx: no`; código nunca leva
+#[test]
+fn test_paging_enforce_nx() {
+    use ignite::config::parser::Parser;
+    use paging_harness::*;
+
+    let text_virt = 0xFFFF_FFFF_8000_0000;
+    let data_virt = 0xFFFF_FFFF_8010_0000;
+
+    for (text, data_nx) in [("timeout: 3\n", NO_EXEC), ("enforce_nx: no\n", 0)] {
+        let config = Parser::new().parse(text).unwrap();
+
+        let mut allocator = MockFrameAllocator::new(16);
+        let mut manager = new_manager(&mut allocator);
+        manager.set_global_kernel_pages(false);
+        manager.set_enforce_nx(config.enforce_nx);
+        manager
+            .map_kernel_segment(0x40_0000, text_virt, 1, true, &mut allocator)
+            .unwrap();
+        manager
+            .map_kernel_segment(0x50_0000, data_virt, 2, false, &mut allocator)
+            .unwrap();
+
+        let pml4 = manager.pml4_addr();
+        assert_eq!(
+            allocator.walk_4k(pml4, text_virt),
+            0x40_0000 | PRESENT | WRITABLE
+        );
+        for page in 0..2u64 {
+            assert_eq!(
+                allocator.walk_4k(pml4, data_virt + page * 4096),
+                (0x50_0000 + page * 4096) | PRESENT | WRITABLE | data_nx
+            );
+        }
+
+        // NX só nas folhas: nas tabelas intermediárias valeria para tudo abaixo
+        assert_eq!(
+            allocator.table(pml4).iter().find(|&&e| e != 0).unwrap() & NO_EXEC,
+            0
+        );
+    }
+}
+
+/// Testa que o split de huge page gera 512 entradas de PT com flags preservadas
+#[test]
+fn test_paging_huge_page_split_preserves_flags() {
+    use paging_harness::*;
+
+    let mut allocator = MockFrameAllocator::new(16);
+    let mut manager = new_manager(&mut allocator);
+    let pml4 = manager.pml4_addr();
+
+    // Identity map de 4 MiB: duas huge pages em PD[0] e PD[1]
+    manager
+        .identity_map_range(0x40_0000, &mut allocator)
+        .unwrap();
+    let (pd, _) = allocator.walk_pd(pml4, 0x20_0000);
+    assert_eq!(
+        allocator.table(pd)[1],
+        0x20_0000 | PRESENT | WRITABLE | HUGE
+    );
+
+    // Marca a huge page com atributos que o split deve manter
+    allocator.table_mut(pd)[1] |= PWT | PCD | HUGE_PAT | NO_EXEC;
+
+    manager
+        .ensure_identity_map_4k(0x20_5000, &mut allocator)
+        .unwrap();
+
+    let pde = allocator.table(pd)[1];
+    assert_eq!(pde & HUGE, 0);
+    let pt = allocator.table(pde & ADDR_MASK);
+
+    let expected_flags = PRESENT | WRITABLE | PWT | PCD | PAT_4K | NO_EXEC;
+    for (i, &entry) in pt.iter().enumerate() {
+        assert_eq!(
+            entry & ADDR_MASK,
+            0x20_0000 + i as u64 * 4096,
+            "entrada {}",
+            i
+        );
+        if i != 5 {
+            assert_eq!(entry & !ADDR_MASK, expected_flags, "entrada {}", i);
+        }
+    }
+
+    // A página pedida é remapeada com as flags do chamador
+    assert_eq!(pt[5], 0x20_5000 | PRESENT | WRITABLE);
+
+    // A huge page vizinha não é tocada
+    assert_eq!(allocator.table(pd)[0], PRESENT | WRITABLE | HUGE);
+}
+
+/// Testa o scratch slot: PT própria e acessível via identity map de 4KiB
+#[test]
+fn test_paging_setup_scratch_slot() {
+    use paging_harness::*;
+
+    const SCRATCH_VIRT: u64 = 0xFFFF_FE00_0000_0000;
+
+    let mut allocator = MockFrameAllocator::new(16);
+    let mut manager = new_manager(&mut allocator);
+    let pml4 = manager.pml4_addr();
+
+    manager
+        .identity_map_range(0x40_0000, &mut allocator)
+        .unwrap();
+    manager.setup_scratch_slot(&mut allocator).unwrap();
+
+    // Scratch: PD aponta para uma PT vazia (não huge page)
+    let (pd, pd_idx) = allocator.walk_pd(pml4, SCRATCH_VIRT);
+    let pde = allocator.table(pd)[pd_idx];
+    assert_eq!(pde & (PRESENT | WRITABLE | HUGE), PRESENT | WRITABLE);
+    let scratch_pt = pde & ADDR_MASK;
+    assert!(allocator.table(scratch_pt).iter().all(|&e| e == 0));
+
+    // A PT do scratch está na arena (região baixa), que agora usa 4KiB
+    assert_eq!(
+        allocator.walk_4k(pml4, scratch_pt),
+        scratch_pt | PRESENT | WRITABLE
+    );
+}
+
+/// Testa que `table_frames` lista cada frame entregue ao gerenciador
+#[test]
+fn test_paging_reports_table_frames() {
+    use paging_harness::*;
+
+    let mut allocator = MockFrameAllocator::new(16);
+    let mut manager = new_manager(&mut allocator);
+
+    manager
+        .identity_map_range(0x40_0000, &mut allocator)
+        .unwrap();
+    manager.setup_scratch_slot(&mut allocator).unwrap();
+
+    // Todos os frames do alocador foram para tabelas (inclui o split da
+    // huge page que cobre a PT do scratch)
+    let frames = manager.table_frames();
+    assert_eq!(frames.len(), allocator.used);
+    assert_eq!(frames[0], manager.pml4_addr());
+
+    let mut sorted = frames.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    assert_eq!(sorted.len(), frames.len());
+    for (i, &frame) in sorted.iter().enumerate() {
+        assert_eq!(frame, ARENA_PHYS_BASE + i as u64 * 4096);
+    }
+}
+
+/// Testa que as páginas do framebuffer recebem os bits de cache PWT/PCD
+#[test]
+fn test_paging_framebuffer_cache_flags() {
+    use ignite::memory::paging::FRAMEBUFFER_CACHE_FLAGS;
+    use paging_harness::*;
+
+    const HHDM: u64 = 0xFFFF_8000_0000_0000;
+    const FB_ADDR: u64 = 0x30_0000;
+
+    assert_eq!(FRAMEBUFFER_CACHE_FLAGS, PWT | PCD);
+
+    let mut allocator = MockFrameAllocator::new(16);
+    let mut manager = new_manager(&mut allocator);
+    let pml4 = manager.pml4_addr();
+
+    manager
+        .identity_map_range(0x40_0000, &mut allocator)
+        .unwrap();
+    manager.map_hhdm(0x40_0000, HHDM, &mut allocator).unwrap();
+
+    // 3 páginas e 1 byte: arredonda para 4 páginas
+    manager
+        .map_framebuffer(FB_ADDR, 3 * 4096 + 1, Some(HHDM), &mut allocator)
+        .unwrap();
+
+    for i in 0..4 {
+        let phys = FB_ADDR + i * 4096;
+        let expected = phys | PRESENT | WRITABLE | PWT | PCD;
+        assert_eq!(allocator.walk_4k(pml4, phys), expected);
+        assert_eq!(allocator.walk_4k(pml4, HHDM + phys), expected);
+    }
+
+    // Fora do framebuffer o split mantém o mapeamento write-back
+    let after = FB_ADDR + 4 * 4096;
+    assert_eq!(allocator.walk_4k(pml4, after), after | PRESENT | WRITABLE);
+    assert_eq!(
+        allocator.walk_4k(pml4, FB_ADDR - 4096),
+        (FB_ADDR - 4096) | PRESENT | WRITABLE
+    );
+}
+
+/// Testa `map_mode: direct`: HHDM completo, na metade inferior só o
+/// trampolim, e o BootInfo entregue em `rdi` pelo HHDM
+#[test]
+fn test_paging_direct_map_mode_keeps_only_trampoline() {
+    use ignite::{
+        config::{parser::Parser, MapMode},
+        core::handoff::{MAP_MODE_DIRECT, MAP_MODE_IDENTITY},
+        protos::redstone::{kernel_visible_addr, map_mode_bits, map_physical_memory, HHDM_BASE},
+    };
+    use paging_harness::*;
+
+    const MAP_LIMIT: u64 = 0x40_0000;
+    // Imagem do bootloader desalinhada: cobre as páginas 0x201000..0x204000
+    const TRAMPOLINE: (u64, u64) = (0x20_1800, 0x2000);
+
+    let config = Parser::new()
+        .parse("/Direto\n    path: boot():/forge\n    map_mode: direct\n")
+        .unwrap();
+    let mode = config.entries[0].map_mode;
+    assert_eq!(mode, MapMode::Direct);
+    assert_eq!(map_mode_bits(mode), MAP_MODE_DIRECT);
+    assert_eq!(
+        map_mode_bits(MapMode::Both),
+        MAP_MODE_IDENTITY | MAP_MODE_DIRECT
+    );
+
+    let mut allocator = MockFrameAllocator::new(16);
+    let mut manager = new_manager(&mut allocator);
+    let pml4 = manager.pml4_addr();
+    map_physical_memory(&mut manager, &mut allocator, mode, MAP_LIMIT, TRAMPOLINE).unwrap();
+
+    // HHDM cobre toda a memória com huge pages
+    for phys in [0, 0x20_0000] {
+        let (pd, idx) = allocator.walk_pd(pml4, HHDM_BASE + phys);
+        assert_eq!(allocator.table(pd)[idx], phys | PRESENT | WRITABLE | HUGE);
+    }
+
+    // Metade inferior: nada no primeiro 2 MiB, só o trampolim no segundo
+    let (pd, idx) = allocator.walk_pd(pml4, 0);
+    assert_eq!(allocator.table(pd)[idx], 0);
+    for page in (0x20_1000..0x20_4000).step_by(4096) {
+        assert_eq!(allocator.walk_4k(pml4, page), page | PRESENT | WRITABLE);
+    }
+    assert_eq!(allocator.walk_4k(pml4, 0x20_0000), 0);
+    assert_eq!(allocator.walk_4k(pml4, 0x20_4000), 0);
+
+    // `rdi` aponta para o BootInfo pelo HHDM, já que o físico não está
+    // mapeado; com identity map, continua físico
+    let boot_info = 0x20_0000;
+    let rdi = kernel_visible_addr(mode, boot_info);
+    assert_eq!(rdi, HHDM_BASE + boot_info);
+    let (pd, idx) = allocator.walk_pd(pml4, rdi);
+    assert_eq!(allocator.table(pd)[idx], boot_info | PRESENT | WRITABLE | HUGE);
+    assert_eq!(kernel_visible_addr(MapMode::Both, boot_info), boot_info);
+    assert_eq!(kernel_visible_addr(MapMode::Identity, boot_info), boot_info);
+
+    // Páginas avulsas de identity map (loader ELF, scratch) não são criadas
+    manager
+        .ensure_identity_map_4k(0x20_5000, &mut allocator)
+        .unwrap();
+    assert_eq!(allocator.walk_4k(pml4, 0x20_5000), 0);
+}
+
+/// Testa a degradação do Heap: usa o terceiro tamanho após duas falhas
+#[test]
+fn test_heap_init_with_fallback() {
+    use core::alloc::{GlobalAlloc, Layout};
+
+    use ignite::memory::BumpAllocator;
+
+    const REQUESTED: usize = 4 * 1024 * 1024;
+    const MIN: usize = 512 * 1024;
+
+    let mut backing = alloc::vec![0u8; REQUESTED / 4];
+    let backing_addr = backing.as_mut_ptr() as usize;
+    let mut attempts = Vec::new();
+
+    let heap = BumpAllocator::new();
+    let size = unsafe {
+        heap.init_with_fallback(REQUESTED, MIN, |size| {
+            attempts.push(size);
+            (attempts.len() == 3).then_some(backing_addr)
+        })
+    }
+    .unwrap();
+
+    assert_eq!(attempts, [REQUESTED, REQUESTED / 2, REQUESTED / 4]);
+    assert_eq!(size, REQUESTED / 4);
+    assert_eq!(heap.capacity(), REQUESTED / 4);
+
+    // O Heap aloca de dentro do bloco obtido, logo após o canário
+    let ptr = unsafe { heap.alloc(Layout::from_size_align(64, 8).unwrap()) };
+    assert_eq!(
+        ptr as usize,
+        backing_addr + ignite::memory::bump_allocator::HEAP_CANARY_SIZE
+    );
+
+    // Abaixo do mínimo, desiste
+    let empty = BumpAllocator::new();
+    assert!(unsafe { empty.init_with_fallback(REQUESTED, MIN, |_| None) }.is_err());
+    assert_eq!(empty.capacity(), 0);
+}
+
+/// Testa que `init` recusa um Heap de tamanho zero
+#[test]
+#[should_panic(expected = "menor que o canario")]
+fn test_heap_init_rejects_zero_size() {
+    let mut backing = alloc::vec![0u8; 64];
+    let heap = ignite::memory::BumpAllocator::new();
+    unsafe { heap.init(backing.as_mut_ptr() as usize, 0) };
+}
+
+/// Testa que `init` recusa limites que estouram o espaço de endereçamento
+#[test]
+#[should_panic(expected = "estoura o espaco de enderecamento")]
+fn test_heap_init_rejects_overflowing_bounds() {
+    let heap = ignite::memory::BumpAllocator::new();
+    unsafe { heap.init(usize::MAX - 0xFFF, 0x2000) };
+}
+
+/// Testa uma inicialização válida
+#[test]
+fn test_heap_init_valid() {
+    use core::alloc::{GlobalAlloc, Layout};
+
+    use ignite::memory::{bump_allocator::HEAP_CANARY_SIZE, BumpAllocator};
+
+    let mut backing = alloc::vec![0u64; 512];
+    let start = backing.as_mut_ptr() as usize;
+    let heap = BumpAllocator::new();
+    unsafe { heap.init(start, 4096) };
+    assert_eq!(heap.capacity(), 4096);
+
+    let layout = Layout::from_size_align(32, 8).unwrap();
+    let ptr = unsafe { heap.alloc(layout) };
+    assert_eq!(ptr as usize, start + HEAP_CANARY_SIZE);
+    unsafe { heap.dealloc(ptr, layout) };
+
+    // Com a arena vazia de novo, a próxima alocação volta ao início
+    let again = unsafe { heap.alloc(layout) };
+    assert_eq!(again, ptr);
+}
+
+/// Testa que um canário sobrescrito é detectado na primeira alocação
+#[test]
+#[should_panic(expected = "corrompido")]
+fn test_heap_detects_corrupted_canary() {
+    use core::alloc::{GlobalAlloc, Layout};
+
+    let mut backing = alloc::vec![0u64; 512];
+    let heap = ignite::memory::BumpAllocator::new();
+    unsafe { heap.init(backing.as_mut_ptr() as usize, 4096) };
+
+    // Escrita fora de qualquer alocação sobre o canário
+    backing[0] = 0;
+    unsafe { heap.alloc(Layout::from_size_align(32, 8).unwrap()) };
+}
+
+/// Testa alloc/free/realloc do `FreeListAllocator`: a memória liberada volta
+/// para a lista e o conteúdo sobrevive ao `realloc`
+#[test]
+fn test_freelist_alloc_free_realloc() {
+    use core::alloc::{GlobalAlloc, Layout};
+
+    use ignite::memory::FreeListAllocator;
+
+    let mut backing = alloc::vec![0u64; 512];
+    let start = backing.as_mut_ptr() as usize;
+    let heap = FreeListAllocator::new();
+
+    // Sem `init`, nada é alocado
+    let layout = Layout::from_size_align(64, 8).unwrap();
+    assert!(unsafe { heap.alloc(layout) }.is_null());
+
+    unsafe { heap.init(start, 4096) };
+    assert_eq!(heap.capacity(), 4096);
+    assert_eq!(heap.free_bytes(), 4096);
+
+    let a = unsafe { heap.alloc(layout) };
+    assert_eq!(a as usize, start);
+    unsafe { a.write_bytes(0xAB, 64) };
+    assert_eq!(heap.free_bytes(), 4096 - 64);
+
+    // Cresce preservando os 64 bytes originais
+    let grown = unsafe { heap.realloc(a, layout, 256) };
+    assert!(!grown.is_null());
+    let bytes = unsafe { core::slice::from_raw_parts(grown, 64) };
+    assert!(bytes.iter().all(|&b| b == 0xAB));
+    assert_eq!(heap.free_bytes(), 4096 - 256);
+
+    // Liberar tudo devolve a arena inteira, e ela pode ser usada de novo
+    unsafe { heap.dealloc(grown, Layout::from_size_align(256, 8).unwrap()) };
+    assert_eq!(heap.free_bytes(), 4096);
+    assert_eq!(heap.free_block_count(), 1);
+
+    let whole = Layout::from_size_align(4096, 8).unwrap();
+    let all = unsafe { heap.alloc(whole) };
+    assert_eq!(all as usize, start);
+    assert!(unsafe { heap.alloc(Layout::from_size_align(1, 1).unwrap()) }.is_null());
+    unsafe { heap.dealloc(all, whole) };
+}
+
+/// Testa a fusão de blocos liberados adjacentes, em qualquer ordem
+#[test]
+fn test_freelist_coalesces_adjacent_frees() {
+    use core::alloc::{GlobalAlloc, Layout};
+
+    use ignite::memory::FreeListAllocator;
+
+    let mut backing = alloc::vec![0u64; 512];
+    let heap = FreeListAllocator::new();
+    unsafe { heap.init(backing.as_mut_ptr() as usize, 4096) };
+
+    // Quatro blocos que ocupam a arena inteira
+    let layout = Layout::from_size_align(1024, 8).unwrap();
+    let blocks: Vec<*mut u8> = (0..4).map(|_| unsafe { heap.alloc(layout) }).collect();
+    assert!(blocks.iter().all(|b| !b.is_null()));
+    assert_eq!(heap.free_bytes(), 0);
+
+    // Blocos não adjacentes ficam separados
+    unsafe {
+        heap.dealloc(blocks[0], layout);
+        heap.dealloc(blocks[2], layout);
+    }
+    assert_eq!(heap.free_block_count(), 2);
+    let big = Layout::from_size_align(2048, 8).unwrap();
+    assert!(unsafe { heap.alloc(big) }.is_null());
+
+    // O bloco do meio funde os três vizinhos num só
+    unsafe { heap.dealloc(blocks[1], layout) };
+    assert_eq!(heap.free_block_count(), 1);
+    assert_eq!(heap.free_bytes(), 3072);
+
+    let merged = unsafe { heap.alloc(Layout::from_size_align(3072, 8).unwrap()) };
+    assert_eq!(merged, blocks[0]);
+
+    // Liberado por último o bloco de trás, volta a arena inteira
+    unsafe {
+        heap.dealloc(merged, Layout::from_size_align(3072, 8).unwrap());
+        heap.dealloc(blocks[3], layout);
+    }
+    assert_eq!(heap.free_block_count(), 1);
+    assert_eq!(heap.free_bytes(), 4096);
+}
+
+/// Testa pedidos de alinhamento: o endereço respeita o alinhamento, e a
+/// sobra antes dele continua utilizável
+#[test]
+fn test_freelist_alignment() {
+    use core::alloc::{GlobalAlloc, Layout};
+
+    use ignite::memory::{freelist::MIN_BLOCK_SIZE, FreeListAllocator};
+
+    let mut backing = alloc::vec![0u64; 1024];
+    let raw = backing.as_mut_ptr() as usize;
+    // Começa a arena logo depois de um limite de 1 KiB
+    let start = ((raw + 1023) & !1023) + MIN_BLOCK_SIZE;
+    let heap = FreeListAllocator::new();
+    unsafe { heap.init(start, 4096) };
+
+    let aligned = Layout::from_size_align(100, 1024).unwrap();
+    let ptr = unsafe { heap.alloc(aligned) };
+    assert!(!ptr.is_null());
+    assert_eq!(ptr as usize % 1024, 0);
+    assert!(ptr as usize >= start);
+
+    // O espaço antes do bloco alinhado vira um bloco livre próprio
+    let small = Layout::from_size_align(8, 8).unwrap();
+    let before = unsafe { heap.alloc(small) };
+    assert_eq!(before as usize, start);
+
+    // Tamanhos pequenos são arredondados para `MIN_BLOCK_SIZE`
+    let odd = Layout::from_size_align(3, 1).unwrap();
+    let p = unsafe { heap.alloc(odd) };
+    assert_eq!(p as usize % MIN_BLOCK_SIZE, 0);
+
+    unsafe {
+        heap.dealloc(p, odd);
+        heap.dealloc(before, small);
+        heap.dealloc(ptr, aligned);
+    }
+    assert_eq!(heap.free_block_count(), 1);
+    assert_eq!(heap.free_bytes(), heap.capacity());
+}
+
+/// Testa a detecção de regiões sobrepostas pelo `OverlapChecker`
+#[test]
+fn test_overlap_checker_detects_conflict() {
+    use ignite::{
+        core::error::{BootError, MemoryError},
+        memory::layout::OverlapChecker,
+    };
+
+    let mut layout = OverlapChecker::new();
+    layout.record("kernel", 0x20_0000, 0x10_0000).unwrap();
+
+    // Módulo começando no último byte do kernel
+    assert_eq!(
+        layout.record("module", 0x2F_F000, 0x2000),
+        Err(BootError::Memory(MemoryError::RegionOverlap))
+    );
+
+    // Região que engloba o kernel inteiro
+    assert!(layout.record("boot_info", 0x10_0000, 0x40_0000).is_err());
+
+    // Regiões rejeitadas não são registradas
+    assert_eq!(layout.regions().len(), 1);
+}
+
+/// Testa que regiões adjacentes (e vazias) são aceitas
+#[test]
+fn test_overlap_checker_accepts_adjacent() {
+    use ignite::memory::layout::OverlapChecker;
+
+    let mut layout = OverlapChecker::new();
+    layout.record("kernel", 0x20_0000, 0x10_0000).unwrap();
+    layout.record("module", 0x30_0000, 0x8000).unwrap();
+    layout.record("boot_info", 0x1F_F000, 0x1000).unwrap();
+    layout.record("cmdline", 0x30_0000, 0).unwrap();
+
+    let names: Vec<&str> = layout.regions().iter().map(|r| r.name).collect();
+    assert_eq!(names, ["kernel", "module", "boot_info"]);
+}
+
+/// Boot Services simulados em que `ExitBootServices` sempre falha.
+#[derive(Default)]
+struct FailingExit {
+    keys:       usize,
+    exits:      Vec<usize>,
+    lines:      Vec<alloc::string::String>,
+    delayed_us: usize,
+    resets:     usize,
+}
+
+impl ignite::memory::exit::BootExit for FailingExit {
+    fn memory_map_key(&mut self) -> (usize, usize) {
+        self.keys += 1;
+        (0x40 + self.keys, 37)
+    }
+
+    fn exit(&mut self, map_key: usize) -> ignite::uefi::Status {
+        self.exits.push(map_key);
+        ignite::uefi::Status::INVALID_PARAMETER
+    }
+
+    fn diagnostic(&mut self, line: &str) {
+        self.lines.push(line.into());
+    }
+
+    fn delay(&mut self, microseconds: usize) {
+        self.delayed_us += microseconds;
+    }
+
+    fn reset(&mut self) {
+        self.resets += 1;
+    }
+}
+
+/// Testa o diagnóstico e o reset quando `ExitBootServices` falha sempre
+#[test]
+fn test_exit_boot_services_failure_resets() {
+    use ignite::memory::exit::{
+        exit_boot_services_or_reset, EXIT_ATTEMPTS, EXIT_FAILURE_RESET_DELAY_US,
+    };
+
+    let mut boot = FailingExit::default();
+    assert!(!exit_boot_services_or_reset(&mut boot));
+
+    // Cada tentativa relê a chave do mapa
+    assert_eq!(boot.exits.len(), EXIT_ATTEMPTS);
+    assert_eq!(boot.exits[0], 0x41);
+    assert_eq!(boot.exits[1], 0x42);
+
+    // Diagnóstico com a última chave, descritores e status
+    let line = &boot.lines[0];
+    assert!(line.contains("map_key=0x42"));
+    assert!(line.contains("descritores=37"));
+    assert!(line.contains("INVALID_PARAMETER"));
+
+    assert_eq!(boot.delayed_us, EXIT_FAILURE_RESET_DELAY_US);
+    assert_eq!(boot.resets, 1);
+}
+
+/// Testa a verificação de intervalos de carga contra o mapa de memória
+#[test]
+fn test_check_range_usable() {
+    use ignite::{
+        core::error::{BootError, MemoryError},
+        memory::{
+            map::check_range_usable,
+            region::{MemoryRegionKind, PhysicalMemoryRegion},
+        },
+    };
+
+    fn region(start: u64, pages: usize, kind: MemoryRegionKind) -> PhysicalMemoryRegion {
+        PhysicalMemoryRegion {
+            start,
+            page_count: pages,
+            kind,
+        }
+    }
+
+    // 1 MiB..2 MiB utilizável em dois pedaços contíguos, depois firmware
+    let map = [
+        region(0x10_0000, 128, MemoryRegionKind::Usable),
+        region(0x18_0000, 128, MemoryRegionKind::Usable),
+        region(0x20_0000, 16, MemoryRegionKind::Reserved),
+        region(0x21_0000, 16, MemoryRegionKind::Usable),
+    ];
+
+    // Kernel a.out em 1 MiB com BSS atravessando a fronteira entre regiões
+    assert!(check_range_usable(map, 0x10_0000, 0x19_0000).is_ok());
+
+    // BSS invade a região reservada
+    assert_eq!(
+        check_range_usable(map, 0x1F_0000, 0x20_8000),
+        Err(BootError::Memory(MemoryError::RegionOverlap))
+    );
+
+    // Além do fim do mapa
+    assert_eq!(
+        check_range_usable(map, 0x21_0000, 0x30_0000),
+        Err(BootError::Memory(MemoryError::InvalidAddress))
+    );
+
+    // Intervalo vazio
+    assert!(check_range_usable(map, 0x10_0000, 0x10_0000).is_err());
+}
+
+/// Testa que o endereço físico máximo ignora MMIO acima da RAM
+#[test]
+fn test_max_usable_phys_ignores_mmio() {
+    use ignite::{
+        core::handoff::{MemoryMapEntry, MemoryType},
+        memory::map::max_usable_phys,
+    };
+
+    let entry = |base: u64, len: u64, typ: MemoryType| MemoryMapEntry { base, len, typ };
+
+    let map = [
+        entry(0x0, 0x9_F000, MemoryType::Usable),
+        entry(0x10_0000, 0x7FF0_0000, MemoryType::Usable),
+        entry(0x8000_0000, 0x10_0000, MemoryType::AcpiReclaimable),
+        entry(0x1_0000_0000, 0x4000_0000, MemoryType::Usable),
+        // Framebuffer e janela PCI muito acima da RAM
+        entry(0x2_0000_0000, 0x100_0000, MemoryType::Framebuffer),
+        entry(0xFD_0000_0000, 0x1000_0000, MemoryType::Reserved),
+    ];
+
+    assert_eq!(max_usable_phys(&map), 0x1_4000_0000);
+
+    // Sem RAM livre não há limite
+    assert_eq!(max_usable_phys(&map[4..]), 0);
+    assert_eq!(max_usable_phys(&[]), 0);
+}
+
+/// Testa `min_memory`: 8 GB exigidos contra mapas com 4 GB e 16 GB de RAM
+#[test]
+fn test_min_memory_against_usable_ram() {
+    use ignite::{
+        config::parser::{parse_size, Parser},
+        core::{
+            error::{BootError, MemoryError},
+            handoff::{MemoryMapEntry, MemoryType},
+        },
+        memory::map::{check_min_memory, usable_ram},
+    };
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+    let entry = |base: u64, len: u64, typ: MemoryType| MemoryMapEntry { base, len, typ };
+
+    let config = Parser::new()
+        .parse("/Grande\n    path: boot():/forge\n    min_memory: 8G\n")
+        .unwrap();
+    let required = config.entries[0].min_memory;
+    assert_eq!(required, Some(8 * GIB));
+
+    // 4 GB de RAM, com um buraco reservado que não conta
+    let small = [
+        entry(0, 2 * GIB, MemoryType::Usable),
+        entry(2 * GIB, GIB, MemoryType::Reserved),
+        entry(3 * GIB, 2 * GIB, MemoryType::Usable),
+    ];
+    assert_eq!(usable_ram(&small), 4 * GIB);
+    assert_eq!(
+        check_min_memory(usable_ram(&small), required),
+        Err(BootError::Memory(MemoryError::OutOfMemory))
+    );
+
+    let large = [
+        entry(0, 3 * GIB, MemoryType::Usable),
+        entry(4 * GIB, 13 * GIB, MemoryType::Usable),
+    ];
+    assert_eq!(usable_ram(&large), 16 * GIB);
+    assert_eq!(check_min_memory(usable_ram(&large), required), Ok(()));
+
+    // Sem `min_memory` nada é exigido
+    assert_eq!(check_min_memory(0, None), Ok(()));
+
+    assert_eq!(parse_size("512M"), Some(512 * 1024 * 1024));
+    assert_eq!(parse_size("64k"), Some(64 * 1024));
+    assert_eq!(parse_size("8GB"), Some(8 * GIB));
+    assert_eq!(parse_size("4096"), Some(4096));
+    assert_eq!(parse_size("8T"), None);
+    assert_eq!(parse_size("G"), None);
+}
+
+/// Testa a linha CSV de `dump_memory_map: csv`
+#[test]
+fn test_memory_map_csv_row() {
+    use ignite::{
+        config::{parser::Parser, MemoryMapDump},
+        core::handoff::{MemoryMapEntry, MemoryType},
+        memory::map::{CsvRow, CSV_HEADER},
+    };
+
+    let entry = MemoryMapEntry {
+        base: 0x10_0000,
+        len:  0x7F0_0000,
+        typ:  MemoryType::Usable,
+    };
+    assert_eq!(
+        alloc::format!("{}", CsvRow(&entry)),
+        "0x100000,0x7F00000,Usable"
+    );
+
+    let reserved = MemoryMapEntry {
+        base: 0xFEE0_0000,
+        len:  0x1000,
+        typ:  MemoryType::Reserved,
+    };
+    assert_eq!(
+        alloc::format!("{}", CsvRow(&reserved)),
+        "0xFEE00000,0x1000,Reserved"
+    );
+    assert_eq!(CSV_HEADER, "base,len,type");
+
+    let config = Parser::new().parse("dump_memory_map: csv\n").unwrap();
+    assert_eq!(config.dump_memory_map, MemoryMapDump::Csv);
+}
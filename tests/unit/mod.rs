@@ -4,8 +4,16 @@
 #![cfg(test)]
 
 // Re-export dos módulos de teste
+pub mod arch_tests;
 pub mod config_tests;
+pub mod core_tests;
 pub mod elf_tests;
 pub mod fs_tests;
+pub mod hardware_tests;
 pub mod memory_tests;
+pub mod protos_tests;
+pub mod recovery_tests;
 pub mod security_tests;
+pub mod uefi_tests;
+pub mod ui_tests;
+pub mod video_tests;
@@ -0,0 +1,596 @@
+//! Testes Unitários para os protocolos de boot
+//!
+//! Testa a preparação dos dados de handoff sobre frames simulados em RAM.
+
+#![no_std]
+#![cfg(test)]
+
+extern crate alloc;
+
+use core::alloc::Layout;
+
+use ignite::{
+    config::{parser::Parser, Protocol},
+    core::error::Result,
+    memory::FrameAllocator,
+    protos::{redstone::stage_cmdline, resolve_protocol},
+};
+
+const PAGE_SIZE: usize = 4096;
+
+/// Alocador de frames sobre um bloco de RAM alinhado à página.
+struct MockFrameAllocator {
+    base:      *mut u8,
+    pages:     usize,
+    next_page: usize,
+}
+
+impl MockFrameAllocator {
+    fn new(pages: usize) -> Self {
+        let layout = Layout::from_size_align(pages * PAGE_SIZE, PAGE_SIZE).unwrap();
+        let base = unsafe { alloc::alloc::alloc_zeroed(layout) };
+        assert!(!base.is_null());
+        Self {
+            base,
+            pages,
+            next_page: 0,
+        }
+    }
+}
+
+impl Drop for MockFrameAllocator {
+    fn drop(&mut self) {
+        let layout = Layout::from_size_align(self.pages * PAGE_SIZE, PAGE_SIZE).unwrap();
+        unsafe { alloc::alloc::dealloc(self.base, layout) };
+    }
+}
+
+impl FrameAllocator for MockFrameAllocator {
+    fn allocate_frame(&mut self, count: usize) -> Result<u64> {
+        assert!(
+            self.next_page + count <= self.pages,
+            "mock sem frames livres"
+        );
+        let addr = self.base as u64 + (self.next_page * PAGE_SIZE) as u64;
+        self.next_page += count;
+        Ok(addr)
+    }
+
+    fn allocate_at(&mut self, addr: u64, _count: usize) -> Result<u64> {
+        Ok(addr)
+    }
+}
+
+/// Testa a cópia da cmdline com NUL final para um frame próprio
+#[test]
+fn test_stage_cmdline_copies_with_nul() {
+    let mut allocator = MockFrameAllocator::new(2);
+    let cmdline = "root=/dev/rfs0 quiet";
+    assert_eq!(cmdline.len(), 20);
+
+    let (addr, len) = stage_cmdline(&mut allocator, Some(cmdline)).unwrap();
+
+    assert_eq!(addr, allocator.base as u64);
+    assert_eq!(addr % PAGE_SIZE as u64, 0);
+    assert_eq!(len, 20);
+    assert_eq!(allocator.next_page, 1);
+
+    let copied = unsafe { core::slice::from_raw_parts(addr as *const u8, 21) };
+    assert_eq!(&copied[..20], cmdline.as_bytes());
+    assert_eq!(copied[20], 0);
+}
+
+/// Testa que cmdline ausente ou vazia resulta em endereço e tamanho zero
+#[test]
+fn test_stage_cmdline_none_yields_zero() {
+    let mut allocator = MockFrameAllocator::new(1);
+
+    assert_eq!(stage_cmdline(&mut allocator, None).unwrap(), (0, 0));
+    assert_eq!(stage_cmdline(&mut allocator, Some("")).unwrap(), (0, 0));
+    assert_eq!(allocator.next_page, 0);
+}
+
+/// Testa a cópia do nome e versão do bootloader para o handoff
+#[test]
+fn test_stage_loader_name() {
+    use ignite::{core::config::meta, protos::redstone::stage_loader_name};
+
+    let mut allocator = MockFrameAllocator::new(1);
+    let addr = stage_loader_name(&mut allocator).unwrap();
+    assert_eq!(addr, allocator.base as u64);
+
+    let expected = alloc::format!("Ignite {}", meta::VERSION);
+    let len = expected.len();
+    let copied = unsafe { core::slice::from_raw_parts(addr as *const u8, len + 1) };
+    assert_eq!(&copied[..len], expected.as_bytes());
+    assert_eq!(copied[len], 0);
+
+    // Versão numérica coerente com a string
+    let mut parts = meta::VERSION.split('.').map(|p| p.parse::<u64>().unwrap());
+    let code =
+        (parts.next().unwrap() << 32) | (parts.next().unwrap() << 16) | parts.next().unwrap();
+    assert_eq!(meta::VERSION_CODE, code);
+}
+
+/// Testa que cada módulo recebe um registro apontando para a própria cmdline
+#[test]
+fn test_stage_modules_with_cmdlines() {
+    use ignite::{
+        core::{handoff::ModuleInfo, types::LoadedFile},
+        protos::redstone::stage_modules,
+    };
+
+    let mut allocator = MockFrameAllocator::new(4);
+    let modules = [
+        LoadedFile {
+            ptr:     0x10_0000,
+            size:    0x2000,
+            cmdline: Some(alloc::string::String::from("initrd")),
+        },
+        LoadedFile {
+            ptr:     0x20_0000,
+            size:    0x300,
+            cmdline: Some(alloc::string::String::from("microcode")),
+        },
+    ];
+
+    let (addr, count) = stage_modules(&mut allocator, &modules).unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(addr % PAGE_SIZE as u64, 0);
+
+    let records = unsafe { core::slice::from_raw_parts(addr as *const ModuleInfo, 2) };
+    for (record, (module, expected)) in records
+        .iter()
+        .zip(modules.iter().zip(["initrd", "microcode"]))
+    {
+        assert_eq!(record.addr, module.ptr);
+        assert_eq!(record.size, module.size as u64);
+        assert_eq!(record.cmdline_len, expected.len() as u64);
+
+        let copied = unsafe {
+            core::slice::from_raw_parts(record.cmdline_addr as *const u8, expected.len() + 1)
+        };
+        assert_eq!(&copied[..expected.len()], expected.as_bytes());
+        assert_eq!(copied[expected.len()], 0);
+    }
+    assert_ne!(records[0].cmdline_addr, records[1].cmdline_addr);
+
+    // Sem módulos, nada é alocado
+    assert_eq!(stage_modules(&mut allocator, &[]).unwrap(), (0, 0));
+}
+
+/// Testa que o array de framebuffers do handoff começa pelo primário e não
+/// repete saídas com o mesmo endereço
+#[test]
+fn test_stage_framebuffers_primary_first() {
+    use ignite::{
+        core::handoff::{FramebufferInfo, PixelFormat},
+        protos::redstone::stage_framebuffers,
+    };
+
+    let fb = |addr, width| FramebufferInfo {
+        addr,
+        size: (width * 48 * 4) as u64,
+        width,
+        height: 48,
+        stride: width,
+        format: PixelFormat::Bgr,
+        masks: Default::default(),
+    };
+    let mut allocator = MockFrameAllocator::new(4);
+    let primary = fb(0x8000_0000, 64);
+    let extra = [fb(0x9000_0000, 128), fb(0x8000_0000, 64)];
+
+    let (addr, count) = stage_framebuffers(&mut allocator, primary, &extra).unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(addr % PAGE_SIZE as u64, 0);
+
+    let records = unsafe { core::slice::from_raw_parts(addr as *const FramebufferInfo, 2) };
+    assert_eq!((records[0].addr, records[0].width), (0x8000_0000, 64));
+    assert_eq!((records[1].addr, records[1].width), (0x9000_0000, 128));
+
+    // Sem framebuffer algum, nada é alocado
+    assert_eq!(
+        stage_framebuffers(&mut allocator, fb(0, 64), &[]).unwrap(),
+        (0, 0)
+    );
+}
+
+/// Testa que duas linhas `param` chegam ao buffer do handoff como pares
+/// `chave\0valor\0`, na ordem do arquivo
+#[test]
+fn test_stage_params_from_config() {
+    use ignite::protos::redstone::{serialize_params, stage_params};
+
+    let config = Parser::new()
+        .parse(
+            "/Redstone\n    path: boot():/forge\n    param: console=ttyS0\n    param: \
+             init.level = 3\n    param: sem_igual\n",
+        )
+        .unwrap();
+    let params = &config.entries[0].params;
+    assert_eq!(params.len(), 2);
+
+    let expected = b"console\0ttyS0\0init.level\x003\0";
+    assert_eq!(serialize_params(params), expected);
+
+    let mut allocator = MockFrameAllocator::new(1);
+    let (addr, count) = stage_params(&mut allocator, params).unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(addr % PAGE_SIZE as u64, 0);
+    let staged = unsafe { core::slice::from_raw_parts(addr as *const u8, expected.len()) };
+    assert_eq!(staged, expected);
+
+    // Sem parâmetros, nada é alocado
+    assert_eq!(stage_params(&mut allocator, &[]).unwrap(), (0, 0));
+    assert_eq!(allocator.next_page, 1);
+}
+
+/// Testa que as opções de `BootInfo` de uma entrada saem do arquivo de
+/// configuração e que só as fora do padrão são listadas para o aviso
+#[test]
+fn test_redstone_options_customized() {
+    use ignite::{config::MapMode, protos::redstone::RedstoneOptions};
+
+    let config = Parser::new()
+        .parse(
+            "/Padrao\n    path: boot():/forge\n/Custom\n    path: boot():/forge\n    \
+             map_mode: direct\n    param: console=ttyS0\n",
+        )
+        .unwrap();
+
+    let default = RedstoneOptions::from_entry(&config.entries[0], &[]);
+    assert_eq!(default.customized().count(), 0);
+
+    let custom = RedstoneOptions::from_entry(&config.entries[1], &[]);
+    assert_eq!(custom.map_mode, MapMode::Direct);
+    assert_eq!(custom.params.len(), 1);
+    assert!(custom.customized().eq(["map_mode", "param"]));
+}
+
+/// `BootInfo` zerado com magic e versão informados.
+fn boot_info_with(magic: u64, version: u32) -> ignite::core::handoff::BootInfo {
+    // SAFETY: BootInfo só tem inteiros e um enum cujo valor 0 é válido (Rgb).
+    let mut info: ignite::core::handoff::BootInfo = unsafe { core::mem::zeroed() };
+    info.magic = magic;
+    info.version = version;
+    info
+}
+
+/// Testa a validação de magic e versão do `BootInfo`
+#[test]
+fn test_boot_info_validate() {
+    use ignite::core::handoff::{HandoffError, BOOT_INFO_MAGIC, BOOT_INFO_VERSION};
+
+    assert_eq!(
+        boot_info_with(BOOT_INFO_MAGIC, BOOT_INFO_VERSION).validate(),
+        Ok(())
+    );
+
+    assert_eq!(
+        boot_info_with(0xDEAD_BEEF, BOOT_INFO_VERSION).validate(),
+        Err(HandoffError::InvalidMagic(0xDEAD_BEEF))
+    );
+
+    assert_eq!(
+        boot_info_with(BOOT_INFO_MAGIC, BOOT_INFO_VERSION - 1).validate(),
+        Err(HandoffError::VersionTooOld {
+            found:    BOOT_INFO_VERSION - 1,
+            required: BOOT_INFO_VERSION,
+        })
+    );
+}
+
+/// Testa a compatibilidade com a versão mínima exigida pelo kernel
+#[test]
+fn test_boot_info_is_compatible() {
+    use ignite::core::handoff::{BOOT_INFO_MAGIC, BOOT_INFO_VERSION};
+
+    let current = boot_info_with(BOOT_INFO_MAGIC, BOOT_INFO_VERSION);
+    assert!(current.is_compatible(BOOT_INFO_VERSION));
+    assert!(current.is_compatible(3));
+
+    // Versão antiga demais para o kernel
+    let old = boot_info_with(BOOT_INFO_MAGIC, 4);
+    assert!(!old.is_compatible(5));
+
+    // Magic inválido nunca é compatível
+    assert!(!boot_info_with(0, BOOT_INFO_VERSION).is_compatible(1));
+}
+
+/// Testa o cabeçalho autodescritivo gravado antes do `BootInfo`
+#[test]
+fn test_handoff_header_precedes_boot_info() {
+    use core::mem::{offset_of, size_of};
+
+    use ignite::core::handoff::{
+        BootInfo, HandoffBlock, HandoffHeader, BOOT_INFO_FIELD_COUNT, BOOT_INFO_MAGIC,
+        BOOT_INFO_VERSION, HANDOFF_HEADER_MAGIC,
+    };
+
+    let block = HandoffBlock::new(boot_info_with(BOOT_INFO_MAGIC, BOOT_INFO_VERSION));
+    let header = block.header;
+
+    // O cabeçalho vem primeiro e o BootInfo começa logo após ele
+    assert_eq!(offset_of!(HandoffBlock, header), 0);
+    assert_eq!(offset_of!(HandoffBlock, info), size_of::<HandoffHeader>());
+    assert_eq!(header.header_size as usize, size_of::<HandoffHeader>());
+    assert_eq!(size_of::<HandoffHeader>() % 8, 0);
+
+    assert_eq!(
+        header.total_size as usize,
+        size_of::<HandoffHeader>() + size_of::<BootInfo>()
+    );
+    assert_eq!(header.magic, HANDOFF_HEADER_MAGIC);
+    assert_eq!(header.version, BOOT_INFO_VERSION);
+    assert_eq!(header.field_count, BOOT_INFO_FIELD_COUNT);
+    assert_eq!(&header.magic.to_be_bytes(), b"IGNHDOFF");
+
+    // Lido como bytes brutos (como num dump), o magic abre o bloco
+    // SAFETY: HandoffBlock é repr(C) e só contém dados simples.
+    let bytes = unsafe {
+        core::slice::from_raw_parts(
+            &block as *const HandoffBlock as *const u8,
+            size_of::<HandoffBlock>(),
+        )
+    };
+    assert_eq!(bytes[..8], HANDOFF_HEADER_MAGIC.to_le_bytes());
+    let info_start = header.header_size as usize;
+    assert_eq!(
+        bytes[info_start..info_start + 8],
+        BOOT_INFO_MAGIC.to_le_bytes()
+    );
+}
+
+/// Cabeçalho ELF mínimo (só o magic importa para a detecção).
+const ELF_KERNEL: &[u8] = b"\x7fELF\x02\x01\x01\0\0\0\0\0\0\0\0\0";
+
+/// Testa que o `protocol:` explícito vence a detecção pelo binário
+#[test]
+fn test_explicit_protocol_overrides_detection() {
+    let config = Parser::new()
+        .parse("/MB\n    protocol: multiboot2\n    kernel_path: boot():/k\n")
+        .unwrap();
+    let entry = &config.entries[0];
+
+    assert_eq!(
+        resolve_protocol(entry.protocol, ELF_KERNEL, true).unwrap(),
+        Protocol::Multiboot2
+    );
+    assert_eq!(
+        resolve_protocol(entry.protocol, ELF_KERNEL, false).unwrap(),
+        Protocol::Multiboot2
+    );
+
+    // Sem protocol: a detecção escolhe pelo magic ELF
+    assert_eq!(
+        resolve_protocol(Protocol::Unknown, ELF_KERNEL, true).unwrap(),
+        Protocol::Redstone
+    );
+}
+
+/// Testa que `protocol_auto_detect: no` rejeita entradas sem protocolo
+#[test]
+fn test_auto_detect_disabled_requires_protocol() {
+    let config = Parser::new()
+        .parse("protocol_auto_detect: no\n/Sem\n    kernel_path: boot():/k\n")
+        .unwrap();
+    assert!(!config.protocol_auto_detect);
+
+    let entry = &config.entries[0];
+    assert_eq!(entry.protocol, Protocol::Unknown);
+    assert!(resolve_protocol(entry.protocol, ELF_KERNEL, config.protocol_auto_detect).is_err());
+}
+
+/// Testa que a System Table só é entregue com `pass_system_table: yes`
+#[test]
+fn test_pass_system_table_handoff() {
+    use ignite::{protos::redstone::efi_system_table_addr, uefi};
+
+    let config = Parser::new()
+        .parse(
+            "/Com Tabela\n    path: boot():/forge\n    pass_system_table: yes\n/Sem Tabela\n    \
+             path: boot():/forge\n",
+        )
+        .unwrap();
+    assert!(config.entries[0].pass_system_table);
+    assert!(!config.entries[1].pass_system_table);
+
+    // Endereço que o firmware teria passado ao `efi_main`
+    let system_table = 0x7F6B_E018;
+    assert_eq!(
+        efi_system_table_addr(config.entries[0].pass_system_table, system_table),
+        system_table
+    );
+    assert_eq!(
+        efi_system_table_addr(config.entries[1].pass_system_table, system_table),
+        0
+    );
+
+    // Antes de `uefi::init` não há tabela global a entregar
+    assert_eq!(uefi::system_table_addr(), 0);
+    assert_eq!(efi_system_table_addr(true, uefi::system_table_addr()), 0);
+}
+
+mod text_console {
+    use ignite::uefi::{
+        base::{Boolean, Char16},
+        table::system::{SimpleTextOutputMode, SimpleTextOutputProtocol},
+        Status,
+    };
+
+    extern "efiapi" fn reset(_: *mut SimpleTextOutputProtocol, _: Boolean) -> Status {
+        Status::SUCCESS
+    }
+
+    extern "efiapi" fn output(_: *mut SimpleTextOutputProtocol, _: *const Char16) -> Status {
+        Status::SUCCESS
+    }
+
+    extern "efiapi" fn with_arg(_: *mut SimpleTextOutputProtocol, _: usize) -> Status {
+        Status::SUCCESS
+    }
+
+    extern "efiapi" fn clear(_: *mut SimpleTextOutputProtocol) -> Status {
+        Status::SUCCESS
+    }
+
+    extern "efiapi" fn cursor(_: *mut SimpleTextOutputProtocol, _: usize, _: usize) -> Status {
+        Status::SUCCESS
+    }
+
+    /// Modos 0 (80x25) e 2 (100x31); os demais não existem.
+    extern "efiapi" fn query_mode(
+        _: *mut SimpleTextOutputProtocol,
+        mode: usize,
+        columns: *mut usize,
+        rows: *mut usize,
+    ) -> Status {
+        let (c, r) = match mode {
+            0 => (80, 25),
+            2 => (100, 31),
+            _ => return Status::UNSUPPORTED,
+        };
+        unsafe {
+            *columns = c;
+            *rows = r;
+        }
+        Status::SUCCESS
+    }
+
+    pub fn mock_con_out(mode: *mut SimpleTextOutputMode) -> SimpleTextOutputProtocol {
+        SimpleTextOutputProtocol {
+            reset,
+            output_string: output,
+            test_string: output,
+            query_mode,
+            set_mode: with_arg,
+            set_attribute: with_arg,
+            clear_screen: clear,
+            set_cursor_position: cursor,
+            enable_cursor: reset,
+            mode,
+        }
+    }
+}
+
+/// Testa a captura de linhas, colunas e cursor do ConOut para o `BootInfo`
+#[test]
+fn test_capture_text_console_state() {
+    use ignite::{
+        core::handoff::TextConsoleInfo,
+        protos::redstone::capture_text_console,
+        uefi::table::system::SimpleTextOutputMode,
+    };
+
+    let mut mode = SimpleTextOutputMode {
+        max_mode:       3,
+        mode:           2,
+        attribute:      0x07,
+        cursor_column:  17,
+        cursor_row:     9,
+        cursor_visible: 1,
+    };
+    let mut con_out = text_console::mock_con_out(&mut mode);
+
+    let info = unsafe { capture_text_console(&mut con_out) };
+    assert_eq!(
+        info,
+        TextConsoleInfo {
+            columns:       100,
+            rows:          31,
+            cursor_column: 17,
+            cursor_row:    9,
+        }
+    );
+
+    // Modo que o firmware não sabe descrever: nada é entregue
+    mode.mode = 1;
+    let mut con_out = text_console::mock_con_out(&mut mode);
+    assert_eq!(
+        unsafe { capture_text_console(&mut con_out) },
+        TextConsoleInfo::default()
+    );
+    assert_eq!(
+        unsafe { capture_text_console(core::ptr::null_mut()) },
+        TextConsoleInfo::default()
+    );
+
+    let config = Parser::new()
+        .parse("/Texto\n    path: boot():/forge\n    pass_text_console: yes\n")
+        .unwrap();
+    assert!(config.entries[0].pass_text_console);
+}
+
+/// Cabeçalho ELF64 little endian mínimo com o `e_machine` dado.
+fn elf_with_machine(e_machine: u16) -> [u8; 64] {
+    let mut elf = [0u8; 64];
+    elf[..4].copy_from_slice(b"\x7fELF");
+    elf[4] = 2; // ELFCLASS64
+    elf[5] = 1; // ELFDATA2LSB
+    elf[16..18].copy_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+    elf[18..20].copy_from_slice(&e_machine.to_le_bytes());
+    elf
+}
+
+/// Testa que um kernel x86_64 passa e um aarch64 é recusado com um erro que
+/// nomeia as duas arquiteturas
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn test_kernel_arch_mismatch_rejected() {
+    use ignite::{
+        core::error::{BootError, ProtocolError},
+        protos::arch::{check_kernel_arch, kernel_arch, KernelArch},
+    };
+
+    let x86_64 = elf_with_machine(62);
+    let aarch64 = elf_with_machine(183);
+    assert_eq!(
+        kernel_arch(Protocol::Redstone, &x86_64),
+        Some(KernelArch::X86_64)
+    );
+    assert!(check_kernel_arch(Protocol::Redstone, &x86_64).is_ok());
+
+    let err = check_kernel_arch(Protocol::Redstone, &aarch64).unwrap_err();
+    assert_eq!(
+        err,
+        BootError::Protocol(ProtocolError::ArchMismatch {
+            kernel: KernelArch::Aarch64,
+            cpu:    KernelArch::X86_64,
+        })
+    );
+    let message = alloc::format!("{}", err);
+    assert!(
+        message.contains("aarch64") && message.contains("x86_64"),
+        "{}",
+        message
+    );
+
+    // Image de ARM64 pelo protocolo Linux: magic "ARM\x64" em 0x38
+    let mut image = [0u8; 0x40];
+    image[0x38..0x3C].copy_from_slice(b"ARM\x64");
+    assert!(check_kernel_arch(Protocol::Linux, &image).is_err());
+
+    // Multiboot2 i386 roda numa CPU x86_64
+    let mut mb2 = [0u8; 32];
+    mb2[8..12].copy_from_slice(&0xE852_50D6u32.to_le_bytes());
+    assert_eq!(
+        kernel_arch(Protocol::Multiboot2, &mb2),
+        Some(KernelArch::X86)
+    );
+    assert!(check_kernel_arch(Protocol::Multiboot2, &mb2).is_ok());
+
+    // Sem arquitetura reconhecível, a decisão fica com o loader
+    assert!(check_kernel_arch(Protocol::Redstone, b"nada").is_ok());
+
+    let config = Parser::new()
+        .parse("verify_kernel_arch: no\n/Redstone\n    path: boot():/forge\n")
+        .unwrap();
+    assert!(!config.verify_kernel_arch);
+    assert!(
+        Parser::new()
+            .parse("/Redstone\n    path: boot():/forge\n")
+            .unwrap()
+            .verify_kernel_arch
+    );
+}
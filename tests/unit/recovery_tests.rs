@@ -0,0 +1,702 @@
+//! Testes Unitários para o módulo de recuperação
+//!
+//! Testa a persistência do estado de boot sobre um armazenamento de variáveis
+//! simulado.
+
+#![no_std]
+#![cfg(test)]
+
+extern crate alloc;
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+use ignite::{
+    config::{types::Module, Entry, MapMode, Protocol},
+    core::error::{BootError, FileSystemError, Result},
+    fs::{Directory, File, FileSystem, Metadata},
+    recovery::{
+        selftest::{self, SelfTestOs, Subsystem},
+        state::{
+            get_last_good_in, set_boot_once_in, set_last_good_in, take_boot_once_in, VariableStore,
+            LAST_GOOD_ATTR, LAST_GOOD_VAR_NAME, MAX_LAST_GOOD_LEN,
+        },
+    },
+    uefi::{
+        base::Guid,
+        table::runtime::{VARIABLE_BOOTSERVICE_ACCESS, VARIABLE_NON_VOLATILE},
+    },
+};
+
+/// NVRAM simulada: guarda (nome, atributos, dados) em memória.
+#[derive(Default)]
+struct MockVariableStore {
+    vars: Vec<(String, u32, Vec<u8>)>,
+}
+
+impl VariableStore for MockVariableStore {
+    fn read(&self, name: &str, _guid: &Guid, buf: &mut [u8]) -> Option<usize> {
+        let (_, _, data) = self.vars.iter().find(|(n, _, _)| n == name)?;
+        if data.len() > buf.len() {
+            return None;
+        }
+        buf[..data.len()].copy_from_slice(data);
+        Some(data.len())
+    }
+
+    fn write(&mut self, name: &str, _guid: &Guid, attributes: u32, data: &[u8]) -> Result<()> {
+        self.vars.retain(|(n, _, _)| n != name);
+        self.vars
+            .push((String::from(name), attributes, data.to_vec()));
+        Ok(())
+    }
+}
+
+/// Testa ida e volta do nome da última entrada válida
+#[test]
+fn test_last_good_round_trip() {
+    let mut store = MockVariableStore::default();
+    assert_eq!(get_last_good_in(&store), None);
+
+    set_last_good_in(&mut store, "Redstone OS").unwrap();
+    assert_eq!(get_last_good_in(&store).as_deref(), Some("Redstone OS"));
+
+    // Sobrescrever mantém apenas o valor mais recente
+    set_last_good_in(&mut store, "Redstone OS (Fallback)").unwrap();
+    assert_eq!(
+        get_last_good_in(&store).as_deref(),
+        Some("Redstone OS (Fallback)")
+    );
+    assert_eq!(store.vars.len(), 1);
+}
+
+/// Testa que a variável é gravada como NV + BootService, sem acesso em runtime
+#[test]
+fn test_last_good_attributes() {
+    let mut store = MockVariableStore::default();
+    set_last_good_in(&mut store, "Linux").unwrap();
+
+    let (name, attr, _) = &store.vars[0];
+    assert_eq!(name, LAST_GOOD_VAR_NAME);
+    assert_eq!(*attr, LAST_GOOD_ATTR);
+    assert_eq!(*attr, VARIABLE_NON_VOLATILE | VARIABLE_BOOTSERVICE_ACCESS);
+}
+
+/// Testa rejeição de nomes vazios ou longos demais
+#[test]
+fn test_last_good_invalid_names() {
+    let mut store = MockVariableStore::default();
+    let long_name: String = core::iter::repeat_n('x', MAX_LAST_GOOD_LEN + 1).collect();
+
+    assert!(set_last_good_in(&mut store, "").is_err());
+    assert!(set_last_good_in(&mut store, &long_name).is_err());
+    assert!(store.vars.is_empty());
+}
+
+/// Sistema de arquivos simulado que só conhece a existência dos arquivos.
+struct MockFs {
+    files: Vec<&'static str>,
+}
+
+struct MockDir {
+    files: Vec<&'static str>,
+}
+
+struct EmptyFile;
+
+impl File for EmptyFile {
+    fn read(&mut self, _buf: &mut [u8]) -> Result<usize> {
+        Ok(0)
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> Result<usize> {
+        Ok(0)
+    }
+
+    fn seek(&mut self, offset: u64) -> Result<u64> {
+        Ok(offset)
+    }
+
+    fn metadata(&self) -> Result<Metadata> {
+        Ok(Metadata {
+            size:        0,
+            is_dir:      false,
+            is_readonly: true,
+        })
+    }
+}
+
+impl Directory for MockDir {
+    fn open_file(&mut self, path: &str) -> Result<Box<dyn File>> {
+        if self.files.contains(&path) {
+            Ok(Box::new(EmptyFile))
+        } else {
+            Err(BootError::FileSystem(FileSystemError::FileNotFound))
+        }
+    }
+
+    fn open_dir(&mut self, _path: &str) -> Result<Box<dyn Directory>> {
+        Err(BootError::FileSystem(FileSystemError::FileNotFound))
+    }
+
+    fn list(&mut self) -> Result<Vec<String>> {
+        Ok(self.files.iter().map(|f| String::from(*f)).collect())
+    }
+}
+
+impl FileSystem for MockFs {
+    fn root(&mut self) -> Result<Box<dyn Directory>> {
+        Ok(Box::new(MockDir {
+            files: self.files.clone(),
+        }))
+    }
+
+    fn name(&self) -> &str {
+        "MOCK"
+    }
+}
+
+fn entry_with(kernel: &str, modules: &[&str]) -> Entry {
+    Entry {
+        name:              String::from("Redstone OS"),
+        protocol:          Protocol::Redstone,
+        path:              String::from(kernel),
+        cmdline:           None,
+        modules:           modules
+            .iter()
+            .map(|m| Module {
+                path:        String::from(*m),
+                cmdline:     None,
+                crc32:       None,
+                module_type: None,
+            })
+            .collect(),
+        dtb_path:          None,
+        iso_path:          None,
+        hidden:            false,
+        load_addr:         None,
+        pass_system_table: false,
+        kernel_crc32:      None,
+        params:            Vec::new(),
+        requires:          Vec::new(),
+        pass_text_console: false,
+        map_mode:          MapMode::Both,
+        http_base:         None,
+        min_memory:        None,
+        initrd_size:       None,
+    }
+}
+
+/// Testa a ordem: entrada completa > módulo ausente > kernel ausente
+#[test]
+fn test_score_entry_ranking() {
+    use ignite::recovery::diagnostics::{Diagnostics, MAX_HEALTH_SCORE};
+
+    let mut fs = MockFs {
+        files: alloc::vec!["/boot/kernel-a", "/boot/initrd-a"],
+    };
+
+    let complete = entry_with("/boot/kernel-a", &["/boot/initrd-a"]);
+    let missing_module = entry_with("/boot/kernel-a", &["/boot/initrd-b"]);
+    let missing_kernel = entry_with("/boot/kernel-b", &["/boot/initrd-a"]);
+
+    let complete_score = Diagnostics::score_entry(&mut fs, &complete, 0);
+    let module_score = Diagnostics::score_entry(&mut fs, &missing_module, 0);
+    let kernel_score = Diagnostics::score_entry(&mut fs, &missing_kernel, 0);
+
+    assert_eq!(complete_score, MAX_HEALTH_SCORE);
+    assert!(complete_score > module_score);
+    assert!(module_score > kernel_score);
+    assert_eq!(kernel_score, 0);
+}
+
+/// Testa a penalidade por falhas recentes da entrada
+#[test]
+fn test_score_entry_recent_failures() {
+    use ignite::recovery::{diagnostics::Diagnostics, state::PersistentState};
+
+    let mut fs = MockFs {
+        files: alloc::vec!["/boot/kernel-a"],
+    };
+    let entry = entry_with("/boot/kernel-a", &[]);
+
+    let state = PersistentState {
+        failed_attempts: 2,
+        last_entry_idx: 1,
+        ..PersistentState::default()
+    };
+    assert_eq!(state.failures_for(0), 0);
+    assert_eq!(state.failures_for(1), 2);
+
+    let healthy = Diagnostics::score_entry(&mut fs, &entry, state.failures_for(0));
+    let failing = Diagnostics::score_entry(&mut fs, &entry, state.failures_for(1));
+    assert!(healthy > failing);
+
+    // Kernel presente nunca empata com kernel ausente
+    assert!(Diagnostics::score_entry(&mut fs, &entry, u8::MAX) > 0);
+}
+
+/// Testa que um arquivo de `requires` ausente torna a entrada não inicializável
+#[test]
+fn test_score_entry_requires() {
+    use ignite::{
+        config::parser::Parser,
+        recovery::diagnostics::{Diagnostics, MAX_HEALTH_SCORE},
+    };
+
+    let mut fs = MockFs {
+        files: alloc::vec!["/boot/kernel-a", "/boot/ucode.bin"],
+    };
+
+    let content = "/Redstone\n    path: /boot/kernel-a\n    requires: /boot/ucode.bin\n/Sem Microcode\n    path: /boot/kernel-a\n    requires: /boot/ucode.bin\n    requires: /boot/fw.bin\n";
+    let config = Parser::new().parse(content).unwrap();
+    assert_eq!(config.entries[1].requires, ["/boot/ucode.bin", "/boot/fw.bin"]);
+
+    let satisfied = Diagnostics::score_entry(&mut fs, &config.entries[0], 0);
+    let missing = Diagnostics::score_entry(&mut fs, &config.entries[1], 0);
+    assert_eq!(satisfied, MAX_HEALTH_SCORE);
+    assert_eq!(missing, 0);
+}
+
+/// Testa que entradas HTTP não são pontuadas pelo ESP local, onde o kernel
+/// nunca está
+#[test]
+fn test_score_entry_http_base() {
+    use ignite::{
+        config::parser::Parser,
+        recovery::diagnostics::{Diagnostics, MAX_HEALTH_SCORE},
+    };
+
+    let mut fs = MockFs {
+        files: alloc::vec!["/boot/ucode.bin"],
+    };
+
+    let content = "/Rede\n    http_base: http://10.0.0.1/boot\n    path: /kernel\n    module_path: /initrd\n/Rede sem Microcode\n    http_base: http://10.0.0.1/boot\n    path: /kernel\n    requires: /boot/fw.bin\n";
+    let config = Parser::new().parse(content).unwrap();
+
+    assert_eq!(
+        Diagnostics::score_entry(&mut fs, &config.entries[0], 0),
+        MAX_HEALTH_SCORE
+    );
+    assert!(Diagnostics::score_entry(&mut fs, &config.entries[0], 2) < MAX_HEALTH_SCORE);
+    // `requires` continua no disco local
+    assert_eq!(Diagnostics::score_entry(&mut fs, &config.entries[1], 0), 0);
+}
+
+/// Testa que um aviso do diagnóstico só impede o boot em modo estrito
+#[test]
+fn test_health_warning_fatal_only_when_strict() {
+    use ignite::{config::parser::Parser, recovery::diagnostics::HealthStatus};
+
+    let warning = HealthStatus::Warning("Módulo ausente");
+    let config = Parser::new().parse("timeout: 3\n").unwrap();
+    assert!(!config.strict_diagnostics);
+    assert!(!warning.is_fatal(config.strict_diagnostics));
+    assert_eq!(warning.message(), Some("Módulo ausente"));
+
+    let config = Parser::new().parse("strict_diagnostics: yes\n").unwrap();
+    assert!(warning.is_fatal(config.strict_diagnostics));
+
+    // Críticos sempre param; saudável nunca
+    assert!(HealthStatus::Critical("Arquivo do Kernel ausente").is_fatal(false));
+    assert!(!HealthStatus::Healthy.is_fatal(true));
+    assert_eq!(HealthStatus::Healthy.message(), None);
+}
+
+/// Testa que uma entrada oculta continua alcançável por boot-once e por nome
+#[test]
+fn test_hidden_entry_reachable_by_name() {
+    use ignite::config::parser::Parser;
+
+    let content = "default_entry: Recovery\n/Redstone\n    path: boot():/forge\n/Recovery\n    path: boot():/forge\n    hidden: yes\n";
+    let config = Parser::new().parse(content).unwrap();
+    assert!(config.entries[1].hidden);
+    assert_eq!(config.default_entry_idx, 1);
+
+    let mut store = MockVariableStore::default();
+    set_boot_once_in(&mut store, "Recovery").unwrap();
+
+    let name = take_boot_once_in(&mut store).unwrap();
+    assert_eq!(config.find_entry(&name), Some(1));
+
+    // O pedido vale uma única vez
+    assert_eq!(take_boot_once_in(&mut store), None);
+    assert_eq!(config.find_entry("Inexistente"), None);
+}
+
+/// Runtime Services simulados: registram a espera e o pedido de reset.
+#[derive(Default)]
+struct MockReset {
+    delayed_us: usize,
+    resets:     usize,
+}
+
+impl ignite::panic::PanicReset for MockReset {
+    fn delay(&mut self, microseconds: usize) {
+        self.delayed_us += microseconds;
+    }
+
+    fn reset(&mut self) {
+        // O reset só pode vir depois da espera
+        assert!(self.delayed_us > 0);
+        self.resets += 1;
+    }
+}
+
+/// Testa que `panic_reboot` pede o reset após a espera, e o padrão paralisa
+#[test]
+fn test_panic_reboot_requests_reset_after_delay() {
+    use ignite::{
+        config::parser::Parser,
+        panic::{reboot_after_panic, set_panic_reboot, PANIC_REBOOT_DELAY_US},
+    };
+
+    let config = Parser::new().parse("timeout: 3\n").unwrap();
+    assert!(!config.panic_reboot);
+    set_panic_reboot(config.panic_reboot);
+
+    let mut reset = MockReset::default();
+    assert!(!reboot_after_panic(&mut reset));
+    assert_eq!(reset.resets, 0);
+    assert_eq!(reset.delayed_us, 0);
+
+    let config = Parser::new().parse("panic_reboot: yes\n").unwrap();
+    set_panic_reboot(config.panic_reboot);
+
+    assert!(reboot_after_panic(&mut reset));
+    assert_eq!(reset.delayed_us, PANIC_REBOOT_DELAY_US);
+    assert_eq!(reset.resets, 1);
+
+    set_panic_reboot(false);
+}
+
+/// Subsistemas simulados: falham os listados em `failing`.
+struct MockSelfTest {
+    failing: &'static [Subsystem],
+    calls:   usize,
+}
+
+impl MockSelfTest {
+    fn outcome(&mut self, subsystem: Subsystem) -> Result<()> {
+        self.calls += 1;
+        if self.failing.contains(&subsystem) {
+            Err(BootError::Generic("falha simulada"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl SelfTestOs for MockSelfTest {
+    fn check_allocation(&mut self) -> Result<()> {
+        self.outcome(Subsystem::Allocation)
+    }
+
+    fn check_serial(&mut self) -> Result<()> {
+        self.outcome(Subsystem::Serial)
+    }
+
+    fn check_video(&mut self) -> Result<()> {
+        self.outcome(Subsystem::Video)
+    }
+
+    fn check_config_read(&mut self) -> Result<()> {
+        self.outcome(Subsystem::ConfigRead)
+    }
+
+    fn check_rng(&mut self) -> Result<()> {
+        self.outcome(Subsystem::Rng)
+    }
+}
+
+/// Testa o relatório do autoteste com tudo passando e com um subsistema falho
+#[test]
+fn test_selftest_report_aggregation() {
+    let mut os = MockSelfTest {
+        failing: &[],
+        calls:   0,
+    };
+    let report = selftest::run(&mut os);
+    assert!(report.passed());
+    assert_eq!(report.failures().count(), 0);
+    assert_eq!(report.results.len(), Subsystem::ALL.len());
+
+    // Uma falha no vídeo não impede as verificações seguintes
+    let mut os = MockSelfTest {
+        failing: &[Subsystem::Video],
+        calls:   0,
+    };
+    let report = selftest::run(&mut os);
+    assert_eq!(os.calls, Subsystem::ALL.len());
+    assert!(!report.passed());
+
+    let failed: Vec<_> = report.failures().map(|r| r.subsystem).collect();
+    assert_eq!(failed, [Subsystem::Video]);
+    assert!(report
+        .results
+        .iter()
+        .filter(|r| r.subsystem != Subsystem::Video)
+        .all(|r| r.outcome.is_ok()));
+
+    // Texto exibido na tela do relatório: uma linha por subsistema
+    assert_eq!(report.summary(), "Autoteste: 1 de 5 verificacoes falharam");
+    let details = report.details();
+    let lines: Vec<_> = details.lines().collect();
+    assert_eq!(lines.len(), Subsystem::ALL.len());
+    assert_eq!(lines[0], "[OK] Alocacao");
+    assert!(lines[2].starts_with("[FAIL] Video: "));
+}
+
+/// Testa que `recovery_max_attempts` define a falha que dispara o fallback
+#[test]
+fn test_recovery_max_attempts_triggers_fallback() {
+    use ignite::{
+        config::parser::Parser,
+        recovery::{RecoveryManager, RecoveryPolicy},
+    };
+
+    let config = Parser::new()
+        .parse("recovery_max_attempts: 2\nrecovery_attempt_decay: 5\n")
+        .unwrap();
+    let policy = RecoveryPolicy::from_config(&config);
+    assert_eq!(policy.max_attempts, 2);
+    assert_eq!(policy.retry_delay(2), 10);
+
+    let mut manager = RecoveryManager::with_store(MockVariableStore::default(), policy);
+    assert!(!manager.needs_fallback());
+
+    // Primeira falha: ainda tenta a entrada padrão
+    manager.record_attempt(0);
+    assert!(!manager.needs_fallback());
+
+    // Segunda falha: cai na recuperação
+    manager.record_attempt(0);
+    assert!(manager.needs_fallback());
+
+    // Valor inválido mantém o padrão (contador desligado)
+    let config = Parser::new().parse("recovery_max_attempts: -1\n").unwrap();
+    assert_eq!(config.recovery_max_attempts, 0);
+}
+
+/// Testa que, com o contador desligado (padrão), boots automáticos seguidos
+/// sem ninguém zerar `IgnBootStat` continuam na entrada padrão
+#[test]
+fn test_recovery_counter_disabled_by_default() {
+    use ignite::{
+        config::parser::Parser,
+        recovery::{state::PersistentState, RecoveryManager, RecoveryPolicy},
+    };
+
+    let content = "default_entry: 2\nrecovery_attempt_decay: 5\n/Linux\n    path: /vmlinuz\n/Redstone\n    path: /forge\n/Recovery\n    path: /forge-rescue\n";
+    let config = Parser::new().parse(content).unwrap();
+    let policy = RecoveryPolicy::from_config(&config);
+    assert!(!policy.enabled());
+
+    let mut manager = RecoveryManager::with_store(MockVariableStore::default(), policy);
+    let mut stalls = 0;
+    for _ in 0..10 {
+        let entry = manager.select_entry_with(&config, false, &mut |_| stalls += 1);
+        assert_eq!(entry.name, "Redstone");
+        assert_eq!(manager.failed_attempts(), 0);
+    }
+    assert_eq!(stalls, 0);
+
+    // Contador deixado por uma configuração antiga: sem espera nem fallback
+    let mut store = MockVariableStore::default();
+    PersistentState::default().mark_attempt_in(&mut store, 1);
+    PersistentState::load_from(&store).mark_attempt_in(&mut store, 1);
+    let mut manager = RecoveryManager::with_store(store, policy);
+    let entry = manager.select_entry_with(&config, false, &mut |_| stalls += 1);
+    assert_eq!(entry.name, "Redstone");
+    assert_eq!(stalls, 0);
+}
+
+/// Testa que um boot bem-sucedido zera o contador de falhas
+#[test]
+fn test_recovery_success_resets_counter() {
+    use ignite::recovery::{state::PersistentState, RecoveryManager, RecoveryPolicy};
+
+    for max_attempts in [1, 3, u8::MAX] {
+        let policy = RecoveryPolicy {
+            max_attempts,
+            attempt_decay: 0,
+        };
+        let mut manager = RecoveryManager::with_store(MockVariableStore::default(), policy);
+
+        for _ in 0..max_attempts.min(4) {
+            manager.record_attempt(1);
+        }
+        assert!(manager.failed_attempts() > 0);
+
+        manager.mark_success();
+        assert_eq!(manager.failed_attempts(), 0);
+        assert!(!manager.needs_fallback());
+    }
+
+    // O estado gravado também volta a zero
+    let mut store = MockVariableStore::default();
+    let mut state = PersistentState::default();
+    state.mark_attempt_in(&mut store, 0);
+    assert_eq!(PersistentState::load_from(&store).failed_attempts, 1);
+    state.reset_in(&mut store);
+    assert_eq!(PersistentState::load_from(&store).failed_attempts, 0);
+}
+
+/// Testa que a falha da entrada escolhida tenta a última válida uma única vez
+/// e depois cai na recuperação
+#[test]
+fn test_last_good_fallback_single_retry() {
+    use ignite::{
+        config::parser::Parser,
+        recovery::{FailureAction, LastGoodFallback},
+    };
+
+    let content = "/Novo\n    path: boot():/novo\n/Antigo\n    path: boot():/antigo\n";
+    let config = Parser::new().parse(content).unwrap();
+    let (novo, antigo) = (&config.entries[0], &config.entries[1]);
+
+    // A primária falha: a última válida é tentada
+    let mut fallback = LastGoodFallback::new();
+    assert_eq!(
+        fallback.on_failure(&config, novo, Some("Antigo")),
+        FailureAction::RetryLastGood(1)
+    );
+
+    // A última válida também falha: recuperação, sem nova tentativa
+    assert_eq!(
+        fallback.on_failure(&config, antigo, Some("Antigo")),
+        FailureAction::Recovery
+    );
+    assert_eq!(
+        fallback.on_failure(&config, novo, Some("Antigo")),
+        FailureAction::Recovery
+    );
+
+    // Sem última válida, ou quando ela é a própria entrada que falhou
+    let mut fallback = LastGoodFallback::new();
+    assert_eq!(fallback.on_failure(&config, novo, None), FailureAction::Recovery);
+    assert_eq!(
+        fallback.on_failure(&config, novo, Some("Novo")),
+        FailureAction::Recovery
+    );
+    assert_eq!(
+        fallback.on_failure(&config, novo, Some("Removida")),
+        FailureAction::Recovery
+    );
+}
+
+/// Testa que um kernel ausente, recusado pelo diagnóstico antes da carga,
+/// também leva à última entrada válida gravada
+#[test]
+fn test_missing_kernel_retries_last_good() {
+    use ignite::{
+        config::parser::Parser,
+        recovery::{diagnostics::HealthStatus, Diagnostics, FailureAction, LastGoodFallback},
+    };
+
+    let content = "/Novo\n    path: /boot/novo\n/Antigo\n    path: /boot/antigo\n";
+    let config = Parser::new().parse(content).unwrap();
+    let mut fs = MockFs {
+        files: alloc::vec!["/boot/antigo"],
+    };
+    let mut store = MockVariableStore::default();
+    set_last_good_in(&mut store, "Antigo").unwrap();
+
+    let health = Diagnostics::check_entry(&mut fs, &config.entries[0]);
+    assert_eq!(health, HealthStatus::Critical("Arquivo do Kernel ausente"));
+    assert!(health.is_fatal(false));
+
+    let mut fallback = LastGoodFallback::new();
+    let action = fallback.on_failure(
+        &config,
+        &config.entries[0],
+        get_last_good_in(&store).as_deref(),
+    );
+    assert_eq!(action, FailureAction::RetryLastGood(1));
+    assert_eq!(
+        Diagnostics::check_entry(&mut fs, &config.entries[1]),
+        HealthStatus::Healthy
+    );
+}
+
+/// Testa que a tela de erro desenha o título na faixa com a cor de erro do
+/// tema, glifo a glifo, sobre o fundo configurado
+#[test]
+fn test_error_screen_title_row() {
+    use ignite::{
+        config::BootConfig,
+        core::handoff::{FramebufferInfo, PixelFormat},
+        recovery::screen::{card_rect, show_error, title_bar_height, CARD_PADDING, MENU_HINT},
+        ui::{font::BitFont, graphics::GraphicsContext, Theme},
+    };
+
+    let (width, height) = (320u32, 200u32);
+    let mut screen = alloc::vec![0u8; (width * height * 4) as usize];
+    let info = FramebufferInfo {
+        addr: screen.as_mut_ptr() as u64,
+        size: screen.len() as u64,
+        width,
+        height,
+        stride: width,
+        format: PixelFormat::Rgb,
+        masks: Default::default(),
+    };
+
+    // O cartão usa o tema da configuração, incluindo `background_color`
+    let config = BootConfig {
+        background_color: Some(0x102030),
+        ..Default::default()
+    };
+    let theme = Theme::from_config(&config);
+    assert_eq!(
+        (theme.background.r, theme.background.g, theme.background.b),
+        (0x10, 0x20, 0x30)
+    );
+
+    let (title, message) = ("Erro: Redstone", "kernel ausente");
+    show_error(
+        &mut unsafe { GraphicsContext::new(info.addr, info) },
+        &theme,
+        title,
+        message,
+        MENU_HINT,
+    );
+
+    // RGB: bytes do pixel gravados como [b, g, r]
+    let pixel = |x: u32, y: u32| {
+        let i = ((y * width + x) * 4) as usize;
+        (screen[i + 2], screen[i + 1], screen[i])
+    };
+    let rgb = |c: ignite::video::Color| (c.r, c.g, c.b);
+
+    let card = card_rect(width, height, title, message, MENU_HINT);
+    assert!(card.x > 0 && card.y > 0);
+    assert_eq!(pixel(card.x, card.y), rgb(theme.error));
+    assert_eq!(
+        pixel(card.x + card.width - 1, card.y + title_bar_height() - 1),
+        rgb(theme.error)
+    );
+    assert_eq!(pixel(0, 0), rgb(theme.background));
+
+    // Cada pixel do título: aceso na cor do fundo, apagado na cor de erro
+    let font = BitFont::new();
+    let (x0, y0) = (card.x + CARD_PADDING, card.y + CARD_PADDING);
+    for (i, c) in title.chars().enumerate() {
+        for (row, bits) in font.get_glyph(c).iter().enumerate() {
+            for bit in 0..8 {
+                let expected = if (bits >> (7 - bit)) & 1 == 1 {
+                    theme.background
+                } else {
+                    theme.error
+                };
+                assert_eq!(
+                    pixel(x0 + i as u32 * 8 + bit, y0 + row as u32),
+                    rgb(expected),
+                    "glifo '{}' linha {} bit {}",
+                    c,
+                    row,
+                    bit
+                );
+            }
+        }
+    }
+}
@@ -337,3 +337,179 @@ fn test_checksum_validation() {
     assert!(verify_checksum(data, checksum));
     assert!(!verify_checksum(b"Different data", checksum));
 }
+
+/// Testa que o buffer é zerado quando a guarda `Zeroizing` é descartada
+#[test]
+fn test_zeroizing_wipes_on_drop() {
+    use ignite::security::Zeroizing;
+
+    let mut password = *b"redstone-secret";
+    {
+        let mut guard = Zeroizing::new(&mut password[..]);
+        assert_eq!(&guard[..8], b"redstone");
+        guard[0] = b'R';
+    }
+    assert_eq!(password, [0u8; 15]);
+
+    // Buffers com dono também são zerados (observados antes do free)
+    let mut owned = Zeroizing::new(Vec::from(&b"key material"[..]));
+    ignite::security::zeroize::zeroize(&mut owned);
+    assert!(owned.iter().all(|&b| b == 0));
+    assert_eq!(alloc::format!("{:?}", owned), "Zeroizing(..)");
+}
+
+/// Testa o flag de Secure Boot entregue ao kernel a partir das variáveis EFI
+#[test]
+fn test_secure_boot_handoff_flag() {
+    use ignite::{
+        core::error::Result,
+        recovery::state::VariableStore,
+        security::secure_boot::{enforcement_required_in, get_state_in, SecureBootState},
+        uefi::base::Guid,
+    };
+
+    /// Variáveis globais simuladas: (SecureBoot, SetupMode), ausentes se `None`.
+    struct MockGlobals(Option<u8>, Option<u8>);
+
+    impl VariableStore for MockGlobals {
+        fn read(&self, name: &str, _guid: &Guid, buf: &mut [u8]) -> Option<usize> {
+            let value = match name {
+                "SecureBoot" => self.0,
+                "SetupMode" => self.1,
+                _ => None,
+            }?;
+            buf[0] = value;
+            Some(1)
+        }
+
+        fn write(&mut self, _: &str, _: &Guid, _: u32, _: &[u8]) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    assert!(enforcement_required_in(&MockGlobals(Some(1), Some(0))));
+    assert!(enforcement_required_in(&MockGlobals(Some(1), None)));
+
+    assert!(!enforcement_required_in(&MockGlobals(Some(0), Some(0))));
+    assert!(!enforcement_required_in(&MockGlobals(Some(1), Some(1))));
+    assert!(!enforcement_required_in(&MockGlobals(None, None)));
+    assert_eq!(
+        get_state_in(&MockGlobals(None, None)),
+        SecureBootState::Unknown
+    );
+}
+
+/// Converte 64 dígitos hexadecimais em um digest SHA-256.
+fn digest_from_hex(hex: &str) -> [u8; 32] {
+    let mut digest = [0u8; 32];
+    for (i, byte) in digest.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap();
+    }
+    digest
+}
+
+/// Testa o SHA-256 contra os vetores da FIPS 180-4
+#[test]
+fn test_sha256_known_vectors() {
+    use ignite::core::sha256::sha256;
+
+    assert_eq!(
+        sha256(b""),
+        digest_from_hex("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+    );
+    assert_eq!(
+        sha256(b"abc"),
+        digest_from_hex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+    );
+    // 56 bytes: o tamanho não cabe no primeiro bloco de padding
+    assert_eq!(
+        sha256(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+        digest_from_hex("248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1")
+    );
+    assert_eq!(
+        sha256(&[b'a'; 1000]),
+        digest_from_hex("41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3")
+    );
+}
+
+/// Testa que a configuração é medida com SHA-256 no PCR 8
+#[test]
+fn test_measure_config_into_pcr8() {
+    use ignite::{
+        core::{error::Result, sha256::sha256},
+        security::tpm::{measure_config_with, tcg2_event, PcrExtender, CONFIG_PCR, EV_IPL},
+    };
+
+    /// TPM simulado que registra o SHA-256 de cada extensão, como o banco
+    /// SHA-256 do firmware.
+    struct MockTpm {
+        present:  bool,
+        extended: Vec<(u32, [u8; 32])>,
+    }
+
+    impl PcrExtender for MockTpm {
+        fn available(&self) -> bool {
+            self.present
+        }
+
+        fn extend(&mut self, pcr_index: u32, data: &[u8], _description: &str) -> Result<()> {
+            self.extended.push((pcr_index, sha256(data)));
+            Ok(())
+        }
+    }
+
+    let config = b"timeout: 3\n/Redstone\nprotocol: redstone\npath: boot():/forge\n";
+
+    let mut tpm = MockTpm {
+        present:  true,
+        extended: Vec::new(),
+    };
+    measure_config_with(&mut tpm, config).unwrap();
+    assert_eq!(CONFIG_PCR, 8);
+    assert_eq!(
+        tpm.extended,
+        [(
+            8,
+            digest_from_hex("e1b1fce1452f8c56486303ce1c65a40365f51753c14fa734eb1f1ef3fd9634ff")
+        )]
+    );
+
+    // Sem TPM nada é medido
+    let mut tpm = MockTpm {
+        present:  false,
+        extended: Vec::new(),
+    };
+    measure_config_with(&mut tpm, config).unwrap();
+    assert!(tpm.extended.is_empty());
+
+    // EFI_TCG2_EVENT: Size, HeaderSize (14), HeaderVersion (1), PCRIndex,
+    // EventType e o texto do log
+    let event = tcg2_event(CONFIG_PCR, EV_IPL, "ignite.cfg");
+    assert_eq!(event.len(), 4 + 14 + 10);
+    assert_eq!(event[0..4], (event.len() as u32).to_le_bytes());
+    assert_eq!(event[4..8], 14u32.to_le_bytes());
+    assert_eq!(event[8..10], 1u16.to_le_bytes());
+    assert_eq!(event[10..14], 8u32.to_le_bytes());
+    assert_eq!(event[14..18], 0x0Du32.to_le_bytes());
+    assert_eq!(&event[18..], b"ignite.cfg");
+}
+
+/// Testa o valor de cada `SecureBootState` em `BootInfo::secure_boot_state`
+#[test]
+fn test_secure_boot_state_handoff_value() {
+    use ignite::{
+        core::handoff::{
+            SECURE_BOOT_STATE_DISABLED, SECURE_BOOT_STATE_ENABLED, SECURE_BOOT_STATE_SETUP,
+        },
+        security::secure_boot::SecureBootState,
+    };
+
+    assert_eq!(SECURE_BOOT_STATE_DISABLED, 0);
+    assert_eq!(SECURE_BOOT_STATE_ENABLED, 1);
+    assert_eq!(SECURE_BOOT_STATE_SETUP, 2);
+
+    assert_eq!(SecureBootState::Disabled.handoff_value(), 0);
+    assert_eq!(SecureBootState::Enforced.handoff_value(), 1);
+    assert_eq!(SecureBootState::SetupMode.handoff_value(), 2);
+    assert_eq!(SecureBootState::Unknown.handoff_value(), 0);
+}
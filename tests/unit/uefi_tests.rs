@@ -0,0 +1,566 @@
+//! Testes Unitários para a camada UEFI
+//!
+//! Testa os wrappers seguros de variáveis sobre uma tabela de Runtime Services
+//! simulada e a busca na Tabela de Configuração.
+
+#![no_std]
+#![cfg(test)]
+
+extern crate alloc;
+extern crate std;
+
+use alloc::vec::Vec;
+use core::ffi::c_void;
+use std::sync::Mutex;
+
+use ignite::{
+    fs::uefi::read_file_info,
+    hardware::smbios::{self, SmbiosEntryPoint},
+    uefi::{
+        base::{Char16, Guid, Handle},
+        proto::media::file::{FileInfo, FileProtocol, FILE_INFO_GUID, FILE_READ_ONLY},
+        table::{
+            boot::{call_allocate_pages, AllocateType, MemoryType},
+            config::{ACPI_20_TABLE_GUID, ACPI_TABLE_GUID, SMBIOS3_TABLE_GUID},
+            header::TableHeader,
+            runtime::{
+                encode_variable_name, ResetType, RuntimeServices, Time, VariableError,
+                MAX_VARIABLE_NAME_LEN, VARIABLE_BOOTSERVICE_ACCESS, VARIABLE_NON_VOLATILE,
+            },
+            system::{ConfigurationTableEntry, SystemTable},
+        },
+        Status,
+    },
+};
+
+const TEST_GUID: Guid = Guid::new(0x1234_5678, 0x9abc, 0xdef0, [1, 2, 3, 4, 5, 6, 7, 8]);
+
+/// Variável simulada: nome UCS-2, atributos e dados.
+type MockVariable = (Vec<u16>, u32, Vec<u8>);
+
+/// NVRAM simulada compartilhada pelas funções FFI abaixo.
+static VARS: Mutex<Vec<MockVariable>> = Mutex::new(Vec::new());
+
+unsafe fn read_name(name: *const Char16) -> Vec<u16> {
+    let mut out = Vec::new();
+    let mut p = name;
+    while *p != 0 {
+        out.push(*p);
+        p = p.add(1);
+    }
+    out
+}
+
+unsafe extern "efiapi" fn mock_get_variable(
+    name: *const Char16,
+    _guid: *const Guid,
+    attributes: *mut u32,
+    size: *mut usize,
+    data: *mut c_void,
+) -> Status {
+    let name = read_name(name);
+    let vars = VARS.lock().unwrap();
+    let Some((_, attr, value)) = vars.iter().find(|(n, _, _)| *n == name) else {
+        return Status::NOT_FOUND;
+    };
+
+    if *size < value.len() {
+        *size = value.len();
+        return Status::BUFFER_TOO_SMALL;
+    }
+
+    core::ptr::copy_nonoverlapping(value.as_ptr(), data as *mut u8, value.len());
+    *size = value.len();
+    *attributes = *attr;
+    Status::SUCCESS
+}
+
+unsafe extern "efiapi" fn mock_set_variable(
+    name: *const Char16,
+    _guid: *const Guid,
+    attributes: u32,
+    size: usize,
+    data: *mut c_void,
+) -> Status {
+    let name = read_name(name);
+    let value = core::slice::from_raw_parts(data as *const u8, size).to_vec();
+    let mut vars = VARS.lock().unwrap();
+    vars.retain(|(n, _, _)| *n != name);
+    vars.push((name, attributes, value));
+    Status::SUCCESS
+}
+
+unsafe extern "efiapi" fn unsupported_time(_: *mut Time, _: *mut c_void) -> Status {
+    Status::UNSUPPORTED
+}
+unsafe extern "efiapi" fn unsupported_set_time(_: *mut Time) -> Status {
+    Status::UNSUPPORTED
+}
+unsafe extern "efiapi" fn unsupported_get_wakeup(_: *mut u8, _: *mut u8, _: *mut Time) -> Status {
+    Status::UNSUPPORTED
+}
+unsafe extern "efiapi" fn unsupported_set_wakeup(_: u8, _: *mut Time) -> Status {
+    Status::UNSUPPORTED
+}
+unsafe extern "efiapi" fn unsupported_set_map(
+    _: usize,
+    _: usize,
+    _: u32,
+    _: *mut c_void,
+) -> Status {
+    Status::UNSUPPORTED
+}
+unsafe extern "efiapi" fn unsupported_convert(_: usize, _: *mut *mut c_void) -> Status {
+    Status::UNSUPPORTED
+}
+unsafe extern "efiapi" fn unsupported_next_name(
+    _: *mut usize,
+    _: *mut Char16,
+    _: *mut Guid,
+) -> Status {
+    Status::UNSUPPORTED
+}
+unsafe extern "efiapi" fn unsupported_count(_: *mut u32) -> Status {
+    Status::UNSUPPORTED
+}
+unsafe extern "efiapi" fn unsupported_reset(
+    _: ResetType,
+    _: Status,
+    _: usize,
+    _: *const c_void,
+) -> ! {
+    panic!("reset_system não deve ser chamado nos testes");
+}
+unsafe extern "efiapi" fn unsupported_capsule(_: *mut *mut c_void, _: usize, _: u64) -> Status {
+    Status::UNSUPPORTED
+}
+unsafe extern "efiapi" fn unsupported_capsule_caps(
+    _: *mut *mut c_void,
+    _: usize,
+    _: *mut u64,
+    _: *mut u32,
+) -> Status {
+    Status::UNSUPPORTED
+}
+unsafe extern "efiapi" fn unsupported_var_info(
+    _: u32,
+    _: *mut u64,
+    _: *mut u64,
+    _: *mut u64,
+) -> Status {
+    Status::UNSUPPORTED
+}
+
+fn mock_runtime() -> RuntimeServices {
+    RuntimeServices {
+        hdr: TableHeader {
+            signature:   0,
+            revision:    0,
+            header_size: 0,
+            crc32:       0,
+            reserved:    0,
+        },
+        get_time: unsupported_time,
+        set_time: unsupported_set_time,
+        get_wakeup_time: unsupported_get_wakeup,
+        set_wakeup_time: unsupported_set_wakeup,
+        set_virtual_address_map: unsupported_set_map,
+        convert_pointer: unsupported_convert,
+        get_variable: mock_get_variable,
+        get_next_variable_name: unsupported_next_name,
+        set_variable: mock_set_variable,
+        get_next_high_monotonic_count: unsupported_count,
+        reset_system: unsupported_reset,
+        update_capsule: unsupported_capsule,
+        query_capsule_capabilities: unsupported_capsule_caps,
+        query_variable_info: unsupported_var_info,
+    }
+}
+
+/// Testa conversão de nomes para UCS-2 terminado em NUL
+#[test]
+fn test_variable_name_conversion() {
+    let name = encode_variable_name("SecureBoot").unwrap();
+    let expected: Vec<u16> = "SecureBoot".encode_utf16().collect();
+    assert_eq!(&name[..expected.len()], expected.as_slice());
+    assert_eq!(name[expected.len()], 0);
+
+    // Caracteres BMP não-ASCII são válidos em UCS-2
+    assert_eq!(encode_variable_name("Configuração").unwrap()[9], 'ç' as u16);
+
+    // Vazio, NUL interno, fora do BMP ou longo demais são rejeitados
+    let long: alloc::string::String = core::iter::repeat_n('a', MAX_VARIABLE_NAME_LEN + 1).collect();
+    assert_eq!(encode_variable_name(""), Err(VariableError::InvalidName));
+    assert_eq!(
+        encode_variable_name("a\0b"),
+        Err(VariableError::InvalidName)
+    );
+    assert_eq!(encode_variable_name("🔥"), Err(VariableError::InvalidName));
+    assert_eq!(encode_variable_name(&long), Err(VariableError::InvalidName));
+}
+
+/// Testa set/get completo e a semântica de BUFFER_TOO_SMALL
+#[test]
+fn test_variable_round_trip_and_buffer_too_small() {
+    let rt = mock_runtime();
+    let attrs = VARIABLE_NON_VOLATILE | VARIABLE_BOOTSERVICE_ACCESS;
+
+    rt.set_variable("IgnTestVar", &TEST_GUID, attrs, b"payload")
+        .unwrap();
+
+    // Buffer pequeno demais: erro informa o tamanho necessário
+    let mut small = [0u8; 3];
+    assert_eq!(
+        rt.get_variable("IgnTestVar", &TEST_GUID, &mut small),
+        Err(VariableError::BufferTooSmall(7))
+    );
+
+    // Buffer suficiente: dados e atributos retornados
+    let mut buf = [0u8; 16];
+    let (size, got_attrs) = rt.get_variable("IgnTestVar", &TEST_GUID, &mut buf).unwrap();
+    assert_eq!(&buf[..size], b"payload");
+    assert_eq!(got_attrs, attrs);
+
+    // Variável inexistente repassa o status do firmware
+    assert_eq!(
+        rt.get_variable("IgnMissing", &TEST_GUID, &mut buf),
+        Err(VariableError::Firmware(Status::NOT_FOUND))
+    );
+}
+
+/// Testa o mapeamento de Ctrl+C vindo do Simple Text Input Ex
+#[test]
+fn test_text_input_ex_ctrl_c() {
+    use ignite::{
+        uefi::{
+            proto::console::text_input_ex::{
+                KeyData, KeyState, LEFT_CONTROL_PRESSED, RIGHT_SHIFT_PRESSED, SHIFT_STATE_VALID,
+            },
+            table::system::InputKey,
+        },
+        ui::input::{map_key_data, Key, KeyEvent, Modifiers},
+    };
+
+    let ctrl_c = |unicode_char| KeyData {
+        key:       InputKey {
+            scan_code: 0,
+            unicode_char,
+        },
+        key_state: KeyState {
+            key_shift_state:  SHIFT_STATE_VALID | LEFT_CONTROL_PRESSED,
+            key_toggle_state: 0,
+        },
+    };
+
+    let expected = KeyEvent {
+        key:       Key::Char('c'),
+        modifiers: Modifiers::CTRL,
+    };
+
+    // Firmware que entrega a letra e firmware que entrega o caractere de
+    // controle
+    assert_eq!(map_key_data(&ctrl_c(b'c' as u16)), expected);
+    assert_eq!(map_key_data(&ctrl_c(0x03)), expected);
+
+    // Sem SHIFT_STATE_VALID os bits de modificador são ignorados
+    let mut stale = ctrl_c(b'c' as u16);
+    stale.key_state.key_shift_state = LEFT_CONTROL_PRESSED | RIGHT_SHIFT_PRESSED;
+    assert_eq!(
+        map_key_data(&stale),
+        KeyEvent {
+            key:       Key::Char('c'),
+            modifiers: Modifiers::empty(),
+        }
+    );
+
+    // Enter sem Ctrl continua sendo Enter
+    let mut enter = ctrl_c(13);
+    enter.key_state.key_shift_state = SHIFT_STATE_VALID;
+    assert_eq!(map_key_data(&enter).key, Key::Enter);
+}
+
+// --- FileInfo ---
+
+const LONG_FILE_NAME: &str =
+    "initramfs-redstone-6.9.0-generic-with-a-very-long-name-for-testing.img";
+
+/// Chamadas recebidas por `mock_get_info`, com o tamanho de buffer oferecido.
+static GET_INFO_CALLS: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+unsafe extern "efiapi" fn mock_get_info(
+    _this: *mut FileProtocol,
+    guid: *const Guid,
+    size: *mut usize,
+    buf: *mut c_void,
+) -> Status {
+    assert!(*guid == FILE_INFO_GUID);
+    GET_INFO_CALLS.lock().unwrap().push(*size);
+
+    let name: Vec<u16> = LONG_FILE_NAME
+        .encode_utf16()
+        .chain(core::iter::once(0))
+        .collect();
+    let header = core::mem::size_of::<FileInfo>();
+    let needed = header + name.len() * 2;
+
+    if *size < needed {
+        *size = needed;
+        return Status::BUFFER_TOO_SMALL;
+    }
+
+    let info = FileInfo {
+        size: needed as u64,
+        file_size: 0x1234,
+        physical_size: 0x2000,
+        attribute: FILE_READ_ONLY,
+        ..Default::default()
+    };
+    core::ptr::write(buf as *mut FileInfo, info);
+    core::ptr::copy_nonoverlapping(
+        name.as_ptr(),
+        (buf as *mut u8).add(header) as *mut u16,
+        name.len(),
+    );
+    *size = needed;
+    Status::SUCCESS
+}
+
+unsafe extern "efiapi" fn unsupported_open(
+    _: *mut FileProtocol,
+    _: *mut *mut FileProtocol,
+    _: *const Char16,
+    _: u64,
+    _: u64,
+) -> Status {
+    Status::UNSUPPORTED
+}
+
+unsafe extern "efiapi" fn unsupported_file_op(_: *mut FileProtocol) -> Status {
+    Status::UNSUPPORTED
+}
+
+unsafe extern "efiapi" fn unsupported_read(
+    _: *mut FileProtocol,
+    _: *mut usize,
+    _: *mut c_void,
+) -> Status {
+    Status::UNSUPPORTED
+}
+
+unsafe extern "efiapi" fn unsupported_write(
+    _: *mut FileProtocol,
+    _: *mut usize,
+    _: *const c_void,
+) -> Status {
+    Status::UNSUPPORTED
+}
+
+unsafe extern "efiapi" fn unsupported_get_position(_: *mut FileProtocol, _: *mut u64) -> Status {
+    Status::UNSUPPORTED
+}
+
+unsafe extern "efiapi" fn unsupported_set_position(_: *mut FileProtocol, _: u64) -> Status {
+    Status::UNSUPPORTED
+}
+
+unsafe extern "efiapi" fn unsupported_set_info(
+    _: *mut FileProtocol,
+    _: *const Guid,
+    _: usize,
+    _: *const c_void,
+) -> Status {
+    Status::UNSUPPORTED
+}
+
+/// Testa a releitura do `FileInfo` com o tamanho exigido pelo firmware
+#[test]
+fn test_read_file_info_retries_on_buffer_too_small() {
+    let mut file = FileProtocol {
+        revision:     0x0001_0000,
+        open:         unsupported_open,
+        close:        unsupported_file_op,
+        delete:       unsupported_file_op,
+        read:         unsupported_read,
+        write:        unsupported_write,
+        get_position: unsupported_get_position,
+        set_position: unsupported_set_position,
+        get_info:     mock_get_info,
+        set_info:     unsupported_set_info,
+        flush:        unsupported_file_op,
+    };
+
+    let file_info = unsafe { read_file_info(&mut file) }.unwrap();
+
+    assert_eq!(file_info.name, LONG_FILE_NAME);
+    assert_eq!(file_info.info.file_size, 0x1234);
+    assert_eq!(file_info.info.physical_size, 0x2000);
+    assert_eq!(file_info.info.attribute, FILE_READ_ONLY);
+
+    // Primeira chamada com o buffer padrão, segunda com o tamanho informado
+    let calls = GET_INFO_CALLS.lock().unwrap();
+    assert_eq!(calls.len(), 2);
+    assert!(calls[0] < calls[1]);
+    assert_eq!(
+        calls[1],
+        core::mem::size_of::<FileInfo>() + (LONG_FILE_NAME.len() + 1) * 2
+    );
+}
+
+/// Pares (tipo, endereço de entrada) recebidos por `mock_allocate_pages`.
+static ALLOCATE_CALLS: Mutex<Vec<(u32, u64)>> = Mutex::new(Vec::new());
+
+unsafe extern "efiapi" fn mock_allocate_pages(
+    ty: AllocateType,
+    _memory_type: MemoryType,
+    _pages: usize,
+    addr: *mut u64,
+) -> Status {
+    ALLOCATE_CALLS.lock().unwrap().push((ty as u32, *addr));
+    if matches!(ty, AllocateType::AllocateAnyPages) {
+        *addr = 0x7000_0000;
+    }
+    Status::SUCCESS
+}
+
+/// Testa que o endereço pedido chega ao firmware só quando o tipo o usa
+#[test]
+fn test_allocate_pages_passes_requested_address() {
+    let fixed = call_allocate_pages(
+        mock_allocate_pages,
+        AllocateType::AllocateAddress,
+        MemoryType::LoaderData,
+        4,
+        0x10_0000,
+    );
+    assert_eq!(fixed, Ok(0x10_0000));
+
+    let any = call_allocate_pages(
+        mock_allocate_pages,
+        AllocateType::AllocateAnyPages,
+        MemoryType::LoaderData,
+        4,
+        0x10_0000,
+    );
+    assert_eq!(any, Ok(0x7000_0000));
+
+    let calls = ALLOCATE_CALLS.lock().unwrap();
+    assert_eq!(
+        *calls,
+        [
+            (AllocateType::AllocateAddress as u32, 0x10_0000),
+            (AllocateType::AllocateAnyPages as u32, 0),
+        ]
+    );
+}
+
+/// System Table simulada apontando para `entries`.
+fn mock_system_table(entries: &mut [ConfigurationTableEntry]) -> SystemTable {
+    SystemTable {
+        hdr:                     TableHeader {
+            signature:   0,
+            revision:    0,
+            header_size: 0,
+            crc32:       0,
+            reserved:    0,
+        },
+        firmware_vendor:         core::ptr::null(),
+        firmware_revision:       0,
+        console_in_handle:       Handle::null(),
+        con_in:                  core::ptr::null_mut(),
+        console_out_handle:      Handle::null(),
+        con_out:                 core::ptr::null_mut(),
+        standard_error_handle:   Handle::null(),
+        std_err:                 core::ptr::null_mut(),
+        runtime_services:        core::ptr::null_mut(),
+        boot_services:           core::ptr::null_mut(),
+        number_of_table_entries: entries.len(),
+        configuration_table:     entries.as_mut_ptr(),
+    }
+}
+
+/// Testa a busca na Tabela de Configuração por GUID
+#[test]
+fn test_find_config_table_by_guid() {
+    let mut entries = [
+        ConfigurationTableEntry {
+            vendor_guid:  ACPI_20_TABLE_GUID,
+            vendor_table: 0x7_F000 as *mut c_void,
+        },
+        ConfigurationTableEntry {
+            vendor_guid:  SMBIOS3_TABLE_GUID,
+            vendor_table: 0x8_0000 as *mut c_void,
+        },
+        ConfigurationTableEntry {
+            vendor_guid:  TEST_GUID,
+            vendor_table: 0x9_1000 as *mut c_void,
+        },
+    ];
+    let st = mock_system_table(&mut entries);
+
+    assert_eq!(st.config_entries().len(), 3);
+    assert_eq!(
+        st.find_config_table(&SMBIOS3_TABLE_GUID),
+        Some(0x8_0000 as *mut c_void)
+    );
+    assert_eq!(
+        st.find_config_table(&TEST_GUID),
+        Some(0x9_1000 as *mut c_void)
+    );
+    assert_eq!(st.find_config_table(&ACPI_TABLE_GUID), None);
+    assert_eq!(
+        smbios::find_entry_point(&st),
+        Some(SmbiosEntryPoint::V3(0x8_0000))
+    );
+
+    let empty = mock_system_table(&mut []);
+    assert!(empty.config_entries().is_empty());
+    assert_eq!(empty.find_config_table(&ACPI_20_TABLE_GUID), None);
+}
+
+/// Testa o `LoadFile2` do initrd: sem buffer responde o tamanho, com buffer
+/// copia os bytes, e o Device Path é o nó Vendor que o EFI stub procura
+#[test]
+fn test_initrd_load_file2_probe_and_copy() {
+    use ignite::uefi::proto::{
+        device_path::device_path_bytes,
+        media::load_file::{InitrdDevicePath, InitrdLoadFile2, LINUX_EFI_INITRD_MEDIA_GUID},
+    };
+
+    let initrd: Vec<u8> = (0..100u8).collect();
+    let mut file = InitrdLoadFile2::new(initrd.as_ptr(), initrd.len());
+    let load_file = file.protocol.load_file;
+    let this = &mut file.protocol as *mut _;
+    let path = InitrdDevicePath::new();
+    let path_ptr = &path.vendor as *const _;
+
+    // Sonda com buffer nulo: só o tamanho
+    let mut size = 0usize;
+    let status = unsafe { load_file(this, path_ptr, 0, &mut size, core::ptr::null_mut()) };
+    assert_eq!(status, Status::BUFFER_TOO_SMALL);
+    assert_eq!(size, 100);
+
+    // Buffer pequeno demais também só informa o tamanho
+    let mut small = [0u8; 10];
+    let mut size = small.len();
+    let status = unsafe { load_file(this, path_ptr, 0, &mut size, small.as_mut_ptr().cast()) };
+    assert_eq!(status, Status::BUFFER_TOO_SMALL);
+    assert_eq!(size, 100);
+    assert_eq!(small, [0u8; 10]);
+
+    // Com o buffer do tamanho certo, copia o initrd inteiro
+    let mut buffer = alloc::vec![0u8; size];
+    let status = unsafe { load_file(this, path_ptr, 0, &mut size, buffer.as_mut_ptr().cast()) };
+    assert_eq!(status, Status::SUCCESS);
+    assert_eq!(size, 100);
+    assert_eq!(buffer, initrd);
+
+    // BootPolicy não é aceito pelo LoadFile2
+    let status = unsafe { load_file(this, path_ptr, 1, &mut size, buffer.as_mut_ptr().cast()) };
+    assert_eq!(status, Status::UNSUPPORTED);
+
+    // VenMedia(GUID)/End: 20 + 4 bytes
+    let bytes = unsafe { device_path_bytes(path_ptr) }.unwrap();
+    assert_eq!(bytes.len(), 24);
+    assert_eq!(&bytes[..4], &[0x04, 0x03, 20, 0]);
+    assert_eq!(path.guid, LINUX_EFI_INITRD_MEDIA_GUID);
+}
@@ -0,0 +1,459 @@
+//! Testes Unitários para a interface do menu
+//!
+//! Testa a lógica do menu que não depende de framebuffer nem de teclado.
+
+#![no_std]
+#![cfg(test)]
+
+extern crate alloc;
+
+use alloc::string::String;
+
+use ignite::{
+    config::{BootConfig, ConsoleMode, Entry, MapMode, Protocol, Timeout, TimeoutAction},
+    core::handoff::PixelFormat,
+    ui::{Menu, MenuAction},
+};
+
+fn config_with(timeout: u32, action: TimeoutAction) -> BootConfig {
+    let mut config = BootConfig {
+        timeout: Timeout::Millis(timeout * 1000),
+        timeout_action: action,
+        ..Default::default()
+    };
+    for name in ["Redstone OS", "Redstone OS (Fallback)"] {
+        config.entries.push(Entry {
+            name:              String::from(name),
+            protocol:          Protocol::Redstone,
+            path:              String::from("boot():/EFI/ignite/forge"),
+            cmdline:           None,
+            modules:           alloc::vec::Vec::new(),
+            dtb_path:          None,
+            iso_path:          None,
+            hidden:            false,
+            load_addr:         None,
+            pass_system_table: false,
+            kernel_crc32:      None,
+            params:            alloc::vec::Vec::new(),
+            requires:          alloc::vec::Vec::new(),
+            pass_text_console: false,
+            map_mode:          MapMode::Both,
+            http_base:         None,
+            min_memory:        None,
+            initrd_size:       None,
+        });
+    }
+    config.default_entry_idx = 1;
+    config
+}
+
+/// Testa que o contador só dispara a ação após `timeout` segundos (passos de
+/// 100ms)
+#[test]
+fn test_menu_timeout_boots_default() {
+    let config = config_with(3, TimeoutAction::Boot);
+    let mut menu = Menu::new(&config);
+
+    for _ in 0..29 {
+        assert!(menu.tick().is_none());
+    }
+    match menu.tick() {
+        Some(MenuAction::Boot(entry)) => assert!(core::ptr::eq(entry, &config.entries[1])),
+        other => panic!("esperado Boot, obtido {:?}", other),
+    }
+}
+
+/// Testa as ações `halt` e `reboot` no fim do contador
+#[test]
+fn test_menu_timeout_halt_and_reboot() {
+    let mut config = config_with(1, TimeoutAction::Halt);
+    config.timeout = Timeout::Millis(100);
+    assert!(matches!(Menu::new(&config).tick(), Some(MenuAction::Halt)));
+
+    config.timeout_action = TimeoutAction::Reboot;
+    assert!(matches!(
+        Menu::new(&config).tick(),
+        Some(MenuAction::Reboot)
+    ));
+}
+
+/// Testa que sem contador o menu nunca expira
+#[test]
+fn test_menu_without_timeout_never_fires() {
+    let mut config = config_with(1, TimeoutAction::Reboot);
+    for timeout in [Timeout::Disabled, Timeout::Infinite] {
+        config.timeout = timeout;
+        let mut menu = Menu::new(&config);
+
+        for _ in 0..100 {
+            assert!(menu.tick().is_none());
+        }
+    }
+}
+
+/// Testa `timeout: infinite`, `no`/`0` e o sufixo `ms`
+#[test]
+fn test_menu_timeout_units() {
+    use ignite::config::parser::Parser;
+
+    // `infinite` exibe o menu e só sai com uma tecla
+    let mut config = Parser::new().parse("timeout: infinite\n").unwrap();
+    assert_eq!(config.timeout, Timeout::Infinite);
+    assert!(config.timeout.shows_menu());
+    config.entries = config_with(1, TimeoutAction::Boot).entries;
+    let mut menu = Menu::new(&config);
+    for _ in 0..1000 {
+        assert!(menu.tick().is_none());
+    }
+
+    // `no` e `0` iniciam direto, sem menu
+    for text in ["timeout: no\n", "timeout: 0\n", "timeout: 0ms\n"] {
+        let config = Parser::new().parse(text).unwrap();
+        assert_eq!(config.timeout, Timeout::Disabled);
+        assert!(!config.timeout.shows_menu());
+    }
+
+    // `500ms` são 5 passos de 100ms
+    let mut config = Parser::new().parse("timeout: 500ms\n").unwrap();
+    assert_eq!(config.timeout, Timeout::Millis(500));
+    assert_eq!(config.timeout.ticks(), Some(5));
+    config.entries = config_with(1, TimeoutAction::Boot).entries;
+    let mut menu = Menu::new(&config);
+    for _ in 0..4 {
+        assert!(menu.tick().is_none());
+    }
+    assert!(matches!(menu.tick(), Some(MenuAction::Boot(_))));
+
+    // Números sem sufixo continuam em segundos; frações de passo arredondam
+    // para cima
+    assert_eq!(Timeout::parse("3"), Some(Timeout::Millis(3000)));
+    assert_eq!(Timeout::parse("150ms").and_then(Timeout::ticks), Some(2));
+    assert_eq!(Timeout::parse("-1"), Some(Timeout::Infinite));
+    assert_eq!(Timeout::parse("abc"), None);
+}
+
+/// Testa que `console: serial` desliga o desenho no framebuffer
+#[test]
+fn test_menu_console_serial_disables_video() {
+    let mut config = config_with(5, TimeoutAction::Boot);
+    config.console = ConsoleMode::Serial;
+    let menu = Menu::new(&config);
+
+    assert!(!menu.video_enabled());
+    assert!(menu.serial_echo_enabled());
+    assert_eq!(
+        menu.serial_lines(),
+        ["  1. Redstone OS", "> 2. Redstone OS (Fallback)"]
+    );
+}
+
+/// Testa que `console: video` desliga o eco do menu na serial
+#[test]
+fn test_menu_console_video_disables_serial_echo() {
+    let mut config = config_with(5, TimeoutAction::Boot);
+    config.console = ConsoleMode::Video;
+    let menu = Menu::new(&config);
+
+    assert!(menu.video_enabled());
+    assert!(!menu.serial_echo_enabled());
+
+    config.console = ConsoleMode::Both;
+    let menu = Menu::new(&config);
+    assert!(menu.video_enabled() && menu.serial_echo_enabled());
+}
+
+/// Testa que sem framebuffer linear o menu de vídeo cai para texto
+#[test]
+fn test_menu_blt_only_falls_back_to_text() {
+    let mut config = config_with(5, TimeoutAction::Boot);
+    config.console = ConsoleMode::Video;
+    let menu = Menu::new(&config);
+
+    assert!(!menu.text_fallback(PixelFormat::Bgr));
+    assert!(!menu.text_fallback(PixelFormat::Bitmask));
+    assert!(menu.text_fallback(PixelFormat::BltOnly));
+
+    // Só com a serial não há o que trocar
+    config.console = ConsoleMode::Serial;
+    let menu = Menu::new(&config);
+    assert!(!menu.text_fallback(PixelFormat::BltOnly));
+}
+
+/// Testa que, com 20 entradas e espaço para 5 linhas, a janela da lista
+/// acompanha a seleção e os indicadores de rolagem aparecem
+#[test]
+fn test_menu_scrolls_to_keep_selection_visible() {
+    use ignite::ui::menu::{FOOTER_HEIGHT, LIST_TOP};
+
+    let mut config = config_with(5, TimeoutAction::Boot);
+    let template = config.entries[0].clone();
+    config.entries = (0..20)
+        .map(|i| Entry {
+            name: alloc::format!("Entrada {}", i + 1),
+            ..template.clone()
+        })
+        .collect();
+    config.default_entry_idx = 0;
+    let mut menu = Menu::new(&config);
+
+    // Fonte de 16px + 4px de espaçamento: 5 linhas e uma sobra menor que uma
+    let height = LIST_TOP + FOOTER_HEIGHT + 5 * 20 + 10;
+    let rows = menu.list_rows(height);
+    assert_eq!(rows, 5);
+
+    let window = menu.scroll_to_selection(rows);
+    assert_eq!((window.first, window.rows), (0, 5));
+    assert!(!window.more_above && window.more_below);
+
+    // Dentro da janela, nada rola
+    for _ in 0..4 {
+        menu.select_next();
+    }
+    assert_eq!(menu.scroll_to_selection(rows).first, 0);
+
+    // A sexta entrada empurra a janela uma linha para baixo
+    menu.select_next();
+    let window = menu.scroll_to_selection(rows);
+    assert_eq!(window.first, 1);
+    assert!(window.more_above && window.more_below);
+
+    // Subindo de volta, a janela só se move quando a seleção sai pelo topo
+    for _ in 0..4 {
+        menu.select_previous();
+    }
+    assert_eq!(menu.scroll_to_selection(rows).first, 1);
+    menu.select_previous();
+    assert_eq!(menu.scroll_to_selection(rows).first, 0);
+
+    // Ciclando para a última entrada, a janela vai para o fim
+    menu.select_previous();
+    assert_eq!(menu.selected_index(), 19);
+    let window = menu.scroll_to_selection(rows);
+    assert_eq!(window.first, 15);
+    assert!(window.more_above && !window.more_below);
+}
+
+/// Testa que `background_color` é lido da configuração e usado para limpar
+/// o framebuffer quando não há wallpaper
+#[test]
+fn test_menu_clears_to_background_color() {
+    use ignite::{
+        config::parser::Parser,
+        core::handoff::FramebufferInfo,
+        ui::{graphics::GraphicsContext, Theme},
+        video::Color,
+    };
+
+    let config = Parser::new()
+        .parse("background_color: #101820\n/Redstone\n    path: boot():/f\n")
+        .unwrap();
+    assert_eq!(config.background_color, Some(0x10_18_20));
+
+    /// Framebuffer BGR em RAM de `width`x`height`.
+    fn framebuffer(buffer: &mut [u8], width: u32, height: u32) -> FramebufferInfo {
+        FramebufferInfo {
+            addr: buffer.as_mut_ptr() as u64,
+            size: buffer.len() as u64,
+            width,
+            height,
+            stride: width,
+            format: PixelFormat::Bgr,
+            masks: Default::default(),
+        }
+    }
+    let (width, height) = (16u32, 8u32);
+
+    let mut screen = alloc::vec![0xAAu8; (width * height * 4) as usize];
+    let info = framebuffer(&mut screen, width, height);
+    let mut menu = Menu::new(&config);
+    menu.draw(&mut unsafe { GraphicsContext::new(info.addr, info) });
+
+    // Referência: o mesmo framebuffer limpo diretamente com a cor pedida
+    let mut expected = alloc::vec![0xAAu8; screen.len()];
+    let info = framebuffer(&mut expected, width, height);
+    unsafe { GraphicsContext::new(info.addr, info) }.clear(Color::new(0x10, 0x18, 0x20));
+    assert_eq!(screen, expected);
+
+    // Com wallpaper, o fundo volta a ser o do tema
+    let mut config = config;
+    config.wallpaper = Some(String::from("boot():/wall.bmp"));
+    let theme = Theme::default().background;
+    let background = Menu::new(&config).background();
+    assert_eq!(
+        (background.r, background.g, background.b),
+        (theme.r, theme.g, theme.b)
+    );
+}
+
+/// PSF2 8x16 com `count` glifos; o glifo `i` tem todas as linhas = `i`.
+fn build_psf2(count: u32, unicode: &[&str]) -> alloc::vec::Vec<u8> {
+    let mut data = alloc::vec::Vec::new();
+    data.extend_from_slice(&ignite::ui::psf::PSF2_MAGIC);
+    let flags = if unicode.is_empty() { 0u32 } else { 1 };
+    for field in [0u32, 32, flags, count, 16, 16, 8] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    for i in 0..count {
+        data.extend_from_slice(&[i as u8; 16]);
+    }
+    for entry in unicode {
+        data.extend_from_slice(entry.as_bytes());
+        data.push(0xFF);
+    }
+    data
+}
+
+/// Testa o cabeçalho PSF1 mínimo (magic, modo e altura)
+#[test]
+fn test_psf1_header() {
+    use ignite::ui::psf::{PsfFont, PSF1_MAGIC};
+
+    let mut data = alloc::vec::Vec::from(PSF1_MAGIC);
+    data.extend_from_slice(&[0x00, 16]);
+    data.resize(4 + 256 * 16, 0xAA);
+
+    let font = PsfFont::parse(&data).unwrap();
+    assert_eq!(font.glyph_count(), 256);
+    assert_eq!((font.width, font.height), (8, 16));
+
+    // Sem tabela Unicode o codepoint é o índice
+    assert_eq!(font.glyph_index('ç'), Some(0xE7));
+    assert_eq!(font.glyph_index('\u{2500}'), None);
+
+    // Truncado ou com magic errado
+    assert!(PsfFont::parse(&data[..100]).is_err());
+    assert!(PsfFont::parse(b"\x7fELF").is_err());
+}
+
+/// Testa a resolução de glifos pela tabela Unicode do PSF2
+#[test]
+fn test_psf2_unicode_glyph_index() {
+    use ignite::ui::psf::PsfFont;
+
+    let data = build_psf2(4, &["?", "ç", "ãâ", "é\u{FE}e\u{301}"]);
+    let font = PsfFont::parse(&data).unwrap();
+
+    assert_eq!(font.glyph_count(), 4);
+    assert_eq!(font.glyph_index('ç'), Some(1));
+    assert_eq!(font.glyph_index('ã'), Some(2));
+    assert_eq!(font.glyph_index('â'), Some(2));
+    assert_eq!(font.glyph_index('é'), Some(3));
+    assert_eq!(font.glyph_index('ü'), None);
+    assert_eq!(font.glyph('ç'), Some(&[1u8; 16]));
+
+    // Glifos maiores que 8x16 não cabem no renderizador
+    let mut wide = build_psf2(1, &[]);
+    wide[28..32].copy_from_slice(&9u32.to_le_bytes());
+    assert!(PsfFont::parse(&wide).is_err());
+}
+
+/// Testa a fonte embutida complementada pela PSF e o glifo de caixa
+#[test]
+fn test_bitfont_extra_fallback() {
+    use ignite::ui::{font::BitFont, psf::PsfFont};
+
+    let font = PsfFont::parse(&build_psf2(3, &["a", "ç", "ã"])).unwrap();
+    let font = BitFont::with_extra(alloc::boxed::Box::leak(alloc::boxed::Box::new(font)));
+    let builtin = BitFont::new();
+
+    // ASCII continua vindo da fonte embutida
+    assert_eq!(font.get_glyph('a'), builtin.get_glyph('a'));
+    assert_eq!(font.get_glyph('ç'), &[1u8; 16]);
+    assert_eq!(font.get_glyph('ã'), &[2u8; 16]);
+
+    // Sem glifo em nenhuma das fontes: caixa
+    assert_eq!(font.get_glyph('ü'), builtin.get_glyph('\u{7F}'));
+    assert_eq!(builtin.get_glyph('ç'), builtin.get_glyph('\u{7F}'));
+}
+
+/// Testa que entradas ocultas não aparecem no menu nem na navegação
+#[test]
+fn test_menu_skips_hidden_entries() {
+    let mut config = config_with(5, TimeoutAction::Boot);
+    config.entries[1].hidden = true;
+    config.default_entry_idx = 0;
+    let menu = Menu::new(&config);
+
+    assert_eq!(menu.serial_lines(), ["> 1. Redstone OS"]);
+}
+
+/// Testa que a navegação não entra em pânico sem entradas
+#[test]
+fn test_menu_navigation_without_entries() {
+    let mut config = config_with(5, TimeoutAction::Boot);
+    config.entries.clear();
+    config.default_entry_idx = 3;
+    let mut menu = Menu::new(&config);
+
+    assert_eq!(menu.selected_index(), 0);
+    menu.select_next();
+    menu.select_previous();
+    menu.select_previous();
+    assert_eq!(menu.selected_index(), 0);
+    assert!(menu.selected_entry().is_none());
+    assert!(menu.serial_lines().is_empty());
+}
+
+/// Testa que com uma única entrada a seleção permanece nela
+#[test]
+fn test_menu_navigation_single_entry() {
+    let mut config = config_with(5, TimeoutAction::Boot);
+    config.entries.truncate(1);
+    let mut menu = Menu::new(&config);
+
+    // `default_entry` além do fim é limitado à última entrada
+    assert_eq!(menu.selected_index(), 0);
+    for _ in 0..3 {
+        menu.select_next();
+        assert_eq!(menu.selected_index(), 0);
+        menu.select_previous();
+        assert_eq!(menu.selected_index(), 0);
+    }
+    assert!(core::ptr::eq(menu.selected_entry().unwrap(), &config.entries[0]));
+}
+
+/// Testa que `default_entry` além do fim seleciona a última entrada
+#[test]
+fn test_menu_clamps_default_entry() {
+    use ignite::config::parser::Parser;
+
+    let content = "default_entry: 10\n/A\n    path: boot():/a\n/B\n    path: boot():/b\n/C\n    path: boot():/c\n";
+    let config = Parser::new().parse(content).unwrap();
+    assert_eq!(config.default_entry_idx, 9);
+
+    let menu = Menu::new(&config);
+    assert_eq!(menu.selected_index(), 2);
+    assert!(core::ptr::eq(menu.selected_entry().unwrap(), &config.entries[2]));
+}
+
+/// Testa que o rodapé acompanha a seleção e trunca cmdlines longas
+#[test]
+fn test_menu_footer_follows_selection() {
+    use ignite::ui::menu::FOOTER_CMDLINE_MAX;
+
+    let mut config = config_with(5, TimeoutAction::Boot);
+    config.entries[0].cmdline = Some(String::from("quiet"));
+    config.entries[1].path = String::from("boot():/EFI/linux/vmlinuz");
+    config.entries[1].protocol = Protocol::Linux;
+    config.entries[1].cmdline = Some("x".repeat(FOOTER_CMDLINE_MAX + 10));
+    config.default_entry_idx = 0;
+    let mut menu = Menu::new(&config);
+
+    assert_eq!(
+        menu.footer_lines(),
+        [
+            "Caminho: boot():/EFI/ignite/forge",
+            "Protocolo: Redstone",
+            "Cmdline: quiet",
+        ]
+    );
+
+    menu.select_next();
+    let footer = menu.footer_lines();
+    assert_eq!(footer[0], "Caminho: boot():/EFI/linux/vmlinuz");
+    assert_eq!(footer[1], "Protocolo: Linux");
+    assert_eq!(
+        footer[2],
+        alloc::format!("Cmdline: {}...", "x".repeat(FOOTER_CMDLINE_MAX))
+    );
+}
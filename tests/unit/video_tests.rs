@@ -0,0 +1,425 @@
+//! Testes Unitários para o módulo de vídeo
+//!
+//! Testa primitivas de desenho sobre um framebuffer em memória comum.
+
+#![no_std]
+#![cfg(test)]
+
+extern crate alloc;
+extern crate std;
+
+use alloc::vec::Vec;
+
+use ignite::video::{
+    draw_test_pattern,
+    gop::{activate_best_modes, best_mode_id, reset_to_safe_mode_with, safe_mode_id, ModeControl},
+    normalize_bpp, select_mode, Framebuffer, FramebufferInfo, PixelFormat, VideoMode,
+    VideoModeInfo,
+};
+
+fn video_mode(id: u32, width: usize, height: usize, format: PixelFormat, bpp: u32) -> VideoMode {
+    VideoMode {
+        id,
+        info: VideoModeInfo {
+            width,
+            height,
+            stride: width,
+            format,
+            bpp,
+        },
+    }
+}
+
+/// Cria um framebuffer BGR 32bpp sobre um buffer em RAM.
+fn mock_framebuffer(width: u32, height: u32, stride: u32) -> (Vec<u8>, FramebufferInfo) {
+    let buffer = alloc::vec![0u8; (stride * height * 4) as usize];
+    let info = FramebufferInfo {
+        addr: buffer.as_ptr() as u64,
+        size: buffer.len(),
+        width,
+        height,
+        stride,
+        format: PixelFormat::BgrReserved8Bit,
+        masks: Default::default(),
+    };
+    (buffer, info)
+}
+
+/// Lê o pixel (x, y) como (r, g, b) num buffer BGR.
+fn pixel_rgb(buffer: &[u8], stride: u32, x: u32, y: u32) -> (u8, u8, u8) {
+    let off = ((y * stride + x) * 4) as usize;
+    (buffer[off + 2], buffer[off + 1], buffer[off])
+}
+
+/// Testa o padrão de barras: vermelho à esquerda, branco na borda direita
+#[test]
+fn test_draw_test_pattern_bars() {
+    // Largura não divisível por 4 e stride com padding
+    let (mut buffer, info) = mock_framebuffer(102, 8, 128);
+    let mut fb = unsafe { Framebuffer::new(buffer.as_mut_ptr() as u64, info) };
+
+    draw_test_pattern(&mut fb);
+
+    assert_eq!(pixel_rgb(&buffer, 128, 0, 0), (255, 0, 0));
+    assert_eq!(pixel_rgb(&buffer, 128, 30, 4), (0, 255, 0));
+    assert_eq!(pixel_rgb(&buffer, 128, 60, 4), (0, 0, 255));
+    assert_eq!(pixel_rgb(&buffer, 128, 101, 7), (255, 255, 255));
+
+    // O padding além da largura visível não é tocado
+    assert_eq!(pixel_rgb(&buffer, 128, 110, 0), (0, 0, 0));
+}
+
+/// Testa que pedir 24 bpp é ajustado para 32 e seleciona o modo 1080p
+#[test]
+fn test_select_mode_clamps_24bpp_to_32() {
+    assert_eq!(normalize_bpp(32), Ok((32, false)));
+    assert_eq!(normalize_bpp(24), Ok((32, true)));
+    assert_eq!(normalize_bpp(16), Ok((32, true)));
+    assert!(normalize_bpp(8).is_err());
+
+    let modes = alloc::vec![
+        video_mode(0, 1024, 768, PixelFormat::BgrReserved8Bit, 32),
+        video_mode(1, 1920, 1080, PixelFormat::BltOnly, 0),
+        video_mode(2, 1920, 1080, PixelFormat::BgrReserved8Bit, 32),
+    ];
+
+    let mode = select_mode(modes, 1920, 1080, 24).expect("modo 1080p deveria ser aceito");
+    assert_eq!(mode.id, 2);
+    assert_eq!(mode.info.bpp, 32);
+}
+
+/// Testa que modos sem bits suficientes são rejeitados
+#[test]
+fn test_select_mode_rejects_insufficient_bpp() {
+    let modes = alloc::vec![video_mode(0, 800, 600, PixelFormat::Bitmask, 16)];
+
+    assert!(select_mode(modes.clone(), 800, 600, 32).is_err());
+    assert!(select_mode(modes, 800, 600, 8).is_err());
+}
+
+/// Testa que um `size` do firmware menor que stride*altura*4 limita o desenho
+#[test]
+fn test_framebuffer_clamps_to_reported_size() {
+    // Geometria de 16 linhas, mas o firmware só informa 10 linhas e meia
+    let (mut buffer, mut info) = mock_framebuffer(16, 16, 16);
+    info.size = 16 * 4 * 10 + 32;
+
+    assert_eq!(info.expected_size(), 16 * 16 * 4);
+    assert!(info.clamp_to_size());
+    assert_eq!(info.height, 10);
+    assert!(!info.clamp_to_size());
+
+    let mut fb = unsafe { Framebuffer::new(buffer.as_mut_ptr() as u64, info) };
+    fb.clear(ignite::video::Color::WHITE);
+    assert_eq!(pixel_rgb(&buffer, 16, 15, 9), (255, 255, 255));
+
+    // Mesmo sem o ajuste de altura, nada é escrito além de `size`
+    info.height = 16;
+    let mut fb = unsafe { Framebuffer::new(buffer.as_mut_ptr() as u64, info) };
+    fb.draw_pixel(0, 10, ignite::video::Color::WHITE);
+    fb.draw_pixel(8, 10, ignite::video::Color::WHITE);
+    fb.draw_pixel(0, 15, ignite::video::Color::WHITE);
+    assert_eq!(pixel_rgb(&buffer, 16, 0, 10), (255, 255, 255));
+    assert_eq!(pixel_rgb(&buffer, 16, 8, 10), (0, 0, 0));
+    assert!(buffer[info.size..].iter().all(|&b| b == 0));
+}
+
+/// Testa que `clear` percorre as linhas pelo `stride` sem tocar o padding
+#[test]
+fn test_clear_honors_stride() {
+    let (mut buffer, info) = mock_framebuffer(10, 6, 16);
+    let mut fb = unsafe { Framebuffer::new(buffer.as_mut_ptr() as u64, info) };
+
+    fb.clear(ignite::video::Color::WHITE);
+
+    for y in 0..6 {
+        for x in 0..16 {
+            let expected = if x < 10 { (255, 255, 255) } else { (0, 0, 0) };
+            assert_eq!(
+                pixel_rgb(&buffer, 16, x, y),
+                expected,
+                "pixel ({}, {})",
+                x,
+                y
+            );
+        }
+    }
+
+    // `fill_rect` recorta na área visível
+    let mut fb = unsafe { Framebuffer::new(buffer.as_mut_ptr() as u64, info) };
+    fb.fill_rect(8, 4, 100, 100, ignite::video::Color::RED);
+    assert_eq!(pixel_rgb(&buffer, 16, 9, 5), (255, 0, 0));
+    assert_eq!(pixel_rgb(&buffer, 16, 7, 4), (255, 255, 255));
+    assert_eq!(pixel_rgb(&buffer, 16, 10, 5), (0, 0, 0));
+}
+
+/// Testa que o preenchimento nunca passa de `size`
+#[test]
+fn test_fill_rect_stops_at_size() {
+    use ignite::video::{fill_rect_bytes, Rect};
+
+    // Duas linhas e meia cabem no buffer de 3 linhas de stride 8
+    let mut buffer = alloc::vec![0u8; 8 * 4 * 3];
+    let size = 8 * 4 * 2 + 12;
+    let rect = Rect {
+        x:      0,
+        y:      0,
+        width:  6,
+        height: 3,
+    };
+    fill_rect_bytes(&mut buffer[..size], 8, (6, 3), rect, [1, 2, 3]);
+
+    assert_eq!(pixel_rgb(&buffer, 8, 5, 1), (3, 2, 1));
+    assert_eq!(pixel_rgb(&buffer, 8, 2, 2), (3, 2, 1));
+    assert!(buffer[size..].iter().all(|&b| b == 0));
+}
+
+/// Testa que `blend_darken` com 0.5 reduz cada canal à metade e com 1.0 não
+/// altera o pixel
+#[test]
+fn test_blend_darken_scales_channels() {
+    use ignite::{
+        core::handoff,
+        ui::graphics::{blend_darken, GraphicsContext},
+        video::Rect,
+    };
+
+    let (width, height, stride) = (4u32, 2u32, 4u32);
+    let mut buffer = alloc::vec![0u8; (stride * height * 4) as usize];
+    for px in buffer.as_chunks_mut::<4>().0 {
+        px.copy_from_slice(&[40, 100, 200, 0]);
+    }
+    let info = handoff::FramebufferInfo {
+        addr: buffer.as_mut_ptr() as u64,
+        size: buffer.len() as u64,
+        width,
+        height,
+        stride,
+        format: handoff::PixelFormat::Bgr,
+        masks: Default::default(),
+    };
+    let mut ctx = unsafe { GraphicsContext::new(info.addr, info) };
+
+    let half = Rect {
+        x:      0,
+        y:      0,
+        width:  2,
+        height: 2,
+    };
+    blend_darken(&mut ctx, half, 0.5);
+    let whole = Rect {
+        x:      0,
+        y:      0,
+        width,
+        height,
+    };
+    blend_darken(&mut ctx, whole, 1.0);
+
+    assert_eq!(pixel_rgb(&buffer, stride, 1, 1), (100, 50, 20));
+    assert_eq!(pixel_rgb(&buffer, stride, 2, 0), (200, 100, 40));
+    assert_eq!(buffer[3], 0);
+}
+
+/// Testa a conversão para o formato do handoff nas quatro variantes, e que
+/// só `BltOnly` é marcado como sem framebuffer linear
+#[test]
+fn test_pixel_format_into_handoff() {
+    use ignite::core::handoff::PixelFormat as HandoffFormat;
+
+    let cases = [
+        (PixelFormat::RgbReserved8Bit, HandoffFormat::Rgb, true),
+        (PixelFormat::BgrReserved8Bit, HandoffFormat::Bgr, true),
+        (PixelFormat::Bitmask, HandoffFormat::Bitmask, true),
+        (PixelFormat::BltOnly, HandoffFormat::BltOnly, false),
+    ];
+    for (format, expected, linear) in cases {
+        let converted = HandoffFormat::from(format);
+        assert_eq!(converted, expected);
+        assert_eq!(converted.is_linear(), linear, "{:?}", format);
+    }
+}
+
+/// GOP simulado: modos fixos e um framebuffer em RAM para o modo ativado.
+struct MockGop {
+    modes:     Vec<VideoMode>,
+    buffer:    Vec<u8>,
+    activated: Option<u32>,
+}
+
+impl ModeControl for MockGop {
+    fn modes(&self) -> ignite::core::error::Result<Vec<VideoMode>> {
+        Ok(self.modes.clone())
+    }
+
+    fn activate(&mut self, id: u32) -> ignite::core::error::Result<FramebufferInfo> {
+        let mode = self.modes.iter().find(|m| m.id == id).unwrap();
+        let (width, height) = (mode.info.width as u32, mode.info.height as u32);
+        self.buffer = alloc::vec![0xAA; (width * height * 4) as usize];
+        self.activated = Some(id);
+        Ok(FramebufferInfo {
+            addr: self.buffer.as_mut_ptr() as u64,
+            size: self.buffer.len(),
+            width,
+            height,
+            stride: width,
+            format: PixelFormat::BgrReserved8Bit,
+            masks: Default::default(),
+        })
+    }
+
+    fn current_framebuffer_addr(&self) -> u64 {
+        if self.buffer.is_empty() {
+            0
+        } else {
+            self.buffer.as_ptr() as u64
+        }
+    }
+}
+
+/// Testa que, após uma falha, o vídeo volta ao modo 0 com a tela limpa
+#[test]
+fn test_reset_to_safe_mode_selects_mode_zero_and_clears() {
+    let mut gop = MockGop {
+        modes:     alloc::vec![
+            video_mode(0, 64, 48, PixelFormat::BgrReserved8Bit, 32),
+            video_mode(1, 1024, 768, PixelFormat::BgrReserved8Bit, 32),
+        ],
+        buffer:    Vec::new(),
+        activated: None,
+    };
+
+    let info = unsafe { reset_to_safe_mode_with(&mut gop) }.unwrap();
+    assert_eq!(gop.activated, Some(0));
+    assert_eq!((info.width, info.height), (64, 48));
+    for y in 0..48 {
+        for x in 0..64 {
+            assert_eq!(pixel_rgb(&gop.buffer, 64, x, y), (0, 0, 0));
+        }
+    }
+
+    // Com 800x600 linear disponível, ele tem preferência sobre o modo 0
+    gop.modes
+        .push(video_mode(2, 800, 600, PixelFormat::BgrReserved8Bit, 32));
+    assert_eq!(safe_mode_id(&gop.modes), 2);
+    gop.modes[2].info.format = PixelFormat::BltOnly;
+    assert_eq!(safe_mode_id(&gop.modes), 0);
+}
+
+/// Testa que duas saídas GOP viram dois framebuffers, cada uma no seu modo
+/// de maior resolução e com endereços distintos
+#[test]
+fn test_activate_best_modes_two_outputs() {
+    let output = |modes| MockGop {
+        modes,
+        buffer: Vec::new(),
+        activated: None,
+    };
+    let mut outputs = [
+        output(alloc::vec![
+            video_mode(0, 64, 48, PixelFormat::BgrReserved8Bit, 32),
+            video_mode(1, 128, 96, PixelFormat::BgrReserved8Bit, 32),
+        ]),
+        output(alloc::vec![
+            video_mode(0, 32, 24, PixelFormat::BgrReserved8Bit, 32),
+            video_mode(1, 256, 192, PixelFormat::BltOnly, 32),
+            video_mode(2, 96, 64, PixelFormat::BgrReserved8Bit, 32),
+        ]),
+    ];
+
+    let framebuffers = activate_best_modes(&mut outputs, 0x8000_0000);
+    assert_eq!(framebuffers.len(), 2);
+    assert_ne!(framebuffers[0].addr, framebuffers[1].addr);
+    assert_eq!((framebuffers[0].width, framebuffers[0].height), (128, 96));
+    // O modo BltOnly, apesar de maior, não tem framebuffer linear
+    assert_eq!((framebuffers[1].width, framebuffers[1].height), (96, 64));
+    assert_eq!((outputs[0].activated, outputs[1].activated), (Some(1), Some(2)));
+
+    // Saída sem nenhum modo linear fica de fora
+    assert_eq!(
+        best_mode_id(&[video_mode(0, 640, 480, PixelFormat::BltOnly, 32)]),
+        None
+    );
+}
+
+/// Testa que uma saída extra cujo framebuffer atual é o do console primário
+/// (o mesmo display em outro handle) fica de fora sem trocar de modo
+#[test]
+fn test_activate_best_modes_skips_primary_display() {
+    let modes = || {
+        alloc::vec![
+            video_mode(0, 64, 48, PixelFormat::BgrReserved8Bit, 32),
+            video_mode(1, 128, 96, PixelFormat::BgrReserved8Bit, 32),
+        ]
+    };
+    let mut outputs = [
+        MockGop {
+            modes:     modes(),
+            buffer:    alloc::vec![0; 64 * 48 * 4],
+            activated: None,
+        },
+        MockGop {
+            modes:     modes(),
+            buffer:    Vec::new(),
+            activated: None,
+        },
+    ];
+    let primary_addr = outputs[0].buffer.as_ptr() as u64;
+
+    let framebuffers = activate_best_modes(&mut outputs, primary_addr);
+    assert_eq!(outputs[0].activated, None);
+    assert_eq!(outputs[0].buffer.as_ptr() as u64, primary_addr);
+    assert_eq!(framebuffers.len(), 1);
+    assert_eq!(outputs[1].activated, Some(1));
+    assert_eq!(framebuffers[0].addr, outputs[1].buffer.as_ptr() as u64);
+}
+
+/// Testa que, num modo `PixelBitMask`, as máscaras do GOP chegam ao
+/// `FramebufferInfo` do handoff (e ficam zeradas nos formatos fixos).
+#[test]
+fn test_bitmask_mode_masks_reach_handoff() {
+    use ignite::{
+        core::handoff::{self, PixelMasks},
+        uefi::proto::console::gop::{
+            GraphicsOutputModeInformation, PixelBitmask, PixelFormat as GopPixelFormat,
+        },
+        video::gop::mode_framebuffer_info,
+    };
+
+    // RGB 5:6:5 em 16 bits
+    let mut info = GraphicsOutputModeInformation {
+        version:               0,
+        horizontal_resolution: 640,
+        vertical_resolution:   480,
+        pixel_format:          GopPixelFormat::PixelBitMask,
+        pixel_information:     PixelBitmask {
+            red_mask:      0xF800,
+            green_mask:    0x07E0,
+            blue_mask:     0x001F,
+            reserved_mask: 0,
+        },
+        pixels_per_scan_line:  640,
+    };
+
+    let fb: handoff::FramebufferInfo =
+        mode_framebuffer_info(0x8000_0000, 640 * 480 * 2, &info).into();
+    assert_eq!(fb.format, handoff::PixelFormat::Bitmask);
+    assert_eq!(
+        fb.masks,
+        PixelMasks {
+            red:      0xF800,
+            green:    0x07E0,
+            blue:     0x001F,
+            reserved: 0,
+        }
+    );
+    assert_eq!(
+        (fb.addr, fb.width, fb.height, fb.stride),
+        (0x8000_0000, 640, 480, 640)
+    );
+
+    info.pixel_format = GopPixelFormat::PixelBlueGreenRedReserved8BitPerColor;
+    let fb: handoff::FramebufferInfo =
+        mode_framebuffer_info(0x8000_0000, 640 * 480 * 4, &info).into();
+    assert_eq!(fb.format, handoff::PixelFormat::Bgr);
+    assert_eq!(fb.masks, PixelMasks::default());
+}